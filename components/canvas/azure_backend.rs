@@ -3,9 +3,9 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
 use crate::canvas_data::{
-    Backend, CanvasPaintState, Color, CompositionOp, DrawOptions, ExtendMode, Filter,
-    GenericDrawTarget, GenericPathBuilder, GradientStop, GradientStops, Path, Pattern,
-    SourceSurface, StrokeOptions, SurfaceFormat,
+    font_style_struct, Backend, CanvasPaintState, Color, CompositionOp, DrawOptions, ExtendMode,
+    Filter, GenericDrawTarget, GenericPathBuilder, GradientStop, GradientStops, Path, Pattern,
+    SourceSurface, StrokeOptions, SurfaceFormat, TextRun,
 };
 use crate::canvas_paint_thread::AntialiasMode;
 use azure::azure::{AzFloat, AzGradientStop, AzPoint};
@@ -130,6 +130,10 @@ impl<'a> CanvasPaintState<'a> {
             shadow_offset_y: 0.0,
             shadow_blur: 0.0,
             shadow_color: Color::Azure(azure_hl::Color::transparent()),
+            font_style: font_style_struct(&CanvasFontStyle::default()),
+            text_align: TextAlign::default(),
+            text_baseline: TextBaseline::default(),
+            filter: vec![],
         }
     }
 }
@@ -296,6 +300,27 @@ impl GenericDrawTarget for azure_hl::DrawTarget {
             operator.into_azure(),
         );
     }
+    fn draw_surface_with_filters(
+        &mut self,
+        surface: SourceSurface,
+        size: Size2D<i32>,
+        _filters: &[FilterFunction],
+        operator: CompositionOp,
+    ) {
+        warn!("Canvas filters are not supported by the azure backend.");
+        let transform = azure_hl::DrawTarget::get_transform(self);
+        azure_hl::DrawTarget::set_transform(self, &Transform2D::identity());
+        let rect = Rect::from_size(size.to_f32());
+        azure_hl::DrawTarget::draw_surface(
+            self,
+            surface.into_azure(),
+            rect,
+            rect,
+            azure_hl::DrawSurfaceOptions::new(azure_hl::Filter::Linear, true),
+            azure_hl::DrawOptions::new(1.0, operator.into_azure(), azure_hl::AntialiasMode::None),
+        );
+        azure_hl::DrawTarget::set_transform(self, &transform);
+    }
     fn fill(&mut self, path: &Path, pattern: Pattern, draw_options: &DrawOptions) {
         azure_hl::DrawTarget::fill(
             self,
@@ -410,6 +435,25 @@ impl GenericDrawTarget for azure_hl::DrawTarget {
     fn snapshot_data_owned(&self) -> Vec<u8> {
         unsafe { self.snapshot().get_data_surface().data().into() }
     }
+
+    fn fill_text(
+        &mut self,
+        _text_runs: &[TextRun],
+        _pattern: Pattern,
+        _draw_options: &DrawOptions,
+    ) {
+        error!("Unimplemented canvas2d.fillText with the azure backend.");
+    }
+
+    fn stroke_text(
+        &mut self,
+        _text_runs: &[TextRun],
+        _pattern: Pattern,
+        _stroke_options: &StrokeOptions,
+        _draw_options: &DrawOptions,
+    ) {
+        error!("Unimplemented canvas2d.strokeText with the azure backend.");
+    }
 }
 
 impl AntialiasMode {
@@ -545,6 +589,12 @@ impl<'a> StrokeOptions<'a> {
             StrokeOptions::Azure(options) => options.line_cap = val.to_azure_style(),
         }
     }
+    pub fn set_line_dash(&mut self, val: Vec<f32>) {
+        if !val.is_empty() {
+            warn!("Line dashes are not supported by the azure backend.");
+        }
+    }
+    pub fn set_line_dash_offset(&mut self, _val: f32) {}
 }
 
 pub trait ToAzureStyle {
@@ -761,6 +811,7 @@ impl Pattern<'_> {
 impl Filter {
     fn as_azure(&self) -> azure_hl::Filter {
         match *self {
+            Filter::Good => azure_hl::Filter::Good,
             Filter::Linear => azure_hl::Filter::Linear,
             Filter::Point => azure_hl::Filter::Point,
         }
@@ -779,6 +830,24 @@ impl Path {
         self.as_azure().contains_point(x, y, path_transform)
     }
 
+    pub fn stroke_contains_point(
+        &self,
+        _x: f64,
+        _y: f64,
+        _stroke_options: &StrokeOptions,
+        _path_transform: &Transform2D<f32>,
+    ) -> bool {
+        error!("Unimplemented canvas2d.isPointInStroke with the azure backend.");
+        false
+    }
+
+    pub fn set_fill_rule(&mut self, fill_rule: FillRule) {
+        // Azure paths only use the fill rule of the builder they were created with.
+        if fill_rule == FillRule::Evenodd {
+            warn!("The evenodd fill rule is not supported by the azure backend.");
+        }
+    }
+
     pub fn copy_to_builder(&self) -> Box<dyn GenericPathBuilder> {
         Box::new(self.as_azure().copy_to_builder())
     }
@@ -3,15 +3,37 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
 use crate::canvas_paint_thread::AntialiasMode;
+use app_units::Au;
 use canvas_traits::canvas::*;
 use cssparser::RGBA;
 use euclid::default::{Point2D, Rect, Size2D, Transform2D, Vector2D};
+use gfx::font::{FontMetrics, FontRef, ShapingFlags, ShapingOptions};
+use gfx::font_cache_thread::FontCacheThread;
+use gfx::font_context::FontContext;
+use gfx::text::glyph::{ByteIndex, GlyphStore};
 use ipc_channel::ipc::{IpcSender, IpcSharedMemory};
 use num_traits::ToPrimitive;
+use ordered_float::NotNan;
+use range::Range;
+use servo_arc::Arc as ServoArc;
+use std::cell::RefCell;
 #[allow(unused_imports)]
 use std::marker::PhantomData;
 use std::mem;
+use std::rc::Rc;
 use std::sync::Arc;
+use style::computed_values::font_kerning::T as FontKerning;
+use style::computed_values::font_variant_caps::T as FontVariantCaps;
+use style::properties::style_structs::Font as FontStyleStruct;
+use style::values::computed::font::SingleFontFamily;
+use style::values::computed::font::{FamilyName, FontFamily, FontFamilyList, FontFamilyNameSyntax};
+use style::values::computed::font::{FontSize, FontStretch, FontStyleAngle, FontWeight};
+use style::values::computed::font::{FontVariantLigatures, FontVariantNumeric};
+use style::values::computed::{Angle, Length, Percentage};
+use style::values::generics::font::{FontSettings, FontStyle};
+use style::values::generics::NonNegative;
+use style::Atom;
+use unicode_script::{get_script, Script};
 use webrender::api::DirtyRect;
 use webrender_api::units::RectExt;
 
@@ -178,6 +200,69 @@ impl<'a> PathBuilderRef<'a> {
             .arc(center, radius, start_angle, end_angle, ccw);
     }
 
+    fn arc_to(&mut self, cp1: &Point2D<f32>, cp2: &Point2D<f32>, radius: f32) {
+        let cp0 = match self.current_point() {
+            Some(p) => p,
+            None => return,
+        };
+        let cp1 = *cp1;
+        let cp2 = *cp2;
+
+        if (cp0.x == cp1.x && cp0.y == cp1.y) || cp1 == cp2 || radius == 0.0 {
+            self.line_to(&cp1);
+            return;
+        }
+
+        // if all three control points lie on a single straight line,
+        // connect the first two by a straight line
+        let direction = (cp2.x - cp1.x) * (cp0.y - cp1.y) + (cp2.y - cp1.y) * (cp1.x - cp0.x);
+        if direction == 0.0 {
+            self.line_to(&cp1);
+            return;
+        }
+
+        // otherwise, draw the Arc
+        let a2 = (cp0.x - cp1.x).powi(2) + (cp0.y - cp1.y).powi(2);
+        let b2 = (cp1.x - cp2.x).powi(2) + (cp1.y - cp2.y).powi(2);
+        let d = {
+            let c2 = (cp0.x - cp2.x).powi(2) + (cp0.y - cp2.y).powi(2);
+            let cosx = (a2 + b2 - c2) / (2.0 * (a2 * b2).sqrt());
+            let sinx = (1.0 - cosx.powi(2)).sqrt();
+            radius / ((1.0 - cosx) / sinx)
+        };
+
+        // first tangent point
+        let anx = (cp1.x - cp0.x) / a2.sqrt();
+        let any = (cp1.y - cp0.y) / a2.sqrt();
+        let tp1 = Point2D::new(cp1.x - anx * d, cp1.y - any * d);
+
+        // second tangent point
+        let bnx = (cp1.x - cp2.x) / b2.sqrt();
+        let bny = (cp1.y - cp2.y) / b2.sqrt();
+        let tp2 = Point2D::new(cp1.x - bnx * d, cp1.y - bny * d);
+
+        // arc center and angles
+        let anticlockwise = direction < 0.0;
+        let cx = tp1.x + any * radius * if anticlockwise { 1.0 } else { -1.0 };
+        let cy = tp1.y - anx * radius * if anticlockwise { 1.0 } else { -1.0 };
+        let angle_start = (tp1.y - cy).atan2(tp1.x - cx);
+        let angle_end = (tp2.y - cy).atan2(tp2.x - cx);
+
+        self.line_to(&tp1);
+        if [cx, cy, angle_start, angle_end]
+            .iter()
+            .all(|x| x.is_finite())
+        {
+            self.arc(
+                &Point2D::new(cx, cy),
+                radius,
+                angle_start,
+                angle_end,
+                anticlockwise,
+            );
+        }
+    }
+
     pub fn ellipse(
         &mut self,
         center: &Point2D<f32>,
@@ -208,6 +293,36 @@ impl<'a> PathBuilderRef<'a> {
         let current_point = self.builder.get_current_point();
         Some(inverse.transform_point(Point2D::new(current_point.x, current_point.y)))
     }
+
+    /// Appends the segments of a `Path2D` object to the path.
+    fn add_segments(&mut self, segments: &[PathSegment]) {
+        for segment in segments {
+            match *segment {
+                PathSegment::ClosePath => self.builder.close(),
+                PathSegment::MoveTo(ref point) => self.move_to(point),
+                PathSegment::LineTo(ref point) => self.line_to(point),
+                PathSegment::QuadraticCurveTo(ref cp, ref point) => {
+                    self.quadratic_curve_to(cp, point)
+                },
+                PathSegment::BezierCurveTo(ref cp1, ref cp2, ref point) => {
+                    self.bezier_curve_to(cp1, cp2, point)
+                },
+                PathSegment::Arc(ref center, radius, start, end, ccw) => {
+                    self.arc(center, radius, start, end, ccw)
+                },
+                PathSegment::ArcTo(ref cp1, ref cp2, radius) => self.arc_to(cp1, cp2, radius),
+                PathSegment::Ellipse(ref center, radius_x, radius_y, rotation, start, end, ccw) => {
+                    self.ellipse(center, radius_x, radius_y, rotation, start, end, ccw)
+                },
+                PathSegment::Rect(ref rect) => self.rect(rect),
+                PathSegment::AddPath(ref segments, ref transform) => PathBuilderRef {
+                    builder: &mut *self.builder,
+                    transform: transform.post_transform(&self.transform),
+                }
+                .add_segments(segments),
+            }
+        }
+    }
 }
 
 // TODO(pylbrecht)
@@ -255,6 +370,13 @@ pub trait GenericDrawTarget {
         sigma: f32,
         operator: CompositionOp,
     );
+    fn draw_surface_with_filters(
+        &mut self,
+        surface: SourceSurface,
+        size: Size2D<i32>,
+        filters: &[FilterFunction],
+        operator: CompositionOp,
+    );
     fn fill(&mut self, path: &Path, pattern: Pattern, draw_options: &DrawOptions);
     fn fill_rect(&mut self, rect: &Rect<f32>, pattern: Pattern, draw_options: Option<&DrawOptions>);
     fn get_format(&self) -> SurfaceFormat;
@@ -288,6 +410,149 @@ pub trait GenericDrawTarget {
     );
     fn snapshot_data(&self, f: &dyn Fn(&[u8]) -> Vec<u8>) -> Vec<u8>;
     fn snapshot_data_owned(&self) -> Vec<u8>;
+    /// Fills the given text runs, laid out horizontally from the origin of the current
+    /// transform along the baseline.
+    fn fill_text(&mut self, text_runs: &[TextRun], pattern: Pattern, draw_options: &DrawOptions);
+    /// Strokes the outlines of the given text runs, laid out like in `fill_text`.
+    fn stroke_text(
+        &mut self,
+        text_runs: &[TextRun],
+        pattern: Pattern,
+        stroke_options: &StrokeOptions,
+        draw_options: &DrawOptions,
+    );
+}
+
+/// A run of text shaped with a single font, in visual order.
+pub struct TextRun {
+    pub font: FontRef,
+    pub glyphs: Arc<GlyphStore>,
+}
+
+impl TextRun {
+    /// The horizontal advance of the whole run, in pixels.
+    pub fn advance(&self) -> f32 {
+        let range = Range::new(ByteIndex(0), self.glyphs.len());
+        self.glyphs
+            .advance_for_byte_range(&range, Au(0))
+            .to_f32_px()
+    }
+}
+
+/// Text that went through the text preparation algorithm and is ready to be drawn.
+/// <https://html.spec.whatwg.org/multipage/#text-preparation-algorithm>
+struct PreparedText {
+    runs: Vec<TextRun>,
+    /// The start of the alphabetic baseline of the text, in user space.
+    origin: Point2D<f32>,
+    /// The horizontal scale that makes the text fit its maximum width.
+    scale: f32,
+    /// The bounds of the text, in user space.
+    bounds: Rect<f32>,
+}
+
+impl PreparedText {
+    fn draw<F>(&self, draw_target: &mut dyn GenericDrawTarget, draw_runs: F)
+    where
+        F: FnOnce(&mut dyn GenericDrawTarget, &[TextRun]),
+    {
+        let transform = draw_target.get_transform();
+        draw_target.set_transform(
+            &transform
+                .pre_translate(self.origin.to_vector())
+                .pre_scale(self.scale, 1.),
+        );
+        draw_runs(draw_target, &self.runs);
+        draw_target.set_transform(&transform);
+    }
+}
+
+/// The vertical metrics of the primary font, in pixels above the alphabetic baseline.
+struct BaselineMetrics {
+    ascent: f32,
+    descent: f32,
+    em_ascent: f32,
+    em_descent: f32,
+}
+
+impl BaselineMetrics {
+    fn new(metrics: &FontMetrics) -> BaselineMetrics {
+        let ascent = metrics.ascent.to_f32_px();
+        let descent = metrics.descent.to_f32_px();
+        // Scale the ascent and descent so that they add up to the em size.
+        let em_ratio = if ascent + descent > 0. {
+            metrics.em_size.to_f32_px() / (ascent + descent)
+        } else {
+            1.
+        };
+        BaselineMetrics {
+            ascent,
+            descent,
+            em_ascent: ascent * em_ratio,
+            em_descent: descent * em_ratio,
+        }
+    }
+
+    /// The height of the given baseline above the alphabetic baseline.
+    fn baseline(&self, baseline: TextBaseline) -> f32 {
+        match baseline {
+            TextBaseline::Top => self.em_ascent,
+            // Fonts rarely provide a hanging baseline, so use the usual
+            // approximation of 80% of the ascent.
+            TextBaseline::Hanging => self.em_ascent * 0.8,
+            TextBaseline::Middle => (self.em_ascent - self.em_descent) / 2.,
+            TextBaseline::Alphabetic => 0.,
+            TextBaseline::Ideographic | TextBaseline::Bottom => -self.em_descent,
+        }
+    }
+}
+
+/// Converts the font of a 2D context into the style struct used for font matching.
+pub fn font_style_struct(font: &CanvasFontStyle) -> ServoArc<FontStyleStruct> {
+    let families: Vec<_> = font
+        .families
+        .iter()
+        .map(|family| match *family {
+            CanvasFontFamily::Named(ref name) => SingleFontFamily::FamilyName(FamilyName {
+                name: Atom::from(&**name),
+                syntax: FontFamilyNameSyntax::Quoted,
+            }),
+            CanvasFontFamily::Generic(ref name) => SingleFontFamily::from_atom(Atom::from(&**name)),
+        })
+        .collect();
+    let font_style = match font.slant {
+        CanvasFontSlant::Normal => FontStyle::Normal,
+        CanvasFontSlant::Italic => FontStyle::Italic,
+        CanvasFontSlant::Oblique(angle) => {
+            FontStyle::Oblique(FontStyleAngle(Angle::from_degrees(angle)))
+        },
+    };
+    let mut style = FontStyleStruct {
+        font_family: FontFamily {
+            families: FontFamilyList::new(families.into_boxed_slice()),
+            is_system_font: false,
+        },
+        font_style,
+        font_variant_caps: if font.small_caps {
+            FontVariantCaps::SmallCaps
+        } else {
+            FontVariantCaps::Normal
+        },
+        font_weight: FontWeight(font.weight),
+        font_size: FontSize {
+            size: NonNegative(Length::new(font.size as f32)),
+            keyword_info: None,
+        },
+        font_stretch: FontStretch(NonNegative(Percentage(font.stretch / 100.))),
+        font_kerning: FontKerning::Auto,
+        font_variant_ligatures: FontVariantLigatures::NORMAL,
+        font_variant_numeric: FontVariantNumeric::NORMAL,
+        font_feature_settings: FontSettings::normal(),
+        font_variation_settings: FontSettings::normal(),
+        hash: 0,
+    };
+    style.compute_font_hash();
+    ServoArc::new(style)
 }
 
 #[derive(Clone)]
@@ -384,6 +649,7 @@ pub enum StrokeOptions<'a> {
 
 #[derive(Clone, Copy)]
 pub enum Filter {
+    Good,
     Linear,
     Point,
 }
@@ -400,6 +666,7 @@ pub struct CanvasData<'a> {
     old_image_key: Option<webrender_api::ImageKey>,
     /// An old webrender image key that can be deleted when the current epoch ends.
     very_old_image_key: Option<webrender_api::ImageKey>,
+    font_context: Rc<RefCell<FontContext<FontCacheThread>>>,
     pub canvas_id: CanvasId,
 }
 
@@ -419,6 +686,7 @@ impl<'a> CanvasData<'a> {
         webrender_api_sender: webrender_api::RenderApiSender,
         antialias: AntialiasMode,
         canvas_id: CanvasId,
+        font_context: Rc<RefCell<FontContext<FontCacheThread>>>,
     ) -> CanvasData<'a> {
         let backend = create_backend();
         let draw_target = backend.create_drawtarget(size);
@@ -433,6 +701,7 @@ impl<'a> CanvasData<'a> {
             image_key: None,
             old_image_key: None,
             very_old_image_key: None,
+            font_context,
             canvas_id: canvas_id,
         }
     }
@@ -444,6 +713,7 @@ impl<'a> CanvasData<'a> {
         dest_rect: Rect<f64>,
         source_rect: Rect<f64>,
         smoothing_enabled: bool,
+        smoothing_quality: ImageSmoothingQuality,
     ) {
         // We round up the floating pixel values to draw the pixels
         let source_rect = source_rect.ceil();
@@ -462,6 +732,7 @@ impl<'a> CanvasData<'a> {
                 source_rect.size,
                 dest_rect,
                 smoothing_enabled,
+                smoothing_quality,
                 &draw_options,
             );
         };
@@ -475,7 +746,7 @@ impl<'a> CanvasData<'a> {
             // TODO(pylbrecht) pass another closure for raqote
             self.draw_with_shadow(&rect, writer);
         } else {
-            writer(&mut *self.drawtarget);
+            self.draw_with_filter(|draw_target, _| writer(draw_target));
         }
     }
 
@@ -491,11 +762,220 @@ impl<'a> CanvasData<'a> {
         }
     }
 
-    pub fn fill_text(&self, text: String, x: f64, y: f64, max_width: Option<f64>) {
-        error!(
-            "Unimplemented canvas2d.fillText. Values received: {}, {}, {}, {:?}.",
-            text, x, y, max_width
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-filltext
+    pub fn fill_text(
+        &mut self,
+        text: String,
+        x: f64,
+        y: f64,
+        max_width: Option<f64>,
+        direction: Direction,
+    ) {
+        if self.state.fill_style.is_zero_size_gradient() {
+            return; // Paint nothing if gradient size is zero.
+        }
+
+        let text = match self.prepare_text(text, x, y, max_width, direction) {
+            Some(text) => text,
+            None => return,
+        };
+        let pattern = self.state.fill_style.clone();
+        let draw_options = self.state.draw_options.clone();
+        let draw_text = |draw_target: &mut dyn GenericDrawTarget| {
+            text.draw(draw_target, |draw_target, runs| {
+                draw_target.fill_text(runs, pattern, &draw_options)
+            });
+        };
+
+        if self.need_to_draw_shadow() {
+            self.draw_with_shadow(&text.bounds, draw_text);
+        } else {
+            self.draw_with_filter(|draw_target, _| draw_text(draw_target));
+        }
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-stroketext
+    pub fn stroke_text(
+        &mut self,
+        text: String,
+        x: f64,
+        y: f64,
+        max_width: Option<f64>,
+        direction: Direction,
+    ) {
+        if self.state.stroke_style.is_zero_size_gradient() {
+            return; // Paint nothing if gradient size is zero.
+        }
+
+        let text = match self.prepare_text(text, x, y, max_width, direction) {
+            Some(text) => text,
+            None => return,
+        };
+        let pattern = self.state.stroke_style.clone();
+        let stroke_options = self.state.stroke_opts.clone();
+        let draw_options = self.state.draw_options.clone();
+        let draw_text = |draw_target: &mut dyn GenericDrawTarget| {
+            text.draw(draw_target, |draw_target, runs| {
+                draw_target.stroke_text(runs, pattern, &stroke_options, &draw_options)
+            });
+        };
+
+        if self.need_to_draw_shadow() {
+            self.draw_with_shadow(&text.bounds, draw_text);
+        } else {
+            self.draw_with_filter(|draw_target, _| draw_text(draw_target));
+        }
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-measuretext
+    pub fn measure_text(&mut self, text: String, direction: Direction) -> TextMetrics {
+        let text = replace_ascii_whitespace(&text);
+        let width = self
+            .text_runs(&text, direction)
+            .iter()
+            .map(TextRun::advance)
+            .sum::<f32>();
+        let metrics = match self.baseline_metrics() {
+            Some(metrics) => metrics,
+            None => return TextMetrics::default(),
+        };
+        let anchor = width * self.text_anchor(direction);
+        let baseline = metrics.baseline(self.state.text_baseline);
+        TextMetrics {
+            width: width as f64,
+            // Glyph outlines are not inspected, so the actual bounding box is
+            // approximated with the advance and the font bounding box.
+            actual_bounding_box_left: anchor as f64,
+            actual_bounding_box_right: (width - anchor) as f64,
+            font_bounding_box_ascent: (metrics.ascent - baseline) as f64,
+            font_bounding_box_descent: (metrics.descent + baseline) as f64,
+            actual_bounding_box_ascent: (metrics.ascent - baseline) as f64,
+            actual_bounding_box_descent: (metrics.descent + baseline) as f64,
+            em_height_ascent: (metrics.em_ascent - baseline) as f64,
+            em_height_descent: (metrics.em_descent + baseline) as f64,
+            hanging_baseline: (metrics.baseline(TextBaseline::Hanging) - baseline) as f64,
+            alphabetic_baseline: -baseline as f64,
+            ideographic_baseline: (metrics.baseline(TextBaseline::Ideographic) - baseline) as f64,
+        }
+    }
+
+    // https://html.spec.whatwg.org/multipage/#text-preparation-algorithm
+    fn prepare_text(
+        &mut self,
+        text: String,
+        x: f64,
+        y: f64,
+        max_width: Option<f64>,
+        direction: Direction,
+    ) -> Option<PreparedText> {
+        let text = replace_ascii_whitespace(&text);
+        let runs = self.text_runs(&text, direction);
+        if runs.is_empty() {
+            return None;
+        }
+        let metrics = self.baseline_metrics()?;
+
+        let width = runs.iter().map(TextRun::advance).sum::<f32>();
+        let scale = match max_width {
+            Some(max_width) if width > max_width as f32 => max_width as f32 / width,
+            _ => 1.,
+        };
+        let width = width * scale;
+
+        let origin = Point2D::new(
+            x as f32 - width * self.text_anchor(direction),
+            y as f32 + metrics.baseline(self.state.text_baseline),
         );
+        let bounds = Rect::new(
+            Point2D::new(origin.x, origin.y - metrics.ascent),
+            Size2D::new(width, metrics.ascent + metrics.descent),
+        );
+        Some(PreparedText {
+            runs,
+            origin,
+            scale,
+            bounds,
+        })
+    }
+
+    /// The position of the anchor point along the text, as a fraction of its width.
+    fn text_anchor(&self, direction: Direction) -> f32 {
+        match (self.state.text_align, direction) {
+            (TextAlign::Left, _) |
+            (TextAlign::Start, Direction::Ltr) |
+            (TextAlign::End, Direction::Rtl) => 0.,
+            (TextAlign::Right, _) |
+            (TextAlign::End, Direction::Ltr) |
+            (TextAlign::Start, Direction::Rtl) => 1.,
+            (TextAlign::Center, _) => 0.5,
+        }
+    }
+
+    fn baseline_metrics(&mut self) -> Option<BaselineMetrics> {
+        let font_group = self
+            .font_context
+            .borrow_mut()
+            .font_group(self.state.font_style.clone());
+        let font = font_group
+            .borrow_mut()
+            .first(&mut *self.font_context.borrow_mut())?;
+        let metrics = BaselineMetrics::new(&font.borrow().metrics);
+        Some(metrics)
+    }
+
+    /// Splits the text in runs of characters supported by the same font and
+    /// shapes them, returning the runs in visual order.
+    fn text_runs(&mut self, text: &str, direction: Direction) -> Vec<TextRun> {
+        let font_group = self
+            .font_context
+            .borrow_mut()
+            .font_group(self.state.font_style.clone());
+        let mut font_group = font_group.borrow_mut();
+        let mut font_context = self.font_context.borrow_mut();
+
+        let mut font_ranges: Vec<(FontRef, std::ops::Range<usize>)> = vec![];
+        for (index, character) in text.char_indices() {
+            let font = match font_group.find_by_codepoint(&mut *font_context, character) {
+                Some(font) => font,
+                None => continue,
+            };
+            let end = index + character.len_utf8();
+            if let Some((last_font, range)) = font_ranges.last_mut() {
+                if Rc::ptr_eq(last_font, &font) {
+                    range.end = end;
+                    continue;
+                }
+            }
+            font_ranges.push((font, index..end));
+        }
+
+        let mut flags = ShapingFlags::empty();
+        if direction == Direction::Rtl {
+            flags.insert(ShapingFlags::RTL_FLAG);
+        }
+        let mut runs: Vec<_> = font_ranges
+            .into_iter()
+            .map(|(font, range)| {
+                let text = &text[range];
+                let script = text
+                    .chars()
+                    .map(get_script)
+                    .find(|script| *script != Script::Common && *script != Script::Inherited)
+                    .unwrap_or(Script::Common);
+                let options = ShapingOptions {
+                    letter_spacing: None,
+                    word_spacing: (Au(0), NotNan::new(0.).unwrap()),
+                    script,
+                    flags,
+                };
+                let glyphs = font.borrow_mut().shape_text(text, &options);
+                TextRun { font, glyphs }
+            })
+            .collect();
+        if direction == Direction::Rtl {
+            runs.reverse();
+        }
+        runs
     }
 
     pub fn fill_rect(&mut self, rect: &Rect<f32>) {
@@ -519,11 +999,13 @@ impl<'a> CanvasData<'a> {
                 );
             });
         } else {
-            self.drawtarget.fill_rect(
-                &draw_rect,
-                self.state.fill_style.clone(),
-                Some(&self.state.draw_options),
-            );
+            self.draw_with_filter(|draw_target, state| {
+                draw_target.fill_rect(
+                    &draw_rect,
+                    state.fill_style.clone(),
+                    Some(&state.draw_options),
+                );
+            });
         }
     }
 
@@ -546,22 +1028,26 @@ impl<'a> CanvasData<'a> {
                 );
             });
         } else if rect.size.width == 0. || rect.size.height == 0. {
-            let mut stroke_opts = self.state.stroke_opts.clone();
-            stroke_opts.set_line_cap(LineCapStyle::Butt);
-            self.drawtarget.stroke_line(
-                rect.origin,
-                rect.bottom_right(),
-                self.state.stroke_style.clone(),
-                &stroke_opts,
-                &self.state.draw_options,
-            );
+            self.draw_with_filter(|draw_target, state| {
+                let mut stroke_opts = state.stroke_opts.clone();
+                stroke_opts.set_line_cap(LineCapStyle::Butt);
+                draw_target.stroke_line(
+                    rect.origin,
+                    rect.bottom_right(),
+                    state.stroke_style.clone(),
+                    &stroke_opts,
+                    &state.draw_options,
+                );
+            });
         } else {
-            self.drawtarget.stroke_rect(
-                rect,
-                self.state.stroke_style.clone(),
-                &self.state.stroke_opts,
-                &self.state.draw_options,
-            );
+            self.draw_with_filter(|draw_target, state| {
+                draw_target.stroke_rect(
+                    rect,
+                    state.stroke_style.clone(),
+                    &state.stroke_opts,
+                    &state.draw_options,
+                );
+            });
         }
     }
 
@@ -639,17 +1125,28 @@ impl<'a> CanvasData<'a> {
             .path()
     }
 
-    pub fn fill(&mut self) {
+    pub fn fill(&mut self, fill_rule: FillRule) {
         if self.state.fill_style.is_zero_size_gradient() {
             return; // Paint nothing if gradient size is zero.
         }
 
         self.ensure_path();
-        self.drawtarget.fill(
-            &self.path().clone(),
-            self.state.fill_style.clone(),
-            &self.state.draw_options,
-        );
+        let mut path = self.path().clone();
+        path.set_fill_rule(fill_rule);
+        self.draw_with_filter(|draw_target, state| {
+            draw_target.fill(&path, state.fill_style.clone(), &state.draw_options);
+        });
+    }
+
+    pub fn fill_path2d(&mut self, segments: &[PathSegment], fill_rule: FillRule) {
+        if self.state.fill_style.is_zero_size_gradient() {
+            return; // Paint nothing if gradient size is zero.
+        }
+
+        let path = self.path2d(segments, fill_rule);
+        self.draw_with_filter(|draw_target, state| {
+            draw_target.fill(&path, state.fill_style.clone(), &state.draw_options);
+        });
     }
 
     pub fn stroke(&mut self) {
@@ -658,39 +1155,116 @@ impl<'a> CanvasData<'a> {
         }
 
         self.ensure_path();
-        self.drawtarget.stroke(
-            &self.path().clone(),
-            self.state.stroke_style.clone(),
-            &self.state.stroke_opts,
-            &self.state.draw_options,
-        );
+        let path = self.path().clone();
+        self.draw_with_filter(|draw_target, state| {
+            draw_target.stroke(
+                &path,
+                state.stroke_style.clone(),
+                &state.stroke_opts,
+                &state.draw_options,
+            );
+        });
+    }
+
+    pub fn stroke_path2d(&mut self, segments: &[PathSegment]) {
+        if self.state.stroke_style.is_zero_size_gradient() {
+            return; // Paint nothing if gradient size is zero.
+        }
+
+        let path = self.path2d(segments, FillRule::Nonzero);
+        self.draw_with_filter(|draw_target, state| {
+            draw_target.stroke(
+                &path,
+                state.stroke_style.clone(),
+                &state.stroke_opts,
+                &state.draw_options,
+            );
+        });
     }
 
-    pub fn clip(&mut self) {
+    pub fn clip(&mut self, fill_rule: FillRule) {
         self.ensure_path();
-        let path = self.path().clone();
+        let mut path = self.path().clone();
+        path.set_fill_rule(fill_rule);
+        self.drawtarget.push_clip(&path);
+    }
+
+    pub fn clip_path2d(&mut self, segments: &[PathSegment], fill_rule: FillRule) {
+        let path = self.path2d(segments, fill_rule);
         self.drawtarget.push_clip(&path);
     }
 
-    pub fn is_point_in_path(
+    pub fn is_point_in_path(&mut self, x: f64, y: f64, fill_rule: FillRule, chan: IpcSender<bool>) {
+        self.ensure_path();
+        let result = match self.path_state.as_ref() {
+            Some(PathState::UserSpacePath(ref path, ref transform)) => {
+                let target_transform = self.drawtarget.get_transform();
+                let path_transform = transform.as_ref().unwrap_or(&target_transform);
+                let mut path = path.clone();
+                path.set_fill_rule(fill_rule);
+                path.contains_point(x, y, path_transform)
+            },
+            Some(_) | None => false,
+        };
+        chan.send(result).unwrap();
+    }
+
+    pub fn is_point_in_path2d(
         &mut self,
+        segments: &[PathSegment],
         x: f64,
         y: f64,
-        _fill_rule: FillRule,
+        fill_rule: FillRule,
         chan: IpcSender<bool>,
     ) {
+        let path = self.path2d(segments, fill_rule);
+        let result = path.contains_point(x, y, &self.drawtarget.get_transform());
+        chan.send(result).unwrap();
+    }
+
+    pub fn is_point_in_stroke(&mut self, x: f64, y: f64, chan: IpcSender<bool>) {
         self.ensure_path();
         let result = match self.path_state.as_ref() {
             Some(PathState::UserSpacePath(ref path, ref transform)) => {
                 let target_transform = self.drawtarget.get_transform();
                 let path_transform = transform.as_ref().unwrap_or(&target_transform);
-                path.contains_point(x, y, path_transform)
+                path.stroke_contains_point(x, y, &self.state.stroke_opts, path_transform)
             },
             Some(_) | None => false,
         };
         chan.send(result).unwrap();
     }
 
+    pub fn is_point_in_stroke2d(
+        &mut self,
+        segments: &[PathSegment],
+        x: f64,
+        y: f64,
+        chan: IpcSender<bool>,
+    ) {
+        let path = self.path2d(segments, FillRule::Nonzero);
+        let result = path.stroke_contains_point(
+            x,
+            y,
+            &self.state.stroke_opts,
+            &self.drawtarget.get_transform(),
+        );
+        chan.send(result).unwrap();
+    }
+
+    /// Builds a user-space path out of the segments of a `Path2D` object.
+    fn path2d(&self, segments: &[PathSegment], fill_rule: FillRule) -> Path {
+        let mut builder = self.drawtarget.create_path_builder();
+        PathBuilderRef {
+            builder: &mut builder,
+            transform: Transform2D::identity(),
+        }
+        .add_segments(segments);
+        let mut path = builder.finish();
+        path.set_fill_rule(fill_rule);
+        path
+    }
+
     pub fn move_to(&mut self, point: &Point2D<f32>) {
         self.path_builder().move_to(point);
     }
@@ -794,66 +1368,7 @@ impl<'a> CanvasData<'a> {
     }
 
     pub fn arc_to(&mut self, cp1: &Point2D<f32>, cp2: &Point2D<f32>, radius: f32) {
-        let cp0 = match self.path_builder().current_point() {
-            Some(p) => p,
-            None => return,
-        };
-        let cp1 = *cp1;
-        let cp2 = *cp2;
-
-        if (cp0.x == cp1.x && cp0.y == cp1.y) || cp1 == cp2 || radius == 0.0 {
-            self.line_to(&cp1);
-            return;
-        }
-
-        // if all three control points lie on a single straight line,
-        // connect the first two by a straight line
-        let direction = (cp2.x - cp1.x) * (cp0.y - cp1.y) + (cp2.y - cp1.y) * (cp1.x - cp0.x);
-        if direction == 0.0 {
-            self.line_to(&cp1);
-            return;
-        }
-
-        // otherwise, draw the Arc
-        let a2 = (cp0.x - cp1.x).powi(2) + (cp0.y - cp1.y).powi(2);
-        let b2 = (cp1.x - cp2.x).powi(2) + (cp1.y - cp2.y).powi(2);
-        let d = {
-            let c2 = (cp0.x - cp2.x).powi(2) + (cp0.y - cp2.y).powi(2);
-            let cosx = (a2 + b2 - c2) / (2.0 * (a2 * b2).sqrt());
-            let sinx = (1.0 - cosx.powi(2)).sqrt();
-            radius / ((1.0 - cosx) / sinx)
-        };
-
-        // first tangent point
-        let anx = (cp1.x - cp0.x) / a2.sqrt();
-        let any = (cp1.y - cp0.y) / a2.sqrt();
-        let tp1 = Point2D::new(cp1.x - anx * d, cp1.y - any * d);
-
-        // second tangent point
-        let bnx = (cp1.x - cp2.x) / b2.sqrt();
-        let bny = (cp1.y - cp2.y) / b2.sqrt();
-        let tp2 = Point2D::new(cp1.x - bnx * d, cp1.y - bny * d);
-
-        // arc center and angles
-        let anticlockwise = direction < 0.0;
-        let cx = tp1.x + any * radius * if anticlockwise { 1.0 } else { -1.0 };
-        let cy = tp1.y - anx * radius * if anticlockwise { 1.0 } else { -1.0 };
-        let angle_start = (tp1.y - cy).atan2(tp1.x - cx);
-        let angle_end = (tp2.y - cy).atan2(tp2.x - cx);
-
-        self.line_to(&tp1);
-        if [cx, cy, angle_start, angle_end]
-            .iter()
-            .all(|x| x.is_finite())
-        {
-            self.arc(
-                &Point2D::new(cx, cy),
-                radius,
-                angle_start,
-                angle_end,
-                anticlockwise,
-            );
-        }
+        self.path_builder().arc_to(cp1, cp2, radius);
     }
 
     pub fn ellipse(
@@ -903,6 +1418,14 @@ impl<'a> CanvasData<'a> {
         self.state.stroke_opts.set_miter_limit(limit);
     }
 
+    pub fn set_line_dash(&mut self, segments: Vec<f32>) {
+        self.state.stroke_opts.set_line_dash(segments);
+    }
+
+    pub fn set_line_dash_offset(&mut self, offset: f32) {
+        self.state.stroke_opts.set_line_dash_offset(offset);
+    }
+
     pub fn set_transform(&mut self, transform: &Transform2D<f32>) {
         // If there is an in-progress path, store the existing transformation required
         // to move between device and user space.
@@ -954,6 +1477,23 @@ impl<'a> CanvasData<'a> {
         });
     }
 
+    /// Sends the pixels like `send_pixels`, then clears them to transparent
+    /// black, leaving the drawing state untouched.
+    pub fn transfer_pixels(&mut self, chan: IpcSender<IpcSharedMemory>) {
+        self.send_pixels(chan);
+        let size = self.drawtarget.get_size();
+        let source_surface = self
+            .drawtarget
+            .create_source_surface_from_data(
+                &vec![0; size.width as usize * size.height as usize * 4],
+                size,
+                size.width * 4,
+            )
+            .unwrap();
+        self.drawtarget
+            .copy_surface(source_surface, Rect::from_size(size), Point2D::zero());
+    }
+
     pub fn send_data(&mut self, chan: IpcSender<CanvasImageData>) {
         let size = self.drawtarget.get_size();
 
@@ -1032,6 +1572,22 @@ impl<'a> CanvasData<'a> {
         self.backend.set_shadow_color(value, &mut self.state);
     }
 
+    pub fn set_filter(&mut self, filter: Vec<FilterFunction>) {
+        self.state.filter = filter;
+    }
+
+    pub fn set_font(&mut self, font: CanvasFontStyle) {
+        self.state.font_style = font_style_struct(&font);
+    }
+
+    pub fn set_text_align(&mut self, text_align: TextAlign) {
+        self.state.text_align = text_align;
+    }
+
+    pub fn set_text_baseline(&mut self, text_baseline: TextBaseline) {
+        self.state.text_baseline = text_baseline;
+    }
+
     // https://html.spec.whatwg.org/multipage/#when-shadows-are-drawn
     fn need_to_draw_shadow(&self) -> bool {
         self.backend.need_to_draw_shadow(&self.state.shadow_color) &&
@@ -1078,6 +1634,32 @@ impl<'a> CanvasData<'a> {
         );
     }
 
+    /// Draws with the given closure, either directly or, if a filter is set,
+    /// in a transparent layer that is then filtered and composited onto the
+    /// canvas.
+    ///
+    /// https://html.spec.whatwg.org/multipage/#drawing-model
+    fn draw_with_filter<F>(&mut self, draw: F)
+    where
+        F: FnOnce(&mut dyn GenericDrawTarget, &CanvasPaintState<'a>),
+    {
+        if self.state.filter.is_empty() {
+            return draw(&mut *self.drawtarget, &self.state);
+        }
+
+        let mut layer = self
+            .drawtarget
+            .create_similar_draw_target(&self.drawtarget.get_size(), self.drawtarget.get_format());
+        layer.set_transform(&self.drawtarget.get_transform());
+        draw(&mut *layer, &self.state);
+        self.drawtarget.draw_surface_with_filters(
+            layer.snapshot(),
+            layer.get_size(),
+            &self.state.filter,
+            self.backend.get_composition_op(&self.state.draw_options),
+        );
+    }
+
     /// It reads image data from the canvas
     /// canvas_size: The size of the canvas we're reading from
     /// read_rect: The area of the canvas we want to read from
@@ -1124,6 +1706,20 @@ pub struct CanvasPaintState<'a> {
     pub shadow_offset_y: f64,
     pub shadow_blur: f64,
     pub shadow_color: Color,
+    pub font_style: ServoArc<FontStyleStruct>,
+    pub text_align: TextAlign,
+    pub text_baseline: TextBaseline,
+    pub filter: Vec<FilterFunction>,
+}
+
+// https://html.spec.whatwg.org/multipage/#text-preparation-algorithm
+fn replace_ascii_whitespace(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\u{0009}' | '\u{000A}' | '\u{000C}' | '\u{000D}' => ' ',
+            _ => c,
+        })
+        .collect()
 }
 
 /// It writes an image to the destination target
@@ -1132,12 +1728,14 @@ pub struct CanvasPaintState<'a> {
 /// image_size: The size of the image to be written
 /// dest_rect: Area of the destination target where the pixels will be copied
 /// smoothing_enabled: It determines if smoothing is applied to the image result
+/// smoothing_quality: The quality of the smoothing, if it is applied
 fn write_image(
     draw_target: &mut dyn GenericDrawTarget,
     image_data: Vec<u8>,
     image_size: Size2D<f64>,
     dest_rect: Rect<f64>,
     smoothing_enabled: bool,
+    smoothing_quality: ImageSmoothingQuality,
     draw_options: &DrawOptions,
 ) {
     if image_data.is_empty() {
@@ -1149,10 +1747,10 @@ fn write_image(
     // When scaling up, if the imageSmoothingEnabled attribute is set to true, the user agent should attempt
     // to apply a smoothing algorithm to the image data when it is scaled.
     // Otherwise, the image must be rendered using nearest-neighbor interpolation.
-    let filter = if smoothing_enabled {
-        Filter::Linear
-    } else {
-        Filter::Point
+    let filter = match (smoothing_enabled, smoothing_quality) {
+        (false, _) => Filter::Point,
+        (true, ImageSmoothingQuality::Low) => Filter::Linear,
+        (true, ImageSmoothingQuality::Medium) | (true, ImageSmoothingQuality::High) => Filter::Good,
     };
     let image_size = image_size.to_i32();
 
@@ -5,9 +5,13 @@
 use crate::canvas_data::*;
 use canvas_traits::canvas::*;
 use euclid::default::Size2D;
+use gfx::font_cache_thread::FontCacheThread;
+use gfx::font_context::FontContext;
 use ipc_channel::ipc::{self, IpcSender};
 use std::borrow::ToOwned;
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::rc::Rc;
 use std::thread;
 
 pub enum AntialiasMode {
@@ -18,24 +22,27 @@ pub enum AntialiasMode {
 pub struct CanvasPaintThread<'a> {
     canvases: HashMap<CanvasId, CanvasData<'a>>,
     next_canvas_id: CanvasId,
+    /// The font context shared by all the canvases, used to render text.
+    font_context: Rc<RefCell<FontContext<FontCacheThread>>>,
 }
 
 impl<'a> CanvasPaintThread<'a> {
-    fn new() -> CanvasPaintThread<'a> {
+    fn new(font_cache_thread: FontCacheThread) -> CanvasPaintThread<'a> {
         CanvasPaintThread {
             canvases: HashMap::new(),
             next_canvas_id: CanvasId(0),
+            font_context: Rc::new(RefCell::new(FontContext::new(font_cache_thread))),
         }
     }
 
     /// Creates a new `CanvasPaintThread` and returns an `IpcSender` to
     /// communicate with it.
-    pub fn start() -> IpcSender<CanvasMsg> {
+    pub fn start(font_cache_thread: FontCacheThread) -> IpcSender<CanvasMsg> {
         let (sender, receiver) = ipc::channel::<CanvasMsg>().unwrap();
         thread::Builder::new()
             .name("CanvasThread".to_owned())
             .spawn(move || {
-                let mut canvas_paint_thread = CanvasPaintThread::new();
+                let mut canvas_paint_thread = CanvasPaintThread::new(font_cache_thread);
                 loop {
                     match receiver.recv() {
                         Ok(msg) => match msg {
@@ -60,6 +67,9 @@ impl<'a> CanvasPaintThread<'a> {
                                 FromScriptMsg::SendPixels(chan) => {
                                     canvas_paint_thread.canvas(canvas_id).send_pixels(chan);
                                 },
+                                FromScriptMsg::TransferPixels(chan) => {
+                                    canvas_paint_thread.canvas(canvas_id).transfer_pixels(chan);
+                                },
                             },
                             CanvasMsg::FromLayout(message, canvas_id) => match message {
                                 FromLayoutMsg::SendData(chan) => {
@@ -94,7 +104,13 @@ impl<'a> CanvasPaintThread<'a> {
         let canvas_id = self.next_canvas_id.clone();
         self.next_canvas_id.0 += 1;
 
-        let canvas_data = CanvasData::new(size, webrender_api_sender, antialias, canvas_id.clone());
+        let canvas_data = CanvasData::new(
+            size,
+            webrender_api_sender,
+            antialias,
+            canvas_id.clone(),
+            self.font_context.clone(),
+        );
         self.canvases.insert(canvas_id.clone(), canvas_data);
 
         canvas_id
@@ -102,26 +118,50 @@ impl<'a> CanvasPaintThread<'a> {
 
     fn process_canvas_2d_message(&mut self, message: Canvas2dMsg, canvas_id: CanvasId) {
         match message {
-            Canvas2dMsg::FillText(text, x, y, max_width) => {
-                self.canvas(canvas_id).fill_text(text, x, y, max_width)
+            Canvas2dMsg::FillText(text, x, y, max_width, direction) => self
+                .canvas(canvas_id)
+                .fill_text(text, x, y, max_width, direction),
+            Canvas2dMsg::StrokeText(text, x, y, max_width, direction) => self
+                .canvas(canvas_id)
+                .stroke_text(text, x, y, max_width, direction),
+            Canvas2dMsg::MeasureText(text, direction, chan) => {
+                let metrics = self.canvas(canvas_id).measure_text(text, direction);
+                chan.send(metrics).unwrap()
             },
             Canvas2dMsg::FillRect(ref rect) => self.canvas(canvas_id).fill_rect(rect),
             Canvas2dMsg::StrokeRect(ref rect) => self.canvas(canvas_id).stroke_rect(rect),
             Canvas2dMsg::ClearRect(ref rect) => self.canvas(canvas_id).clear_rect(rect),
             Canvas2dMsg::BeginPath => self.canvas(canvas_id).begin_path(),
             Canvas2dMsg::ClosePath => self.canvas(canvas_id).close_path(),
-            Canvas2dMsg::Fill => self.canvas(canvas_id).fill(),
+            Canvas2dMsg::Fill(fill_rule) => self.canvas(canvas_id).fill(fill_rule),
+            Canvas2dMsg::FillPath2D(segments, fill_rule) => {
+                self.canvas(canvas_id).fill_path2d(&segments, fill_rule)
+            },
             Canvas2dMsg::Stroke => self.canvas(canvas_id).stroke(),
-            Canvas2dMsg::Clip => self.canvas(canvas_id).clip(),
+            Canvas2dMsg::StrokePath2D(segments) => self.canvas(canvas_id).stroke_path2d(&segments),
+            Canvas2dMsg::Clip(fill_rule) => self.canvas(canvas_id).clip(fill_rule),
+            Canvas2dMsg::ClipPath2D(segments, fill_rule) => {
+                self.canvas(canvas_id).clip_path2d(&segments, fill_rule)
+            },
             Canvas2dMsg::IsPointInPath(x, y, fill_rule, chan) => self
                 .canvas(canvas_id)
                 .is_point_in_path(x, y, fill_rule, chan),
+            Canvas2dMsg::IsPointInPath2D(segments, x, y, fill_rule, chan) => self
+                .canvas(canvas_id)
+                .is_point_in_path2d(&segments, x, y, fill_rule, chan),
+            Canvas2dMsg::IsPointInStroke(x, y, chan) => {
+                self.canvas(canvas_id).is_point_in_stroke(x, y, chan)
+            },
+            Canvas2dMsg::IsPointInStroke2D(segments, x, y, chan) => self
+                .canvas(canvas_id)
+                .is_point_in_stroke2d(&segments, x, y, chan),
             Canvas2dMsg::DrawImage(
                 imagedata,
                 image_size,
                 dest_rect,
                 source_rect,
                 smoothing_enabled,
+                smoothing_quality,
             ) => {
                 let data = imagedata.map_or_else(
                     || vec![0; image_size.width as usize * image_size.height as usize * 4],
@@ -133,6 +173,7 @@ impl<'a> CanvasPaintThread<'a> {
                     dest_rect,
                     source_rect,
                     smoothing_enabled,
+                    smoothing_quality,
                 )
             },
             Canvas2dMsg::DrawImageInOther(
@@ -141,6 +182,7 @@ impl<'a> CanvasPaintThread<'a> {
                 dest_rect,
                 source_rect,
                 smoothing,
+                smoothing_quality,
             ) => {
                 let image_data = self
                     .canvas(canvas_id)
@@ -151,6 +193,7 @@ impl<'a> CanvasPaintThread<'a> {
                     dest_rect,
                     source_rect,
                     smoothing,
+                    smoothing_quality,
                 );
             },
             Canvas2dMsg::MoveTo(ref point) => self.canvas(canvas_id).move_to(point),
@@ -179,6 +222,10 @@ impl<'a> CanvasPaintThread<'a> {
             Canvas2dMsg::SetLineCap(cap) => self.canvas(canvas_id).set_line_cap(cap),
             Canvas2dMsg::SetLineJoin(join) => self.canvas(canvas_id).set_line_join(join),
             Canvas2dMsg::SetMiterLimit(limit) => self.canvas(canvas_id).set_miter_limit(limit),
+            Canvas2dMsg::SetLineDash(segments) => self.canvas(canvas_id).set_line_dash(segments),
+            Canvas2dMsg::SetLineDashOffset(offset) => {
+                self.canvas(canvas_id).set_line_dash_offset(offset)
+            },
             Canvas2dMsg::SetTransform(ref matrix) => self.canvas(canvas_id).set_transform(matrix),
             Canvas2dMsg::SetGlobalAlpha(alpha) => self.canvas(canvas_id).set_global_alpha(alpha),
             Canvas2dMsg::SetGlobalComposition(op) => {
@@ -200,6 +247,14 @@ impl<'a> CanvasPaintThread<'a> {
             },
             Canvas2dMsg::SetShadowBlur(value) => self.canvas(canvas_id).set_shadow_blur(value),
             Canvas2dMsg::SetShadowColor(color) => self.canvas(canvas_id).set_shadow_color(color),
+            Canvas2dMsg::SetFilter(filter) => self.canvas(canvas_id).set_filter(filter),
+            Canvas2dMsg::SetFont(font) => self.canvas(canvas_id).set_font(font),
+            Canvas2dMsg::SetTextAlign(text_align) => {
+                self.canvas(canvas_id).set_text_align(text_align)
+            },
+            Canvas2dMsg::SetTextBaseline(text_baseline) => {
+                self.canvas(canvas_id).set_text_baseline(text_baseline)
+            },
         }
     }
 
@@ -3,17 +3,29 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
 use crate::canvas_data::{
-    Backend, CanvasPaintState, Color, CompositionOp, DrawOptions, ExtendMode, Filter,
-    GenericDrawTarget, GenericPathBuilder, GradientStop, GradientStops, Path, Pattern,
-    SourceSurface, StrokeOptions, SurfaceFormat,
+    font_style_struct, Backend, CanvasPaintState, Color, CompositionOp, DrawOptions, ExtendMode,
+    Filter, GenericDrawTarget, GenericPathBuilder, GradientStop, GradientStops, Path, Pattern,
+    SourceSurface, StrokeOptions, SurfaceFormat, TextRun,
 };
 use crate::canvas_paint_thread::AntialiasMode;
 use canvas_traits::canvas::*;
 use cssparser::RGBA;
 use euclid::default::{Point2D, Rect, Size2D, Transform2D, Vector2D};
+use font_kit::hinting::HintingOptions;
+use font_kit::loaders::default::Font as FontKitFont;
+use gfx::font::{Font, FontHandleMethods};
+use gfx::text::glyph::ByteIndex;
+use lyon_path::builder::{FlatPathBuilder, PathBuilder as LyonPathBuilder};
+use lyon_path::math::{Angle, Vector};
+use range::Range;
 use raqote::PathOp;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::f32::consts::PI;
 use std::marker::PhantomData;
+use std::rc::Rc;
+use std::sync::Arc;
+use style::Atom;
 
 pub struct RaqoteBackend;
 
@@ -105,6 +117,10 @@ impl<'a> CanvasPaintState<'a> {
                 b: 0,
                 a: 0,
             }),
+            font_style: font_style_struct(&CanvasFontStyle::default()),
+            text_align: TextAlign::default(),
+            text_baseline: TextBaseline::default(),
+            filter: vec![],
         }
     }
 }
@@ -152,6 +168,16 @@ impl<'a> StrokeOptions<'a> {
             StrokeOptions::Raqote(options, _) => options.cap = val.to_raqote_style(),
         }
     }
+    pub fn set_line_dash(&mut self, val: Vec<f32>) {
+        match self {
+            StrokeOptions::Raqote(options, _) => options.dash_array = val,
+        }
+    }
+    pub fn set_line_dash_offset(&mut self, val: f32) {
+        match self {
+            StrokeOptions::Raqote(options, _) => options.dash_offset = val,
+        }
+    }
     pub fn as_raqote(&self) -> &raqote::StrokeStyle {
         match self {
             StrokeOptions::Raqote(options, _) => options,
@@ -187,8 +213,38 @@ impl Path {
         ))))
     }
 
-    pub fn contains_point(&self, x: f64, y: f64, _path_transform: &Transform2D<f32>) -> bool {
-        self.as_raqote().contains_point(0.1, x as f32, y as f32)
+    pub fn contains_point(&self, x: f64, y: f64, path_transform: &Transform2D<f32>) -> bool {
+        let point = match user_space_point(x, y, path_transform) {
+            Some(point) => point,
+            None => return false,
+        };
+        self.as_raqote().contains_point(0.1, point.x, point.y)
+    }
+
+    pub fn stroke_contains_point(
+        &self,
+        x: f64,
+        y: f64,
+        stroke_options: &StrokeOptions,
+        path_transform: &Transform2D<f32>,
+    ) -> bool {
+        let point = match user_space_point(x, y, path_transform) {
+            Some(point) => point,
+            None => return false,
+        };
+        raqote::stroke_to_path(self.as_raqote(), stroke_options.as_raqote())
+            .contains_point(0.1, point.x, point.y)
+    }
+
+    pub fn set_fill_rule(&mut self, fill_rule: FillRule) {
+        match self {
+            Path::Raqote(p) => {
+                p.winding = match fill_rule {
+                    FillRule::Nonzero => raqote::Winding::NonZero,
+                    FillRule::Evenodd => raqote::Winding::EvenOdd,
+                }
+            },
+        }
     }
 
     pub fn copy_to_builder(&self) -> Box<dyn GenericPathBuilder> {
@@ -204,6 +260,12 @@ impl Path {
     }
 }
 
+/// Maps a point of the canvas coordinate space to the user space of a path.
+fn user_space_point(x: f64, y: f64, path_transform: &Transform2D<f32>) -> Option<Point2D<f32>> {
+    let inverse = path_transform.inverse()?;
+    Some(inverse.transform_point(Point2D::new(x as f32, y as f32)))
+}
+
 impl GenericDrawTarget for raqote::DrawTarget {
     fn clear_rect(&mut self, rect: &Rect<f32>) {
         let mut pb = raqote::PathBuilder::new();
@@ -275,7 +337,7 @@ impl GenericDrawTarget for raqote::DrawTarget {
         surface: SourceSurface,
         dest: Rect<f64>,
         source: Rect<f64>,
-        _filter: Filter,
+        filter: Filter,
         draw_options: &DrawOptions,
     ) {
         let v = surface.as_raqote();
@@ -289,13 +351,24 @@ impl GenericDrawTarget for raqote::DrawTarget {
                 )
             },
         };
-        raqote::DrawTarget::draw_image_with_size_at(
+        let filter = match filter {
+            Filter::Good | Filter::Linear => raqote::FilterMode::Bilinear,
+            Filter::Point => raqote::FilterMode::Nearest,
+        };
+        let transform =
+            raqote::Transform::create_translation(-dest.origin.x as f32, -dest.origin.y as f32)
+                .post_scale(
+                    image.width as f32 / dest.size.width as f32,
+                    image.height as f32 / dest.size.height as f32,
+                );
+        let source = raqote::Source::Image(image, raqote::ExtendMode::Pad, filter, transform);
+        raqote::DrawTarget::fill_rect(
             self,
-            dest.size.width as f32,
-            dest.size.height as f32,
             dest.origin.x as f32,
             dest.origin.y as f32,
-            &image,
+            dest.size.width as f32,
+            dest.size.height as f32,
+            &source,
             draw_options.as_raqote(),
         );
     }
@@ -310,6 +383,40 @@ impl GenericDrawTarget for raqote::DrawTarget {
     ) {
         warn!("no support for drawing shadows");
     }
+    fn draw_surface_with_filters(
+        &mut self,
+        surface: SourceSurface,
+        size: Size2D<i32>,
+        filters: &[FilterFunction],
+        operator: CompositionOp,
+    ) {
+        let mut pixels: Vec<u32> = surface
+            .as_raqote()
+            .chunks_exact(4)
+            .map(|bytes| u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect();
+        let size = size.to_usize();
+        for filter in filters {
+            apply_filter(&mut pixels, size, filter);
+        }
+
+        let image = raqote::Image {
+            width: size.width as i32,
+            height: size.height as i32,
+            data: &pixels,
+        };
+        let draw_options = raqote::DrawOptions {
+            blend_mode: match operator {
+                CompositionOp::Raqote(blend_mode) => blend_mode,
+            },
+            ..raqote::DrawOptions::new()
+        };
+        // The layer is already in device space.
+        let transform = *self.get_transform();
+        self.set_transform(&raqote::Transform::identity());
+        self.draw_image_at(0., 0., &image, &draw_options);
+        self.set_transform(&transform);
+    }
     fn fill(&mut self, path: &Path, pattern: Pattern, draw_options: &DrawOptions) {
         self.fill(
             path.as_raqote(),
@@ -441,6 +548,153 @@ impl GenericDrawTarget for raqote::DrawTarget {
             .into()
         }
     }
+    fn fill_text(&mut self, text_runs: &[TextRun], pattern: Pattern, draw_options: &DrawOptions) {
+        self.fill(
+            &text_path(text_runs),
+            pattern.as_raqote(),
+            draw_options.as_raqote(),
+        );
+    }
+    fn stroke_text(
+        &mut self,
+        text_runs: &[TextRun],
+        pattern: Pattern,
+        stroke_options: &StrokeOptions,
+        draw_options: &DrawOptions,
+    ) {
+        self.stroke(
+            &text_path(text_runs),
+            pattern.as_raqote(),
+            stroke_options.as_raqote(),
+            draw_options.as_raqote(),
+        );
+    }
+}
+
+thread_local! {
+    /// The font-kit fonts used to get glyph outlines, keyed by the identifier of
+    /// their gfx font.
+    static FONT_KIT_FONTS: RefCell<HashMap<Atom, Option<Rc<FontKitFont>>>> =
+        RefCell::new(HashMap::new());
+}
+
+fn font_kit_font(font: &Font) -> Option<Rc<FontKitFont>> {
+    FONT_KIT_FONTS.with(|fonts| {
+        fonts
+            .borrow_mut()
+            .entry(font.identifier())
+            .or_insert_with(|| {
+                let bytes = font.handle.template().bytes();
+                match FontKitFont::from_bytes(Arc::new(bytes), 0) {
+                    Ok(font) => Some(Rc::new(font)),
+                    Err(error) => {
+                        warn!("Couldn't load font for canvas text: {:?}", error);
+                        None
+                    },
+                }
+            })
+            .clone()
+    })
+}
+
+/// Builds a path out of the glyph outlines of the given text runs, laid out
+/// along the x axis from the origin.
+fn text_path(text_runs: &[TextRun]) -> raqote::Path {
+    let mut builder = GlyphPathBuilder {
+        builder: raqote::PathBuilder::new(),
+        transform: Transform2D::identity(),
+        current_position: Point2D::zero(),
+    };
+    let mut advance = 0.;
+    for run in text_runs {
+        let font = run.font.borrow();
+        let font_kit_font = match font_kit_font(&font) {
+            Some(font_kit_font) => font_kit_font,
+            None => {
+                advance += run.advance();
+                continue;
+            },
+        };
+        let scale = font.actual_pt_size.to_f32_px() / font_kit_font.metrics().units_per_em as f32;
+        let range = Range::new(ByteIndex(0), run.glyphs.len());
+        for glyph in run.glyphs.iter_glyphs_for_byte_range(&range) {
+            let offset = glyph.offset().unwrap_or(Point2D::zero());
+            // Outlines are in font units, with the y axis pointing up.
+            builder.transform = Transform2D::create_scale(scale, -scale).post_translate(
+                Vector2D::new(advance + offset.x.to_f32_px(), offset.y.to_f32_px()),
+            );
+            if let Err(error) =
+                font_kit_font.outline(glyph.id(), HintingOptions::None, &mut builder)
+            {
+                warn!(
+                    "Couldn't get the outline of glyph {}: {:?}",
+                    glyph.id(),
+                    error
+                );
+            }
+            advance += glyph.advance().to_f32_px();
+        }
+    }
+    builder.builder.finish()
+}
+
+/// Adds the glyph outlines produced by font-kit to a raqote path.
+struct GlyphPathBuilder {
+    builder: raqote::PathBuilder,
+    /// The transform from font units to the coordinates of the path.
+    transform: Transform2D<f32>,
+    current_position: Point2D<f32>,
+}
+
+impl FlatPathBuilder for GlyphPathBuilder {
+    type PathType = ();
+
+    fn move_to(&mut self, to: Point2D<f32>) {
+        let point = self.transform.transform_point(to);
+        self.builder.move_to(point.x, point.y);
+        self.current_position = to;
+    }
+    fn line_to(&mut self, to: Point2D<f32>) {
+        let point = self.transform.transform_point(to);
+        self.builder.line_to(point.x, point.y);
+        self.current_position = to;
+    }
+    fn close(&mut self) {
+        self.builder.close();
+    }
+    fn build(self) {}
+    fn build_and_reset(&mut self) {}
+    fn current_position(&self) -> Point2D<f32> {
+        self.current_position
+    }
+}
+
+impl LyonPathBuilder for GlyphPathBuilder {
+    fn quadratic_bezier_to(&mut self, ctrl: Point2D<f32>, to: Point2D<f32>) {
+        let ctrl_point = self.transform.transform_point(ctrl);
+        let point = self.transform.transform_point(to);
+        self.builder
+            .quad_to(ctrl_point.x, ctrl_point.y, point.x, point.y);
+        self.current_position = to;
+    }
+    fn cubic_bezier_to(&mut self, ctrl1: Point2D<f32>, ctrl2: Point2D<f32>, to: Point2D<f32>) {
+        let ctrl_point1 = self.transform.transform_point(ctrl1);
+        let ctrl_point2 = self.transform.transform_point(ctrl2);
+        let point = self.transform.transform_point(to);
+        self.builder.cubic_to(
+            ctrl_point1.x,
+            ctrl_point1.y,
+            ctrl_point2.x,
+            ctrl_point2.y,
+            point.x,
+            point.y,
+        );
+        self.current_position = to;
+    }
+    fn arc(&mut self, _center: Point2D<f32>, _radii: Vector, _sweep: Angle, _x_rotation: Angle) {
+        // Glyph outlines are only made of lines and bézier curves.
+        warn!("Unexpected arc in glyph outline.");
+    }
 }
 
 struct PathBuilder(Option<raqote::PathBuilder>);
@@ -794,3 +1048,243 @@ impl GradientStop {
         }
     }
 }
+
+/// Applies a filter function to an image made of premultiplied ARGB pixels.
+///
+/// https://drafts.fxtf.org/filter-effects/#supported-filter-functions
+fn apply_filter(pixels: &mut Vec<u32>, size: Size2D<usize>, filter: &FilterFunction) {
+    match *filter {
+        FilterFunction::Blur(std_deviation) => blur(pixels, size, std_deviation),
+        FilterFunction::DropShadow(offset, std_deviation, color) => {
+            drop_shadow(pixels, size, offset, std_deviation, color)
+        },
+        ref filter => apply_color_matrix(pixels, &color_matrix(filter)),
+    }
+}
+
+/// Returns the `feColorMatrix` equivalent of a color filter function, in
+/// row-major order.
+// We rustfmt_skip here because we want the matrices to look like matrices.
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn color_matrix(filter: &FilterFunction) -> [f32; 20] {
+    match *filter {
+        FilterFunction::Brightness(amount) => [
+            amount, 0.,     0.,     0., 0.,
+            0.,     amount, 0.,     0., 0.,
+            0.,     0.,     amount, 0., 0.,
+            0.,     0.,     0.,     1., 0.,
+        ],
+        FilterFunction::Contrast(amount) => {
+            let intercept = 0.5 - 0.5 * amount;
+            [
+                amount, 0.,     0.,     0., intercept,
+                0.,     amount, 0.,     0., intercept,
+                0.,     0.,     amount, 0., intercept,
+                0.,     0.,     0.,     1., 0.,
+            ]
+        },
+        FilterFunction::Grayscale(amount) => {
+            let s = 1. - amount.min(1.);
+            [
+                0.2126 + 0.7874 * s, 0.7152 - 0.7152 * s, 0.0722 - 0.0722 * s, 0., 0.,
+                0.2126 - 0.2126 * s, 0.7152 + 0.2848 * s, 0.0722 - 0.0722 * s, 0., 0.,
+                0.2126 - 0.2126 * s, 0.7152 - 0.7152 * s, 0.0722 + 0.9278 * s, 0., 0.,
+                0.,                  0.,                  0.,                  1., 0.,
+            ]
+        },
+        FilterFunction::HueRotate(degrees) => {
+            let (sin, cos) = degrees.to_radians().sin_cos();
+            [
+                0.213 + cos * 0.787 - sin * 0.213,
+                0.715 - cos * 0.715 - sin * 0.715,
+                0.072 - cos * 0.072 + sin * 0.928, 0., 0.,
+                0.213 - cos * 0.213 + sin * 0.143,
+                0.715 + cos * 0.285 + sin * 0.140,
+                0.072 - cos * 0.072 - sin * 0.283, 0., 0.,
+                0.213 - cos * 0.213 - sin * 0.787,
+                0.715 - cos * 0.715 + sin * 0.715,
+                0.072 + cos * 0.928 + sin * 0.072, 0., 0.,
+                0., 0., 0., 1., 0.,
+            ]
+        },
+        FilterFunction::Invert(amount) => {
+            let slope = 1. - 2. * amount;
+            [
+                slope, 0.,    0.,    0., amount,
+                0.,    slope, 0.,    0., amount,
+                0.,    0.,    slope, 0., amount,
+                0.,    0.,    0.,    1., 0.,
+            ]
+        },
+        FilterFunction::Opacity(amount) => [
+            1., 0., 0., 0.,     0.,
+            0., 1., 0., 0.,     0.,
+            0., 0., 1., 0.,     0.,
+            0., 0., 0., amount, 0.,
+        ],
+        FilterFunction::Saturate(s) => [
+            0.213 + 0.787 * s, 0.715 - 0.715 * s, 0.072 - 0.072 * s, 0., 0.,
+            0.213 - 0.213 * s, 0.715 + 0.285 * s, 0.072 - 0.072 * s, 0., 0.,
+            0.213 - 0.213 * s, 0.715 - 0.715 * s, 0.072 + 0.928 * s, 0., 0.,
+            0.,                0.,                0.,                1., 0.,
+        ],
+        FilterFunction::Sepia(amount) => {
+            let s = 1. - amount.min(1.);
+            [
+                0.393 + 0.607 * s, 0.769 - 0.769 * s, 0.189 - 0.189 * s, 0., 0.,
+                0.349 - 0.349 * s, 0.686 + 0.314 * s, 0.168 - 0.168 * s, 0., 0.,
+                0.272 - 0.272 * s, 0.534 - 0.534 * s, 0.131 + 0.869 * s, 0., 0.,
+                0.,                0.,                0.,                1., 0.,
+            ]
+        },
+        FilterFunction::Blur(..) | FilterFunction::DropShadow(..) => {
+            unreachable!("not a color filter function")
+        },
+    }
+}
+
+/// Applies a `feColorMatrix` matrix, given in row-major order, to the
+/// unpremultiplied RGBA components of every pixel.
+fn apply_color_matrix(pixels: &mut [u32], matrix: &[f32; 20]) {
+    for pixel in pixels.iter_mut() {
+        let [a, r, g, b] = unpremultiply(*pixel);
+        let mut result = [0.; 4];
+        for (row, component) in result.iter_mut().enumerate() {
+            let m = &matrix[row * 5..row * 5 + 5];
+            *component = (m[0] * r + m[1] * g + m[2] * b + m[3] * a + m[4])
+                .max(0.)
+                .min(1.);
+        }
+        *pixel = premultiply(result[3], result[0], result[1], result[2]);
+    }
+}
+
+/// Returns the unpremultiplied alpha, red, green and blue components of a
+/// pixel, in the [0, 1] range.
+fn unpremultiply(pixel: u32) -> [f32; 4] {
+    let a = (pixel >> 24) as f32 / 255.;
+    if a == 0. {
+        return [0.; 4];
+    }
+    let component = |shift: u32| ((pixel >> shift) & 0xff) as f32 / 255. / a;
+    [a, component(16), component(8), component(0)]
+}
+
+fn premultiply(a: f32, r: f32, g: f32, b: f32) -> u32 {
+    let component = |value: f32| (value * a * 255.).round().max(0.).min(255.) as u32;
+    ((a * 255.).round() as u32) << 24 | component(r) << 16 | component(g) << 8 | component(b)
+}
+
+/// Approximates a gaussian blur with three successive box blurs in each
+/// direction.
+///
+/// https://drafts.fxtf.org/filter-effects/#feGaussianBlurElement
+fn blur(pixels: &mut Vec<u32>, size: Size2D<usize>, std_deviation: f32) {
+    let box_size = (std_deviation * 3. * (2. * PI).sqrt() / 4. + 0.5).floor();
+    if box_size <= 1. {
+        return;
+    }
+    let radius = box_size as usize / 2;
+    for _ in 0..3 {
+        *pixels = box_blur_rows(pixels, size.width, size.height, radius);
+    }
+    let mut transposed = transpose(pixels, size.width, size.height);
+    for _ in 0..3 {
+        transposed = box_blur_rows(&transposed, size.height, size.width, radius);
+    }
+    *pixels = transpose(&transposed, size.height, size.width);
+}
+
+/// Blurs each row of an image with a box of the given radius, treating the
+/// pixels outside of the image as transparent black.
+fn box_blur_rows(pixels: &[u32], width: usize, height: usize, radius: usize) -> Vec<u32> {
+    let box_size = 2 * radius as u32 + 1;
+    let mut result = vec![0; pixels.len()];
+    for y in 0..height {
+        let row = &pixels[y * width..(y + 1) * width];
+        let mut sums = [0u32; 4];
+        for &pixel in row.iter().take(radius) {
+            add_pixel(&mut sums, pixel);
+        }
+        for x in 0..width {
+            if x + radius < width {
+                add_pixel(&mut sums, row[x + radius]);
+            }
+            if x > radius {
+                remove_pixel(&mut sums, row[x - radius - 1]);
+            }
+            let component = |index: usize| (sums[index] + box_size / 2) / box_size;
+            result[y * width + x] =
+                component(0) << 24 | component(1) << 16 | component(2) << 8 | component(3);
+        }
+    }
+    result
+}
+
+fn add_pixel(sums: &mut [u32; 4], pixel: u32) {
+    for (index, sum) in sums.iter_mut().enumerate() {
+        *sum += (pixel >> (24 - 8 * index)) & 0xff;
+    }
+}
+
+fn remove_pixel(sums: &mut [u32; 4], pixel: u32) {
+    for (index, sum) in sums.iter_mut().enumerate() {
+        *sum -= (pixel >> (24 - 8 * index)) & 0xff;
+    }
+}
+
+fn transpose(pixels: &[u32], width: usize, height: usize) -> Vec<u32> {
+    let mut result = vec![0; pixels.len()];
+    for y in 0..height {
+        for x in 0..width {
+            result[x * height + y] = pixels[y * width + x];
+        }
+    }
+    result
+}
+
+/// Draws the image over a blurred and offset copy of its alpha mask, filled
+/// with the shadow color.
+///
+/// https://drafts.fxtf.org/filter-effects/#funcdef-filter-drop-shadow
+fn drop_shadow(
+    pixels: &mut Vec<u32>,
+    size: Size2D<usize>,
+    offset: Vector2D<f32>,
+    std_deviation: f32,
+    color: RGBA,
+) {
+    let (dx, dy) = (offset.x.round() as isize, offset.y.round() as isize);
+    let mut shadow = vec![0; pixels.len()];
+    for y in 0..size.height as isize {
+        for x in 0..size.width as isize {
+            let (source_x, source_y) = (x - dx, y - dy);
+            if source_x < 0 ||
+                source_y < 0 ||
+                source_x >= size.width as isize ||
+                source_y >= size.height as isize
+            {
+                continue;
+            }
+            let alpha = (pixels[source_y as usize * size.width + source_x as usize] >> 24) as f32;
+            shadow[y as usize * size.width + x as usize] = premultiply(
+                alpha / 255. * color.alpha_f32(),
+                color.red_f32(),
+                color.green_f32(),
+                color.blue_f32(),
+            );
+        }
+    }
+    blur(&mut shadow, size, std_deviation);
+
+    for (pixel, shadow) in pixels.iter_mut().zip(shadow) {
+        let inverse_alpha = 255 - (*pixel >> 24);
+        let mut result = 0;
+        for shift in &[24, 16, 8, 0] {
+            let source = (*pixel >> shift) & 0xff;
+            let backdrop = (shadow >> shift) & 0xff;
+            result |= (source + (backdrop * inverse_alpha + 127) / 255).min(255) << shift;
+        }
+        *pixel = result;
+    }
+}
@@ -3,18 +3,35 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
 use cssparser::RGBA;
-use euclid::default::{Point2D, Rect, Size2D, Transform2D};
+use euclid::default::{Point2D, Rect, Size2D, Transform2D, Vector2D};
 use ipc_channel::ipc::{IpcBytesReceiver, IpcBytesSender, IpcSender, IpcSharedMemory};
 use serde_bytes::ByteBuf;
 use std::default::Default;
+use std::fmt;
 use std::str::FromStr;
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
 pub enum FillRule {
     Nonzero,
     Evenodd,
 }
 
+/// A segment of a `Path2D` object, in the coordinate space of the path.
+#[derive(Clone, Debug, Deserialize, MallocSizeOf, Serialize)]
+pub enum PathSegment {
+    ClosePath,
+    MoveTo(Point2D<f32>),
+    LineTo(Point2D<f32>),
+    QuadraticCurveTo(Point2D<f32>, Point2D<f32>),
+    BezierCurveTo(Point2D<f32>, Point2D<f32>, Point2D<f32>),
+    Arc(Point2D<f32>, f32, f32, f32, bool),
+    ArcTo(Point2D<f32>, Point2D<f32>, f32),
+    Ellipse(Point2D<f32>, f32, f32, f32, f32, f32, bool),
+    Rect(Rect<f32>),
+    /// The segments of another path, transformed by the given matrix.
+    AddPath(Vec<PathSegment>, Transform2D<f32>),
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, MallocSizeOf, PartialEq, Serialize)]
 pub struct CanvasId(pub u64);
 
@@ -43,20 +60,40 @@ pub struct CanvasImageData {
 pub enum Canvas2dMsg {
     Arc(Point2D<f32>, f32, f32, f32, bool),
     ArcTo(Point2D<f32>, Point2D<f32>, f32),
-    DrawImage(Option<ByteBuf>, Size2D<f64>, Rect<f64>, Rect<f64>, bool),
-    DrawImageInOther(CanvasId, Size2D<f64>, Rect<f64>, Rect<f64>, bool),
+    DrawImage(
+        Option<ByteBuf>,
+        Size2D<f64>,
+        Rect<f64>,
+        Rect<f64>,
+        bool,
+        ImageSmoothingQuality,
+    ),
+    DrawImageInOther(
+        CanvasId,
+        Size2D<f64>,
+        Rect<f64>,
+        Rect<f64>,
+        bool,
+        ImageSmoothingQuality,
+    ),
     BeginPath,
     BezierCurveTo(Point2D<f32>, Point2D<f32>, Point2D<f32>),
     ClearRect(Rect<f32>),
-    Clip,
+    Clip(FillRule),
+    ClipPath2D(Vec<PathSegment>, FillRule),
     ClosePath,
     Ellipse(Point2D<f32>, f32, f32, f32, f32, f32, bool),
-    Fill,
-    FillText(String, f64, f64, Option<f64>),
+    Fill(FillRule),
+    FillPath2D(Vec<PathSegment>, FillRule),
+    FillText(String, f64, f64, Option<f64>, Direction),
     FillRect(Rect<f32>),
     GetImageData(Rect<u32>, Size2D<u32>, IpcBytesSender),
     IsPointInPath(f64, f64, FillRule, IpcSender<bool>),
+    IsPointInPath2D(Vec<PathSegment>, f64, f64, FillRule, IpcSender<bool>),
+    IsPointInStroke(f64, f64, IpcSender<bool>),
+    IsPointInStroke2D(Vec<PathSegment>, f64, f64, IpcSender<bool>),
     LineTo(Point2D<f32>),
+    MeasureText(String, Direction, IpcSender<TextMetrics>),
     MoveTo(Point2D<f32>),
     PutImageData(Rect<u32>, IpcBytesReceiver),
     QuadraticCurveTo(Point2D<f32>, Point2D<f32>),
@@ -65,12 +102,16 @@ pub enum Canvas2dMsg {
     SaveContext,
     StrokeRect(Rect<f32>),
     Stroke,
+    StrokePath2D(Vec<PathSegment>),
+    StrokeText(String, f64, f64, Option<f64>, Direction),
     SetFillStyle(FillOrStrokeStyle),
     SetStrokeStyle(FillOrStrokeStyle),
     SetLineWidth(f32),
     SetLineCap(LineCapStyle),
     SetLineJoin(LineJoinStyle),
     SetMiterLimit(f32),
+    SetLineDash(Vec<f32>),
+    SetLineDashOffset(f32),
     SetGlobalAlpha(f32),
     SetGlobalComposition(CompositionOrBlending),
     SetTransform(Transform2D<f32>),
@@ -78,6 +119,10 @@ pub enum Canvas2dMsg {
     SetShadowOffsetY(f64),
     SetShadowBlur(f64),
     SetShadowColor(RGBA),
+    SetFilter(Vec<FilterFunction>),
+    SetFont(CanvasFontStyle),
+    SetTextAlign(TextAlign),
+    SetTextBaseline(TextBaseline),
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -88,6 +133,7 @@ pub enum FromLayoutMsg {
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum FromScriptMsg {
     SendPixels(IpcSender<IpcSharedMemory>),
+    TransferPixels(IpcSender<IpcSharedMemory>),
 }
 
 #[derive(Clone, Debug, Deserialize, MallocSizeOf, Serialize)]
@@ -397,3 +443,254 @@ impl FromStr for CompositionOrBlending {
         Err(())
     }
 }
+
+#[derive(Clone, Copy, Debug, Deserialize, MallocSizeOf, PartialEq, Serialize)]
+pub enum TextAlign {
+    Start,
+    End,
+    Left,
+    Right,
+    Center,
+}
+
+impl FromStr for TextAlign {
+    type Err = ();
+
+    fn from_str(string: &str) -> Result<TextAlign, ()> {
+        match string {
+            "start" => Ok(TextAlign::Start),
+            "end" => Ok(TextAlign::End),
+            "left" => Ok(TextAlign::Left),
+            "right" => Ok(TextAlign::Right),
+            "center" => Ok(TextAlign::Center),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Default for TextAlign {
+    fn default() -> TextAlign {
+        TextAlign::Start
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, MallocSizeOf, PartialEq, Serialize)]
+pub enum TextBaseline {
+    Top,
+    Hanging,
+    Middle,
+    Alphabetic,
+    Ideographic,
+    Bottom,
+}
+
+impl FromStr for TextBaseline {
+    type Err = ();
+
+    fn from_str(string: &str) -> Result<TextBaseline, ()> {
+        match string {
+            "top" => Ok(TextBaseline::Top),
+            "hanging" => Ok(TextBaseline::Hanging),
+            "middle" => Ok(TextBaseline::Middle),
+            "alphabetic" => Ok(TextBaseline::Alphabetic),
+            "ideographic" => Ok(TextBaseline::Ideographic),
+            "bottom" => Ok(TextBaseline::Bottom),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Default for TextBaseline {
+    fn default() -> TextBaseline {
+        TextBaseline::Alphabetic
+    }
+}
+
+/// The resolved direction of a piece of canvas text.
+#[derive(Clone, Copy, Debug, Deserialize, MallocSizeOf, PartialEq, Serialize)]
+pub enum Direction {
+    Ltr,
+    Rtl,
+}
+
+impl Default for Direction {
+    fn default() -> Direction {
+        Direction::Ltr
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, MallocSizeOf, PartialEq, Serialize)]
+pub enum ImageSmoothingQuality {
+    Low,
+    Medium,
+    High,
+}
+
+impl Default for ImageSmoothingQuality {
+    fn default() -> ImageSmoothingQuality {
+        ImageSmoothingQuality::Low
+    }
+}
+
+/// A single function of the `filter` of a 2D context, with its lengths
+/// resolved to pixels and its factors resolved to numbers.
+///
+/// https://drafts.fxtf.org/filter-effects/#supported-filter-functions
+#[derive(Clone, Debug, Deserialize, MallocSizeOf, PartialEq, Serialize)]
+pub enum FilterFunction {
+    /// The standard deviation of the blur.
+    Blur(f32),
+    Brightness(f32),
+    Contrast(f32),
+    /// The offset of the shadow, the standard deviation of its blur and its
+    /// color.
+    DropShadow(Vector2D<f32>, f32, RGBA),
+    Grayscale(f32),
+    /// The rotation angle, in degrees.
+    HueRotate(f32),
+    Invert(f32),
+    Opacity(f32),
+    Saturate(f32),
+    Sepia(f32),
+}
+
+/// A single entry of the font family list of a `CanvasFontStyle`.
+#[derive(Clone, Debug, Deserialize, MallocSizeOf, PartialEq, Serialize)]
+pub enum CanvasFontFamily {
+    /// A named font family, such as `Times New Roman`.
+    Named(String),
+    /// A generic font family keyword, such as `sans-serif`.
+    Generic(String),
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, MallocSizeOf, PartialEq, Serialize)]
+pub enum CanvasFontSlant {
+    Normal,
+    Italic,
+    /// An oblique font style with the given angle in degrees.
+    Oblique(f32),
+}
+
+/// The default angle of `font-style: oblique`, in degrees.
+pub const DEFAULT_OBLIQUE_ANGLE: f32 = 14.;
+
+/// The computed value of the `font` attribute of a 2D canvas context, with
+/// every relative value resolved to an absolute one.
+#[derive(Clone, Debug, Deserialize, MallocSizeOf, PartialEq, Serialize)]
+pub struct CanvasFontStyle {
+    pub families: Vec<CanvasFontFamily>,
+    /// The font size, in CSS pixels.
+    pub size: f64,
+    pub weight: f32,
+    pub slant: CanvasFontSlant,
+    /// The font stretch, as a percentage of the normal width.
+    pub stretch: f32,
+    pub small_caps: bool,
+}
+
+impl Default for CanvasFontStyle {
+    /// The initial `10px sans-serif` font.
+    fn default() -> CanvasFontStyle {
+        CanvasFontStyle {
+            families: vec![CanvasFontFamily::Generic("sans-serif".to_owned())],
+            size: 10.,
+            weight: 400.,
+            slant: CanvasFontSlant::Normal,
+            stretch: 100.,
+            small_caps: false,
+        }
+    }
+}
+
+const FONT_STRETCH_KEYWORDS: [(f32, &str); 8] = [
+    (50., "ultra-condensed"),
+    (62.5, "extra-condensed"),
+    (75., "condensed"),
+    (87.5, "semi-condensed"),
+    (112.5, "semi-expanded"),
+    (125., "expanded"),
+    (150., "extra-expanded"),
+    (200., "ultra-expanded"),
+];
+
+const GENERIC_FONT_FAMILIES: [&str; 5] = ["serif", "sans-serif", "cursive", "fantasy", "monospace"];
+
+fn write_family_name(name: &str, f: &mut fmt::Formatter) -> fmt::Result {
+    let is_identifier = |word: &str| {
+        let mut chars = word.chars();
+        chars
+            .next()
+            .map_or(false, |c| c.is_alphabetic() || c == '_' || !c.is_ascii()) &&
+            chars.all(|c| c.is_alphanumeric() || c == '_' || c == '-' || !c.is_ascii())
+    };
+    let needs_quotes = GENERIC_FONT_FAMILIES.contains(&&*name.to_ascii_lowercase()) ||
+        !name.split(' ').all(is_identifier);
+    if !needs_quotes {
+        return f.write_str(name);
+    }
+    f.write_str("\"")?;
+    for c in name.chars() {
+        match c {
+            '"' | '\\' => write!(f, "\\{}", c)?,
+            _ => write!(f, "{}", c)?,
+        }
+    }
+    f.write_str("\"")
+}
+
+/// Serializes the font following the canonical order of the `font` shorthand,
+/// omitting every component that has its initial value.
+/// <https://html.spec.whatwg.org/multipage/#dom-context-2d-font>
+impl fmt::Display for CanvasFontStyle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.slant {
+            CanvasFontSlant::Normal => {},
+            CanvasFontSlant::Italic => f.write_str("italic ")?,
+            CanvasFontSlant::Oblique(angle) if angle == DEFAULT_OBLIQUE_ANGLE => {
+                f.write_str("oblique ")?
+            },
+            CanvasFontSlant::Oblique(angle) => write!(f, "oblique {}deg ", angle)?,
+        }
+        if self.small_caps {
+            f.write_str("small-caps ")?;
+        }
+        if self.weight == 700. {
+            f.write_str("bold ")?;
+        } else if self.weight != 400. {
+            write!(f, "{} ", self.weight)?;
+        }
+        if let Some(&(_, keyword)) = FONT_STRETCH_KEYWORDS
+            .iter()
+            .find(|&&(stretch, _)| stretch == self.stretch)
+        {
+            write!(f, "{} ", keyword)?;
+        }
+        write!(f, "{}px", self.size)?;
+        for (i, family) in self.families.iter().enumerate() {
+            f.write_str(if i == 0 { " " } else { ", " })?;
+            match *family {
+                CanvasFontFamily::Named(ref name) => write_family_name(name, f)?,
+                CanvasFontFamily::Generic(ref name) => f.write_str(name)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The result of measuring a piece of text, in CSS pixels.
+/// <https://html.spec.whatwg.org/multipage/#textmetrics>
+#[derive(Clone, Debug, Default, Deserialize, MallocSizeOf, Serialize)]
+pub struct TextMetrics {
+    pub width: f64,
+    pub actual_bounding_box_left: f64,
+    pub actual_bounding_box_right: f64,
+    pub font_bounding_box_ascent: f64,
+    pub font_bounding_box_descent: f64,
+    pub actual_bounding_box_ascent: f64,
+    pub actual_bounding_box_descent: f64,
+    pub em_height_ascent: f64,
+    pub em_height_descent: f64,
+    pub hanging_baseline: f64,
+    pub alphabetic_baseline: f64,
+    pub ideographic_baseline: f64,
+}
@@ -6,14 +6,15 @@ use crate::compositor_thread::CompositorReceiver;
 use crate::compositor_thread::{InitialCompositorState, Msg};
 #[cfg(feature = "gl")]
 use crate::gl;
-use crate::touch::{TouchAction, TouchHandler};
+use crate::scroll;
+use crate::touch::{TouchAction, TouchHandler, TouchState};
 use crate::windowing::{
-    self, EmbedderCoordinates, MouseWindowEvent, WebRenderDebugOption, WindowMethods,
+    self, EmbedderCoordinates, FileDragEvent, MouseWindowEvent, WebRenderDebugOption, WindowMethods,
 };
 use crate::CompositionPipeline;
 use crate::SendableFrameTree;
 use crossbeam_channel::Sender;
-use embedder_traits::Cursor;
+use embedder_traits::{Cursor, DragOperation};
 use euclid::{Point2D, Rect, Scale, Vector2D};
 use gfx_traits::Epoch;
 #[cfg(feature = "gl")]
@@ -27,8 +28,12 @@ use num_traits::FromPrimitive;
 #[cfg(feature = "gl")]
 use pixels::PixelFormat;
 use profile_traits::time::{self as profile_time, profile, ProfilerCategory};
-use script_traits::CompositorEvent::{MouseButtonEvent, MouseMoveEvent, TouchEvent, WheelEvent};
+use script_traits::CompositorEvent::{DragEvent, MouseButtonEvent, MouseMoveEvent};
+use script_traits::CompositorEvent::{TouchEvent, WheelEvent};
 use script_traits::{AnimationState, AnimationTickType, ConstellationMsg, LayoutControlMsg};
+use script_traits::{
+    CompositorEvent, DragData, DragEventType, DragImage, DraggedFile, ScrollContainerInfo,
+};
 use script_traits::{
     MouseButton, MouseEventType, ScrollState, TouchEventType, TouchId, WheelDelta,
 };
@@ -38,13 +43,20 @@ use std::collections::HashMap;
 use std::env;
 use std::fs::{create_dir_all, File};
 use std::io::Write;
+use std::mem;
 use std::num::NonZeroU32;
+use std::path::PathBuf;
 use std::rc::Rc;
 use style_traits::viewport::ViewportConstraints;
 use style_traits::{CSSPixel, DevicePixel, PinchZoomFactor};
 use time::{now, precise_time_ns, precise_time_s};
-use webrender_api::units::{DeviceIntPoint, DeviceIntSize, DevicePoint, LayoutVector2D};
-use webrender_api::{self, HitTestFlags, HitTestResult, ScrollLocation};
+use webrender_api::units::{DeviceIntPoint, DeviceIntSize, DevicePoint};
+use webrender_api::units::{LayoutPoint, LayoutRect, LayoutSize, LayoutVector2D};
+use webrender_api::HitTestResult;
+use webrender_api::{self, AlphaType, ClipId, ColorF, CommonItemProperties, DisplayItem};
+use webrender_api::{DisplayListBuilder, ExternalScrollId, HitTestFlags, HitTestItem};
+use webrender_api::{ImageDisplayItem, ImageRendering, ScrollLocation};
+use webrender_api::{SpaceAndClipInfo, SpatialId};
 use webvr_traits::WebVRMainThreadHeartbeat;
 
 #[derive(Debug, PartialEq)]
@@ -165,6 +177,21 @@ pub struct IOCompositor<Window: WindowMethods + ?Sized> {
     /// Whether we're waiting on a recomposite after dispatching a scroll.
     waiting_for_results_of_scroll: bool,
 
+    /// The last known scroll positions of the scroll roots of all pipelines.
+    scroll_positions: HashMap<ExternalScrollId, LayoutVector2D>,
+
+    /// The scroll positions that the scroll roots which input events scrolled since they
+    /// last snapped started at.
+    scroll_snap_starts: HashMap<ExternalScrollId, LayoutVector2D>,
+
+    /// Whether a scroll gesture, like a touch pan or a touchpad swipe, is in progress.
+    /// Scroll roots snap when the gesture ends.
+    scroll_gesture_active: bool,
+
+    /// Whether the scrolled scroll roots should snap once the pending scrolls are done,
+    /// and whether they should snap in the direction of the scroll.
+    pending_scroll_snap: Option<ScrollSnapDirectionality>,
+
     /// Used by the logic that determines when it is safe to output an
     /// image for the reftest framework.
     ready_to_save_state: ReadyState,
@@ -200,6 +227,12 @@ pub struct IOCompositor<Window: WindowMethods + ?Sized> {
     /// Current cursor position.
     cursor_pos: DevicePoint,
 
+    /// The drag-and-drop operation in progress, if any.
+    drag_session: Option<DragSession>,
+
+    /// The epoch of the display list drawing the drag image.
+    drag_image_epoch: Epoch,
+
     output_file: Option<String>,
 
     is_running_problem_test: bool,
@@ -214,6 +247,30 @@ pub struct IOCompositor<Window: WindowMethods + ?Sized> {
     device_pixels_per_px: Option<f32>,
 }
 
+/// The WebRender pipeline of the display list that draws the drag image over
+/// the root pipeline during a drag-and-drop operation.
+const DRAG_IMAGE_PIPELINE: webrender_api::PipelineId =
+    webrender_api::PipelineId(u32::max_value(), 0);
+
+/// The state of a drag-and-drop operation, whose events the compositor sends
+/// to the pipelines under the cursor instead of mouse events.
+struct DragSession {
+    /// The pipeline the drag started in, or `None` for files dragged from the
+    /// host.
+    source: Option<PipelineId>,
+    /// The dragged data.
+    data: DragData,
+    /// The paths of the files dragged from the host, which documents only get
+    /// to know once the files are dropped.
+    paths: Vec<PathBuf>,
+    /// The image drawn under the cursor.
+    image: Option<DragImage>,
+    /// The pipeline the cursor is over.
+    target: Option<PipelineId>,
+    /// The operation dropping on the target would perform.
+    operation: DragOperation,
+}
+
 #[derive(Clone, Copy)]
 struct ScrollZoomEvent {
     /// Change the pinch zoom level by this factor
@@ -251,6 +308,9 @@ struct PipelineDetails {
 
     /// Whether this pipeline is visible
     visible: bool,
+
+    /// The scroll containers of this pipeline that input events can scroll.
+    scroll_containers: Vec<ScrollContainerInfo>,
 }
 
 impl PipelineDetails {
@@ -260,10 +320,20 @@ impl PipelineDetails {
             animations_running: false,
             animation_callbacks_running: false,
             visible: true,
+            scroll_containers: vec![],
         }
     }
 }
 
+/// How scroll roots snap after the scrolls of input events.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ScrollSnapDirectionality {
+    /// Snap to the nearest snap position, after a scroll gesture.
+    Nearest,
+    /// Snap in the direction of the scroll, after a key press or a mouse wheel notch.
+    Directional,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 enum CompositeTarget {
     /// Normal composition to a window
@@ -302,6 +372,10 @@ impl<Window: WindowMethods + ?Sized> IOCompositor<Window> {
             touch_handler: TouchHandler::new(),
             pending_scroll_zoom_events: Vec::new(),
             waiting_for_results_of_scroll: false,
+            scroll_positions: HashMap::new(),
+            scroll_snap_starts: HashMap::new(),
+            scroll_gesture_active: false,
+            pending_scroll_snap: None,
             composite_target,
             shutdown_state: ShutdownState::NotShuttingDown,
             page_zoom: Scale::new(1.0),
@@ -323,6 +397,8 @@ impl<Window: WindowMethods + ?Sized> IOCompositor<Window> {
             pending_paint_metrics: HashMap::new(),
             cursor: Cursor::None,
             cursor_pos: DevicePoint::new(0.0, 0.0),
+            drag_session: None,
+            drag_image_epoch: Epoch(0),
             output_file,
             is_running_problem_test,
             exit_after_load,
@@ -443,6 +519,24 @@ impl<Window: WindowMethods + ?Sized> IOCompositor<Window> {
                 self.touch_handler.on_event_processed(result);
             },
 
+            (Msg::StartDrag(source, data, image), ShutdownState::NotShuttingDown) => {
+                self.drag_session = Some(DragSession {
+                    source: Some(source),
+                    data,
+                    paths: vec![],
+                    image,
+                    target: None,
+                    operation: DragOperation::None,
+                });
+                self.dispatch_drag_over(self.cursor_pos);
+            },
+
+            (Msg::DragOverProcessed(operation), ShutdownState::NotShuttingDown) => {
+                if let Some(ref mut session) = self.drag_session {
+                    session.operation = operation;
+                }
+            },
+
             (Msg::CreatePng(rect, reply), ShutdownState::NotShuttingDown) => {
                 let res = self.composite_specific_target(CompositeTarget::WindowAndPng, rect);
                 if let Err(ref e) = res {
@@ -461,6 +555,13 @@ impl<Window: WindowMethods + ?Sized> IOCompositor<Window> {
                 self.constrain_viewport(pipeline_id, constraints);
             },
 
+            (
+                Msg::ScrollContainers(pipeline_id, scroll_containers),
+                ShutdownState::NotShuttingDown,
+            ) => {
+                self.pipeline_details(pipeline_id).scroll_containers = scroll_containers;
+            },
+
             (Msg::IsReadyToSaveImageReply(is_ready), ShutdownState::NotShuttingDown) => {
                 assert_eq!(
                     self.ready_to_save_state,
@@ -729,6 +830,15 @@ impl<Window: WindowMethods + ?Sized> IOCompositor<Window> {
             MouseWindowEvent::MouseUp(_, p) => p,
         };
 
+        // Mouse buttons don't generate mouse events during drag-and-drop,
+        // and releasing them drops the dragged data.
+        if self.drag_session.is_some() {
+            if let MouseWindowEvent::MouseUp(..) = mouse_window_event {
+                self.end_drag(Some(point));
+            }
+            return;
+        }
+
         let results = self.hit_test_at_point(point);
         let result = match results.items.first() {
             Some(result) => result,
@@ -780,6 +890,12 @@ impl<Window: WindowMethods + ?Sized> IOCompositor<Window> {
     }
 
     fn dispatch_mouse_window_move_event_class(&mut self, cursor: DevicePoint) {
+        self.cursor_pos = cursor;
+        if self.drag_session.is_some() {
+            self.dispatch_drag_over(cursor);
+            return;
+        }
+
         let root_pipeline_id = match self.get_root_pipeline_id() {
             Some(root_pipeline_id) => root_pipeline_id,
             None => return,
@@ -843,6 +959,235 @@ impl<Window: WindowMethods + ?Sized> IOCompositor<Window> {
         }
     }
 
+    pub fn on_file_drag_event(&mut self, event: FileDragEvent) {
+        let (paths, point, dropped) = match event {
+            FileDragEvent::Over(paths, point) => (paths, point, false),
+            FileDragEvent::Drop(paths, point) => (paths, point, true),
+            FileDragEvent::Cancel => {
+                if let Some(DragSession { source: None, .. }) = self.drag_session {
+                    self.end_drag(None);
+                }
+                return;
+            },
+        };
+
+        let files = paths
+            .iter()
+            .map(|path| {
+                DraggedFile::Pending(
+                    mime_guess::from_path(path)
+                        .first()
+                        .map_or(String::new(), |mime| mime.to_string()),
+                )
+            })
+            .collect();
+        match self.drag_session.as_mut() {
+            Some(session) => {
+                // Files can't be dragged in while script is dragging data.
+                if session.source.is_some() {
+                    return;
+                }
+                session.data.files = files;
+                session.paths = paths;
+            },
+            None => {
+                self.drag_session = Some(DragSession {
+                    source: None,
+                    data: DragData {
+                        strings: vec![],
+                        files,
+                        effect_allowed: "all".to_owned(),
+                    },
+                    paths,
+                    image: None,
+                    target: None,
+                    operation: DragOperation::None,
+                });
+            },
+        }
+
+        self.cursor_pos = point;
+        self.dispatch_drag_over(point);
+        if dropped {
+            self.end_drag(Some(point));
+        }
+    }
+
+    /// Sends the events of a drag moving over the given point to the source
+    /// pipeline and to the pipelines it leaves and enters.
+    fn dispatch_drag_over(&mut self, point: DevicePoint) {
+        let results = self.hit_test_at_point(point);
+        let item = results.items.first();
+        let target = item.map(|item| PipelineId::from_webrender(item.pipeline));
+        let (source, old_target, data) = match self.drag_session {
+            Some(ref mut session) => {
+                let old_target = mem::replace(&mut session.target, target);
+                if old_target != target {
+                    session.operation = DragOperation::None;
+                }
+                (session.source, old_target, session.data.clone())
+            },
+            None => return,
+        };
+
+        if old_target != target {
+            if let Some(old_target) = old_target {
+                self.send_drag_event(old_target, DragEventType::Leave, None, data.clone());
+            }
+        }
+        if let Some(source) = source {
+            self.send_drag_event(source, DragEventType::Drag, None, data.clone());
+        }
+        if let (Some(target), Some(item)) = (target, item) {
+            self.send_drag_event(target, DragEventType::Over, Some(item), data);
+        }
+
+        self.update_drag_image();
+    }
+
+    /// Ends the drag-and-drop operation, dropping the data at the given point
+    /// if the pipeline under it accepts the drop, or canceling it otherwise.
+    fn end_drag(&mut self, point: Option<DevicePoint>) {
+        let session = match self.drag_session.take() {
+            Some(session) => session,
+            None => return,
+        };
+
+        let results = point.map(|point| self.hit_test_at_point(point));
+        let item = results.as_ref().and_then(|results| results.items.first());
+        let mut operation = DragOperation::None;
+        if let Some(target) = session.target {
+            match item {
+                Some(item)
+                    if session.operation != DragOperation::None &&
+                        PipelineId::from_webrender(item.pipeline) == target =>
+                {
+                    operation = session.operation;
+                    let event =
+                        self.drag_event(DragEventType::Drop, Some(item), session.data.clone());
+                    let msg = if session.paths.is_empty() {
+                        ConstellationMsg::ForwardEvent(target, event)
+                    } else {
+                        ConstellationMsg::ForwardDropEvent(target, event, session.paths.clone())
+                    };
+                    if let Err(e) = self.constellation_chan.send(msg) {
+                        warn!("Sending event to constellation failed ({:?}).", e);
+                    }
+                }
+                _ => {
+                    self.send_drag_event(target, DragEventType::Leave, None, session.data.clone());
+                },
+            }
+        }
+        if let Some(source) = session.source {
+            self.send_drag_event(source, DragEventType::End(operation), None, session.data);
+        }
+
+        self.update_drag_image();
+
+        // The embedder may have shown the drag operation with the cursor.
+        self.cursor = Cursor::None;
+        self.update_cursor(self.hit_test_at_point(self.cursor_pos));
+    }
+
+    fn send_drag_event(
+        &self,
+        pipeline_id: PipelineId,
+        event_type: DragEventType,
+        item: Option<&HitTestItem>,
+        data: DragData,
+    ) {
+        let event = self.drag_event(event_type, item, data);
+        let msg = ConstellationMsg::ForwardEvent(pipeline_id, event);
+        if let Err(e) = self.constellation_chan.send(msg) {
+            warn!("Sending event to constellation failed ({:?}).", e);
+        }
+    }
+
+    fn drag_event(
+        &self,
+        event_type: DragEventType,
+        item: Option<&HitTestItem>,
+        data: DragData,
+    ) -> CompositorEvent {
+        DragEvent(
+            event_type,
+            item.map(|item| item.point_in_viewport.to_untyped()),
+            item.map(|item| UntrustedNodeAddress(item.tag.0 as *const c_void)),
+            data,
+        )
+    }
+
+    /// Draws the drag image under the cursor by making the root of the
+    /// WebRender document a display list with the image over the root
+    /// pipeline, or removes it when there is no image to draw.
+    fn update_drag_image(&mut self) {
+        let root_pipeline_id = match self.get_root_pipeline_id() {
+            Some(root_pipeline_id) => root_pipeline_id.to_webrender(),
+            None => return,
+        };
+
+        let mut txn = webrender_api::Transaction::new();
+        match self.drag_session.as_ref().and_then(|session| session.image) {
+            Some(image) => {
+                let dppx = self.device_pixels_per_page_px();
+                let viewport_size = self.embedder_coordinates.viewport.size.to_f32() / dppx;
+                let viewport_size = LayoutSize::from_untyped(viewport_size.to_untyped());
+                let cursor = self.cursor_pos / dppx - image.hot_spot;
+                let bounds = LayoutRect::new(
+                    LayoutPoint::from_untyped(cursor.to_untyped()),
+                    LayoutSize::from_untyped(image.size.to_untyped()),
+                );
+                let viewport = LayoutRect::new(LayoutPoint::zero(), viewport_size);
+                let space_and_clip = SpaceAndClipInfo {
+                    spatial_id: SpatialId::root_scroll_node(DRAG_IMAGE_PIPELINE),
+                    clip_id: ClipId::root(DRAG_IMAGE_PIPELINE),
+                };
+
+                let mut builder = DisplayListBuilder::new(DRAG_IMAGE_PIPELINE, viewport_size);
+                builder.push_iframe(viewport, viewport, &space_and_clip, root_pipeline_id, true);
+                builder.push_item(&DisplayItem::Image(ImageDisplayItem {
+                    bounds,
+                    common: CommonItemProperties {
+                        clip_rect: bounds,
+                        spatial_id: space_and_clip.spatial_id,
+                        clip_id: space_and_clip.clip_id,
+                        is_backface_visible: true,
+                        hit_info: None,
+                    },
+                    image_key: image.key,
+                    stretch_size: bounds.size,
+                    tile_spacing: LayoutSize::zero(),
+                    image_rendering: ImageRendering::Auto,
+                    alpha_type: AlphaType::PremultipliedAlpha,
+                    // Drag images are translucent, so the drop target stays visible.
+                    color: ColorF::new(1.0, 1.0, 1.0, 0.7),
+                }));
+
+                self.drag_image_epoch.next();
+                txn.set_display_list(
+                    webrender_api::Epoch(self.drag_image_epoch.0),
+                    None,
+                    viewport_size,
+                    builder.finalize(),
+                    true,
+                );
+                txn.set_root_pipeline(DRAG_IMAGE_PIPELINE);
+            },
+            None => {
+                if self.drag_image_epoch == Epoch(0) {
+                    return;
+                }
+                self.drag_image_epoch = Epoch(0);
+                txn.set_root_pipeline(root_pipeline_id);
+                txn.remove_pipeline(DRAG_IMAGE_PIPELINE);
+            },
+        }
+        txn.generate_frame();
+        self.webrender_api
+            .send_transaction(self.webrender_document, txn);
+    }
+
     pub fn on_touch_event(
         &mut self,
         event_type: TouchEventType,
@@ -864,10 +1209,22 @@ impl<Window: WindowMethods + ?Sized> IOCompositor<Window> {
 
     fn on_touch_move(&mut self, identifier: TouchId, point: DevicePoint) {
         match self.touch_handler.on_touch_move(identifier, point) {
-            TouchAction::Scroll(delta) => self.on_scroll_window_event(
-                ScrollLocation::Delta(LayoutVector2D::from_untyped(delta.to_untyped())),
-                point.cast(),
-            ),
+            TouchAction::Scroll(delta) => {
+                self.scroll_gesture_active = true;
+                self.on_scroll_window_event(
+                    ScrollLocation::Delta(LayoutVector2D::from_untyped(delta.to_untyped())),
+                    point.cast(),
+                )
+            },
+            TouchAction::StartScroll(delta) => {
+                // https://w3c.github.io/pointerevents/#the-pointercancel-event
+                self.send_touch_event(TouchEventType::Cancel, identifier, point);
+                self.scroll_gesture_active = true;
+                self.on_scroll_window_event(
+                    ScrollLocation::Delta(LayoutVector2D::from_untyped(delta.to_untyped())),
+                    point.cast(),
+                )
+            },
             TouchAction::Zoom(magnification, scroll_delta) => {
                 let cursor = Point2D::new(-1, -1); // Make sure this hits the base layer.
                 self.pending_scroll_zoom_events.push(ScrollZoomEvent {
@@ -887,17 +1244,37 @@ impl<Window: WindowMethods + ?Sized> IOCompositor<Window> {
     }
 
     fn on_touch_up(&mut self, identifier: TouchId, point: DevicePoint) {
-        self.send_touch_event(TouchEventType::Up, identifier, point);
+        // Content already got a touchcancel for touch points used for scrolling.
+        if !self.touch_handler.is_canceled(identifier) {
+            self.send_touch_event(TouchEventType::Up, identifier, point);
+        }
 
         if let TouchAction::Click = self.touch_handler.on_touch_up(identifier, point) {
             self.simulate_mouse_click(point);
         }
+        self.maybe_end_touch_scroll_gesture();
     }
 
     fn on_touch_cancel(&mut self, identifier: TouchId, point: DevicePoint) {
-        // Send the event to script.
+        let already_canceled = self.touch_handler.is_canceled(identifier);
         self.touch_handler.on_touch_cancel(identifier, point);
-        self.send_touch_event(TouchEventType::Cancel, identifier, point);
+        // Send the event to script, unless it was sent when scrolling started.
+        if !already_canceled {
+            self.send_touch_event(TouchEventType::Cancel, identifier, point);
+        }
+        self.maybe_end_touch_scroll_gesture();
+    }
+
+    /// Ends the scroll gesture of a touch pan once all the touch points are lifted.
+    fn maybe_end_touch_scroll_gesture(&mut self) {
+        if self.scroll_gesture_active && self.touch_handler.state == TouchState::Nothing {
+            self.end_scroll_gesture();
+        }
+    }
+
+    fn end_scroll_gesture(&mut self) {
+        self.scroll_gesture_active = false;
+        self.pending_scroll_snap = Some(ScrollSnapDirectionality::Nearest);
     }
 
     /// <http://w3c.github.io/touch-events/#mouse-events>
@@ -920,11 +1297,20 @@ impl<Window: WindowMethods + ?Sized> IOCompositor<Window> {
         phase: TouchEventType,
     ) {
         match phase {
-            TouchEventType::Move => self.on_scroll_window_event(delta, cursor),
+            TouchEventType::Move => {
+                self.on_scroll_window_event(delta, cursor);
+                // Scrolls outside of gestures, like those of keys and mouse wheel notches,
+                // snap right away.
+                if !self.scroll_gesture_active {
+                    self.pending_scroll_snap = Some(ScrollSnapDirectionality::Directional);
+                }
+            },
             TouchEventType::Up | TouchEventType::Cancel => {
                 self.on_scroll_window_event(delta, cursor);
+                self.end_scroll_gesture();
             },
             TouchEventType::Down => {
+                self.scroll_gesture_active = true;
                 self.on_scroll_window_event(delta, cursor);
             },
         }
@@ -987,17 +1373,20 @@ impl<Window: WindowMethods + ?Sized> IOCompositor<Window> {
         }
 
         if let Some(combined_event) = last_combined_event {
+            let cursor = (combined_event.cursor.to_f32() / self.scale).to_untyped();
+            let layout_cursor = LayoutPoint::from_untyped(cursor);
             let scroll_location = match combined_event.scroll_location {
                 ScrollLocation::Delta(delta) => {
                     let scaled_delta =
                         (Vector2D::from_untyped(delta.to_untyped()) / self.scale).to_untyped();
                     let calculated_delta = LayoutVector2D::from_untyped(scaled_delta);
-                    ScrollLocation::Delta(calculated_delta)
+                    ScrollLocation::Delta(
+                        self.contain_scroll_delta(layout_cursor, calculated_delta),
+                    )
                 },
                 // Leave ScrollLocation unchanged if it is Start or End location.
                 sl @ ScrollLocation::Start | sl @ ScrollLocation::End => sl,
             };
-            let cursor = (combined_event.cursor.to_f32() / self.scale).to_untyped();
             let cursor = webrender_api::units::WorldPoint::from_untyped(cursor);
             let mut txn = webrender_api::Transaction::new();
             txn.scroll(scroll_location, cursor);
@@ -1013,6 +1402,77 @@ impl<Window: WindowMethods + ?Sized> IOCompositor<Window> {
         }
 
         if had_events {
+            let old_scroll_positions = self.scroll_positions.clone();
+            self.send_viewport_rects();
+            for (scroll_id, position) in &self.scroll_positions {
+                match old_scroll_positions.get(scroll_id) {
+                    Some(old_position) if old_position != position => {
+                        self.scroll_snap_starts
+                            .entry(*scroll_id)
+                            .or_insert(*old_position);
+                    },
+                    _ => {},
+                }
+            }
+        }
+    }
+
+    /// Drops the parts of a scroll delta at `cursor` that the `overscroll-behavior` of the
+    /// scroll containers of the root pipeline under the cursor doesn't let chain.
+    fn contain_scroll_delta(&self, cursor: LayoutPoint, delta: LayoutVector2D) -> LayoutVector2D {
+        let details = self
+            .get_root_pipeline_id()
+            .and_then(|pipeline_id| self.pipeline_details.get(&pipeline_id));
+        match details {
+            Some(details) => scroll::contain_scroll_delta(
+                &details.scroll_containers,
+                &self.scroll_positions,
+                cursor,
+                delta,
+            ),
+            None => delta,
+        }
+    }
+
+    /// Snaps the scroll roots that input events scrolled since they last snapped.
+    ///
+    /// <https://drafts.csswg.org/css-scroll-snap-1/#re-snap>
+    fn snap_scrolled_scroll_roots(&mut self, directionality: ScrollSnapDirectionality) {
+        let mut txn = webrender_api::Transaction::new();
+        let mut snapped = false;
+        for (scroll_id, start) in self.scroll_snap_starts.drain() {
+            let pipeline_id = scroll_id.pipeline_id().from_webrender();
+            let container = self.pipeline_details.get(&pipeline_id).and_then(|details| {
+                details
+                    .scroll_containers
+                    .iter()
+                    .find(|container| container.scroll_id == scroll_id)
+            });
+            let container = match container {
+                Some(container) => container,
+                None => continue,
+            };
+
+            let position = match self.scroll_positions.get(&scroll_id) {
+                Some(position) => *position,
+                None => continue,
+            };
+            let directional = directionality == ScrollSnapDirectionality::Directional;
+            let snap_position = scroll::snap_position(container, start, position, directional);
+            if snap_position != position {
+                txn.scroll_node_with_id(
+                    snap_position.to_point(),
+                    scroll_id,
+                    webrender_api::ScrollClamping::ToContentBounds,
+                );
+                snapped = true;
+            }
+        }
+
+        if snapped {
+            txn.generate_frame();
+            self.webrender_api
+                .send_transaction(self.webrender_document, txn);
             self.send_viewport_rects();
         }
     }
@@ -1136,8 +1596,9 @@ impl<Window: WindowMethods + ?Sized> IOCompositor<Window> {
         });
     }
 
-    fn send_viewport_rects(&self) {
+    fn send_viewport_rects(&mut self) {
         let mut scroll_states_per_pipeline = HashMap::new();
+        self.scroll_positions.clear();
         for scroll_layer_state in self
             .webrender_api
             .get_scroll_node_state(self.webrender_document)
@@ -1146,6 +1607,8 @@ impl<Window: WindowMethods + ?Sized> IOCompositor<Window> {
                 scroll_id: scroll_layer_state.id,
                 scroll_offset: scroll_layer_state.scroll_offset,
             };
+            self.scroll_positions
+                .insert(scroll_layer_state.id, -scroll_layer_state.scroll_offset);
 
             scroll_states_per_pipeline
                 .entry(scroll_layer_state.id.pipeline_id())
@@ -1520,8 +1983,12 @@ impl<Window: WindowMethods + ?Sized> IOCompositor<Window> {
         // Run the WebXR main thread
         self.webxr_main_thread.run_one_frame();
 
-        if !self.pending_scroll_zoom_events.is_empty() && !self.waiting_for_results_of_scroll {
-            self.process_pending_scroll_events()
+        if !self.waiting_for_results_of_scroll {
+            if !self.pending_scroll_zoom_events.is_empty() {
+                self.process_pending_scroll_events()
+            } else if let Some(directionality) = self.pending_scroll_snap.take() {
+                self.snap_scrolled_scroll_roots(directionality)
+            }
         }
         self.shutdown_state != ShutdownState::FinishedShuttingDown
     }
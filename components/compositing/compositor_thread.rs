@@ -7,7 +7,7 @@
 use crate::compositor::CompositingReason;
 use crate::SendableFrameTree;
 use crossbeam_channel::{Receiver, Sender};
-use embedder_traits::EventLoopWaker;
+use embedder_traits::{DragOperation, EventLoopWaker};
 use euclid::Rect;
 use gfx_traits::Epoch;
 use ipc_channel::ipc::IpcSender;
@@ -16,6 +16,7 @@ use net_traits::image::base::Image;
 use profile_traits::mem;
 use profile_traits::time;
 use script_traits::{AnimationState, ConstellationMsg, EventResult, MouseButton, MouseEventType};
+use script_traits::{DragData, DragImage, ScrollContainerInfo};
 use std::fmt::{Debug, Error, Formatter};
 use style_traits::viewport::ViewportConstraints;
 use style_traits::CSSPixel;
@@ -81,10 +82,17 @@ pub enum Msg {
     Recomposite(CompositingReason),
     /// Script has handled a touch event, and either prevented or allowed default actions.
     TouchEventProcessed(EventResult),
+    /// The user started dragging data out of the given pipeline.
+    StartDrag(PipelineId, DragData, Option<DragImage>),
+    /// Script has determined the operation that dropping at the current
+    /// position would perform.
+    DragOverProcessed(DragOperation),
     /// Composite to a PNG file and return the Image over a passed channel.
     CreatePng(Option<Rect<f32, CSSPixel>>, IpcSender<Option<Image>>),
     /// Alerts the compositor that the viewport has been constrained in some manner
     ViewportConstrained(PipelineId, ViewportConstraints),
+    /// Replaces the scroll containers of the given pipeline that input events can scroll.
+    ScrollContainers(PipelineId, Vec<ScrollContainerInfo>),
     /// A reply to the compositor asking if the output image is stable.
     IsReadyToSaveImageReply(bool),
     /// Pipeline visibility changed
@@ -129,8 +137,11 @@ impl Debug for Msg {
             Msg::SetFrameTree(..) => write!(f, "SetFrameTree"),
             Msg::Recomposite(..) => write!(f, "Recomposite"),
             Msg::TouchEventProcessed(..) => write!(f, "TouchEventProcessed"),
+            Msg::StartDrag(..) => write!(f, "StartDrag"),
+            Msg::DragOverProcessed(..) => write!(f, "DragOverProcessed"),
             Msg::CreatePng(..) => write!(f, "CreatePng"),
             Msg::ViewportConstrained(..) => write!(f, "ViewportConstrained"),
+            Msg::ScrollContainers(..) => write!(f, "ScrollContainers"),
             Msg::IsReadyToSaveImageReply(..) => write!(f, "IsReadyToSaveImageReply"),
             Msg::PipelineVisibilityChanged(..) => write!(f, "PipelineVisibilityChanged"),
             Msg::PipelineExited(..) => write!(f, "PipelineExited"),
@@ -20,7 +20,8 @@ mod compositor;
 pub mod compositor_thread;
 #[cfg(feature = "gl")]
 mod gl;
-mod touch;
+pub mod scroll;
+pub mod touch;
 pub mod windowing;
 
 pub struct SendableFrameTree {
@@ -0,0 +1,187 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Snapping and chaining of the scrolls that input events perform.
+
+use euclid::Vector2D;
+use script_traits::{OverscrollBehavior, ScrollAxisBehavior};
+use script_traits::{ScrollContainerInfo, ScrollSnapStrictness};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use webrender_api::units::{LayoutPoint, LayoutVector2D};
+use webrender_api::ExternalScrollId;
+
+/// The distance from a proximity snap position within which scrolls snap to it, as a
+/// fraction of the size of the snapport.
+const PROXIMITY_SNAP_THRESHOLD: f32 = 0.3;
+
+/// Returns the part of the scroll `delta` at `point` that may chain past the scroll
+/// containers under `point`, given the current scroll positions of the containers.
+///
+/// WebRender scrolls the innermost scroll container that can move and chains scrolls that
+/// reach the edge of a container to its ancestors, so this drops the axes along which a
+/// container that can't move doesn't let scrolls chain.
+///
+/// <https://drafts.csswg.org/css-overscroll-1/#scroll-chaining-and-boundary-default-actions>
+pub fn contain_scroll_delta(
+    containers: &[ScrollContainerInfo],
+    positions: &HashMap<ExternalScrollId, LayoutVector2D>,
+    point: LayoutPoint,
+    delta: LayoutVector2D,
+) -> LayoutVector2D {
+    let mut delta = delta;
+    for container in scroll_chain(containers, positions, point) {
+        // Scroll deltas move the contents, so they are opposite to scroll movements.
+        let position = scroll_position(positions, container.scroll_id);
+        let max_position = container.max_scroll_position;
+        if can_scroll(-delta.x, position.x, max_position.x) ||
+            can_scroll(-delta.y, position.y, max_position.y)
+        {
+            break;
+        }
+
+        if container.x.overscroll_behavior != OverscrollBehavior::Auto {
+            delta.x = 0.;
+        }
+        if container.y.overscroll_behavior != OverscrollBehavior::Auto {
+            delta.y = 0.;
+        }
+    }
+    delta
+}
+
+fn scroll_position(
+    positions: &HashMap<ExternalScrollId, LayoutVector2D>,
+    scroll_id: ExternalScrollId,
+) -> LayoutVector2D {
+    positions
+        .get(&scroll_id)
+        .cloned()
+        .unwrap_or_else(Vector2D::zero)
+}
+
+fn can_scroll(movement: f32, position: f32, max_position: f32) -> bool {
+    (movement < 0. && position > 0.) || (movement > 0. && position < max_position)
+}
+
+/// Returns the scroll containers under `point`, from the innermost one outwards.
+fn scroll_chain<'a>(
+    containers: &'a [ScrollContainerInfo],
+    positions: &HashMap<ExternalScrollId, LayoutVector2D>,
+    point: LayoutPoint,
+) -> Vec<&'a ScrollContainerInfo> {
+    let container_with_id =
+        move |scroll_id: ExternalScrollId| containers.iter().find(|c| c.scroll_id == scroll_id);
+    let ancestors = move |container: &'a ScrollContainerInfo| {
+        let mut ancestors = vec![];
+        let mut parent_scroll_id = container.parent_scroll_id;
+        while let Some(parent) = parent_scroll_id.and_then(container_with_id) {
+            ancestors.push(parent);
+            parent_scroll_id = parent.parent_scroll_id;
+        }
+        ancestors
+    };
+
+    // The scrollports move with the scroll positions of their ancestors.
+    let innermost = containers
+        .iter()
+        .map(|container| (container, ancestors(container)))
+        .filter(|&(container, ref ancestors)| {
+            let scrolled = ancestors
+                .iter()
+                .fold(Vector2D::zero(), |scrolled, ancestor| {
+                    scrolled + scroll_position(positions, ancestor.scroll_id)
+                });
+            container.scrollport.translate(-scrolled).contains(point)
+        })
+        .max_by_key(|&(_, ref ancestors)| ancestors.len());
+
+    match innermost {
+        Some((container, ancestors)) => {
+            let mut chain = vec![container];
+            chain.extend(ancestors);
+            chain
+        },
+        None => vec![],
+    }
+}
+
+/// Returns the scroll position that a container scrolled from `start` to `position` rests
+/// at after snapping.
+///
+/// Directional scrolls, like those of keys and mouse wheel notches, prefer the snap
+/// positions past `start` in the direction of the scroll.
+///
+/// <https://drafts.csswg.org/css-scroll-snap-1/#choosing>
+pub fn snap_position(
+    container: &ScrollContainerInfo,
+    start: LayoutVector2D,
+    position: LayoutVector2D,
+    directional: bool,
+) -> LayoutVector2D {
+    let snapport_size = container.scrollport.size;
+    Vector2D::new(
+        snap_axis(
+            &container.x,
+            start.x,
+            position.x,
+            snapport_size.width,
+            directional,
+        ),
+        snap_axis(
+            &container.y,
+            start.y,
+            position.y,
+            snapport_size.height,
+            directional,
+        ),
+    )
+}
+
+fn snap_axis(
+    behavior: &ScrollAxisBehavior,
+    start: f32,
+    position: f32,
+    snapport_size: f32,
+    directional: bool,
+) -> f32 {
+    let strictness = match behavior.snap_strictness {
+        Some(strictness) => strictness,
+        None => return position,
+    };
+
+    let compare_distances =
+        |a: &&f32, b: &&f32| (**a - position).abs().partial_cmp(&(**b - position).abs());
+    let nearest = |directional: bool| {
+        behavior
+            .snap_positions
+            .iter()
+            .filter(|&&snap_position| {
+                !directional || (snap_position - start) * (position - start) > 0.
+            })
+            .min_by(|a, b| compare_distances(a, b).unwrap_or(Ordering::Equal))
+            .cloned()
+    };
+
+    match nearest(directional).or_else(|| nearest(false)) {
+        Some(snap_position)
+            if strictness == ScrollSnapStrictness::Mandatory ||
+                (snap_position - position).abs() <= snapport_size * PROXIMITY_SNAP_THRESHOLD =>
+        {
+            snap_position
+        },
+        _ => position,
+    }
+}
+
+/// A function to be able to test the snapping of scrolls along one axis.
+pub fn snap_axis_for_testing(
+    behavior: &ScrollAxisBehavior,
+    start: f32,
+    position: f32,
+    snapport_size: f32,
+    directional: bool,
+) -> f32 {
+    snap_axis(behavior, start, position, snapport_size, directional)
+}
@@ -4,7 +4,7 @@
 
 use self::TouchState::*;
 use euclid::{Point2D, Scale, Vector2D};
-use script_traits::{EventResult, TouchId};
+use script_traits::{EventResult, TouchBehavior, TouchId};
 use style_traits::DevicePixel;
 
 /// Minimum number of `DeviceIndependentPixel` to begin touch scrolling.
@@ -13,12 +13,18 @@ const TOUCH_PAN_MIN_SCREEN_PX: f32 = 20.0;
 pub struct TouchHandler {
     pub state: TouchState,
     pub active_touch_points: Vec<TouchPoint>,
+    /// The default actions that `touch-action` allows for the current touch
+    /// sequence.
+    pub allowed_behavior: TouchBehavior,
 }
 
 #[derive(Clone, Copy, Debug)]
 pub struct TouchPoint {
     pub id: TouchId,
     pub point: Point2D<f32, DevicePixel>,
+    /// Whether content stopped receiving events for this touch point, because
+    /// it is used for scrolling.
+    pub canceled: bool,
 }
 
 impl TouchPoint {
@@ -26,6 +32,7 @@ impl TouchPoint {
         TouchPoint {
             id: id,
             point: point,
+            canceled: false,
         }
     }
 }
@@ -60,6 +67,9 @@ pub enum TouchAction {
     Click,
     /// Scroll by the provided offset.
     Scroll(Vector2D<f32, DevicePixel>),
+    /// Cancel the touch point in content, and start scrolling by the provided
+    /// offset.
+    StartScroll(Vector2D<f32, DevicePixel>),
     /// Zoom by a magnification factor and scroll by the provided offset.
     Zoom(f32, Vector2D<f32, DevicePixel>),
     /// Send a JavaScript event to content.
@@ -73,6 +83,7 @@ impl TouchHandler {
         TouchHandler {
             state: Nothing,
             active_touch_points: Vec::new(),
+            allowed_behavior: TouchBehavior::auto(),
         }
     }
 
@@ -81,8 +92,14 @@ impl TouchHandler {
         self.active_touch_points.push(point);
 
         self.state = match self.state {
-            Nothing => WaitingForScript,
-            Touching | Panning => Pinching,
+            Nothing => {
+                // A new touch sequence starts; its allowed behaviors are
+                // unknown until script has hit tested the first touch point.
+                self.allowed_behavior = TouchBehavior::auto();
+                WaitingForScript
+            },
+            Touching | Panning if self.allowed_behavior.pinch_zoom => Pinching,
+            Touching | Panning => MultiTouch,
             WaitingForScript => WaitingForScript,
             DefaultPrevented => DefaultPrevented,
             Pinching | MultiTouch => MultiTouch,
@@ -101,19 +118,20 @@ impl TouchHandler {
 
         let action = match self.state {
             Touching => {
-                let delta = point - old_point;
+                let delta = self.allowed_delta(point - old_point);
 
                 if delta.x.abs() > TOUCH_PAN_MIN_SCREEN_PX ||
                     delta.y.abs() > TOUCH_PAN_MIN_SCREEN_PX
                 {
                     self.state = Panning;
-                    TouchAction::Scroll(delta)
+                    self.active_touch_points[idx].canceled = true;
+                    TouchAction::StartScroll(delta)
                 } else {
-                    TouchAction::NoAction
+                    TouchAction::DispatchEvent
                 }
             },
             Panning => {
-                let delta = point - old_point;
+                let delta = self.allowed_delta(point - old_point);
                 TouchAction::Scroll(delta)
             },
             DefaultPrevented => TouchAction::DispatchEvent,
@@ -140,6 +158,13 @@ impl TouchHandler {
         action
     }
 
+    /// Whether content stopped receiving events for the given touch point.
+    pub fn is_canceled(&self, id: TouchId) -> bool {
+        self.active_touch_points
+            .iter()
+            .any(|t| t.id == id && t.canceled)
+    }
+
     pub fn on_touch_up(&mut self, id: TouchId, _point: Point2D<f32, DevicePixel>) -> TouchAction {
         match self.active_touch_points.iter().position(|t| t.id == id) {
             Some(i) => {
@@ -203,15 +228,34 @@ impl TouchHandler {
         if let WaitingForScript = self.state {
             self.state = match result {
                 EventResult::DefaultPrevented => DefaultPrevented,
-                EventResult::DefaultAllowed => match self.touch_count() {
-                    1 => Touching,
-                    2 => Pinching,
-                    _ => MultiTouch,
+                EventResult::DefaultAllowed(allowed_behavior) => {
+                    self.allowed_behavior = allowed_behavior;
+                    match self.touch_count() {
+                        1 => Touching,
+                        2 if allowed_behavior.pinch_zoom => Pinching,
+                        _ => MultiTouch,
+                    }
                 },
             }
         }
     }
 
+    /// Drops the components of a scroll that `touch-action` doesn't allow.
+    fn allowed_delta(&self, delta: Vector2D<f32, DevicePixel>) -> Vector2D<f32, DevicePixel> {
+        Vector2D::new(
+            if self.allowed_behavior.pan_x {
+                delta.x
+            } else {
+                0.
+            },
+            if self.allowed_behavior.pan_y {
+                delta.y
+            } else {
+                0.
+            },
+        )
+    }
+
     fn touch_count(&self) -> usize {
         self.active_touch_points.len()
     }
@@ -15,10 +15,11 @@ use servo_geometry::DeviceIndependentPixel;
 use servo_media::player::context::{GlApi, GlContext, NativeDisplay};
 use servo_url::ServoUrl;
 use std::fmt::{Debug, Error, Formatter};
+use std::path::PathBuf;
 #[cfg(feature = "gl")]
 use std::rc::Rc;
 use std::time::Duration;
-use style_traits::DevicePixel;
+use style_traits::{ColorScheme, DevicePixel};
 use webrender_api::units::DevicePoint;
 use webrender_api::units::{DeviceIntPoint, DeviceIntRect, DeviceIntSize};
 use webrender_api::ScrollLocation;
@@ -32,6 +33,17 @@ pub enum MouseWindowEvent {
     MouseUp(MouseButton, DevicePoint),
 }
 
+/// A drag of files from the host into the window.
+#[derive(Clone)]
+pub enum FileDragEvent {
+    /// The files are dragged over the given point.
+    Over(Vec<PathBuf>, DevicePoint),
+    /// The files were dropped on the given point.
+    Drop(Vec<PathBuf>, DevicePoint),
+    /// The drag left the window or was canceled.
+    Cancel,
+}
+
 /// Various debug and profiling flags that WebRender supports.
 #[derive(Clone)]
 pub enum WebRenderDebugOption {
@@ -65,6 +77,8 @@ pub enum WindowEvent {
     MouseWindowMoveEventClass(DevicePoint),
     /// Touch event: type, identifier, point
     Touch(TouchEventType, TouchId, DevicePoint),
+    /// Sent when files from the host are dragged over or dropped on the window.
+    FileDrag(FileDragEvent),
     /// Sent when user moves the mouse wheel.
     Wheel(WheelDelta, DevicePoint),
     /// Sent when the user scrolls. The first point is the delta and the second point is the
@@ -101,6 +115,10 @@ pub enum WindowEvent {
     CaptureWebRender,
     /// Toggle sampling profiler with the given sampling rate and max duration.
     ToggleSamplingProfiler(Duration, Duration),
+    /// Sent when the color scheme the user prefers changes.
+    ColorSchemeChanged(Option<ColorScheme>),
+    /// Sent when the user starts or stops preferring reduced motion.
+    ReducedMotionChanged(bool),
 }
 
 impl Debug for WindowEvent {
@@ -115,6 +133,7 @@ impl Debug for WindowEvent {
             WindowEvent::MouseWindowEventClass(..) => write!(f, "Mouse"),
             WindowEvent::MouseWindowMoveEventClass(..) => write!(f, "MouseMove"),
             WindowEvent::Touch(..) => write!(f, "Touch"),
+            WindowEvent::FileDrag(..) => write!(f, "FileDrag"),
             WindowEvent::Wheel(..) => write!(f, "Wheel"),
             WindowEvent::Scroll(..) => write!(f, "Scroll"),
             WindowEvent::Zoom(..) => write!(f, "Zoom"),
@@ -131,6 +150,8 @@ impl Debug for WindowEvent {
             WindowEvent::CaptureWebRender => write!(f, "CaptureWebRender"),
             WindowEvent::ToggleSamplingProfiler(..) => write!(f, "ToggleSamplingProfiler"),
             WindowEvent::ExitFullScreen(..) => write!(f, "ExitFullScreen"),
+            WindowEvent::ColorSchemeChanged(..) => write!(f, "ColorSchemeChanged"),
+            WindowEvent::ReducedMotionChanged(..) => write!(f, "ReducedMotionChanged"),
         }
     }
 }
@@ -169,6 +169,9 @@ mod gen {
                     #[serde(rename = "dom.canvas-text.enabled")]
                     enabled: bool,
                 },
+                clipboard: {
+                    enabled: bool,
+                },
                 composition_event: {
                     #[serde(rename = "dom.compositionevent.enabled")]
                     enabled: bool,
@@ -181,6 +184,12 @@ mod gen {
                     dblclick_timeout: i64,
                     dblclick_dist: i64,
                 },
+                drag_and_drop: {
+                    enabled: bool,
+                },
+                fontface: {
+                    enabled: bool,
+                },
                 forcetouch: {
                     enabled: bool,
                 },
@@ -213,6 +222,9 @@ mod gen {
                         allowed_in_nonsecure_contexts: bool,
                     }
                 },
+                pointer_events: {
+                    enabled: bool,
+                },
                 serviceworker: {
                     enabled: bool,
                     timeout_seconds: i64,
@@ -274,6 +286,10 @@ mod gen {
                     #[serde(default)]
                     enabled: bool,
                 },
+                web_animations: {
+                    #[serde(rename = "dom.webanimations.enabled")]
+                    enabled: bool,
+                },
                 webgl: {
                     dom_to_texture: {
                         enabled: bool,
@@ -132,14 +132,15 @@ use msg::constellation_msg::{
 use msg::constellation_msg::{
     PipelineNamespace, PipelineNamespaceId, PipelineNamespaceRequest, TraversalDirection,
 };
+use net_traits::blob_url_store::get_blob_origin;
+use net_traits::filemanager_thread::{FileManagerResult, FileManagerThreadMsg, SelectedFile};
 use net_traits::pub_domains::reg_host;
 use net_traits::request::RequestBuilder;
 use net_traits::storage_thread::{StorageThreadMsg, StorageType};
-use net_traits::{self, FetchResponseMsg, IpcSend, ResourceThreads};
+use net_traits::{self, CoreResourceMsg, FetchResponseMsg, IpcSend, ResourceThreads};
 use profile_traits::mem;
 use profile_traits::time;
-use script_traits::CompositorEvent::{MouseButtonEvent, MouseMoveEvent};
-use script_traits::MouseEventType;
+use script_traits::CompositorEvent::{DragEvent, MouseButtonEvent, MouseMoveEvent};
 use script_traits::{webdriver_msg, LogEntry, ScriptToConstellationChan, ServiceWorkerMsg};
 use script_traits::{
     AnimationState, AnimationTickType, AuxiliaryBrowsingContextLoadInfo, CompositorEvent,
@@ -148,6 +149,7 @@ use script_traits::{
     ConstellationControlMsg, ConstellationMsg as FromCompositorMsg, DiscardBrowsingContext,
 };
 use script_traits::{DocumentActivity, DocumentState, LayoutControlMsg, LoadData, LoadOrigin};
+use script_traits::{DragData, DragEventType, DraggedFile, MouseEventType, ScrollContainerInfo};
 use script_traits::{HistoryEntryReplacement, IFrameSizeMsg, WindowSizeData, WindowSizeType};
 use script_traits::{
     IFrameLoadInfo, IFrameLoadInfoWithData, IFrameSandboxState, TimerSchedulerMsg,
@@ -165,12 +167,14 @@ use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::marker::PhantomData;
 use std::mem::replace;
+use std::path::PathBuf;
 use std::process;
 use std::rc::{Rc, Weak};
 use std::sync::Arc;
 use std::thread;
 use style_traits::viewport::ViewportConstraints;
 use style_traits::CSSPixel;
+use style_traits::MediaPreferences;
 use webvr_traits::{WebVREvent, WebVRMsg};
 
 type PendingApprovalNavigations = HashMap<PipelineId, (LoadData, HistoryEntryReplacement)>;
@@ -262,6 +266,14 @@ pub struct Constellation<Message, LTF, STF> {
     /// A channel for the constellation to receive messages from network listener.
     network_listener_receiver: Receiver<(PipelineId, FetchResponseMsg)>,
 
+    /// A channel for the router to send drop events to the constellation, once
+    /// the file manager registered the dropped files.
+    dropped_files_sender: Sender<(PipelineId, CompositorEvent)>,
+
+    /// A channel for the constellation to receive drop events with registered
+    /// files.
+    dropped_files_receiver: Receiver<(PipelineId, CompositorEvent)>,
+
     /// A channel for the constellation to receive messages from the compositor thread.
     compositor_receiver: Receiver<FromCompositorMsg>,
 
@@ -369,6 +381,9 @@ pub struct Constellation<Message, LTF, STF> {
     /// The size of the top-level window.
     window_size: WindowSizeData,
 
+    /// The preferences of the user that media queries can test for.
+    media_preferences: MediaPreferences,
+
     /// Bits of state used to interact with the webdriver implementation
     webdriver: WebDriverData,
 
@@ -716,12 +731,16 @@ where
 
                 let (network_listener_sender, network_listener_receiver) = unbounded();
 
+                let (dropped_files_sender, dropped_files_receiver) = unbounded();
+
                 let swmanager_receiver =
                     route_ipc_receiver_to_new_mpsc_receiver_preserving_errors(swmanager_receiver);
 
                 // Zero is reserved for the embedder.
                 PipelineNamespace::install(PipelineNamespaceId(1));
 
+                let canvas_chan = CanvasPaintThread::start(state.font_cache_thread.clone());
+
                 let mut constellation: Constellation<Message, LTF, STF> = Constellation {
                     namespace_receiver,
                     namespace_sender,
@@ -736,6 +755,8 @@ where
                     layout_receiver: layout_receiver,
                     network_listener_sender: network_listener_sender,
                     network_listener_receiver: network_listener_receiver,
+                    dropped_files_sender,
+                    dropped_files_receiver,
                     embedder_proxy: state.embedder_proxy,
                     compositor_proxy: state.compositor_proxy,
                     active_browser_id: None,
@@ -763,6 +784,7 @@ where
                         initial_viewport: initial_window_size.to_f32() * Scale::new(1.0),
                         device_pixel_ratio: Scale::new(device_pixels_per_px.unwrap_or(1.0)),
                     },
+                    media_preferences: MediaPreferences::default(),
                     phantom: PhantomData,
                     webdriver: WebDriverData::new(),
                     scheduler_chan: TimerScheduler::start(),
@@ -781,7 +803,7 @@ where
                     webgl_threads: state.webgl_threads,
                     webvr_chan: state.webvr_chan,
                     webxr_registry: state.webxr_registry,
-                    canvas_chan: CanvasPaintThread::start(),
+                    canvas_chan,
                     pending_approval_navigations: HashMap::new(),
                     pressed_mouse_buttons: 0,
                     is_running_problem_test,
@@ -1023,6 +1045,7 @@ where
             event_loop,
             load_data,
             device_pixel_ratio: self.window_size.device_pixel_ratio,
+            media_preferences: self.media_preferences,
             prev_visibility: is_visible,
             webrender_api_sender: self.webrender_api_sender.clone(),
             webrender_document: self.webrender_document,
@@ -1179,6 +1202,7 @@ where
             Layout(FromLayoutMsg),
             NetworkListener((PipelineId, FetchResponseMsg)),
             FromSWManager(SWManagerMsg),
+            DroppedFiles((PipelineId, CompositorEvent)),
         }
 
         // Get one incoming request.
@@ -1216,6 +1240,11 @@ where
             recv(self.swmanager_receiver) -> msg => {
                 msg.expect("Unexpected panic channel panic in constellation").map(Request::FromSWManager)
             }
+            recv(self.dropped_files_receiver) -> msg => {
+                Ok(Request::DroppedFiles(
+                    msg.expect("Unexpected dropped files channel panic in constellation")
+                ))
+            }
         };
 
         let request = match request {
@@ -1243,6 +1272,9 @@ where
             Request::FromSWManager(message) => {
                 self.handle_request_from_swmanager(message);
             },
+            Request::DroppedFiles((pipeline_id, event)) => {
+                self.forward_event(pipeline_id, event);
+            },
         }
     }
 
@@ -1449,6 +1481,9 @@ where
             FromCompositorMsg::ForwardEvent(destination_pipeline_id, event) => {
                 self.forward_event(destination_pipeline_id, event);
             },
+            FromCompositorMsg::ForwardDropEvent(destination_pipeline_id, event, paths) => {
+                self.forward_drop_event(destination_pipeline_id, event, paths);
+            },
             FromCompositorMsg::SetCursor(cursor) => self.handle_set_cursor_msg(cursor),
             FromCompositorMsg::EnableProfiler(rate, max_duration) => {
                 for chan in &self.sampling_profiler_control {
@@ -1467,6 +1502,20 @@ where
             FromCompositorMsg::ExitFullScreen(top_level_browsing_context_id) => {
                 self.handle_exit_fullscreen_msg(top_level_browsing_context_id);
             },
+            FromCompositorMsg::ColorSchemeChanged(color_scheme) => {
+                let media_preferences = MediaPreferences {
+                    color_scheme,
+                    ..self.media_preferences
+                };
+                self.handle_media_preferences_msg(media_preferences);
+            },
+            FromCompositorMsg::ReducedMotionChanged(reduced_motion) => {
+                let media_preferences = MediaPreferences {
+                    reduced_motion,
+                    ..self.media_preferences
+                };
+                self.handle_media_preferences_msg(media_preferences);
+            },
         }
     }
 
@@ -1603,6 +1652,17 @@ where
             FromScriptMsg::TouchEventProcessed(result) => self
                 .compositor_proxy
                 .send(ToCompositorMsg::TouchEventProcessed(result)),
+            FromScriptMsg::StartDrag(data, image) => self
+                .compositor_proxy
+                .send(ToCompositorMsg::StartDrag(source_pipeline_id, data, image)),
+            FromScriptMsg::DragOverProcessed(operation) => {
+                self.compositor_proxy
+                    .send(ToCompositorMsg::DragOverProcessed(operation));
+                self.embedder_proxy.send((
+                    Some(source_top_ctx_id),
+                    EmbedderMsg::UpdateDragOperation(operation),
+                ));
+            },
             FromScriptMsg::GetBrowsingContextInfo(pipeline_id, sender) => {
                 let result = self
                     .pipelines
@@ -1682,6 +1742,9 @@ where
             FromLayoutMsg::ViewportConstrained(pipeline_id, constraints) => {
                 self.handle_viewport_constrained_msg(pipeline_id, constraints);
             },
+            FromLayoutMsg::ScrollContainers(pipeline_id, scroll_containers) => {
+                self.handle_scroll_containers_msg(pipeline_id, scroll_containers);
+            },
         }
     }
 
@@ -2040,6 +2103,13 @@ where
             MouseMoveEvent(point, node_address, _) => {
                 MouseMoveEvent(point, node_address, self.pressed_mouse_buttons)
             },
+            DragEvent(event_type, point, node_address, data) => {
+                // The compositor doesn't forward the mouse up that ends a drag.
+                if let DragEventType::End(_) = event_type {
+                    self.pressed_mouse_buttons = 0;
+                }
+                DragEvent(event_type, point, node_address, data)
+            },
             _ => event,
         };
 
@@ -2059,6 +2129,76 @@ where
         }
     }
 
+    /// Registers the files dropped from the embedder with the file manager,
+    /// for the origin of the pipeline they are dropped on, and forwards the
+    /// drop event with the registered files once the file manager replies.
+    /// The reply is routed back to the constellation, so that it doesn't
+    /// block on the resource thread.
+    fn forward_drop_event(
+        &mut self,
+        pipeline_id: PipelineId,
+        event: CompositorEvent,
+        paths: Vec<PathBuf>,
+    ) {
+        let (point, node_address, data) = match event {
+            DragEvent(DragEventType::Drop, point, node_address, data) => {
+                (point, node_address, data)
+            },
+            event => return self.forward_event(pipeline_id, event),
+        };
+
+        let pipeline = match self.pipelines.get(&pipeline_id) {
+            Some(pipeline) => pipeline,
+            None => return debug!("Pipeline {:?} got event after closure.", pipeline_id),
+        };
+        let is_private = self
+            .browsing_contexts
+            .get(&pipeline.browsing_context_id)
+            .map_or(false, |ctx| ctx.is_private);
+        let resource_threads = if is_private {
+            &self.private_resource_threads
+        } else {
+            &self.public_resource_threads
+        };
+
+        let (sender, receiver) = match ipc::channel() {
+            Ok(channel) => channel,
+            Err(e) => return warn!("Failed to create IPC channel ({:?}).", e),
+        };
+        let dropped_files_sender = self.dropped_files_sender.clone();
+        ROUTER.add_route(
+            receiver.to_opaque(),
+            Box::new(move |message| {
+                let files = match message.to::<FileManagerResult<Vec<SelectedFile>>>() {
+                    Ok(Ok(selected_files)) => selected_files
+                        .into_iter()
+                        .map(DraggedFile::Selected)
+                        .collect(),
+                    Ok(Err(e)) => {
+                        warn!("Failed to select dropped files ({:?}).", e);
+                        vec![]
+                    },
+                    Err(e) => {
+                        warn!("Failed to receive dropped files ({:?}).", e);
+                        vec![]
+                    },
+                };
+                let data = DragData {
+                    files,
+                    ..data.clone()
+                };
+                let event = DragEvent(DragEventType::Drop, point, node_address, data);
+                let _ = dropped_files_sender.send((pipeline_id, event));
+            }),
+        );
+
+        let origin = get_blob_origin(&pipeline.url);
+        let msg = FileManagerThreadMsg::SelectDroppedFiles(paths, sender, origin);
+        if let Err(e) = resource_threads.send(CoreResourceMsg::ToFileManager(msg)) {
+            warn!("Sending dropped files to file manager failed ({:?}).", e);
+        }
+    }
+
     fn handle_new_top_level_browsing_context(
         &mut self,
         url: ServoUrl,
@@ -3994,6 +4134,25 @@ where
         self.switch_fullscreen_mode(browsing_context_id);
     }
 
+    /// Send the new preferences of the user to every pipeline, so that their
+    /// media queries are evaluated against them.
+    fn handle_media_preferences_msg(&mut self, media_preferences: MediaPreferences) {
+        if media_preferences == self.media_preferences {
+            return;
+        }
+        self.media_preferences = media_preferences;
+        for pipeline in self.pipelines.values() {
+            let msg =
+                ConstellationControlMsg::MediaPreferencesChanged(pipeline.id, media_preferences);
+            if let Err(e) = pipeline.event_loop.send(msg) {
+                warn!(
+                    "Sending media preferences to pipeline {} failed ({:?}).",
+                    pipeline.id, e
+                );
+            }
+        }
+    }
+
     /// Handle updating actual viewport / zoom due to @viewport rules
     fn handle_viewport_constrained_msg(
         &mut self,
@@ -4007,6 +4166,19 @@ where
             ));
     }
 
+    /// Handle updating the scroll containers whose scrolls the compositor snaps and chains.
+    fn handle_scroll_containers_msg(
+        &mut self,
+        pipeline_id: PipelineId,
+        scroll_containers: Vec<ScrollContainerInfo>,
+    ) {
+        self.compositor_proxy
+            .send(ToCompositorMsg::ScrollContainers(
+                pipeline_id,
+                scroll_containers,
+            ));
+    }
+
     /// Checks the state of all script and layout pipelines to see if they are idle
     /// and compares the current layout state to what the compositor has. This is used
     /// to check if the output image is "stable" and can be written as a screenshot
@@ -49,6 +49,7 @@ use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use style_traits::CSSPixel;
 use style_traits::DevicePixel;
+use style_traits::MediaPreferences;
 use webvr_traits::WebVRMsg;
 
 /// A `Pipeline` is the constellation's view of a `Document`. Each pipeline has an
@@ -170,6 +171,9 @@ pub struct InitialPipelineState {
     /// Information about the device pixel ratio.
     pub device_pixel_ratio: Scale<f32, CSSPixel, DevicePixel>,
 
+    /// The preferences of the user that media queries can test for.
+    pub media_preferences: MediaPreferences,
+
     /// The ID of the pipeline namespace for this script thread.
     pub pipeline_namespace_id: PipelineNamespaceId,
 
@@ -298,6 +302,7 @@ impl Pipeline {
                     time_profiler_chan: state.time_profiler_chan,
                     mem_profiler_chan: state.mem_profiler_chan,
                     window_size: window_size,
+                    media_preferences: state.media_preferences,
                     layout_to_constellation_chan: state.layout_to_constellation_chan,
                     script_chan: script_chan.clone(),
                     load_data: state.load_data.clone(),
@@ -505,6 +510,7 @@ pub struct UnprivilegedPipelineContent {
     time_profiler_chan: time::ProfilerChan,
     mem_profiler_chan: profile_mem::ProfilerChan,
     window_size: WindowSizeData,
+    media_preferences: MediaPreferences,
     script_chan: IpcSender<ConstellationControlMsg>,
     load_data: LoadData,
     script_port: IpcReceiver<ConstellationControlMsg>,
@@ -564,6 +570,7 @@ impl UnprivilegedPipelineContent {
                 mem_profiler_chan: self.mem_profiler_chan.clone(),
                 devtools_chan: self.devtools_chan,
                 window_size: self.window_size,
+                media_preferences: self.media_preferences,
                 pipeline_namespace_id: self.pipeline_namespace_id,
                 content_process_shutdown_chan: content_process_shutdown_chan,
                 webgl_chan: self.webgl_chan,
@@ -65,6 +65,44 @@ pub enum Cursor {
     ZoomOut,
 }
 
+/// The operation a drag-and-drop operation would perform if the user dropped
+/// at the current position.
+///
+/// <https://html.spec.whatwg.org/multipage/#current-drag-operation>
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum DragOperation {
+    None,
+    Copy,
+    Link,
+    Move,
+}
+
+/// The contents of the system clipboard, as the same data in every format it
+/// is available in.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ClipboardData {
+    /// Pairs of MIME type and data, such as `text/plain` and UTF-8 text, in
+    /// order of preference.
+    pub representations: Vec<(String, Vec<u8>)>,
+}
+
+impl ClipboardData {
+    /// Clipboard data holding only plain text.
+    pub fn from_text(text: String) -> ClipboardData {
+        ClipboardData {
+            representations: vec![("text/plain".to_owned(), text.into_bytes())],
+        }
+    }
+
+    /// The plain text representation of the data, if any.
+    pub fn text(&self) -> Option<String> {
+        self.representations
+            .iter()
+            .find(|&&(ref type_, _)| type_ == "text/plain")
+            .map(|&(_, ref data)| String::from_utf8_lossy(data).into_owned())
+    }
+}
+
 /// Sends messages to the embedder.
 pub struct EmbedderProxy {
     pub sender: Sender<(Option<TopLevelBrowsingContextId>, EmbedderMsg)>,
@@ -132,6 +170,10 @@ pub enum EmbedderMsg {
     GetClipboardContents(IpcSender<String>),
     /// Sets system clipboard contents
     SetClipboardContents(String),
+    /// Gets system clipboard contents in every format they are available in
+    GetClipboardData(IpcSender<ClipboardData>),
+    /// Sets system clipboard contents in several formats
+    SetClipboardData(ClipboardData),
     /// Changes the cursor.
     SetCursor(Cursor),
     /// A favicon was detected
@@ -162,6 +204,9 @@ pub enum EmbedderMsg {
     Shutdown,
     /// Report a complete sampled profile
     ReportProfile(Vec<u8>),
+    /// The operation that dropping the dragged data at the current position
+    /// would perform changed, so the embedder can update its drag feedback.
+    UpdateDragOperation(DragOperation),
 }
 
 impl Debug for EmbedderMsg {
@@ -177,6 +222,8 @@ impl Debug for EmbedderMsg {
             EmbedderMsg::Keyboard(..) => write!(f, "Keyboard"),
             EmbedderMsg::GetClipboardContents(..) => write!(f, "GetClipboardContents"),
             EmbedderMsg::SetClipboardContents(..) => write!(f, "SetClipboardContents"),
+            EmbedderMsg::GetClipboardData(..) => write!(f, "GetClipboardData"),
+            EmbedderMsg::SetClipboardData(..) => write!(f, "SetClipboardData"),
             EmbedderMsg::SetCursor(..) => write!(f, "SetCursor"),
             EmbedderMsg::NewFavicon(..) => write!(f, "NewFavicon"),
             EmbedderMsg::HeadParsed => write!(f, "HeadParsed"),
@@ -194,6 +241,7 @@ impl Debug for EmbedderMsg {
             EmbedderMsg::AllowOpeningBrowser(..) => write!(f, "AllowOpeningBrowser"),
             EmbedderMsg::BrowserCreated(..) => write!(f, "BrowserCreated"),
             EmbedderMsg::ReportProfile(..) => write!(f, "ReportProfile"),
+            EmbedderMsg::UpdateDragOperation(..) => write!(f, "UpdateDragOperation"),
         }
     }
 }
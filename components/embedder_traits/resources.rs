@@ -57,6 +57,7 @@ pub enum Resource {
     NetErrorHTML,
     UserAgentCSS,
     ServoCSS,
+    SVGFallbackCSS,
     PresentationalHintsCSS,
     QuirksModeCSS,
     RippyPNG,
@@ -93,6 +94,7 @@ fn resources_for_tests() -> Box<dyn ResourceReaderMethods + Sync + Send> {
                 Resource::NetErrorHTML => "neterror.html",
                 Resource::UserAgentCSS => "user-agent.css",
                 Resource::ServoCSS => "servo.css",
+                Resource::SVGFallbackCSS => "svg-fallback.css",
                 Resource::PresentationalHintsCSS => "presentational-hints.css",
                 Resource::QuirksModeCSS => "quirks-mode.css",
                 Resource::RippyPNG => "rippy.png",
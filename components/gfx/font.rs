@@ -19,13 +19,16 @@ use smallvec::SmallVec;
 use std::borrow::ToOwned;
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use std::iter;
 use std::rc::Rc;
 use std::str;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use style::computed_values::font_kerning::T as FontKerning;
 use style::computed_values::{font_stretch, font_style, font_variant_caps, font_weight};
 use style::properties::style_structs::Font as FontStyleStruct;
+use style::values::computed::font::{FontVariantLigatures, FontVariantNumeric};
 use style::values::computed::font::{GenericFontFamily, SingleFontFamily};
 use unicode_script::Script;
 
@@ -35,9 +38,22 @@ macro_rules! ot_tag {
     };
 }
 
+pub const AFRC: u32 = ot_tag!('a', 'f', 'r', 'c');
+pub const CALT: u32 = ot_tag!('c', 'a', 'l', 't');
+pub const CLIG: u32 = ot_tag!('c', 'l', 'i', 'g');
+pub const DLIG: u32 = ot_tag!('d', 'l', 'i', 'g');
+pub const FRAC: u32 = ot_tag!('f', 'r', 'a', 'c');
 pub const GPOS: u32 = ot_tag!('G', 'P', 'O', 'S');
 pub const GSUB: u32 = ot_tag!('G', 'S', 'U', 'B');
+pub const HLIG: u32 = ot_tag!('h', 'l', 'i', 'g');
 pub const KERN: u32 = ot_tag!('k', 'e', 'r', 'n');
+pub const LIGA: u32 = ot_tag!('l', 'i', 'g', 'a');
+pub const LNUM: u32 = ot_tag!('l', 'n', 'u', 'm');
+pub const ONUM: u32 = ot_tag!('o', 'n', 'u', 'm');
+pub const ORDN: u32 = ot_tag!('o', 'r', 'd', 'n');
+pub const PNUM: u32 = ot_tag!('p', 'n', 'u', 'm');
+pub const TNUM: u32 = ot_tag!('t', 'n', 'u', 'm');
+pub const ZERO: u32 = ot_tag!('z', 'e', 'r', 'o');
 
 static TEXT_SHAPING_PERFORMANCE_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
@@ -51,6 +67,7 @@ pub trait FontHandleMethods: Sized {
         fctx: &FontContextHandle,
         template: Arc<FontTemplateData>,
         pt_size: Option<Au>,
+        variations: &[FontVariation],
     ) -> Result<Self, ()>;
 
     fn template(&self) -> Arc<FontTemplateData>;
@@ -124,6 +141,10 @@ pub struct FontDescriptor {
     pub template_descriptor: FontTemplateDescriptor,
     pub variant: font_variant_caps::T,
     pub pt_size: Au,
+    /// The OpenType features to apply when shaping text, in the order HarfBuzz applies them.
+    pub features: Vec<FontFeature>,
+    /// The coordinates of the instance of a variable font to use.
+    pub variations: Vec<FontVariation>,
 }
 
 impl<'a> From<&'a FontStyleStruct> for FontDescriptor {
@@ -132,10 +153,144 @@ impl<'a> From<&'a FontStyleStruct> for FontDescriptor {
             template_descriptor: FontTemplateDescriptor::from(style),
             variant: style.font_variant_caps,
             pt_size: style.font_size.size(),
+            features: font_features(style),
+            variations: style
+                .font_variation_settings
+                .0
+                .iter()
+                .map(|variation| FontVariation {
+                    tag: variation.tag.0,
+                    value: variation.value,
+                })
+                .collect(),
         }
     }
 }
 
+/// An OpenType feature, identified by its tag, and the value it is set to. Zero disables the
+/// feature, one enables it and larger values select alternate glyphs.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct FontFeature {
+    pub tag: u32,
+    pub value: u32,
+}
+
+impl FontFeature {
+    fn new(tag: u32, enabled: bool) -> FontFeature {
+        FontFeature {
+            tag,
+            value: enabled as u32,
+        }
+    }
+}
+
+/// A coordinate along an axis of a variable font, identified by the tag of the axis.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct FontVariation {
+    pub tag: u32,
+    pub value: f32,
+}
+
+// CSS numbers are never NaN, so comparing the bits of the values is enough.
+impl PartialEq for FontVariation {
+    fn eq(&self, other: &FontVariation) -> bool {
+        self.tag == other.tag && self.value.to_bits() == other.value.to_bits()
+    }
+}
+
+impl Eq for FontVariation {}
+
+impl Hash for FontVariation {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.tag.hash(state);
+        self.value.to_bits().hash(state);
+    }
+}
+
+/// Returns the OpenType features that the `font-kerning`, `font-variant-ligatures`,
+/// `font-variant-numeric` and `font-feature-settings` properties of a style turn on or off.
+///
+/// Features from `font-feature-settings` come last so that they override the others.
+///
+/// <https://drafts.csswg.org/css-fonts-3/#feature-precedence>
+fn font_features(style: &FontStyleStruct) -> Vec<FontFeature> {
+    let mut features = vec![];
+
+    match style.font_kerning {
+        FontKerning::Auto => {},
+        FontKerning::Normal => features.push(FontFeature::new(KERN, true)),
+        FontKerning::None => features.push(FontFeature::new(KERN, false)),
+    }
+
+    let ligatures = style.font_variant_ligatures;
+    if ligatures.contains(FontVariantLigatures::NONE) {
+        for &tag in &[LIGA, CLIG, DLIG, HLIG, CALT] {
+            features.push(FontFeature::new(tag, false));
+        }
+    }
+    let ligature_features = [
+        (
+            FontVariantLigatures::COMMON_LIGATURES,
+            FontVariantLigatures::NO_COMMON_LIGATURES,
+            &[LIGA, CLIG][..],
+        ),
+        (
+            FontVariantLigatures::DISCRETIONARY_LIGATURES,
+            FontVariantLigatures::NO_DISCRETIONARY_LIGATURES,
+            &[DLIG][..],
+        ),
+        (
+            FontVariantLigatures::HISTORICAL_LIGATURES,
+            FontVariantLigatures::NO_HISTORICAL_LIGATURES,
+            &[HLIG][..],
+        ),
+        (
+            FontVariantLigatures::CONTEXTUAL,
+            FontVariantLigatures::NO_CONTEXTUAL,
+            &[CALT][..],
+        ),
+    ];
+    for &(on, off, tags) in &ligature_features {
+        let enabled = if ligatures.contains(on) {
+            true
+        } else if ligatures.contains(off) {
+            false
+        } else {
+            continue;
+        };
+        features.extend(tags.iter().map(|&tag| FontFeature::new(tag, enabled)));
+    }
+
+    let numeric = style.font_variant_numeric;
+    let numeric_features = [
+        (FontVariantNumeric::LINING_NUMS, LNUM),
+        (FontVariantNumeric::OLDSTYLE_NUMS, ONUM),
+        (FontVariantNumeric::PROPORTIONAL_NUMS, PNUM),
+        (FontVariantNumeric::TABULAR_NUMS, TNUM),
+        (FontVariantNumeric::DIAGONAL_FRACTIONS, FRAC),
+        (FontVariantNumeric::STACKED_FRACTIONS, AFRC),
+        (FontVariantNumeric::ORDINAL, ORDN),
+        (FontVariantNumeric::SLASHED_ZERO, ZERO),
+    ];
+    for &(flag, tag) in &numeric_features {
+        if numeric.contains(flag) {
+            features.push(FontFeature::new(tag, true));
+        }
+    }
+
+    features.extend(
+        style
+            .font_feature_settings
+            .0
+            .iter()
+            .map(|feature| FontFeature {
+                tag: feature.tag.0,
+                value: feature.value.max(0) as u32,
+            }),
+    );
+    features
+}
+
 #[derive(Debug)]
 pub struct Font {
     pub handle: FontHandle,
@@ -262,6 +417,7 @@ impl Font {
         options.script == Script::Latin &&
             !options.flags.contains(ShapingFlags::RTL_FLAG) &&
             self.handle.can_do_fast_shaping() &&
+            self.descriptor.features.is_empty() &&
             text.is_ascii()
     }
 
@@ -2,7 +2,7 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
-use crate::font::{FontFamilyDescriptor, FontFamilyName, FontSearchScope};
+use crate::font::{FontFamilyDescriptor, FontFamilyName, FontSearchScope, FontVariation};
 use crate::font_context::FontSource;
 use crate::font_template::{FontTemplate, FontTemplateDescriptor};
 use crate::platform::font_context::FontContextHandle;
@@ -12,6 +12,7 @@ use crate::platform::font_list::system_default_family;
 use crate::platform::font_list::SANS_SERIF_FONT_FAMILY;
 use crate::platform::font_template::FontTemplateData;
 use app_units::Au;
+use gfx_traits::WebFontLoadResult;
 use ipc_channel::ipc::{self, IpcReceiver, IpcSender};
 use net_traits::request::{Destination, RequestBuilder};
 use net_traits::{fetch_async, CoreResourceThread, FetchResponseMsg};
@@ -111,10 +112,21 @@ pub enum Command {
     GetFontInstance(
         webrender_api::FontKey,
         Au,
+        Vec<FontVariation>,
         IpcSender<webrender_api::FontInstanceKey>,
     ),
-    AddWebFont(LowercaseString, EffectiveSources, IpcSender<()>),
-    AddDownloadedWebFont(LowercaseString, ServoUrl, Vec<u8>, IpcSender<()>),
+    AddWebFont(
+        LowercaseString,
+        EffectiveSources,
+        IpcSender<WebFontLoadResult>,
+    ),
+    AddDownloadedWebFont(
+        LowercaseString,
+        ServoUrl,
+        Vec<u8>,
+        IpcSender<WebFontLoadResult>,
+    ),
+    AddWebFontData(LowercaseString, Vec<u8>, IpcSender<WebFontLoadResult>),
     Exit(IpcSender<()>),
     Ping,
 }
@@ -137,7 +149,8 @@ struct FontCache {
     core_resource_thread: CoreResourceThread,
     webrender_api: webrender_api::RenderApi,
     webrender_fonts: HashMap<Atom, webrender_api::FontKey>,
-    font_instances: HashMap<(webrender_api::FontKey, Au), webrender_api::FontInstanceKey>,
+    font_instances:
+        HashMap<(webrender_api::FontKey, Au, Vec<FontVariation>), webrender_api::FontInstanceKey>,
 }
 
 fn populate_generic_fonts() -> HashMap<FontFamilyName, LowercaseString> {
@@ -178,20 +191,26 @@ impl FontCache {
                         self.find_font_template(&template_descriptor, &family_descriptor);
                     let _ = result.send(Reply::GetFontTemplateReply(maybe_font_template));
                 },
-                Command::GetFontInstance(font_key, size, result) => {
+                Command::GetFontInstance(font_key, size, variations, result) => {
                     let webrender_api = &self.webrender_api;
 
-                    let instance_key =
-                        *self
-                            .font_instances
-                            .entry((font_key, size))
-                            .or_insert_with(|| {
-                                let key = webrender_api.generate_font_instance_key();
-                                let mut txn = webrender_api::Transaction::new();
-                                txn.add_font_instance(key, font_key, size, None, None, Vec::new());
-                                webrender_api.update_resources(txn.resource_updates);
-                                key
-                            });
+                    let instance_key = *self
+                        .font_instances
+                        .entry((font_key, size, variations.clone()))
+                        .or_insert_with(|| {
+                            let key = webrender_api.generate_font_instance_key();
+                            let variations = variations
+                                .iter()
+                                .map(|variation| webrender_api::FontVariation {
+                                    tag: variation.tag,
+                                    value: variation.value,
+                                })
+                                .collect();
+                            let mut txn = webrender_api::Transaction::new();
+                            txn.add_font_instance(key, font_key, size, None, None, variations);
+                            webrender_api.update_resources(txn.resource_updates);
+                            key
+                        });
 
                     let _ = result.send(instance_key);
                 },
@@ -201,7 +220,13 @@ impl FontCache {
                 Command::AddDownloadedWebFont(family_name, url, bytes, result) => {
                     let templates = &mut self.web_families.get_mut(&family_name).unwrap();
                     templates.add_template(Atom::from(url.to_string()), Some(bytes));
-                    drop(result.send(()));
+                    drop(result.send(WebFontLoadResult {
+                        family_name: family_name.to_string(),
+                        loaded: true,
+                    }));
+                },
+                Command::AddWebFontData(family_name, bytes, result) => {
+                    self.handle_add_web_font_data(family_name, bytes, result);
                 },
                 Command::Ping => (),
                 Command::Exit(result) => {
@@ -216,12 +241,15 @@ impl FontCache {
         &mut self,
         family_name: LowercaseString,
         mut sources: EffectiveSources,
-        sender: IpcSender<()>,
+        sender: IpcSender<WebFontLoadResult>,
     ) {
         let src = if let Some(src) = sources.next() {
             src
         } else {
-            sender.send(()).unwrap();
+            let _ = sender.send(WebFontLoadResult {
+                family_name: family_name.to_string(),
+                loaded: false,
+            });
             return;
         };
 
@@ -313,7 +341,10 @@ impl FontCache {
                     templates.add_template(Atom::from(&*path), None);
                 });
                 if found {
-                    sender.send(()).unwrap();
+                    let _ = sender.send(WebFontLoadResult {
+                        family_name: family_name.to_string(),
+                        loaded: true,
+                    });
                 } else {
                     let msg = Command::AddWebFont(family_name, sources, sender);
                     self.channel_to_self.send(msg).unwrap();
@@ -322,6 +353,36 @@ impl FontCache {
         }
     }
 
+    /// Adds a font face whose data was provided directly (e.g. by a `FontFace` constructed
+    /// from an `ArrayBuffer`) rather than fetched from a `src` descriptor.
+    fn handle_add_web_font_data(
+        &mut self,
+        family_name: LowercaseString,
+        bytes: Vec<u8>,
+        sender: IpcSender<WebFontLoadResult>,
+    ) {
+        let loaded = match fontsan::process(&bytes) {
+            Ok(bytes) => {
+                let templates = self
+                    .web_families
+                    .entry(family_name.clone())
+                    .or_insert_with(FontTemplates::new);
+                let identifier = format!("{}#{}", family_name, templates.templates.len());
+                templates.add_template(Atom::from(identifier), Some(bytes));
+                true
+            },
+            Err(_) => {
+                // FIXME(servo/fontsan#1): get an error message
+                debug!("Sanitiser rejected web font data: family={}", family_name);
+                false
+            },
+        };
+        let _ = sender.send(WebFontLoadResult {
+            family_name: family_name.to_string(),
+            loaded,
+        });
+    }
+
     fn refresh_local_families(&mut self) {
         self.local_families.clear();
         for_each_available_family(|family_name| {
@@ -477,7 +538,7 @@ impl FontCacheThread {
         &self,
         family: FamilyName,
         sources: EffectiveSources,
-        sender: IpcSender<()>,
+        sender: IpcSender<WebFontLoadResult>,
     ) {
         self.chan
             .send(Command::AddWebFont(
@@ -488,6 +549,21 @@ impl FontCacheThread {
             .unwrap();
     }
 
+    pub fn add_web_font_data(
+        &self,
+        family: FamilyName,
+        bytes: Vec<u8>,
+        sender: IpcSender<WebFontLoadResult>,
+    ) {
+        self.chan
+            .send(Command::AddWebFontData(
+                LowercaseString::new(&family.name),
+                bytes,
+                sender,
+            ))
+            .unwrap();
+    }
+
     pub fn exit(&self) {
         let (response_chan, response_port) = ipc::channel().unwrap();
         self.chan
@@ -504,10 +580,16 @@ impl FontSource for FontCacheThread {
         &mut self,
         key: webrender_api::FontKey,
         size: Au,
+        variations: Vec<FontVariation>,
     ) -> webrender_api::FontInstanceKey {
         let (response_chan, response_port) = ipc::channel().expect("failed to create IPC channel");
         self.chan
-            .send(Command::GetFontInstance(key, size, response_chan))
+            .send(Command::GetFontInstance(
+                key,
+                size,
+                variations,
+                response_chan,
+            ))
             .expect("failed to send message to font cache thread");
 
         let instance_key = response_port.recv();
@@ -4,6 +4,7 @@
 
 use crate::font::{
     Font, FontDescriptor, FontFamilyDescriptor, FontGroup, FontHandleMethods, FontRef,
+    FontVariation,
 };
 use crate::font_cache_thread::FontTemplateInfo;
 use crate::font_template::FontTemplateDescriptor;
@@ -33,6 +34,7 @@ pub trait FontSource {
         &mut self,
         key: webrender_api::FontKey,
         size: Au,
+        variations: Vec<FontVariation>,
     ) -> webrender_api::FontInstanceKey;
 
     fn font_template(
@@ -188,11 +190,14 @@ impl<S: FontSource> FontContext<S> {
             &self.platform_handle,
             info.font_template,
             Some(actual_pt_size),
+            &descriptor.variations,
         )?;
 
-        let font_instance_key = self
-            .font_source
-            .get_font_instance(info.font_key, actual_pt_size);
+        let font_instance_key = self.font_source.get_font_instance(
+            info.font_key,
+            actual_pt_size,
+            descriptor.variations.clone(),
+        );
         Ok(Font::new(
             handle,
             descriptor,
@@ -190,7 +190,7 @@ impl FontTemplate {
 
         let data = self.data().map_err(|_| ())?;
         let handle: Result<FontHandle, ()> =
-            FontHandleMethods::new_from_template(font_context, data, None);
+            FontHandleMethods::new_from_template(font_context, data, None, &[]);
         self.is_valid = handle.is_ok();
         let handle = handle?;
         self.descriptor = Some(FontTemplateDescriptor::new(
@@ -3,7 +3,7 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
 use super::c_str_to_string;
-use crate::font::{FontHandleMethods, FontMetrics, FontTableMethods};
+use crate::font::{FontHandleMethods, FontMetrics, FontTableMethods, FontVariation};
 use crate::font::{FontTableTag, FractionalPixel, GPOS, GSUB, KERN};
 use crate::platform::font_context::FontContextHandle;
 use crate::platform::font_template::FontTemplateData;
@@ -11,21 +11,21 @@ use crate::text::glyph::GlyphId;
 use crate::text::util::fixed_to_float;
 use app_units::Au;
 use freetype::freetype::FT_Sfnt_Tag;
-use freetype::freetype::{FT_Done_Face, FT_New_Face, FT_New_Memory_Face};
+use freetype::freetype::{FT_Done_Face, FT_Error, FT_New_Face, FT_New_Memory_Face};
 use freetype::freetype::{FT_F26Dot6, FT_Face, FT_FaceRec};
 use freetype::freetype::{FT_Get_Char_Index, FT_Get_Postscript_Name};
 use freetype::freetype::{FT_Get_Kerning, FT_Get_Sfnt_Table, FT_Load_Sfnt_Table};
 use freetype::freetype::{FT_GlyphSlot, FT_Library, FT_Long, FT_ULong};
 use freetype::freetype::{FT_Int32, FT_Kerning_Mode, FT_STYLE_FLAG_ITALIC};
 use freetype::freetype::{FT_Load_Glyph, FT_Set_Char_Size};
-use freetype::freetype::{FT_SizeRec, FT_Size_Metrics, FT_UInt, FT_Vector};
+use freetype::freetype::{FT_SizeRec, FT_Size_Metrics, FT_String, FT_UInt, FT_Vector};
 use freetype::succeeded;
 use freetype::tt_os2::TT_OS2;
 use servo_atoms::Atom;
 use std::ffi::CString;
-use std::os::raw::{c_char, c_long};
+use std::os::raw::{c_char, c_long, c_void};
 use std::sync::Arc;
-use std::{mem, ptr};
+use std::{mem, ptr, slice};
 use style::computed_values::font_stretch::T as FontStretch;
 use style::computed_values::font_weight::T as FontWeight;
 use style::values::computed::font::FontStyle;
@@ -45,6 +45,42 @@ fn fixed_to_float_ft(f: i32) -> f64 {
     fixed_to_float(6, f)
 }
 
+// The multiple masters API is not present in the freetype bindings, so declare the parts of it
+// that are needed to instance variable fonts.
+#[allow(non_camel_case_types)]
+type FT_Fixed = c_long;
+
+#[allow(non_camel_case_types)]
+#[repr(C)]
+struct FT_Var_Axis {
+    name: *mut FT_String,
+    minimum: FT_Fixed,
+    def: FT_Fixed,
+    maximum: FT_Fixed,
+    tag: FT_ULong,
+    strid: FT_UInt,
+}
+
+#[allow(non_camel_case_types)]
+#[repr(C)]
+struct FT_MM_Var {
+    num_axis: FT_UInt,
+    num_designs: FT_UInt,
+    num_namedstyles: FT_UInt,
+    axis: *mut FT_Var_Axis,
+    namedstyle: *mut c_void,
+}
+
+extern "C" {
+    fn FT_Get_MM_Var(face: FT_Face, amaster: *mut *mut FT_MM_Var) -> FT_Error;
+    fn FT_Done_MM_Var(library: FT_Library, amaster: *mut FT_MM_Var) -> FT_Error;
+    fn FT_Set_Var_Design_Coordinates(
+        face: FT_Face,
+        num_coords: FT_UInt,
+        coords: *mut FT_Fixed,
+    ) -> FT_Error;
+}
+
 #[derive(Debug)]
 pub struct FontTable {
     buffer: Vec<u8>,
@@ -92,6 +128,7 @@ fn create_face(
     lib: FT_Library,
     template: &FontTemplateData,
     pt_size: Option<Au>,
+    variations: &[FontVariation],
 ) -> Result<FT_Face, ()> {
     unsafe {
         let mut face: FT_Face = ptr::null_mut();
@@ -124,22 +161,64 @@ fn create_face(
             FontHandle::set_char_size(face, s).or(Err(()))?
         }
 
+        if !variations.is_empty() {
+            set_variations(lib, face, variations);
+        }
+
         Ok(face)
     }
 }
 
+/// Selects the instance of a variable font at the given coordinates. Axes without a coordinate
+/// keep their default value, and coordinates for axes that the font doesn't have are ignored.
+unsafe fn set_variations(lib: FT_Library, face: FT_Face, variations: &[FontVariation]) {
+    let mut master: *mut FT_MM_Var = ptr::null_mut();
+    if !succeeded(FT_Get_MM_Var(face, &mut master)) || master.is_null() {
+        return;
+    }
+
+    let axes = slice::from_raw_parts((*master).axis, (*master).num_axis as usize);
+    let mut coords: Vec<FT_Fixed> = axes
+        .iter()
+        .map(|axis| {
+            // The last value specified for an axis wins.
+            match variations
+                .iter()
+                .rev()
+                .find(|variation| variation.tag as FT_ULong == axis.tag)
+            {
+                Some(variation) => {
+                    let value = (variation.value as f64 * 65536.0) as FT_Fixed;
+                    value.max(axis.minimum).min(axis.maximum)
+                },
+                None => axis.def,
+            }
+        })
+        .collect();
+    if !succeeded(FT_Set_Var_Design_Coordinates(
+        face,
+        coords.len() as FT_UInt,
+        coords.as_mut_ptr(),
+    )) {
+        debug!("FT_Set_Var_Design_Coordinates failed");
+    }
+
+    FT_Done_MM_Var(lib, master);
+}
+
 impl FontHandleMethods for FontHandle {
     fn new_from_template(
         fctx: &FontContextHandle,
         template: Arc<FontTemplateData>,
         pt_size: Option<Au>,
+        variations: &[FontVariation],
     ) -> Result<FontHandle, ()> {
         let ft_ctx: FT_Library = fctx.ctx.ctx;
         if ft_ctx.is_null() {
             return Err(());
         }
 
-        let face = create_face(ft_ctx, &template, pt_size)?;
+        let face = create_face(ft_ctx, &template, pt_size, variations)?;
 
         let mut handle = FontHandle {
             face: face,
@@ -5,7 +5,7 @@
 use crate::font::{
     FontHandleMethods, FontMetrics, FontTableMethods, FontTableTag, FractionalPixel,
 };
-use crate::font::{GPOS, GSUB, KERN};
+use crate::font::{FontVariation, GPOS, GSUB, KERN};
 use crate::platform::font_template::FontTemplateData;
 use crate::platform::macos::font_context::FontContextHandle;
 use crate::text::glyph::GlyphId;
@@ -166,6 +166,8 @@ impl FontHandleMethods for FontHandle {
         _fctx: &FontContextHandle,
         template: Arc<FontTemplateData>,
         pt_size: Option<Au>,
+        // TODO: Instance variable fonts.
+        _variations: &[FontVariation],
     ) -> Result<FontHandle, ()> {
         let size = match pt_size {
             Some(s) => s.to_f64_px(),
@@ -6,6 +6,7 @@ use app_units::Au;
 use gfx::font::{
     fallback_font_families, FontDescriptor, FontFamilyDescriptor, FontFamilyName, FontSearchScope,
 };
+use gfx::font::{FontFeature, FontVariation, KERN, TNUM};
 use gfx::font_cache_thread::{FontTemplateInfo, FontTemplates};
 use gfx::font_context::{FontContext, FontContextHandle, FontSource};
 use gfx::font_template::FontTemplateDescriptor;
@@ -17,13 +18,15 @@ use std::fs::File;
 use std::io::prelude::*;
 use std::path::PathBuf;
 use std::rc::Rc;
+use style::properties::longhands::font_kerning::computed_value::T as FontKerning;
 use style::properties::longhands::font_variant_caps::computed_value::T as FontVariantCaps;
 use style::properties::style_structs::Font as FontStyleStruct;
 use style::values::computed::font::{
     FamilyName, FontFamily, FontFamilyList, FontFamilyNameSyntax, FontSize,
 };
 use style::values::computed::font::{FontStretch, FontWeight, SingleFontFamily};
-use style::values::generics::font::FontStyle;
+use style::values::computed::font::{FontVariantLigatures, FontVariantNumeric};
+use style::values::generics::font::{FeatureTagValue, FontSettings, FontStyle, FontTag};
 
 struct TestFontSource {
     handle: FontContextHandle,
@@ -72,6 +75,7 @@ impl FontSource for TestFontSource {
         &mut self,
         _key: webrender_api::FontKey,
         _size: Au,
+        _variations: Vec<FontVariation>,
     ) -> webrender_api::FontInstanceKey {
         webrender_api::FontInstanceKey(webrender_api::IdNamespace(0), 0)
     }
@@ -102,6 +106,11 @@ fn style() -> FontStyleStruct {
         font_weight: FontWeight::normal(),
         font_size: FontSize::medium(),
         font_stretch: FontStretch::hundred(),
+        font_kerning: FontKerning::Auto,
+        font_variant_ligatures: FontVariantLigatures::NORMAL,
+        font_variant_numeric: FontVariantNumeric::NORMAL,
+        font_feature_settings: FontSettings::normal(),
+        font_variation_settings: FontSettings::normal(),
         hash: 0,
     };
     style.compute_font_hash();
@@ -234,6 +243,8 @@ fn test_font_template_is_cached() {
         },
         variant: FontVariantCaps::Normal,
         pt_size: Au(10),
+        features: vec![],
+        variations: vec![],
     };
 
     let family_descriptor =
@@ -256,3 +267,36 @@ fn test_font_template_is_cached() {
         "we should only have fetched the template data from the cache thread once"
     );
 }
+
+#[test]
+fn test_font_descriptor_features() {
+    let mut style = style();
+    style.font_kerning = FontKerning::None;
+    style.font_variant_numeric = FontVariantNumeric::TABULAR_NUMS;
+    style.font_feature_settings = FontSettings(
+        vec![FeatureTagValue {
+            tag: FontTag(KERN),
+            value: 1,
+        }]
+        .into_boxed_slice(),
+    );
+
+    assert_eq!(
+        FontDescriptor::from(&style).features,
+        vec![
+            FontFeature {
+                tag: KERN,
+                value: 0
+            },
+            FontFeature {
+                tag: TNUM,
+                value: 1
+            },
+            FontFeature {
+                tag: KERN,
+                value: 1
+            },
+        ],
+        "font-feature-settings should come after the features of the other properties"
+    );
+}
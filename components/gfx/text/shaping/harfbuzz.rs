@@ -4,7 +4,8 @@
 
 #![allow(unsafe_code)]
 
-use crate::font::{Font, FontTableMethods, FontTableTag, ShapingFlags, ShapingOptions, KERN};
+use crate::font::{Font, FontTableMethods, FontTableTag, ShapingFlags, ShapingOptions};
+use crate::font::{KERN, LIGA};
 use crate::platform::font::FontTable;
 use crate::text::glyph::{ByteIndex, GlyphData, GlyphId, GlyphStore};
 use crate::text::shaping::ShaperMethods;
@@ -42,7 +43,6 @@ use std::os::raw::{c_char, c_int, c_uint, c_void};
 use std::{char, cmp, ptr};
 
 const NO_GLYPH: i32 = -1;
-const LIGA: u32 = ot_tag!('l', 'i', 'g', 'a');
 
 pub struct ShapedGlyphData {
     count: usize,
@@ -216,7 +216,20 @@ impl ShaperMethods for Shaper {
                 text.len() as c_int,
             );
 
-            let mut features = Vec::new();
+            // Features later in the list override earlier ones, so the features requested by the
+            // style come before those that the shaping options turn off.
+            let end = hb_buffer_get_length(hb_buffer);
+            let mut features: Vec<hb_feature_t> = (*self.font)
+                .descriptor
+                .features
+                .iter()
+                .map(|feature| hb_feature_t {
+                    tag: feature.tag,
+                    value: feature.value,
+                    start: 0,
+                    end,
+                })
+                .collect();
             if options
                 .flags
                 .contains(ShapingFlags::IGNORE_LIGATURES_SHAPING_FLAG)
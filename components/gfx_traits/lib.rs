@@ -103,3 +103,13 @@ pub fn node_id_from_scroll_id(id: usize) -> Option<usize> {
     }
     None
 }
+
+/// The outcome of loading a web font, reported by the font cache thread once every source of
+/// a font face has been tried.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WebFontLoadResult {
+    /// The lowercased family name the font face was registered under.
+    pub family_name: String,
+    /// Whether any of the sources of the font face could be loaded.
+    pub loaded: bool,
+}
@@ -8,13 +8,16 @@ use crate::context::LayoutContext;
 use crate::display_list::items::OpaqueNode;
 use crate::flow::{Flow, GetBaseFlow};
 use crate::opaque_node::OpaqueNodeMethods;
-use crossbeam_channel::Receiver;
+use crossbeam_channel::{Receiver, Sender};
 use fxhash::{FxHashMap, FxHashSet};
 use ipc_channel::ipc::IpcSender;
 use msg::constellation_msg::PipelineId;
+use script_layout_interface::message::{RunningAnimationInfo, RunningAnimationKind};
 use script_traits::UntrustedNodeAddress;
 use script_traits::{AnimationState, ConstellationControlMsg, LayoutMsg as ConstellationMsg};
-use style::animation::{update_style_for_animation, Animation};
+use servo_atoms::Atom;
+use style::animation::{update_style_for_animation, Animation, TransitionState};
+use style::animation::{KeyframesAnimationOrigin, KeyframesIterationState, KeyframesRunningState};
 use style::dom::TElement;
 use style::font_metrics::ServoMetricsProvider;
 use style::selector_parser::RestyleDamage;
@@ -80,8 +83,9 @@ pub fn update_animation_state<E>(
         for mut running_animation in running_animations.drain(..) {
             let still_running = !running_animation.is_expired() &&
                 match running_animation {
-                    Animation::Transition(_, started_at, ref frame) => {
-                        now < started_at + frame.duration
+                    Animation::Transition(_, ref state, ref frame) => match state.running_state {
+                        KeyframesRunningState::Running => now < state.started_at + frame.duration,
+                        KeyframesRunningState::Paused(_) => true,
                     },
                     Animation::Keyframes(_, _, _, ref mut state) => {
                         // This animation is still running, or we need to keep
@@ -100,7 +104,10 @@ pub fn update_animation_state<E>(
                 continue;
             }
 
-            if let Animation::Transition(node, _, ref frame) = running_animation {
+            // Canceled transitions don't reach their end.
+            if let Animation::Transition(node, TransitionState { expired: false, .. }, ref frame) =
+                running_animation
+            {
                 script_chan
                     .send(ConstellationControlMsg::TransitionEnd(
                         node.to_untrusted_node_address(),
@@ -209,3 +216,156 @@ fn do_recalc_style_for_animations<E>(
         do_recalc_style_for_animations::<E>(context, kid, animations, invalid_nodes)
     }
 }
+
+/// Cancels the keyframes animation with the given name on the given node,
+/// whether it is already running or still waiting to be picked up by the next
+/// animation update.
+pub fn cancel_animation(
+    running_animations: &mut FxHashMap<OpaqueNode, Vec<Animation>>,
+    new_animations_sender: &Sender<Animation>,
+    new_animations_receiver: &Receiver<Animation>,
+    node: OpaqueNode,
+    name: &Atom,
+) {
+    let pending: Vec<Animation> = new_animations_receiver.try_iter().collect();
+    for animation in pending {
+        if let Animation::Keyframes(ref animation_node, _, ref animation_name, _) = animation {
+            if *animation_node == node && animation_name == name {
+                continue;
+            }
+        }
+        new_animations_sender.send(animation).unwrap();
+    }
+
+    if let Some(animations) = running_animations.get_mut(&node) {
+        for animation in animations.iter_mut() {
+            if let Animation::Keyframes(_, _, ref animation_name, ref mut state) = *animation {
+                if animation_name == name {
+                    debug!("cancel_animation: Expiring animation {}", name);
+                    state.expired = true;
+                }
+            }
+        }
+    }
+}
+
+/// Pauses or resumes the keyframes animation with the given name on the given
+/// node.
+pub fn set_animation_paused(
+    running_animations: &mut FxHashMap<OpaqueNode, Vec<Animation>>,
+    node: OpaqueNode,
+    name: &Atom,
+    paused: bool,
+    timer: &Timer,
+) {
+    let animations = match running_animations.get_mut(&node) {
+        Some(animations) => animations,
+        None => return,
+    };
+    for animation in animations.iter_mut() {
+        if let Animation::Keyframes(_, _, ref animation_name, ref mut state) = *animation {
+            if animation_name == name {
+                state.set_paused(paused, timer);
+            }
+        }
+    }
+}
+
+/// Cancels the transition of the given property on the given node, so that it
+/// is removed without reaching its end.
+pub fn cancel_transition(
+    running_animations: &mut FxHashMap<OpaqueNode, Vec<Animation>>,
+    node: OpaqueNode,
+    property: &Atom,
+) {
+    let animations = match running_animations.get_mut(&node) {
+        Some(animations) => animations,
+        None => return,
+    };
+    for animation in animations.iter_mut() {
+        if let Animation::Transition(_, ref mut state, ref frame) = *animation {
+            if frame.property_animation.property_name() == &**property {
+                debug!("cancel_transition: Expiring transition of {}", property);
+                state.expired = true;
+            }
+        }
+    }
+}
+
+/// Seeks the transition of the given property on the given node to the given
+/// time since its start, in seconds, and pauses or resumes it.
+pub fn set_transition_time(
+    running_animations: &mut FxHashMap<OpaqueNode, Vec<Animation>>,
+    node: OpaqueNode,
+    property: &Atom,
+    time: f64,
+    paused: bool,
+    timer: &Timer,
+) {
+    let animations = match running_animations.get_mut(&node) {
+        Some(animations) => animations,
+        None => return,
+    };
+    for animation in animations.iter_mut() {
+        if let Animation::Transition(_, ref mut state, ref frame) = *animation {
+            if frame.property_animation.property_name() == &**property {
+                state.set_time(time, paused, frame.duration, timer);
+            }
+        }
+    }
+}
+
+/// Returns a snapshot of the animations running on the given node, or on every
+/// node if none is given, so that script can reflect them.
+pub fn running_animation_info(
+    running_animations: &FxHashMap<OpaqueNode, Vec<Animation>>,
+    node: Option<OpaqueNode>,
+    timer: &Timer,
+) -> Vec<RunningAnimationInfo> {
+    let now = timer.seconds();
+    running_animations
+        .iter()
+        .filter(|&(key, _)| node.map_or(true, |node| node == *key))
+        .flat_map(|(_, animations)| animations.iter())
+        .filter(|animation| !animation.is_expired())
+        .map(|animation| match *animation {
+            Animation::Transition(node, ref state, ref frame) => RunningAnimationInfo {
+                node: node.to_untrusted_node_address(),
+                kind: RunningAnimationKind::Transition,
+                name: Atom::from(animation.name()),
+                started_at: state.started_at,
+                duration: frame.duration,
+                current_iteration: 0.,
+                iterations: Some(1.),
+                paused_progress: match state.running_state {
+                    KeyframesRunningState::Paused(progress) => Some(progress),
+                    KeyframesRunningState::Running => None,
+                },
+                now,
+            },
+            Animation::Keyframes(node, _, ref name, ref state) => RunningAnimationInfo {
+                node: node.to_untrusted_node_address(),
+                kind: match state.origin {
+                    KeyframesAnimationOrigin::Css => RunningAnimationKind::CssAnimation,
+                    KeyframesAnimationOrigin::Script(..) => RunningAnimationKind::ScriptAnimation,
+                },
+                name: name.clone(),
+                started_at: state.started_at,
+                duration: state.duration,
+                current_iteration: match state.iteration_state {
+                    KeyframesIterationState::Infinite => 0.,
+                    KeyframesIterationState::Finite(current, _) => current as f64,
+                },
+                iterations: match state.iteration_state {
+                    KeyframesIterationState::Infinite => None,
+                    KeyframesIterationState::Finite(_, max) => Some(max as f64),
+                },
+                paused_progress: match state.running_state {
+                    KeyframesRunningState::Paused(progress) => Some(progress),
+                    KeyframesRunningState::Running => None,
+                },
+                now,
+            },
+        })
+        .collect()
+}
@@ -1170,6 +1170,12 @@ impl BlockFlow {
                 block_size = block_size + floats.clearance(ClearType::Both);
             }
 
+            // Size containment makes the block be sized as if it had no
+            // content. See https://drafts.csswg.org/css-contain-3/#containment-size
+            if self.fragment.style.has_block_size_containment() {
+                block_size = Au(0);
+            }
+
             if self
                 .base
                 .flags
@@ -2151,12 +2157,13 @@ impl Flow for BlockFlow {
     /// inline-sizes and the dimensions of any fragments it is responsible for flowing.
     fn bubble_inline_sizes(&mut self) {
         // If this block has a fixed width, just use that for the minimum and preferred width,
-        // rather than bubbling up children inline width.
+        // rather than bubbling up children inline width. Likewise for size
+        // containment, which ignores the contents of the box.
         // FIXME(emilio): This should probably be writing-mode-aware.
         let consult_children = match self.fragment.style().get_position().width {
             Size::Auto => true,
             Size::LengthPercentage(ref lp) => lp.maybe_to_used_value(None).is_none(),
-        };
+        } && !self.fragment.style().has_inline_size_containment();
         self.bubble_inline_sizes_for_block(consult_children);
         self.fragment
             .restyle_damage
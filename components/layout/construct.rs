@@ -37,6 +37,7 @@ use crate::linked_list::prepend_from;
 use crate::list_item::{ListItemFlow, ListStyleTypeContent};
 use crate::multicol::{MulticolColumnFlow, MulticolFlow};
 use crate::parallel;
+use crate::svg::SvgScene;
 use crate::table::TableFlow;
 use crate::table_caption::TableCaptionFlow;
 use crate::table_cell::TableCellFlow;
@@ -48,6 +49,7 @@ use crate::text::TextRunScanner;
 use crate::traversal::PostorderNodeMutTraversal;
 use crate::wrapper::{LayoutNodeLayoutData, TextContent, ThreadSafeLayoutNodeHelpers};
 use crate::ServoArc;
+use euclid::default::Size2D;
 use script_layout_interface::wrapper_traits::{
     PseudoElementType, ThreadSafeLayoutElement, ThreadSafeLayoutNode,
 };
@@ -455,7 +457,9 @@ impl<'a, ConcreteThreadSafeLayoutNode: ThreadSafeLayoutNode>
             },
             Some(LayoutNodeType::Element(LayoutElementType::SVGSVGElement)) => {
                 let data = node.svg_data().unwrap();
-                SpecificFragmentInfo::Svg(Box::new(SvgFragmentInfo::new(data)))
+                let viewport_size = Size2D::new(data.width as f32, data.height as f32);
+                let scene = SvgScene::new(node, viewport_size, self.style_context());
+                SpecificFragmentInfo::Svg(Box::new(SvgFragmentInfo::new(data, scene)))
             },
             _ => {
                 // This includes pseudo-elements.
@@ -5,7 +5,9 @@
 //! Data needed by the layout thread.
 
 use crate::display_list::items::{OpaqueNode, WebRenderImageInfo};
+use crate::display_list::MaskImageCache;
 use crate::opaque_node::OpaqueNodeMethods;
+use crate::svg::SvgImageCache;
 use fnv::FnvHasher;
 use gfx::font_cache_thread::FontCacheThread;
 use gfx::font_context::FontContext;
@@ -79,6 +81,12 @@ pub struct LayoutContext<'a> {
         >,
     >,
 
+    /// The images that inline SVG content has been rasterized into.
+    pub svg_image_cache: Arc<Mutex<SvgImageCache>>,
+
+    /// The images that clip paths and masks have been rasterized into.
+    pub mask_image_cache: Arc<Mutex<MaskImageCache>>,
+
     /// Paint worklets
     pub registered_painters: &'a dyn RegisteredPainters,
 
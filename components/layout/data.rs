@@ -3,7 +3,9 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
 use crate::construct::ConstructionResult;
+use app_units::Au;
 use atomic_refcell::AtomicRefCell;
+use euclid::default::Size2D;
 use script_layout_interface::StyleData;
 
 #[repr(C)]
@@ -40,6 +42,10 @@ pub struct LayoutData {
 
     pub details_content_flow_construction_result: ConstructionResult,
 
+    /// The size of the content box of this node as of the last layout, if it
+    /// is a query container. See `TElement::query_container_size`.
+    pub container_size: Option<Size2D<Au>>,
+
     /// Various flags.
     pub flags: LayoutDataFlags,
 }
@@ -53,6 +59,7 @@ impl LayoutData {
             after_flow_construction_result: ConstructionResult::None,
             details_summary_flow_construction_result: ConstructionResult::None,
             details_content_flow_construction_result: ConstructionResult::None,
+            container_size: None,
             flags: LayoutDataFlags::empty(),
         }
     }
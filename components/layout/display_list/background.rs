@@ -8,8 +8,11 @@ use euclid::default::{Point2D, Rect, SideOffsets2D, Size2D};
 use style::computed_values::background_attachment::single_value::T as BackgroundAttachment;
 use style::computed_values::background_clip::single_value::T as BackgroundClip;
 use style::computed_values::background_origin::single_value::T as BackgroundOrigin;
-use style::properties::style_structs::Background;
-use style::values::computed::{BackgroundSize, NonNegativeLengthPercentageOrAuto};
+use style::computed_values::mask_clip::single_value::T as MaskClip;
+use style::computed_values::mask_origin::single_value::T as MaskOrigin;
+use style::properties::style_structs::{Background, SVG};
+use style::values::computed::NonNegativeLengthPercentageOrAuto;
+use style::values::computed::{BackgroundRepeat, BackgroundSize, LengthPercentage};
 use style::values::specified::background::BackgroundRepeatKeyword;
 use webrender_api::BorderRadius;
 
@@ -153,10 +156,6 @@ pub fn placement(
     let bg_attachment = *get_cyclic(&bg.background_attachment.0, index);
     let bg_clip = *get_cyclic(&bg.background_clip.0, index);
     let bg_origin = *get_cyclic(&bg.background_origin.0, index);
-    let bg_position_x = get_cyclic(&bg.background_position_x.0, index);
-    let bg_position_y = get_cyclic(&bg.background_position_y.0, index);
-    let bg_repeat = get_cyclic(&bg.background_repeat.0, index);
-    let bg_size = *get_cyclic(&bg.background_size.0, index);
 
     let (clip_rect, clip_radii) = clip(
         bg_clip,
@@ -167,7 +166,7 @@ pub fn placement(
     );
 
     let mut fixed = false;
-    let mut bounds = match bg_attachment {
+    let bounds = match bg_attachment {
         BackgroundAttachment::Scroll => match bg_origin {
             BackgroundOrigin::BorderBox => absolute_bounds,
             BackgroundOrigin::PaddingBox => absolute_bounds.inner_rect(border),
@@ -179,14 +178,84 @@ pub fn placement(
         },
     };
 
-    let mut tile_size = compute_background_image_size(bg_size, bounds.size, intrinsic_size);
+    place_layer(
+        bounds,
+        get_cyclic(&bg.background_position_x.0, index),
+        get_cyclic(&bg.background_position_y.0, index),
+        get_cyclic(&bg.background_repeat.0, index),
+        *get_cyclic(&bg.background_size.0, index),
+        intrinsic_size,
+        clip_rect,
+        clip_radii,
+        fixed,
+    )
+}
+
+/// Determines where to place an element mask image or gradient.
+///
+/// Mask layers are positioned like background layers, but they never have
+/// rounded corners nor are fixed to the viewport.
+pub fn mask_placement(
+    svg: &SVG,
+    absolute_bounds: Rect<Au>,
+    intrinsic_size: Option<Size2D<Au>>,
+    border: SideOffsets2D<Au>,
+    border_padding: SideOffsets2D<Au>,
+    index: usize,
+) -> BackgroundPlacement {
+    let mask_clip = match *get_cyclic(&svg.mask_clip.0, index) {
+        MaskClip::BorderBox => BackgroundClip::BorderBox,
+        MaskClip::PaddingBox => BackgroundClip::PaddingBox,
+        MaskClip::ContentBox => BackgroundClip::ContentBox,
+    };
+    let bounds = match *get_cyclic(&svg.mask_origin.0, index) {
+        MaskOrigin::BorderBox => absolute_bounds,
+        MaskOrigin::PaddingBox => absolute_bounds.inner_rect(border),
+        MaskOrigin::ContentBox => absolute_bounds.inner_rect(border_padding),
+    };
+
+    let (clip_rect, clip_radii) = clip(
+        mask_clip,
+        absolute_bounds,
+        border,
+        border_padding,
+        BorderRadius::zero(),
+    );
+
+    place_layer(
+        bounds,
+        get_cyclic(&svg.mask_position_x.0, index),
+        get_cyclic(&svg.mask_position_y.0, index),
+        get_cyclic(&svg.mask_repeat.0, index),
+        *get_cyclic(&svg.mask_size.0, index),
+        intrinsic_size,
+        clip_rect,
+        clip_radii,
+        false,
+    )
+}
+
+/// Sizes, positions and tiles a background or mask layer within the bounds
+/// of its positioning area.
+fn place_layer(
+    mut bounds: Rect<Au>,
+    position_x: &LengthPercentage,
+    position_y: &LengthPercentage,
+    repeat: &BackgroundRepeat,
+    size: BackgroundSize,
+    intrinsic_size: Option<Size2D<Au>>,
+    clip_rect: Rect<Au>,
+    clip_radii: BorderRadius,
+    fixed: bool,
+) -> BackgroundPlacement {
+    let mut tile_size = compute_background_image_size(size, bounds.size, intrinsic_size);
 
     let mut tile_spacing = Size2D::zero();
     let own_position = bounds.size - tile_size;
-    let pos_x = bg_position_x.to_used_value(own_position.width);
-    let pos_y = bg_position_y.to_used_value(own_position.height);
+    let pos_x = position_x.to_used_value(own_position.width);
+    let pos_y = position_y.to_used_value(own_position.height);
     tile_image_axis(
-        bg_repeat.0,
+        repeat.0,
         &mut bounds.origin.x,
         &mut bounds.size.width,
         &mut tile_size.width,
@@ -196,7 +265,7 @@ pub fn placement(
         clip_rect.size.width,
     );
     tile_image_axis(
-        bg_repeat.1,
+        repeat.1,
         &mut bounds.origin.y,
         &mut bounds.size.height,
         &mut tile_size.height,
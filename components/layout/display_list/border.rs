@@ -8,6 +8,7 @@ use euclid::default::{Rect, SideOffsets2D as UntypedSideOffsets2D, Size2D as Unt
 use euclid::{SideOffsets2D, Size2D};
 use style::computed_values::border_image_outset::T as BorderImageOutset;
 use style::properties::style_structs::Border;
+use style::values::computed::BorderRadius as ComputedBorderRadius;
 use style::values::computed::NumberOrPercentage;
 use style::values::computed::{BorderCornerRadius, BorderImageWidth};
 use style::values::computed::{BorderImageSideWidth, NonNegativeLengthOrNumber};
@@ -103,6 +104,19 @@ pub fn radii(abs_bounds: Rect<Au>, border_style: &Border) -> BorderRadius {
     )
 }
 
+/// Determine the four corner radii of an `inset()` basic shape.
+pub fn inset_radii(abs_bounds: Rect<Au>, radius: &ComputedBorderRadius) -> BorderRadius {
+    overlapping_radii(
+        abs_bounds.size.to_layout(),
+        BorderRadius {
+            top_left: corner_radius(radius.top_left, abs_bounds.size).to_layout(),
+            top_right: corner_radius(radius.top_right, abs_bounds.size).to_layout(),
+            bottom_right: corner_radius(radius.bottom_right, abs_bounds.size).to_layout(),
+            bottom_left: corner_radius(radius.bottom_left, abs_bounds.size).to_layout(),
+        },
+    )
+}
+
 /// Calculates radii for the inner side.
 ///
 /// Radii usually describe the outer side of a border but for the lines to look nice
@@ -127,6 +141,33 @@ pub fn inner_radii(mut radii: BorderRadius, offsets: UntypedSideOffsets2D<Au>) -
     radii
 }
 
+/// Calculates radii for the outer side of the margin.
+///
+/// Only rounded corners grow with the margin, so that square corners stay square.
+///
+/// This is used to determine the `margin-box` reference box of masking.
+pub fn outer_radii(mut radii: BorderRadius, offsets: UntypedSideOffsets2D<Au>) -> BorderRadius {
+    fn outer_length(x: f32, offset: Au) -> f32 {
+        if x > 0. {
+            x + offset.to_f32_px().max(0.)
+        } else {
+            x
+        }
+    }
+    radii.top_left.width = outer_length(radii.top_left.width, offsets.left);
+    radii.bottom_left.width = outer_length(radii.bottom_left.width, offsets.left);
+
+    radii.top_right.width = outer_length(radii.top_right.width, offsets.right);
+    radii.bottom_right.width = outer_length(radii.bottom_right.width, offsets.right);
+
+    radii.top_left.height = outer_length(radii.top_left.height, offsets.top);
+    radii.top_right.height = outer_length(radii.top_right.height, offsets.top);
+
+    radii.bottom_left.height = outer_length(radii.bottom_left.height, offsets.bottom);
+    radii.bottom_right.height = outer_length(radii.bottom_right.height, offsets.bottom);
+    radii
+}
+
 /// Creates a four-sided border with square corners and uniform color and width.
 pub fn simple(color: ColorF, style: BorderStyle) -> NormalBorder {
     let side = BorderSide { color, style };
@@ -21,10 +21,13 @@ use crate::display_list::items::{IframeDisplayItem, OpaqueNode};
 use crate::display_list::items::{PopAllTextShadowsDisplayItem, PushTextShadowDisplayItem};
 use crate::display_list::items::{StackingContext, StackingContextType, StickyFrameData};
 use crate::display_list::items::{TextOrientation, WebRenderImageInfo};
+use crate::display_list::masking;
+use crate::display_list::scroll_snap::ScrollSnapContainer;
 use crate::display_list::ToLayout;
 use crate::flow::{BaseFlow, Flow, FlowFlags};
 use crate::flow_ref::FlowRef;
 use crate::fragment::SpecificFragmentInfo;
+use crate::fragment::SvgFragmentInfo;
 use crate::fragment::{CanvasFragmentSource, CoordinateSystem, Fragment, ScannedTextFragmentInfo};
 use crate::inline::InlineFragmentNodeFlags;
 use crate::model::MaybeAuto;
@@ -33,7 +36,7 @@ use app_units::{Au, AU_PER_PX};
 use canvas_traits::canvas::{CanvasMsg, FromLayoutMsg};
 use embedder_traits::Cursor;
 use euclid::{
-    default::{Point2D, Rect, SideOffsets2D as UntypedSideOffsets2D, Size2D},
+    default::{Point2D, Rect, SideOffsets2D as UntypedSideOffsets2D, Size2D, Vector2D},
     rect, SideOffsets2D,
 };
 use fnv::FnvHashMap;
@@ -44,7 +47,7 @@ use ipc_channel::ipc;
 use msg::constellation_msg::PipelineId;
 use net_traits::image_cache::UsePlaceholder;
 use range::Range;
-use script_traits::IFrameSize;
+use script_traits::{IFrameSize, ScrollContainerInfo};
 use servo_config::opts;
 use servo_geometry::{self, MaxRect};
 use std::default::Default;
@@ -150,7 +153,10 @@ impl StackingContextInfo {
     }
 }
 
-pub struct StackingContextCollectionState {
+pub struct StackingContextCollectionState<'a> {
+    /// A LayoutContext reference important for creating WebRender image masks.
+    pub layout_context: &'a LayoutContext<'a>,
+
     /// The PipelineId of this stacking context collection.
     pub pipeline_id: PipelineId,
 
@@ -195,10 +201,14 @@ pub struct StackingContextCollectionState {
 
     /// The flow parent's content box, used to calculate sticky constraints.
     parent_stacking_relative_content_box: Rect<Au>,
+
+    /// The scroll containers that input events can scroll, along with the snap areas
+    /// inside them.
+    scroll_snap_containers: Vec<ScrollSnapContainer>,
 }
 
-impl StackingContextCollectionState {
-    pub fn new(pipeline_id: PipelineId) -> StackingContextCollectionState {
+impl<'a> StackingContextCollectionState<'a> {
+    pub fn new(layout_context: &'a LayoutContext) -> StackingContextCollectionState<'a> {
         let root_clip_indices =
             ClippingAndScrolling::simple(ClipScrollNodeIndex::root_scroll_node());
 
@@ -216,7 +226,8 @@ impl StackingContextCollectionState {
         let clip_scroll_nodes = vec![ClipScrollNode::placeholder(), ClipScrollNode::placeholder()];
 
         StackingContextCollectionState {
-            pipeline_id: pipeline_id,
+            layout_context: layout_context,
+            pipeline_id: layout_context.id,
             root_stacking_context: StackingContext::root(),
             stacking_context_info,
             clip_scroll_nodes,
@@ -229,6 +240,90 @@ impl StackingContextCollectionState {
             clip_stack: Vec::new(),
             containing_block_clip_stack: Vec::new(),
             parent_stacking_relative_content_box: Rect::zero(),
+            scroll_snap_containers: Vec::new(),
+        }
+    }
+
+    /// Adds the viewport of the root flow as the outermost scroll container. Its scroll-related
+    /// properties are taken from the root element.
+    pub fn add_root_scroll_container(
+        &mut self,
+        root_style: &ComputedValues,
+        content_size: Size2D<Au>,
+    ) {
+        let viewport_size = self.layout_context.shared_context().viewport_size();
+        let max_scroll_position = Vector2D::new(
+            (content_size.width - viewport_size.width).max(Au(0)),
+            (content_size.height - viewport_size.height).max(Au(0)),
+        );
+        self.scroll_snap_containers.push(ScrollSnapContainer::new(
+            ClipScrollNodeIndex::root_scroll_node(),
+            self.pipeline_id.root_scroll_id(),
+            None,
+            root_style,
+            Rect::new(Point2D::zero(), viewport_size),
+            max_scroll_position,
+        ));
+    }
+
+    /// Returns the position in `scroll_snap_containers` of the nearest scroll container
+    /// that scrolls the clip scroll node at `index`, or `None` if there isn't one.
+    ///
+    /// Reference frames other than the root one move their contents out of the coordinate
+    /// space of the scroll containers, so nodes inside them don't have a scroll container.
+    fn nearest_scroll_snap_container(&self, mut index: ClipScrollNodeIndex) -> Option<usize> {
+        while index != ClipScrollNodeIndex::root_reference_frame() {
+            if let Some(position) = self
+                .scroll_snap_containers
+                .iter()
+                .position(|container| container.node_index == index)
+            {
+                return Some(position);
+            }
+
+            let node = &self.clip_scroll_nodes[index.to_index()];
+            if node.is_placeholder() {
+                return None;
+            }
+            index = node.parent_index;
+        }
+        None
+    }
+
+    /// Adds a scroll container that input events can scroll, whose contents are scrolled by
+    /// the clip scroll node at `index`.
+    fn add_scroll_snap_container(
+        &mut self,
+        index: ClipScrollNodeIndex,
+        scroll_id: ExternalScrollId,
+        style: &ComputedValues,
+        scrollport: Rect<Au>,
+        max_scroll_position: Vector2D<Au>,
+    ) {
+        let parent_index = self.clip_scroll_nodes[index.to_index()].parent_index;
+        let parent_scroll_id = self
+            .nearest_scroll_snap_container(parent_index)
+            .map(|position| self.scroll_snap_containers[position].scroll_id());
+        self.scroll_snap_containers.push(ScrollSnapContainer::new(
+            index,
+            scroll_id,
+            parent_scroll_id,
+            style,
+            scrollport,
+            max_scroll_position,
+        ));
+    }
+
+    /// Adds the snap area of a box, if it has one, to the nearest scroll container that
+    /// scrolls the clip scroll node at `index`.
+    fn add_scroll_snap_area(
+        &mut self,
+        index: ClipScrollNodeIndex,
+        style: &ComputedValues,
+        border_box: Rect<Au>,
+    ) {
+        if let Some(position) = self.nearest_scroll_snap_container(index) {
+            self.scroll_snap_containers[position].add_snap_area(style, border_box);
         }
     }
 
@@ -325,6 +420,10 @@ pub struct DisplayListBuildState<'a> {
 
     /// Stores text runs to answer text queries used to place a cursor inside text.
     pub indexable_text: IndexableText,
+
+    /// The scroll containers that input events can scroll, used to inform the compositor
+    /// about how to snap and chain scrolls.
+    pub scroll_containers: Vec<ScrollContainerInfo>,
 }
 
 impl<'a> DisplayListBuildState<'a> {
@@ -345,6 +444,11 @@ impl<'a> DisplayListBuildState<'a> {
             ),
             iframe_sizes: Vec::new(),
             indexable_text: IndexableText::default(),
+            scroll_containers: state
+                .scroll_snap_containers
+                .into_iter()
+                .map(ScrollSnapContainer::finish)
+                .collect(),
         }
     }
 
@@ -569,6 +673,22 @@ impl<'a> DisplayListBuildState<'a> {
 /// The logical width of an insertion point: at the moment, a one-pixel-wide line.
 const INSERTION_POINT_LOGICAL_WIDTH: Au = Au(1 * AU_PER_PX);
 
+/// Returns the regions that the `clip-path` and then the `mask` of a fragment with the given
+/// border box clip its contents to.
+fn masking_clips(
+    layout_context: &LayoutContext,
+    fragment: &Fragment,
+    border_box: Rect<Au>,
+) -> Vec<ClippingRegion> {
+    if !fragment.has_clip_path_or_mask() {
+        return Vec::new();
+    }
+    masking::clip_path_region(layout_context, fragment, border_box)
+        .into_iter()
+        .chain(masking::mask_region(layout_context, fragment, border_box))
+        .collect()
+}
+
 /// Get the border radius for the rectangle inside of a rounded border. This is useful
 /// for building the clip for the content inside the border.
 fn build_border_radius_for_inner_rect(
@@ -633,6 +753,25 @@ impl Fragment {
             None
         };
 
+        // The contents of the fragment are clipped by its clip path and masks, which are
+        // children of its reference frame if it has one, so they are defined in its own
+        // stacking context.
+        let border_box = base.stacking_relative_border_box_for_display_list(self);
+        let mut parent_index =
+            established_reference_frame.unwrap_or(state.current_clipping_and_scrolling.scrolling);
+        let parent_real_stacking_context_id = state.current_real_stacking_context_id;
+        state.current_real_stacking_context_id = self.stacking_context_id;
+        for clip in masking_clips(state.layout_context, self, border_box) {
+            parent_index = state.add_clip_scroll_node(ClipScrollNode {
+                parent_index,
+                clip,
+                content_rect: LayoutRect::zero(), // content_rect isn't important for clips.
+                node_type: ClipScrollNodeType::Clip,
+            });
+            self.established_masking_clip = Some(parent_index);
+        }
+        state.current_real_stacking_context_id = parent_real_stacking_context_id;
+
         let current_stacking_context_id = state.current_stacking_context_id;
         let stacking_context = self.create_stacking_context(
             self.stacking_context_id,
@@ -1524,6 +1663,9 @@ impl Fragment {
         if let Some(index) = self.established_reference_frame {
             state.current_clipping_and_scrolling = ClippingAndScrolling::simple(index);
         }
+        if let Some(index) = self.established_masking_clip {
+            state.current_clipping_and_scrolling = ClippingAndScrolling::simple(index);
+        }
 
         self.restyle_damage.remove(ServoRestyleDamage::REPAINT);
         self.build_display_list_no_damage(
@@ -1784,8 +1926,23 @@ impl Fragment {
             SpecificFragmentInfo::InlineBlock(_) |
             SpecificFragmentInfo::InlineAbsoluteHypothetical(_) |
             SpecificFragmentInfo::InlineAbsolute(_) |
-            SpecificFragmentInfo::TruncatedFragment(_) |
-            SpecificFragmentInfo::Svg(_) => {
+            SpecificFragmentInfo::TruncatedFragment(_) => {
+                if opts::get().show_debug_fragment_borders {
+                    self.build_debug_borders_around_fragment(
+                        state,
+                        stacking_relative_border_box,
+                        clip,
+                    );
+                }
+            },
+            SpecificFragmentInfo::Svg(ref svg_fragment) => {
+                self.build_display_list_for_svg_fragment(
+                    state,
+                    svg_fragment,
+                    stacking_relative_content_box,
+                    clip,
+                );
+
                 if opts::get().show_debug_fragment_borders {
                     self.build_debug_borders_around_fragment(
                         state,
@@ -1962,6 +2119,90 @@ impl Fragment {
         )
     }
 
+    /// Creates the display items for the shapes and text inside an `<svg>` element.
+    ///
+    /// Shapes are rasterized into a single image covering the content box, and text is drawn
+    /// on top of it.
+    fn build_display_list_for_svg_fragment(
+        &self,
+        state: &mut DisplayListBuildState,
+        svg_fragment: &SvgFragmentInfo,
+        stacking_relative_content_box: Rect<Au>,
+        clip: Rect<Au>,
+    ) {
+        let viewport_size = Size2D::new(
+            stacking_relative_content_box.size.width.to_f32_px(),
+            stacking_relative_content_box.size.height.to_f32_px(),
+        );
+        if viewport_size.is_empty_or_negative() {
+            return;
+        }
+
+        let base = state.create_base_display_item(
+            clip,
+            self.node,
+            get_cursor(&self.style, Cursor::Default),
+            DisplayListSection::Content,
+        );
+
+        if svg_fragment.scene.has_shapes() {
+            let device_pixel_ratio = state.layout_context.style_context.device_pixel_ratio();
+            let image_key = state
+                .layout_context
+                .svg_image_cache
+                .lock()
+                .unwrap()
+                .image_key(
+                    self.node,
+                    &svg_fragment.scene,
+                    viewport_size,
+                    device_pixel_ratio.get(),
+                );
+            if let Some(image_key) = image_key {
+                state.add_image_item(
+                    base.clone(),
+                    webrender_api::ImageDisplayItem {
+                        bounds: stacking_relative_content_box.to_layout(),
+                        common: items::empty_common_item_properties(),
+                        image_key,
+                        stretch_size: stacking_relative_content_box.size.to_layout(),
+                        tile_spacing: LayoutSize::zero(),
+                        image_rendering: ImageRendering::Auto,
+                        alpha_type: webrender_api::AlphaType::PremultipliedAlpha,
+                        color: webrender_api::ColorF::WHITE,
+                    },
+                );
+            }
+        }
+
+        for text_run in svg_fragment
+            .scene
+            .text_runs(state.layout_context, viewport_size)
+        {
+            let baseline_origin = stacking_relative_content_box.origin +
+                Vector2D::new(
+                    Au::from_f32_px(text_run.baseline_origin.x),
+                    Au::from_f32_px(text_run.baseline_origin.y),
+                );
+            let range = Range::new(ByteIndex(0), ByteIndex(text_run.run.text.len() as isize));
+            let glyphs = convert_text_run_to_glyphs(text_run.run.clone(), range, baseline_origin);
+            if glyphs.is_empty() {
+                continue;
+            }
+            state.add_display_item(DisplayItem::Text(CommonDisplayItem::with_data(
+                base.clone(),
+                webrender_api::TextDisplayItem {
+                    bounds: stacking_relative_content_box.to_layout(),
+                    common: items::empty_common_item_properties(),
+                    font_key: text_run.run.font_key,
+                    color: text_run.color.to_layout(),
+                    glyph_options: None,
+                },
+                glyphs,
+            )));
+        }
+    }
+
     /// Creates the text display item for one text fragment. This can be called multiple times for
     /// one fragment if there are text shadows.
     ///
@@ -2446,8 +2687,15 @@ impl BlockFlow {
             self.transform_clip_to_coordinate_space(state, preserved_state);
         }
 
+        state.add_scroll_snap_area(
+            containing_clipping_and_scrolling.scrolling,
+            &self.fragment.style,
+            stacking_relative_border_box,
+        );
+
         if !flags.contains(StackingContextCollectionFlags::NEVER_CREATES_CLIP_SCROLL_NODE) {
             self.setup_clip_scroll_node_for_position(state, stacking_relative_border_box);
+            self.setup_clip_scroll_node_for_clip_path_and_mask(state, stacking_relative_border_box);
             self.setup_clip_scroll_node_for_overflow(state, stacking_relative_border_box);
             self.setup_clip_scroll_node_for_css_clip(
                 state,
@@ -2563,6 +2811,26 @@ impl BlockFlow {
         state.current_clipping_and_scrolling = new_clipping_and_scrolling;
     }
 
+    /// Adds clip nodes for a block to take the `clip-path` and `mask` properties into account.
+    fn setup_clip_scroll_node_for_clip_path_and_mask(
+        &mut self,
+        state: &mut StackingContextCollectionState,
+        border_box: Rect<Au>,
+    ) {
+        for clip in masking_clips(state.layout_context, &self.fragment, border_box) {
+            let new_clip_scroll_index = state.add_clip_scroll_node(ClipScrollNode {
+                parent_index: self.clipping_and_scrolling().scrolling,
+                clip: clip,
+                content_rect: LayoutRect::zero(), // content_rect isn't important for clips.
+                node_type: ClipScrollNodeType::Clip,
+            });
+
+            let new_clipping_and_scrolling = ClippingAndScrolling::simple(new_clip_scroll_index);
+            self.base.clipping_and_scrolling = Some(new_clipping_and_scrolling);
+            state.current_clipping_and_scrolling = new_clipping_and_scrolling;
+        }
+    }
+
     fn setup_clip_scroll_node_for_overflow(
         &mut self,
         state: &mut StackingContextCollectionState,
@@ -2617,6 +2885,20 @@ impl BlockFlow {
             node_type: ClipScrollNodeType::ScrollFrame(sensitivity, external_id),
         });
 
+        if let ScrollSensitivity::ScriptAndInputEvents = sensitivity {
+            let max_scroll_position = Vector2D::new(
+                (content_size.width - clip_rect.size.width).max(Au(0)),
+                (content_size.height - clip_rect.size.height).max(Au(0)),
+            );
+            state.add_scroll_snap_container(
+                new_clip_scroll_index,
+                external_id,
+                &self.fragment.style,
+                clip_rect,
+                max_scroll_position,
+            );
+        }
+
         let new_clipping_and_scrolling = ClippingAndScrolling::simple(new_clip_scroll_index);
         self.base.clipping_and_scrolling = Some(new_clipping_and_scrolling);
         state.current_clipping_and_scrolling = new_clipping_and_scrolling;
@@ -27,7 +27,7 @@ use webrender_api as wr;
 use webrender_api::units::{LayoutPixel, LayoutPoint, LayoutRect, LayoutSize, LayoutTransform};
 use webrender_api::{BorderRadius, ClipId, ClipMode, CommonItemProperties, ComplexClipRegion};
 use webrender_api::{ExternalScrollId, FilterOp, GlyphInstance, GradientStop, ImageKey};
-use webrender_api::{MixBlendMode, ScrollSensitivity, Shadow, SpatialId};
+use webrender_api::{ImageMask, MixBlendMode, ScrollSensitivity, Shadow, SpatialId};
 use webrender_api::{StickyOffsetBounds, TransformStyle};
 
 pub use style::dom::OpaqueNode;
@@ -467,6 +467,9 @@ pub struct ClippingRegion {
     /// TODO(pcwalton): Atomically reference count these? Not sure if it's worth the trouble.
     /// Measure and follow up.
     pub complex: Vec<ComplexClipRegion>,
+    /// An image whose alpha channel clips the main rectangle, for masks and clip paths that are
+    /// not rounded rectangles.
+    pub image_mask: Option<ImageMask>,
 }
 
 impl ClippingRegion {
@@ -476,6 +479,7 @@ impl ClippingRegion {
         ClippingRegion {
             main: LayoutRect::zero(),
             complex: Vec::new(),
+            image_mask: None,
         }
     }
 
@@ -485,6 +489,7 @@ impl ClippingRegion {
         ClippingRegion {
             main: LayoutRect::max_rect(),
             complex: Vec::new(),
+            image_mask: None,
         }
     }
 
@@ -494,6 +499,7 @@ impl ClippingRegion {
         ClippingRegion {
             main: rect,
             complex: Vec::new(),
+            image_mask: None,
         }
     }
 
@@ -534,6 +540,12 @@ impl fmt::Debug for ClippingRegion {
             write!(f, "ClippingRegion::Empty")
         } else if self.main == LayoutRect::max_rect() {
             write!(f, "ClippingRegion(Complex={:?})", self.complex)
+        } else if let Some(ref image_mask) = self.image_mask {
+            write!(
+                f,
+                "ClippingRegion(Rect={:?}, Complex={:?}, ImageMask={:?})",
+                self.main, self.complex, image_mask
+            )
         } else {
             write!(
                 f,
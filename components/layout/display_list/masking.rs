@@ -0,0 +1,910 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Clip regions for the `clip-path` and `mask` properties.
+//!
+//! Boxes, insets, circles and ellipses become rounded rectangle clips. Polygons, references to
+//! `<clipPath>` elements and mask layers are rasterized into image masks.
+//!
+//! See https://drafts.fxtf.org/css-masking/
+
+use crate::context::LayoutContext;
+use crate::display_list::background::{self, BackgroundPlacement};
+use crate::display_list::border;
+use crate::display_list::gradient;
+use crate::display_list::items::{ClippingRegion, OpaqueNode};
+use crate::display_list::ToLayout;
+use crate::fragment::Fragment;
+use crate::svg::SvgClipPath;
+use crate::ServoArc;
+use app_units::Au;
+use euclid::default::{Point2D, Rect, SideOffsets2D, Size2D, Transform2D, Vector2D};
+use fnv::FnvHashMap;
+use net_traits::image::base::Image as NetImage;
+use net_traits::image_cache::{ImageOrMetadataAvailable, UsePlaceholder};
+use pixels::PixelFormat;
+use servo_geometry::au_rect_to_f32_rect;
+use std::sync::Arc;
+use style::computed_values::mask_composite::single_value::T as MaskComposite;
+use style::computed_values::mask_mode::single_value::T as MaskMode;
+use style::properties::style_structs;
+use style::values::computed::basic_shape::{BasicShape, ShapeRadius};
+use style::values::computed::image::{Image, ImageLayer};
+use style::values::computed::{Gradient, Position};
+use style::values::generics::basic_shape::{FillRule, GeometryBox, ShapeBox, ShapeSource};
+use style::values::generics::image::GradientKind;
+use webrender_api::units::DeviceIntSize;
+use webrender_api::{BorderRadius, DirtyRect, ExtendMode, GradientStop, ImageData};
+use webrender_api::{ImageDescriptor, ImageFormat, ImageKey, ImageMask, RenderApi, Transaction};
+
+/// The largest width or height of an image mask, in device pixels. Larger masks are rasterized
+/// at a lower resolution.
+const MAX_MASK_SIZE: i32 = 8192;
+
+/// The boxes of a fragment that clip paths and mask layers can be relative to.
+#[derive(Clone, Copy, PartialEq)]
+struct ReferenceBoxes {
+    border_box: Rect<Au>,
+    border_radii: BorderRadius,
+    margin: SideOffsets2D<Au>,
+    border: SideOffsets2D<Au>,
+    border_padding: SideOffsets2D<Au>,
+}
+
+impl ReferenceBoxes {
+    fn new(fragment: &Fragment, border_box: Rect<Au>) -> ReferenceBoxes {
+        let style = &fragment.style;
+        ReferenceBoxes {
+            border_box,
+            border_radii: border::radii(border_box, style.get_border()),
+            margin: fragment.margin.to_physical(style.writing_mode),
+            border: style.logical_border_width().to_physical(style.writing_mode),
+            border_padding: fragment.border_padding.to_physical(style.writing_mode),
+        }
+    }
+
+    /// Returns the box that a `<geometry-box>` refers to, with its corner radii. Elements with
+    /// CSS layout have no SVG boxes, so `fill-box` is the content box, and `stroke-box` and
+    /// `view-box` are the border box.
+    fn geometry_box(&self, geometry_box: GeometryBox) -> (Rect<Au>, BorderRadius) {
+        match geometry_box {
+            GeometryBox::ShapeBox(ShapeBox::MarginBox) => (
+                self.border_box.outer_rect(self.margin),
+                border::outer_radii(self.border_radii, self.margin),
+            ),
+            GeometryBox::ShapeBox(ShapeBox::BorderBox) |
+            GeometryBox::StrokeBox |
+            GeometryBox::ViewBox => (self.border_box, self.border_radii),
+            GeometryBox::ShapeBox(ShapeBox::PaddingBox) => (
+                self.border_box.inner_rect(self.border),
+                border::inner_radii(self.border_radii, self.border),
+            ),
+            GeometryBox::ShapeBox(ShapeBox::ContentBox) | GeometryBox::FillBox => (
+                self.border_box.inner_rect(self.border_padding),
+                border::inner_radii(self.border_radii, self.border_padding),
+            ),
+        }
+    }
+
+    fn translate(&self, offset: Vector2D<Au>) -> ReferenceBoxes {
+        ReferenceBoxes {
+            border_box: self.border_box.translate(offset),
+            ..*self
+        }
+    }
+}
+
+/// Returns the clip region of the `clip-path` of a fragment with the given border box, or
+/// `None` if it has no clip path.
+pub fn clip_path_region(
+    layout_context: &LayoutContext,
+    fragment: &Fragment,
+    border_box: Rect<Au>,
+) -> Option<ClippingRegion> {
+    let boxes = ReferenceBoxes::new(fragment, border_box);
+    match fragment.style.get_svg().clip_path {
+        ShapeSource::None | ShapeSource::Path(_) => None,
+        ShapeSource::Box(geometry_box) => {
+            let (rect, radii) = boxes.geometry_box(geometry_box);
+            Some(rounded_rect_region(rect, radii))
+        },
+        ShapeSource::Shape(ref shape, geometry_box) => {
+            let geometry_box = geometry_box.unwrap_or(GeometryBox::ShapeBox(ShapeBox::BorderBox));
+            let (reference_box, _) = boxes.geometry_box(geometry_box);
+            Some(basic_shape_region(
+                layout_context,
+                fragment,
+                &boxes,
+                shape,
+                reference_box,
+            ))
+        },
+        ShapeSource::ImageOrUrl(_) => {
+            // References to anything but a `<clipPath>` element are ignored.
+            let clip_path = fragment.clip_path.as_ref()?;
+            let reference_box = au_rect_to_f32_rect(border_box);
+            let bounds = match clip_path.bounds(&reference_box) {
+                Some(bounds) => rect_to_au(&bounds),
+                None => return Some(ClippingRegion::empty()),
+            };
+            let source = MaskSource::new(fragment, Some(clip_path), &boxes, bounds);
+            let image_key = layout_context.mask_image_cache.lock().unwrap().image_key(
+                fragment.node,
+                MaskKind::ClipPath,
+                source,
+                bounds,
+                device_pixel_ratio(layout_context),
+                |draw_target, _, transform| {
+                    clip_path.fill(draw_target, &reference_box, transform);
+                    true
+                },
+            );
+            Some(image_mask_region(image_key, bounds))
+        },
+    }
+}
+
+/// Returns the clip region of a basic shape, resolved against its reference box.
+fn basic_shape_region(
+    layout_context: &LayoutContext,
+    fragment: &Fragment,
+    boxes: &ReferenceBoxes,
+    shape: &BasicShape,
+    reference_box: Rect<Au>,
+) -> ClippingRegion {
+    match *shape {
+        BasicShape::Inset(ref inset) => {
+            let rect = reference_box.inner_rect(SideOffsets2D::new(
+                inset.rect.0.to_used_value(reference_box.size.height),
+                inset.rect.1.to_used_value(reference_box.size.width),
+                inset.rect.2.to_used_value(reference_box.size.height),
+                inset.rect.3.to_used_value(reference_box.size.width),
+            ));
+            if rect.size.width <= Au(0) || rect.size.height <= Au(0) {
+                return ClippingRegion::empty();
+            }
+            rounded_rect_region(rect, border::inset_radii(rect, &inset.round))
+        },
+        BasicShape::Circle(ref circle) => {
+            let center = resolve_position(&circle.position, reference_box);
+            // Percentages are relative to the normalized diagonal of the reference box.
+            let diagonal = Au::from_f32_px(
+                reference_box
+                    .size
+                    .width
+                    .to_f32_px()
+                    .hypot(reference_box.size.height.to_f32_px()) /
+                    2f32.sqrt(),
+            );
+            let radius = match circle.radius {
+                ShapeRadius::Length(ref length) => length.0.to_used_value(diagonal),
+                ShapeRadius::ClosestSide => {
+                    let (x, y) = side_distances(center, reference_box);
+                    x.0.min(y.0)
+                },
+                ShapeRadius::FarthestSide => {
+                    let (x, y) = side_distances(center, reference_box);
+                    x.1.max(y.1)
+                },
+            };
+            ellipse_region(center, Size2D::new(radius, radius))
+        },
+        BasicShape::Ellipse(ref ellipse) => {
+            let center = resolve_position(&ellipse.position, reference_box);
+            let (x, y) = side_distances(center, reference_box);
+            let resolve = |radius: &ShapeRadius, size: Au, distances: (Au, Au)| match *radius {
+                ShapeRadius::Length(ref length) => length.0.to_used_value(size),
+                ShapeRadius::ClosestSide => distances.0,
+                ShapeRadius::FarthestSide => distances.1,
+            };
+            ellipse_region(
+                center,
+                Size2D::new(
+                    resolve(&ellipse.semiaxis_x, reference_box.size.width, x),
+                    resolve(&ellipse.semiaxis_y, reference_box.size.height, y),
+                ),
+            )
+        },
+        BasicShape::Polygon(ref polygon) => {
+            let points: Vec<Point2D<Au>> = polygon
+                .coordinates
+                .iter()
+                .map(|coordinate| {
+                    reference_box.origin +
+                        Vector2D::new(
+                            coordinate.0.to_used_value(reference_box.size.width),
+                            coordinate.1.to_used_value(reference_box.size.height),
+                        )
+                })
+                .collect();
+            let bounds = Rect::from_points(&points);
+            if points.len() < 3 || bounds.is_empty_or_negative() {
+                return ClippingRegion::empty();
+            }
+            let source = MaskSource::new(fragment, None, boxes, bounds);
+            let image_key = layout_context.mask_image_cache.lock().unwrap().image_key(
+                fragment.node,
+                MaskKind::ClipPath,
+                source,
+                bounds,
+                device_pixel_ratio(layout_context),
+                |draw_target, _, transform| {
+                    let mut path = raqote::PathBuilder::new();
+                    for (index, point) in points.iter().enumerate() {
+                        let (x, y) = (point.x.to_f32_px(), point.y.to_f32_px());
+                        if index == 0 {
+                            path.move_to(x, y);
+                        } else {
+                            path.line_to(x, y);
+                        }
+                    }
+                    path.close();
+                    let mut path = path.finish();
+                    path.winding = match polygon.fill {
+                        FillRule::Nonzero => raqote::Winding::NonZero,
+                        FillRule::Evenodd => raqote::Winding::EvenOdd,
+                    };
+                    draw_target.set_transform(transform);
+                    draw_target.fill(&path, &opaque_source(), &raqote::DrawOptions::new());
+                    true
+                },
+            );
+            image_mask_region(image_key, bounds)
+        },
+    }
+}
+
+/// Returns the clip region of the mask layers of a fragment with the given border box, or
+/// `None` if all its mask layers are `none`.
+pub fn mask_region(
+    layout_context: &LayoutContext,
+    fragment: &Fragment,
+    border_box: Rect<Au>,
+) -> Option<ClippingRegion> {
+    let svg = fragment.style.get_svg();
+    let has_mask_image = svg.mask_image.0.iter().any(|layer| match *layer {
+        ImageLayer::None => false,
+        ImageLayer::Image(_) => true,
+    });
+    if !has_mask_image {
+        return None;
+    }
+
+    // The mask painting area is at most the border box, and everything outside of it is
+    // masked out.
+    let bounds = border_box;
+    if bounds.is_empty_or_negative() {
+        return Some(ClippingRegion::empty());
+    }
+    let boxes = ReferenceBoxes::new(fragment, border_box);
+    let source = MaskSource::new(fragment, None, &boxes, bounds);
+    let image_key = layout_context.mask_image_cache.lock().unwrap().image_key(
+        fragment.node,
+        MaskKind::Mask,
+        source,
+        bounds,
+        device_pixel_ratio(layout_context),
+        |draw_target, device_size, transform| {
+            paint_mask_layers(
+                layout_context,
+                fragment,
+                &boxes,
+                draw_target,
+                device_size,
+                transform,
+            )
+        },
+    );
+    Some(image_mask_region(image_key, bounds))
+}
+
+/// Paints the mask layers of a fragment, compositing them from the bottom one up. Returns
+/// false if some mask images have not been loaded yet.
+fn paint_mask_layers(
+    layout_context: &LayoutContext,
+    fragment: &Fragment,
+    boxes: &ReferenceBoxes,
+    draw_target: &mut raqote::DrawTarget,
+    device_size: DeviceIntSize,
+    transform: &Transform2D<f32>,
+) -> bool {
+    let svg = fragment.style.get_svg();
+    let mut complete = true;
+    let mut is_bottom_layer = true;
+    for (index, layer) in svg.mask_image.0.iter().enumerate().rev() {
+        let mut layer_target = raqote::DrawTarget::new(device_size.width, device_size.height);
+        layer_target.set_transform(transform);
+        if let ImageLayer::Image(ref image) = *layer {
+            complete &= paint_mask_image(
+                layout_context,
+                fragment,
+                boxes,
+                image,
+                index,
+                &mut layer_target,
+            );
+        }
+
+        let mode = *background::get_cyclic(&svg.mask_mode.0, index);
+        if mode == MaskMode::Luminance {
+            for pixel in layer_target.get_data_mut() {
+                *pixel = luminance_to_alpha(*pixel);
+            }
+        }
+
+        // The composite operator of a layer combines it with the layers below it.
+        let blend_mode = if is_bottom_layer {
+            raqote::BlendMode::SrcOver
+        } else {
+            match *background::get_cyclic(&svg.mask_composite.0, index) {
+                MaskComposite::Add => raqote::BlendMode::SrcOver,
+                MaskComposite::Subtract => raqote::BlendMode::SrcOut,
+                MaskComposite::Intersect => raqote::BlendMode::SrcIn,
+                MaskComposite::Exclude => raqote::BlendMode::Xor,
+            }
+        };
+        is_bottom_layer = false;
+
+        draw_target.set_transform(&Transform2D::identity());
+        draw_target.draw_image_at(
+            0.,
+            0.,
+            &raqote::Image {
+                width: device_size.width,
+                height: device_size.height,
+                data: layer_target.get_data(),
+            },
+            &raqote::DrawOptions {
+                blend_mode,
+                ..raqote::DrawOptions::new()
+            },
+        );
+    }
+    complete
+}
+
+/// Paints a mask image in the tiles of its layer. Returns false if the image has not been
+/// loaded yet.
+fn paint_mask_image(
+    layout_context: &LayoutContext,
+    fragment: &Fragment,
+    boxes: &ReferenceBoxes,
+    image: &Image,
+    index: usize,
+    draw_target: &mut raqote::DrawTarget,
+) -> bool {
+    let svg = fragment.style.get_svg();
+    match *image {
+        Image::Gradient(ref gradient) => {
+            let placement = background::mask_placement(
+                svg,
+                boxes.border_box,
+                None,
+                boxes.border,
+                boxes.border_padding,
+                index,
+            );
+            for_each_tile(&placement, |tile, clip| {
+                if let Some(source) = gradient_source(fragment, gradient, tile) {
+                    fill_rect(draw_target, clip, &source);
+                }
+            });
+            true
+        },
+        Image::Url(ref image_url) => {
+            let url = match image_url.url() {
+                Some(url) => url,
+                None => return true,
+            };
+            let image = match layout_context.get_or_request_image_or_meta(
+                fragment.node,
+                url.clone(),
+                UsePlaceholder::No,
+            ) {
+                Some(ImageOrMetadataAvailable::ImageAvailable(image, _)) => image,
+                _ => return false,
+            };
+            let pixels = match image_pixels(&image) {
+                Some(pixels) => pixels,
+                None => return true,
+            };
+            let placement = background::mask_placement(
+                svg,
+                boxes.border_box,
+                Some(Size2D::new(
+                    Au::from_px(image.width as i32),
+                    Au::from_px(image.height as i32),
+                )),
+                boxes.border,
+                boxes.border_padding,
+                index,
+            );
+            let image_source = |tile: Rect<Au>, extend_mode: raqote::ExtendMode| {
+                let tile = au_rect_to_f32_rect(tile);
+                raqote::Source::Image(
+                    raqote::Image {
+                        width: image.width as i32,
+                        height: image.height as i32,
+                        data: &pixels,
+                    },
+                    extend_mode,
+                    raqote::FilterMode::Bilinear,
+                    Transform2D::create_translation(-tile.origin.x, -tile.origin.y).post_scale(
+                        image.width as f32 / tile.size.width,
+                        image.height as f32 / tile.size.height,
+                    ),
+                )
+            };
+
+            // Without spacing, the tiles can be painted at once.
+            if placement.tile_spacing == Size2D::zero() {
+                if placement.tile_size.width > Au(0) && placement.tile_size.height > Au(0) {
+                    if let Some(clip) = placement.bounds.intersection(&placement.clip_rect) {
+                        let tile = Rect::new(placement.bounds.origin, placement.tile_size);
+                        fill_rect(
+                            draw_target,
+                            clip,
+                            &image_source(tile, raqote::ExtendMode::Repeat),
+                        );
+                    }
+                }
+            } else {
+                for_each_tile(&placement, |tile, clip| {
+                    fill_rect(
+                        draw_target,
+                        clip,
+                        &image_source(tile, raqote::ExtendMode::Pad),
+                    );
+                });
+            }
+            true
+        },
+        // TODO: Implement paint worklets, `-moz-image-rect` and `-moz-element` in masks.
+        Image::PaintWorklet(_) | Image::Rect(_) | Image::Element(_) => true,
+    }
+}
+
+/// Calls `f` with each tile of a layer that is visible, and the part of it that is visible.
+fn for_each_tile<F: FnMut(Rect<Au>, Rect<Au>)>(placement: &BackgroundPlacement, mut f: F) {
+    if placement.tile_size.width <= Au(0) || placement.tile_size.height <= Au(0) {
+        return;
+    }
+    let step = placement.tile_size + placement.tile_spacing;
+    let mut y = placement.bounds.origin.y;
+    while y < placement.bounds.max_y() {
+        let mut x = placement.bounds.origin.x;
+        while x < placement.bounds.max_x() {
+            let tile = Rect::new(Point2D::new(x, y), placement.tile_size);
+            if let Some(clip) = tile.intersection(&placement.clip_rect) {
+                f(tile, clip);
+            }
+            x += step.width;
+        }
+        y += step.height;
+    }
+}
+
+fn fill_rect(draw_target: &mut raqote::DrawTarget, rect: Rect<Au>, source: &raqote::Source) {
+    let rect = au_rect_to_f32_rect(rect);
+    draw_target.fill_rect(
+        rect.origin.x,
+        rect.origin.y,
+        rect.size.width,
+        rect.size.height,
+        source,
+        &raqote::DrawOptions::new(),
+    );
+}
+
+/// Returns a raqote source for a gradient painted in the given tile.
+fn gradient_source(
+    fragment: &Fragment,
+    gradient: &Gradient,
+    tile: Rect<Au>,
+) -> Option<raqote::Source<'static>> {
+    let tile_to_user_space =
+        Transform2D::create_translation(tile.origin.x.to_f32_px(), tile.origin.y.to_f32_px());
+    match gradient.kind {
+        GradientKind::Linear(direction) => {
+            let (gradient, stops) = gradient::linear(
+                &fragment.style,
+                tile.size,
+                &gradient.items[..],
+                direction,
+                gradient.repeating,
+            );
+            let source = raqote::Source::new_linear_gradient(
+                raqote_gradient(&stops, 0., 1.),
+                Point2D::new(gradient.start_point.x, gradient.start_point.y),
+                Point2D::new(gradient.end_point.x, gradient.end_point.y),
+                raqote_spread(gradient.extend_mode),
+            );
+            with_gradient_space(source, tile_to_user_space)
+        },
+        GradientKind::Radial(shape, center) => {
+            let (gradient, stops) = gradient::radial(
+                &fragment.style,
+                tile.size,
+                &gradient.items[..],
+                shape,
+                center,
+                gradient.repeating,
+            );
+            if gradient.radius.width <= 0. || gradient.radius.height <= 0. {
+                return None;
+            }
+            // Elliptical gradients are circular ones scaled vertically.
+            let source = raqote::Source::new_radial_gradient(
+                raqote_gradient(&stops, gradient.start_offset, gradient.end_offset),
+                Point2D::origin(),
+                gradient.radius.width,
+                raqote_spread(gradient.extend_mode),
+            );
+            let gradient_to_tile =
+                Transform2D::create_scale(1., gradient.radius.height / gradient.radius.width)
+                    .post_translate(Vector2D::new(gradient.center.x, gradient.center.y));
+            with_gradient_space(source, gradient_to_tile.post_transform(&tile_to_user_space))
+        },
+    }
+}
+
+/// Converts WebRender gradient stops, whose offsets are normalized to the range from
+/// `start_offset` to `end_offset` of the gradient line, to raqote ones.
+fn raqote_gradient(stops: &[GradientStop], start_offset: f32, end_offset: f32) -> raqote::Gradient {
+    let to_u8 = |component: f32| (component.max(0.).min(1.) * 255.).round() as u8;
+    raqote::Gradient {
+        stops: stops
+            .iter()
+            .map(|stop| raqote::GradientStop {
+                position: start_offset + stop.offset * (end_offset - start_offset),
+                color: raqote::Color::new(
+                    to_u8(stop.color.a),
+                    to_u8(stop.color.r),
+                    to_u8(stop.color.g),
+                    to_u8(stop.color.b),
+                ),
+            })
+            .collect(),
+    }
+}
+
+fn raqote_spread(extend_mode: ExtendMode) -> raqote::Spread {
+    match extend_mode {
+        ExtendMode::Clamp => raqote::Spread::Pad,
+        ExtendMode::Repeat => raqote::Spread::Repeat,
+    }
+}
+
+/// Places a gradient source built in gradient space into user space.
+fn with_gradient_space(
+    source: raqote::Source<'static>,
+    gradient_to_user_space: Transform2D<f32>,
+) -> Option<raqote::Source<'static>> {
+    let user_space_to_gradient_space = gradient_to_user_space.inverse()?;
+    Some(match source {
+        raqote::Source::LinearGradient(gradient, spread, transform) => {
+            raqote::Source::LinearGradient(
+                gradient,
+                spread,
+                transform.pre_transform(&user_space_to_gradient_space),
+            )
+        },
+        raqote::Source::RadialGradient(gradient, spread, transform) => {
+            raqote::Source::RadialGradient(
+                gradient,
+                spread,
+                transform.pre_transform(&user_space_to_gradient_space),
+            )
+        },
+        source => source,
+    })
+}
+
+/// Returns the pixels of a decoded image as premultiplied ARGB, or `None` for formats that are
+/// not supported.
+fn image_pixels(image: &NetImage) -> Option<Vec<u32>> {
+    match image.format {
+        PixelFormat::BGRA8 => Some(
+            image
+                .bytes
+                .chunks_exact(4)
+                .map(|bgra| {
+                    u32::from_le_bytes([
+                        pixels::multiply_u8_color(bgra[0], bgra[3]),
+                        pixels::multiply_u8_color(bgra[1], bgra[3]),
+                        pixels::multiply_u8_color(bgra[2], bgra[3]),
+                        bgra[3],
+                    ])
+                })
+                .collect(),
+        ),
+        PixelFormat::RGB8 => Some(
+            image
+                .bytes
+                .chunks_exact(3)
+                .map(|rgb| u32::from_le_bytes([rgb[2], rgb[1], rgb[0], 255]))
+                .collect(),
+        ),
+        PixelFormat::K8 | PixelFormat::KA8 | PixelFormat::RGBA8 => None,
+    }
+}
+
+/// Turns a premultiplied ARGB pixel into one whose alpha is its luminance.
+///
+/// https://drafts.fxtf.org/css-masking/#MaskValues
+fn luminance_to_alpha(pixel: u32) -> u32 {
+    let [b, g, r, _] = pixel.to_le_bytes();
+    let luminance = (0.2125 * r as f32 + 0.7154 * g as f32 + 0.0721 * b as f32)
+        .round()
+        .min(255.) as u8;
+    u32::from_le_bytes([luminance, luminance, luminance, luminance])
+}
+
+fn opaque_source() -> raqote::Source<'static> {
+    raqote::Source::Solid(raqote::SolidSource {
+        r: 255,
+        g: 255,
+        b: 255,
+        a: 255,
+    })
+}
+
+fn resolve_position(position: &Position, reference_box: Rect<Au>) -> Point2D<Au> {
+    reference_box.origin +
+        Vector2D::new(
+            position.horizontal.to_used_value(reference_box.size.width),
+            position.vertical.to_used_value(reference_box.size.height),
+        )
+}
+
+/// Returns the distances from a point to the closest and farthest horizontal sides of a box,
+/// and to its closest and farthest vertical sides.
+fn side_distances(point: Point2D<Au>, rect: Rect<Au>) -> ((Au, Au), (Au, Au)) {
+    let x = (
+        (point.x - rect.min_x()).abs(),
+        (rect.max_x() - point.x).abs(),
+    );
+    let y = (
+        (point.y - rect.min_y()).abs(),
+        (rect.max_y() - point.y).abs(),
+    );
+    ((x.0.min(x.1), x.0.max(x.1)), (y.0.min(y.1), y.0.max(y.1)))
+}
+
+fn rounded_rect_region(rect: Rect<Au>, radii: BorderRadius) -> ClippingRegion {
+    let mut region = ClippingRegion::from_rect(rect.to_layout());
+    if !radii.is_zero() {
+        region.intersect_with_rounded_rect(rect.to_layout(), radii);
+    }
+    region
+}
+
+fn ellipse_region(center: Point2D<Au>, radii: Size2D<Au>) -> ClippingRegion {
+    if radii.width <= Au(0) || radii.height <= Au(0) {
+        return ClippingRegion::empty();
+    }
+    let rect = Rect::new(
+        center - Vector2D::new(radii.width, radii.height),
+        Size2D::new(radii.width * 2, radii.height * 2),
+    );
+    let radius = radii.to_layout();
+    rounded_rect_region(
+        rect,
+        BorderRadius {
+            top_left: radius,
+            top_right: radius,
+            bottom_right: radius,
+            bottom_left: radius,
+        },
+    )
+}
+
+/// Returns the clip region of an image mask over `bounds`, or an empty one if the mask could
+/// not be rasterized.
+fn image_mask_region(image_key: Option<ImageKey>, bounds: Rect<Au>) -> ClippingRegion {
+    match image_key {
+        Some(image_key) => ClippingRegion {
+            main: bounds.to_layout(),
+            complex: Vec::new(),
+            image_mask: Some(ImageMask {
+                image: image_key,
+                rect: bounds.to_layout(),
+                repeat: false,
+            }),
+        },
+        None => ClippingRegion::empty(),
+    }
+}
+
+/// Returns the smallest rectangle of whole CSS pixels that contains `rect`.
+fn rect_to_au(rect: &Rect<f32>) -> Rect<Au> {
+    let origin = Point2D::new(rect.origin.x.floor(), rect.origin.y.floor());
+    let size = Size2D::new(
+        (rect.max_x() - origin.x).ceil(),
+        (rect.max_y() - origin.y).ceil(),
+    );
+    Rect::new(
+        Point2D::new(Au::from_f32_px(origin.x), Au::from_f32_px(origin.y)),
+        Size2D::new(Au::from_f32_px(size.width), Au::from_f32_px(size.height)),
+    )
+}
+
+fn device_pixel_ratio(layout_context: &LayoutContext) -> f32 {
+    layout_context.style_context.device_pixel_ratio().get()
+}
+
+/// The WebRender images that clip paths and masks have been rasterized into.
+pub struct MaskImageCache {
+    webrender_api: RenderApi,
+    images: FnvHashMap<(OpaqueNode, MaskKind), MaskImage>,
+}
+
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+enum MaskKind {
+    ClipPath,
+    Mask,
+}
+
+struct MaskImage {
+    key: ImageKey,
+    /// What the image was rasterized from.
+    source: MaskSource,
+    /// Whether all the mask images were loaded when the image was rasterized.
+    complete: bool,
+    /// Whether the image was used by the display list being built.
+    used: bool,
+}
+
+/// What an image mask is rasterized from: the masking properties of a fragment, and its boxes
+/// relative to the bounds of the mask.
+struct MaskSource {
+    svg: ServoArc<style_structs::SVG>,
+    clip_path: Option<Arc<SvgClipPath>>,
+    boxes: ReferenceBoxes,
+    size: Size2D<Au>,
+    scale: f32,
+}
+
+impl MaskSource {
+    fn new(
+        fragment: &Fragment,
+        clip_path: Option<&Arc<SvgClipPath>>,
+        boxes: &ReferenceBoxes,
+        bounds: Rect<Au>,
+    ) -> MaskSource {
+        MaskSource {
+            svg: fragment.style.clone_svg(),
+            clip_path: clip_path.cloned(),
+            boxes: boxes.translate(-bounds.origin.to_vector()),
+            size: bounds.size,
+            scale: 0.,
+        }
+    }
+}
+
+impl PartialEq for MaskSource {
+    fn eq(&self, other: &MaskSource) -> bool {
+        let same_clip_path = match (&self.clip_path, &other.clip_path) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        };
+        (ServoArc::ptr_eq(&self.svg, &other.svg) || self.svg == other.svg) &&
+            same_clip_path &&
+            self.boxes == other.boxes &&
+            self.size == other.size &&
+            self.scale == other.scale
+    }
+}
+
+impl MaskImageCache {
+    pub fn new(webrender_api: RenderApi) -> MaskImageCache {
+        MaskImageCache {
+            webrender_api,
+            images: FnvHashMap::default(),
+        }
+    }
+
+    /// Returns the image mask of the given kind for `node`, rasterizing it with `rasterize` if
+    /// its source changed or some of its images were still loading.
+    ///
+    /// `rasterize` is given the size of the draw target and a transform from CSS pixels to its
+    /// pixels, and returns whether the mask is complete. Only the alpha channel is kept.
+    fn image_key<F>(
+        &mut self,
+        node: OpaqueNode,
+        kind: MaskKind,
+        mut source: MaskSource,
+        bounds: Rect<Au>,
+        scale: f32,
+        rasterize: F,
+    ) -> Option<ImageKey>
+    where
+        F: FnOnce(&mut raqote::DrawTarget, DeviceIntSize, &Transform2D<f32>) -> bool,
+    {
+        source.scale = scale;
+        if let Some(image) = self.images.get_mut(&(node, kind)) {
+            if image.complete && image.source == source {
+                image.used = true;
+                return Some(image.key);
+            }
+        }
+
+        let bounds = au_rect_to_f32_rect(bounds);
+        let largest_side = bounds.size.width.max(bounds.size.height) * scale;
+        let scale = if largest_side > MAX_MASK_SIZE as f32 {
+            scale * MAX_MASK_SIZE as f32 / largest_side
+        } else {
+            scale
+        };
+        let device_size = DeviceIntSize::new(
+            ((bounds.size.width * scale).ceil() as i32).min(MAX_MASK_SIZE),
+            ((bounds.size.height * scale).ceil() as i32).min(MAX_MASK_SIZE),
+        );
+        if device_size.width <= 0 || device_size.height <= 0 {
+            return None;
+        }
+
+        let mut draw_target = raqote::DrawTarget::new(device_size.width, device_size.height);
+        let transform = Transform2D::create_translation(-bounds.origin.x, -bounds.origin.y)
+            .post_scale(scale, scale);
+        let complete = rasterize(&mut draw_target, device_size, &transform);
+        let alpha: Vec<u8> = draw_target
+            .get_data()
+            .iter()
+            .map(|pixel| (pixel >> 24) as u8)
+            .collect();
+
+        let descriptor = ImageDescriptor {
+            size: device_size,
+            stride: None,
+            format: ImageFormat::R8,
+            offset: 0,
+            is_opaque: false,
+            allow_mipmaps: false,
+        };
+        let data = ImageData::Raw(Arc::new(alpha));
+
+        let mut txn = Transaction::new();
+        let key = match self.images.get(&(node, kind)) {
+            Some(image) => {
+                txn.update_image(image.key, descriptor, data, &DirtyRect::All);
+                image.key
+            },
+            None => {
+                let key = self.webrender_api.generate_image_key();
+                txn.add_image(key, descriptor, data, None);
+                key
+            },
+        };
+        self.webrender_api.update_resources(txn.resource_updates);
+
+        self.images.insert(
+            (node, kind),
+            MaskImage {
+                key,
+                source,
+                complete,
+                used: true,
+            },
+        );
+        Some(key)
+    }
+
+    /// Deletes the images that the last display list did not use.
+    pub fn evict_unused_images(&mut self) {
+        let mut txn = Transaction::new();
+        self.images.retain(|_, image| {
+            if !image.used {
+                txn.delete_image(image.key);
+            }
+            let used = image.used;
+            image.used = false;
+            used
+        });
+        if !txn.resource_updates.is_empty() {
+            self.webrender_api.update_resources(txn.resource_updates);
+        }
+    }
+}
@@ -8,6 +8,8 @@ pub use self::builder::IndexableText;
 pub use self::builder::StackingContextCollectionFlags;
 pub use self::builder::StackingContextCollectionState;
 pub use self::conversions::ToLayout;
+pub use self::masking::MaskImageCache;
+pub use self::scroll_snap::snap_positions_for_testing;
 
 mod background;
 mod border;
@@ -15,4 +17,6 @@ mod builder;
 mod conversions;
 mod gradient;
 pub mod items;
+mod masking;
+mod scroll_snap;
 mod webrender_helpers;
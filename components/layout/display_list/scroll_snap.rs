@@ -0,0 +1,235 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Snap positions of scroll containers, which the compositor snaps scrolls to.
+//!
+//! Snap positions are the scroll positions that align the snap area of a descendant with the
+//! `scroll-snap-align` property to the snapport of its nearest scroll container.
+//!
+//! See https://drafts.csswg.org/css-scroll-snap-1/
+
+use crate::display_list::items::ClipScrollNodeIndex;
+use crate::display_list::ToLayout;
+use app_units::Au;
+use euclid::default::{Rect, SideOffsets2D, Vector2D};
+use script_traits::{OverscrollBehavior, ScrollAxisBehavior};
+use script_traits::{ScrollContainerInfo, ScrollSnapStrictness};
+use style::logical_geometry::WritingMode;
+use style::properties::ComputedValues;
+use style::values::computed::box_::OverscrollBehavior as StyleOverscrollBehavior;
+use style::values::computed::box_::ScrollSnapStrictness as StyleScrollSnapStrictness;
+use style::values::computed::box_::{ScrollSnapAlignKeyword, ScrollSnapAxis};
+use webrender_api::ExternalScrollId;
+
+/// A scroll container that input events can scroll, with the snap areas inside it.
+pub struct ScrollSnapContainer {
+    /// The index of the clip scroll node that scrolls the contents of the container.
+    pub node_index: ClipScrollNodeIndex,
+    info: ScrollContainerInfo,
+    snapport: Rect<Au>,
+    max_scroll_position: Vector2D<Au>,
+    writing_mode: WritingMode,
+    snap_areas: Vec<ScrollSnapArea>,
+}
+
+/// The snap area of a box and how it aligns with the snapport along each physical axis.
+struct ScrollSnapArea {
+    rect: Rect<Au>,
+    align_x: ScrollSnapAlignKeyword,
+    align_y: ScrollSnapAlignKeyword,
+}
+
+impl ScrollSnapContainer {
+    /// Creates a scroll container with the given style, scrollport and largest scroll
+    /// position, which doesn't have any snap areas yet.
+    pub fn new(
+        node_index: ClipScrollNodeIndex,
+        scroll_id: ExternalScrollId,
+        parent_scroll_id: Option<ExternalScrollId>,
+        style: &ComputedValues,
+        scrollport: Rect<Au>,
+        max_scroll_position: Vector2D<Au>,
+    ) -> ScrollSnapContainer {
+        let box_style = style.get_box();
+        let writing_mode = style.writing_mode;
+        let snap_type = box_style.scroll_snap_type;
+        let strictness = match snap_type.strictness {
+            StyleScrollSnapStrictness::None => None,
+            StyleScrollSnapStrictness::Mandatory => Some(ScrollSnapStrictness::Mandatory),
+            StyleScrollSnapStrictness::Proximity => Some(ScrollSnapStrictness::Proximity),
+        };
+        let (snaps_x, snaps_y) = match snap_type.axis {
+            ScrollSnapAxis::X => (true, false),
+            ScrollSnapAxis::Y => (false, true),
+            ScrollSnapAxis::Both => (true, true),
+            ScrollSnapAxis::Block => (writing_mode.is_vertical(), !writing_mode.is_vertical()),
+            ScrollSnapAxis::Inline => (!writing_mode.is_vertical(), writing_mode.is_vertical()),
+        };
+
+        // https://drafts.csswg.org/css-scroll-snap-1/#scroll-padding
+        let padding = style.get_padding();
+        let width = scrollport.size.width;
+        let height = scrollport.size.height;
+        let scroll_padding = SideOffsets2D::new(
+            padding.scroll_padding_top.to_used_value(height),
+            padding.scroll_padding_right.to_used_value(width),
+            padding.scroll_padding_bottom.to_used_value(height),
+            padding.scroll_padding_left.to_used_value(width),
+        );
+        let scroll_padding = SideOffsets2D::new(
+            scroll_padding.top.unwrap_or(Au(0)),
+            scroll_padding.right.unwrap_or(Au(0)),
+            scroll_padding.bottom.unwrap_or(Au(0)),
+            scroll_padding.left.unwrap_or(Au(0)),
+        );
+
+        let axis_behavior =
+            |snaps: bool, overscroll_behavior: StyleOverscrollBehavior| ScrollAxisBehavior {
+                snap_strictness: if snaps { strictness } else { None },
+                snap_positions: vec![],
+                overscroll_behavior: match overscroll_behavior {
+                    StyleOverscrollBehavior::Auto => OverscrollBehavior::Auto,
+                    StyleOverscrollBehavior::Contain => OverscrollBehavior::Contain,
+                    StyleOverscrollBehavior::None => OverscrollBehavior::None,
+                },
+            };
+
+        ScrollSnapContainer {
+            node_index,
+            info: ScrollContainerInfo {
+                scroll_id,
+                parent_scroll_id,
+                scrollport: scrollport.to_layout(),
+                max_scroll_position: max_scroll_position.to_layout(),
+                x: axis_behavior(snaps_x, box_style.overscroll_behavior_x),
+                y: axis_behavior(snaps_y, box_style.overscroll_behavior_y),
+            },
+            snapport: scrollport.inner_rect(scroll_padding),
+            max_scroll_position,
+            writing_mode,
+            snap_areas: vec![],
+        }
+    }
+
+    /// Returns the ID of the scroll root of this scroll container.
+    pub fn scroll_id(&self) -> ExternalScrollId {
+        self.info.scroll_id
+    }
+
+    /// Adds the snap area of a box with the given style and border box, if it has one.
+    ///
+    /// <https://drafts.csswg.org/css-scroll-snap-1/#scroll-snap-area>
+    pub fn add_snap_area(&mut self, style: &ComputedValues, border_box: Rect<Au>) {
+        let align = style.get_box().scroll_snap_align;
+        let (align_x, align_y) = if self.writing_mode.is_vertical() {
+            (align.block, align.inline)
+        } else {
+            (align.inline, align.block)
+        };
+        if align_x == ScrollSnapAlignKeyword::None && align_y == ScrollSnapAlignKeyword::None {
+            return;
+        }
+
+        let margin = style.get_margin();
+        let scroll_margin = SideOffsets2D::new(
+            Au::from(margin.scroll_margin_top),
+            Au::from(margin.scroll_margin_right),
+            Au::from(margin.scroll_margin_bottom),
+            Au::from(margin.scroll_margin_left),
+        );
+        self.snap_areas.push(ScrollSnapArea {
+            rect: border_box.outer_rect(scroll_margin),
+            align_x,
+            align_y,
+        });
+    }
+
+    /// Computes the snap positions of the container and returns what the compositor needs to
+    /// know about it.
+    pub fn finish(mut self) -> ScrollContainerInfo {
+        // Start and end are flipped along axes that run from right to left or bottom to top.
+        let writing_mode = self.writing_mode;
+        let (reversed_x, reversed_y) = if writing_mode.is_vertical() {
+            (!writing_mode.is_vertical_lr(), !writing_mode.is_inline_tb())
+        } else {
+            (!writing_mode.is_bidi_ltr(), false)
+        };
+
+        if self.info.x.snap_strictness.is_some() {
+            self.info.x.snap_positions = snap_positions(
+                self.snap_areas
+                    .iter()
+                    .map(|area| (area.align_x, area.rect.min_x(), area.rect.max_x())),
+                (self.snapport.min_x(), self.snapport.max_x()),
+                self.max_scroll_position.x,
+                reversed_x,
+            );
+        }
+        if self.info.y.snap_strictness.is_some() {
+            self.info.y.snap_positions = snap_positions(
+                self.snap_areas
+                    .iter()
+                    .map(|area| (area.align_y, area.rect.min_y(), area.rect.max_y())),
+                (self.snapport.min_y(), self.snapport.max_y()),
+                self.max_scroll_position.y,
+                reversed_y,
+            );
+        }
+        self.info
+    }
+}
+
+/// Returns the scroll positions along one axis that align the given snap areas, as their
+/// alignment and their start and end edges, with the snapport, in ascending order.
+///
+/// <https://drafts.csswg.org/css-scroll-snap-1/#scroll-snap-align>
+fn snap_positions<I>(
+    snap_areas: I,
+    snapport: (Au, Au),
+    max_scroll_position: Au,
+    reversed: bool,
+) -> Vec<f32>
+where
+    I: Iterator<Item = (ScrollSnapAlignKeyword, Au, Au)>,
+{
+    // The edges are in the coordinates of the contents when nothing is scrolled, so the
+    // difference between the edges of a snap area and the snapport is the scroll position
+    // that aligns them.
+    let (snapport_start, snapport_end) = snapport;
+    let mut positions: Vec<f32> = snap_areas
+        .filter_map(|(align, start, end)| {
+            let position =
+                match (align, reversed) {
+                    (ScrollSnapAlignKeyword::None, _) => return None,
+                    (ScrollSnapAlignKeyword::Start, false) |
+                    (ScrollSnapAlignKeyword::End, true) => start - snapport_start,
+                    (ScrollSnapAlignKeyword::End, false) |
+                    (ScrollSnapAlignKeyword::Start, true) => end - snapport_end,
+                    (ScrollSnapAlignKeyword::Center, _) => {
+                        (start + end - snapport_start - snapport_end) / 2
+                    },
+                };
+            Some(position.max(Au(0)).min(max_scroll_position).to_f32_px())
+        })
+        .collect();
+    positions.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    positions.dedup();
+    positions
+}
+
+/// A function to be able to test the snap positions of snap areas, without needing to build
+/// a display list.
+pub fn snap_positions_for_testing(
+    snap_areas: &[(ScrollSnapAlignKeyword, Au, Au)],
+    snapport: (Au, Au),
+    max_scroll_position: Au,
+    reversed: bool,
+) -> Vec<f32> {
+    snap_positions(
+        snap_areas.iter().cloned(),
+        snapport,
+        max_scroll_position,
+        reversed,
+    )
+}
@@ -269,7 +269,7 @@ impl DisplayItem {
                             },
                             item_rect,
                             node.clip.complex.clone(),
-                            None,
+                            node.clip.image_mask,
                         );
 
                         state.spatial_ids[item.node_index.to_index()] = Some(parent_spatial_id);
@@ -285,7 +285,7 @@ impl DisplayItem {
                             node.content_rect,
                             node.clip.main,
                             node.clip.complex.clone(),
-                            None,
+                            node.clip.image_mask,
                             scroll_sensitivity,
                             webrender_api::units::LayoutVector2D::zero(),
                         );
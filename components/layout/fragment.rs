@@ -16,6 +16,7 @@ use crate::inline::{InlineMetrics, LineMetrics};
 use crate::layout_debug;
 use crate::model::style_length;
 use crate::model::{self, IntrinsicISizes, IntrinsicISizesContribution, MaybeAuto, SizeConstraint};
+use crate::svg::{SvgClipPath, SvgScene};
 use crate::text;
 use crate::text::TextRunScanner;
 use crate::wrapper::ThreadSafeLayoutNodeHelpers;
@@ -61,7 +62,9 @@ use style::selector_parser::RestyleDamage;
 use style::servo::restyle_damage::ServoRestyleDamage;
 use style::str::char_is_whitespace;
 use style::values::computed::counters::ContentItem;
+use style::values::computed::image::ImageLayer;
 use style::values::computed::{LengthPercentage, LengthPercentageOrAuto, Size, VerticalAlign};
+use style::values::generics::basic_shape::ShapeSource;
 use style::values::generics::box_::{Perspective, VerticalAlignKeyword};
 use style::values::generics::transform;
 use style::Zero;
@@ -157,6 +160,13 @@ pub struct Fragment {
     /// `established_reference_frame` assigned, it will use the `clipping_and_scrolling` of the
     /// parent block.
     pub established_reference_frame: Option<ClipScrollNodeIndex>,
+
+    /// The index of the ClipScrollNode that applies the `clip-path` and `mask` of this fragment,
+    /// when it is an inline-level fragment that establishes a stacking context.
+    pub established_masking_clip: Option<ClipScrollNodeIndex>,
+
+    /// The `<clipPath>` element that the `clip-path` of this fragment refers to, if any.
+    pub clip_path: Option<Arc<SvgClipPath>>,
 }
 
 impl Serialize for Fragment {
@@ -384,13 +394,16 @@ impl MediaFragmentInfo {
 pub struct SvgFragmentInfo {
     pub dom_width: Au,
     pub dom_height: Au,
+    /// The shapes and text inside the `<svg>` element.
+    pub scene: Arc<SvgScene>,
 }
 
 impl SvgFragmentInfo {
-    pub fn new(data: SVGSVGData) -> SvgFragmentInfo {
+    pub fn new(data: SVGSVGData, scene: SvgScene) -> SvgFragmentInfo {
         SvgFragmentInfo {
             dom_width: Au::from_px(data.width as i32),
             dom_height: Au::from_px(data.height as i32),
+            scene: Arc::new(scene),
         }
     }
 }
@@ -672,6 +685,7 @@ impl Fragment {
         let shared_context = ctx.shared_context();
         let style = node.style(shared_context);
         let writing_mode = style.writing_mode;
+        let clip_path = SvgClipPath::new(node, &style, shared_context).map(Arc::new);
 
         let mut restyle_damage = node.restyle_damage();
         restyle_damage.remove(ServoRestyleDamage::RECONSTRUCT_FLOW);
@@ -691,6 +705,8 @@ impl Fragment {
             debug_id: DebugId::new(),
             stacking_context_id: StackingContextId::root(),
             established_reference_frame: None,
+            established_masking_clip: None,
+            clip_path: clip_path,
         }
     }
 
@@ -722,6 +738,8 @@ impl Fragment {
             debug_id: DebugId::new(),
             stacking_context_id: StackingContextId::root(),
             established_reference_frame: None,
+            established_masking_clip: None,
+            clip_path: None,
         }
     }
 
@@ -749,6 +767,8 @@ impl Fragment {
             debug_id: DebugId::new(),
             stacking_context_id: StackingContextId::root(),
             established_reference_frame: None,
+            established_masking_clip: None,
+            clip_path: self.clip_path.clone(),
         }
     }
 
@@ -776,6 +796,8 @@ impl Fragment {
             debug_id: self.debug_id.clone(),
             stacking_context_id: StackingContextId::root(),
             established_reference_frame: None,
+            established_masking_clip: None,
+            clip_path: self.clip_path.clone(),
         }
     }
 
@@ -2711,6 +2733,19 @@ impl Fragment {
             self.style().get_box().perspective != Perspective::None
     }
 
+    /// Returns true if this fragment has a clip-path or mask-image property set.
+    pub fn has_clip_path_or_mask(&self) -> bool {
+        let svg = self.style().get_svg();
+        if let ShapeSource::None = svg.clip_path {
+            svg.mask_image.0.iter().any(|layer| match *layer {
+                ImageLayer::None => false,
+                ImageLayer::Image(_) => true,
+            })
+        } else {
+            true
+        }
+    }
+
     /// Returns true if this fragment establishes a new stacking context and false otherwise.
     pub fn establishes_stacking_context(&self) -> bool {
         // Text fragments shouldn't create stacking contexts.
@@ -2733,6 +2768,10 @@ impl Fragment {
             return true;
         }
 
+        if self.has_clip_path_or_mask() {
+            return true;
+        }
+
         if self.style().get_box().transform_style == TransformStyle::Preserve3d ||
             self.style().overrides_transform_style()
         {
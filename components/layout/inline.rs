@@ -1882,6 +1882,7 @@ impl Flow for InlineFlow {
             // We clear this here, but it might be set again if we create a stacking context for
             // this fragment.
             fragment.established_reference_frame = None;
+            fragment.established_masking_clip = None;
 
             if !fragment.collect_stacking_contexts_for_blocklike_fragment(state) {
                 if !fragment.establishes_stacking_context() {
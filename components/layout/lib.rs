@@ -44,6 +44,7 @@ pub mod parallel;
 mod persistent_list;
 pub mod query;
 pub mod sequential;
+pub mod svg;
 mod table;
 mod table_caption;
 mod table_cell;
@@ -16,6 +16,7 @@ use crate::sequential;
 use crate::wrapper::LayoutNodeLayoutData;
 use app_units::Au;
 use euclid::default::{Point2D, Rect, Size2D, Vector2D};
+use fxhash::FxHashMap;
 use ipc_channel::ipc::IpcSender;
 use msg::constellation_msg::PipelineId;
 use script_layout_interface::rpc::TextIndexResponse;
@@ -23,23 +24,33 @@ use script_layout_interface::rpc::{ContentBoxResponse, ContentBoxesResponse, Lay
 use script_layout_interface::rpc::{NodeGeometryResponse, NodeScrollIdResponse};
 use script_layout_interface::rpc::{OffsetParentResponse, ResolvedStyleResponse, StyleResponse};
 use script_layout_interface::wrapper_traits::{
-    LayoutNode, ThreadSafeLayoutElement, ThreadSafeLayoutNode,
+    LayoutNode, PseudoElementType, ThreadSafeLayoutElement, ThreadSafeLayoutNode,
 };
 use script_layout_interface::StyleData;
 use script_layout_interface::{LayoutElementType, LayoutNodeType};
 use script_traits::LayoutMsg as ConstellationMsg;
 use script_traits::UntrustedNodeAddress;
+use servo_arc::Arc as ServoArc;
+use servo_url::ServoUrl;
 use std::cmp::{max, min};
 use std::ops::Deref;
 use std::sync::{Arc, Mutex};
+use style::computed_values::container_type::T as ContainerType;
 use style::computed_values::display::T as Display;
 use style::computed_values::position::T as Position;
 use style::computed_values::visibility::T as Visibility;
-use style::context::{StyleContext, ThreadLocalStyleContext};
+use style::context::{QuirksMode, StyleContext, ThreadLocalStyleContext};
 use style::dom::TElement;
 use style::logical_geometry::{BlockFlowDirection, InlineBaseDirection, WritingMode};
-use style::properties::{style_structs, LonghandId, PropertyDeclarationId, PropertyId};
+use style::properties::style_structs::{self, Font};
+use style::properties::{
+    parse_one_declaration_into, ComputedValues, Importance, LonghandId, PropertyDeclaration,
+    PropertyDeclarationBlock, PropertyDeclarationId, PropertyId, ShorthandId,
+    SourcePropertyDeclaration,
+};
 use style::selector_parser::PseudoElement;
+use style::shared_lock::SharedRwLock;
+use style_traits::ParsingMode;
 use style_traits::ToCss;
 use webrender_api::ExternalScrollId;
 
@@ -73,6 +84,9 @@ pub struct LayoutThreadData {
     /// A queued response for the resolved style property of an element.
     pub resolved_style_response: String,
 
+    /// A queued response for the resolved font style for canvas.
+    pub resolved_font_style_response: Option<ServoArc<Font>>,
+
     /// A queued response for the offset parent/rect of a node.
     pub offset_parent_response: OffsetParentResponse,
 
@@ -170,6 +184,13 @@ impl LayoutRPC for LayoutRPCImpl {
         ResolvedStyleResponse(rw_data.resolved_style_response.clone())
     }
 
+    /// Retrieves the resolved font style for canvas.
+    fn resolved_font_style(&self) -> Option<ServoArc<Font>> {
+        let &LayoutRPCImpl(ref rw_data) = self;
+        let rw_data = rw_data.lock().unwrap();
+        rw_data.resolved_font_style_response.clone()
+    }
+
     fn offset_parent(&self) -> OffsetParentResponse {
         let &LayoutRPCImpl(ref rw_data) = self;
         let rw_data = rw_data.lock().unwrap();
@@ -386,6 +407,45 @@ pub fn process_content_boxes_request(
     iterator.rects
 }
 
+struct ContainerSizeFragmentBorderBoxIterator {
+    sizes: FxHashMap<OpaqueNode, Size2D<Au>>,
+}
+
+impl FragmentBorderBoxIterator for ContainerSizeFragmentBorderBoxIterator {
+    fn process(&mut self, fragment: &Fragment, _: i32, _: &Rect<Au>) {
+        let mut size = fragment.border_box.size;
+        size.inline = max(
+            Au(0),
+            size.inline - fragment.border_padding.inline_start_end(),
+        );
+        size.block = max(
+            Au(0),
+            size.block - fragment.border_padding.block_start_end(),
+        );
+        // A container split across several fragments is sized by its first one.
+        self.sizes
+            .entry(fragment.node)
+            .or_insert(size.to_physical(fragment.style.writing_mode));
+    }
+
+    fn should_process(&mut self, fragment: &Fragment) -> bool {
+        fragment.pseudo == PseudoElementType::Normal &&
+            fragment.style.get_box().container_type != ContainerType::Normal
+    }
+}
+
+/// Returns the size of the content box of every query container in the flow
+/// tree, keyed by the node that generated it.
+pub fn process_container_sizes_request(
+    layout_root: &mut dyn Flow,
+) -> FxHashMap<OpaqueNode, Size2D<Au>> {
+    let mut iterator = ContainerSizeFragmentBorderBoxIterator {
+        sizes: FxHashMap::default(),
+    };
+    sequential::iterate_through_flow_tree_fragment_border_boxes(layout_root, &mut iterator);
+    iterator.sizes
+}
+
 struct FragmentLocatingFragmentIterator {
     node_address: OpaqueNode,
     client_rect: Rect<i32>,
@@ -786,6 +846,106 @@ where
     style.computed_value_to_string(PropertyDeclarationId::Longhand(longhand_id))
 }
 
+/// Parses a `font` value, as given to the 2D canvas `font` attribute, and resolves it against
+/// the computed font of the given element. Disconnected elements resolve the value against the
+/// default `10px sans-serif` font.
+/// <https://html.spec.whatwg.org/multipage/#dom-context-2d-font>
+pub fn process_resolved_font_style_request<'a, N>(
+    context: &LayoutContext,
+    node: N,
+    value: &str,
+    property: &PropertyId,
+    url_data: ServoUrl,
+    shared_lock: &SharedRwLock,
+) -> Option<ServoArc<Font>>
+where
+    N: LayoutNode,
+{
+    use style::stylist::RuleInclusion;
+    use style::traversal::resolve_style;
+
+    // 1. Parse the given font property value.
+    let quirks_mode = context.style_context.quirks_mode();
+    let declarations = parse_font_declarations(value, property, &url_data, quirks_mode)?;
+
+    // 2. Get the resolved style of the element the value is relative to.
+    let element = node.as_element().unwrap();
+    let parent_style = if node.is_connected() {
+        if element.get_data().is_some() {
+            node.to_threadsafe().as_element().unwrap().resolved_style()
+        } else {
+            let mut tlc = ThreadLocalStyleContext::new(&context.style_context);
+            let mut context = StyleContext {
+                shared: &context.style_context,
+                thread_local: &mut tlc,
+            };
+            let styles = resolve_style(&mut context, element, RuleInclusion::All, None);
+            styles.primary().clone()
+        }
+    } else {
+        let default_declarations = parse_font_declarations(
+            "10px sans-serif",
+            &PropertyId::Shorthand(ShorthandId::Font),
+            &url_data,
+            quirks_mode,
+        )?;
+        context
+            .style_context
+            .stylist
+            .compute_for_declarations::<N::ConcreteElement>(
+                &context.style_context.guards,
+                ComputedValues::initial_values(),
+                ServoArc::new(shared_lock.wrap(default_declarations)),
+            )
+    };
+
+    // 3. Resolve the parsed value against the resolved style of the element.
+    let style = context
+        .style_context
+        .stylist
+        .compute_for_declarations::<N::ConcreteElement>(
+            &context.style_context.guards,
+            &*parent_style,
+            ServoArc::new(shared_lock.wrap(declarations)),
+        );
+    Some(style.clone_font())
+}
+
+fn parse_font_declarations(
+    value: &str,
+    property: &PropertyId,
+    url_data: &ServoUrl,
+    quirks_mode: QuirksMode,
+) -> Option<PropertyDeclarationBlock> {
+    let mut declarations = SourcePropertyDeclaration::new();
+    parse_one_declaration_into(
+        &mut declarations,
+        property.clone(),
+        value,
+        url_data,
+        None,
+        ParsingMode::DEFAULT,
+        quirks_mode,
+    )
+    .ok()?;
+    let mut block = PropertyDeclarationBlock::new();
+    block.extend(declarations.drain(), Importance::Normal);
+    // CSS-wide keywords and variable references are not valid font values here.
+    let is_valid = block
+        .declarations()
+        .iter()
+        .all(|declaration| match *declaration {
+            PropertyDeclaration::CSSWideKeyword(..) | PropertyDeclaration::WithVariables(..) => {
+                false
+            },
+            _ => true,
+        });
+    if !is_valid {
+        return None;
+    }
+    Some(block)
+}
+
 /// The primary resolution logic, which assumes that the element is styled.
 fn process_resolved_style_request_internal<'a, N>(
     requested_node: N,
@@ -915,7 +1075,7 @@ where
                 .result
                 .map(|r| r.to_css_string())
                 .unwrap_or(String::new())
-        }
+        },
 
         LonghandId::Bottom | LonghandId::Top | LonghandId::Right | LonghandId::Left
             if applies && positioned && style.get_box().display != Display::None =>
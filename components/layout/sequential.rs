@@ -76,7 +76,11 @@ pub fn build_display_list_for_subtree<'a>(
     background_color: webrender_api::ColorF,
     client_size: Size2D<Au>,
 ) -> DisplayListBuildState<'a> {
-    let mut state = StackingContextCollectionState::new(layout_context.id);
+    let mut state = StackingContextCollectionState::new(layout_context);
+    state.add_root_scroll_container(
+        &flow_root.as_block().fragment.style,
+        flow_root.base().overflow.scroll.size,
+    );
     flow_root.collect_stacking_contexts(&mut state);
 
     let mut state = DisplayListBuildState::new(layout_context, state);
@@ -0,0 +1,1858 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Inline SVG content.
+//!
+//! The subtree of an `<svg>` element is turned into an `SvgScene` during flow construction.
+//! Since the scene is only rebuilt along with its flow, the properties it reads have
+//! `rebuild_and_reflow` restyle damage.
+//! When the display list is built, the shapes of the scene are rasterized into a WebRender
+//! image, while its text is placed with regular text display items.
+
+use crate::context::{with_thread_local_font_context, LayoutContext};
+use crate::display_list::items::OpaqueNode;
+use crate::ServoArc;
+use app_units::Au;
+use cssparser::{Parser, ParserInput};
+use euclid::default::{Point2D, Rect, Size2D, Transform2D, Vector2D};
+use fnv::FnvHashMap;
+use gfx::font::{ShapingFlags, ShapingOptions};
+use gfx::text::glyph::ByteIndex;
+use gfx::text::text_run::TextRun;
+use html5ever::LocalName;
+use range::Range;
+use script_layout_interface::wrapper_traits::{
+    PseudoElementType, ThreadSafeLayoutElement, ThreadSafeLayoutNode,
+};
+use script_layout_interface::{LayoutElementType, LayoutNodeType};
+use servo_atoms::Atom;
+use servo_url::ServoUrl;
+use std::f32::consts::{FRAC_PI_2, PI};
+use std::sync::Arc;
+use style::computed_values::display::T as Display;
+use style::computed_values::stroke_linecap::T as StrokeLinecap;
+use style::computed_values::stroke_linejoin::T as StrokeLinejoin;
+use style::computed_values::visibility::T as Visibility;
+use style::context::SharedStyleContext;
+use style::parser::{Parse, ParserContext};
+use style::properties::ComputedValues;
+use style::stylesheets::{CssRuleType, Origin};
+use style::values::computed::font::FontSize;
+use style::values::computed::{Context, ToComputedValue};
+use style::values::computed::{Length, LengthPercentage, NonNegativeLengthPercentageOrAuto};
+use style::values::computed::{SVGPaint, SVGPaintKind, Size};
+use style::values::generics::basic_shape::{FillRule, ShapeSource};
+use style::values::generics::length::{GenericSize, LengthPercentageOrAuto};
+use style::values::generics::svg::{SVGLength, SVGOpacity, SVGPaintFallback, SVGStrokeDashArray};
+use style::values::specified;
+use style::values::specified::svg_path::{PathCommand, SVGPathData};
+use style::values::RGBA;
+use style_traits::{ParseError, ParsingMode};
+use unicode_bidi as bidi;
+use unicode_script::Script;
+use webrender_api::units::DeviceIntSize;
+use webrender_api::Transaction;
+use webrender_api::{DirtyRect, ImageData, ImageDescriptor, ImageFormat, ImageKey, RenderApi};
+
+/// The largest width or height, in device pixels, that inline SVG content is rasterized at.
+const MAX_RASTER_SIZE: i32 = 8192;
+
+/// The deepest chain of `<use>` references and gradient `href`s that is followed.
+const MAX_REFERENCE_DEPTH: u32 = 16;
+
+/// Approximates a quarter of a circle with a cubic Bézier curve.
+const KAPPA: f32 = 0.552_284_8;
+
+/// The shapes and text of an `<svg>` element, in its user coordinate system.
+pub struct SvgScene {
+    view_box: Option<Rect<f32>>,
+    preserve_aspect_ratio: PreserveAspectRatio,
+    shapes: Vec<SvgShape>,
+    texts: Vec<SvgText>,
+}
+
+/// A text run of an `<svg>` element, ready to be placed in the display list.
+pub struct SvgTextRun {
+    pub run: Arc<TextRun>,
+    /// The start of the baseline, in CSS pixels relative to the content box of the `<svg>`.
+    pub baseline_origin: Point2D<f32>,
+    pub color: RGBA,
+}
+
+impl SvgScene {
+    /// Builds the scene of the `<svg>` element `node`, whose viewport has the given size in
+    /// CSS pixels when it has no `viewBox`.
+    pub fn new<N: ThreadSafeLayoutNode>(
+        node: &N,
+        viewport_size: Size2D<f32>,
+        context: &SharedStyleContext,
+    ) -> SvgScene {
+        let element = node.as_element().unwrap();
+        let view_box = element
+            .get_attr(&ns!(), &local_name!("viewBox"))
+            .and_then(parse_view_box);
+        let preserve_aspect_ratio = element
+            .get_attr(&ns!(), &local_name!("preserveAspectRatio"))
+            .map_or(PreserveAspectRatio::default(), PreserveAspectRatio::parse);
+
+        let mut builder = SceneBuilder {
+            context,
+            viewport: view_box.map_or(viewport_size, |view_box| view_box.size),
+            elements_by_id: FnvHashMap::default(),
+            gradients: FnvHashMap::default(),
+            shapes: vec![],
+            texts: vec![],
+        };
+        builder.collect_ids(*node);
+        builder.render_children(*node, Transform2D::identity(), 1., 0);
+
+        SvgScene {
+            view_box,
+            preserve_aspect_ratio,
+            shapes: builder.shapes,
+            texts: builder.texts,
+        }
+    }
+
+    /// Returns whether the scene has any shapes to rasterize.
+    pub fn has_shapes(&self) -> bool {
+        !self.shapes.is_empty()
+    }
+
+    /// Returns the transform from the user coordinate system of the scene to CSS pixels
+    /// relative to a viewport of the given size.
+    ///
+    /// https://svgwg.org/svg2-draft/coords.html#ComputingAViewportsTransform
+    fn viewport_transform(&self, viewport_size: Size2D<f32>) -> Transform2D<f32> {
+        let view_box = match self.view_box {
+            Some(view_box) => view_box,
+            None => return Transform2D::identity(),
+        };
+        let scale_x = viewport_size.width / view_box.size.width;
+        let scale_y = viewport_size.height / view_box.size.height;
+        let to_origin = Transform2D::create_translation(-view_box.origin.x, -view_box.origin.y);
+
+        let (align_x, align_y) = match self.preserve_aspect_ratio.align {
+            Some(align) => align,
+            None => return to_origin.post_scale(scale_x, scale_y),
+        };
+        let scale = if self.preserve_aspect_ratio.slice {
+            scale_x.max(scale_y)
+        } else {
+            scale_x.min(scale_y)
+        };
+        let offset = Vector2D::new(
+            align_x.offset(viewport_size.width - view_box.size.width * scale),
+            align_y.offset(viewport_size.height - view_box.size.height * scale),
+        );
+        to_origin.post_scale(scale, scale).post_translate(offset)
+    }
+
+    /// Rasterizes the shapes of the scene for a viewport of the given size in CSS pixels, at
+    /// the given device pixel ratio. The pixels are premultiplied BGRA.
+    fn rasterize(
+        &self,
+        device_size: DeviceIntSize,
+        viewport_size: Size2D<f32>,
+        scale: f32,
+    ) -> Vec<u8> {
+        let mut draw_target = raqote::DrawTarget::new(device_size.width, device_size.height);
+        let viewport_transform = self
+            .viewport_transform(viewport_size)
+            .post_scale(scale, scale);
+
+        for shape in &self.shapes {
+            draw_target.set_transform(&shape.transform.post_transform(&viewport_transform));
+            if let Some(ref fill) = shape.fill {
+                if let Some(source) = fill.server.to_raqote(&shape.bounds) {
+                    let mut options = raqote::DrawOptions::new();
+                    options.alpha = fill.opacity * shape.opacity;
+                    draw_target.fill(&shape.path, &source, &options);
+                }
+            }
+            if let Some((ref stroke, ref stroke_style)) = shape.stroke {
+                if let Some(source) = stroke.server.to_raqote(&shape.bounds) {
+                    let mut options = raqote::DrawOptions::new();
+                    options.alpha = stroke.opacity * shape.opacity;
+                    draw_target.stroke(&shape.path, &source, &stroke_style.to_raqote(), &options);
+                }
+            }
+        }
+
+        let mut pixels = Vec::with_capacity(draw_target.get_data().len() * 4);
+        for pixel in draw_target.get_data() {
+            pixels.extend_from_slice(&pixel.to_le_bytes());
+        }
+        pixels
+    }
+
+    /// Shapes the text of the scene for a viewport of the given size in CSS pixels.
+    ///
+    /// Glyphs are laid out horizontally from the transformed position of each text chunk, at
+    /// a font size scaled by the transform.
+    pub fn text_runs(
+        &self,
+        layout_context: &LayoutContext,
+        viewport_size: Size2D<f32>,
+    ) -> Vec<SvgTextRun> {
+        let viewport_transform = self.viewport_transform(viewport_size);
+        let mut runs = vec![];
+        for text in &self.texts {
+            let transform = text.transform.post_transform(&viewport_transform);
+            let scale = transform.determinant().abs().sqrt();
+            if scale == 0. {
+                continue;
+            }
+
+            let mut pen = Point2D::zero();
+            for chunk in &text.chunks {
+                pen.x = chunk.position.x.unwrap_or(pen.x) + chunk.position.dx.unwrap_or(0.);
+                pen.y = chunk.position.y.unwrap_or(pen.y) + chunk.position.dy.unwrap_or(0.);
+                if chunk.text.is_empty() {
+                    continue;
+                }
+
+                let run = match shape_text(layout_context, &chunk.style, scale, chunk.text.clone())
+                {
+                    Some(run) => run,
+                    None => continue,
+                };
+                let range = Range::new(ByteIndex(0), ByteIndex(run.text.len() as isize));
+                let advance = run.advance_for_range(&range).to_f32_px() / scale;
+                if let Some(color) = chunk.color {
+                    runs.push(SvgTextRun {
+                        run: Arc::new(run),
+                        baseline_origin: transform.transform_point(pen),
+                        color,
+                    });
+                }
+                pen.x += advance;
+            }
+        }
+        runs
+    }
+}
+
+/// Shapes `text` with the font of `style`, scaled by `scale`.
+fn shape_text(
+    layout_context: &LayoutContext,
+    style: &ComputedValues,
+    scale: f32,
+    text: String,
+) -> Option<TextRun> {
+    let mut font_style = (*style.get_font()).clone();
+    let font_size = font_style.font_size.size().to_f32_px() * scale;
+    font_style.font_size = FontSize {
+        size: Au::from_f32_px(font_size).into(),
+        keyword_info: None,
+    };
+
+    with_thread_local_font_context(layout_context, |font_context| {
+        let font_group = font_context.font_group(ServoArc::new(font_style));
+        let font = font_group.borrow_mut().first(font_context)?;
+        let options = ShapingOptions {
+            letter_spacing: None,
+            word_spacing: style.get_inherited_text().word_spacing.to_hash_key(),
+            script: Script::Common,
+            flags: ShapingFlags::empty(),
+        };
+        let (run, _) = TextRun::new(
+            &mut *font.borrow_mut(),
+            text,
+            &options,
+            bidi::Level::ltr(),
+            &mut None,
+        );
+        Some(run)
+    })
+}
+
+/// The shapes of a `<clipPath>` element that a `clip-path: url(#id)` refers to.
+///
+/// https://drafts.fxtf.org/css-masking/#ClipPathElement
+pub struct SvgClipPath {
+    /// Whether the shapes are in `objectBoundingBox` units rather than `userSpaceOnUse` ones.
+    bounding_box_units: bool,
+    shapes: Vec<SvgClipShape>,
+}
+
+/// A shape of a `<clipPath>`, in the user coordinate system of its element.
+struct SvgClipShape {
+    path: raqote::Path,
+    bounds: Rect<f32>,
+    /// The transform from the user coordinate system of the shape to that of the `<clipPath>`.
+    transform: Transform2D<f32>,
+}
+
+impl SvgClipPath {
+    /// Builds the clip path that the `clip-path` of `node` refers to, or returns `None` if it
+    /// doesn't refer to a `<clipPath>` element of the document.
+    pub fn new<N: ThreadSafeLayoutNode>(
+        node: &N,
+        style: &ComputedValues,
+        context: &SharedStyleContext,
+    ) -> Option<SvgClipPath> {
+        let id = match style.get_svg().clip_path {
+            ShapeSource::ImageOrUrl(ref url) => url.url()?.as_url().fragment()?,
+            _ => return None,
+        };
+        let clip_path = node.element_with_id(&Atom::from(id))?;
+        if clip_path.type_id() !=
+            Some(LayoutNodeType::Element(
+                LayoutElementType::SVGClipPathElement,
+            ))
+        {
+            return None;
+        }
+        let element = clip_path.as_element().unwrap();
+        let bounding_box_units =
+            element.get_attr(&ns!(), &local_name!("clipPathUnits")) == Some("objectBoundingBox");
+        let clip_path_transform = element
+            .get_attr(&ns!(), &local_name!("transform"))
+            .map_or(Transform2D::identity(), |transform| {
+                parse_transform(transform, context)
+            });
+
+        // Percentages are relative to the bounding box, or to the viewport of the document.
+        let viewport = if bounding_box_units {
+            Size2D::new(1., 1.)
+        } else {
+            let viewport_size = context.viewport_size();
+            Size2D::new(
+                viewport_size.width.to_f32_px(),
+                viewport_size.height.to_f32_px(),
+            )
+        };
+        let builder: SceneBuilder<N> = SceneBuilder {
+            context,
+            viewport,
+            elements_by_id: FnvHashMap::default(),
+            gradients: FnvHashMap::default(),
+            shapes: vec![],
+            texts: vec![],
+        };
+
+        let mut shapes = vec![];
+        for child in svg_child_elements(clip_path) {
+            let style = match style_if_styled(child, context) {
+                Some(style) => style,
+                None => continue,
+            };
+            if style.get_box().clone_display() == Display::None ||
+                style.get_inherited_box().visibility != Visibility::Visible
+            {
+                continue;
+            }
+            let element_type = match child.type_id() {
+                Some(LayoutNodeType::Element(element_type)) => element_type,
+                _ => continue,
+            };
+            let child_element = child.as_element().unwrap();
+            let (mut path, bounds) =
+                match builder.shape_geometry(element_type, &child_element, &style) {
+                    Some(geometry) => geometry,
+                    None => continue,
+                };
+            path.winding = match style.get_inherited_svg().clip_rule {
+                FillRule::Nonzero => raqote::Winding::NonZero,
+                FillRule::Evenodd => raqote::Winding::EvenOdd,
+            };
+            let transform = child_element
+                .get_attr(&ns!(), &local_name!("transform"))
+                .map_or(Transform2D::identity(), |transform| {
+                    parse_transform(transform, context)
+                })
+                .post_transform(&clip_path_transform);
+            shapes.push(SvgClipShape {
+                path,
+                bounds,
+                transform,
+            });
+        }
+
+        Some(SvgClipPath {
+            bounding_box_units,
+            shapes,
+        })
+    }
+
+    /// Returns the bounds of the clip path in CSS pixels for the given reference box, or `None`
+    /// if it has no shapes, and so clips everything out.
+    pub fn bounds(&self, reference_box: &Rect<f32>) -> Option<Rect<f32>> {
+        let reference_transform = self.reference_transform(reference_box);
+        self.shapes
+            .iter()
+            .map(|shape| {
+                shape
+                    .transform
+                    .post_transform(&reference_transform)
+                    .transform_rect(&shape.bounds)
+            })
+            .fold(None, |bounds: Option<Rect<f32>>, rect| {
+                Some(bounds.map_or(rect, |bounds| bounds.union(&rect)))
+            })
+    }
+
+    /// Fills the shapes of the clip path for the given reference box, with `transform` mapping
+    /// CSS pixels to the pixels of `draw_target`.
+    pub fn fill(
+        &self,
+        draw_target: &mut raqote::DrawTarget,
+        reference_box: &Rect<f32>,
+        transform: &Transform2D<f32>,
+    ) {
+        let reference_transform = self
+            .reference_transform(reference_box)
+            .post_transform(transform);
+        let source = raqote::Source::Solid(raqote::SolidSource {
+            r: 255,
+            g: 255,
+            b: 255,
+            a: 255,
+        });
+        for shape in &self.shapes {
+            draw_target.set_transform(&shape.transform.post_transform(&reference_transform));
+            draw_target.fill(&shape.path, &source, &raqote::DrawOptions::new());
+        }
+    }
+
+    /// Returns the transform from the user coordinate system of the `<clipPath>` to CSS pixels.
+    fn reference_transform(&self, reference_box: &Rect<f32>) -> Transform2D<f32> {
+        let transform = if self.bounding_box_units {
+            Transform2D::create_scale(reference_box.size.width, reference_box.size.height)
+        } else {
+            Transform2D::identity()
+        };
+        transform.post_translate(reference_box.origin.to_vector())
+    }
+}
+
+/// The WebRender images that inline SVG content has been rasterized into.
+pub struct SvgImageCache {
+    webrender_api: RenderApi,
+    images: FnvHashMap<OpaqueNode, SvgImage>,
+}
+
+struct SvgImage {
+    key: ImageKey,
+    /// The scene the image was rasterized from.
+    scene: Arc<SvgScene>,
+    viewport_size: Size2D<f32>,
+    scale: f32,
+    /// Whether the image was used by the display list being built.
+    used: bool,
+}
+
+impl SvgImageCache {
+    pub fn new(webrender_api: RenderApi) -> SvgImageCache {
+        SvgImageCache {
+            webrender_api,
+            images: FnvHashMap::default(),
+        }
+    }
+
+    /// Returns the image for the scene of the `<svg>` element `node`, rasterizing it if the
+    /// scene or the size it is displayed at changed.
+    pub fn image_key(
+        &mut self,
+        node: OpaqueNode,
+        scene: &Arc<SvgScene>,
+        viewport_size: Size2D<f32>,
+        scale: f32,
+    ) -> Option<ImageKey> {
+        if let Some(image) = self.images.get_mut(&node) {
+            if Arc::ptr_eq(&image.scene, scene) &&
+                image.viewport_size == viewport_size &&
+                image.scale == scale
+            {
+                image.used = true;
+                return Some(image.key);
+            }
+        }
+
+        let device_size = DeviceIntSize::new(
+            (viewport_size.width * scale).ceil() as i32,
+            (viewport_size.height * scale).ceil() as i32,
+        );
+        if device_size.width <= 0 ||
+            device_size.height <= 0 ||
+            device_size.width > MAX_RASTER_SIZE ||
+            device_size.height > MAX_RASTER_SIZE
+        {
+            return None;
+        }
+
+        let descriptor = ImageDescriptor {
+            size: device_size,
+            stride: None,
+            format: ImageFormat::BGRA8,
+            offset: 0,
+            is_opaque: false,
+            allow_mipmaps: false,
+        };
+        let data = ImageData::Raw(Arc::new(scene.rasterize(device_size, viewport_size, scale)));
+
+        let mut txn = Transaction::new();
+        let key = match self.images.get(&node) {
+            Some(image) => {
+                txn.update_image(image.key, descriptor, data, &DirtyRect::All);
+                image.key
+            },
+            None => {
+                let key = self.webrender_api.generate_image_key();
+                txn.add_image(key, descriptor, data, None);
+                key
+            },
+        };
+        self.webrender_api.update_resources(txn.resource_updates);
+
+        self.images.insert(
+            node,
+            SvgImage {
+                key,
+                scene: scene.clone(),
+                viewport_size,
+                scale,
+                used: true,
+            },
+        );
+        Some(key)
+    }
+
+    /// Deletes the images that the last display list did not use.
+    pub fn evict_unused_images(&mut self) {
+        let mut txn = Transaction::new();
+        self.images.retain(|_, image| {
+            if !image.used {
+                txn.delete_image(image.key);
+            }
+            let used = image.used;
+            image.used = false;
+            used
+        });
+        if !txn.resource_updates.is_empty() {
+            self.webrender_api.update_resources(txn.resource_updates);
+        }
+    }
+}
+
+/// How the `viewBox` of an `<svg>` is fitted into its viewport.
+///
+/// https://svgwg.org/svg2-draft/coords.html#PreserveAspectRatioAttribute
+#[derive(Clone, Copy)]
+struct PreserveAspectRatio {
+    /// The alignment on each axis, or `None` for non-uniform scaling.
+    align: Option<(Align, Align)>,
+    /// Whether the `viewBox` covers the viewport rather than fitting in it.
+    slice: bool,
+}
+
+#[derive(Clone, Copy)]
+enum Align {
+    Min,
+    Mid,
+    Max,
+}
+
+impl Align {
+    fn parse(value: &str) -> Option<Align> {
+        match value {
+            "Min" => Some(Align::Min),
+            "Mid" => Some(Align::Mid),
+            "Max" => Some(Align::Max),
+            _ => None,
+        }
+    }
+
+    /// The offset of the content in the given free space.
+    fn offset(self, free_space: f32) -> f32 {
+        match self {
+            Align::Min => 0.,
+            Align::Mid => free_space / 2.,
+            Align::Max => free_space,
+        }
+    }
+}
+
+impl Default for PreserveAspectRatio {
+    fn default() -> Self {
+        PreserveAspectRatio {
+            align: Some((Align::Mid, Align::Mid)),
+            slice: false,
+        }
+    }
+}
+
+impl PreserveAspectRatio {
+    fn parse(value: &str) -> Self {
+        let mut tokens = value.split_whitespace().peekable();
+        if tokens.peek() == Some(&"defer") {
+            tokens.next();
+        }
+        let align = match tokens.next() {
+            Some("none") => None,
+            Some(token) if token.len() == 8 && token.starts_with('x') && &token[4..5] == "Y" => {
+                match (Align::parse(&token[1..4]), Align::parse(&token[5..8])) {
+                    (Some(x), Some(y)) => Some((x, y)),
+                    _ => return Self::default(),
+                }
+            },
+            _ => return Self::default(),
+        };
+        let slice = match tokens.next() {
+            None | Some("meet") => false,
+            Some("slice") => true,
+            Some(_) => return Self::default(),
+        };
+        PreserveAspectRatio { align, slice }
+    }
+}
+
+/// A filled and stroked shape, in the user coordinate system of its element.
+struct SvgShape {
+    path: raqote::Path,
+    /// The bounding box of the shape, for paint servers in `objectBoundingBox` units.
+    bounds: Rect<f32>,
+    /// The transform from the user coordinate system of the shape to that of the scene.
+    transform: Transform2D<f32>,
+    fill: Option<Paint>,
+    stroke: Option<(Paint, StrokeStyle)>,
+    /// The group opacity of the shape and its ancestors.
+    opacity: f32,
+}
+
+/// A `<text>` element, made of chunks of text from its text nodes and `<tspan>`s.
+struct SvgText {
+    transform: Transform2D<f32>,
+    chunks: Vec<TextChunk>,
+}
+
+struct TextChunk {
+    position: TextPosition,
+    text: String,
+    style: ServoArc<ComputedValues>,
+    /// The fill color, or `None` if the text is not filled with a color.
+    color: Option<RGBA>,
+}
+
+/// The explicit position of a text chunk, from the `x`, `y`, `dx` and `dy` attributes.
+#[derive(Clone, Copy, Default)]
+struct TextPosition {
+    x: Option<f32>,
+    y: Option<f32>,
+    dx: Option<f32>,
+    dy: Option<f32>,
+}
+
+impl TextPosition {
+    fn or(self, other: TextPosition) -> TextPosition {
+        TextPosition {
+            x: self.x.or(other.x),
+            y: self.y.or(other.y),
+            dx: self.dx.or(other.dx),
+            dy: self.dy.or(other.dy),
+        }
+    }
+}
+
+struct Paint {
+    server: PaintServer,
+    opacity: f32,
+}
+
+enum PaintServer {
+    Color(RGBA),
+    Gradient(Arc<Gradient>),
+}
+
+impl PaintServer {
+    fn to_raqote(&self, bounds: &Rect<f32>) -> Option<raqote::Source<'static>> {
+        match *self {
+            PaintServer::Color(color) => Some(raqote::Source::Solid(raqote::SolidSource {
+                r: color.red,
+                g: color.green,
+                b: color.blue,
+                a: 255,
+            })),
+            PaintServer::Gradient(ref gradient) => gradient.to_raqote(bounds),
+        }
+    }
+}
+
+/// A `<linearGradient>` or `<radialGradient>`.
+///
+/// Coordinates are fractions of the bounding box of the painted shape, or user units when
+/// `bounding_box_units` is false.
+struct Gradient {
+    kind: GradientKind,
+    bounding_box_units: bool,
+    transform: Transform2D<f32>,
+    spread: SpreadMethod,
+    /// The offset and unpremultiplied color of each stop.
+    stops: Vec<(f32, RGBA)>,
+}
+
+enum GradientKind {
+    Linear {
+        start: Point2D<f32>,
+        end: Point2D<f32>,
+    },
+    Radial {
+        center: Point2D<f32>,
+        radius: f32,
+        focus: Point2D<f32>,
+    },
+}
+
+#[derive(Clone, Copy)]
+enum SpreadMethod {
+    Pad,
+    Reflect,
+    Repeat,
+}
+
+impl Gradient {
+    fn to_raqote(&self, bounds: &Rect<f32>) -> Option<raqote::Source<'static>> {
+        // A gradient with a single stop paints its color.
+        if self.stops.len() == 1 {
+            return PaintServer::Color(self.stops[0].1).to_raqote(bounds);
+        }
+
+        let mut gradient_space = self.transform;
+        if self.bounding_box_units {
+            gradient_space = gradient_space
+                .post_scale(bounds.size.width, bounds.size.height)
+                .post_translate(bounds.origin.to_vector());
+        }
+        let user_space_to_gradient_space = gradient_space.inverse()?;
+
+        let gradient = raqote::Gradient {
+            stops: self
+                .stops
+                .iter()
+                .map(|&(position, color)| raqote::GradientStop {
+                    position,
+                    color: raqote::Color::new(color.alpha, color.red, color.green, color.blue),
+                })
+                .collect(),
+        };
+        let spread = match self.spread {
+            SpreadMethod::Pad => raqote::Spread::Pad,
+            SpreadMethod::Reflect => raqote::Spread::Reflect,
+            SpreadMethod::Repeat => raqote::Spread::Repeat,
+        };
+
+        // The sources are built in gradient space, and their transforms map from user space.
+        let source = match self.kind {
+            GradientKind::Linear { start, end } => {
+                raqote::Source::new_linear_gradient(gradient, start, end, spread)
+            },
+            GradientKind::Radial {
+                center,
+                radius,
+                focus,
+            } if focus == center => {
+                raqote::Source::new_radial_gradient(gradient, center, radius, spread)
+            },
+            GradientKind::Radial {
+                center,
+                radius,
+                focus,
+            } => raqote::Source::new_two_circle_radial_gradient(
+                gradient, focus, 0., center, radius, spread,
+            ),
+        };
+        Some(match source {
+            raqote::Source::LinearGradient(gradient, spread, transform) => {
+                raqote::Source::LinearGradient(
+                    gradient,
+                    spread,
+                    transform.pre_transform(&user_space_to_gradient_space),
+                )
+            },
+            raqote::Source::RadialGradient(gradient, spread, transform) => {
+                raqote::Source::RadialGradient(
+                    gradient,
+                    spread,
+                    transform.pre_transform(&user_space_to_gradient_space),
+                )
+            },
+            raqote::Source::TwoCircleRadialGradient(
+                gradient,
+                spread,
+                center1,
+                radius1,
+                center2,
+                radius2,
+                transform,
+            ) => raqote::Source::TwoCircleRadialGradient(
+                gradient,
+                spread,
+                center1,
+                radius1,
+                center2,
+                radius2,
+                transform.pre_transform(&user_space_to_gradient_space),
+            ),
+            source => source,
+        })
+    }
+}
+
+struct StrokeStyle {
+    width: f32,
+    cap: StrokeLinecap,
+    join: StrokeLinejoin,
+    miter_limit: f32,
+    dash_array: Vec<f32>,
+    dash_offset: f32,
+}
+
+impl StrokeStyle {
+    fn to_raqote(&self) -> raqote::StrokeStyle {
+        let mut style = raqote::StrokeStyle::default();
+        style.width = self.width;
+        style.cap = match self.cap {
+            StrokeLinecap::Butt => raqote::LineCap::Butt,
+            StrokeLinecap::Round => raqote::LineCap::Round,
+            StrokeLinecap::Square => raqote::LineCap::Square,
+        };
+        style.join = match self.join {
+            StrokeLinejoin::Miter => raqote::LineJoin::Miter,
+            StrokeLinejoin::Round => raqote::LineJoin::Round,
+            StrokeLinejoin::Bevel => raqote::LineJoin::Bevel,
+        };
+        style.miter_limit = self.miter_limit;
+        style.dash_array = self.dash_array.clone();
+        style.dash_offset = self.dash_offset;
+        style
+    }
+}
+
+/// Walks the subtree of an `<svg>` element to build its scene.
+struct SceneBuilder<'a, 'b, N: ThreadSafeLayoutNode> {
+    context: &'a SharedStyleContext<'b>,
+    /// The size of the viewport in user units, that percentages are resolved against.
+    viewport: Size2D<f32>,
+    elements_by_id: FnvHashMap<String, N>,
+    /// The gradients that have been resolved, or `None` for invalid ones.
+    gradients: FnvHashMap<String, Option<Arc<Gradient>>>,
+    shapes: Vec<SvgShape>,
+    texts: Vec<SvgText>,
+}
+
+impl<'a, 'b, N: ThreadSafeLayoutNode> SceneBuilder<'a, 'b, N> {
+    /// Records the elements of the subtree by id, for `<use>` and paint server references.
+    ///
+    /// Descendants of elements with `display: none` are not styled, so they are skipped.
+    fn collect_ids(&mut self, node: N) {
+        for child in svg_child_elements(node) {
+            let element = child.as_element().unwrap();
+            if let Some(id) = element.get_attr(&ns!(), &local_name!("id")) {
+                self.elements_by_id.entry(id.to_owned()).or_insert(child);
+            }
+            if element.style(self.context).get_box().clone_display() != Display::None {
+                self.collect_ids(child);
+            }
+        }
+    }
+
+    fn render_children(&mut self, node: N, transform: Transform2D<f32>, opacity: f32, depth: u32) {
+        for child in svg_child_elements(node) {
+            self.render_element(child, transform, opacity, depth);
+        }
+    }
+
+    /// Adds the shapes and text of an element and its descendants to the scene.
+    ///
+    /// `transform` maps from the user coordinate system of the parent element to the one of
+    /// the scene.
+    fn render_element(&mut self, node: N, transform: Transform2D<f32>, opacity: f32, depth: u32) {
+        let element = node.as_element().unwrap();
+        let style = element.style(self.context);
+        if style.get_box().clone_display() == Display::None {
+            return;
+        }
+
+        let transform = element
+            .get_attr(&ns!(), &local_name!("transform"))
+            .map_or(Transform2D::identity(), |transform| {
+                parse_transform(transform, self.context)
+            })
+            .post_transform(&transform);
+        let opacity = opacity * style.get_effects().opacity;
+        let visible = style.get_inherited_box().visibility == Visibility::Visible;
+
+        match node.type_id() {
+            Some(LayoutNodeType::Element(LayoutElementType::SVGGElement)) |
+            Some(LayoutNodeType::Element(LayoutElementType::SVGSVGElement)) => {
+                self.render_children(node, transform, opacity, depth)
+            },
+            Some(LayoutNodeType::Element(LayoutElementType::SVGUseElement)) => {
+                if depth >= MAX_REFERENCE_DEPTH {
+                    return;
+                }
+                let target = match href(&element).and_then(|id| self.elements_by_id.get(id)) {
+                    Some(target) => *target,
+                    None => return,
+                };
+                let offset = Transform2D::create_translation(
+                    self.length_attr(&element, &local_name!("x"), Axis::X)
+                        .unwrap_or(0.),
+                    self.length_attr(&element, &local_name!("y"), Axis::Y)
+                        .unwrap_or(0.),
+                );
+                self.render_element(
+                    target,
+                    offset.post_transform(&transform),
+                    opacity,
+                    depth + 1,
+                )
+            },
+            Some(LayoutNodeType::Element(LayoutElementType::SVGTextElement)) => {
+                if visible {
+                    self.add_text(node, &style, transform)
+                }
+            },
+            Some(LayoutNodeType::Element(element_type)) => {
+                if !visible {
+                    return;
+                }
+                if let Some((path, bounds)) = self.shape_geometry(element_type, &element, &style) {
+                    self.add_shape(path, bounds, &style, transform, opacity, element_type);
+                }
+            },
+            _ => {},
+        }
+    }
+
+    /// Returns the path of a basic shape or `<path>` element in its user coordinate system,
+    /// or `None` for other elements.
+    fn shape_geometry(
+        &self,
+        element_type: LayoutElementType,
+        element: &N::ConcreteThreadSafeLayoutElement,
+        style: &ComputedValues,
+    ) -> Option<(raqote::Path, Rect<f32>)> {
+        let svg = style.get_svg();
+        let mut path = PathSink::new();
+        match element_type {
+            LayoutElementType::SVGRectElement => {
+                let x = self.resolve(&svg.x, Axis::X);
+                let y = self.resolve(&svg.y, Axis::Y);
+                let width = self.resolve_size(&style.get_position().width, Axis::X);
+                let height = self.resolve_size(&style.get_position().height, Axis::Y);
+                if width <= 0. || height <= 0. {
+                    return None;
+                }
+                let (rx, ry) = match (
+                    self.resolve_auto(&svg.rx, Axis::X),
+                    self.resolve_auto(&svg.ry, Axis::Y),
+                ) {
+                    (None, None) => (0., 0.),
+                    (Some(rx), None) => (rx, rx),
+                    (None, Some(ry)) => (ry, ry),
+                    (Some(rx), Some(ry)) => (rx, ry),
+                };
+                path.rounded_rect(
+                    Rect::new(Point2D::new(x, y), Size2D::new(width, height)),
+                    rx.min(width / 2.),
+                    ry.min(height / 2.),
+                );
+            },
+            LayoutElementType::SVGCircleElement => {
+                let center = Point2D::new(
+                    self.resolve(&svg.cx, Axis::X),
+                    self.resolve(&svg.cy, Axis::Y),
+                );
+                let radius = self.resolve(&svg.r.0, Axis::Other);
+                if radius <= 0. {
+                    return None;
+                }
+                path.ellipse(center, radius, radius);
+            },
+            LayoutElementType::SVGEllipseElement => {
+                let center = Point2D::new(
+                    self.resolve(&svg.cx, Axis::X),
+                    self.resolve(&svg.cy, Axis::Y),
+                );
+                let (rx, ry) = match (
+                    self.resolve_auto(&svg.rx, Axis::X),
+                    self.resolve_auto(&svg.ry, Axis::Y),
+                ) {
+                    (None, None) => return None,
+                    (Some(rx), None) => (rx, rx),
+                    (None, Some(ry)) => (ry, ry),
+                    (Some(rx), Some(ry)) => (rx, ry),
+                };
+                if rx <= 0. || ry <= 0. {
+                    return None;
+                }
+                path.ellipse(center, rx, ry);
+            },
+            LayoutElementType::SVGLineElement => {
+                let start = Point2D::new(
+                    self.length_attr(element, &local_name!("x1"), Axis::X)
+                        .unwrap_or(0.),
+                    self.length_attr(element, &local_name!("y1"), Axis::Y)
+                        .unwrap_or(0.),
+                );
+                let end = Point2D::new(
+                    self.length_attr(element, &local_name!("x2"), Axis::X)
+                        .unwrap_or(0.),
+                    self.length_attr(element, &local_name!("y2"), Axis::Y)
+                        .unwrap_or(0.),
+                );
+                path.move_to(start);
+                path.line_to(end);
+            },
+            LayoutElementType::SVGPolylineElement | LayoutElementType::SVGPolygonElement => {
+                let points = parse_number_list(element.get_attr(&ns!(), &local_name!("points"))?);
+                let mut points = points
+                    .chunks_exact(2)
+                    .map(|point| Point2D::new(point[0], point[1]));
+                path.move_to(points.next()?);
+                for point in points {
+                    path.line_to(point);
+                }
+                if element_type == LayoutElementType::SVGPolygonElement {
+                    path.close();
+                }
+            },
+            LayoutElementType::SVGPathElement => {
+                parse_path_data(element.get_attr(&ns!(), &local_name!("d"))?, &mut path);
+            },
+            _ => return None,
+        }
+        path.finish()
+    }
+
+    fn add_shape(
+        &mut self,
+        mut path: raqote::Path,
+        bounds: Rect<f32>,
+        style: &ComputedValues,
+        transform: Transform2D<f32>,
+        opacity: f32,
+        element_type: LayoutElementType,
+    ) {
+        let svg = style.get_inherited_svg();
+        path.winding = match svg.fill_rule {
+            FillRule::Nonzero => raqote::Winding::NonZero,
+            FillRule::Evenodd => raqote::Winding::EvenOdd,
+        };
+
+        // Lines have no interior.
+        let fill = if element_type == LayoutElementType::SVGLineElement {
+            None
+        } else {
+            self.paint(style, &svg.fill, &svg.fill_opacity)
+        };
+
+        let stroke = self
+            .paint(style, &svg.stroke, &svg.stroke_opacity)
+            .and_then(|paint| {
+                let width = match svg.stroke_width {
+                    SVGLength::LengthPercentage(ref width) => self.resolve(&width.0, Axis::Other),
+                    SVGLength::ContextValue => return None,
+                };
+                if width <= 0. {
+                    return None;
+                }
+                let mut dash_array = match svg.stroke_dasharray {
+                    SVGStrokeDashArray::Values(ref values) => values
+                        .iter()
+                        .map(|value| self.resolve(&value.0, Axis::Other))
+                        .collect(),
+                    SVGStrokeDashArray::ContextValue => vec![],
+                };
+                // An odd number of dashes is repeated, and dashes that add up to zero are ignored.
+                if dash_array.len() % 2 == 1 {
+                    let repeated = dash_array.clone();
+                    dash_array.extend(repeated);
+                }
+                if dash_array.iter().sum::<f32>() <= 0. {
+                    dash_array.clear();
+                }
+                let dash_offset = match svg.stroke_dashoffset {
+                    SVGLength::LengthPercentage(ref offset) => self.resolve(offset, Axis::Other),
+                    SVGLength::ContextValue => 0.,
+                };
+                Some((
+                    paint,
+                    StrokeStyle {
+                        width,
+                        cap: svg.stroke_linecap,
+                        join: svg.stroke_linejoin,
+                        miter_limit: svg.stroke_miterlimit.0,
+                        dash_array,
+                        dash_offset,
+                    },
+                ))
+            });
+
+        if fill.is_none() && stroke.is_none() {
+            return;
+        }
+        self.shapes.push(SvgShape {
+            path,
+            bounds,
+            transform,
+            fill,
+            stroke,
+            opacity,
+        });
+    }
+
+    fn add_text(&mut self, node: N, style: &ServoArc<ComputedValues>, transform: Transform2D<f32>) {
+        let mut chunks = vec![];
+        let mut last_was_space = true;
+        self.collect_text_chunks(
+            node,
+            style,
+            TextPosition::default(),
+            &mut chunks,
+            &mut last_was_space,
+        );
+        if let Some(last) = chunks.last_mut() {
+            let trimmed_len = last.text.trim_end().len();
+            last.text.truncate(trimmed_len);
+        }
+        self.texts.push(SvgText { transform, chunks });
+    }
+
+    /// Collects the text of a `<text>` or `<tspan>` element, collapsing white space.
+    ///
+    /// `position` is the position of the parent element that no text used yet.
+    fn collect_text_chunks(
+        &mut self,
+        node: N,
+        style: &ServoArc<ComputedValues>,
+        position: TextPosition,
+        chunks: &mut Vec<TextChunk>,
+        last_was_space: &mut bool,
+    ) {
+        let element = node.as_element().unwrap();
+        let mut position = TextPosition {
+            x: self.first_length_attr(&element, &local_name!("x"), Axis::X),
+            y: self.first_length_attr(&element, &local_name!("y"), Axis::Y),
+            dx: self.first_length_attr(&element, &local_name!("dx"), Axis::X),
+            dy: self.first_length_attr(&element, &local_name!("dy"), Axis::Y),
+        }
+        .or(position);
+
+        for child in node.children() {
+            if child.get_pseudo_element_type() != PseudoElementType::Normal {
+                continue;
+            }
+            match child.type_id() {
+                Some(LayoutNodeType::Text) => {
+                    let svg = style.get_inherited_svg();
+                    let color = self
+                        .paint(style, &svg.fill, &svg.fill_opacity)
+                        .and_then(|paint| match paint.server {
+                            PaintServer::Color(mut color) => {
+                                color.alpha = (color.alpha as f32 * paint.opacity).round() as u8;
+                                Some(color)
+                            },
+                            PaintServer::Gradient(..) => None,
+                        });
+                    chunks.push(TextChunk {
+                        position,
+                        text: collapse_white_space(&child.node_text_content(), last_was_space),
+                        style: style.clone(),
+                        color,
+                    });
+                    position = TextPosition::default();
+                },
+                Some(LayoutNodeType::Element(LayoutElementType::SVGTSpanElement)) => {
+                    let child_style = child.as_element().unwrap().style(self.context);
+                    if child_style.get_box().clone_display() == Display::None {
+                        continue;
+                    }
+                    self.collect_text_chunks(child, &child_style, position, chunks, last_was_space);
+                    position = TextPosition::default();
+                },
+                _ => {},
+            }
+        }
+    }
+
+    /// Resolves a `fill` or `stroke` value.
+    fn paint(
+        &mut self,
+        style: &ComputedValues,
+        paint: &SVGPaint,
+        opacity: &SVGOpacity<f32>,
+    ) -> Option<Paint> {
+        let opacity = match *opacity {
+            SVGOpacity::Opacity(opacity) => opacity,
+            _ => 1.,
+        };
+        let fallback = || match paint.fallback {
+            SVGPaintFallback::Color(color) => Some(PaintServer::Color(style.resolve_color(color))),
+            SVGPaintFallback::None | SVGPaintFallback::Unset => None,
+        };
+        let server = match paint.kind {
+            SVGPaintKind::None => None,
+            SVGPaintKind::Color(color) => Some(PaintServer::Color(style.resolve_color(color))),
+            SVGPaintKind::PaintServer(ref url) => {
+                let gradient = url
+                    .url()
+                    .and_then(|url| url.as_url().fragment())
+                    .and_then(|id| self.gradient(id, 0));
+                match gradient {
+                    Some(gradient) => Some(PaintServer::Gradient(gradient)),
+                    None => fallback(),
+                }
+            },
+            SVGPaintKind::ContextFill | SVGPaintKind::ContextStroke => fallback(),
+        }?;
+        Some(Paint { server, opacity })
+    }
+
+    /// Returns the gradient with the given id, if any.
+    fn gradient(&mut self, id: &str, depth: u32) -> Option<Arc<Gradient>> {
+        if let Some(gradient) = self.gradients.get(id) {
+            return gradient.clone();
+        }
+        // Mark the gradient as invalid while it is resolved, to break reference cycles.
+        self.gradients.insert(id.to_owned(), None);
+        let gradient = self.resolve_gradient(id, depth).map(Arc::new);
+        self.gradients.insert(id.to_owned(), gradient.clone());
+        gradient
+    }
+
+    fn resolve_gradient(&mut self, id: &str, depth: u32) -> Option<Gradient> {
+        let node = *self.elements_by_id.get(id)?;
+        let element = node.as_element().unwrap();
+        let linear = match node.type_id() {
+            Some(LayoutNodeType::Element(LayoutElementType::SVGLinearGradientElement)) => true,
+            Some(LayoutNodeType::Element(LayoutElementType::SVGRadialGradientElement)) => false,
+            _ => return None,
+        };
+
+        let bounding_box_units =
+            element.get_attr(&ns!(), &local_name!("gradientUnits")) != Some("userSpaceOnUse");
+        let coordinate = |name: &LocalName, axis: Axis, default: f32| {
+            element
+                .get_attr(&ns!(), name)
+                .and_then(|value| {
+                    if bounding_box_units {
+                        parse_length(value, 1., self.context)
+                    } else {
+                        parse_length(value, self.percentage_basis(axis), self.context)
+                    }
+                })
+                .unwrap_or(if bounding_box_units {
+                    default
+                } else {
+                    default * self.percentage_basis(axis)
+                })
+        };
+
+        let kind = if linear {
+            GradientKind::Linear {
+                start: Point2D::new(
+                    coordinate(&local_name!("x1"), Axis::X, 0.),
+                    coordinate(&local_name!("y1"), Axis::Y, 0.),
+                ),
+                end: Point2D::new(
+                    coordinate(&local_name!("x2"), Axis::X, 1.),
+                    coordinate(&local_name!("y2"), Axis::Y, 0.),
+                ),
+            }
+        } else {
+            let center = Point2D::new(
+                coordinate(&local_name!("cx"), Axis::X, 0.5),
+                coordinate(&local_name!("cy"), Axis::Y, 0.5),
+            );
+            let focus = Point2D::new(
+                element
+                    .get_attr(&ns!(), &local_name!("fx"))
+                    .map_or(center.x, |_| coordinate(&local_name!("fx"), Axis::X, 0.5)),
+                element
+                    .get_attr(&ns!(), &local_name!("fy"))
+                    .map_or(center.y, |_| coordinate(&local_name!("fy"), Axis::Y, 0.5)),
+            );
+            GradientKind::Radial {
+                center,
+                radius: coordinate(&local_name!("r"), Axis::Other, 0.5),
+                focus,
+            }
+        };
+        let transform = element
+            .get_attr(&ns!(), &local_name!("gradientTransform"))
+            .map_or(Transform2D::identity(), |transform| {
+                parse_transform(transform, self.context)
+            });
+        let spread = match element.get_attr(&ns!(), &local_name!("spreadMethod")) {
+            Some("reflect") => SpreadMethod::Reflect,
+            Some("repeat") => SpreadMethod::Repeat,
+            _ => SpreadMethod::Pad,
+        };
+
+        // A gradient without stops uses the ones of the gradient it references.
+        let mut stops = self.gradient_stops(node);
+        if stops.is_empty() && depth < MAX_REFERENCE_DEPTH {
+            if let Some(referenced) = href(&element).and_then(|href| self.gradient(href, depth + 1))
+            {
+                stops = referenced.stops.clone();
+            }
+        }
+        if stops.is_empty() {
+            return None;
+        }
+
+        Some(Gradient {
+            kind,
+            bounding_box_units,
+            transform,
+            spread,
+            stops,
+        })
+    }
+
+    fn gradient_stops(&self, node: N) -> Vec<(f32, RGBA)> {
+        let mut stops: Vec<(f32, RGBA)> = vec![];
+        for child in svg_child_elements(node) {
+            if child.type_id() != Some(LayoutNodeType::Element(LayoutElementType::SVGStopElement)) {
+                continue;
+            }
+            let element = child.as_element().unwrap();
+            let style = element.style(self.context);
+            let offset = element
+                .get_attr(&ns!(), &local_name!("offset"))
+                .and_then(|offset| parse_length(offset, 1., self.context))
+                .unwrap_or(0.)
+                .max(0.)
+                .min(1.);
+            // Stop offsets never decrease.
+            let offset = stops
+                .last()
+                .map_or(offset, |&(previous, _)| offset.max(previous));
+            let svg = style.get_svg();
+            let mut color = style.resolve_color(svg.stop_color);
+            color.alpha = (color.alpha as f32 * svg.stop_opacity).round() as u8;
+            stops.push((offset, color));
+        }
+        stops
+    }
+
+    fn percentage_basis(&self, axis: Axis) -> f32 {
+        match axis {
+            Axis::X => self.viewport.width,
+            Axis::Y => self.viewport.height,
+            Axis::Other => self.viewport.to_vector().length() / 2f32.sqrt(),
+        }
+    }
+
+    fn resolve(&self, length: &LengthPercentage, axis: Axis) -> f32 {
+        length
+            .percentage_relative_to(Length::new(self.percentage_basis(axis)))
+            .px()
+    }
+
+    fn resolve_size(&self, size: &Size, axis: Axis) -> f32 {
+        match *size {
+            GenericSize::LengthPercentage(ref length) => self.resolve(&length.0, axis),
+            _ => 0.,
+        }
+    }
+
+    fn resolve_auto(&self, length: &NonNegativeLengthPercentageOrAuto, axis: Axis) -> Option<f32> {
+        match *length {
+            LengthPercentageOrAuto::LengthPercentage(ref length) => {
+                Some(self.resolve(&length.0, axis))
+            },
+            LengthPercentageOrAuto::Auto => None,
+        }
+    }
+
+    fn length_attr(
+        &self,
+        element: &N::ConcreteThreadSafeLayoutElement,
+        name: &LocalName,
+        axis: Axis,
+    ) -> Option<f32> {
+        parse_length(
+            element.get_attr(&ns!(), name)?,
+            self.percentage_basis(axis),
+            self.context,
+        )
+    }
+
+    /// Returns the first length of a list-valued attribute, like the `x` of `<text>`.
+    fn first_length_attr(
+        &self,
+        element: &N::ConcreteThreadSafeLayoutElement,
+        name: &LocalName,
+        axis: Axis,
+    ) -> Option<f32> {
+        let value = element.get_attr(&ns!(), name)?;
+        let first = value
+            .split(|c: char| c == ',' || c.is_ascii_whitespace())
+            .find(|value| !value.is_empty())?;
+        parse_length(first, self.percentage_basis(axis), self.context)
+    }
+}
+
+/// The dimension that a percentage is resolved against.
+#[derive(Clone, Copy)]
+enum Axis {
+    X,
+    Y,
+    /// The normalized diagonal of the viewport.
+    Other,
+}
+
+/// Returns the child elements of `node`, without pseudo-elements.
+fn svg_child_elements<N: ThreadSafeLayoutNode>(node: N) -> impl Iterator<Item = N> {
+    node.children().filter(|child| {
+        child.get_pseudo_element_type() == PseudoElementType::Normal && child.as_element().is_some()
+    })
+}
+
+/// Returns the style of an element, or `None` if it was not styled, like the descendants of
+/// elements with `display: none`.
+fn style_if_styled<N: ThreadSafeLayoutNode>(
+    node: N,
+    context: &SharedStyleContext,
+) -> Option<ServoArc<ComputedValues>> {
+    ThreadSafeLayoutNode::get_style_and_layout_data(&node)?;
+    let element = node.as_element()?;
+    if !element.style_data().has_styles() {
+        return None;
+    }
+    Some(element.style(context))
+}
+
+/// Returns the id referenced by the `href` or `xlink:href` attribute of an element.
+fn href<E: ThreadSafeLayoutElement>(element: &E) -> Option<&str> {
+    element
+        .get_attr(&ns!(), &local_name!("href"))
+        .or_else(|| element.get_attr(&ns!(xlink), &local_name!("href")))
+        .and_then(|href| {
+            let href = href.trim();
+            if href.starts_with('#') {
+                Some(&href[1..])
+            } else {
+                None
+            }
+        })
+}
+
+/// Collapses white space the way `xml:space="default"` does: newlines are removed, tabs
+/// become spaces, and consecutive spaces are merged.
+fn collapse_white_space(text: &str, last_was_space: &mut bool) -> String {
+    let mut result = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '\n' | '\r' => {},
+            ' ' | '\t' => {
+                if !*last_was_space {
+                    result.push(' ');
+                    *last_was_space = true;
+                }
+            },
+            character => {
+                result.push(character);
+                *last_was_space = false;
+            },
+        }
+    }
+    result
+}
+
+/// A path builder that keeps track of the bounding box of the points it is given.
+struct PathSink {
+    builder: raqote::PathBuilder,
+    min: Point2D<f32>,
+    max: Point2D<f32>,
+}
+
+impl PathSink {
+    fn new() -> PathSink {
+        PathSink {
+            builder: raqote::PathBuilder::new(),
+            min: Point2D::new(f32::INFINITY, f32::INFINITY),
+            max: Point2D::new(f32::NEG_INFINITY, f32::NEG_INFINITY),
+        }
+    }
+
+    fn include(&mut self, point: Point2D<f32>) {
+        self.min = self.min.min(point);
+        self.max = self.max.max(point);
+    }
+
+    fn move_to(&mut self, point: Point2D<f32>) {
+        self.include(point);
+        self.builder.move_to(point.x, point.y);
+    }
+
+    fn line_to(&mut self, point: Point2D<f32>) {
+        self.include(point);
+        self.builder.line_to(point.x, point.y);
+    }
+
+    fn quad_to(&mut self, control: Point2D<f32>, point: Point2D<f32>) {
+        self.include(control);
+        self.include(point);
+        self.builder.quad_to(control.x, control.y, point.x, point.y);
+    }
+
+    fn cubic_to(&mut self, control1: Point2D<f32>, control2: Point2D<f32>, point: Point2D<f32>) {
+        self.include(control1);
+        self.include(control2);
+        self.include(point);
+        self.builder.cubic_to(
+            control1.x, control1.y, control2.x, control2.y, point.x, point.y,
+        );
+    }
+
+    fn close(&mut self) {
+        self.builder.close();
+    }
+
+    fn ellipse(&mut self, center: Point2D<f32>, rx: f32, ry: f32) {
+        let (kx, ky) = (rx * KAPPA, ry * KAPPA);
+        let (cx, cy) = (center.x, center.y);
+        self.move_to(Point2D::new(cx + rx, cy));
+        self.cubic_to(
+            Point2D::new(cx + rx, cy + ky),
+            Point2D::new(cx + kx, cy + ry),
+            Point2D::new(cx, cy + ry),
+        );
+        self.cubic_to(
+            Point2D::new(cx - kx, cy + ry),
+            Point2D::new(cx - rx, cy + ky),
+            Point2D::new(cx - rx, cy),
+        );
+        self.cubic_to(
+            Point2D::new(cx - rx, cy - ky),
+            Point2D::new(cx - kx, cy - ry),
+            Point2D::new(cx, cy - ry),
+        );
+        self.cubic_to(
+            Point2D::new(cx + kx, cy - ry),
+            Point2D::new(cx + rx, cy - ky),
+            Point2D::new(cx + rx, cy),
+        );
+        self.close();
+    }
+
+    fn rounded_rect(&mut self, rect: Rect<f32>, rx: f32, ry: f32) {
+        let (left, top) = (rect.min_x(), rect.min_y());
+        let (right, bottom) = (rect.max_x(), rect.max_y());
+        let (kx, ky) = (rx * (1. - KAPPA), ry * (1. - KAPPA));
+        self.move_to(Point2D::new(left + rx, top));
+        self.line_to(Point2D::new(right - rx, top));
+        if rx > 0. && ry > 0. {
+            self.cubic_to(
+                Point2D::new(right - kx, top),
+                Point2D::new(right, top + ky),
+                Point2D::new(right, top + ry),
+            );
+        }
+        self.line_to(Point2D::new(right, bottom - ry));
+        if rx > 0. && ry > 0. {
+            self.cubic_to(
+                Point2D::new(right, bottom - ky),
+                Point2D::new(right - kx, bottom),
+                Point2D::new(right - rx, bottom),
+            );
+        }
+        self.line_to(Point2D::new(left + rx, bottom));
+        if rx > 0. && ry > 0. {
+            self.cubic_to(
+                Point2D::new(left + kx, bottom),
+                Point2D::new(left, bottom - ky),
+                Point2D::new(left, bottom - ry),
+            );
+        }
+        self.line_to(Point2D::new(left, top + ry));
+        if rx > 0. && ry > 0. {
+            self.cubic_to(
+                Point2D::new(left, top + ky),
+                Point2D::new(left + kx, top),
+                Point2D::new(left + rx, top),
+            );
+        }
+        self.close();
+    }
+
+    /// Adds an elliptical arc from `from` to `to`, converted to cubic Bézier curves.
+    ///
+    /// https://svgwg.org/svg2-draft/implnote.html#ArcConversionEndpointToCenter
+    fn arc_to(
+        &mut self,
+        from: Point2D<f32>,
+        radii: (f32, f32),
+        x_axis_rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        to: Point2D<f32>,
+    ) {
+        if from == to {
+            return;
+        }
+        let (mut rx, mut ry) = (radii.0.abs(), radii.1.abs());
+        if rx == 0. || ry == 0. {
+            return self.line_to(to);
+        }
+
+        let (sin, cos) = x_axis_rotation.to_radians().sin_cos();
+        let half_difference = (from - to) / 2.;
+        let x1 = cos * half_difference.x + sin * half_difference.y;
+        let y1 = -sin * half_difference.x + cos * half_difference.y;
+
+        // Scale up radii that are too small to reach the end point.
+        let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+        if lambda > 1. {
+            rx *= lambda.sqrt();
+            ry *= lambda.sqrt();
+        }
+
+        let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+        let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+        let mut coefficient = (numerator / denominator).max(0.).sqrt();
+        if large_arc == sweep {
+            coefficient = -coefficient;
+        }
+        let center_x1 = coefficient * rx * y1 / ry;
+        let center_y1 = -coefficient * ry * x1 / rx;
+        let middle = from.lerp(to, 0.5);
+        let center = Point2D::new(
+            cos * center_x1 - sin * center_y1 + middle.x,
+            sin * center_x1 + cos * center_y1 + middle.y,
+        );
+
+        let angle = |u: Vector2D<f32>, v: Vector2D<f32>| u.cross(v).atan2(u.dot(v));
+        let start_vector = Vector2D::new((x1 - center_x1) / rx, (y1 - center_y1) / ry);
+        let end_vector = Vector2D::new((-x1 - center_x1) / rx, (-y1 - center_y1) / ry);
+        let start_angle = angle(Vector2D::new(1., 0.), start_vector);
+        let mut sweep_angle = angle(start_vector, end_vector);
+        if !sweep && sweep_angle > 0. {
+            sweep_angle -= 2. * PI;
+        } else if sweep && sweep_angle < 0. {
+            sweep_angle += 2. * PI;
+        }
+
+        let map = |x: f32, y: f32| {
+            Point2D::new(
+                center.x + rx * cos * x - ry * sin * y,
+                center.y + rx * sin * x + ry * cos * y,
+            )
+        };
+        let segments = (sweep_angle.abs() / FRAC_PI_2).ceil().max(1.) as u32;
+        let segment_angle = sweep_angle / segments as f32;
+        let handle = 4. / 3. * (segment_angle / 4.).tan();
+        for segment in 0..segments {
+            let angle1 = start_angle + segment as f32 * segment_angle;
+            let angle2 = angle1 + segment_angle;
+            let (sin1, cos1) = angle1.sin_cos();
+            let (sin2, cos2) = angle2.sin_cos();
+            let end = if segment + 1 == segments {
+                to
+            } else {
+                map(cos2, sin2)
+            };
+            self.cubic_to(
+                map(cos1 - handle * sin1, sin1 + handle * cos1),
+                map(cos2 + handle * sin2, sin2 - handle * cos2),
+                end,
+            );
+        }
+    }
+
+    /// Returns the path and its bounding box, or `None` if no point was added.
+    fn finish(self) -> Option<(raqote::Path, Rect<f32>)> {
+        if self.min.x > self.max.x {
+            return None;
+        }
+        let bounds = Rect::new(self.min, (self.max - self.min).to_size());
+        Some((self.builder.finish(), bounds))
+    }
+}
+
+/// Parses an attribute with a parser of the style system, allowing unitless lengths as in
+/// presentation attributes.
+fn parse_attribute<T, F>(input: &str, context: &SharedStyleContext, parse: F) -> Option<T>
+where
+    F: for<'i, 't> FnOnce(&ParserContext, &mut Parser<'i, 't>) -> Result<T, ParseError<'i>>,
+{
+    let url = ServoUrl::parse("about:blank").unwrap();
+    let parser_context = ParserContext::new(
+        Origin::Author,
+        &url,
+        Some(CssRuleType::Style),
+        ParsingMode::ALLOW_UNITLESS_LENGTH,
+        context.quirks_mode(),
+        None,
+        None,
+    );
+    let mut input = ParserInput::new(input);
+    Parser::new(&mut input)
+        .parse_entirely(|input| parse(&parser_context, input))
+        .ok()
+}
+
+fn to_computed_value<T: ToComputedValue>(
+    value: &T,
+    context: &SharedStyleContext,
+) -> T::ComputedValue {
+    Context::for_media_query_evaluation(
+        context.stylist.device(),
+        context.quirks_mode(),
+        |context| value.to_computed_value(context),
+    )
+}
+
+/// Parses numbers separated by white space or commas, up to the first invalid one.
+fn parse_number_list(input: &str) -> Vec<f32> {
+    let mut input = ParserInput::new(input);
+    let mut parser = Parser::new(&mut input);
+    let mut numbers = vec![];
+    while let Ok(number) = parser.r#try(|input| input.expect_number()) {
+        numbers.push(number);
+        let _ = parser.r#try(|input| input.expect_comma());
+    }
+    numbers
+}
+
+/// Parses a length attribute, resolving percentages against `percentage_basis`.
+fn parse_length(input: &str, percentage_basis: f32, context: &SharedStyleContext) -> Option<f32> {
+    let length = parse_attribute(input, context, specified::LengthPercentage::parse)?;
+    Some(
+        to_computed_value(&length, context)
+            .percentage_relative_to(Length::new(percentage_basis))
+            .px(),
+    )
+}
+
+fn parse_view_box(input: &str) -> Option<Rect<f32>> {
+    match parse_number_list(input)[..] {
+        [x, y, width, height] if width > 0. && height > 0. => {
+            Some(Rect::new(Point2D::new(x, y), Size2D::new(width, height)))
+        },
+        _ => None,
+    }
+}
+
+/// Parses a `transform` attribute with the parser of the `transform` property. An invalid
+/// list is ignored.
+///
+/// FIXME: The SVG 1.1 syntax, which allows unitless angles, arguments separated by white
+/// space, and a center for `rotate()`, isn't supported.
+///
+/// https://svgwg.org/svg2-draft/coords.html#TransformProperty
+fn parse_transform(input: &str, context: &SharedStyleContext) -> Transform2D<f32> {
+    parse_attribute(input, context, specified::Transform::parse)
+        .and_then(|transform| {
+            to_computed_value(&transform, context)
+                .to_transform_3d_matrix(None)
+                .ok()
+        })
+        .map_or(Transform2D::identity(), |(matrix, _)| matrix.to_2d())
+}
+
+/// Parses path data into `path`. Parsing stops at the first error, keeping the segments
+/// before it.
+fn parse_path_data(input: &str, path: &mut PathSink) {
+    let mut current = Point2D::zero();
+    let mut subpath_start = Point2D::zero();
+    // The control point to reflect for the following smooth curve command, if the previous
+    // command was a curve of the same kind.
+    let mut last_cubic_control = None;
+    let mut last_quad_control = None;
+    let reflect = |control: Option<Point2D<f32>>, current: Point2D<f32>| {
+        control.map_or(current, |control| current + (current - control))
+    };
+
+    for command in SVGPathData::parse_normalized_commands(input).iter() {
+        let mut cubic_control = None;
+        let mut quad_control = None;
+        match *command {
+            PathCommand::Unknown => break,
+            PathCommand::MoveTo { point, .. } => {
+                current = Point2D::new(point.x(), point.y());
+                subpath_start = current;
+                path.move_to(current);
+            },
+            PathCommand::LineTo { point, .. } => {
+                current = Point2D::new(point.x(), point.y());
+                path.line_to(current);
+            },
+            PathCommand::HorizontalLineTo { x, .. } => {
+                current = Point2D::new(x, current.y);
+                path.line_to(current);
+            },
+            PathCommand::VerticalLineTo { y, .. } => {
+                current = Point2D::new(current.x, y);
+                path.line_to(current);
+            },
+            PathCommand::CurveTo {
+                control1,
+                control2,
+                point,
+                ..
+            } => {
+                let control1 = Point2D::new(control1.x(), control1.y());
+                let control2 = Point2D::new(control2.x(), control2.y());
+                current = Point2D::new(point.x(), point.y());
+                cubic_control = Some(control2);
+                path.cubic_to(control1, control2, current);
+            },
+            PathCommand::SmoothCurveTo {
+                control2, point, ..
+            } => {
+                let control1 = reflect(last_cubic_control, current);
+                let control2 = Point2D::new(control2.x(), control2.y());
+                current = Point2D::new(point.x(), point.y());
+                cubic_control = Some(control2);
+                path.cubic_to(control1, control2, current);
+            },
+            PathCommand::QuadBezierCurveTo {
+                control1, point, ..
+            } => {
+                let control1 = Point2D::new(control1.x(), control1.y());
+                current = Point2D::new(point.x(), point.y());
+                quad_control = Some(control1);
+                path.quad_to(control1, current);
+            },
+            PathCommand::SmoothQuadBezierCurveTo { point, .. } => {
+                let control1 = reflect(last_quad_control, current);
+                current = Point2D::new(point.x(), point.y());
+                quad_control = Some(control1);
+                path.quad_to(control1, current);
+            },
+            PathCommand::EllipticalArc {
+                rx,
+                ry,
+                angle,
+                large_arc_flag,
+                sweep_flag,
+                point,
+                ..
+            } => {
+                let to = Point2D::new(point.x(), point.y());
+                path.arc_to(
+                    current,
+                    (rx, ry),
+                    angle,
+                    large_arc_flag.is_set(),
+                    sweep_flag.is_set(),
+                    to,
+                );
+                current = to;
+            },
+            PathCommand::ClosePath => {
+                current = subpath_start;
+                path.close();
+            },
+        }
+        last_cubic_control = cubic_control;
+        last_quad_control = quad_control;
+    }
+}
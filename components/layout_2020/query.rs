@@ -20,6 +20,8 @@ use script_traits::UntrustedNodeAddress;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use style::dom::OpaqueNode;
+use servo_arc::Arc as ServoArc;
+use style::properties::style_structs::Font;
 use style::properties::PropertyId;
 use style::selector_parser::PseudoElement;
 use webrender_api::units::LayoutPixel;
@@ -53,6 +55,9 @@ pub struct LayoutThreadData {
     /// A queued response for the resolved style property of an element.
     pub resolved_style_response: String,
 
+    /// A queued response for the resolved font style for canvas.
+    pub resolved_font_style_response: Option<ServoArc<Font>>,
+
     /// A queued response for the offset parent/rect of a node.
     pub offset_parent_response: OffsetParentResponse,
 
@@ -127,6 +132,13 @@ impl LayoutRPC for LayoutRPCImpl {
         ResolvedStyleResponse(rw_data.resolved_style_response.clone())
     }
 
+    /// Retrieves the resolved font style for canvas.
+    fn resolved_font_style(&self) -> Option<ServoArc<Font>> {
+        let &LayoutRPCImpl(ref rw_data) = self;
+        let rw_data = rw_data.lock().unwrap();
+        rw_data.resolved_font_style_response.clone()
+    }
+
     fn offset_parent(&self) -> OffsetParentResponse {
         let &LayoutRPCImpl(ref rw_data) = self;
         let rw_data = rw_data.lock().unwrap();
@@ -191,6 +203,18 @@ where
     "".to_owned()
 }
 
+pub fn process_resolved_font_style_request<'a, N>(
+    _context: &LayoutContext,
+    _node: N,
+    _value: &str,
+    _property: &PropertyId,
+) -> Option<ServoArc<Font>>
+where
+    N: LayoutNode,
+{
+    None
+}
+
 pub fn process_offset_parent_query(_requested_node: OpaqueNode) -> OffsetParentResponse {
     OffsetParentResponse::empty()
 }
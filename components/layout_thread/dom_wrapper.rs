@@ -30,7 +30,9 @@
 
 #![allow(unsafe_code)]
 
+use app_units::Au;
 use atomic_refcell::{AtomicRef, AtomicRefCell, AtomicRefMut};
+use euclid::default::Size2D;
 use gfx_traits::ByteIndex;
 use html5ever::{LocalName, Namespace};
 use layout::data::StyleAndLayoutData;
@@ -455,6 +457,11 @@ impl<'ld> ServoLayoutDocument<'ld> {
         }
     }
 
+    pub fn element_with_id(&self, id: &Atom) -> Option<ServoLayoutElement<'ld>> {
+        unsafe { self.document.element_with_id_for_layout(id) }
+            .map(ServoLayoutElement::from_layout_js)
+    }
+
     pub fn from_layout_js(doc: LayoutDom<Document>) -> ServoLayoutDocument<'ld> {
         ServoLayoutDocument {
             document: doc,
@@ -620,6 +627,10 @@ impl<'le> TElement for ServoLayoutElement<'le> {
         }
     }
 
+    fn query_container_size(&self) -> Option<Size2D<Au>> {
+        self.get_raw_data()?.layout_data.borrow().container_size
+    }
+
     fn skip_item_display_fixup(&self) -> bool {
         false
     }
@@ -1211,6 +1222,13 @@ impl<'ln> ThreadSafeLayoutNode for ServoThreadSafeLayoutNode<'ln> {
         this.svg_data()
     }
 
+    fn element_with_id(&self, id: &Atom) -> Option<Self> {
+        self.node
+            .owner_doc()
+            .element_with_id(id)
+            .map(|element| ServoThreadSafeLayoutNode::new(&element.as_node()))
+    }
+
     // Can return None if the iframe has no nested browsing context
     fn iframe_browsing_context_id(&self) -> Option<BrowsingContextId> {
         let this = unsafe { self.get_jsmanaged() };
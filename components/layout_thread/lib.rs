@@ -34,7 +34,7 @@ use fxhash::{FxHashMap, FxHashSet};
 use gfx::font;
 use gfx::font_cache_thread::FontCacheThread;
 use gfx::font_context;
-use gfx_traits::{node_id_from_scroll_id, Epoch};
+use gfx_traits::{node_id_from_scroll_id, Epoch, WebFontLoadResult};
 use histogram::Histogram;
 use ipc_channel::ipc::{self, IpcReceiver, IpcSender};
 use ipc_channel::router::ROUTER;
@@ -45,21 +45,24 @@ use layout::context::LayoutContext;
 use layout::context::RegisteredPainter;
 use layout::context::RegisteredPainters;
 use layout::display_list::items::{OpaqueNode, WebRenderImageInfo};
-use layout::display_list::{IndexableText, ToLayout};
+use layout::display_list::{IndexableText, MaskImageCache, ToLayout};
 use layout::flow::{Flow, GetBaseFlow, ImmutableFlowUtils, MutableOwnedFlowUtils};
 use layout::flow_ref::FlowRef;
 use layout::incremental::{RelayoutMode, SpecialRestyleDamage};
 use layout::layout_debug;
 use layout::parallel;
 use layout::query::{
-    process_content_box_request, process_content_boxes_request, LayoutRPCImpl, LayoutThreadData,
+    process_container_sizes_request, process_content_box_request, process_content_boxes_request,
+    LayoutRPCImpl, LayoutThreadData,
 };
 use layout::query::{process_element_inner_text_query, process_node_geometry_request};
 use layout::query::{process_node_scroll_area_request, process_node_scroll_id_request};
 use layout::query::{
-    process_offset_parent_query, process_resolved_style_request, process_style_query,
+    process_offset_parent_query, process_resolved_font_style_request,
+    process_resolved_style_request, process_style_query,
 };
 use layout::sequential;
+use layout::svg::SvgImageCache;
 use layout::traversal::{
     ComputeStackingRelativePositions, PreorderFlowTraversal, RecalcStyleAndConstructFlows,
 };
@@ -78,6 +81,7 @@ use parking_lot::RwLock;
 use profile_traits::mem::{self as profile_mem, Report, ReportKind, ReportsChan};
 use profile_traits::time::{self as profile_time, profile, TimerMetadata};
 use profile_traits::time::{TimerMetadataFrameType, TimerMetadataReflowType};
+use script_layout_interface::message::WebFontSource;
 use script_layout_interface::message::{LayoutThreadInit, Msg, NodesFromPointQueryType, Reflow};
 use script_layout_interface::message::{QueryMsg, ReflowComplete, ReflowGoal, ScriptReflow};
 use script_layout_interface::rpc::TextIndexResponse;
@@ -86,7 +90,7 @@ use script_layout_interface::wrapper_traits::LayoutNode;
 use script_traits::Painter;
 use script_traits::{ConstellationControlMsg, LayoutControlMsg, LayoutMsg as ConstellationMsg};
 use script_traits::{DrawAPaintImageResult, IFrameSizeMsg, PaintWorkletError, WindowSizeType};
-use script_traits::{ScrollState, UntrustedNodeAddress};
+use script_traits::{ScrollContainerInfo, ScrollState, UntrustedNodeAddress};
 use selectors::Element;
 use servo_arc::Arc as ServoArc;
 use servo_atoms::Atom;
@@ -129,6 +133,10 @@ use style_traits::CSSPixel;
 use style_traits::DevicePixel;
 use style_traits::SpeculativePainter;
 
+/// The maximum number of times that styles are recomputed and laid out again
+/// in a single reflow because the size of a query container changed.
+const MAX_CONTAINER_QUERY_PASSES: usize = 3;
+
 /// Information needed by the layout thread.
 pub struct LayoutThread {
     /// The ID of the pipeline that we belong to.
@@ -153,10 +161,10 @@ pub struct LayoutThread {
     pipeline_port: Receiver<LayoutControlMsg>,
 
     /// The port on which we receive messages from the font cache thread.
-    font_cache_receiver: Receiver<()>,
+    font_cache_receiver: Receiver<WebFontLoadResult>,
 
     /// The channel on which the font cache can send messages to us.
-    font_cache_sender: IpcSender<()>,
+    font_cache_sender: IpcSender<WebFontLoadResult>,
 
     /// A means of communication with the background hang monitor.
     background_hang_monitor: Box<dyn BackgroundHangMonitor>,
@@ -226,6 +234,12 @@ pub struct LayoutThread {
 
     webrender_image_cache: Arc<RwLock<FnvHashMap<(ServoUrl, UsePlaceholder), WebRenderImageInfo>>>,
 
+    /// The images that inline SVG content has been rasterized into.
+    svg_image_cache: Arc<Mutex<SvgImageCache>>,
+
+    /// The images that clip paths and masks have been rasterized into.
+    mask_image_cache: Arc<Mutex<MaskImageCache>>,
+
     /// The executors for paint worklets.
     registered_painters: RegisteredPaintersImpl,
 
@@ -248,6 +262,9 @@ pub struct LayoutThread {
     /// The sizes of all iframes encountered during the last layout operation.
     last_iframe_sizes: RefCell<HashMap<BrowsingContextId, Size2D<f32, CSSPixel>>>,
 
+    /// The scroll containers that input events can scroll, as of the last layout operation.
+    last_scroll_containers: RefCell<Vec<ScrollContainerInfo>>,
+
     /// Flag that indicates if LayoutThread is busy handling a request.
     busy: Arc<AtomicBool>,
 
@@ -478,10 +495,11 @@ fn add_font_face_rules(
     guard: &SharedRwLockReadGuard,
     device: &Device,
     font_cache_thread: &FontCacheThread,
-    font_cache_sender: &IpcSender<()>,
+    font_cache_sender: &IpcSender<WebFontLoadResult>,
     outstanding_web_fonts_counter: &Arc<AtomicUsize>,
     load_webfonts_synchronously: bool,
-) {
+) -> Vec<WebFontLoadResult> {
+    let mut results = vec![];
     if load_webfonts_synchronously {
         let (sender, receiver) = ipc::channel().unwrap();
         stylesheet.effective_font_face_rules(&device, guard, |rule| {
@@ -492,7 +510,7 @@ fn add_font_face_rules(
                     effective_sources,
                     sender.clone(),
                 );
-                receiver.recv().unwrap();
+                results.push(receiver.recv().unwrap());
             }
         })
     } else {
@@ -508,6 +526,7 @@ fn add_font_face_rules(
             }
         })
     }
+    results
 }
 
 impl LayoutThread {
@@ -603,6 +622,7 @@ impl LayoutThread {
                 scroll_id_response: None,
                 scroll_area_response: Rect::zero(),
                 resolved_style_response: String::new(),
+                resolved_font_style_response: None,
                 offset_parent_response: OffsetParentResponse::empty(),
                 style_response: StyleResponse(None),
                 scroll_offsets: HashMap::new(),
@@ -611,6 +631,12 @@ impl LayoutThread {
                 element_inner_text_response: String::new(),
             })),
             webrender_image_cache: Arc::new(RwLock::new(FnvHashMap::default())),
+            svg_image_cache: Arc::new(Mutex::new(SvgImageCache::new(
+                webrender_api_sender.create_api(),
+            ))),
+            mask_image_cache: Arc::new(Mutex::new(MaskImageCache::new(
+                webrender_api_sender.create_api(),
+            ))),
             timer: if pref!(layout.animations.test.enabled) {
                 Timer::test_mode()
             } else {
@@ -619,6 +645,7 @@ impl LayoutThread {
             paint_time_metrics: paint_time_metrics,
             layout_query_waiting_time: Histogram::new(),
             last_iframe_sizes: Default::default(),
+            last_scroll_containers: Default::default(),
             busy,
             load_webfonts_synchronously,
             initial_window_size,
@@ -676,6 +703,8 @@ impl LayoutThread {
             image_cache: self.image_cache.clone(),
             font_cache_thread: Mutex::new(self.font_cache_thread.clone()),
             webrender_image_cache: self.webrender_image_cache.clone(),
+            svg_image_cache: self.svg_image_cache.clone(),
+            mask_image_cache: self.mask_image_cache.clone(),
             pending_images: if script_initiated_layout {
                 Some(Mutex::new(vec![]))
             } else {
@@ -714,6 +743,14 @@ impl LayoutThread {
             Msg::RegisterPaint(..) => LayoutHangAnnotation::RegisterPaint,
             Msg::SetNavigationStart(..) => LayoutHangAnnotation::SetNavigationStart,
             Msg::GetRunningAnimations(..) => LayoutHangAnnotation::GetRunningAnimations,
+            Msg::UpdateScriptAnimation(..) => LayoutHangAnnotation::UpdateScriptAnimation,
+            Msg::CancelAnimation(..) => LayoutHangAnnotation::CancelAnimation,
+            Msg::SetAnimationPaused(..) => LayoutHangAnnotation::SetAnimationPaused,
+            Msg::CancelTransition(..) => LayoutHangAnnotation::CancelTransition,
+            Msg::SetTransitionTime(..) => LayoutHangAnnotation::SetTransitionTime,
+            Msg::GetAnimations(..) => LayoutHangAnnotation::GetAnimations,
+            Msg::LoadWebFont(..) => LayoutHangAnnotation::LoadWebFont,
+            Msg::RegisterCustomProperty(..) => LayoutHangAnnotation::RegisterCustomProperty,
         };
         self.background_hang_monitor
             .notify_activity(HangAnnotation::Layout(hang_annotation));
@@ -724,7 +761,7 @@ impl LayoutThread {
         enum Request {
             FromPipeline(LayoutControlMsg),
             FromScript(Msg),
-            FromFontCache,
+            FromFontCache(WebFontLoadResult),
         }
 
         // Notify the background-hang-monitor we are waiting for an event.
@@ -733,7 +770,7 @@ impl LayoutThread {
         let request = select! {
             recv(self.pipeline_port) -> msg => Request::FromPipeline(msg.unwrap()),
             recv(self.port) -> msg => Request::FromScript(msg.unwrap()),
-            recv(self.font_cache_receiver) -> msg => { msg.unwrap(); Request::FromFontCache }
+            recv(self.font_cache_receiver) -> msg => Request::FromFontCache(msg.unwrap()),
         };
 
         self.busy.store(true, Ordering::Relaxed);
@@ -759,12 +796,12 @@ impl LayoutThread {
                 true
             },
             Request::FromScript(msg) => self.handle_request_helper(msg, possibly_locked_rw_data),
-            Request::FromFontCache => {
+            Request::FromFontCache(result) => {
                 let _rw_data = possibly_locked_rw_data.lock();
                 self.outstanding_web_fonts.fetch_sub(1, Ordering::SeqCst);
                 font_context::invalidate_font_caches();
                 self.script_chan
-                    .send(ConstellationControlMsg::WebFontLoaded(self.id))
+                    .send(ConstellationControlMsg::WebFontLoaded(self.id, result))
                     .unwrap();
                 true
             },
@@ -892,6 +929,69 @@ impl LayoutThread {
             Msg::GetRunningAnimations(sender) => {
                 let _ = sender.send(self.running_animations.read().len());
             },
+            Msg::UpdateScriptAnimation(mut animation) => {
+                if let Animation::Keyframes(_, _, _, ref mut state) = animation {
+                    state.started_at += self.timer.seconds();
+                }
+                self.new_animations_sender.send(animation).unwrap();
+            },
+            Msg::CancelAnimation(node, name) => {
+                animation::cancel_animation(
+                    &mut *self.running_animations.write(),
+                    &self.new_animations_sender,
+                    &self.new_animations_receiver,
+                    node,
+                    &name,
+                );
+            },
+            Msg::SetAnimationPaused(node, name, paused) => {
+                animation::set_animation_paused(
+                    &mut *self.running_animations.write(),
+                    node,
+                    &name,
+                    paused,
+                    &self.timer,
+                );
+            },
+            Msg::CancelTransition(node, property) => {
+                animation::cancel_transition(
+                    &mut *self.running_animations.write(),
+                    node,
+                    &property,
+                );
+            },
+            Msg::SetTransitionTime(node, property, time, paused) => {
+                animation::set_transition_time(
+                    &mut *self.running_animations.write(),
+                    node,
+                    &property,
+                    time,
+                    paused,
+                    &self.timer,
+                );
+            },
+            Msg::GetAnimations(node, sender) => {
+                let _ = sender.send(animation::running_animation_info(
+                    &*self.running_animations.read(),
+                    node,
+                    &self.timer,
+                ));
+            },
+            Msg::LoadWebFont(family, source) => {
+                self.outstanding_web_fonts.fetch_add(1, Ordering::SeqCst);
+                let sender = self.font_cache_sender.clone();
+                match source {
+                    WebFontSource::Sources(sources) => {
+                        self.font_cache_thread.add_web_font(family, sources, sender)
+                    },
+                    WebFontSource::Data(bytes) => self
+                        .font_cache_thread
+                        .add_web_font_data(family, bytes, sender),
+                }
+            },
+            Msg::RegisterCustomProperty(registration) => {
+                self.stylist.register_custom_property(registration);
+            },
         }
 
         true
@@ -1011,7 +1111,7 @@ impl LayoutThread {
         // Find all font-face rules and notify the font cache of them.
         // GWTODO: Need to handle unloading web fonts.
         if stylesheet.is_effective_for_device(self.stylist.device(), &guard) {
-            add_font_face_rules(
+            let loaded = add_font_face_rules(
                 &*stylesheet,
                 &guard,
                 self.stylist.device(),
@@ -1020,6 +1120,13 @@ impl LayoutThread {
                 &self.outstanding_web_fonts,
                 self.load_webfonts_synchronously,
             );
+            // Let script know about fonts that were loaded synchronously, so that
+            // the corresponding `FontFace` objects can settle.
+            for result in loaded {
+                let _ = self
+                    .script_chan
+                    .send(ConstellationControlMsg::WebFontLoaded(self.id, result));
+            }
         }
     }
 
@@ -1143,6 +1250,8 @@ impl LayoutThread {
                         );
 
                         debug!("Done building display list.");
+                        self.svg_image_cache.lock().unwrap().evict_unused_images();
+                        self.mask_image_cache.lock().unwrap().evict_unused_images();
 
                         let root_size = {
                             let root_flow = layout_root.base();
@@ -1200,6 +1309,21 @@ impl LayoutThread {
                             }
                         }
 
+                        // Only notify the constellation when the scroll containers, or how
+                        // they snap and chain scrolls, changed.
+                        let scroll_containers =
+                            std::mem::replace(&mut build_state.scroll_containers, vec![]);
+                        if *self.last_scroll_containers.borrow() != scroll_containers {
+                            let msg = ConstellationMsg::ScrollContainers(
+                                self.id,
+                                scroll_containers.clone(),
+                            );
+                            if let Err(e) = self.constellation_chan.send(msg) {
+                                warn!("Layout scroll containers to constellation failed ({}).", e);
+                            }
+                            *self.last_scroll_containers.borrow_mut() = scroll_containers;
+                        }
+
                         rw_data.indexable_text = std::mem::replace(
                             &mut build_state.indexable_text,
                             IndexableText::default(),
@@ -1321,6 +1445,9 @@ impl LayoutThread {
                         &QueryMsg::ResolvedStyleQuery(_, _, _) => {
                             rw_data.resolved_style_response = String::new();
                         },
+                        &QueryMsg::ResolvedFontStyleQuery(_, _, _) => {
+                            rw_data.resolved_font_style_response = None;
+                        },
                         &QueryMsg::OffsetParentQuery(_) => {
                             rw_data.offset_parent_response = OffsetParentResponse::empty();
                         },
@@ -1369,7 +1496,8 @@ impl LayoutThread {
         };
 
         let had_used_viewport_units = self.stylist.device().used_viewport_units();
-        let device = Device::new(MediaType::screen(), initial_viewport, device_pixel_ratio);
+        let mut device = Device::new(MediaType::screen(), initial_viewport, device_pixel_ratio);
+        device.set_media_preferences(data.media_preferences);
         let sheet_origins_affected_by_device_change = self.stylist.set_device(device, &guards);
 
         self.stylist
@@ -1583,6 +1711,51 @@ impl LayoutThread {
             );
         }
 
+        // `@container` rules are matched against the size of their container
+        // as of the last layout, so restyle the descendants of the containers
+        // whose size changed and lay them out again. Nested containers may
+        // need a few rounds of this.
+        if self.stylist.has_container_queries() {
+            for _ in 0..MAX_CONTAINER_QUERY_PASSES {
+                let container_sizes_changed = match self.root_flow.borrow_mut().as_mut() {
+                    Some(root_flow) => LayoutThread::update_container_sizes(element, root_flow),
+                    None => false,
+                };
+                if !container_sizes_changed {
+                    break;
+                }
+
+                let traversal = RecalcStyleAndConstructFlows::new(layout_context);
+                let token = {
+                    let shared = <RecalcStyleAndConstructFlows as DomTraversal<
+                        ServoLayoutElement,
+                    >>::shared_context(&traversal);
+                    RecalcStyleAndConstructFlows::pre_traverse(element, shared)
+                };
+                if token.should_traverse() {
+                    driver::traverse_dom::<ServoLayoutElement, RecalcStyleAndConstructFlows>(
+                        &traversal,
+                        token,
+                        thread_pool,
+                    );
+                    *self.root_flow.borrow_mut() = self.try_get_layout_root(element.as_node());
+                }
+                layout_context = traversal.destroy();
+
+                if let Some(mut root_flow) = self.root_flow.borrow().clone() {
+                    self.perform_post_style_recalc_layout_passes(
+                        &mut root_flow,
+                        &data.reflow_info,
+                        &data.reflow_goal,
+                        Some(&document),
+                        &mut rw_data,
+                        &mut layout_context,
+                        FxHashSet::default(),
+                    );
+                }
+            }
+        }
+
         self.first_reflow.set(false);
         self.respond_to_query_if_necessary(
             &data.reflow_goal,
@@ -1649,6 +1822,19 @@ impl LayoutThread {
                     rw_data.resolved_style_response =
                         process_resolved_style_request(context, node, pseudo, property, root_flow);
                 },
+                &QueryMsg::ResolvedFontStyleQuery(node, ref property, ref value) => {
+                    let node = unsafe { ServoLayoutNode::new(&node) };
+                    let url = self.url.clone();
+                    let shared_lock = self.document_shared_lock.clone().unwrap();
+                    rw_data.resolved_font_style_response = process_resolved_font_style_request(
+                        context,
+                        node,
+                        value,
+                        property,
+                        url,
+                        &shared_lock,
+                    );
+                },
                 &QueryMsg::OffsetParentQuery(node) => {
                     rw_data.offset_parent_response = process_offset_parent_query(node, root_flow);
                 },
@@ -1933,6 +2119,40 @@ impl LayoutThread {
         self.generation.set(self.generation.get() + 1);
     }
 
+    /// Stores the size that every query container got in the last layout,
+    /// and marks the descendants of the containers whose size changed for
+    /// restyling. Returns whether any container size changed.
+    fn update_container_sizes(root: ServoLayoutElement, root_flow: &mut FlowRef) -> bool {
+        let sizes = process_container_sizes_request(FlowRef::deref_mut(root_flow));
+        let root = root.as_node();
+        let mut changed = false;
+        for node in Some(root).into_iter().chain(root.dom_descendants()) {
+            let element = match node.as_element() {
+                Some(element) => element,
+                None => continue,
+            };
+            let size = sizes.get(&node.opaque()).cloned();
+            {
+                let mut layout_data = match element.mutate_layout_data() {
+                    Some(layout_data) => layout_data,
+                    None => continue,
+                };
+                if layout_data.container_size == size {
+                    continue;
+                }
+                layout_data.container_size = size;
+            }
+
+            debug!("Container size changed for {:?}: {:?}", element, size);
+            if let Some(mut data) = element.mutate_data() {
+                data.hint.insert(RestyleHint::RESTYLE_DESCENDANTS);
+            }
+            unsafe { element.note_dirty_descendant() };
+            changed = true;
+        }
+        changed
+    }
+
     fn reflow_all_nodes(flow: &mut dyn Flow) {
         debug!("reflowing all nodes!");
         flow.mut_base().restyle_damage.insert(
@@ -2058,6 +2278,15 @@ fn get_ua_stylesheets() -> Result<UserAgentStylesheets, &'static str> {
         )?,
     ];
 
+    // Without SVG support, the children of <svg> elements must not be laid out as HTML.
+    if !pref!(dom.svg.enabled) {
+        user_or_user_agent_stylesheets.push(parse_ua_stylesheet(
+            &shared_lock,
+            "svg-fallback.css",
+            &resources::read_bytes(Resource::SVGFallbackCSS),
+        )?);
+    }
+
     for &(ref contents, ref url) in &opts::get().user_stylesheets {
         user_or_user_agent_stylesheets.push(DocumentStyleSheet(ServoArc::new(
             Stylesheet::from_bytes(
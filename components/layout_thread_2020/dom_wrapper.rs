@@ -462,6 +462,11 @@ impl<'ld> ServoLayoutDocument<'ld> {
         }
     }
 
+    pub fn element_with_id(&self, id: &Atom) -> Option<ServoLayoutElement<'ld>> {
+        unsafe { self.document.element_with_id_for_layout(id) }
+            .map(ServoLayoutElement::from_layout_js)
+    }
+
     pub fn from_layout_js(doc: LayoutDom<Document>) -> ServoLayoutDocument<'ld> {
         ServoLayoutDocument {
             document: doc,
@@ -1218,6 +1223,13 @@ impl<'ln> ThreadSafeLayoutNode for ServoThreadSafeLayoutNode<'ln> {
         this.svg_data()
     }
 
+    fn element_with_id(&self, id: &Atom) -> Option<Self> {
+        self.node
+            .owner_doc()
+            .element_with_id(id)
+            .map(|element| ServoThreadSafeLayoutNode::new(&element.as_node()))
+    }
+
     // Can return None if the iframe has no nested browsing context
     fn iframe_browsing_context_id(&self) -> Option<BrowsingContextId> {
         let this = unsafe { self.get_jsmanaged() };
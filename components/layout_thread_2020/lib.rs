@@ -31,7 +31,7 @@ use fnv::FnvHashMap;
 use fxhash::FxHashMap;
 use gfx::font_cache_thread::FontCacheThread;
 use gfx::font_context;
-use gfx_traits::{node_id_from_scroll_id, Epoch};
+use gfx_traits::{node_id_from_scroll_id, Epoch, WebFontLoadResult};
 use ipc_channel::ipc::{self, IpcReceiver, IpcSender};
 use ipc_channel::router::ROUTER;
 use layout::context::LayoutContext;
@@ -41,7 +41,8 @@ use layout::query::{
 use layout::query::{process_element_inner_text_query, process_node_geometry_request};
 use layout::query::{process_node_scroll_area_request, process_node_scroll_id_request};
 use layout::query::{
-    process_offset_parent_query, process_resolved_style_request, process_style_query,
+    process_offset_parent_query, process_resolved_font_style_request,
+    process_resolved_style_request, process_style_query,
     process_text_index_request,
 };
 use layout::traversal::RecalcStyle;
@@ -59,6 +60,7 @@ use net_traits::image_cache::ImageCache;
 use profile_traits::mem::{self as profile_mem, Report, ReportKind, ReportsChan};
 use profile_traits::time::{self as profile_time, profile, TimerMetadata};
 use profile_traits::time::{TimerMetadataFrameType, TimerMetadataReflowType};
+use script_layout_interface::message::WebFontSource;
 use script_layout_interface::message::{LayoutThreadInit, Msg, NodesFromPointQueryType};
 use script_layout_interface::message::{QueryMsg, ReflowComplete, ReflowGoal, ScriptReflow};
 use script_layout_interface::rpc::TextIndexResponse;
@@ -130,10 +132,10 @@ pub struct LayoutThread {
     pipeline_port: Receiver<LayoutControlMsg>,
 
     /// The port on which we receive messages from the font cache thread.
-    font_cache_receiver: Receiver<()>,
+    font_cache_receiver: Receiver<WebFontLoadResult>,
 
     /// The channel on which the font cache can send messages to us.
-    font_cache_sender: IpcSender<()>,
+    font_cache_sender: IpcSender<WebFontLoadResult>,
 
     /// A means of communication with the background hang monitor.
     background_hang_monitor: Box<dyn BackgroundHangMonitor>,
@@ -401,10 +403,11 @@ fn add_font_face_rules(
     guard: &SharedRwLockReadGuard,
     device: &Device,
     font_cache_thread: &FontCacheThread,
-    font_cache_sender: &IpcSender<()>,
+    font_cache_sender: &IpcSender<WebFontLoadResult>,
     outstanding_web_fonts_counter: &Arc<AtomicUsize>,
     load_webfonts_synchronously: bool,
-) {
+) -> Vec<WebFontLoadResult> {
+    let mut results = vec![];
     if load_webfonts_synchronously {
         let (sender, receiver) = ipc::channel().unwrap();
         stylesheet.effective_font_face_rules(&device, guard, |rule| {
@@ -415,7 +418,7 @@ fn add_font_face_rules(
                     effective_sources,
                     sender.clone(),
                 );
-                receiver.recv().unwrap();
+                results.push(receiver.recv().unwrap());
             }
         })
     } else {
@@ -431,6 +434,7 @@ fn add_font_face_rules(
             }
         })
     }
+    results
 }
 
 impl LayoutThread {
@@ -512,6 +516,7 @@ impl LayoutThread {
                 scroll_id_response: None,
                 scroll_area_response: Rect::zero(),
                 resolved_style_response: String::new(),
+                resolved_font_style_response: None,
                 offset_parent_response: OffsetParentResponse::empty(),
                 style_response: StyleResponse(None),
                 scroll_offsets: HashMap::new(),
@@ -598,6 +603,14 @@ impl LayoutThread {
             Msg::RegisterPaint(..) => LayoutHangAnnotation::RegisterPaint,
             Msg::SetNavigationStart(..) => LayoutHangAnnotation::SetNavigationStart,
             Msg::GetRunningAnimations(..) => LayoutHangAnnotation::GetRunningAnimations,
+            Msg::UpdateScriptAnimation(..) => LayoutHangAnnotation::UpdateScriptAnimation,
+            Msg::CancelAnimation(..) => LayoutHangAnnotation::CancelAnimation,
+            Msg::SetAnimationPaused(..) => LayoutHangAnnotation::SetAnimationPaused,
+            Msg::CancelTransition(..) => LayoutHangAnnotation::CancelTransition,
+            Msg::SetTransitionTime(..) => LayoutHangAnnotation::SetTransitionTime,
+            Msg::GetAnimations(..) => LayoutHangAnnotation::GetAnimations,
+            Msg::LoadWebFont(..) => LayoutHangAnnotation::LoadWebFont,
+            Msg::RegisterCustomProperty(..) => LayoutHangAnnotation::RegisterCustomProperty,
         };
         self.background_hang_monitor
             .notify_activity(HangAnnotation::Layout(hang_annotation));
@@ -608,7 +621,7 @@ impl LayoutThread {
         enum Request {
             FromPipeline(LayoutControlMsg),
             FromScript(Msg),
-            FromFontCache,
+            FromFontCache(WebFontLoadResult),
         }
 
         // Notify the background-hang-monitor we are waiting for an event.
@@ -617,7 +630,7 @@ impl LayoutThread {
         let request = select! {
             recv(self.pipeline_port) -> msg => Request::FromPipeline(msg.unwrap()),
             recv(self.port) -> msg => Request::FromScript(msg.unwrap()),
-            recv(self.font_cache_receiver) -> msg => { msg.unwrap(); Request::FromFontCache }
+            recv(self.font_cache_receiver) -> msg => Request::FromFontCache(msg.unwrap()),
         };
 
         self.busy.store(true, Ordering::Relaxed);
@@ -643,12 +656,12 @@ impl LayoutThread {
                 true
             },
             Request::FromScript(msg) => self.handle_request_helper(msg, possibly_locked_rw_data),
-            Request::FromFontCache => {
+            Request::FromFontCache(result) => {
                 let _rw_data = possibly_locked_rw_data.lock();
                 self.outstanding_web_fonts.fetch_sub(1, Ordering::SeqCst);
                 font_context::invalidate_font_caches();
                 self.script_chan
-                    .send(ConstellationControlMsg::WebFontLoaded(self.id))
+                    .send(ConstellationControlMsg::WebFontLoaded(self.id, result))
                     .unwrap();
                 true
             },
@@ -760,6 +773,29 @@ impl LayoutThread {
             Msg::GetRunningAnimations(sender) => {
                 let _ = sender.send(0);
             },
+            Msg::UpdateScriptAnimation(..) |
+            Msg::CancelAnimation(..) |
+            Msg::SetAnimationPaused(..) |
+            Msg::CancelTransition(..) |
+            Msg::SetTransitionTime(..) => {},
+            Msg::GetAnimations(_, sender) => {
+                let _ = sender.send(vec![]);
+            },
+            Msg::LoadWebFont(family, source) => {
+                self.outstanding_web_fonts.fetch_add(1, Ordering::SeqCst);
+                let sender = self.font_cache_sender.clone();
+                match source {
+                    WebFontSource::Sources(sources) => {
+                        self.font_cache_thread.add_web_font(family, sources, sender)
+                    },
+                    WebFontSource::Data(bytes) => self
+                        .font_cache_thread
+                        .add_web_font_data(family, bytes, sender),
+                }
+            },
+            Msg::RegisterCustomProperty(registration) => {
+                self.stylist.register_custom_property(registration);
+            },
         }
 
         true
@@ -857,7 +893,7 @@ impl LayoutThread {
         // Find all font-face rules and notify the font cache of them.
         // GWTODO: Need to handle unloading web fonts.
         if stylesheet.is_effective_for_device(self.stylist.device(), &guard) {
-            add_font_face_rules(
+            let loaded = add_font_face_rules(
                 &*stylesheet,
                 &guard,
                 self.stylist.device(),
@@ -866,6 +902,13 @@ impl LayoutThread {
                 &self.outstanding_web_fonts,
                 self.load_webfonts_synchronously,
             );
+            // Let script know about fonts that were loaded synchronously, so that
+            // the corresponding `FontFace` objects can settle.
+            for result in loaded {
+                let _ = self
+                    .script_chan
+                    .send(ConstellationControlMsg::WebFontLoaded(self.id, result));
+            }
         }
     }
 
@@ -920,6 +963,9 @@ impl LayoutThread {
                         &QueryMsg::ResolvedStyleQuery(_, _, _) => {
                             rw_data.resolved_style_response = String::new();
                         },
+                        &QueryMsg::ResolvedFontStyleQuery(_, _, _) => {
+                            rw_data.resolved_font_style_response = None;
+                        },
                         &QueryMsg::OffsetParentQuery(_) => {
                             rw_data.offset_parent_response = OffsetParentResponse::empty();
                         },
@@ -962,7 +1008,8 @@ impl LayoutThread {
         };
 
         let had_used_viewport_units = self.stylist.device().used_viewport_units();
-        let device = Device::new(MediaType::screen(), initial_viewport, device_pixel_ratio);
+        let mut device = Device::new(MediaType::screen(), initial_viewport, device_pixel_ratio);
+        device.set_media_preferences(data.media_preferences);
         let sheet_origins_affected_by_device_change = self.stylist.set_device(device, &guards);
 
         self.stylist
@@ -1142,6 +1189,11 @@ impl LayoutThread {
                     rw_data.resolved_style_response =
                         process_resolved_style_request(context, node, pseudo, property);
                 },
+                &QueryMsg::ResolvedFontStyleQuery(node, ref property, ref value) => {
+                    let node = unsafe { ServoLayoutNode::new(&node) };
+                    rw_data.resolved_font_style_response =
+                        process_resolved_font_style_request(context, node, value, property);
+                },
                 &QueryMsg::OffsetParentQuery(node) => {
                     rw_data.offset_parent_response = process_offset_parent_query(node);
                 },
@@ -1356,6 +1408,14 @@ fn get_ua_stylesheets() -> Result<UserAgentStylesheets, &'static str> {
         )?,
     ];
 
+    // This layout does not render SVG content, so regardless of `dom.svg.enabled` the
+    // children of <svg> elements must not be laid out as HTML.
+    user_or_user_agent_stylesheets.push(parse_ua_stylesheet(
+        &shared_lock,
+        "svg-fallback.css",
+        &resources::read_bytes(Resource::SVGFallbackCSS),
+    )?);
+
     for &(ref contents, ref url) in &opts::get().user_stylesheets {
         user_or_user_agent_stylesheets.push(DocumentStyleSheet(ServoArc::new(
             Stylesheet::from_bytes(
@@ -388,6 +388,14 @@ pub enum LayoutHangAnnotation {
     RegisterPaint,
     SetNavigationStart,
     GetRunningAnimations,
+    UpdateScriptAnimation,
+    CancelAnimation,
+    SetAnimationPaused,
+    CancelTransition,
+    SetTransitionTime,
+    GetAnimations,
+    LoadWebFont,
+    RegisterCustomProperty,
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Serialize)]
@@ -151,6 +151,9 @@ impl FileManager {
                     })
                     .expect("Thread spawning failed");
             },
+            FileManagerThreadMsg::SelectDroppedFiles(paths, sender, origin) => {
+                let _ = sender.send(self.store.select_dropped_files(&paths, &origin));
+            },
             FileManagerThreadMsg::ReadFile(sender, id, check_url_validity, origin) => {
                 self.read_file(sender, id, check_url_validity, origin);
             },
@@ -363,6 +366,17 @@ impl FileManagerStore {
         }
     }
 
+    fn select_dropped_files(
+        &self,
+        paths: &[PathBuf],
+        origin: &str,
+    ) -> Result<Vec<SelectedFile>, FileManagerThreadError> {
+        paths
+            .iter()
+            .map(|path| self.create_entry(path, origin))
+            .collect()
+    }
+
     fn create_entry(
         &self,
         file_path: &Path,
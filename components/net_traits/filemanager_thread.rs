@@ -93,7 +93,7 @@ impl RelativePos {
 }
 
 /// Response to file selection request
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct SelectedFile {
     pub id: Uuid,
     pub filename: PathBuf,
@@ -121,6 +121,14 @@ pub enum FileManagerThreadMsg {
         Option<Vec<String>>,
     ),
 
+    /// Select files the user dropped from the embedder. This is only sent by
+    /// the constellation, since script must not pick arbitrary paths.
+    SelectDroppedFiles(
+        Vec<PathBuf>,
+        IpcSender<FileManagerResult<Vec<SelectedFile>>>,
+        FileOrigin,
+    ),
+
     /// Read FileID-indexed file in chunks, optionally check URL validity based on boolean flag
     ReadFile(
         IpcSender<FileManagerResult<ReadFileProgress>>,
@@ -2,7 +2,7 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
-use embedder_traits::EmbedderMsg;
+use embedder_traits::{ClipboardData, EmbedderMsg};
 use ipc_channel::ipc::channel;
 use script_traits::{ScriptMsg, ScriptToConstellationChan};
 
@@ -11,6 +11,14 @@ pub trait ClipboardProvider {
     fn clipboard_contents(&mut self) -> String;
     // blocking method to set the clipboard contents
     fn set_clipboard_contents(&mut self, _: String);
+    // blocking method to get the clipboard contents in every format they are available in
+    fn clipboard_data(&mut self) -> ClipboardData {
+        ClipboardData::from_text(self.clipboard_contents())
+    }
+    // blocking method to set the clipboard contents in several formats
+    fn set_clipboard_data(&mut self, data: ClipboardData) {
+        self.set_clipboard_contents(data.text().unwrap_or_default())
+    }
 }
 
 impl ClipboardProvider for ScriptToConstellationChan {
@@ -28,4 +36,18 @@ impl ClipboardProvider for ScriptToConstellationChan {
         ))
         .unwrap();
     }
+    fn clipboard_data(&mut self) -> ClipboardData {
+        let (tx, rx) = channel().unwrap();
+        self.send(ScriptMsg::ForwardToEmbedder(EmbedderMsg::GetClipboardData(
+            tx,
+        )))
+        .unwrap();
+        rx.recv().unwrap()
+    }
+    fn set_clipboard_data(&mut self, data: ClipboardData) {
+        self.send(ScriptMsg::ForwardToEmbedder(EmbedderMsg::SetClipboardData(
+            data,
+        )))
+        .unwrap();
+    }
 }
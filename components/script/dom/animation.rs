@@ -0,0 +1,1144 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::animationeffect::{AnimationEffect, EffectTimingValues};
+use crate::dom::animationplaybackevent::AnimationPlaybackEvent;
+use crate::dom::animationtimeline::AnimationTimeline;
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::AnimationBinding::{
+    self, AnimationMethods, AnimationPlayState,
+};
+use crate::dom::bindings::codegen::Bindings::AnimationEffectBinding::PlaybackDirection;
+use crate::dom::bindings::codegen::Bindings::AnimationPlaybackEventBinding::AnimationPlaybackEventInit;
+use crate::dom::bindings::codegen::Bindings::DocumentBinding::DocumentMethods;
+use crate::dom::bindings::codegen::Bindings::EventBinding::EventInit;
+use crate::dom::bindings::codegen::Bindings::EventHandlerBinding::EventHandlerNonNull;
+use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
+use crate::dom::bindings::error::{Error, ErrorResult, Fallible};
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::num::Finite;
+use crate::dom::bindings::refcounted::Trusted;
+use crate::dom::bindings::reflector::{reflect_dom_object, DomObject};
+use crate::dom::bindings::root::{DomRoot, MutNullableDom};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::element::Element;
+use crate::dom::event::Event;
+use crate::dom::eventtarget::EventTarget;
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::keyframeeffect::KeyframeEffect;
+use crate::dom::node::{window_from_node, Node, NodeDamage};
+use crate::dom::promise::Promise;
+use crate::dom::window::Window;
+use crate::task_source::TaskSource;
+use crate::timers::{OneshotTimerCallback, OneshotTimerHandle};
+use dom_struct::dom_struct;
+use script_layout_interface::message::{Msg, RunningAnimationInfo};
+use script_traits::MsDuration;
+use servo_arc::Arc;
+use servo_atoms::Atom;
+use std::cell::Cell;
+use std::f64;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use style::animation::{Animation as StyleAnimation, KeyframesAnimationOrigin};
+use style::animation::{KeyframesAnimationState, KeyframesIterationState, KeyframesRunningState};
+use style::properties::longhands::animation_direction::computed_value::single_value::T as AnimationDirection;
+use style::properties::ComputedValues;
+
+/// Used to give every script animation a distinct name in layout.
+static NEXT_SCRIPT_ANIMATION_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Where the effect of an animation is computed.
+#[derive(Clone, Copy, Debug, JSTraceable, MallocSizeOf, PartialEq)]
+pub enum AnimationOrigin {
+    /// An animation created by script, whose keyframes are sent to layout.
+    Script,
+    /// A CSS animation started by layout, which script only reflects and
+    /// pauses or cancels.
+    CssAnimation,
+    /// A CSS transition started by layout, which script reflects and pauses,
+    /// seeks or cancels.
+    CssTransition,
+}
+
+/// <https://drafts.csswg.org/web-animations/#pending-play-task>
+#[derive(Clone, Copy, Debug, JSTraceable, MallocSizeOf, PartialEq)]
+enum PendingTask {
+    None,
+    Play,
+    Pause,
+}
+
+#[dom_struct]
+pub struct Animation {
+    eventtarget: EventTarget,
+    id: DomRefCell<DOMString>,
+    effect: MutNullableDom<AnimationEffect>,
+    timeline: MutNullableDom<AnimationTimeline>,
+    /// The start time, in milliseconds of timeline time.
+    start_time: Cell<Option<f64>>,
+    /// The hold time, in milliseconds.
+    hold_time: Cell<Option<f64>>,
+    playback_rate: Cell<f64>,
+    /// <https://drafts.csswg.org/web-animations/#previous-current-time>
+    previous_current_time: Cell<Option<f64>>,
+    pending_task: Cell<PendingTask>,
+    /// Incremented whenever the pending task is canceled, so that the queued
+    /// task can tell it is stale.
+    pending_task_generation: Cell<u32>,
+    #[ignore_malloc_size_of = "promises are hard"]
+    ready_promise: DomRefCell<Rc<Promise>>,
+    #[ignore_malloc_size_of = "promises are hard"]
+    finished_promise: DomRefCell<Rc<Promise>>,
+    /// The timer that checks the finished state when the animation is
+    /// expected to reach the end of its effect.
+    finish_timer: Cell<Option<OneshotTimerHandle>>,
+    origin: AnimationOrigin,
+    /// The name identifying this animation among the animations of its target
+    /// in layout: the animation name or transitioned property for CSS
+    /// animations and transitions.
+    name: Atom,
+}
+
+impl Animation {
+    pub fn new_inherited(
+        global: &GlobalScope,
+        timeline: Option<&AnimationTimeline>,
+        origin: AnimationOrigin,
+        name: Atom,
+    ) -> Animation {
+        Animation {
+            eventtarget: EventTarget::new_inherited(),
+            id: DomRefCell::new(DOMString::new()),
+            effect: Default::default(),
+            timeline: MutNullableDom::new(timeline),
+            start_time: Cell::new(None),
+            hold_time: Cell::new(None),
+            playback_rate: Cell::new(1.),
+            previous_current_time: Cell::new(None),
+            pending_task: Cell::new(PendingTask::None),
+            pending_task_generation: Cell::new(0),
+            ready_promise: DomRefCell::new(Promise::new(global)),
+            finished_promise: DomRefCell::new(Promise::new(global)),
+            finish_timer: Cell::new(None),
+            origin,
+            name,
+        }
+    }
+
+    pub fn new(
+        window: &Window,
+        effect: Option<&AnimationEffect>,
+        timeline: Option<&AnimationTimeline>,
+    ) -> DomRoot<Animation> {
+        let id = NEXT_SCRIPT_ANIMATION_ID.fetch_add(1, Ordering::Relaxed);
+        let animation = reflect_dom_object(
+            Box::new(Animation::new_inherited(
+                window.upcast(),
+                timeline,
+                AnimationOrigin::Script,
+                Atom::from(format!("-servo-script-animation-{}", id)),
+            )),
+            window,
+            AnimationBinding::Wrap,
+        );
+        animation.initialize(effect);
+        animation
+    }
+
+    /// Finishes setting up a newly reflected animation.
+    pub fn initialize(&self, effect: Option<&AnimationEffect>) {
+        // The current ready promise is initially resolved.
+        self.resolve_ready_promise();
+        self.SetEffect(effect);
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-animation-animation
+    pub fn Constructor(
+        window: &Window,
+        effect: Option<&AnimationEffect>,
+        timeline: Option<Option<&AnimationTimeline>>,
+    ) -> Fallible<DomRoot<Animation>> {
+        let document_timeline = window.Document().Timeline();
+        let timeline = timeline.unwrap_or(Some(document_timeline.upcast()));
+        Ok(Animation::new(window, effect, timeline))
+    }
+
+    pub fn origin(&self) -> AnimationOrigin {
+        self.origin
+    }
+
+    pub fn name(&self) -> &Atom {
+        &self.name
+    }
+
+    /// The target element of the keyframe effect of this animation, if any.
+    pub fn target(&self) -> Option<DomRoot<Element>> {
+        self.effect
+            .get()
+            .and_then(|effect| DomRoot::downcast::<KeyframeEffect>(effect))
+            .and_then(|effect| effect.target())
+    }
+
+    /// <https://drafts.csswg.org/web-animations/#relevant-animations-section>
+    pub fn is_relevant(&self) -> bool {
+        self.effect
+            .get()
+            .map_or(false, |effect| effect.is_relevant())
+    }
+
+    fn timeline_time(&self) -> Option<f64> {
+        self.timeline
+            .get()
+            .and_then(|timeline| timeline.current_time())
+    }
+
+    /// The current time computed from the start time, ignoring the hold time.
+    fn time_from_start_time(&self) -> Option<f64> {
+        let timeline_time = self.timeline_time()?;
+        let start_time = self.start_time.get()?;
+        Some((timeline_time - start_time) * self.playback_rate.get())
+    }
+
+    /// <https://drafts.csswg.org/web-animations/#the-current-time-of-an-animation>
+    pub fn current_time(&self) -> Option<f64> {
+        match self.hold_time.get() {
+            Some(hold_time) => Some(hold_time),
+            None => self.time_from_start_time(),
+        }
+    }
+
+    /// <https://drafts.csswg.org/web-animations/#effect-end>
+    fn effect_end(&self) -> f64 {
+        self.effect
+            .get()
+            .map_or(0., |effect| effect.timing().end_time())
+    }
+
+    /// <https://drafts.csswg.org/web-animations/#play-states>
+    fn play_state(&self) -> AnimationPlayState {
+        let current_time = self.current_time();
+        let pending_task = self.pending_task.get();
+        if current_time.is_none() &&
+            self.start_time.get().is_none() &&
+            pending_task == PendingTask::None
+        {
+            return AnimationPlayState::Idle;
+        }
+        if pending_task == PendingTask::Pause ||
+            (self.start_time.get().is_none() && pending_task != PendingTask::Play)
+        {
+            return AnimationPlayState::Paused;
+        }
+        let rate = self.playback_rate.get();
+        match current_time {
+            Some(current_time)
+                if (rate > 0. && current_time >= self.effect_end()) ||
+                    (rate < 0. && current_time <= 0.) =>
+            {
+                AnimationPlayState::Finished
+            },
+            _ => AnimationPlayState::Running,
+        }
+    }
+
+    /// <https://drafts.csswg.org/web-animations/#silently-set-the-current-time>
+    fn silently_set_current_time(&self, seek_time: Option<f64>) -> ErrorResult {
+        // Step 1.
+        let seek_time = match seek_time {
+            Some(seek_time) => seek_time,
+            None => {
+                if self.current_time().is_some() {
+                    return Err(Error::Type(
+                        "The current time can't be set to null".to_owned(),
+                    ));
+                }
+                return Ok(());
+            },
+        };
+
+        // Step 2.
+        let timeline_time = self.timeline_time();
+        let rate = self.playback_rate.get();
+        match timeline_time {
+            Some(timeline_time)
+                if self.hold_time.get().is_none() &&
+                    self.start_time.get().is_some() &&
+                    rate != 0. =>
+            {
+                self.start_time.set(Some(timeline_time - seek_time / rate));
+            },
+            _ => self.hold_time.set(Some(seek_time)),
+        }
+
+        // Step 3.
+        if timeline_time.is_none() {
+            self.start_time.set(None);
+        }
+
+        // Step 4.
+        self.previous_current_time.set(None);
+        Ok(())
+    }
+
+    /// <https://drafts.csswg.org/web-animations/#set-the-current-time>
+    fn set_current_time(&self, seek_time: Option<f64>) -> ErrorResult {
+        // Step 1.
+        self.silently_set_current_time(seek_time)?;
+
+        // Step 2.
+        if self.pending_task.get() == PendingTask::Pause {
+            self.hold_time.set(seek_time);
+            self.start_time.set(None);
+            self.cancel_pending_task();
+            self.resolve_ready_promise();
+        }
+
+        // Step 3.
+        self.update_finished_state(true, false);
+        Ok(())
+    }
+
+    /// <https://drafts.csswg.org/web-animations/#set-the-playback-rate>
+    fn set_playback_rate(&self, rate: f64) {
+        let previous_time = self.current_time();
+        self.playback_rate.set(rate);
+        if previous_time.is_some() {
+            // This can't fail, since the time is not null.
+            let _ = self.set_current_time(previous_time);
+        }
+    }
+
+    fn resolve_ready_promise(&self) {
+        self.ready_promise
+            .borrow()
+            .resolve_native(&DomRoot::from_ref(self));
+    }
+
+    fn replace_ready_promise(&self) {
+        *self.ready_promise.borrow_mut() = Promise::new(&self.global());
+    }
+
+    fn cancel_pending_task(&self) {
+        self.pending_task.set(PendingTask::None);
+        self.pending_task_generation
+            .set(self.pending_task_generation.get().wrapping_add(1));
+    }
+
+    /// Schedules a pending play or pause task, which runs as soon as possible
+    /// once the timeline of the animation is active.
+    fn schedule_pending_task(&self, task: PendingTask) {
+        self.pending_task.set(task);
+        let generation = self.pending_task_generation.get();
+        let this = Trusted::new(self);
+        let global = self.global();
+        let window = global.as_window();
+        let _ = window.task_manager().dom_manipulation_task_source().queue(
+            task!(animation_pending_task: move || {
+                let this = this.root();
+                if this.pending_task_generation.get() != generation {
+                    return;
+                }
+                this.run_pending_task();
+            }),
+            window.upcast(),
+        );
+    }
+
+    /// <https://drafts.csswg.org/web-animations/#pending-play-task>
+    /// <https://drafts.csswg.org/web-animations/#pending-pause-task>
+    fn run_pending_task(&self) {
+        let ready_time = match self.timeline_time() {
+            Some(ready_time) => ready_time,
+            None => return,
+        };
+        let rate = self.playback_rate.get();
+        match self.pending_task.get() {
+            PendingTask::Play => {
+                if let Some(hold_time) = self.hold_time.get() {
+                    if rate == 0. {
+                        self.start_time.set(Some(ready_time));
+                    } else {
+                        self.start_time.set(Some(ready_time - hold_time / rate));
+                        self.hold_time.set(None);
+                    }
+                }
+            },
+            PendingTask::Pause => {
+                if let (Some(start_time), None) = (self.start_time.get(), self.hold_time.get()) {
+                    self.hold_time.set(Some((ready_time - start_time) * rate));
+                }
+                self.start_time.set(None);
+            },
+            PendingTask::None => return,
+        }
+        self.pending_task.set(PendingTask::None);
+        self.resolve_ready_promise();
+        self.update_finished_state(false, false);
+    }
+
+    /// <https://drafts.csswg.org/web-animations/#play-an-animation>
+    fn play(&self, auto_rewind: bool) -> ErrorResult {
+        // Step 1.
+        let abort_pause = self.pending_task.get() == PendingTask::Pause;
+        // Step 2.
+        let mut has_pending_ready_promise = false;
+
+        // Step 3.
+        let rate = self.playback_rate.get();
+        let current_time = self.current_time();
+        let end = self.effect_end();
+        let mut seek_time = None;
+        if rate > 0. && auto_rewind && current_time.map_or(true, |time| time < 0. || time >= end) {
+            seek_time = Some(0.);
+        } else if rate < 0. &&
+            auto_rewind &&
+            current_time.map_or(true, |time| time <= 0. || time > end)
+        {
+            if end.is_infinite() {
+                return Err(Error::InvalidState);
+            }
+            seek_time = Some(end);
+        } else if rate == 0. && current_time.is_none() {
+            seek_time = Some(0.);
+        }
+
+        // Step 4.
+        if seek_time.is_some() {
+            self.hold_time.set(seek_time);
+        }
+
+        // Step 5.
+        if self.hold_time.get().is_some() {
+            self.start_time.set(None);
+        }
+
+        // Step 6.
+        if self.pending_task.get() != PendingTask::None {
+            self.cancel_pending_task();
+            has_pending_ready_promise = true;
+        }
+
+        // Step 7.
+        if self.hold_time.get().is_none() && seek_time.is_none() && !abort_pause {
+            return Ok(());
+        }
+
+        // Step 8.
+        if !has_pending_ready_promise {
+            self.replace_ready_promise();
+        }
+
+        // Step 9.
+        self.schedule_pending_task(PendingTask::Play);
+
+        // Step 10.
+        self.update_finished_state(false, false);
+        Ok(())
+    }
+
+    /// <https://drafts.csswg.org/web-animations/#update-an-animations-finished-state>
+    fn update_finished_state(&self, did_seek: bool, synchronously: bool) {
+        // Step 1.
+        let unconstrained_current_time = if did_seek {
+            self.current_time()
+        } else {
+            self.time_from_start_time()
+        };
+
+        // Step 2.
+        if let (Some(current_time), Some(_)) = (unconstrained_current_time, self.start_time.get()) {
+            if self.pending_task.get() == PendingTask::None {
+                let rate = self.playback_rate.get();
+                let end = self.effect_end();
+                let previous_time = self.previous_current_time.get();
+                if rate > 0. && current_time >= end {
+                    self.hold_time.set(Some(match previous_time {
+                        Some(previous_time) if !did_seek => previous_time.max(end),
+                        _ if !did_seek => end,
+                        _ => current_time,
+                    }));
+                } else if rate < 0. && current_time <= 0. {
+                    self.hold_time.set(Some(match previous_time {
+                        Some(previous_time) if !did_seek => previous_time.min(0.),
+                        _ if !did_seek => 0.,
+                        _ => current_time,
+                    }));
+                } else if rate != 0. {
+                    if let Some(timeline_time) = self.timeline_time() {
+                        if let (true, Some(hold_time)) = (did_seek, self.hold_time.get()) {
+                            self.start_time.set(Some(timeline_time - hold_time / rate));
+                        }
+                        self.hold_time.set(None);
+                    }
+                }
+            }
+        }
+
+        // Step 3.
+        self.previous_current_time.set(self.current_time());
+
+        // Step 4.
+        let finished = self.play_state() == AnimationPlayState::Finished;
+
+        // Steps 5 and 6.
+        let notified = self.finished_promise.borrow().is_fulfilled();
+        if finished && !notified {
+            if synchronously {
+                self.finish_notification();
+            } else {
+                let this = Trusted::new(self);
+                let global = self.global();
+                let window = global.as_window();
+                let _ = window.task_manager().dom_manipulation_task_source().queue(
+                    task!(animation_finish_notification: move || {
+                        let this = this.root();
+                        if this.play_state() == AnimationPlayState::Finished &&
+                            !this.finished_promise.borrow().is_fulfilled()
+                        {
+                            this.finish_notification();
+                        }
+                    }),
+                    window.upcast(),
+                );
+            }
+        }
+
+        // Step 7.
+        if !finished && notified {
+            *self.finished_promise.borrow_mut() = Promise::new(&self.global());
+        }
+
+        self.update_layout_animation();
+        self.schedule_finish_timer();
+    }
+
+    /// <https://drafts.csswg.org/web-animations/#finish-notification-steps>
+    fn finish_notification(&self) {
+        self.finished_promise
+            .borrow()
+            .resolve_native(&DomRoot::from_ref(self));
+        self.queue_playback_event(atom!("finish"), self.current_time());
+    }
+
+    /// Queues a task to fire an `AnimationPlaybackEvent` at this animation.
+    fn queue_playback_event(&self, type_: Atom, current_time: Option<f64>) {
+        let timeline_time = self.timeline_time();
+        let this = Trusted::new(self);
+        let global = self.global();
+        let window = global.as_window();
+        let _ = window.task_manager().dom_manipulation_task_source().queue(
+            task!(fire_animation_playback_event: move || {
+                let this = this.root();
+                let global = this.global();
+                let init = AnimationPlaybackEventInit {
+                    parent: EventInit {
+                        bubbles: false,
+                        cancelable: false,
+                    },
+                    currentTime: current_time.map(Finite::wrap),
+                    timelineTime: timeline_time.map(Finite::wrap),
+                };
+                let event = AnimationPlaybackEvent::new(global.as_window(), type_, &init);
+                event.upcast::<Event>().fire(this.upcast());
+            }),
+            window.upcast(),
+        );
+    }
+
+    /// Schedules a timer to update the finished state once the animation is
+    /// expected to reach the end of its effect, since nothing else would.
+    fn schedule_finish_timer(&self) {
+        let global = self.global();
+        if let Some(handle) = self.finish_timer.take() {
+            global.unschedule_callback(handle);
+        }
+        if self.play_state() != AnimationPlayState::Running {
+            return;
+        }
+        let current_time = match self.current_time() {
+            Some(current_time) => current_time,
+            None => return,
+        };
+        let rate = self.playback_rate.get();
+        let remaining = if rate > 0. {
+            (self.effect_end() - current_time) / rate
+        } else if rate < 0. {
+            current_time / -rate
+        } else {
+            return;
+        };
+        if !remaining.is_finite() {
+            return;
+        }
+        let callback = AnimationFinishedCallback {
+            animation: Trusted::new(self),
+        };
+        let handle = global.schedule_callback(
+            OneshotTimerCallback::AnimationFinished(callback),
+            MsDuration::new(remaining.max(0.).ceil() as u64),
+        );
+        self.finish_timer.set(Some(handle));
+    }
+
+    /// Called when the timing or the keyframes of the effect of this animation
+    /// changed.
+    pub fn timing_updated(&self) {
+        self.update_finished_state(false, false);
+    }
+
+    /// Called when the target of the effect of this animation changed.
+    pub fn effect_target_changed(&self, old_target: Option<&Element>) {
+        if let Some(old_target) = old_target {
+            self.cancel_layout_animation(old_target);
+        }
+        self.update_layout_animation();
+    }
+
+    fn cancel_layout_animation(&self, target: &Element) {
+        let node = target.upcast::<Node>();
+        let window = window_from_node(node);
+        let msg = match self.origin {
+            AnimationOrigin::CssTransition => {
+                Msg::CancelTransition(node.to_opaque(), self.name.clone())
+            },
+            AnimationOrigin::Script | AnimationOrigin::CssAnimation => {
+                Msg::CancelAnimation(node.to_opaque(), self.name.clone())
+            },
+        };
+        let _ = window.layout_chan().send(msg);
+        node.dirty(NodeDamage::OtherNodeDamage);
+    }
+
+    /// Sends the state of this animation to layout, which applies its effect
+    /// to the style of its target.
+    fn update_layout_animation(&self) {
+        let target = match self.target() {
+            Some(target) => target,
+            None => return,
+        };
+        let node = target.upcast::<Node>();
+        let window = window_from_node(node);
+        let document = window.Document();
+        if self.origin == AnimationOrigin::Script {
+            if self.play_state() == AnimationPlayState::Idle {
+                document.remove_script_animation(self);
+            } else {
+                document.add_script_animation(self);
+            }
+        }
+
+        let msg = match self.origin {
+            AnimationOrigin::Script => match self.style_animation(node) {
+                Some(animation) => Msg::UpdateScriptAnimation(animation),
+                None => Msg::CancelAnimation(node.to_opaque(), self.name.clone()),
+            },
+            AnimationOrigin::CssAnimation => match self.play_state() {
+                AnimationPlayState::Idle => {
+                    Msg::CancelAnimation(node.to_opaque(), self.name.clone())
+                },
+                AnimationPlayState::Paused => {
+                    Msg::SetAnimationPaused(node.to_opaque(), self.name.clone(), true)
+                },
+                AnimationPlayState::Running | AnimationPlayState::Finished => {
+                    Msg::SetAnimationPaused(node.to_opaque(), self.name.clone(), false)
+                },
+            },
+            // Layout plays transitions forwards at their normal speed, from
+            // the time they are seeked to.
+            AnimationOrigin::CssTransition => match (self.play_state(), self.current_time()) {
+                (AnimationPlayState::Idle, _) | (_, None) => {
+                    Msg::CancelTransition(node.to_opaque(), self.name.clone())
+                },
+                (play_state, Some(current_time)) => Msg::SetTransitionTime(
+                    node.to_opaque(),
+                    self.name.clone(),
+                    current_time / 1000.,
+                    play_state == AnimationPlayState::Paused,
+                ),
+            },
+        };
+        let _ = window.layout_chan().send(msg);
+        node.dirty(NodeDamage::OtherNodeDamage);
+    }
+
+    /// Maps the timing of a script animation to the state of a keyframes
+    /// animation in layout, or returns `None` if it has no effect.
+    ///
+    /// Layout plays the animation forwards from the state it gets until it
+    /// ends, so a new state is sent whenever the animation is paused, seeked
+    /// or finished.
+    fn style_animation(&self, node: &Node) -> Option<StyleAnimation> {
+        if self.play_state() == AnimationPlayState::Idle {
+            return None;
+        }
+        let effect = self.effect.get()?;
+        let keyframe_effect = effect.downcast::<KeyframeEffect>()?;
+        let timing = effect.timing();
+        let duration = timing.iteration_duration();
+        if duration == 0. || timing.iterations == 0. {
+            return None;
+        }
+        let local_time = self.current_time()?;
+        let keyframes = keyframe_effect.style_animation(&window_from_node(node).Document())?;
+
+        let rate = self.playback_rate.get();
+        let running =
+            self.start_time.get().is_some() && self.hold_time.get().is_none() && rate != 0.;
+        let state = if running {
+            running_state(&timing, local_time, rate)
+        } else {
+            let progress = timing.compute(Some(local_time)).directed_progress?;
+            KeyframesAnimationState {
+                started_at: 0.,
+                duration: duration / 1000.,
+                delay: 0.,
+                iteration_state: KeyframesIterationState::Finite(0., 1.),
+                running_state: KeyframesRunningState::Paused(progress),
+                direction: AnimationDirection::Normal,
+                current_direction: AnimationDirection::Normal,
+                expired: false,
+                cascade_style: Arc::new(ComputedValues::initial_values().clone()),
+                origin: KeyframesAnimationOrigin::Script(timing.timing_function.clone()),
+            }
+        };
+
+        Some(StyleAnimation::Keyframes(
+            node.to_opaque(),
+            keyframes,
+            self.name.clone(),
+            state,
+        ))
+    }
+
+    /// Sets up a newly reflected CSS animation or transition from the state
+    /// layout reported, without sending anything back to layout.
+    pub fn initialize_from_layout(&self, effect: &AnimationEffect, info: &RunningAnimationInfo) {
+        self.resolve_ready_promise();
+        effect.set_animation(Some(self));
+        self.effect.set(Some(effect));
+        self.reflect_layout_state(info);
+    }
+
+    /// Updates a CSS animation or transition from the state layout reported.
+    pub fn reflect_layout_state(&self, info: &RunningAnimationInfo) {
+        let duration = info.duration * 1000.;
+        if let Some(effect) = self.effect.get() {
+            effect.set_timing(timing_from_layout(info));
+        }
+
+        let iteration_progress = match info.paused_progress {
+            Some(progress) => progress,
+            None => (info.now - info.started_at) / info.duration,
+        };
+        let current_time = (info.current_iteration + iteration_progress) * duration;
+        self.playback_rate.set(1.);
+        match (info.paused_progress, self.timeline_time()) {
+            (None, Some(timeline_time)) => {
+                self.start_time.set(Some(timeline_time - current_time));
+                self.hold_time.set(None);
+            },
+            _ => {
+                self.start_time.set(None);
+                self.hold_time.set(Some(current_time));
+            },
+        }
+        self.previous_current_time.set(self.current_time());
+        self.schedule_finish_timer();
+    }
+}
+
+/// The timing of the effect of a CSS animation or transition running in
+/// layout.
+pub fn timing_from_layout(info: &RunningAnimationInfo) -> EffectTimingValues {
+    EffectTimingValues {
+        duration: Some(info.duration * 1000.),
+        iterations: info.iterations.unwrap_or(f64::INFINITY),
+        ..Default::default()
+    }
+}
+
+/// The state of a running script animation in layout, whose times are in
+/// seconds, relative to the time layout gets it.
+fn running_state(
+    timing: &EffectTimingValues,
+    local_time: f64,
+    rate: f64,
+) -> KeyframesAnimationState {
+    let duration = timing.iteration_duration();
+    let active_duration = timing.active_duration();
+    let speed = rate.abs();
+
+    // The position in the active interval, clamped to it, and the time left
+    // until the animation gets there.
+    let active_time = local_time - timing.delay;
+    let clamped_active_time = active_time.max(0.).min(active_duration);
+    let wait = if rate > 0. {
+        -active_time
+    } else {
+        active_time - active_duration
+    }
+    .max(0.) /
+        speed;
+
+    let overall_progress = clamped_active_time / duration + timing.iteration_start;
+    let mut iteration = overall_progress.floor();
+    let mut simple_progress = overall_progress - iteration;
+    if simple_progress == 0. && clamped_active_time == active_duration {
+        iteration -= 1.;
+        simple_progress = 1.;
+    }
+
+    // Layout always moves forwards in time, so playing backwards means playing
+    // the keyframes in the opposite direction.
+    let forwards = timing.plays_forwards(iteration) == (rate > 0.);
+    let current_direction = if forwards {
+        AnimationDirection::Normal
+    } else {
+        AnimationDirection::Reverse
+    };
+    let progress = if rate > 0. {
+        simple_progress
+    } else {
+        1. - simple_progress
+    };
+    let direction = match timing.direction {
+        PlaybackDirection::Alternate | PlaybackDirection::Alternate_reverse => {
+            AnimationDirection::Alternate
+        },
+        PlaybackDirection::Normal | PlaybackDirection::Reverse => current_direction,
+    };
+
+    let iteration_state = if rate > 0. && timing.iterations.is_infinite() {
+        KeyframesIterationState::Infinite
+    } else {
+        let remaining = if rate > 0. {
+            timing.iteration_start + timing.iterations - iteration
+        } else {
+            iteration - timing.iteration_start.floor() + 1.
+        };
+        KeyframesIterationState::Finite(0., remaining.ceil() as f32)
+    };
+
+    let scaled_duration = duration / speed / 1000.;
+    KeyframesAnimationState {
+        started_at: wait / 1000. - progress * scaled_duration,
+        duration: scaled_duration,
+        delay: 0.,
+        iteration_state,
+        running_state: KeyframesRunningState::Running,
+        direction,
+        current_direction,
+        expired: false,
+        cascade_style: Arc::new(ComputedValues::initial_values().clone()),
+        origin: KeyframesAnimationOrigin::Script(timing.timing_function.clone()),
+    }
+}
+
+impl AnimationMethods for Animation {
+    // https://drafts.csswg.org/web-animations/#dom-animation-id
+    fn Id(&self) -> DOMString {
+        self.id.borrow().clone()
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-animation-id
+    fn SetId(&self, id: DOMString) {
+        *self.id.borrow_mut() = id;
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-animation-effect
+    fn GetEffect(&self) -> Option<DomRoot<AnimationEffect>> {
+        self.effect.get()
+    }
+
+    // https://drafts.csswg.org/web-animations/#setting-the-associated-effect
+    fn SetEffect(&self, effect: Option<&AnimationEffect>) {
+        let old_effect = self.effect.get();
+        // Step 2.
+        if old_effect
+            .as_ref()
+            .map(|effect| &**effect as *const AnimationEffect) ==
+            effect.map(|effect| effect as *const AnimationEffect)
+        {
+            return;
+        }
+
+        if let Some(ref old_effect) = old_effect {
+            if let Some(target) = self.target() {
+                self.cancel_layout_animation(&target);
+            }
+            old_effect.set_animation(None);
+        }
+
+        // Step 4.
+        if let Some(effect) = effect {
+            if let Some(previous_animation) = effect.animation() {
+                previous_animation.SetEffect(None);
+            }
+            effect.set_animation(Some(self));
+        }
+
+        // Step 5.
+        self.effect.set(effect);
+
+        // Step 6.
+        self.update_finished_state(false, false);
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-animation-timeline
+    fn GetTimeline(&self) -> Option<DomRoot<AnimationTimeline>> {
+        self.timeline.get()
+    }
+
+    // https://drafts.csswg.org/web-animations/#setting-the-timeline
+    fn SetTimeline(&self, timeline: Option<&AnimationTimeline>) {
+        self.timeline.set(timeline);
+        if self.start_time.get().is_some() {
+            self.hold_time.set(None);
+        }
+        self.update_finished_state(false, false);
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-animation-starttime
+    fn GetStartTime(&self) -> Option<Finite<f64>> {
+        self.start_time.get().map(Finite::wrap)
+    }
+
+    // https://drafts.csswg.org/web-animations/#set-the-start-time
+    fn SetStartTime(&self, start_time: Option<Finite<f64>>) {
+        let start_time = start_time.map(|start_time| *start_time);
+        // Step 2.
+        let timeline_time = self.timeline_time();
+        // Step 3.
+        if timeline_time.is_none() && start_time.is_some() {
+            self.hold_time.set(None);
+        }
+        // Step 4.
+        let previous_current_time = self.current_time();
+        // Step 6.
+        self.start_time.set(start_time);
+        // Step 7.
+        if start_time.is_some() {
+            if self.playback_rate.get() != 0. {
+                self.hold_time.set(None);
+            }
+        } else {
+            self.hold_time.set(previous_current_time);
+        }
+        // Step 8.
+        if self.pending_task.get() != PendingTask::None {
+            self.cancel_pending_task();
+            self.resolve_ready_promise();
+        }
+        // Step 9.
+        self.update_finished_state(true, false);
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-animation-currenttime
+    fn GetCurrentTime(&self) -> Option<Finite<f64>> {
+        self.current_time().map(Finite::wrap)
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-animation-currenttime
+    fn SetCurrentTime(&self, current_time: Option<Finite<f64>>) -> ErrorResult {
+        self.set_current_time(current_time.map(|current_time| *current_time))
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-animation-playbackrate
+    fn PlaybackRate(&self) -> Finite<f64> {
+        Finite::wrap(self.playback_rate.get())
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-animation-playbackrate
+    fn SetPlaybackRate(&self, rate: Finite<f64>) {
+        self.set_playback_rate(*rate);
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-animation-playstate
+    fn PlayState(&self) -> AnimationPlayState {
+        self.play_state()
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-animation-pending
+    fn Pending(&self) -> bool {
+        self.pending_task.get() != PendingTask::None
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-animation-ready
+    fn Ready(&self) -> Rc<Promise> {
+        self.ready_promise.borrow().clone()
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-animation-finished
+    fn Finished(&self) -> Rc<Promise> {
+        self.finished_promise.borrow().clone()
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-animation-onfinish
+    event_handler!(finish, GetOnfinish, SetOnfinish);
+
+    // https://drafts.csswg.org/web-animations/#dom-animation-oncancel
+    event_handler!(cancel, GetOncancel, SetOncancel);
+
+    // https://drafts.csswg.org/web-animations/#cancel-an-animation
+    fn Cancel(&self) {
+        // Step 1.
+        if self.play_state() != AnimationPlayState::Idle {
+            // https://drafts.csswg.org/web-animations/#reset-an-animations-pending-tasks
+            if self.pending_task.get() != PendingTask::None {
+                self.cancel_pending_task();
+                self.ready_promise.borrow().reject_error(Error::Abort);
+                self.replace_ready_promise();
+                self.resolve_ready_promise();
+            }
+
+            self.finished_promise.borrow().reject_error(Error::Abort);
+            *self.finished_promise.borrow_mut() = Promise::new(&self.global());
+
+            self.queue_playback_event(atom!("cancel"), None);
+        }
+
+        // Steps 2 and 3.
+        self.hold_time.set(None);
+        self.start_time.set(None);
+        self.previous_current_time.set(None);
+
+        if let Some(handle) = self.finish_timer.take() {
+            self.global().unschedule_callback(handle);
+        }
+        self.update_layout_animation();
+    }
+
+    // https://drafts.csswg.org/web-animations/#finish-an-animation
+    fn Finish(&self) -> ErrorResult {
+        // Step 1.
+        let rate = self.playback_rate.get();
+        let end = self.effect_end();
+        if rate == 0. || (rate > 0. && end.is_infinite()) {
+            return Err(Error::InvalidState);
+        }
+
+        // Steps 2 and 3.
+        let limit = if rate > 0. { end } else { 0. };
+        self.silently_set_current_time(Some(limit))?;
+
+        // Step 4.
+        if self.start_time.get().is_none() {
+            if let Some(timeline_time) = self.timeline_time() {
+                self.start_time.set(Some(timeline_time - limit / rate));
+            }
+        }
+
+        // Step 5.
+        if self.pending_task.get() == PendingTask::Pause && self.start_time.get().is_some() {
+            self.hold_time.set(None);
+            self.cancel_pending_task();
+            self.resolve_ready_promise();
+        }
+
+        // Step 6.
+        if self.pending_task.get() == PendingTask::Play && self.start_time.get().is_some() {
+            self.cancel_pending_task();
+            self.resolve_ready_promise();
+        }
+
+        // Step 7.
+        self.update_finished_state(true, true);
+        Ok(())
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-animation-play
+    fn Play(&self) -> ErrorResult {
+        self.play(true)
+    }
+
+    // https://drafts.csswg.org/web-animations/#pause-an-animation
+    fn Pause(&self) -> ErrorResult {
+        // Step 1.
+        if self.pending_task.get() == PendingTask::Pause {
+            return Ok(());
+        }
+        // Step 2.
+        if self.play_state() == AnimationPlayState::Paused {
+            return Ok(());
+        }
+
+        // Step 3.
+        if self.current_time().is_none() {
+            let seek_time = if self.playback_rate.get() >= 0. {
+                0.
+            } else {
+                let end = self.effect_end();
+                if end.is_infinite() {
+                    return Err(Error::InvalidState);
+                }
+                end
+            };
+            self.hold_time.set(Some(seek_time));
+        }
+
+        // Steps 4 and 5.
+        if self.pending_task.get() == PendingTask::Play {
+            self.cancel_pending_task();
+        } else {
+            self.replace_ready_promise();
+        }
+
+        // Step 6.
+        self.schedule_pending_task(PendingTask::Pause);
+
+        // Step 7.
+        self.update_finished_state(false, false);
+        Ok(())
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-animation-updateplaybackrate
+    //
+    // FIXME: The new rate should only apply once the animation is ready.
+    fn UpdatePlaybackRate(&self, rate: Finite<f64>) {
+        self.set_playback_rate(*rate);
+    }
+
+    // https://drafts.csswg.org/web-animations/#reverse-an-animation
+    fn Reverse(&self) -> ErrorResult {
+        // Step 1.
+        if self.timeline_time().is_none() {
+            return Err(Error::InvalidState);
+        }
+
+        // Steps 2 and 3.
+        let rate = self.playback_rate.get();
+        let previous_time = self.current_time();
+        self.playback_rate.set(-rate);
+        if previous_time.is_some() {
+            self.silently_set_current_time(previous_time)?;
+        }
+
+        // Step 4.
+        if let Err(error) = self.play(true) {
+            self.playback_rate.set(rate);
+            if previous_time.is_some() {
+                self.silently_set_current_time(previous_time)?;
+            }
+            return Err(error);
+        }
+        Ok(())
+    }
+}
+
+/// Updates the finished state of an animation once it is expected to reach
+/// the end of its effect.
+#[derive(JSTraceable, MallocSizeOf)]
+pub struct AnimationFinishedCallback {
+    #[ignore_malloc_size_of = "non-owning"]
+    animation: Trusted<Animation>,
+}
+
+impl AnimationFinishedCallback {
+    pub fn invoke(self) {
+        let animation = self.animation.root();
+        animation.finish_timer.set(None);
+        animation.update_finished_state(false, false);
+    }
+}
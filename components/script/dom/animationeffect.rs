@@ -0,0 +1,473 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::animation::Animation;
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::AnimationEffectBinding::{
+    AnimationEffectMethods, ComputedEffectTiming, EffectTiming, FillMode, OptionalEffectTiming,
+    PlaybackDirection,
+};
+use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
+use crate::dom::bindings::codegen::UnionTypes::UnrestrictedDoubleOrString;
+use crate::dom::bindings::error::{Error, ErrorResult, Fallible};
+use crate::dom::bindings::num::Finite;
+use crate::dom::bindings::reflector::{DomObject, Reflector};
+use crate::dom::bindings::root::{DomRoot, MutNullableDom};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::document::Document;
+use cssparser::{Parser, ParserInput};
+use dom_struct::dom_struct;
+use std::f64;
+use style::animation::timing_function_output;
+use style::parser::{Parse, ParserContext};
+use style::stylesheets::CssRuleType;
+use style::values::computed::TimingFunction;
+use style::values::generics::easing::{TimingFunction as GenericTimingFunction, TimingKeyword};
+use style::values::specified::TimingFunction as SpecifiedTimingFunction;
+use style_traits::ParsingMode;
+
+/// The timing properties of an animation effect. Times are in milliseconds.
+///
+/// <https://drafts.csswg.org/web-animations/#the-effecttiming-dictionaries>
+#[derive(Clone, JSTraceable, MallocSizeOf)]
+pub struct EffectTimingValues {
+    pub delay: f64,
+    pub end_delay: f64,
+    pub fill: FillMode,
+    pub iteration_start: f64,
+    pub iterations: f64,
+    /// The iteration duration, or `None` for `auto`.
+    pub duration: Option<f64>,
+    pub direction: PlaybackDirection,
+    /// The easing as specified, for serialization.
+    pub easing: DOMString,
+    #[ignore_malloc_size_of = "Defined in style"]
+    pub timing_function: TimingFunction,
+}
+
+impl Default for EffectTimingValues {
+    fn default() -> Self {
+        EffectTimingValues {
+            delay: 0.,
+            end_delay: 0.,
+            fill: FillMode::Auto,
+            iteration_start: 0.,
+            iterations: 1.,
+            duration: None,
+            direction: PlaybackDirection::Normal,
+            easing: DOMString::from("linear"),
+            timing_function: GenericTimingFunction::Keyword(TimingKeyword::Linear),
+        }
+    }
+}
+
+impl EffectTimingValues {
+    /// <https://drafts.csswg.org/web-animations/#update-the-timing-properties-of-an-animation-effect>
+    pub fn from_dictionary(document: &Document, timing: &EffectTiming) -> Fallible<Self> {
+        let mut values = EffectTimingValues::default();
+        values.update(
+            document,
+            &OptionalEffectTiming {
+                delay: Some(timing.delay),
+                endDelay: Some(timing.endDelay),
+                fill: Some(timing.fill),
+                iterationStart: Some(timing.iterationStart),
+                iterations: Some(timing.iterations),
+                duration: Some(timing.duration.clone()),
+                direction: Some(timing.direction),
+                easing: Some(timing.easing.clone()),
+            },
+        )?;
+        Ok(values)
+    }
+
+    /// The timing of an effect created with just an iteration duration.
+    pub fn from_duration(document: &Document, duration: f64) -> Fallible<Self> {
+        let mut values = EffectTimingValues::default();
+        values.update(
+            document,
+            &OptionalEffectTiming {
+                delay: None,
+                endDelay: None,
+                fill: None,
+                iterationStart: None,
+                iterations: None,
+                duration: Some(UnrestrictedDoubleOrString::UnrestrictedDouble(duration)),
+                direction: None,
+                easing: None,
+            },
+        )?;
+        Ok(values)
+    }
+
+    /// Validates and applies the members present in `timing`. Nothing is
+    /// changed if any of them is invalid.
+    pub fn update(&mut self, document: &Document, timing: &OptionalEffectTiming) -> ErrorResult {
+        // Step 1.
+        if let Some(iteration_start) = timing.iterationStart {
+            if *iteration_start < 0. {
+                return Err(Error::Type(
+                    "iterationStart must not be negative".to_owned(),
+                ));
+            }
+        }
+        // Step 2.
+        if let Some(iterations) = timing.iterations {
+            if iterations.is_nan() || iterations < 0. {
+                return Err(Error::Type(
+                    "iterations must be a positive number".to_owned(),
+                ));
+            }
+        }
+        // Step 3.
+        let duration = match timing.duration {
+            Some(UnrestrictedDoubleOrString::UnrestrictedDouble(duration)) => {
+                if duration.is_nan() || duration < 0. {
+                    return Err(Error::Type("duration must be a positive number".to_owned()));
+                }
+                Some(Some(duration))
+            },
+            Some(UnrestrictedDoubleOrString::String(ref duration)) => {
+                if &**duration != "auto" {
+                    return Err(Error::Type(
+                        "duration must be a number or 'auto'".to_owned(),
+                    ));
+                }
+                Some(None)
+            },
+            None => None,
+        };
+        // Step 4.
+        let timing_function = match timing.easing {
+            Some(ref easing) => Some(parse_easing(document, easing)?),
+            None => None,
+        };
+
+        // Step 5.
+        if let Some(delay) = timing.delay {
+            self.delay = *delay;
+        }
+        if let Some(end_delay) = timing.endDelay {
+            self.end_delay = *end_delay;
+        }
+        if let Some(fill) = timing.fill {
+            self.fill = fill;
+        }
+        if let Some(iteration_start) = timing.iterationStart {
+            self.iteration_start = *iteration_start;
+        }
+        if let Some(iterations) = timing.iterations {
+            self.iterations = iterations;
+        }
+        if let Some(duration) = duration {
+            self.duration = duration;
+        }
+        if let Some(direction) = timing.direction {
+            self.direction = direction;
+        }
+        if let Some(timing_function) = timing_function {
+            self.easing = timing.easing.clone().unwrap();
+            self.timing_function = timing_function;
+        }
+        Ok(())
+    }
+
+    /// <https://drafts.csswg.org/web-animations/#iteration-duration>
+    pub fn iteration_duration(&self) -> f64 {
+        self.duration.unwrap_or(0.)
+    }
+
+    /// <https://drafts.csswg.org/web-animations/#active-duration>
+    pub fn active_duration(&self) -> f64 {
+        let duration = self.iteration_duration();
+        if duration == 0. || self.iterations == 0. {
+            return 0.;
+        }
+        duration * self.iterations
+    }
+
+    /// <https://drafts.csswg.org/web-animations/#end-time>
+    pub fn end_time(&self) -> f64 {
+        (self.delay + self.active_duration() + self.end_delay).max(0.)
+    }
+
+    /// The fill mode with `auto` resolved, which means `none` for keyframe
+    /// effects.
+    fn resolved_fill(&self) -> FillMode {
+        match self.fill {
+            FillMode::Auto => FillMode::None,
+            fill => fill,
+        }
+    }
+
+    fn fills_backwards(&self) -> bool {
+        match self.resolved_fill() {
+            FillMode::Backwards | FillMode::Both => true,
+            _ => false,
+        }
+    }
+
+    fn fills_forwards(&self) -> bool {
+        match self.resolved_fill() {
+            FillMode::Forwards | FillMode::Both => true,
+            _ => false,
+        }
+    }
+
+    /// Whether the given iteration plays forwards, according to the playback
+    /// direction.
+    pub fn plays_forwards(&self, iteration: f64) -> bool {
+        match self.direction {
+            PlaybackDirection::Normal => true,
+            PlaybackDirection::Reverse => false,
+            PlaybackDirection::Alternate => iteration % 2. == 0.,
+            PlaybackDirection::Alternate_reverse => iteration % 2. != 0.,
+        }
+    }
+
+    /// <https://drafts.csswg.org/web-animations/#calculating-the-active-time>
+    pub fn compute(&self, local_time: Option<f64>) -> ComputedTimingValues {
+        let mut computed = ComputedTimingValues {
+            local_time,
+            ..Default::default()
+        };
+        let local_time = match local_time {
+            Some(local_time) => local_time,
+            None => return computed,
+        };
+
+        let active_duration = self.active_duration();
+        let end_time = self.end_time();
+        let before_active_boundary = self.delay.min(end_time);
+        let active_after_boundary = (self.delay + active_duration).min(end_time);
+
+        let (phase, active_time) = if local_time < before_active_boundary {
+            let active_time = if self.fills_backwards() {
+                Some((local_time - self.delay).max(0.))
+            } else {
+                None
+            };
+            (Phase::Before, active_time)
+        } else if local_time < active_after_boundary {
+            (Phase::Active, Some(local_time - self.delay))
+        } else {
+            let active_time = if self.fills_forwards() {
+                Some((local_time - self.delay).min(active_duration).max(0.))
+            } else {
+                None
+            };
+            (Phase::After, active_time)
+        };
+        computed.active_time = active_time;
+        let active_time = match active_time {
+            Some(active_time) => active_time,
+            None => return computed,
+        };
+
+        // https://drafts.csswg.org/web-animations/#calculating-the-overall-progress
+        let duration = self.iteration_duration();
+        let overall_progress = if duration == 0. {
+            match phase {
+                Phase::Before => 0.,
+                _ => self.iterations,
+            }
+        } else {
+            active_time / duration
+        } + self.iteration_start;
+
+        // https://drafts.csswg.org/web-animations/#calculating-the-simple-iteration-progress
+        let mut simple_iteration_progress = if overall_progress.is_infinite() {
+            self.iteration_start % 1.
+        } else {
+            overall_progress % 1.
+        };
+        if simple_iteration_progress == 0. &&
+            phase != Phase::Before &&
+            active_time == active_duration &&
+            self.iterations != 0.
+        {
+            simple_iteration_progress = 1.;
+        }
+
+        // https://drafts.csswg.org/web-animations/#calculating-the-current-iteration
+        let current_iteration = if phase == Phase::After && self.iterations.is_infinite() {
+            f64::INFINITY
+        } else if simple_iteration_progress == 1. {
+            overall_progress.floor() - 1.
+        } else {
+            overall_progress.floor()
+        };
+
+        // https://drafts.csswg.org/web-animations/#calculating-the-directed-progress
+        let directed_progress = if self.plays_forwards(current_iteration) {
+            simple_iteration_progress
+        } else {
+            1. - simple_iteration_progress
+        };
+
+        // https://drafts.csswg.org/web-animations/#calculating-the-transformed-progress
+        let epsilon = 1. / (200. * duration.max(1.));
+        computed.directed_progress = Some(directed_progress);
+        computed.progress = Some(timing_function_output(
+            &self.timing_function,
+            directed_progress,
+            epsilon,
+        ));
+        computed.current_iteration = Some(current_iteration);
+        computed
+    }
+}
+
+/// <https://drafts.csswg.org/web-animations/#animation-effect-phases-and-states>
+#[derive(Clone, Copy, PartialEq)]
+enum Phase {
+    Before,
+    Active,
+    After,
+}
+
+/// The result of the timing calculations of an animation effect at a given
+/// local time.
+#[derive(Clone, Copy, Default)]
+pub struct ComputedTimingValues {
+    pub local_time: Option<f64>,
+    pub active_time: Option<f64>,
+    /// The iteration progress with the playback direction applied, but not
+    /// the easing.
+    pub directed_progress: Option<f64>,
+    pub progress: Option<f64>,
+    pub current_iteration: Option<f64>,
+}
+
+/// Parses the `easing` member of the timing dictionaries.
+///
+/// <https://drafts.csswg.org/web-animations/#dom-effecttiming-easing>
+pub fn parse_easing(document: &Document, easing: &str) -> Fallible<TimingFunction> {
+    let url = document.url();
+    let context = ParserContext::new_for_cssom(
+        &url,
+        Some(CssRuleType::Style),
+        ParsingMode::DEFAULT,
+        document.quirks_mode(),
+        None,
+        None,
+    );
+    let mut input = ParserInput::new(easing);
+    let mut parser = Parser::new(&mut input);
+    parser
+        .parse_entirely(|input| SpecifiedTimingFunction::parse(&context, input))
+        .map(|timing_function| timing_function.to_computed_value_without_context())
+        .map_err(|_| Error::Type(format!("'{}' is not a valid easing", easing)))
+}
+
+#[dom_struct]
+pub struct AnimationEffect {
+    reflector_: Reflector,
+    timing: DomRefCell<EffectTimingValues>,
+    /// <https://drafts.csswg.org/web-animations/#associated-animation>
+    animation: MutNullableDom<Animation>,
+}
+
+impl AnimationEffect {
+    pub fn new_inherited(timing: EffectTimingValues) -> AnimationEffect {
+        AnimationEffect {
+            reflector_: Reflector::new(),
+            timing: DomRefCell::new(timing),
+            animation: Default::default(),
+        }
+    }
+
+    pub fn timing(&self) -> EffectTimingValues {
+        self.timing.borrow().clone()
+    }
+
+    pub fn animation(&self) -> Option<DomRoot<Animation>> {
+        self.animation.get()
+    }
+
+    pub fn set_animation(&self, animation: Option<&Animation>) {
+        self.animation.set(animation);
+    }
+
+    /// <https://drafts.csswg.org/web-animations/#local-time>
+    pub fn local_time(&self) -> Option<f64> {
+        self.animation
+            .get()
+            .and_then(|animation| animation.current_time())
+    }
+
+    pub fn set_timing(&self, timing: EffectTimingValues) {
+        *self.timing.borrow_mut() = timing;
+    }
+
+    pub fn computed_timing(&self) -> ComputedTimingValues {
+        self.timing.borrow().compute(self.local_time())
+    }
+
+    /// <https://drafts.csswg.org/web-animations/#relevant-animations-section>
+    ///
+    /// An effect is relevant while it is in play, going to play, or filling.
+    pub fn is_relevant(&self) -> bool {
+        let local_time = match self.local_time() {
+            Some(local_time) => local_time,
+            None => return false,
+        };
+        local_time < self.timing.borrow().end_time() || self.computed_timing().active_time.is_some()
+    }
+}
+
+impl AnimationEffectMethods for AnimationEffect {
+    // https://drafts.csswg.org/web-animations/#dom-animationeffect-gettiming
+    fn GetTiming(&self) -> EffectTiming {
+        let timing = self.timing.borrow();
+        EffectTiming {
+            delay: Finite::wrap(timing.delay),
+            endDelay: Finite::wrap(timing.end_delay),
+            fill: timing.fill,
+            iterationStart: Finite::wrap(timing.iteration_start),
+            iterations: timing.iterations,
+            duration: match timing.duration {
+                Some(duration) => UnrestrictedDoubleOrString::UnrestrictedDouble(duration),
+                None => UnrestrictedDoubleOrString::String(DOMString::from("auto")),
+            },
+            direction: timing.direction,
+            easing: timing.easing.clone(),
+        }
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-animationeffect-getcomputedtiming
+    fn GetComputedTiming(&self) -> ComputedEffectTiming {
+        let computed = self.computed_timing();
+        let timing = self.timing.borrow();
+        ComputedEffectTiming {
+            parent: EffectTiming {
+                delay: Finite::wrap(timing.delay),
+                endDelay: Finite::wrap(timing.end_delay),
+                fill: timing.resolved_fill(),
+                iterationStart: Finite::wrap(timing.iteration_start),
+                iterations: timing.iterations,
+                duration: UnrestrictedDoubleOrString::UnrestrictedDouble(
+                    timing.iteration_duration(),
+                ),
+                direction: timing.direction,
+                easing: timing.easing.clone(),
+            },
+            endTime: timing.end_time(),
+            activeDuration: timing.active_duration(),
+            localTime: computed.local_time.map(Finite::wrap),
+            progress: computed.progress.map(Finite::wrap),
+            currentIteration: computed.current_iteration,
+        }
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-animationeffect-updatetiming
+    fn UpdateTiming(&self, timing: &OptionalEffectTiming) -> ErrorResult {
+        let document = self.global().as_window().Document();
+        self.timing.borrow_mut().update(&document, timing)?;
+        if let Some(animation) = self.animation.get() {
+            animation.timing_updated();
+        }
+        Ok(())
+    }
+}
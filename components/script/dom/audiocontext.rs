@@ -11,6 +11,7 @@ use crate::dom::bindings::codegen::Bindings::AudioContextBinding::{
 use crate::dom::bindings::codegen::Bindings::AudioContextBinding::{
     AudioContextOptions, AudioTimestamp,
 };
+use crate::dom::bindings::codegen::Bindings::AudioNodeBinding::AudioNodeOptions;
 use crate::dom::bindings::codegen::Bindings::BaseAudioContextBinding::AudioContextState;
 use crate::dom::bindings::codegen::Bindings::BaseAudioContextBinding::BaseAudioContextBinding::BaseAudioContextMethods;
 use crate::dom::bindings::error::{Error, Fallible};
@@ -19,6 +20,11 @@ use crate::dom::bindings::num::Finite;
 use crate::dom::bindings::refcounted::{Trusted, TrustedPromise};
 use crate::dom::bindings::reflector::{reflect_dom_object, DomObject};
 use crate::dom::bindings::root::DomRoot;
+use crate::dom::htmlmediaelement::HTMLMediaElement;
+use crate::dom::mediaelementaudiosourcenode::MediaElementAudioSourceNode;
+use crate::dom::mediastream::MediaStream;
+use crate::dom::mediastreamaudiodestinationnode::MediaStreamAudioDestinationNode;
+use crate::dom::mediastreamaudiosourcenode::MediaStreamAudioSourceNode;
 use crate::dom::promise::Promise;
 use crate::dom::window::Window;
 use crate::task_source::TaskSource;
@@ -234,6 +240,33 @@ impl AudioContextMethods for AudioContext {
         // Step 6.
         promise
     }
+
+    // https://webaudio.github.io/web-audio-api/#dom-audiocontext-createmediaelementsource
+    fn CreateMediaElementSource(
+        &self,
+        media_element: &HTMLMediaElement,
+    ) -> Fallible<DomRoot<MediaElementAudioSourceNode>> {
+        let global = self.global();
+        let window = global.as_window();
+        MediaElementAudioSourceNode::new(window, self, media_element)
+    }
+
+    // https://webaudio.github.io/web-audio-api/#dom-audiocontext-createmediastreamsource
+    fn CreateMediaStreamSource(
+        &self,
+        stream: &MediaStream,
+    ) -> Fallible<DomRoot<MediaStreamAudioSourceNode>> {
+        let global = self.global();
+        let window = global.as_window();
+        MediaStreamAudioSourceNode::new(window, self, stream)
+    }
+
+    // https://webaudio.github.io/web-audio-api/#dom-audiocontext-createmediastreamdestination
+    fn CreateMediaStreamDestination(&self) -> Fallible<DomRoot<MediaStreamAudioDestinationNode>> {
+        let global = self.global();
+        let window = global.as_window();
+        MediaStreamAudioDestinationNode::new(window, self, &AudioNodeOptions::empty())
+    }
 }
 
 impl From<AudioContextLatencyCategory> for LatencyCategory {
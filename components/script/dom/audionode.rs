@@ -279,6 +279,12 @@ impl AudioNodeMethods for AudioNode {
             EventTargetTypeId::AudioNode(AudioNodeTypeId::ChannelSplitterNode) => {
                 return Err(Error::InvalidState);
             },
+            EventTargetTypeId::AudioNode(AudioNodeTypeId::ConvolverNode) |
+            EventTargetTypeId::AudioNode(AudioNodeTypeId::DynamicsCompressorNode) => {
+                if value > 2 {
+                    return Err(Error::NotSupported);
+                }
+            },
             // XXX We do not support any of the other AudioNodes with
             // constraints yet. Add more cases here as we add support
             // for new AudioNodes.
@@ -330,6 +336,12 @@ impl AudioNodeMethods for AudioNode {
             EventTargetTypeId::AudioNode(AudioNodeTypeId::ChannelSplitterNode) => {
                 return Err(Error::InvalidState);
             },
+            EventTargetTypeId::AudioNode(AudioNodeTypeId::ConvolverNode) |
+            EventTargetTypeId::AudioNode(AudioNodeTypeId::DynamicsCompressorNode) => {
+                if value == ChannelCountMode::Max {
+                    return Err(Error::NotSupported);
+                }
+            },
             // XXX We do not support any of the other AudioNodes with
             // constraints yet. Add more cases here as we add support
             // for new AudioNodes.
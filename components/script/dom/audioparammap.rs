@@ -0,0 +1,81 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::audioparam::AudioParam;
+use crate::dom::bindings::codegen::Bindings::AudioParamMapBinding::{self, AudioParamMapMethods};
+use crate::dom::bindings::iterable::Iterable;
+use crate::dom::bindings::reflector::{reflect_dom_object, Reflector};
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::window::Window;
+use dom_struct::dom_struct;
+
+#[dom_struct]
+pub struct AudioParamMap {
+    reflector_: Reflector,
+    /// The parameters, in the order of their descriptors.
+    params: Vec<(DOMString, Dom<AudioParam>)>,
+}
+
+impl AudioParamMap {
+    #[allow(unrooted_must_root)]
+    fn new_inherited(params: &[(DOMString, DomRoot<AudioParam>)]) -> AudioParamMap {
+        AudioParamMap {
+            reflector_: Reflector::new(),
+            params: params
+                .iter()
+                .map(|&(ref name, ref param)| (name.clone(), Dom::from_ref(&**param)))
+                .collect(),
+        }
+    }
+
+    #[allow(unrooted_must_root)]
+    pub fn new(
+        window: &Window,
+        params: &[(DOMString, DomRoot<AudioParam>)],
+    ) -> DomRoot<AudioParamMap> {
+        reflect_dom_object(
+            Box::new(AudioParamMap::new_inherited(params)),
+            window,
+            AudioParamMapBinding::Wrap,
+        )
+    }
+}
+
+impl AudioParamMapMethods for AudioParamMap {
+    // https://webaudio.github.io/web-audio-api/#audioparammap
+    fn Size(&self) -> u32 {
+        self.params.len() as u32
+    }
+
+    // https://webaudio.github.io/web-audio-api/#audioparammap
+    fn Get(&self, name: DOMString) -> Option<DomRoot<AudioParam>> {
+        self.params
+            .iter()
+            .find(|&&(ref key, _)| *key == name)
+            .map(|&(_, ref param)| DomRoot::from_ref(&**param))
+    }
+
+    // https://webaudio.github.io/web-audio-api/#audioparammap
+    fn Has(&self, name: DOMString) -> bool {
+        self.params.iter().any(|&(ref key, _)| *key == name)
+    }
+}
+
+impl Iterable for AudioParamMap {
+    type Key = DOMString;
+    type Value = DomRoot<AudioParam>;
+
+    fn get_iterable_length(&self) -> u32 {
+        self.params.len() as u32
+    }
+
+    fn get_value_at_index(&self, n: u32) -> DomRoot<AudioParam> {
+        DomRoot::from_ref(&*self.params[n as usize].1)
+    }
+
+    fn get_key_at_index(&self, n: u32) -> DOMString {
+        self.params[n as usize].0.clone()
+    }
+}
@@ -0,0 +1,69 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::audioworkletglobalscope::AudioParamDescriptorData;
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::AudioWorkletBinding;
+use crate::dom::bindings::reflector::reflect_dom_object;
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::window::Window;
+use crate::dom::worklet::{Worklet, WorkletExecutor};
+use crate::dom::workletglobalscope::WorkletGlobalScopeType;
+use dom_struct::dom_struct;
+use std::collections::HashMap;
+
+/// <https://webaudio.github.io/web-audio-api/#audioworklet>
+#[dom_struct]
+pub struct AudioWorklet {
+    worklet: Worklet,
+    /// The parameter descriptors of the processors registered so far.
+    /// <https://webaudio.github.io/web-audio-api/#node-name-to-parameter-descriptor-map>
+    node_name_to_parameter_descriptor_map:
+        DomRefCell<HashMap<String, Vec<AudioParamDescriptorData>>>,
+}
+
+impl AudioWorklet {
+    fn new_inherited(window: &Window, sample_rate: f32) -> AudioWorklet {
+        AudioWorklet {
+            worklet: Worklet::new_inherited(window, WorkletGlobalScopeType::Audio(sample_rate)),
+            node_name_to_parameter_descriptor_map: Default::default(),
+        }
+    }
+
+    pub fn new(window: &Window, sample_rate: f32) -> DomRoot<AudioWorklet> {
+        reflect_dom_object(
+            Box::new(AudioWorklet::new_inherited(window, sample_rate)),
+            window,
+            AudioWorkletBinding::Wrap,
+        )
+    }
+
+    /// The parameter descriptors of the processor registered as `name`,
+    /// or `None` if there is no such processor.
+    pub fn parameter_descriptors(&self, name: &str) -> Option<Vec<AudioParamDescriptorData>> {
+        if let Some(descriptors) = self
+            .node_name_to_parameter_descriptor_map
+            .borrow()
+            .get(name)
+        {
+            return Some(descriptors.clone());
+        }
+        // Processors can't be unregistered, so lookups are cached.
+        let descriptors = self
+            .worklet
+            .thread_pool()
+            .audio_worklet_lookup(self.worklet.worklet_id(), name.to_owned())?;
+        self.node_name_to_parameter_descriptor_map
+            .borrow_mut()
+            .insert(name.to_owned(), descriptors.clone());
+        Some(descriptors)
+    }
+
+    /// An executor of tasks in the global scope of this worklet.
+    pub fn executor(&self) -> WorkletExecutor {
+        self.worklet
+            .thread_pool()
+            .executor(self.worklet.worklet_id())
+    }
+}
@@ -0,0 +1,626 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::audioworkletnode::AudioWorkletNode;
+use crate::dom::audioworkletprocessor::AudioWorkletProcessor;
+use crate::dom::bindings::callback::CallbackContainer;
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::AudioParamBinding::AutomationRate;
+use crate::dom::bindings::codegen::Bindings::AudioParamDescriptorBinding::AudioParamDescriptor;
+use crate::dom::bindings::codegen::Bindings::AudioWorkletGlobalScopeBinding;
+use crate::dom::bindings::codegen::Bindings::AudioWorkletGlobalScopeBinding::AudioWorkletGlobalScopeMethods;
+use crate::dom::bindings::codegen::Bindings::VoidFunctionBinding::VoidFunction;
+use crate::dom::bindings::conversions::{get_property_jsval, root_from_object};
+use crate::dom::bindings::error::{Error, Fallible};
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::num::Finite;
+use crate::dom::bindings::refcounted::Trusted;
+use crate::dom::bindings::reflector::DomObject;
+use crate::dom::bindings::root::{Dom, DomRoot, MutNullableDom};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::bindings::structuredclone::StructuredCloneData;
+use crate::dom::bindings::utils::set_dictionary_property;
+use crate::dom::messageport::{EntangledPort, MessagePort};
+use crate::dom::worklet::WorkletExecutor;
+use crate::dom::workletglobalscope::{WorkletGlobalScope, WorkletGlobalScopeInit};
+use crate::script_runtime::JSContext;
+use crossbeam_channel::Sender;
+use dom_struct::dom_struct;
+use js::conversions::{ConversionResult, FromJSValConvertible};
+use js::jsapi::{HandleValueArray, Heap, IsConstructor, JSAutoRealm, JSObject};
+use js::jsapi::{JS_ClearPendingException, JS_IsExceptionPending};
+use js::jsapi::{JS_NewArrayObject, JS_NewPlainObject};
+use js::jsval::{JSVal, ObjectValue, UndefinedValue};
+use js::rust::wrappers::{Call, Construct1};
+use js::rust::{HandleObject, MutableHandleValue, Runtime};
+use js::typedarray::{CreateWith, Float32Array};
+use msg::constellation_msg::PipelineId;
+use servo_media::audio::audio_worklet_node::AudioWorkletQuantum;
+use servo_media::audio::block::FRAMES_PER_BLOCK_USIZE;
+use servo_url::ServoUrl;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::ptr;
+use std::rc::Rc;
+use uuid::Uuid;
+
+/// The identifier of an `AudioWorkletProcessor`, shared with the
+/// `AudioWorkletNode` it renders for.
+#[derive(Clone, Copy, Debug, Eq, Hash, JSTraceable, PartialEq)]
+pub struct AudioWorkletProcessorId(Uuid);
+
+malloc_size_of_is_0!(AudioWorkletProcessorId);
+
+impl AudioWorkletProcessorId {
+    pub fn new() -> AudioWorkletProcessorId {
+        AudioWorkletProcessorId(servo_rand::random_uuid())
+    }
+}
+
+/// A validated `AudioParamDescriptor`, which can be sent between threads.
+/// <https://webaudio.github.io/web-audio-api/#dictdef-audioparamdescriptor>
+#[derive(Clone, Debug, JSTraceable, MallocSizeOf)]
+pub struct AudioParamDescriptorData {
+    pub name: String,
+    pub default_value: f32,
+    pub min_value: f32,
+    pub max_value: f32,
+    pub automation_rate: AutomationRate,
+}
+
+impl<'a> From<&'a AudioParamDescriptor> for AudioParamDescriptorData {
+    fn from(descriptor: &'a AudioParamDescriptor) -> AudioParamDescriptorData {
+        AudioParamDescriptorData {
+            name: descriptor.name.to_string(),
+            default_value: *descriptor.defaultValue,
+            min_value: *descriptor.minValue,
+            max_value: *descriptor.maxValue,
+            automation_rate: descriptor.automationRate,
+        }
+    }
+}
+
+/// What the global scope of an audio worklet needs to construct
+/// the processor of a new `AudioWorkletNode`.
+pub struct ProcessorConstructionData {
+    /// The name the processor was registered with.
+    pub name: String,
+    /// The serialized `AudioWorkletNodeOptions` the node was created with.
+    pub options: StructuredCloneData,
+    pub number_of_inputs: u32,
+    pub number_of_outputs: u32,
+    pub output_channel_counts: Option<Vec<u32>>,
+    /// The node, kept alive for as long as the processor is active.
+    pub node: Trusted<AudioWorkletNode>,
+    /// The port of the node, which the port of the processor is entangled with.
+    pub node_port: Trusted<MessagePort>,
+}
+
+/// Tasks which can be performed by an audio worklet
+pub enum AudioWorkletTask {
+    /// Look up the parameter descriptors of a registered processor.
+    LookUpParameterDescriptors(String, Sender<Option<Vec<AudioParamDescriptorData>>>),
+    /// Construct the processor of a new `AudioWorkletNode`.
+    CreateProcessor(AudioWorkletProcessorId, ProcessorConstructionData),
+    /// Render a quantum, sending back the outputs of the processor,
+    /// or `None` if it is silent.
+    Process(
+        AudioWorkletProcessorId,
+        AudioWorkletQuantum,
+        Sender<Option<Vec<Vec<Vec<f32>>>>>,
+    ),
+    /// Deliver a message to the port of a processor.
+    PostMessage(AudioWorkletProcessorId, StructuredCloneData),
+    /// Drop a processor whose node has been garbage collected.
+    DestroyProcessor(AudioWorkletProcessorId),
+}
+
+/// <https://webaudio.github.io/web-audio-api/#audioworkletglobalscope>
+#[dom_struct]
+pub struct AudioWorkletGlobalScope {
+    /// The worklet global for this object
+    worklet_global: WorkletGlobalScope,
+    /// The sample rate of the audio context
+    sample_rate: f32,
+    /// The frame of the quantum being processed
+    current_frame: Cell<u64>,
+    /// <https://webaudio.github.io/web-audio-api/#node-name-to-processor-constructor-map>
+    #[ignore_malloc_size_of = "mozjs"]
+    processor_constructors: DomRefCell<HashMap<String, Box<Heap<JSVal>>>>,
+    /// <https://webaudio.github.io/web-audio-api/#node-name-to-parameter-descriptor-map>
+    parameter_descriptors: DomRefCell<HashMap<String, Vec<AudioParamDescriptorData>>>,
+    /// The port of the processor being constructed
+    /// <https://webaudio.github.io/web-audio-api/#pending-processor-construction-data>
+    pending_processor_port: MutNullableDom<MessagePort>,
+    /// The processors rendering for the nodes of the audio context
+    processors: DomRefCell<HashMap<AudioWorkletProcessorId, ProcessorInstance>>,
+}
+
+impl AudioWorkletGlobalScope {
+    #[allow(unsafe_code)]
+    pub fn new(
+        runtime: &Runtime,
+        pipeline_id: PipelineId,
+        base_url: ServoUrl,
+        executor: WorkletExecutor,
+        init: &WorkletGlobalScopeInit,
+        sample_rate: f32,
+    ) -> DomRoot<AudioWorkletGlobalScope> {
+        debug!(
+            "Creating audio worklet global scope for pipeline {}.",
+            pipeline_id
+        );
+        let global = Box::new(AudioWorkletGlobalScope {
+            worklet_global: WorkletGlobalScope::new_inherited(
+                pipeline_id,
+                base_url,
+                executor,
+                init,
+            ),
+            sample_rate: sample_rate,
+            current_frame: Cell::new(0),
+            processor_constructors: Default::default(),
+            parameter_descriptors: Default::default(),
+            pending_processor_port: Default::default(),
+            processors: Default::default(),
+        });
+        unsafe { AudioWorkletGlobalScopeBinding::Wrap(JSContext::from_ptr(runtime.cx()), global) }
+    }
+
+    /// Take the port of the processor being constructed, if any.
+    pub fn take_pending_processor_port(&self) -> Option<DomRoot<MessagePort>> {
+        self.pending_processor_port.take()
+    }
+
+    #[allow(unrooted_must_root)]
+    pub fn perform_a_worklet_task(&self, task: AudioWorkletTask) {
+        match task {
+            AudioWorkletTask::LookUpParameterDescriptors(name, sender) => {
+                let descriptors = self.parameter_descriptors.borrow().get(&name).cloned();
+                let _ = sender.send(descriptors);
+            },
+            AudioWorkletTask::CreateProcessor(id, data) => self.create_processor(id, data),
+            AudioWorkletTask::Process(id, quantum, sender) => {
+                let _ = sender.send(self.process(id, quantum));
+            },
+            AudioWorkletTask::PostMessage(id, data) => {
+                let port = self
+                    .processors
+                    .borrow()
+                    .get(&id)
+                    .map(|instance| instance.processor.port());
+                if let Some(port) = port {
+                    port.handle_message(data);
+                }
+            },
+            AudioWorkletTask::DestroyProcessor(id) => {
+                self.processors.borrow_mut().remove(&id);
+            },
+        }
+
+        // Ports which were started during this task deliver their messages now.
+        rooted_vec!(let ports <- self.processors.borrow().values()
+                    .map(|instance| instance.processor.port()));
+        for port in ports.iter() {
+            port.deliver_pending_messages();
+        }
+    }
+
+    /// <https://webaudio.github.io/web-audio-api/#instantiation-of-AudioWorkletNode-and-AudioWorkletProcessor>
+    #[allow(unsafe_code, unrooted_must_root)]
+    fn create_processor(&self, id: AudioWorkletProcessorId, data: ProcessorConstructionData) {
+        let cx = self.worklet_global.get_cx();
+        let _ac = JSAutoRealm::new(*cx, self.worklet_global.reflector().get_jsobject().get());
+
+        rooted!(in(*cx) let mut constructor = UndefinedValue());
+        match self.processor_constructors.borrow().get(&data.name) {
+            Some(processor_constructor) => constructor.set(processor_constructor.get()),
+            None => {
+                warn!(
+                    "Creating un-registered audio worklet processor {}.",
+                    data.name
+                );
+                return self.report_processor_error(data.node);
+            },
+        }
+
+        // Steps 1-4.
+        let port = MessagePort::new(
+            self.upcast(),
+            Some(EntangledPort::AudioWorkletNode(data.node_port)),
+        );
+        rooted!(in(*cx) let mut options = UndefinedValue());
+        data.options.read(self.upcast(), options.handle_mut());
+
+        // Steps 5-6.
+        self.pending_processor_port.set(Some(&port));
+        let args_slice = [options.get()];
+        let args = unsafe { HandleValueArray::from_rooted_slice(&args_slice) };
+        rooted!(in(*cx) let mut result = ptr::null_mut::<JSObject>());
+        unsafe {
+            Construct1(*cx, constructor.handle(), &args, result.handle_mut());
+        }
+        self.pending_processor_port.set(None);
+
+        // Step 7.
+        if unsafe { JS_IsExceptionPending(*cx) } {
+            debug!(
+                "Audio worklet processor constructor threw an exception {}.",
+                data.name
+            );
+            unsafe {
+                JS_ClearPendingException(*cx);
+            }
+            return self.report_processor_error(data.node);
+        }
+        let processor = match root_from_object::<AudioWorkletProcessor>(result.get(), *cx) {
+            Ok(processor) => processor,
+            Err(()) => {
+                debug!(
+                    "Audio worklet processor constructor didn't return a processor {}.",
+                    data.name
+                );
+                return self.report_processor_error(data.node);
+            },
+        };
+
+        // Step 8.
+        let parameter_names = self
+            .parameter_descriptors
+            .borrow()
+            .get(&data.name)
+            .map(|descriptors| descriptors.iter().map(|d| d.name.clone()).collect())
+            .unwrap_or_default();
+        let instance = ProcessorInstance {
+            processor: Dom::from_ref(&*processor),
+            parameter_names: parameter_names,
+            number_of_inputs: data.number_of_inputs,
+            number_of_outputs: data.number_of_outputs,
+            output_channel_counts: data.output_channel_counts,
+            node: DomRefCell::new(Some(data.node)),
+            errored: Cell::new(false),
+        };
+        self.processors.borrow_mut().insert(id, instance);
+    }
+
+    /// <https://webaudio.github.io/web-audio-api/#rendering-loop>
+    #[allow(unsafe_code)]
+    fn process(
+        &self,
+        id: AudioWorkletProcessorId,
+        quantum: AudioWorkletQuantum,
+    ) -> Option<Vec<Vec<Vec<f32>>>> {
+        self.current_frame.set(quantum.current_frame);
+
+        // The processors musn't be borrowed while `process` runs.
+        let (processor, parameter_names, output_channel_counts) = {
+            let processors = self.processors.borrow();
+            let instance = processors.get(&id)?;
+            if instance.errored.get() {
+                return None;
+            }
+            (
+                DomRoot::from_ref(&*instance.processor),
+                instance.parameter_names.clone(),
+                instance.output_channel_counts(&quantum.inputs),
+            )
+        };
+
+        let cx = self.worklet_global.get_cx();
+        let _ac = JSAutoRealm::new(*cx, self.worklet_global.reflector().get_jsobject().get());
+
+        rooted!(in(*cx) let mut inputs = UndefinedValue());
+        rooted!(in(*cx) let mut outputs = UndefinedValue());
+        rooted!(in(*cx) let parameters = unsafe { JS_NewPlainObject(*cx) });
+        let silence: Vec<Vec<Vec<f32>>> = output_channel_counts
+            .iter()
+            .map(|&count| vec![vec![0.; FRAMES_PER_BLOCK_USIZE]; count as usize])
+            .collect();
+        let created = unsafe {
+            blocks_to_jsval(cx, &quantum.inputs, inputs.handle_mut())
+                .and_then(|_| blocks_to_jsval(cx, &silence, outputs.handle_mut()))
+        }
+        .and_then(|_| {
+            parameter_names
+                .iter()
+                .zip(quantum.parameters.iter())
+                .try_for_each(|(name, values)| unsafe {
+                    rooted!(in(*cx) let mut array = ptr::null_mut::<JSObject>());
+                    Float32Array::create(*cx, CreateWith::Slice(values), array.handle_mut())?;
+                    rooted!(in(*cx) let array = ObjectValue(array.get()));
+                    set_dictionary_property(*cx, parameters.handle(), name, array.handle())
+                })
+        });
+        if created.is_err() {
+            warn!("Failed to create the arguments of an audio worklet processor.");
+            unsafe {
+                JS_ClearPendingException(*cx);
+            }
+            return None;
+        }
+
+        // Steps 1-2 of the processing of an AudioWorkletNode.
+        rooted!(in(*cx) let object = processor.reflector().get_jsobject().get());
+        rooted!(in(*cx) let mut process_function = UndefinedValue());
+        rooted!(in(*cx) let mut result = UndefinedValue());
+        let args_slice = [inputs.get(), outputs.get(), ObjectValue(parameters.get())];
+        let args = unsafe { HandleValueArray::from_rooted_slice(&args_slice) };
+        unsafe {
+            let _ = get_property_jsval(
+                *cx,
+                object.handle(),
+                "process",
+                process_function.handle_mut(),
+            );
+            rooted!(in(*cx) let this = ObjectValue(object.get()));
+            Call(
+                *cx,
+                this.handle(),
+                process_function.handle(),
+                &args,
+                result.handle_mut(),
+            );
+        }
+
+        // Step 3.
+        if unsafe { JS_IsExceptionPending(*cx) } {
+            debug!("Audio worklet processor threw an exception.");
+            unsafe {
+                JS_ClearPendingException(*cx);
+            }
+            let node = self.processors.borrow().get(&id).and_then(|instance| {
+                instance.errored.set(true);
+                instance.node.borrow_mut().take()
+            });
+            if let Some(node) = node {
+                self.report_processor_error(node);
+            }
+            return None;
+        }
+
+        // Step 4: once `process` returns a falsy value, the node is
+        // only kept alive by script.
+        let keep_alive = match unsafe { bool::from_jsval(*cx, result.handle(), ()) } {
+            Ok(ConversionResult::Success(keep_alive)) => keep_alive,
+            _ => false,
+        };
+        if !keep_alive {
+            if let Some(instance) = self.processors.borrow().get(&id) {
+                instance.node.borrow_mut().take();
+            }
+        }
+
+        // Step 5.
+        rooted!(in(*cx) let outputs = outputs.to_object());
+        let outputs = unsafe { read_blocks(cx, outputs.handle(), &output_channel_counts) };
+        unsafe {
+            JS_ClearPendingException(*cx);
+        }
+        Some(outputs)
+    }
+
+    /// Fire a `processorerror` event at `node`, in the script thread.
+    fn report_processor_error(&self, node: Trusted<AudioWorkletNode>) {
+        self.worklet_global
+            .run_in_script_thread(task!(fire_processor_error: move || {
+                node.root().fire_processor_error();
+            }));
+    }
+}
+
+/// A processor constructed in this global scope, and what it needs
+/// to render its quanta.
+/// This type is dangerous, because it contains an unrooted `Dom` value.
+#[derive(JSTraceable, MallocSizeOf)]
+#[must_root]
+struct ProcessorInstance {
+    processor: Dom<AudioWorkletProcessor>,
+    /// The names of the parameters, in the order of their descriptors.
+    parameter_names: Vec<String>,
+    number_of_inputs: u32,
+    number_of_outputs: u32,
+    output_channel_counts: Option<Vec<u32>>,
+    /// The node of this processor, while its active processing keeps it alive.
+    #[ignore_malloc_size_of = "Trusted"]
+    node: DomRefCell<Option<Trusted<AudioWorkletNode>>>,
+    /// Whether the processor threw, which makes it output silence.
+    errored: Cell<bool>,
+}
+
+impl ProcessorInstance {
+    /// <https://webaudio.github.io/web-audio-api/#configuring-channels-with-audioworkletnodeoptions>
+    fn output_channel_counts(&self, inputs: &[Vec<Vec<f32>>]) -> Vec<u32> {
+        if let Some(ref counts) = self.output_channel_counts {
+            return counts.clone();
+        }
+        // A single output follows the channel count of a single input.
+        let count = if self.number_of_inputs == 1 && self.number_of_outputs == 1 {
+            inputs.first().map_or(1, |input| input.len().max(1) as u32)
+        } else {
+            1
+        };
+        vec![count; self.number_of_outputs as usize]
+    }
+}
+
+/// Create a JS array holding, for each block, an array of `Float32Array`s
+/// with a copy of each of its channels.
+#[allow(unsafe_code)]
+unsafe fn blocks_to_jsval(
+    cx: JSContext,
+    blocks: &[Vec<Vec<f32>>],
+    mut rval: MutableHandleValue,
+) -> Result<(), ()> {
+    rooted!(in(*cx) let array = JS_NewArrayObject(*cx, &HandleValueArray::new()));
+    for (index, block) in blocks.iter().enumerate() {
+        rooted!(in(*cx) let channels = JS_NewArrayObject(*cx, &HandleValueArray::new()));
+        for (channel_index, channel) in block.iter().enumerate() {
+            rooted!(in(*cx) let mut data = ptr::null_mut::<JSObject>());
+            Float32Array::create(*cx, CreateWith::Slice(channel), data.handle_mut())?;
+            rooted!(in(*cx) let data = ObjectValue(data.get()));
+            set_dictionary_property(
+                *cx,
+                channels.handle(),
+                &channel_index.to_string(),
+                data.handle(),
+            )?;
+        }
+        rooted!(in(*cx) let channels = ObjectValue(channels.get()));
+        set_dictionary_property(*cx, array.handle(), &index.to_string(), channels.handle())?;
+    }
+    rval.set(ObjectValue(array.get()));
+    Ok(())
+}
+
+/// Read the blocks written by a processor in an array created by
+/// `blocks_to_jsval`, with silence in place of missing channels.
+#[allow(unsafe_code)]
+unsafe fn read_blocks(
+    cx: JSContext,
+    blocks: HandleObject,
+    channel_counts: &[u32],
+) -> Vec<Vec<Vec<f32>>> {
+    let mut result = Vec::with_capacity(channel_counts.len());
+    for (index, &channel_count) in channel_counts.iter().enumerate() {
+        rooted!(in(*cx) let mut block = UndefinedValue());
+        let _ = get_property_jsval(*cx, blocks, &index.to_string(), block.handle_mut());
+        let mut channels = Vec::with_capacity(channel_count as usize);
+        for channel_index in 0..channel_count as usize {
+            let mut data = vec![0.; FRAMES_PER_BLOCK_USIZE];
+            if block.is_object() {
+                rooted!(in(*cx) let block = block.to_object());
+                rooted!(in(*cx) let mut channel = UndefinedValue());
+                let _ = get_property_jsval(
+                    *cx,
+                    block.handle(),
+                    &channel_index.to_string(),
+                    channel.handle_mut(),
+                );
+                if channel.is_object() {
+                    typedarray!(in(*cx) let array: Float32Array = channel.to_object());
+                    if let Ok(array) = array {
+                        let samples = array.as_slice();
+                        let len = samples.len().min(FRAMES_PER_BLOCK_USIZE);
+                        data[..len].copy_from_slice(&samples[..len]);
+                    }
+                }
+            }
+            channels.push(data);
+        }
+        result.push(channels);
+    }
+    result
+}
+
+impl AudioWorkletGlobalScopeMethods for AudioWorkletGlobalScope {
+    #[allow(unsafe_code)]
+    /// <https://webaudio.github.io/web-audio-api/#dom-audioworkletglobalscope-registerprocessor>
+    fn RegisterProcessor(&self, name: DOMString, processor_ctor: Rc<VoidFunction>) -> Fallible<()> {
+        let name = String::from(name);
+        let cx = self.worklet_global.get_cx();
+        rooted!(in(*cx) let processor_obj = processor_ctor.callback_holder().get());
+        rooted!(in(*cx) let processor_val = ObjectValue(processor_obj.get()));
+
+        debug!("Registering audio worklet processor {}.", name);
+
+        // Step 1.
+        if name.is_empty() {
+            return Err(Error::NotSupported);
+        }
+
+        // Step 2.
+        if self.processor_constructors.borrow().contains_key(&name) {
+            return Err(Error::NotSupported);
+        }
+
+        // Step 3.
+        if unsafe { !IsConstructor(processor_obj.get()) } {
+            return Err(Error::Type(String::from("Not a constructor.")));
+        }
+
+        // Steps 4-5.
+        rooted!(in(*cx) let mut prototype = UndefinedValue());
+        unsafe {
+            get_property_jsval(
+                *cx,
+                processor_obj.handle(),
+                "prototype",
+                prototype.handle_mut(),
+            )?;
+        }
+        if !prototype.is_object() {
+            return Err(Error::Type(String::from("Prototype is not an object.")));
+        }
+
+        // Steps 6-7.
+        rooted!(in(*cx) let mut parameter_descriptors = UndefinedValue());
+        unsafe {
+            get_property_jsval(
+                *cx,
+                processor_obj.handle(),
+                "parameterDescriptors",
+                parameter_descriptors.handle_mut(),
+            )?;
+        }
+        let descriptors: Vec<AudioParamDescriptor> = if parameter_descriptors.is_undefined() {
+            vec![]
+        } else {
+            match unsafe {
+                Vec::<AudioParamDescriptor>::from_jsval(*cx, parameter_descriptors.handle(), ())
+            } {
+                Ok(ConversionResult::Success(descriptors)) => descriptors,
+                Ok(ConversionResult::Failure(error)) => {
+                    return Err(Error::Type(error.into_owned()));
+                },
+                Err(()) => return Err(Error::JSFailed),
+            }
+        };
+
+        // Step 8.
+        let mut names = vec![];
+        for descriptor in &descriptors {
+            // Step 8.1.
+            if names.contains(&&*descriptor.name) {
+                return Err(Error::NotSupported);
+            }
+            names.push(&*descriptor.name);
+            // Step 8.2.
+            let default_value = *descriptor.defaultValue;
+            if default_value < *descriptor.minValue || default_value > *descriptor.maxValue {
+                return Err(Error::InvalidState);
+            }
+        }
+
+        // Steps 9-10.
+        let constructor = Box::new(Heap::default());
+        constructor.set(processor_val.get());
+        self.processor_constructors
+            .borrow_mut()
+            .insert(name.clone(), constructor);
+        self.parameter_descriptors.borrow_mut().insert(
+            name,
+            descriptors
+                .iter()
+                .map(AudioParamDescriptorData::from)
+                .collect(),
+        );
+
+        Ok(())
+    }
+
+    /// <https://webaudio.github.io/web-audio-api/#dom-audioworkletglobalscope-currentframe>
+    fn CurrentFrame(&self) -> u64 {
+        self.current_frame.get()
+    }
+
+    /// <https://webaudio.github.io/web-audio-api/#dom-audioworkletglobalscope-currenttime>
+    fn CurrentTime(&self) -> Finite<f64> {
+        Finite::wrap(self.current_frame.get() as f64 / self.sample_rate as f64)
+    }
+
+    /// <https://webaudio.github.io/web-audio-api/#dom-audioworkletglobalscope-samplerate>
+    fn SampleRate(&self) -> Finite<f32> {
+        Finite::wrap(self.sample_rate)
+    }
+}
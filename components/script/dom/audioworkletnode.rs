@@ -0,0 +1,243 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::audionode::{AudioNode, MAX_CHANNEL_COUNT};
+use crate::dom::audioparam::AudioParam;
+use crate::dom::audioparammap::AudioParamMap;
+use crate::dom::audioworkletglobalscope::{
+    AudioWorkletProcessorId, AudioWorkletTask, ProcessorConstructionData,
+};
+use crate::dom::baseaudiocontext::BaseAudioContext;
+use crate::dom::bindings::codegen::Bindings::AudioNodeBinding::{
+    ChannelCountMode, ChannelInterpretation,
+};
+use crate::dom::bindings::codegen::Bindings::AudioParamBinding::AudioParamMethods;
+use crate::dom::bindings::codegen::Bindings::AudioWorkletNodeBinding::{
+    self, AudioWorkletNodeMethods, AudioWorkletNodeOptions,
+};
+use crate::dom::bindings::error::{Error, Fallible};
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::num::Finite;
+use crate::dom::bindings::refcounted::Trusted;
+use crate::dom::bindings::reflector::reflect_dom_object;
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::bindings::structuredclone::StructuredCloneData;
+use crate::dom::bindings::trace::RootedTraceableBox;
+use crate::dom::eventtarget::EventTarget;
+use crate::dom::messageport::{EntangledPort, MessagePort};
+use crate::dom::window::Window;
+use crate::dom::worklet::WorkletExecutor;
+use crate::dom::workletglobalscope::WorkletTask;
+use crossbeam_channel::unbounded;
+use dom_struct::dom_struct;
+use js::conversions::ToJSValConvertible;
+use js::jsval::UndefinedValue;
+use servo_atoms::Atom;
+use servo_config::pref;
+use servo_media::audio::audio_worklet_node::{
+    AudioWorkletNodeOptions as ServoMediaAudioWorkletNodeOptions, AudioWorkletParamDescriptor,
+};
+use servo_media::audio::node::AudioNodeInit;
+use servo_media::audio::param::ParamType;
+use std::time::Duration;
+
+#[dom_struct]
+pub struct AudioWorkletNode {
+    node: AudioNode,
+    parameters: Dom<AudioParamMap>,
+    port: Dom<MessagePort>,
+    /// The processor of this node, in the global scope of the audio worklet.
+    processor_id: AudioWorkletProcessorId,
+    executor: WorkletExecutor,
+}
+
+impl AudioWorkletNode {
+    #[allow(unrooted_must_root)]
+    #[allow(unsafe_code)]
+    pub fn new(
+        window: &Window,
+        context: &BaseAudioContext,
+        name: DOMString,
+        options: RootedTraceableBox<AudioWorkletNodeOptions>,
+    ) -> Fallible<DomRoot<AudioWorkletNode>> {
+        let name = String::from(name);
+        let worklet = context.audio_worklet();
+
+        // Step 1.
+        let descriptors = worklet
+            .parameter_descriptors(&name)
+            .ok_or(Error::NotSupported)?;
+
+        // Step 2.
+        if options.numberOfInputs == 0 && options.numberOfOutputs == 0 {
+            return Err(Error::NotSupported);
+        }
+
+        // Step 3.
+        if let Some(ref counts) = options.outputChannelCount {
+            if counts
+                .iter()
+                .any(|&count| count == 0 || count > MAX_CHANNEL_COUNT)
+            {
+                return Err(Error::NotSupported);
+            }
+            if counts.len() != options.numberOfOutputs as usize {
+                return Err(Error::IndexSize);
+            }
+        }
+
+        // Step 9: the options are serialized before the node is created,
+        // so that the processor sees them as they were passed.
+        let cx = window.get_cx();
+        rooted!(in(*cx) let mut options_value = UndefinedValue());
+        unsafe {
+            options.to_jsval(*cx, options_value.handle_mut());
+        }
+        let serialized_options = StructuredCloneData::write(*cx, options_value.handle())?;
+
+        // Steps 4-8.
+        let processor_id = AudioWorkletProcessorId::new();
+        let executor = worklet.executor();
+        let render_executor = executor.clone();
+        let timeout = Duration::from_millis(pref!(dom.worklet.timeout_ms) as u64);
+        let process = move |quantum| {
+            let (sender, receiver) = unbounded();
+            let task = AudioWorkletTask::Process(processor_id, quantum, sender);
+            render_executor.schedule_a_worklet_task(WorkletTask::Audio(task));
+            // A processor which misses its deadline is silent for this quantum.
+            receiver
+                .recv_timeout(timeout)
+                .ok()
+                .and_then(|outputs| outputs)
+        };
+        let node_options =
+            options
+                .parent
+                .unwrap_or(2, ChannelCountMode::Max, ChannelInterpretation::Speakers);
+        let servo_media_options = ServoMediaAudioWorkletNodeOptions {
+            number_of_inputs: options.numberOfInputs,
+            number_of_outputs: options.numberOfOutputs,
+            output_channel_counts: options.outputChannelCount.clone(),
+            parameters: descriptors
+                .iter()
+                .map(|descriptor| AudioWorkletParamDescriptor {
+                    default_value: descriptor.default_value,
+                    min_value: descriptor.min_value,
+                    max_value: descriptor.max_value,
+                })
+                .collect(),
+        };
+        let node = AudioNode::new_inherited(
+            AudioNodeInit::AudioWorkletNode(servo_media_options, Box::new(process)),
+            context,
+            node_options,
+            options.numberOfInputs,
+            options.numberOfOutputs,
+        )?;
+
+        // Step 10.
+        let params: Vec<_> = descriptors
+            .iter()
+            .enumerate()
+            .map(|(index, descriptor)| {
+                let param = AudioParam::new(
+                    window,
+                    context,
+                    node.node_id(),
+                    ParamType::AudioWorkletParam(index as u32),
+                    descriptor.automation_rate,
+                    descriptor.default_value,
+                    descriptor.min_value,
+                    descriptor.max_value,
+                );
+                let name = DOMString::from(descriptor.name.clone());
+                if let Some(value) = options
+                    .parameterData
+                    .as_ref()
+                    .and_then(|data| data.get(&name))
+                {
+                    param.SetValue(Finite::wrap(**value as f32));
+                }
+                (name, param)
+            })
+            .collect();
+        let parameters = AudioParamMap::new(window, &params);
+
+        // Step 11.
+        let port = MessagePort::new(
+            window.upcast(),
+            Some(EntangledPort::AudioWorkletProcessor(
+                executor.clone(),
+                processor_id,
+            )),
+        );
+
+        let node = reflect_dom_object(
+            Box::new(AudioWorkletNode {
+                node,
+                parameters: Dom::from_ref(&*parameters),
+                port: Dom::from_ref(&*port),
+                processor_id,
+                executor,
+            }),
+            window,
+            AudioWorkletNodeBinding::Wrap,
+        );
+
+        // Step 12.
+        let data = ProcessorConstructionData {
+            name,
+            options: serialized_options,
+            number_of_inputs: options.numberOfInputs,
+            number_of_outputs: options.numberOfOutputs,
+            output_channel_counts: options.outputChannelCount.clone(),
+            node: Trusted::new(&*node),
+            node_port: Trusted::new(&*port),
+        };
+        node.executor.schedule_a_worklet_task(WorkletTask::Audio(
+            AudioWorkletTask::CreateProcessor(processor_id, data),
+        ));
+
+        Ok(node)
+    }
+
+    pub fn Constructor(
+        window: &Window,
+        context: &BaseAudioContext,
+        name: DOMString,
+        options: RootedTraceableBox<AudioWorkletNodeOptions>,
+    ) -> Fallible<DomRoot<AudioWorkletNode>> {
+        AudioWorkletNode::new(window, context, name, options)
+    }
+
+    /// Report that the processor of this node failed.
+    pub fn fire_processor_error(&self) {
+        self.upcast::<EventTarget>()
+            .fire_event(Atom::from("processorerror"));
+    }
+}
+
+impl Drop for AudioWorkletNode {
+    fn drop(&mut self) {
+        let task = AudioWorkletTask::DestroyProcessor(self.processor_id);
+        self.executor
+            .schedule_a_worklet_task(WorkletTask::Audio(task));
+    }
+}
+
+impl AudioWorkletNodeMethods for AudioWorkletNode {
+    // https://webaudio.github.io/web-audio-api/#dom-audioworkletnode-parameters
+    fn Parameters(&self) -> DomRoot<AudioParamMap> {
+        DomRoot::from_ref(&self.parameters)
+    }
+
+    // https://webaudio.github.io/web-audio-api/#dom-audioworkletnode-port
+    fn Port(&self) -> DomRoot<MessagePort> {
+        DomRoot::from_ref(&self.port)
+    }
+
+    // https://webaudio.github.io/web-audio-api/#dom-audioworkletnode-onprocessorerror
+    event_handler!(processorerror, GetOnprocessorerror, SetOnprocessorerror);
+}
@@ -0,0 +1,60 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::audioworkletglobalscope::AudioWorkletGlobalScope;
+use crate::dom::bindings::codegen::Bindings::AudioWorkletProcessorBinding::{
+    self, AudioWorkletProcessorMethods,
+};
+use crate::dom::bindings::error::{Error, Fallible};
+use crate::dom::bindings::reflector::{reflect_dom_object, Reflector};
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::messageport::MessagePort;
+use dom_struct::dom_struct;
+
+#[dom_struct]
+pub struct AudioWorkletProcessor {
+    reflector_: Reflector,
+    port: Dom<MessagePort>,
+}
+
+impl AudioWorkletProcessor {
+    fn new_inherited(port: &MessagePort) -> AudioWorkletProcessor {
+        AudioWorkletProcessor {
+            reflector_: Reflector::new(),
+            port: Dom::from_ref(port),
+        }
+    }
+
+    #[allow(unrooted_must_root)]
+    fn new(global: &AudioWorkletGlobalScope, port: &MessagePort) -> DomRoot<AudioWorkletProcessor> {
+        reflect_dom_object(
+            Box::new(AudioWorkletProcessor::new_inherited(port)),
+            global,
+            AudioWorkletProcessorBinding::Wrap,
+        )
+    }
+
+    /// <https://webaudio.github.io/web-audio-api/#dom-audioworkletprocessor-audioworkletprocessor>
+    pub fn Constructor(
+        global: &AudioWorkletGlobalScope,
+    ) -> Fallible<DomRoot<AudioWorkletProcessor>> {
+        // Steps 1-2: processors can only be constructed on behalf of a node.
+        let port = global
+            .take_pending_processor_port()
+            .ok_or_else(|| Error::Type(String::from("Illegal constructor.")))?;
+        // Steps 3-5.
+        Ok(AudioWorkletProcessor::new(global, &port))
+    }
+
+    pub fn port(&self) -> DomRoot<MessagePort> {
+        DomRoot::from_ref(&*self.port)
+    }
+}
+
+impl AudioWorkletProcessorMethods for AudioWorkletProcessor {
+    /// <https://webaudio.github.io/web-audio-api/#dom-audioworkletprocessor-port>
+    fn Port(&self) -> DomRoot<MessagePort> {
+        self.port()
+    }
+}
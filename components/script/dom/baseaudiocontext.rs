@@ -9,6 +9,7 @@ use crate::dom::audiobuffersourcenode::AudioBufferSourceNode;
 use crate::dom::audiodestinationnode::AudioDestinationNode;
 use crate::dom::audiolistener::AudioListener;
 use crate::dom::audionode::MAX_CHANNEL_COUNT;
+use crate::dom::audioworklet::AudioWorklet;
 use crate::dom::bindings::callback::ExceptionHandling;
 use crate::dom::bindings::cell::DomRefCell;
 use crate::dom::bindings::codegen::Bindings::AnalyserNodeBinding::AnalyserOptions;
@@ -25,10 +26,15 @@ use crate::dom::bindings::codegen::Bindings::BiquadFilterNodeBinding::BiquadFilt
 use crate::dom::bindings::codegen::Bindings::ChannelMergerNodeBinding::ChannelMergerOptions;
 use crate::dom::bindings::codegen::Bindings::ChannelSplitterNodeBinding::ChannelSplitterOptions;
 use crate::dom::bindings::codegen::Bindings::ConstantSourceNodeBinding::ConstantSourceOptions;
+use crate::dom::bindings::codegen::Bindings::ConvolverNodeBinding::ConvolverOptions;
+use crate::dom::bindings::codegen::Bindings::DelayNodeBinding::DelayOptions;
+use crate::dom::bindings::codegen::Bindings::DynamicsCompressorNodeBinding::DynamicsCompressorOptions;
 use crate::dom::bindings::codegen::Bindings::GainNodeBinding::GainOptions;
+use crate::dom::bindings::codegen::Bindings::IIRFilterNodeBinding::IIRFilterOptions;
 use crate::dom::bindings::codegen::Bindings::OscillatorNodeBinding::OscillatorOptions;
 use crate::dom::bindings::codegen::Bindings::PannerNodeBinding::PannerOptions;
 use crate::dom::bindings::codegen::Bindings::StereoPannerNodeBinding::StereoPannerOptions;
+use crate::dom::bindings::codegen::Bindings::WaveShaperNodeBinding::WaveShaperOptions;
 use crate::dom::bindings::error::{Error, ErrorResult, Fallible};
 use crate::dom::bindings::inheritance::Castable;
 use crate::dom::bindings::num::Finite;
@@ -39,13 +45,18 @@ use crate::dom::biquadfilternode::BiquadFilterNode;
 use crate::dom::channelmergernode::ChannelMergerNode;
 use crate::dom::channelsplitternode::ChannelSplitterNode;
 use crate::dom::constantsourcenode::ConstantSourceNode;
+use crate::dom::convolvernode::ConvolverNode;
+use crate::dom::delaynode::DelayNode;
 use crate::dom::domexception::{DOMErrorName, DOMException};
+use crate::dom::dynamicscompressornode::DynamicsCompressorNode;
 use crate::dom::eventtarget::EventTarget;
 use crate::dom::gainnode::GainNode;
+use crate::dom::iirfilternode::IIRFilterNode;
 use crate::dom::oscillatornode::OscillatorNode;
 use crate::dom::pannernode::PannerNode;
 use crate::dom::promise::Promise;
 use crate::dom::stereopannernode::StereoPannerNode;
+use crate::dom::waveshapernode::WaveShaperNode;
 use crate::dom::window::Window;
 use crate::task_source::TaskSource;
 use dom_struct::dom_struct;
@@ -86,6 +97,8 @@ pub struct BaseAudioContext {
     /// https://webaudio.github.io/web-audio-api/#dom-baseaudiocontext-destination
     destination: MutNullableDom<AudioDestinationNode>,
     listener: MutNullableDom<AudioListener>,
+    /// https://webaudio.github.io/web-audio-api/#dom-baseaudiocontext-audioworklet
+    audio_worklet: MutNullableDom<AudioWorklet>,
     /// Resume promises which are soon to be fulfilled by a queued task.
     #[ignore_malloc_size_of = "promises are hard"]
     in_flight_resume_promises_queue: DomRefCell<VecDeque<(Box<[Rc<Promise>]>, ErrorResult)>>,
@@ -127,6 +140,7 @@ impl BaseAudioContext {
                 .create_audio_context(&client_context_id, options.into()),
             destination: Default::default(),
             listener: Default::default(),
+            audio_worklet: Default::default(),
             in_flight_resume_promises_queue: Default::default(),
             pending_resume_promises: Default::default(),
             decode_resolvers: Default::default(),
@@ -155,6 +169,14 @@ impl BaseAudioContext {
         self.audio_context_impl.lock().unwrap().listener()
     }
 
+    /// The worklet running the processors of this context's AudioWorkletNodes.
+    pub fn audio_worklet(&self) -> DomRoot<AudioWorklet> {
+        let global = self.global();
+        let window = global.as_window();
+        self.audio_worklet
+            .or_init(|| AudioWorklet::new(&window, self.sample_rate))
+    }
+
     // https://webaudio.github.io/web-audio-api/#allowed-to-start
     pub fn is_allowed_to_start(&self) -> bool {
         self.state.get() == AudioContextState::Suspended
@@ -331,6 +353,11 @@ impl BaseAudioContextMethods for BaseAudioContext {
         self.listener.or_init(|| AudioListener::new(&window, self))
     }
 
+    /// https://webaudio.github.io/web-audio-api/#dom-baseaudiocontext-audioworklet
+    fn AudioWorklet(&self) -> DomRoot<AudioWorklet> {
+        self.audio_worklet()
+    }
+
     /// https://webaudio.github.io/web-audio-api/#dom-baseaudiocontext-onstatechange
     event_handler!(statechange, GetOnstatechange, SetOnstatechange);
 
@@ -348,6 +375,13 @@ impl BaseAudioContextMethods for BaseAudioContext {
         GainNode::new(&self.global().as_window(), &self, &GainOptions::empty())
     }
 
+    /// https://webaudio.github.io/web-audio-api/#dom-baseaudiocontext-createdelay
+    fn CreateDelay(&self, max_delay_time: Finite<f64>) -> Fallible<DomRoot<DelayNode>> {
+        let mut opts = DelayOptions::empty();
+        opts.maxDelayTime = max_delay_time;
+        DelayNode::new(&self.global().as_window(), &self, &opts)
+    }
+
     /// https://webaudio.github.io/web-audio-api/#dom-baseaudiocontext-createpanner
     fn CreatePanner(&self) -> Fallible<DomRoot<PannerNode>> {
         PannerNode::new(&self.global().as_window(), &self, &PannerOptions::empty())
@@ -367,6 +401,29 @@ impl BaseAudioContextMethods for BaseAudioContext {
         )
     }
 
+    /// https://webaudio.github.io/web-audio-api/#dom-baseaudiocontext-createiirfilter
+    fn CreateIIRFilter(
+        &self,
+        feedforward: Vec<Finite<f64>>,
+        feedback: Vec<Finite<f64>>,
+    ) -> Fallible<DomRoot<IIRFilterNode>> {
+        let opts = IIRFilterOptions {
+            parent: AudioNodeOptions::empty(),
+            feedforward,
+            feedback,
+        };
+        IIRFilterNode::new(&self.global().as_window(), &self, &opts)
+    }
+
+    /// https://webaudio.github.io/web-audio-api/#dom-baseaudiocontext-createwaveshaper
+    fn CreateWaveShaper(&self) -> Fallible<DomRoot<WaveShaperNode>> {
+        WaveShaperNode::new(
+            &self.global().as_window(),
+            &self,
+            &WaveShaperOptions::empty(),
+        )
+    }
+
     /// https://webaudio.github.io/web-audio-api/#dom-baseaudiocontext-createstereopanner
     fn CreateStereoPanner(&self) -> Fallible<DomRoot<StereoPannerNode>> {
         StereoPannerNode::new(
@@ -385,6 +442,15 @@ impl BaseAudioContextMethods for BaseAudioContext {
         )
     }
 
+    /// https://webaudio.github.io/web-audio-api/#dom-baseaudiocontext-createconvolver
+    fn CreateConvolver(&self) -> Fallible<DomRoot<ConvolverNode>> {
+        ConvolverNode::new(
+            &self.global().as_window(),
+            &self,
+            &ConvolverOptions::empty(),
+        )
+    }
+
     /// https://webaudio.github.io/web-audio-api/#dom-baseaudiocontext-createchannelmerger
     fn CreateChannelMerger(&self, count: u32) -> Fallible<DomRoot<ChannelMergerNode>> {
         let mut opts = ChannelMergerOptions::empty();
@@ -399,6 +465,15 @@ impl BaseAudioContextMethods for BaseAudioContext {
         ChannelSplitterNode::new(&self.global().as_window(), &self, &opts)
     }
 
+    /// https://webaudio.github.io/web-audio-api/#dom-baseaudiocontext-createdynamicscompressor
+    fn CreateDynamicsCompressor(&self) -> Fallible<DomRoot<DynamicsCompressorNode>> {
+        DynamicsCompressorNode::new(
+            &self.global().as_window(),
+            &self,
+            &DynamicsCompressorOptions::empty(),
+        )
+    }
+
     /// https://webaudio.github.io/web-audio-api/#dom-baseaudiocontext-createbuffer
     fn CreateBuffer(
         &self,
@@ -732,8 +732,12 @@ def getJSToNativeConversionInfo(type, descriptorProvider, failureCode=None,
                 default = "%s::Boolean(%s)" % (
                     union_native_type(type),
                     "true" if defaultValue.value else "false")
+            elif tag is IDLType.Tags.domstring:
+                default = '%s::String(DOMString::from("%s"))' % (
+                    union_native_type(type),
+                    defaultValue.value)
             else:
-                raise("We don't currently support default values that aren't null, boolean or default dictionary")
+                raise("We don't currently support default values that aren't null, boolean, string or default dictionary")
         elif dictionaries:
             if defaultValue:
                 assert isinstance(defaultValue, IDLDefaultDictionaryValue)
@@ -3488,7 +3492,11 @@ class CGPerSignatureCall(CGThing):
         return 'infallible' not in self.extendedAttributes
 
     def wrap_return_value(self):
-        return wrapForType('MutableHandleValue::from_raw(args.rval())')
+        successCode = 'return true;'
+        if self.idlNode.isStatic() and self.idlNode.identifier.name == "constructor":
+            # Classes extending the interface construct instances of themselves.
+            successCode = 'return set_prototype_from_new_target(cx, &args);'
+        return wrapForType('MutableHandleValue::from_raw(args.rval())', successCode=successCode)
 
     def define(self):
         return (self.cgRoot.define() + "\n" + self.wrap_return_value())
@@ -4212,12 +4220,12 @@ def getEnumValueName(value):
     # characters in them.  Deal with the former by returning "_empty",
     # deal with possible name collisions from that by throwing if the
     # enum value is actually "_empty", and throw on any value
-    # containing non-ASCII chars for now. Replace all chars other than
-    # [0-9A-Za-z_] with '_'.
+    # containing non-ASCII chars for now. Prefix values starting with a
+    # digit with '_', and replace all chars other than [0-9A-Za-z_] with '_'.
     if re.match("[^\x20-\x7E]", value):
         raise SyntaxError('Enum value "' + value + '" contains non-ASCII characters')
     if re.match("^[0-9]", value):
-        raise SyntaxError('Enum value "' + value + '" starts with a digit')
+        value = '_' + value
     value = re.sub(r'[^0-9A-Za-z_]', '_', value)
     if re.match("^_[A-Z]|__", value):
         raise SyntaxError('Enum value "' + value + '" is reserved by the C++ spec')
@@ -6003,6 +6011,7 @@ def generate_imports(config, cgthings, descriptors, callbacks=None, dictionaries
         'crate::dom::bindings::interface::define_guarded_properties',
         'crate::dom::bindings::htmlconstructor::html_constructor',
         'crate::dom::bindings::interface::is_exposed_in',
+        'crate::dom::bindings::interface::set_prototype_from_new_target',
         'crate::dom::bindings::htmlconstructor::pop_current_element_queue',
         'crate::dom::bindings::htmlconstructor::push_new_element_queue',
         'crate::dom::bindings::iterable::Iterable',
@@ -169,7 +169,9 @@ class Descriptor(DescriptorProvider):
         DescriptorProvider.__init__(self, config)
         self.interface = interface
 
-        if not self.isExposedConditionally():
+        # Interfaces without an interface object expose nothing on the global,
+        # so there is nothing to hide when their parent is not exposed.
+        if not self.isExposedConditionally() and interface.hasInterfaceObject():
             if interface.parent and interface.parent.isExposedConditionally():
                 raise TypeError("%s is not conditionally exposed but inherits from "
                                 "%s which is" %
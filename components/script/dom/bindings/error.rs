@@ -87,6 +87,10 @@ pub enum Error {
     NotReadable,
     /// OperationError DOMException
     Operation,
+    /// NotAllowedError DOMException
+    NotAllowed,
+    /// EncodingError DOMException
+    Encoding,
 
     /// TypeError JavaScript Error
     Type(String),
@@ -140,6 +144,8 @@ pub fn throw_dom_exception(cx: SafeJSContext, global: &GlobalScope, result: Erro
         Error::InvalidModification => DOMErrorName::InvalidModificationError,
         Error::NotReadable => DOMErrorName::NotReadableError,
         Error::Operation => DOMErrorName::OperationError,
+        Error::NotAllowed => DOMErrorName::NotAllowedError,
+        Error::Encoding => DOMErrorName::EncodingError,
         Error::Type(message) => unsafe {
             assert!(!JS_IsExceptionPending(*cx));
             throw_type_error(*cx, &message);
@@ -17,7 +17,7 @@ use js::error::throw_type_error;
 use js::glue::UncheckedUnwrapObject;
 use js::jsapi::HandleObject as RawHandleObject;
 use js::jsapi::MutableHandleValue as RawMutableHandleValue;
-use js::jsapi::{jsid, Class, ClassOps};
+use js::jsapi::{jsid, CallArgs, Class, ClassOps};
 use js::jsapi::{GetNonCCWObjectGlobal, GetWellKnownSymbol};
 use js::jsapi::{JSAutoRealm, JSClass, JSContext, JSFunctionSpec, JSObject, JSFUN_CONSTRUCTOR};
 use js::jsapi::{JSPropertySpec, JSString, JSTracer, JS_AtomizeAndPinString};
@@ -27,14 +27,16 @@ use js::jsapi::{JS_NewStringCopyN, JS_SetReservedSlot};
 use js::jsapi::{ObjectOps, OnNewGlobalHookOption, SymbolCode};
 use js::jsapi::{TrueHandleValue, Value};
 use js::jsapi::{JSPROP_PERMANENT, JSPROP_READONLY, JSPROP_RESOLVING};
-use js::jsval::{JSVal, PrivateValue};
+use js::jsval::{JSVal, PrivateValue, UndefinedValue};
 use js::rust::wrappers::RUST_SYMBOL_TO_JSID;
 use js::rust::wrappers::{JS_DefineProperty, JS_DefineProperty5};
 use js::rust::wrappers::{JS_DefineProperty3, JS_DefineProperty4, JS_DefinePropertyById5};
-use js::rust::wrappers::{JS_FireOnNewGlobalObject, JS_GetPrototype};
+use js::rust::wrappers::{JS_FireOnNewGlobalObject, JS_GetProperty, JS_GetPrototype};
 use js::rust::wrappers::{JS_LinkConstructorAndPrototype, JS_NewObjectWithUniqueType};
+use js::rust::wrappers::{JS_SetPrototype, JS_WrapObject};
+use js::rust::RealmOptions;
 use js::rust::{define_methods, define_properties, get_object_class};
-use js::rust::{HandleObject, HandleValue, MutableHandleObject, RealmOptions};
+use js::rust::{HandleObject, HandleValue, MutableHandleObject, MutableHandleValue};
 use std::convert::TryFrom;
 use std::ptr;
 
@@ -375,6 +377,39 @@ pub fn is_exposed_in(object: HandleObject, globals: Globals) -> bool {
     }
 }
 
+/// Give the object returned by a constructor the prototype of `new.target`,
+/// when it is a class extending the interface rather than the interface
+/// object itself. Returns false if an exception is pending.
+pub unsafe fn set_prototype_from_new_target(cx: SafeJSContext, args: &CallArgs) -> bool {
+    rooted!(in(*cx) let new_target = args.new_target().to_object());
+    if args.callee() == new_target.get() {
+        return true;
+    }
+
+    rooted!(in(*cx) let mut proto_val = UndefinedValue());
+    {
+        let _ac = JSAutoRealm::new(*cx, new_target.get());
+        if !JS_GetProperty(
+            *cx,
+            new_target.handle(),
+            b"prototype\0".as_ptr() as *const libc::c_char,
+            proto_val.handle_mut(),
+        ) {
+            return false;
+        }
+    }
+    // Otherwise the object keeps the prototype of the interface.
+    if !proto_val.is_object() {
+        return true;
+    }
+    rooted!(in(*cx) let mut prototype = proto_val.to_object());
+    if !JS_WrapObject(*cx, prototype.handle_mut()) {
+        return false;
+    }
+    rooted!(in(*cx) let object = MutableHandleValue::from_raw(args.rval()).to_object());
+    JS_SetPrototype(*cx, object.handle(), prototype.handle())
+}
+
 /// Define a property with a given name on the global object. Should be called
 /// through the resolve hook.
 pub fn define_on_global_object(
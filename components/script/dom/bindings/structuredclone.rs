@@ -6,12 +6,15 @@
 //! (https://html.spec.whatwg.org/multipage/#safe-passing-of-structured-data).
 
 use crate::compartments::enter_realm;
-use crate::dom::bindings::conversions::root_from_handleobject;
+use crate::dom::bindings::conversions::{root_from_handleobject, root_from_object};
 use crate::dom::bindings::error::{Error, Fallible};
 use crate::dom::bindings::reflector::DomObject;
 use crate::dom::bindings::root::DomRoot;
 use crate::dom::blob::{Blob, BlobImpl};
 use crate::dom::globalscope::GlobalScope;
+use crate::dom::imagebitmap::ImageBitmap;
+use crate::dom::offscreencanvas::{OffscreenCanvas, TransferredOffscreenCanvas};
+use euclid::default::Size2D;
 use js::glue::CopyJSStructuredCloneData;
 use js::glue::DeleteJSAutoStructuredCloneBuffer;
 use js::glue::GetLengthOfJSStructuredCloneData;
@@ -44,6 +47,8 @@ enum StructuredCloneTags {
     /// To support additional types, add new tags with values incremented from the last one before Max.
     Min = 0xFFFF8000,
     DomBlob = 0xFFFF8001,
+    DomImageBitmap = 0xFFFF8002,
+    DomOffscreenCanvas = 0xFFFF8003,
     Max = 0xFFFFFFFF,
 }
 
@@ -155,11 +160,99 @@ unsafe fn write_blob(blob: DomRoot<Blob>, w: *mut JSStructuredCloneWriter) -> Re
     return Ok(());
 }
 
+unsafe fn read_image_bitmap(
+    cx: *mut JSContext,
+    r: *mut JSStructuredCloneReader,
+    premultiplied: bool,
+    sc_holder: &mut StructuredCloneHolder,
+) -> *mut JSObject {
+    let structured_reader = StructuredCloneReader { r: r };
+    let mut width: u32 = 0;
+    let mut height: u32 = 0;
+    assert!(JS_ReadUint32Pair(
+        r,
+        &mut width as *mut u32,
+        &mut height as *mut u32
+    ));
+    let bitmap_data = structured_reader.read_bytes();
+    let target_global = GlobalScope::from_context(cx);
+    let bitmap = ImageBitmap::new(
+        &target_global,
+        Size2D::new(width, height),
+        bitmap_data,
+        premultiplied,
+        true,
+    );
+    let js_object = bitmap.reflector().get_jsobject().get();
+    sc_holder.image_bitmap = Some(bitmap);
+    js_object
+}
+
+// https://html.spec.whatwg.org/multipage/#the-imagebitmap-interface:serialization-steps
+unsafe fn write_image_bitmap(
+    bitmap: DomRoot<ImageBitmap>,
+    w: *mut JSStructuredCloneWriter,
+) -> Result<(), ()> {
+    if !bitmap.origin_is_clean() {
+        return Err(());
+    }
+    let structured_writer = StructuredCloneWriter { w: w };
+    let bitmap_data = bitmap.bitmap_data().ok_or(())?;
+    let size = bitmap.get_size();
+    assert!(JS_WriteUint32Pair(
+        w,
+        StructuredCloneTags::DomImageBitmap as u32,
+        bitmap.is_premultiplied() as u32
+    ));
+    assert!(JS_WriteUint32Pair(w, size.width, size.height));
+    structured_writer.write_slice(&bitmap_data);
+    return Ok(());
+}
+
+unsafe fn read_offscreen_canvas(
+    cx: *mut JSContext,
+    index: u32,
+    sc_holder: &mut StructuredCloneHolder,
+) -> *mut JSObject {
+    let transferred = match sc_holder
+        .transferred_offscreen_canvases
+        .get_mut(index as usize)
+        .and_then(|transferred| transferred.take())
+    {
+        Some(transferred) => transferred,
+        None => return ptr::null_mut(),
+    };
+    let target_global = GlobalScope::from_context(cx);
+    let canvas = OffscreenCanvas::new_transferred(&target_global, transferred);
+    let js_object = canvas.reflector().get_jsobject().get();
+    sc_holder.offscreen_canvas = Some(canvas);
+    js_object
+}
+
+// Offscreen canvases can't be serialized, only transferred, so they are
+// written as their index in the list of transferred canvases.
+unsafe fn write_offscreen_canvas(
+    canvas: DomRoot<OffscreenCanvas>,
+    w: *mut JSStructuredCloneWriter,
+    transferred: &[DomRoot<OffscreenCanvas>],
+) -> Result<(), ()> {
+    let index = transferred
+        .iter()
+        .position(|transferred| *transferred == canvas)
+        .ok_or(())?;
+    assert!(JS_WriteUint32Pair(
+        w,
+        StructuredCloneTags::DomOffscreenCanvas as u32,
+        index as u32
+    ));
+    return Ok(());
+}
+
 unsafe extern "C" fn read_callback(
     cx: *mut JSContext,
     r: *mut JSStructuredCloneReader,
     tag: u32,
-    _data: u32,
+    data: u32,
     closure: *mut raw::c_void,
 ) -> *mut JSObject {
     assert!(
@@ -173,6 +266,17 @@ unsafe extern "C" fn read_callback(
     if tag == StructuredCloneTags::DomBlob as u32 {
         return read_blob(cx, r, &mut *(closure as *mut StructuredCloneHolder));
     }
+    if tag == StructuredCloneTags::DomImageBitmap as u32 {
+        return read_image_bitmap(
+            cx,
+            r,
+            data != 0,
+            &mut *(closure as *mut StructuredCloneHolder),
+        );
+    }
+    if tag == StructuredCloneTags::DomOffscreenCanvas as u32 {
+        return read_offscreen_canvas(cx, data, &mut *(closure as *mut StructuredCloneHolder));
+    }
     return ptr::null_mut();
 }
 
@@ -180,11 +284,18 @@ unsafe extern "C" fn write_callback(
     cx: *mut JSContext,
     w: *mut JSStructuredCloneWriter,
     obj: RawHandleObject,
-    _closure: *mut raw::c_void,
+    closure: *mut raw::c_void,
 ) -> bool {
     if let Ok(blob) = root_from_handleobject::<Blob>(Handle::from_raw(obj), cx) {
         return write_blob(blob, w).is_ok();
     }
+    if let Ok(bitmap) = root_from_handleobject::<ImageBitmap>(Handle::from_raw(obj), cx) {
+        return write_image_bitmap(bitmap, w).is_ok();
+    }
+    if let Ok(canvas) = root_from_handleobject::<OffscreenCanvas>(Handle::from_raw(obj), cx) {
+        let transferred = &*(closure as *const Vec<DomRoot<OffscreenCanvas>>);
+        return write_offscreen_canvas(canvas, w, transferred).is_ok();
+    }
     return false;
 }
 
@@ -243,6 +354,9 @@ static STRUCTURED_CLONE_CALLBACKS: JSStructuredCloneCallbacks = JSStructuredClon
 
 struct StructuredCloneHolder {
     blob: Option<DomRoot<Blob>>,
+    image_bitmap: Option<DomRoot<ImageBitmap>>,
+    offscreen_canvas: Option<DomRoot<OffscreenCanvas>>,
+    transferred_offscreen_canvases: Vec<Option<TransferredOffscreenCanvas>>,
 }
 
 /// A buffer for a structured clone.
@@ -251,12 +365,26 @@ pub enum StructuredCloneData {
     Struct(*mut u64, size_t),
     /// A variant that can be serialized
     Vector(Vec<u8>),
+    /// A variant that took over offscreen canvases, which can only be sent
+    /// to another thread of the same process
+    Transferred(Vec<u8>, Vec<TransferredOffscreenCanvas>),
 }
 
 impl StructuredCloneData {
     // TODO: should this be unsafe?
     /// Writes a structured clone. Returns a `DataClone` error if that fails.
     pub fn write(cx: *mut JSContext, message: HandleValue) -> Fallible<StructuredCloneData> {
+        let data = StructuredCloneData::write_clone(cx, message, &vec![])?;
+        Ok(StructuredCloneData::Vector(data))
+    }
+
+    /// Writes a structured clone in which the offscreen canvases of
+    /// `offscreen_canvases` are written as transferred.
+    fn write_clone(
+        cx: *mut JSContext,
+        message: HandleValue,
+        offscreen_canvases: &Vec<DomRoot<OffscreenCanvas>>,
+    ) -> Fallible<Vec<u8>> {
         unsafe {
             let scbuf = NewJSAutoStructuredCloneBuffer(
                 StructuredCloneScope::DifferentProcess,
@@ -274,7 +402,7 @@ impl StructuredCloneData {
                 StructuredCloneScope::DifferentProcess,
                 policy,
                 &STRUCTURED_CLONE_CALLBACKS,
-                ptr::null_mut(),
+                offscreen_canvases as *const _ as *mut raw::c_void,
                 HandleValue::undefined(),
             );
             if !result {
@@ -289,10 +417,58 @@ impl StructuredCloneData {
 
             DeleteJSAutoStructuredCloneBuffer(scbuf);
 
-            Ok(StructuredCloneData::Vector(data))
+            Ok(data)
         }
     }
 
+    /// Writes a structured clone of `message` that takes over the objects in
+    /// `transfer`. `ImageBitmap`s are serialized like any other bitmap, then
+    /// detached. `OffscreenCanvas`es without a rendering context are detached,
+    /// and what they draw into is handed over to the canvases the clone is
+    /// read into. Returns a `DataClone` error if that fails.
+    ///
+    /// <https://html.spec.whatwg.org/multipage/#structuredserializewithtransfer>
+    pub fn write_with_transfer(
+        cx: *mut JSContext,
+        message: HandleValue,
+        transfer: &[*mut JSObject],
+    ) -> Fallible<StructuredCloneData> {
+        let mut bitmaps: Vec<DomRoot<ImageBitmap>> = vec![];
+        let mut offscreen_canvases: Vec<DomRoot<OffscreenCanvas>> = vec![];
+        for object in transfer {
+            if let Ok(canvas) = root_from_object::<OffscreenCanvas>(*object, cx) {
+                if canvas.is_detached() || offscreen_canvases.contains(&canvas) {
+                    return Err(Error::DataClone);
+                }
+                if canvas.context().is_some() {
+                    return Err(Error::InvalidState);
+                }
+                offscreen_canvases.push(canvas);
+                continue;
+            }
+            let bitmap =
+                root_from_object::<ImageBitmap>(*object, cx).map_err(|_| Error::DataClone)?;
+            if bitmap.is_detached() || !bitmap.origin_is_clean() || bitmaps.contains(&bitmap) {
+                return Err(Error::DataClone);
+            }
+            bitmaps.push(bitmap);
+        }
+
+        let data = StructuredCloneData::write_clone(cx, message, &offscreen_canvases)?;
+
+        for bitmap in bitmaps {
+            bitmap.detach();
+        }
+        if offscreen_canvases.is_empty() {
+            return Ok(StructuredCloneData::Vector(data));
+        }
+        let transferred = offscreen_canvases
+            .iter()
+            .map(|canvas| canvas.transfer())
+            .collect();
+        Ok(StructuredCloneData::Transferred(data, transferred))
+    }
+
     /// Converts a StructuredCloneData to Vec<u8> for inter-thread sharing
     pub fn move_to_arraybuffer(self) -> Vec<u8> {
         match self {
@@ -300,16 +476,33 @@ impl StructuredCloneData {
                 slice::from_raw_parts(data as *mut u8, nbytes).to_vec()
             },
             StructuredCloneData::Vector(msg) => msg,
+            StructuredCloneData::Transferred(..) => {
+                unreachable!("Transferred offscreen canvases can't leave the process")
+            },
         }
     }
 
     /// Reads a structured clone.
     ///
     /// Panics if `JS_ReadStructuredClone` fails.
-    fn read_clone(global: &GlobalScope, data: *mut u64, nbytes: size_t, rval: MutableHandleValue) {
+    fn read_clone(
+        global: &GlobalScope,
+        data: *mut u64,
+        nbytes: size_t,
+        transferred_offscreen_canvases: Vec<TransferredOffscreenCanvas>,
+        rval: MutableHandleValue,
+    ) {
         let cx = global.get_cx();
         let _ac = enter_realm(&*global);
-        let mut sc_holder = StructuredCloneHolder { blob: None };
+        let mut sc_holder = StructuredCloneHolder {
+            blob: None,
+            image_bitmap: None,
+            offscreen_canvas: None,
+            transferred_offscreen_canvases: transferred_offscreen_canvases
+                .into_iter()
+                .map(Some)
+                .collect(),
+        };
         let sc_holder_ptr = &mut sc_holder as *mut _;
         unsafe {
             let scbuf = NewJSAutoStructuredCloneBuffer(
@@ -340,10 +533,15 @@ impl StructuredCloneData {
             StructuredCloneData::Vector(mut vec_msg) => {
                 let nbytes = vec_msg.len();
                 let data = vec_msg.as_mut_ptr() as *mut u64;
-                StructuredCloneData::read_clone(global, data, nbytes, rval);
+                StructuredCloneData::read_clone(global, data, nbytes, vec![], rval);
             },
             StructuredCloneData::Struct(data, nbytes) => {
-                StructuredCloneData::read_clone(global, data, nbytes, rval)
+                StructuredCloneData::read_clone(global, data, nbytes, vec![], rval)
+            },
+            StructuredCloneData::Transferred(mut vec_msg, offscreen_canvases) => {
+                let nbytes = vec_msg.len();
+                let data = vec_msg.as_mut_ptr() as *mut u64;
+                StructuredCloneData::read_clone(global, data, nbytes, offscreen_canvases, rval);
             },
         }
     }
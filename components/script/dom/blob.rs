@@ -61,6 +61,17 @@ impl BlobImpl {
             size: size,
         })
     }
+
+    /// Construct file-backed BlobImpl from the ID of bytes that were already
+    /// promoted to the file manager, keeping them cached
+    pub fn new_from_promoted_bytes(file_id: Uuid, bytes: Vec<u8>) -> BlobImpl {
+        BlobImpl::File(FileBlob {
+            id: file_id,
+            name: None,
+            size: bytes.len() as u64,
+            cache: DomRefCell::new(Some(bytes)),
+        })
+    }
 }
 
 // https://w3c.github.io/FileAPI/#blob
@@ -0,0 +1,89 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::bindings::codegen::Bindings::BlobEventBinding::{self, BlobEventMethods};
+use crate::dom::bindings::codegen::Bindings::EventBinding::EventBinding::EventMethods;
+use crate::dom::bindings::codegen::Bindings::PerformanceBinding::DOMHighResTimeStamp;
+use crate::dom::bindings::error::Fallible;
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::num::Finite;
+use crate::dom::bindings::reflector::{reflect_dom_object, DomObject};
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::blob::Blob;
+use crate::dom::event::Event;
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::window::Window;
+use dom_struct::dom_struct;
+use servo_atoms::Atom;
+
+#[dom_struct]
+pub struct BlobEvent {
+    event: Event,
+    data: Dom<Blob>,
+    timecode: f64,
+}
+
+impl BlobEvent {
+    #[allow(unrooted_must_root)]
+    fn new_inherited(data: &Blob, timecode: f64) -> BlobEvent {
+        BlobEvent {
+            event: Event::new_inherited(),
+            data: Dom::from_ref(data),
+            timecode,
+        }
+    }
+
+    pub fn new(
+        global: &GlobalScope,
+        type_: Atom,
+        bubbles: bool,
+        cancelable: bool,
+        data: &Blob,
+        timecode: f64,
+    ) -> DomRoot<BlobEvent> {
+        let blobevent = reflect_dom_object(
+            Box::new(BlobEvent::new_inherited(data, timecode)),
+            global,
+            BlobEventBinding::Wrap,
+        );
+        {
+            let event = blobevent.upcast::<Event>();
+            event.init_event(type_, bubbles, cancelable);
+        }
+        blobevent
+    }
+
+    pub fn Constructor(
+        window: &Window,
+        type_: DOMString,
+        init: &BlobEventBinding::BlobEventInit,
+    ) -> Fallible<DomRoot<BlobEvent>> {
+        Ok(BlobEvent::new(
+            &window.global(),
+            Atom::from(type_),
+            init.parent.bubbles,
+            init.parent.cancelable,
+            &init.data,
+            init.timecode.map_or(0., |timecode| *timecode),
+        ))
+    }
+}
+
+impl BlobEventMethods for BlobEvent {
+    // https://w3c.github.io/mediacapture-record/#dom-blobevent-data
+    fn Data(&self) -> DomRoot<Blob> {
+        DomRoot::from_ref(&*self.data)
+    }
+
+    // https://w3c.github.io/mediacapture-record/#dom-blobevent-timecode
+    fn Timecode(&self) -> DOMHighResTimeStamp {
+        Finite::wrap(self.timecode)
+    }
+
+    // https://dom.spec.whatwg.org/#dom-event-istrusted
+    fn IsTrusted(&self) -> bool {
+        self.event.IsTrusted()
+    }
+}
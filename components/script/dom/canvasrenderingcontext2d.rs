@@ -4,11 +4,15 @@
 
 use crate::dom::bindings::cell::DomRefCell;
 use crate::dom::bindings::codegen::Bindings::CanvasRenderingContext2DBinding;
+use crate::dom::bindings::codegen::Bindings::CanvasRenderingContext2DBinding::CanvasDirection;
 use crate::dom::bindings::codegen::Bindings::CanvasRenderingContext2DBinding::CanvasFillRule;
 use crate::dom::bindings::codegen::Bindings::CanvasRenderingContext2DBinding::CanvasImageSource;
 use crate::dom::bindings::codegen::Bindings::CanvasRenderingContext2DBinding::CanvasLineCap;
 use crate::dom::bindings::codegen::Bindings::CanvasRenderingContext2DBinding::CanvasLineJoin;
 use crate::dom::bindings::codegen::Bindings::CanvasRenderingContext2DBinding::CanvasRenderingContext2DMethods;
+use crate::dom::bindings::codegen::Bindings::CanvasRenderingContext2DBinding::CanvasTextAlign;
+use crate::dom::bindings::codegen::Bindings::CanvasRenderingContext2DBinding::CanvasTextBaseline;
+use crate::dom::bindings::codegen::Bindings::CanvasRenderingContext2DBinding::ImageSmoothingQuality;
 use crate::dom::bindings::codegen::Bindings::ImageDataBinding::ImageDataMethods;
 use crate::dom::bindings::codegen::UnionTypes::StringOrCanvasGradientOrCanvasPattern;
 use crate::dom::bindings::error::{Error, ErrorResult, Fallible};
@@ -24,12 +28,16 @@ use crate::dom::element::Element;
 use crate::dom::globalscope::GlobalScope;
 use crate::dom::htmlcanvaselement::{CanvasContext, HTMLCanvasElement};
 use crate::dom::imagedata::ImageData;
-use crate::dom::node::{Node, NodeDamage};
+use crate::dom::node::{window_from_node, Node, NodeDamage};
 use crate::dom::paintworkletglobalscope::PaintWorkletGlobalScope;
+use crate::dom::path2d::Path2D;
 use crate::dom::textmetrics::TextMetrics;
 use crate::unpremultiplytable::UNPREMULTIPLY_TABLE;
+use canvas_traits::canvas::ImageSmoothingQuality as SmoothingQuality;
 use canvas_traits::canvas::{Canvas2dMsg, CanvasId, CanvasMsg};
+use canvas_traits::canvas::{CanvasFontFamily, CanvasFontSlant, CanvasFontStyle};
 use canvas_traits::canvas::{CompositionOrBlending, FillOrStrokeStyle, FillRule};
+use canvas_traits::canvas::{Direction, FilterFunction, TextAlign, TextBaseline};
 use canvas_traits::canvas::{LineCapStyle, LineJoinStyle, LinearGradientStyle};
 use canvas_traits::canvas::{RadialGradientStyle, RepetitionStyle};
 use cssparser::Color as CSSColor;
@@ -55,6 +63,16 @@ use std::cell::Cell;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::{fmt, mem};
+use style::computed_values::direction::T as ComputedDirection;
+use style::context::QuirksMode;
+use style::parser::{Parse, ParserContext};
+use style::properties::longhands::font_variant_caps::computed_value::T as FontVariantCaps;
+use style::properties::style_structs::Font;
+use style::stylesheets::{CssRuleType, Origin};
+use style::values::computed::font::{FontStyle, SingleFontFamily};
+use style::values::generics::effects::Filter as GenericFilter;
+use style::values::specified::{Filter, Length, SimpleShadow};
+use style_traits::{ParsingMode, ToCss};
 
 #[must_root]
 #[derive(Clone, JSTraceable, MallocSizeOf)]
@@ -81,17 +99,25 @@ struct CanvasContextState {
     global_alpha: f64,
     global_composition: CompositionOrBlending,
     image_smoothing_enabled: bool,
+    image_smoothing_quality: SmoothingQuality,
     fill_style: CanvasFillOrStrokeStyle,
     stroke_style: CanvasFillOrStrokeStyle,
     line_width: f64,
     line_cap: LineCapStyle,
     line_join: LineJoinStyle,
     miter_limit: f64,
+    line_dash: Vec<f64>,
+    line_dash_offset: f64,
     transform: Transform2D<f32>,
     shadow_offset_x: f64,
     shadow_offset_y: f64,
     shadow_blur: f64,
     shadow_color: RGBA,
+    filter: DOMString,
+    font_style: CanvasFontStyle,
+    text_align: TextAlign,
+    text_baseline: TextBaseline,
+    direction: CanvasDirection,
 }
 
 impl CanvasContextState {
@@ -101,17 +127,25 @@ impl CanvasContextState {
             global_alpha: 1.0,
             global_composition: CompositionOrBlending::default(),
             image_smoothing_enabled: true,
+            image_smoothing_quality: SmoothingQuality::default(),
             fill_style: CanvasFillOrStrokeStyle::Color(black),
             stroke_style: CanvasFillOrStrokeStyle::Color(black),
             line_width: 1.0,
             line_cap: LineCapStyle::Butt,
             line_join: LineJoinStyle::Miter,
             miter_limit: 10.0,
+            line_dash: vec![],
+            line_dash_offset: 0.0,
             transform: Transform2D::identity(),
             shadow_offset_x: 0.0,
             shadow_offset_y: 0.0,
             shadow_blur: 0.0,
             shadow_color: RGBA::transparent(),
+            filter: DOMString::from("none"),
+            font_style: CanvasFontStyle::default(),
+            text_align: TextAlign::default(),
+            text_baseline: TextBaseline::default(),
+            direction: CanvasDirection::Inherit,
         }
     }
 }
@@ -135,18 +169,33 @@ pub struct CanvasState {
     saved_states: DomRefCell<Vec<CanvasContextState>>,
 }
 
+/// Asks the constellation to create a new canvas in the canvas paint thread.
+pub fn create_canvas(global: &GlobalScope, size: Size2D<u64>) -> (IpcSender<CanvasMsg>, CanvasId) {
+    let (sender, receiver) = profiled_ipc::channel(global.time_profiler_chan().clone()).unwrap();
+    let script_to_constellation_chan = global.script_to_constellation_chan();
+    debug!("Asking constellation to create new canvas thread.");
+    script_to_constellation_chan
+        .send(ScriptMsg::CreateCanvasPaintThread(size, sender))
+        .unwrap();
+    let canvas = receiver.recv().unwrap();
+    debug!("Done.");
+    canvas
+}
+
 impl CanvasState {
     pub fn new(global: &GlobalScope, size: Size2D<u64>) -> CanvasState {
         debug!("Creating new canvas rendering context.");
-        let (sender, receiver) =
-            profiled_ipc::channel(global.time_profiler_chan().clone()).unwrap();
-        let script_to_constellation_chan = global.script_to_constellation_chan();
-        debug!("Asking constellation to create new canvas thread.");
-        script_to_constellation_chan
-            .send(ScriptMsg::CreateCanvasPaintThread(size, sender))
-            .unwrap();
-        let (ipc_renderer, canvas_id) = receiver.recv().unwrap();
-        debug!("Done.");
+        let (ipc_renderer, canvas_id) = create_canvas(global, size);
+        CanvasState::new_with_canvas(global, ipc_renderer, canvas_id)
+    }
+
+    /// Creates a state drawing into an existing canvas of the canvas paint
+    /// thread, like the one shown by the placeholder of an offscreen canvas.
+    pub fn new_with_canvas(
+        global: &GlobalScope,
+        ipc_renderer: IpcSender<CanvasMsg>,
+        canvas_id: CanvasId,
+    ) -> CanvasState {
         // Worklets always receive a unique origin. This messes with fetching
         // cached images in the case of paint worklets, since the image cache
         // is keyed on the origin requesting the image data.
@@ -172,6 +221,10 @@ impl CanvasState {
         self.canvas_id.clone()
     }
 
+    pub fn get_ipc_renderer(&self) -> IpcSender<CanvasMsg> {
+        self.ipc_renderer.clone()
+    }
+
     pub fn send_canvas_2d_msg(&self, msg: Canvas2dMsg) {
         self.ipc_renderer
             .send(CanvasMsg::Canvas2d(msg, self.get_canvas_id()))
@@ -193,7 +246,7 @@ impl CanvasState {
         ))
     }
 
-    fn origin_is_clean(&self) -> bool {
+    pub fn origin_is_clean(&self) -> bool {
         self.origin_clean.get()
     }
 
@@ -205,6 +258,7 @@ impl CanvasState {
     fn is_origin_clean(&self, image: CanvasImageSource) -> bool {
         match image {
             CanvasImageSource::HTMLCanvasElement(canvas) => canvas.origin_is_clean(),
+            CanvasImageSource::ImageBitmap(bitmap) => bitmap.origin_is_clean(),
             CanvasImageSource::HTMLImageElement(image) => {
                 image.same_origin(GlobalScope::entry().origin())
             },
@@ -378,6 +432,27 @@ impl CanvasState {
                     dh,
                 )
             },
+            CanvasImageSource::ImageBitmap(ref bitmap) => {
+                // https://html.spec.whatwg.org/multipage/#check-the-usability-of-the-image-argument
+                // If the image argument is a detached ImageBitmap, then throw an
+                // InvalidStateError exception
+                let image_data = bitmap
+                    .premultiplied_bitmap_data()
+                    .ok_or(Error::InvalidState)?;
+                self.draw_image_data(
+                    htmlcanvas,
+                    image_data,
+                    bitmap.get_size(),
+                    sx,
+                    sy,
+                    sw,
+                    sh,
+                    dx,
+                    dy,
+                    dw,
+                    dh,
+                )
+            },
             CanvasImageSource::CSSStyleValue(ref value) => {
                 let url = value
                     .get_url(self.base_url.clone())
@@ -428,6 +503,7 @@ impl CanvasState {
         }
 
         let smoothing_enabled = self.state.borrow().image_smoothing_enabled;
+        let smoothing_quality = self.state.borrow().image_smoothing_quality;
 
         if let Some(context) = canvas.context() {
             match *context {
@@ -438,8 +514,28 @@ impl CanvasState {
                         dest_rect,
                         source_rect,
                         smoothing_enabled,
+                        smoothing_quality,
                     ));
                 },
+                CanvasContext::Placeholder(ref offscreen_canvas) => {
+                    let placeholder_canvas = offscreen_canvas
+                        .placeholder_canvas()
+                        .ok_or(Error::InvalidState)?;
+                    placeholder_canvas
+                        .ipc_renderer
+                        .send(CanvasMsg::Canvas2d(
+                            Canvas2dMsg::DrawImageInOther(
+                                self.get_canvas_id(),
+                                image_size,
+                                dest_rect,
+                                source_rect,
+                                smoothing_enabled,
+                                smoothing_quality,
+                            ),
+                            placeholder_canvas.canvas_id,
+                        ))
+                        .unwrap();
+                },
                 _ => return Err(Error::InvalidState),
             }
         } else {
@@ -449,6 +545,7 @@ impl CanvasState {
                 dest_rect,
                 source_rect,
                 smoothing_enabled,
+                smoothing_quality,
             ));
         }
 
@@ -475,6 +572,26 @@ impl CanvasState {
             .fetch_image_data(url, cors_setting)
             .ok_or(Error::InvalidState)?;
         pixels::rgba8_premultiply_inplace(&mut image_data);
+        self.draw_image_data(
+            canvas, image_data, image_size, sx, sy, sw, sh, dx, dy, dw, dh,
+        )
+    }
+
+    /// Draws `image_data`, in BGRA8 format with premultiplied alpha.
+    fn draw_image_data(
+        &self,
+        canvas: Option<&HTMLCanvasElement>,
+        image_data: Vec<u8>,
+        image_size: Size2D<u32>,
+        sx: f64,
+        sy: f64,
+        sw: Option<f64>,
+        sh: Option<f64>,
+        dx: f64,
+        dy: f64,
+        dw: Option<f64>,
+        dh: Option<f64>,
+    ) -> ErrorResult {
         let image_size = image_size.to_f64();
 
         let dw = dw.unwrap_or(image_size.width);
@@ -491,20 +608,37 @@ impl CanvasState {
         }
 
         let smoothing_enabled = self.state.borrow().image_smoothing_enabled;
+        let smoothing_quality = self.state.borrow().image_smoothing_quality;
         self.send_canvas_2d_msg(Canvas2dMsg::DrawImage(
             Some(image_data.into()),
             image_size,
             dest_rect,
             source_rect,
             smoothing_enabled,
+            smoothing_quality,
         ));
         self.mark_as_dirty(canvas);
         Ok(())
     }
 
+    // https://html.spec.whatwg.org/multipage/#concept-canvas-set-bitmap-dimensions
+    pub fn set_bitmap_dimensions(&self, size: Size2D<u32>) {
+        self.reset_to_initial_state();
+        self.ipc_renderer
+            .send(CanvasMsg::Recreate(size, self.get_canvas_id()))
+            .unwrap();
+    }
+
+    // https://html.spec.whatwg.org/multipage/#reset-the-rendering-context-to-its-default-state
+    fn reset_to_initial_state(&self) {
+        self.saved_states.borrow_mut().clear();
+        *self.state.borrow_mut() = CanvasContextState::new();
+    }
+
     fn mark_as_dirty(&self, canvas: Option<&HTMLCanvasElement>) {
         if let Some(ref canvas) = canvas {
             canvas.upcast::<Node>().dirty(NodeDamage::OtherNodeDamage);
+            canvas.content_changed();
         }
     }
 
@@ -828,6 +962,12 @@ impl CanvasState {
                     .unwrap_or_else(|| vec![0; size.area() as usize * 4]);
                 (data, size)
             },
+            CanvasImageSource::ImageBitmap(ref bitmap) => {
+                let data = bitmap
+                    .premultiplied_bitmap_data()
+                    .ok_or(Error::InvalidState)?;
+                (data, bitmap.get_size())
+            },
             CanvasImageSource::CSSStyleValue(ref value) => value
                 .get_url(self.base_url.clone())
                 .and_then(|url| self.fetch_image_data(url, None))
@@ -910,21 +1050,220 @@ impl CanvasState {
         self.state.borrow_mut().image_smoothing_enabled = value;
     }
 
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-imagesmoothingquality
+    pub fn ImageSmoothingQuality(&self) -> ImageSmoothingQuality {
+        match self.state.borrow().image_smoothing_quality {
+            SmoothingQuality::Low => ImageSmoothingQuality::Low,
+            SmoothingQuality::Medium => ImageSmoothingQuality::Medium,
+            SmoothingQuality::High => ImageSmoothingQuality::High,
+        }
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-imagesmoothingquality
+    pub fn SetImageSmoothingQuality(&self, value: ImageSmoothingQuality) {
+        self.state.borrow_mut().image_smoothing_quality = match value {
+            ImageSmoothingQuality::Low => SmoothingQuality::Low,
+            ImageSmoothingQuality::Medium => SmoothingQuality::Medium,
+            ImageSmoothingQuality::High => SmoothingQuality::High,
+        };
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-filter
+    pub fn Filter(&self) -> DOMString {
+        self.state.borrow().filter.clone()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-filter
+    pub fn SetFilter(&self, value: DOMString) {
+        let filter = match parse_filter(&value) {
+            Some(filter) => filter,
+            None => return,
+        };
+        self.state.borrow_mut().filter = value;
+        self.send_canvas_2d_msg(Canvas2dMsg::SetFilter(filter));
+    }
+
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-filltext
-    pub fn FillText(&self, text: DOMString, x: f64, y: f64, max_width: Option<f64>) {
-        let parsed_text: String = text.into();
-        self.send_canvas_2d_msg(Canvas2dMsg::FillText(parsed_text, x, y, max_width));
+    pub fn FillText(
+        &self,
+        canvas: Option<&HTMLCanvasElement>,
+        text: DOMString,
+        x: f64,
+        y: f64,
+        max_width: Option<f64>,
+    ) {
+        if !self.text_drawing_arguments_are_valid(x, y, max_width) {
+            return;
+        }
+        let direction = self.resolved_direction(canvas);
+        self.send_canvas_2d_msg(Canvas2dMsg::FillText(
+            text.into(),
+            x,
+            y,
+            max_width,
+            direction,
+        ));
     }
 
-    // https://html.spec.whatwg.org/multipage/#textmetrics
-    pub fn MeasureText(&self, global: &GlobalScope, _text: DOMString) -> DomRoot<TextMetrics> {
-        // FIXME: for now faking the implementation of MeasureText().
-        // See https://github.com/servo/servo/issues/5411#issuecomment-533776291
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-stroketext
+    pub fn StrokeText(
+        &self,
+        canvas: Option<&HTMLCanvasElement>,
+        text: DOMString,
+        x: f64,
+        y: f64,
+        max_width: Option<f64>,
+    ) {
+        if !self.text_drawing_arguments_are_valid(x, y, max_width) {
+            return;
+        }
+        let direction = self.resolved_direction(canvas);
+        self.send_canvas_2d_msg(Canvas2dMsg::StrokeText(
+            text.into(),
+            x,
+            y,
+            max_width,
+            direction,
+        ));
+    }
+
+    // https://html.spec.whatwg.org/multipage/#text-preparation-algorithm
+    fn text_drawing_arguments_are_valid(&self, x: f64, y: f64, max_width: Option<f64>) -> bool {
+        if !x.is_finite() || !y.is_finite() {
+            return false;
+        }
+        match max_width {
+            Some(max_width) => max_width.is_finite() && max_width > 0.,
+            None => true,
+        }
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-direction
+    fn resolved_direction(&self, canvas: Option<&HTMLCanvasElement>) -> Direction {
+        match self.state.borrow().direction {
+            CanvasDirection::Ltr => Direction::Ltr,
+            CanvasDirection::Rtl => Direction::Rtl,
+            CanvasDirection::Inherit => {
+                let style = canvas.and_then(|canvas| canvas.upcast::<Element>().style());
+                match style.map(|style| style.get_inherited_box().direction) {
+                    Some(ComputedDirection::Rtl) => Direction::Rtl,
+                    _ => Direction::Ltr,
+                }
+            },
+        }
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-measuretext
+    pub fn MeasureText(
+        &self,
+        global: &GlobalScope,
+        canvas: Option<&HTMLCanvasElement>,
+        text: DOMString,
+    ) -> DomRoot<TextMetrics> {
+        let direction = self.resolved_direction(canvas);
+        let (sender, receiver) = ipc::channel().unwrap();
+        self.send_canvas_2d_msg(Canvas2dMsg::MeasureText(text.into(), direction, sender));
+        let metrics = receiver.recv().unwrap();
         TextMetrics::new(
-            global, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+            global,
+            metrics.width,
+            metrics.actual_bounding_box_left,
+            metrics.actual_bounding_box_right,
+            metrics.font_bounding_box_ascent,
+            metrics.font_bounding_box_descent,
+            metrics.actual_bounding_box_ascent,
+            metrics.actual_bounding_box_descent,
+            metrics.em_height_ascent,
+            metrics.em_height_descent,
+            metrics.hanging_baseline,
+            metrics.alphabetic_baseline,
+            metrics.ideographic_baseline,
         )
     }
 
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-font
+    pub fn Font(&self) -> DOMString {
+        DOMString::from(self.state.borrow().font_style.to_string())
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-font
+    pub fn SetFont(&self, canvas: Option<&HTMLCanvasElement>, value: DOMString) {
+        // TODO: Resolve fonts of contexts that are not associated with a
+        // canvas element, such as offscreen canvases in workers.
+        let canvas = match canvas {
+            Some(canvas) => canvas,
+            None => return,
+        };
+        let window = window_from_node(canvas);
+        let font = match window.resolved_font_style_query(canvas.upcast(), value.into()) {
+            Some(font) => font,
+            None => return,
+        };
+        let font_style = canvas_font_style(&font);
+        self.state.borrow_mut().font_style = font_style.clone();
+        self.send_canvas_2d_msg(Canvas2dMsg::SetFont(font_style));
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-textalign
+    pub fn TextAlign(&self) -> CanvasTextAlign {
+        match self.state.borrow().text_align {
+            TextAlign::Start => CanvasTextAlign::Start,
+            TextAlign::End => CanvasTextAlign::End,
+            TextAlign::Left => CanvasTextAlign::Left,
+            TextAlign::Right => CanvasTextAlign::Right,
+            TextAlign::Center => CanvasTextAlign::Center,
+        }
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-textalign
+    pub fn SetTextAlign(&self, value: CanvasTextAlign) {
+        let text_align = match value {
+            CanvasTextAlign::Start => TextAlign::Start,
+            CanvasTextAlign::End => TextAlign::End,
+            CanvasTextAlign::Left => TextAlign::Left,
+            CanvasTextAlign::Right => TextAlign::Right,
+            CanvasTextAlign::Center => TextAlign::Center,
+        };
+        self.state.borrow_mut().text_align = text_align;
+        self.send_canvas_2d_msg(Canvas2dMsg::SetTextAlign(text_align));
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-textbaseline
+    pub fn TextBaseline(&self) -> CanvasTextBaseline {
+        match self.state.borrow().text_baseline {
+            TextBaseline::Top => CanvasTextBaseline::Top,
+            TextBaseline::Hanging => CanvasTextBaseline::Hanging,
+            TextBaseline::Middle => CanvasTextBaseline::Middle,
+            TextBaseline::Alphabetic => CanvasTextBaseline::Alphabetic,
+            TextBaseline::Ideographic => CanvasTextBaseline::Ideographic,
+            TextBaseline::Bottom => CanvasTextBaseline::Bottom,
+        }
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-textbaseline
+    pub fn SetTextBaseline(&self, value: CanvasTextBaseline) {
+        let text_baseline = match value {
+            CanvasTextBaseline::Top => TextBaseline::Top,
+            CanvasTextBaseline::Hanging => TextBaseline::Hanging,
+            CanvasTextBaseline::Middle => TextBaseline::Middle,
+            CanvasTextBaseline::Alphabetic => TextBaseline::Alphabetic,
+            CanvasTextBaseline::Ideographic => TextBaseline::Ideographic,
+            CanvasTextBaseline::Bottom => TextBaseline::Bottom,
+        };
+        self.state.borrow_mut().text_baseline = text_baseline;
+        self.send_canvas_2d_msg(Canvas2dMsg::SetTextBaseline(text_baseline));
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-direction
+    pub fn Direction(&self) -> CanvasDirection {
+        self.state.borrow().direction
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-direction
+    pub fn SetDirection(&self, value: CanvasDirection) {
+        self.state.borrow_mut().direction = value;
+    }
+
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-linewidth
     pub fn LineWidth(&self) -> f64 {
         self.state.borrow().line_width
@@ -995,6 +1334,45 @@ impl CanvasState {
         self.send_canvas_2d_msg(Canvas2dMsg::SetMiterLimit(limit as f32))
     }
 
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-setlinedash
+    pub fn SetLineDash(&self, segments: Vec<f64>) {
+        if segments
+            .iter()
+            .any(|segment| !segment.is_finite() || *segment < 0.0)
+        {
+            return;
+        }
+
+        let mut line_dash = segments.clone();
+        if segments.len() % 2 == 1 {
+            line_dash.extend(segments);
+        }
+        self.send_canvas_2d_msg(Canvas2dMsg::SetLineDash(
+            line_dash.iter().map(|segment| *segment as f32).collect(),
+        ));
+        self.state.borrow_mut().line_dash = line_dash;
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-getlinedash
+    pub fn GetLineDash(&self) -> Vec<f64> {
+        self.state.borrow().line_dash.clone()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-linedashoffset
+    pub fn LineDashOffset(&self) -> f64 {
+        self.state.borrow().line_dash_offset
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-linedashoffset
+    pub fn SetLineDashOffset(&self, offset: f64) {
+        if !offset.is_finite() {
+            return;
+        }
+
+        self.state.borrow_mut().line_dash_offset = offset;
+        self.send_canvas_2d_msg(Canvas2dMsg::SetLineDashOffset(offset as f32))
+    }
+
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-createimagedata
     pub fn CreateImageData(
         &self,
@@ -1225,9 +1603,13 @@ impl CanvasState {
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-fill
-    pub fn Fill(&self, _fill_rule: CanvasFillRule) {
-        // TODO: Process fill rule
-        self.send_canvas_2d_msg(Canvas2dMsg::Fill);
+    pub fn Fill(&self, fill_rule: CanvasFillRule) {
+        self.send_canvas_2d_msg(Canvas2dMsg::Fill(fill_rule.into()));
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-fill
+    pub fn Fill_(&self, path: &Path2D, fill_rule: CanvasFillRule) {
+        self.send_canvas_2d_msg(Canvas2dMsg::FillPath2D(path.segments(), fill_rule.into()));
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-stroke
@@ -1235,10 +1617,19 @@ impl CanvasState {
         self.send_canvas_2d_msg(Canvas2dMsg::Stroke);
     }
 
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-stroke
+    pub fn Stroke_(&self, path: &Path2D) {
+        self.send_canvas_2d_msg(Canvas2dMsg::StrokePath2D(path.segments()));
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-clip
+    pub fn Clip(&self, fill_rule: CanvasFillRule) {
+        self.send_canvas_2d_msg(Canvas2dMsg::Clip(fill_rule.into()));
+    }
+
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-clip
-    pub fn Clip(&self, _fill_rule: CanvasFillRule) {
-        // TODO: Process fill rule
-        self.send_canvas_2d_msg(Canvas2dMsg::Clip);
+    pub fn Clip_(&self, path: &Path2D, fill_rule: CanvasFillRule) {
+        self.send_canvas_2d_msg(Canvas2dMsg::ClipPath2D(path.segments(), fill_rule.into()));
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-ispointinpath
@@ -1249,13 +1640,67 @@ impl CanvasState {
         y: f64,
         fill_rule: CanvasFillRule,
     ) -> bool {
-        let fill_rule = match fill_rule {
-            CanvasFillRule::Nonzero => FillRule::Nonzero,
-            CanvasFillRule::Evenodd => FillRule::Evenodd,
-        };
+        if !(x.is_finite() && y.is_finite()) {
+            return false;
+        }
+
+        let (sender, receiver) =
+            profiled_ipc::channel::<bool>(global.time_profiler_chan().clone()).unwrap();
+        self.send_canvas_2d_msg(Canvas2dMsg::IsPointInPath(x, y, fill_rule.into(), sender));
+        receiver.recv().unwrap()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-ispointinpath
+    pub fn IsPointInPath_(
+        &self,
+        global: &GlobalScope,
+        path: &Path2D,
+        x: f64,
+        y: f64,
+        fill_rule: CanvasFillRule,
+    ) -> bool {
+        if !(x.is_finite() && y.is_finite()) {
+            return false;
+        }
+
+        let (sender, receiver) =
+            profiled_ipc::channel::<bool>(global.time_profiler_chan().clone()).unwrap();
+        self.send_canvas_2d_msg(Canvas2dMsg::IsPointInPath2D(
+            path.segments(),
+            x,
+            y,
+            fill_rule.into(),
+            sender,
+        ));
+        receiver.recv().unwrap()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-ispointinstroke
+    pub fn IsPointInStroke(&self, global: &GlobalScope, x: f64, y: f64) -> bool {
+        if !(x.is_finite() && y.is_finite()) {
+            return false;
+        }
+
         let (sender, receiver) =
             profiled_ipc::channel::<bool>(global.time_profiler_chan().clone()).unwrap();
-        self.send_canvas_2d_msg(Canvas2dMsg::IsPointInPath(x, y, fill_rule, sender));
+        self.send_canvas_2d_msg(Canvas2dMsg::IsPointInStroke(x, y, sender));
+        receiver.recv().unwrap()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-ispointinstroke
+    pub fn IsPointInStroke_(&self, global: &GlobalScope, path: &Path2D, x: f64, y: f64) -> bool {
+        if !(x.is_finite() && y.is_finite()) {
+            return false;
+        }
+
+        let (sender, receiver) =
+            profiled_ipc::channel::<bool>(global.time_profiler_chan().clone()).unwrap();
+        self.send_canvas_2d_msg(Canvas2dMsg::IsPointInStroke2D(
+            path.segments(),
+            x,
+            y,
+            sender,
+        ));
         receiver.recv().unwrap()
     }
 
@@ -1506,21 +1951,7 @@ impl CanvasRenderingContext2D {
 
     // https://html.spec.whatwg.org/multipage/#concept-canvas-set-bitmap-dimensions
     pub fn set_bitmap_dimensions(&self, size: Size2D<u32>) {
-        self.reset_to_initial_state();
-        self.canvas_state
-            .borrow()
-            .ipc_renderer
-            .send(CanvasMsg::Recreate(
-                size,
-                self.canvas_state.borrow().get_canvas_id(),
-            ))
-            .unwrap();
-    }
-
-    // https://html.spec.whatwg.org/multipage/#reset-the-rendering-context-to-its-default-state
-    fn reset_to_initial_state(&self) {
-        self.canvas_state.borrow().saved_states.borrow_mut().clear();
-        *self.canvas_state.borrow().state.borrow_mut() = CanvasContextState::new();
+        self.canvas_state.borrow().set_bitmap_dimensions(size);
     }
 
     fn mark_as_dirty(&self) {
@@ -1699,17 +2130,34 @@ impl CanvasRenderingContext2DMethods for CanvasRenderingContext2D {
         self.mark_as_dirty();
     }
 
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-fill
+    fn Fill_(&self, path: &Path2D, fill_rule: CanvasFillRule) {
+        self.canvas_state.borrow().Fill_(path, fill_rule);
+        self.mark_as_dirty();
+    }
+
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-stroke
     fn Stroke(&self) {
         self.canvas_state.borrow().Stroke();
         self.mark_as_dirty();
     }
 
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-stroke
+    fn Stroke_(&self, path: &Path2D) {
+        self.canvas_state.borrow().Stroke_(path);
+        self.mark_as_dirty();
+    }
+
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-clip
     fn Clip(&self, fill_rule: CanvasFillRule) {
         self.canvas_state.borrow().Clip(fill_rule)
     }
 
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-clip
+    fn Clip_(&self, path: &Path2D, fill_rule: CanvasFillRule) {
+        self.canvas_state.borrow().Clip_(path, fill_rule)
+    }
+
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-ispointinpath
     fn IsPointInPath(&self, x: f64, y: f64, fill_rule: CanvasFillRule) -> bool {
         self.canvas_state
@@ -1717,15 +2165,100 @@ impl CanvasRenderingContext2DMethods for CanvasRenderingContext2D {
             .IsPointInPath(&self.global(), x, y, fill_rule)
     }
 
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-ispointinpath
+    fn IsPointInPath_(&self, path: &Path2D, x: f64, y: f64, fill_rule: CanvasFillRule) -> bool {
+        self.canvas_state
+            .borrow()
+            .IsPointInPath_(&self.global(), path, x, y, fill_rule)
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-ispointinstroke
+    fn IsPointInStroke(&self, x: f64, y: f64) -> bool {
+        self.canvas_state
+            .borrow()
+            .IsPointInStroke(&self.global(), x, y)
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-ispointinstroke
+    fn IsPointInStroke_(&self, path: &Path2D, x: f64, y: f64) -> bool {
+        self.canvas_state
+            .borrow()
+            .IsPointInStroke_(&self.global(), path, x, y)
+    }
+
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-filltext
     fn FillText(&self, text: DOMString, x: f64, y: f64, max_width: Option<f64>) {
-        self.canvas_state.borrow().FillText(text, x, y, max_width);
+        self.canvas_state.borrow().FillText(
+            self.canvas.as_ref().map(|c| &**c),
+            text,
+            x,
+            y,
+            max_width,
+        );
+        self.mark_as_dirty();
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-stroketext
+    fn StrokeText(&self, text: DOMString, x: f64, y: f64, max_width: Option<f64>) {
+        self.canvas_state.borrow().StrokeText(
+            self.canvas.as_ref().map(|c| &**c),
+            text,
+            x,
+            y,
+            max_width,
+        );
         self.mark_as_dirty();
     }
 
     // https://html.spec.whatwg.org/multipage/#textmetrics
     fn MeasureText(&self, text: DOMString) -> DomRoot<TextMetrics> {
-        self.canvas_state.borrow().MeasureText(&self.global(), text)
+        self.canvas_state.borrow().MeasureText(
+            &self.global(),
+            self.canvas.as_ref().map(|c| &**c),
+            text,
+        )
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-font
+    fn Font(&self) -> DOMString {
+        self.canvas_state.borrow().Font()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-font
+    fn SetFont(&self, value: DOMString) {
+        self.canvas_state
+            .borrow()
+            .SetFont(self.canvas.as_ref().map(|c| &**c), value)
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-textalign
+    fn TextAlign(&self) -> CanvasTextAlign {
+        self.canvas_state.borrow().TextAlign()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-textalign
+    fn SetTextAlign(&self, value: CanvasTextAlign) {
+        self.canvas_state.borrow().SetTextAlign(value)
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-textbaseline
+    fn TextBaseline(&self) -> CanvasTextBaseline {
+        self.canvas_state.borrow().TextBaseline()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-textbaseline
+    fn SetTextBaseline(&self, value: CanvasTextBaseline) {
+        self.canvas_state.borrow().SetTextBaseline(value)
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-direction
+    fn Direction(&self) -> CanvasDirection {
+        self.canvas_state.borrow().Direction()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-direction
+    fn SetDirection(&self, value: CanvasDirection) {
+        self.canvas_state.borrow().SetDirection(value)
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-drawimage
@@ -1845,6 +2378,26 @@ impl CanvasRenderingContext2DMethods for CanvasRenderingContext2D {
         self.canvas_state.borrow().SetImageSmoothingEnabled(value)
     }
 
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-imagesmoothingquality
+    fn ImageSmoothingQuality(&self) -> ImageSmoothingQuality {
+        self.canvas_state.borrow().ImageSmoothingQuality()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-imagesmoothingquality
+    fn SetImageSmoothingQuality(&self, value: ImageSmoothingQuality) {
+        self.canvas_state.borrow().SetImageSmoothingQuality(value)
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-filter
+    fn Filter(&self) -> DOMString {
+        self.canvas_state.borrow().Filter()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-filter
+    fn SetFilter(&self, value: DOMString) {
+        self.canvas_state.borrow().SetFilter(value)
+    }
+
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-strokestyle
     fn StrokeStyle(&self) -> StringOrCanvasGradientOrCanvasPattern {
         self.canvas_state.borrow().StrokeStyle()
@@ -2009,6 +2562,26 @@ impl CanvasRenderingContext2DMethods for CanvasRenderingContext2D {
         self.canvas_state.borrow().SetMiterLimit(limit)
     }
 
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-setlinedash
+    fn SetLineDash(&self, segments: Vec<f64>) {
+        self.canvas_state.borrow().SetLineDash(segments)
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-getlinedash
+    fn GetLineDash(&self) -> Vec<f64> {
+        self.canvas_state.borrow().GetLineDash()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-linedashoffset
+    fn LineDashOffset(&self) -> f64 {
+        self.canvas_state.borrow().LineDashOffset()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-linedashoffset
+    fn SetLineDashOffset(&self, offset: f64) {
+        self.canvas_state.borrow().SetLineDashOffset(offset)
+    }
+
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-shadowoffsetx
     fn ShadowOffsetX(&self) -> f64 {
         self.canvas_state.borrow().ShadowOffsetX()
@@ -2078,6 +2651,129 @@ pub fn parse_color(string: &str) -> Result<RGBA, ()> {
     }
 }
 
+// https://html.spec.whatwg.org/multipage/#dom-context-2d-filter
+// Parses a `<filter-value-list>`, or "none", into the filter functions used by
+// the canvas thread. Lengths that can't be resolved without a computed style,
+// like font-relative ones, make the whole value invalid.
+fn parse_filter(value: &str) -> Option<Vec<FilterFunction>> {
+    if value == "none" {
+        return Some(vec![]);
+    }
+
+    let mut input = ParserInput::new(value);
+    let mut parser = Parser::new(&mut input);
+    let url = ServoUrl::parse("about:blank").unwrap();
+    let context = ParserContext::new(
+        Origin::Author,
+        &url,
+        Some(CssRuleType::Style),
+        ParsingMode::DEFAULT,
+        QuirksMode::NoQuirks,
+        None,
+        None,
+    );
+    let functions = parser
+        .parse_entirely(|input| {
+            let mut functions = vec![];
+            loop {
+                // The servo build of style doesn't support drop-shadow() in
+                // the filter property, so it is parsed separately.
+                let drop_shadow = input.r#try(|input| {
+                    input.expect_function_matching("drop-shadow")?;
+                    input.parse_nested_block(|input| SimpleShadow::parse(&context, input))
+                });
+                functions.push(match drop_shadow {
+                    Ok(shadow) => drop_shadow_filter_function(&shadow),
+                    Err(_) => filter_function(&Filter::parse(&context, input)?),
+                });
+                if input.is_exhausted() {
+                    return Ok(functions);
+                }
+            }
+        })
+        .ok()?;
+    functions.into_iter().collect()
+}
+
+fn filter_function(filter: &Filter) -> Option<FilterFunction> {
+    Some(match *filter {
+        GenericFilter::Blur(ref length) => FilterFunction::Blur(pixel_length(&length.0)?),
+        GenericFilter::Brightness(ref factor) => FilterFunction::Brightness(factor.to_number()),
+        GenericFilter::Contrast(ref factor) => FilterFunction::Contrast(factor.to_number()),
+        GenericFilter::Grayscale(ref factor) => FilterFunction::Grayscale(factor.to_number()),
+        GenericFilter::HueRotate(ref angle) => FilterFunction::HueRotate(angle.degrees()),
+        GenericFilter::Invert(ref factor) => FilterFunction::Invert(factor.to_number()),
+        GenericFilter::Opacity(ref factor) => FilterFunction::Opacity(factor.to_number()),
+        GenericFilter::Saturate(ref factor) => FilterFunction::Saturate(factor.to_number()),
+        GenericFilter::Sepia(ref factor) => FilterFunction::Sepia(factor.to_number()),
+        GenericFilter::DropShadow(..) | GenericFilter::Url(..) => return None,
+    })
+}
+
+fn drop_shadow_filter_function(shadow: &SimpleShadow) -> Option<FilterFunction> {
+    let offset = vec2(
+        pixel_length(&shadow.horizontal)?,
+        pixel_length(&shadow.vertical)?,
+    );
+    let std_deviation = match shadow.blur {
+        Some(ref blur) => pixel_length(&blur.0)?,
+        None => 0.,
+    };
+    // There is no element to resolve currentColor against, so it is black.
+    let black = RGBA::new(0, 0, 0, 255);
+    let color = match shadow.color {
+        Some(ref color) => color.to_computed_color(None)?.to_rgba(black),
+        None => black,
+    };
+    Some(FilterFunction::DropShadow(offset, std_deviation, color))
+}
+
+fn pixel_length(length: &Length) -> Option<f32> {
+    match *length {
+        Length::NoCalc(ref length) => length.to_computed_pixel_length_without_context().ok(),
+        Length::Calc(..) => None,
+    }
+}
+
+impl From<CanvasFillRule> for FillRule {
+    fn from(fill_rule: CanvasFillRule) -> FillRule {
+        match fill_rule {
+            CanvasFillRule::Nonzero => FillRule::Nonzero,
+            CanvasFillRule::Evenodd => FillRule::Evenodd,
+        }
+    }
+}
+
+// Converts a computed font style to the representation used by the canvas thread.
+fn canvas_font_style(font: &Font) -> CanvasFontStyle {
+    let families = font
+        .font_family
+        .families
+        .iter()
+        .map(|family| match *family {
+            SingleFontFamily::FamilyName(ref name) => {
+                CanvasFontFamily::Named(name.name.to_string())
+            },
+            SingleFontFamily::Generic(generic) => {
+                CanvasFontFamily::Generic(generic.to_css_string())
+            },
+        })
+        .collect();
+    let slant = match font.font_style {
+        FontStyle::Normal => CanvasFontSlant::Normal,
+        FontStyle::Italic => CanvasFontSlant::Italic,
+        FontStyle::Oblique(ref angle) => CanvasFontSlant::Oblique(angle.0.degrees()),
+    };
+    CanvasFontStyle {
+        families,
+        size: font.font_size.size().to_f64_px(),
+        weight: font.font_weight.0,
+        slant,
+        stretch: ((font.font_stretch.0).0).0 * 100.,
+        small_caps: font.font_variant_caps == FontVariantCaps::SmallCaps,
+    }
+}
+
 // Used by drawImage to determine if a source or destination rectangle is valid
 // Origin coordinates and size cannot be negative. Size has to be greater than zero
 fn is_rect_valid(rect: Rect<f64>) -> bool {
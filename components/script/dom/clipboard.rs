@@ -0,0 +1,209 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::compartments::InCompartment;
+use crate::dom::bindings::codegen::Bindings::ClipboardBinding;
+use crate::dom::bindings::codegen::Bindings::ClipboardBinding::ClipboardMethods;
+use crate::dom::bindings::codegen::Bindings::PermissionStatusBinding::{
+    PermissionName, PermissionState,
+};
+use crate::dom::bindings::error::Error;
+use crate::dom::bindings::refcounted::TrustedPromise;
+use crate::dom::bindings::reflector::{reflect_dom_object, DomObject};
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::bindings::str::DOMString;
+use crate::dom::clipboarditem::ClipboardItem;
+use crate::dom::eventtarget::EventTarget;
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::permissions::request_permission_to_use;
+use crate::dom::promise::Promise;
+use crate::task_source::TaskSource;
+use dom_struct::dom_struct;
+use embedder_traits::{ClipboardData, EmbedderMsg};
+use ipc_channel::router::ROUTER;
+use profile_traits::ipc;
+use script_traits::ScriptMsg;
+use std::rc::Rc;
+
+/// What a read of the system clipboard resolves its promise with.
+#[derive(Clone, Copy)]
+enum ReadKind {
+    /// A `ClipboardItem` holding every representation of the data.
+    Items,
+    /// The plain text representation of the data.
+    Text,
+}
+
+#[dom_struct]
+pub struct Clipboard {
+    eventtarget: EventTarget,
+}
+
+impl Clipboard {
+    fn new_inherited() -> Clipboard {
+        Clipboard {
+            eventtarget: EventTarget::new_inherited(),
+        }
+    }
+
+    pub fn new(global: &GlobalScope) -> DomRoot<Clipboard> {
+        reflect_dom_object(
+            Box::new(Clipboard::new_inherited()),
+            global,
+            ClipboardBinding::Wrap,
+        )
+    }
+
+    /// <https://w3c.github.io/clipboard-apis/#dom-clipboard-read>
+    /// <https://w3c.github.io/clipboard-apis/#dom-clipboard-readtext>
+    fn read(&self, kind: ReadKind, comp: InCompartment) -> Rc<Promise> {
+        let global = self.global();
+
+        // Step 1.
+        let promise = Promise::new_in_current_compartment(&global, comp);
+
+        // Steps 2.1-2.2.
+        if request_permission_to_use(PermissionName::Clipboard_read, &global) !=
+            PermissionState::Granted
+        {
+            promise.reject_error(Error::NotAllowed);
+            return promise;
+        }
+
+        // Steps 2.3-2.5.
+        let mut trusted_promise = Some(TrustedPromise::new(promise.clone()));
+        let (task_source, canceller) = global
+            .as_window()
+            .task_manager()
+            .dom_manipulation_task_source_with_canceller();
+        let (sender, receiver) = ipc::channel(global.time_profiler_chan().clone()).unwrap();
+        ROUTER.add_route(
+            receiver.to_opaque(),
+            Box::new(move |message| {
+                // router doesn't know this is only called once
+                let trusted_promise = match trusted_promise.take() {
+                    Some(trusted_promise) => trusted_promise,
+                    None => {
+                        error!("clipboard read callback called twice!");
+                        return;
+                    },
+                };
+                let data: ClipboardData = match message.to() {
+                    Ok(data) => data,
+                    Err(_) => {
+                        error!("clipboard read callback given incorrect payload");
+                        return;
+                    },
+                };
+                let _ = task_source.queue_with_canceller(
+                    task!(resolve_clipboard_read: move || {
+                        let promise = trusted_promise.root();
+                        match kind {
+                            ReadKind::Items => {
+                                let item = ClipboardItem::new_from_clipboard_data(
+                                    &promise.global(),
+                                    data,
+                                );
+                                promise.resolve_native(&vec![item]);
+                            },
+                            ReadKind::Text => {
+                                let text = data.text().unwrap_or_default();
+                                promise.resolve_native(&DOMString::from(text));
+                            },
+                        }
+                    }),
+                    &canceller,
+                );
+            }),
+        );
+        let _ = global
+            .script_to_constellation_chan()
+            .send(ScriptMsg::ForwardToEmbedder(EmbedderMsg::GetClipboardData(
+                sender,
+            )));
+
+        // Step 3.
+        promise
+    }
+
+    /// <https://w3c.github.io/clipboard-apis/#dom-clipboard-write>
+    /// <https://w3c.github.io/clipboard-apis/#dom-clipboard-writetext>
+    fn write(&self, data: Result<ClipboardData, Error>, comp: InCompartment) -> Rc<Promise> {
+        let global = self.global();
+
+        // Step 1.
+        let promise = Promise::new_in_current_compartment(&global, comp);
+
+        // Steps 2.1-2.2.
+        if request_permission_to_use(PermissionName::Clipboard_write, &global) !=
+            PermissionState::Granted
+        {
+            promise.reject_error(Error::NotAllowed);
+            return promise;
+        }
+
+        // Steps 2.3-2.4.
+        let data = match data {
+            Ok(data) => data,
+            Err(error) => {
+                promise.reject_error(error);
+                return promise;
+            },
+        };
+
+        // Step 2.5.
+        let _ = global
+            .script_to_constellation_chan()
+            .send(ScriptMsg::ForwardToEmbedder(EmbedderMsg::SetClipboardData(
+                data,
+            )));
+        let (task_source, canceller) = global
+            .as_window()
+            .task_manager()
+            .dom_manipulation_task_source_with_canceller();
+        let _ = task_source.queue_with_canceller(
+            TrustedPromise::new(promise.clone()).resolve_task(()),
+            &canceller,
+        );
+
+        // Step 3.
+        promise
+    }
+}
+
+impl ClipboardMethods for Clipboard {
+    // https://w3c.github.io/clipboard-apis/#dom-clipboard-read
+    fn Read(&self, comp: InCompartment) -> Rc<Promise> {
+        self.read(ReadKind::Items, comp)
+    }
+
+    // https://w3c.github.io/clipboard-apis/#dom-clipboard-readtext
+    fn ReadText(&self, comp: InCompartment) -> Rc<Promise> {
+        self.read(ReadKind::Text, comp)
+    }
+
+    // https://w3c.github.io/clipboard-apis/#dom-clipboard-write
+    fn Write(&self, data: Vec<DomRoot<ClipboardItem>>, comp: InCompartment) -> Rc<Promise> {
+        // The system clipboard holds a single item, so the representations of
+        // all the items are merged, earlier items taking precedence.
+        let mut merged = ClipboardData::default();
+        for item in &data {
+            let item_data = match item.clipboard_data() {
+                Ok(item_data) => item_data,
+                Err(error) => return self.write(Err(error), comp),
+            };
+            for (type_, bytes) in item_data.representations {
+                if !merged.representations.iter().any(|&(ref t, _)| *t == type_) {
+                    merged.representations.push((type_, bytes));
+                }
+            }
+        }
+        self.write(Ok(merged), comp)
+    }
+
+    // https://w3c.github.io/clipboard-apis/#dom-clipboard-writetext
+    fn WriteText(&self, data: DOMString, comp: InCompartment) -> Rc<Promise> {
+        self.write(Ok(ClipboardData::from_text(data.into())), comp)
+    }
+}
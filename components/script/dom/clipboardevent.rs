@@ -0,0 +1,113 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::bindings::codegen::Bindings::ClipboardEventBinding;
+use crate::dom::bindings::codegen::Bindings::ClipboardEventBinding::ClipboardEventMethods;
+use crate::dom::bindings::codegen::Bindings::EventBinding::EventMethods;
+use crate::dom::bindings::error::Fallible;
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::reflector::reflect_dom_object;
+use crate::dom::bindings::root::{DomRoot, MutNullableDom};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::datatransfer::DataTransfer;
+use crate::dom::event::{Event, EventBubbles, EventCancelable};
+use crate::dom::window::Window;
+use crate::textinput::CMD_OR_CONTROL;
+use dom_struct::dom_struct;
+use keyboard_types::{Key, KeyState, Modifiers, ShortcutMatcher};
+use servo_atoms::Atom;
+
+/// The clipboard actions the user can trigger, which fire the clipboard
+/// event of the same name.
+///
+/// <https://w3c.github.io/clipboard-apis/#clipboard-actions>
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClipboardEventType {
+    Copy,
+    Cut,
+    Paste,
+}
+
+impl ClipboardEventType {
+    /// The clipboard action the given key press triggers, if any.
+    pub fn from_shortcut(
+        state: KeyState,
+        key: Key,
+        modifiers: Modifiers,
+    ) -> Option<ClipboardEventType> {
+        ShortcutMatcher::new(state, key, modifiers)
+            .shortcut(CMD_OR_CONTROL, 'C', || Some(ClipboardEventType::Copy))
+            .shortcut(CMD_OR_CONTROL, 'X', || Some(ClipboardEventType::Cut))
+            .shortcut(CMD_OR_CONTROL, 'V', || Some(ClipboardEventType::Paste))
+            .otherwise(|| None)
+            .unwrap()
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            ClipboardEventType::Copy => "copy",
+            ClipboardEventType::Cut => "cut",
+            ClipboardEventType::Paste => "paste",
+        }
+    }
+}
+
+#[dom_struct]
+pub struct ClipboardEvent {
+    event: Event,
+    clipboard_data: MutNullableDom<DataTransfer>,
+}
+
+impl ClipboardEvent {
+    fn new_inherited(clipboard_data: Option<&DataTransfer>) -> ClipboardEvent {
+        ClipboardEvent {
+            event: Event::new_inherited(),
+            clipboard_data: MutNullableDom::new(clipboard_data),
+        }
+    }
+
+    pub fn new(
+        window: &Window,
+        type_: Atom,
+        bubbles: EventBubbles,
+        cancelable: EventCancelable,
+        clipboard_data: Option<&DataTransfer>,
+    ) -> DomRoot<ClipboardEvent> {
+        let ev = reflect_dom_object(
+            Box::new(ClipboardEvent::new_inherited(clipboard_data)),
+            window,
+            ClipboardEventBinding::Wrap,
+        );
+        ev.upcast::<Event>()
+            .init_event(type_, bool::from(bubbles), bool::from(cancelable));
+        ev
+    }
+
+    // https://w3c.github.io/clipboard-apis/#dom-clipboardevent-clipboardevent
+    pub fn Constructor(
+        window: &Window,
+        type_: DOMString,
+        init: &ClipboardEventBinding::ClipboardEventInit,
+    ) -> Fallible<DomRoot<ClipboardEvent>> {
+        Ok(ClipboardEvent::new(
+            window,
+            Atom::from(type_),
+            EventBubbles::from(init.parent.bubbles),
+            EventCancelable::from(init.parent.cancelable),
+            init.clipboardData.as_deref(),
+        ))
+    }
+}
+
+impl ClipboardEventMethods for ClipboardEvent {
+    // https://w3c.github.io/clipboard-apis/#dom-clipboardevent-clipboarddata
+    fn GetClipboardData(&self) -> Option<DomRoot<DataTransfer>> {
+        self.clipboard_data.get()
+    }
+
+    // https://dom.spec.whatwg.org/#dom-event-istrusted
+    fn IsTrusted(&self) -> bool {
+        self.event.IsTrusted()
+    }
+}
@@ -0,0 +1,179 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::compartments::InCompartment;
+use crate::dom::bindings::codegen::Bindings::ClipboardItemBinding;
+use crate::dom::bindings::codegen::Bindings::ClipboardItemBinding::{
+    ClipboardItemMethods, ClipboardItemOptions, PresentationStyle,
+};
+use crate::dom::bindings::codegen::UnionTypes::BlobOrString;
+use crate::dom::bindings::error::{Error, Fallible};
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::record::Record;
+use crate::dom::bindings::reflector::{reflect_dom_object, DomObject, Reflector};
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::blob::{Blob, BlobImpl};
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::promise::Promise;
+use crate::dom::window::Window;
+use crate::script_runtime::JSContext;
+use dom_struct::dom_struct;
+use embedder_traits::ClipboardData;
+use js::conversions::ToJSValConvertible;
+use js::jsval::{JSVal, UndefinedValue};
+use std::rc::Rc;
+
+/// The types the system clipboard can hold.
+///
+/// <https://w3c.github.io/clipboard-apis/#mandatory-data-types-x>
+pub const SUPPORTED_TYPES: &[&str] = &["text/plain", "text/html", "image/png"];
+
+#[dom_struct]
+pub struct ClipboardItem {
+    reflector_: Reflector,
+    presentation_style: PresentationStyle,
+    /// The representations of the item, as pairs of MIME type and data.
+    representations: Vec<(DOMString, Dom<Blob>)>,
+}
+
+impl ClipboardItem {
+    fn new_inherited(
+        presentation_style: PresentationStyle,
+        representations: &[(DOMString, DomRoot<Blob>)],
+    ) -> ClipboardItem {
+        ClipboardItem {
+            reflector_: Reflector::new(),
+            presentation_style,
+            representations: representations
+                .iter()
+                .map(|&(ref type_, ref blob)| (type_.clone(), Dom::from_ref(&**blob)))
+                .collect(),
+        }
+    }
+
+    fn new(
+        global: &GlobalScope,
+        presentation_style: PresentationStyle,
+        representations: &[(DOMString, DomRoot<Blob>)],
+    ) -> DomRoot<ClipboardItem> {
+        reflect_dom_object(
+            Box::new(ClipboardItem::new_inherited(
+                presentation_style,
+                representations,
+            )),
+            global,
+            ClipboardItemBinding::Wrap,
+        )
+    }
+
+    /// Creates an item holding the data read from the system clipboard.
+    pub fn new_from_clipboard_data(
+        global: &GlobalScope,
+        data: ClipboardData,
+    ) -> DomRoot<ClipboardItem> {
+        let representations: Vec<_> = data
+            .representations
+            .into_iter()
+            .map(|(type_, bytes)| {
+                let blob = Blob::new(global, BlobImpl::new_from_bytes(bytes), type_.clone());
+                (DOMString::from(type_), blob)
+            })
+            .collect();
+        ClipboardItem::new(global, PresentationStyle::Unspecified, &representations)
+    }
+
+    // https://w3c.github.io/clipboard-apis/#dom-clipboarditem-clipboarditem
+    pub fn Constructor(
+        window: &Window,
+        items: Record<DOMString, BlobOrString>,
+        options: &ClipboardItemOptions,
+    ) -> Fallible<DomRoot<ClipboardItem>> {
+        // Step 1.
+        if items.is_empty() {
+            return Err(Error::Type(
+                "ClipboardItem needs at least one item".to_owned(),
+            ));
+        }
+
+        // Steps 2-4.
+        let global = window.upcast::<GlobalScope>();
+        let representations: Vec<_> = items
+            .iter()
+            .map(|(type_, data)| {
+                let blob = match *data {
+                    BlobOrString::Blob(ref blob) => blob.clone(),
+                    BlobOrString::String(ref string) => Blob::new(
+                        global,
+                        BlobImpl::new_from_bytes(string.as_bytes().to_vec()),
+                        type_.to_string(),
+                    ),
+                };
+                (type_.clone(), blob)
+            })
+            .collect();
+        Ok(ClipboardItem::new(
+            global,
+            options.presentationStyle,
+            &representations,
+        ))
+    }
+
+    /// The data of the item to write to the system clipboard, which fails if
+    /// it has a type the clipboard can't hold.
+    pub fn clipboard_data(&self) -> Result<ClipboardData, Error> {
+        let mut representations = vec![];
+        for &(ref type_, ref blob) in &self.representations {
+            if !SUPPORTED_TYPES.contains(&&**type_) {
+                return Err(Error::NotAllowed);
+            }
+            let bytes = blob.get_bytes().map_err(|_| Error::NotReadable)?;
+            representations.push((type_.to_string(), bytes));
+        }
+        Ok(ClipboardData { representations })
+    }
+}
+
+impl ClipboardItemMethods for ClipboardItem {
+    // https://w3c.github.io/clipboard-apis/#dom-clipboarditem-presentationstyle
+    fn PresentationStyle(&self) -> PresentationStyle {
+        self.presentation_style
+    }
+
+    // https://w3c.github.io/clipboard-apis/#dom-clipboarditem-types
+    #[allow(unsafe_code)]
+    fn Types(&self, cx: JSContext) -> JSVal {
+        let types: Vec<DOMString> = self
+            .representations
+            .iter()
+            .map(|&(ref type_, _)| type_.clone())
+            .collect();
+
+        // TODO: This should be a frozen array that stays the same object.
+        unsafe {
+            rooted!(in(*cx) let mut types_value = UndefinedValue());
+            types.to_jsval(*cx, types_value.handle_mut());
+            types_value.get()
+        }
+    }
+
+    // https://w3c.github.io/clipboard-apis/#dom-clipboarditem-gettype
+    fn GetType(&self, type_: DOMString, comp: InCompartment) -> Rc<Promise> {
+        // Step 1.
+        let promise = Promise::new_in_current_compartment(&self.global(), comp);
+
+        // Steps 2-4.
+        match self
+            .representations
+            .iter()
+            .find(|&&(ref representation_type, _)| *representation_type == type_)
+        {
+            Some(&(_, ref blob)) => promise.resolve_native(&**blob),
+            None => promise.reject_error(Error::NotFound),
+        }
+
+        // Step 5.
+        promise
+    }
+}
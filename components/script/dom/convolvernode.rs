@@ -0,0 +1,136 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::audiobuffer::AudioBuffer;
+use crate::dom::audionode::AudioNode;
+use crate::dom::baseaudiocontext::BaseAudioContext;
+use crate::dom::bindings::codegen::Bindings::AudioBufferBinding::AudioBufferMethods;
+use crate::dom::bindings::codegen::Bindings::AudioNodeBinding::AudioNodeMethods;
+use crate::dom::bindings::codegen::Bindings::AudioNodeBinding::{
+    ChannelCountMode, ChannelInterpretation,
+};
+use crate::dom::bindings::codegen::Bindings::BaseAudioContextBinding::BaseAudioContextMethods;
+use crate::dom::bindings::codegen::Bindings::ConvolverNodeBinding::{
+    self, ConvolverNodeMethods, ConvolverOptions,
+};
+use crate::dom::bindings::error::{Error, Fallible};
+use crate::dom::bindings::reflector::reflect_dom_object;
+use crate::dom::bindings::root::{DomRoot, MutNullableDom};
+use crate::dom::window::Window;
+use dom_struct::dom_struct;
+use servo_media::audio::convolver_node::{ConvolverNodeMessage, ConvolverNodeOptions};
+use servo_media::audio::node::{AudioNodeInit, AudioNodeMessage};
+use std::cell::Cell;
+
+#[dom_struct]
+pub struct ConvolverNode {
+    node: AudioNode,
+    buffer: MutNullableDom<AudioBuffer>,
+    normalize: Cell<bool>,
+}
+
+impl ConvolverNode {
+    #[allow(unrooted_must_root)]
+    pub fn new_inherited(
+        _: &Window,
+        context: &BaseAudioContext,
+        options: &ConvolverOptions,
+    ) -> Fallible<ConvolverNode> {
+        let node_options = options.parent.unwrap_or(
+            2,
+            ChannelCountMode::Clamped_max,
+            ChannelInterpretation::Speakers,
+        );
+        if node_options.mode == ChannelCountMode::Max {
+            return Err(Error::NotSupported);
+        }
+        if node_options.count > 2 || node_options.count == 0 {
+            return Err(Error::NotSupported);
+        }
+        let normalize = !options.disableNormalization;
+        let node = AudioNode::new_inherited(
+            AudioNodeInit::ConvolverNode(ConvolverNodeOptions {
+                buffer: None,
+                normalize,
+            }),
+            context,
+            node_options,
+            1, // inputs
+            1, // outputs
+        )?;
+        let node = ConvolverNode {
+            node,
+            buffer: Default::default(),
+            normalize: Cell::new(normalize),
+        };
+        if let Some(Some(ref buffer)) = options.buffer {
+            node.SetBuffer(Some(&**buffer))?;
+        }
+        Ok(node)
+    }
+
+    #[allow(unrooted_must_root)]
+    pub fn new(
+        window: &Window,
+        context: &BaseAudioContext,
+        options: &ConvolverOptions,
+    ) -> Fallible<DomRoot<ConvolverNode>> {
+        let node = ConvolverNode::new_inherited(window, context, options)?;
+        Ok(reflect_dom_object(
+            Box::new(node),
+            window,
+            ConvolverNodeBinding::Wrap,
+        ))
+    }
+
+    pub fn Constructor(
+        window: &Window,
+        context: &BaseAudioContext,
+        options: &ConvolverOptions,
+    ) -> Fallible<DomRoot<ConvolverNode>> {
+        ConvolverNode::new(window, context, options)
+    }
+}
+
+impl ConvolverNodeMethods for ConvolverNode {
+    // https://webaudio.github.io/web-audio-api/#dom-convolvernode-buffer
+    fn GetBuffer(&self) -> Fallible<Option<DomRoot<AudioBuffer>>> {
+        Ok(self.buffer.get())
+    }
+
+    // https://webaudio.github.io/web-audio-api/#dom-convolvernode-buffer
+    fn SetBuffer(&self, new_buffer: Option<&AudioBuffer>) -> Fallible<()> {
+        let channels = match new_buffer {
+            Some(buffer) => {
+                match buffer.NumberOfChannels() {
+                    1 | 2 | 4 => {},
+                    _ => return Err(Error::NotSupported),
+                }
+                if *buffer.SampleRate() != *self.node.Context().SampleRate() {
+                    return Err(Error::NotSupported);
+                }
+                // Acquire the content of the buffer.
+                buffer.get_channels().clone()
+            },
+            None => None,
+        };
+
+        self.buffer.set(new_buffer);
+        // The normalization is only computed when the buffer is set.
+        self.node.message(AudioNodeMessage::ConvolverNode(
+            ConvolverNodeMessage::SetBuffer(channels, self.normalize.get()),
+        ));
+        Ok(())
+    }
+
+    // https://webaudio.github.io/web-audio-api/#dom-convolvernode-normalize
+    fn Normalize(&self) -> bool {
+        self.normalize.get()
+    }
+
+    // https://webaudio.github.io/web-audio-api/#dom-convolvernode-normalize
+    fn SetNormalize(&self, normalize: bool) {
+        self.normalize.set(normalize);
+    }
+}
@@ -79,7 +79,23 @@ use crate::dom::htmltrackelement::HTMLTrackElement;
 use crate::dom::htmlulistelement::HTMLUListElement;
 use crate::dom::htmlunknownelement::HTMLUnknownElement;
 use crate::dom::htmlvideoelement::HTMLVideoElement;
+use crate::dom::svgcircleelement::SVGCircleElement;
+use crate::dom::svgclippathelement::SVGClipPathElement;
+use crate::dom::svgdefselement::SVGDefsElement;
+use crate::dom::svgellipseelement::SVGEllipseElement;
+use crate::dom::svggelement::SVGGElement;
+use crate::dom::svglineargradientelement::SVGLinearGradientElement;
+use crate::dom::svglineelement::SVGLineElement;
+use crate::dom::svgpathelement::SVGPathElement;
+use crate::dom::svgpolygonelement::SVGPolygonElement;
+use crate::dom::svgpolylineelement::SVGPolylineElement;
+use crate::dom::svgradialgradientelement::SVGRadialGradientElement;
+use crate::dom::svgrectelement::SVGRectElement;
+use crate::dom::svgstopelement::SVGStopElement;
 use crate::dom::svgsvgelement::SVGSVGElement;
+use crate::dom::svgtextelement::SVGTextElement;
+use crate::dom::svgtspanelement::SVGTSpanElement;
+use crate::dom::svguseelement::SVGUseElement;
 use crate::script_thread::ScriptThread;
 use html5ever::{LocalName, Prefix, QualName};
 use js::jsapi::JSAutoRealm;
@@ -108,7 +124,23 @@ fn create_svg_element(
     }
 
     match name.local {
+        local_name!("circle") => make!(SVGCircleElement),
+        local_name!("clipPath") => make!(SVGClipPathElement),
+        local_name!("defs") => make!(SVGDefsElement),
+        local_name!("ellipse") => make!(SVGEllipseElement),
+        local_name!("g") => make!(SVGGElement),
+        local_name!("line") => make!(SVGLineElement),
+        local_name!("linearGradient") => make!(SVGLinearGradientElement),
+        local_name!("path") => make!(SVGPathElement),
+        local_name!("polygon") => make!(SVGPolygonElement),
+        local_name!("polyline") => make!(SVGPolylineElement),
+        local_name!("radialGradient") => make!(SVGRadialGradientElement),
+        local_name!("rect") => make!(SVGRectElement),
+        local_name!("stop") => make!(SVGStopElement),
         local_name!("svg") => make!(SVGSVGElement),
+        local_name!("text") => make!(SVGTextElement),
+        local_name!("tspan") => make!(SVGTSpanElement),
+        local_name!("use") => make!(SVGUseElement),
         _ => Element::new(name.local, name.ns, prefix, document),
     }
 }
@@ -2,8 +2,9 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
+use crate::dom::bindings::codegen::Bindings::CSSBinding::PropertyDefinition;
 use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowBinding::WindowMethods;
-use crate::dom::bindings::error::Fallible;
+use crate::dom::bindings::error::{Error, ErrorResult, Fallible};
 use crate::dom::bindings::reflector::Reflector;
 use crate::dom::bindings::root::DomRoot;
 use crate::dom::bindings::str::DOMString;
@@ -11,8 +12,11 @@ use crate::dom::window::Window;
 use crate::dom::worklet::Worklet;
 use cssparser::{serialize_identifier, Parser, ParserInput};
 use dom_struct::dom_struct;
+use script_layout_interface::message::Msg;
 use style::context::QuirksMode;
+use style::custom_properties::{self, VariableValue};
 use style::parser::ParserContext;
+use style::properties_and_values::{PropertyRegistration, Syntax};
 use style::stylesheets::supports_rule::{parse_condition_or_declaration, Declaration};
 use style::stylesheets::CssRuleType;
 use style_traits::ParsingMode;
@@ -70,6 +74,43 @@ impl CSS {
         cond.eval(&context, &Default::default())
     }
 
+    /// <https://drafts.css-houdini.org/css-properties-values-api/#the-registerproperty-function>
+    pub fn RegisterProperty(win: &Window, definition: &PropertyDefinition) -> ErrorResult {
+        let syntax = Syntax::parse(&definition.syntax).map_err(|()| Error::Syntax)?;
+        let name = custom_properties::parse_name(&definition.name).map_err(|()| Error::Syntax)?;
+
+        let document = win.Document();
+        let initial_value = match definition.initialValue {
+            Some(ref initial_value) => {
+                let mut input = ParserInput::new(initial_value);
+                let value = Parser::new(&mut input)
+                    .parse_entirely(VariableValue::parse)
+                    .map_err(|_| Error::Syntax)?;
+                Some(value)
+            },
+            None => None,
+        };
+        let registration = PropertyRegistration::new(
+            name.into(),
+            syntax,
+            definition.inherits,
+            initial_value,
+            document.url(),
+            QuirksMode::NoQuirks,
+        )
+        .map_err(|()| Error::Syntax)?;
+
+        if !document.add_registered_custom_property(registration.name.clone()) {
+            return Err(Error::InvalidModification);
+        }
+
+        let _ = win
+            .layout_chan()
+            .send(Msg::RegisterCustomProperty(registration));
+        document.invalidate_stylesheets();
+        Ok(())
+    }
+
     /// <https://drafts.css-houdini.org/css-paint-api-1/#paint-worklet>
     pub fn PaintWorklet(win: &Window) -> DomRoot<Worklet> {
         win.paint_worklet()
@@ -0,0 +1,64 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::animation::{timing_from_layout, Animation, AnimationOrigin};
+use crate::dom::bindings::codegen::Bindings::CSSAnimationBinding::{self, CSSAnimationMethods};
+use crate::dom::bindings::codegen::Bindings::DocumentBinding::DocumentMethods;
+use crate::dom::bindings::codegen::Bindings::KeyframeEffectBinding::CompositeOperation;
+use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::reflector::reflect_dom_object;
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::bindings::str::DOMString;
+use crate::dom::element::Element;
+use crate::dom::keyframeeffect::KeyframeEffect;
+use crate::dom::window::Window;
+use dom_struct::dom_struct;
+use script_layout_interface::message::RunningAnimationInfo;
+
+/// A CSS animation running in layout, as seen by script.
+#[dom_struct]
+pub struct CSSAnimation {
+    animation: Animation,
+}
+
+impl CSSAnimation {
+    pub fn new(
+        window: &Window,
+        target: &Element,
+        info: &RunningAnimationInfo,
+    ) -> DomRoot<CSSAnimation> {
+        let timeline = window.Document().Timeline();
+        let css_animation = reflect_dom_object(
+            Box::new(CSSAnimation {
+                animation: Animation::new_inherited(
+                    window.upcast(),
+                    Some(timeline.upcast()),
+                    AnimationOrigin::CssAnimation,
+                    info.name.clone(),
+                ),
+            }),
+            window,
+            CSSAnimationBinding::Wrap,
+        );
+        let effect = KeyframeEffect::new(
+            window,
+            Some(target),
+            vec![],
+            timing_from_layout(info),
+            CompositeOperation::Replace,
+        );
+        css_animation
+            .animation
+            .initialize_from_layout(effect.upcast(), info);
+        css_animation
+    }
+}
+
+impl CSSAnimationMethods for CSSAnimation {
+    // https://drafts.csswg.org/css-animations-2/#dom-cssanimation-animationname
+    fn AnimationName(&self) -> DOMString {
+        DOMString::from(&**self.animation.name())
+    }
+}
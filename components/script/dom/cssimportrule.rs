@@ -3,6 +3,7 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
 use crate::dom::bindings::codegen::Bindings::CSSImportRuleBinding;
+use crate::dom::bindings::codegen::Bindings::CSSImportRuleBinding::CSSImportRuleMethods;
 use crate::dom::bindings::reflector::reflect_dom_object;
 use crate::dom::bindings::root::DomRoot;
 use crate::dom::bindings::str::DOMString;
@@ -13,6 +14,7 @@ use dom_struct::dom_struct;
 use servo_arc::Arc;
 use style::shared_lock::{Locked, ToCssWithGuard};
 use style::stylesheets::ImportRule;
+use style_traits::ToCss;
 
 #[dom_struct]
 pub struct CSSImportRule {
@@ -60,3 +62,16 @@ impl SpecificCSSRule for CSSImportRule {
             .into()
     }
 }
+
+impl CSSImportRuleMethods for CSSImportRule {
+    // https://drafts.csswg.org/css-cascade-5/#dom-cssimportrule-layername
+    fn GetLayerName(&self) -> Option<DOMString> {
+        let guard = self.cssrule.shared_lock().read();
+        let rule = self.import_rule.read_with(&guard);
+        let layer = rule.layer.as_ref()?;
+        Some(match layer.name {
+            Some(ref name) => name.to_css_string().into(),
+            None => DOMString::new(),
+        })
+    }
+}
@@ -0,0 +1,81 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::bindings::codegen::Bindings::CSSLayerBlockRuleBinding;
+use crate::dom::bindings::codegen::Bindings::CSSLayerBlockRuleBinding::CSSLayerBlockRuleMethods;
+use crate::dom::bindings::reflector::reflect_dom_object;
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::bindings::str::DOMString;
+use crate::dom::cssgroupingrule::CSSGroupingRule;
+use crate::dom::cssrule::SpecificCSSRule;
+use crate::dom::cssstylesheet::CSSStyleSheet;
+use crate::dom::window::Window;
+use dom_struct::dom_struct;
+use servo_arc::Arc;
+use style::shared_lock::{Locked, ToCssWithGuard};
+use style::stylesheets::LayerBlockRule;
+use style_traits::ToCss;
+
+#[dom_struct]
+pub struct CSSLayerBlockRule {
+    cssgroupingrule: CSSGroupingRule,
+    #[ignore_malloc_size_of = "Arc"]
+    layerblockrule: Arc<Locked<LayerBlockRule>>,
+}
+
+impl CSSLayerBlockRule {
+    fn new_inherited(
+        parent_stylesheet: &CSSStyleSheet,
+        layerblockrule: Arc<Locked<LayerBlockRule>>,
+    ) -> CSSLayerBlockRule {
+        let guard = parent_stylesheet.shared_lock().read();
+        let list = layerblockrule.read_with(&guard).rules.clone();
+        CSSLayerBlockRule {
+            cssgroupingrule: CSSGroupingRule::new_inherited(parent_stylesheet, list),
+            layerblockrule: layerblockrule,
+        }
+    }
+
+    #[allow(unrooted_must_root)]
+    pub fn new(
+        window: &Window,
+        parent_stylesheet: &CSSStyleSheet,
+        layerblockrule: Arc<Locked<LayerBlockRule>>,
+    ) -> DomRoot<CSSLayerBlockRule> {
+        reflect_dom_object(
+            Box::new(CSSLayerBlockRule::new_inherited(
+                parent_stylesheet,
+                layerblockrule,
+            )),
+            window,
+            CSSLayerBlockRuleBinding::Wrap,
+        )
+    }
+}
+
+impl SpecificCSSRule for CSSLayerBlockRule {
+    fn ty(&self) -> u16 {
+        // Rule types added after CSSOM froze CSSRule.type have no constant.
+        0
+    }
+
+    fn get_css(&self) -> DOMString {
+        let guard = self.cssgroupingrule.shared_lock().read();
+        self.layerblockrule
+            .read_with(&guard)
+            .to_css_string(&guard)
+            .into()
+    }
+}
+
+impl CSSLayerBlockRuleMethods for CSSLayerBlockRule {
+    // https://drafts.csswg.org/css-cascade-5/#dom-csslayerblockrule-name
+    fn Name(&self) -> DOMString {
+        let guard = self.cssgroupingrule.shared_lock().read();
+        match self.layerblockrule.read_with(&guard).name {
+            Some(ref name) => name.to_css_string().into(),
+            None => DOMString::new(),
+        }
+    }
+}
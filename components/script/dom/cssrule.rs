@@ -7,12 +7,16 @@ use crate::dom::bindings::inheritance::Castable;
 use crate::dom::bindings::reflector::Reflector;
 use crate::dom::bindings::root::{Dom, DomRoot};
 use crate::dom::bindings::str::DOMString;
+use crate::dom::csscontainerrule::CSSContainerRule;
 use crate::dom::cssfontfacerule::CSSFontFaceRule;
 use crate::dom::cssimportrule::CSSImportRule;
 use crate::dom::csskeyframerule::CSSKeyframeRule;
 use crate::dom::csskeyframesrule::CSSKeyframesRule;
+use crate::dom::csslayerblockrule::CSSLayerBlockRule;
+use crate::dom::csslayerstatementrule::CSSLayerStatementRule;
 use crate::dom::cssmediarule::CSSMediaRule;
 use crate::dom::cssnamespacerule::CSSNamespaceRule;
+use crate::dom::csspropertyrule::CSSPropertyRule;
 use crate::dom::cssstylerule::CSSStyleRule;
 use crate::dom::cssstylesheet::CSSStyleSheet;
 use crate::dom::csssupportsrule::CSSSupportsRule;
@@ -63,6 +67,14 @@ impl CSSRule {
             rule as &dyn SpecificCSSRule
         } else if let Some(rule) = self.downcast::<CSSSupportsRule>() {
             rule as &dyn SpecificCSSRule
+        } else if let Some(rule) = self.downcast::<CSSLayerBlockRule>() {
+            rule as &dyn SpecificCSSRule
+        } else if let Some(rule) = self.downcast::<CSSLayerStatementRule>() {
+            rule as &dyn SpecificCSSRule
+        } else if let Some(rule) = self.downcast::<CSSContainerRule>() {
+            rule as &dyn SpecificCSSRule
+        } else if let Some(rule) = self.downcast::<CSSPropertyRule>() {
+            rule as &dyn SpecificCSSRule
         } else {
             unreachable!()
         }
@@ -103,6 +115,18 @@ impl CSSRule {
             StyleCssRule::Supports(s) => {
                 DomRoot::upcast(CSSSupportsRule::new(window, parent_stylesheet, s))
             },
+            StyleCssRule::LayerBlock(s) => {
+                DomRoot::upcast(CSSLayerBlockRule::new(window, parent_stylesheet, s))
+            },
+            StyleCssRule::LayerStatement(s) => {
+                DomRoot::upcast(CSSLayerStatementRule::new(window, parent_stylesheet, s))
+            },
+            StyleCssRule::Container(s) => {
+                DomRoot::upcast(CSSContainerRule::new(window, parent_stylesheet, s))
+            },
+            StyleCssRule::Property(s) => {
+                DomRoot::upcast(CSSPropertyRule::new(window, parent_stylesheet, s))
+            },
             StyleCssRule::Page(_) => unreachable!(),
             StyleCssRule::Document(_) => unimplemented!(), // TODO
         }
@@ -92,7 +92,13 @@ impl CSSRuleList {
 
     /// Should only be called for CssRules-backed rules. Use append_lazy_rule
     /// for keyframes-backed rules.
-    pub fn insert_rule(&self, rule: &str, idx: u32, nested: bool) -> Fallible<u32> {
+    pub fn insert_rule(
+        &self,
+        rule: &str,
+        idx: u32,
+        nested: bool,
+        in_style_rule: bool,
+    ) -> Fallible<u32> {
         let css_rules = if let RulesSource::Rules(ref rules) = self.rules {
             rules
         } else {
@@ -117,6 +123,7 @@ impl CSSRuleList {
                 &parent_stylesheet.contents,
                 index,
                 nested,
+                in_style_rule,
                 Some(&loader),
             )
         })?;
@@ -3,11 +3,13 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
 use crate::dom::bindings::codegen::Bindings::CSSStyleRuleBinding::{self, CSSStyleRuleMethods};
+use crate::dom::bindings::error::{ErrorResult, Fallible};
 use crate::dom::bindings::inheritance::Castable;
 use crate::dom::bindings::reflector::{reflect_dom_object, DomObject};
 use crate::dom::bindings::root::{Dom, DomRoot, MutNullableDom};
 use crate::dom::bindings::str::DOMString;
 use crate::dom::cssrule::{CSSRule, SpecificCSSRule};
+use crate::dom::cssrulelist::{CSSRuleList, RulesSource};
 use crate::dom::cssstyledeclaration::{CSSModificationAccess, CSSStyleDeclaration, CSSStyleOwner};
 use crate::dom::cssstylesheet::CSSStyleSheet;
 use crate::dom::node::{stylesheets_owner_from_node, Node};
@@ -20,7 +22,7 @@ use servo_arc::Arc;
 use std::mem;
 use style::selector_parser::SelectorParser;
 use style::shared_lock::{Locked, ToCssWithGuard};
-use style::stylesheets::{Origin, StyleRule};
+use style::stylesheets::{CssRules, Origin, StyleRule};
 
 #[dom_struct]
 pub struct CSSStyleRule {
@@ -28,6 +30,7 @@ pub struct CSSStyleRule {
     #[ignore_malloc_size_of = "Arc"]
     stylerule: Arc<Locked<StyleRule>>,
     style_decl: MutNullableDom<CSSStyleDeclaration>,
+    rulelist: MutNullableDom<CSSRuleList>,
 }
 
 impl CSSStyleRule {
@@ -39,6 +42,7 @@ impl CSSStyleRule {
             cssrule: CSSRule::new_inherited(parent_stylesheet),
             stylerule: stylerule,
             style_decl: Default::default(),
+            rulelist: MutNullableDom::new(None),
         }
     }
 
@@ -54,6 +58,28 @@ impl CSSStyleRule {
             CSSStyleRuleBinding::Wrap,
         )
     }
+
+    fn rulelist(&self) -> DomRoot<CSSRuleList> {
+        let parent_stylesheet = self.cssrule.parent_stylesheet();
+        self.rulelist.or_init(|| {
+            // Style rules without nested rules don't have a list to begin
+            // with.
+            let rules = {
+                let lock = self.cssrule.shared_lock();
+                let mut guard = lock.write();
+                self.stylerule
+                    .write_with(&mut guard)
+                    .rules
+                    .get_or_insert_with(|| CssRules::new(vec![], lock))
+                    .clone()
+            };
+            CSSRuleList::new(
+                self.global().as_window(),
+                parent_stylesheet,
+                RulesSource::Rules(rules),
+            )
+        })
+    }
 }
 
 impl SpecificCSSRule for CSSStyleRule {
@@ -122,4 +148,21 @@ impl CSSStyleRuleMethods for CSSStyleRule {
                 .invalidate_stylesheets();
         }
     }
+
+    // https://drafts.csswg.org/css-nesting/#dom-cssstylerule-cssrules
+    fn CssRules(&self) -> DomRoot<CSSRuleList> {
+        self.rulelist()
+    }
+
+    // https://drafts.csswg.org/css-nesting/#dom-cssstylerule-insertrule
+    fn InsertRule(&self, rule: DOMString, index: u32) -> Fallible<u32> {
+        self.rulelist().insert_rule(
+            &rule, index, /* nested */ true, /* in_style_rule */ true,
+        )
+    }
+
+    // https://drafts.csswg.org/css-nesting/#dom-cssstylerule-deleterule
+    fn DeleteRule(&self, index: u32) -> ErrorResult {
+        self.rulelist().remove_rule(index)
+    }
 }
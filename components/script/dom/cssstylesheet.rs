@@ -117,8 +117,9 @@ impl CSSStyleSheetMethods for CSSStyleSheet {
         if !self.origin_clean.get() {
             return Err(Error::Security);
         }
-        self.rulelist()
-            .insert_rule(&rule, index, /* nested */ false)
+        self.rulelist().insert_rule(
+            &rule, index, /* nested */ false, /* in_style_rule */ false,
+        )
     }
 
     // https://drafts.csswg.org/cssom/#dom-cssstylesheet-deleterule
@@ -0,0 +1,511 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::DataTransferBinding;
+use crate::dom::bindings::codegen::Bindings::DataTransferBinding::DataTransferMethods;
+use crate::dom::bindings::error::Fallible;
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::reflector::{reflect_dom_object, DomObject, Reflector};
+use crate::dom::bindings::root::{Dom, DomRoot, MutNullableDom};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::blob::{Blob, BlobImpl};
+use crate::dom::datatransferitem::DataTransferItem;
+use crate::dom::datatransferitemlist::DataTransferItemList;
+use crate::dom::element::Element;
+use crate::dom::file::File;
+use crate::dom::filelist::FileList;
+use crate::dom::window::Window;
+use crate::script_runtime::JSContext;
+use dom_struct::dom_struct;
+use embedder_traits::{ClipboardData, DragOperation};
+use js::conversions::ToJSValConvertible;
+use js::jsval::{JSVal, UndefinedValue};
+use script_traits::{DragData, DraggedFile};
+use std::cell::Cell;
+
+/// <https://html.spec.whatwg.org/multipage/#drag-data-store-mode>
+#[derive(Clone, Copy, Debug, JSTraceable, MallocSizeOf, PartialEq)]
+pub enum DataTransferMode {
+    ReadWrite,
+    ReadOnly,
+    Protected,
+}
+
+/// The values `dropEffect` can be set to.
+const DROP_EFFECTS: &[&str] = &["none", "copy", "link", "move"];
+
+/// The values `effectAllowed` can be set to.
+const EFFECTS_ALLOWED: &[&str] = &[
+    "none",
+    "copy",
+    "copyLink",
+    "copyMove",
+    "link",
+    "linkMove",
+    "move",
+    "all",
+    "uninitialized",
+];
+
+#[dom_struct]
+pub struct DataTransfer {
+    reflector_: Reflector,
+    /// The mode of the drag data store, or `None` once the `DataTransfer` is
+    /// no longer associated with it.
+    mode: Cell<Option<DataTransferMode>>,
+    /// <https://html.spec.whatwg.org/multipage/#drag-data-store-item-list>
+    items: DomRefCell<Vec<Dom<DataTransferItem>>>,
+    item_list: MutNullableDom<DataTransferItemList>,
+    drop_effect: DomRefCell<DOMString>,
+    /// <https://html.spec.whatwg.org/multipage/#drag-data-store-allowed-effects-state>
+    effect_allowed: DomRefCell<DOMString>,
+    /// <https://html.spec.whatwg.org/multipage/#drag-data-store-bitmap>
+    drag_image: MutNullableDom<Element>,
+    /// <https://html.spec.whatwg.org/multipage/#drag-data-store-hot-spot-coordinate>
+    drag_image_hot_spot: Cell<(i32, i32)>,
+}
+
+impl DataTransfer {
+    fn new_inherited(mode: DataTransferMode, effect_allowed: DOMString) -> DataTransfer {
+        DataTransfer {
+            reflector_: Reflector::new(),
+            mode: Cell::new(Some(mode)),
+            items: DomRefCell::new(vec![]),
+            item_list: Default::default(),
+            drop_effect: DomRefCell::new(DOMString::from("none")),
+            effect_allowed: DomRefCell::new(effect_allowed),
+            drag_image: Default::default(),
+            drag_image_hot_spot: Cell::new((0, 0)),
+        }
+    }
+
+    pub fn new(
+        window: &Window,
+        mode: DataTransferMode,
+        effect_allowed: DOMString,
+    ) -> DomRoot<DataTransfer> {
+        reflect_dom_object(
+            Box::new(DataTransfer::new_inherited(mode, effect_allowed)),
+            window,
+            DataTransferBinding::Wrap,
+        )
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-datatransfer
+    pub fn Constructor(window: &Window) -> Fallible<DomRoot<DataTransfer>> {
+        Ok(DataTransfer::new(
+            window,
+            DataTransferMode::ReadWrite,
+            DOMString::from("none"),
+        ))
+    }
+
+    /// Creates a `DataTransfer` for a drag-and-drop event, associated with a
+    /// drag data store holding the given data.
+    pub fn new_from_drag_data(
+        window: &Window,
+        data: &DragData,
+        mode: DataTransferMode,
+    ) -> DomRoot<DataTransfer> {
+        let data_transfer =
+            DataTransfer::new(window, mode, DOMString::from(data.effect_allowed.clone()));
+        for &(ref type_, ref string) in &data.strings {
+            data_transfer.push_item(&DataTransferItem::new_string(
+                window,
+                &data_transfer,
+                DOMString::from(type_.clone()),
+                DOMString::from(string.clone()),
+            ));
+        }
+        for file in &data.files {
+            let item = match *file {
+                DraggedFile::Selected(ref selected) => {
+                    let file = File::new_from_selected(window, selected.clone());
+                    DataTransferItem::new_file(
+                        window,
+                        &data_transfer,
+                        DOMString::from(selected.type_string.clone()),
+                        Some(&file),
+                    )
+                },
+                // The contents of files that aren't dropped yet are not
+                // available, but their type is.
+                DraggedFile::Pending(ref type_) => DataTransferItem::new_file(
+                    window,
+                    &data_transfer,
+                    DOMString::from(type_.clone()),
+                    None,
+                ),
+            };
+            data_transfer.push_item(&item);
+        }
+        data_transfer
+    }
+
+    /// The data of the drag data store, to be sent along with the
+    /// drag-and-drop operation.
+    ///
+    /// Files added by script can't leave the document, so only the text items
+    /// are kept.
+    pub fn drag_data(&self) -> DragData {
+        let strings = self
+            .items
+            .borrow()
+            .iter()
+            .filter_map(|item| {
+                item.string()
+                    .map(|string| (item.type_().to_string(), string.to_string()))
+            })
+            .collect();
+        DragData {
+            strings,
+            files: vec![],
+            effect_allowed: self.effect_allowed.borrow().to_string(),
+        }
+    }
+
+    /// Creates a `DataTransfer` for a clipboard event, associated with a
+    /// drag data store holding the given clipboard data.
+    ///
+    /// Text is exposed as string items, and everything else as files.
+    pub fn new_from_clipboard_data(
+        window: &Window,
+        data: ClipboardData,
+        mode: DataTransferMode,
+    ) -> DomRoot<DataTransfer> {
+        let data_transfer = DataTransfer::new(window, mode, DOMString::from("none"));
+        for (type_, bytes) in data.representations {
+            let item = if type_.starts_with("text/") {
+                DataTransferItem::new_string(
+                    window,
+                    &data_transfer,
+                    DOMString::from(type_),
+                    DOMString::from(String::from_utf8_lossy(&bytes).into_owned()),
+                )
+            } else {
+                let name = if type_ == "image/png" {
+                    "image.png"
+                } else {
+                    ""
+                };
+                let file = File::new(
+                    window.upcast(),
+                    BlobImpl::new_from_bytes(bytes),
+                    DOMString::from(name),
+                    None,
+                    &type_,
+                );
+                DataTransferItem::new_file(
+                    window,
+                    &data_transfer,
+                    DOMString::from(type_),
+                    Some(&file),
+                )
+            };
+            data_transfer.push_item(&item);
+        }
+        data_transfer
+    }
+
+    /// The data of the drag data store, to be written to the system clipboard.
+    pub fn clipboard_data(&self) -> ClipboardData {
+        let representations = self
+            .items
+            .borrow()
+            .iter()
+            .filter_map(|item| match item.string() {
+                Some(string) => Some((item.type_().to_string(), string.to_string().into_bytes())),
+                None => item.file().and_then(|file| {
+                    let bytes = file.upcast::<Blob>().get_bytes().ok()?;
+                    Some((item.type_().to_string(), bytes))
+                }),
+            })
+            .collect();
+        ClipboardData { representations }
+    }
+
+    pub fn mode(&self) -> Option<DataTransferMode> {
+        self.mode.get()
+    }
+
+    /// Breaks the association between the `DataTransfer` and its drag data
+    /// store, once the event it was created for is dispatched.
+    pub fn disassociate(&self) {
+        self.mode.set(None);
+    }
+
+    /// Sets `dropEffect` to its initial value for `dragenter` and `dragover`
+    /// events, which depends on the `effectAllowed` state.
+    ///
+    /// <https://html.spec.whatwg.org/multipage/#dropEffect-initialisation>
+    pub fn initialize_drop_effect(&self) {
+        let drop_effect = match &*self.effect_allowed.borrow().to_ascii_lowercase() {
+            "copy" | "copylink" | "copymove" | "all" | "uninitialized" => "copy",
+            "link" | "linkmove" => "link",
+            "move" => "move",
+            _ => "none",
+        };
+        *self.drop_effect.borrow_mut() = DOMString::from(drop_effect);
+    }
+
+    pub fn set_drop_effect(&self, operation: DragOperation) {
+        let drop_effect = match operation {
+            DragOperation::None => "none",
+            DragOperation::Copy => "copy",
+            DragOperation::Link => "link",
+            DragOperation::Move => "move",
+        };
+        *self.drop_effect.borrow_mut() = DOMString::from(drop_effect);
+    }
+
+    /// Whether the `effectAllowed` state allows the given operation.
+    pub fn allows(&self, operation: DragOperation) -> bool {
+        let effect = match operation {
+            DragOperation::None => return false,
+            DragOperation::Copy => "copy",
+            DragOperation::Link => "link",
+            DragOperation::Move => "move",
+        };
+        match &*self.effect_allowed.borrow().to_ascii_lowercase() {
+            "all" | "uninitialized" => true,
+            effect_allowed => effect_allowed.contains(effect),
+        }
+    }
+
+    /// The operation `dropEffect` selects, if the `effectAllowed` state
+    /// allows it.
+    pub fn drag_operation(&self) -> DragOperation {
+        let operation = match &**self.drop_effect.borrow() {
+            "copy" => DragOperation::Copy,
+            "link" => DragOperation::Link,
+            "move" => DragOperation::Move,
+            _ => DragOperation::None,
+        };
+        if self.allows(operation) {
+            operation
+        } else {
+            DragOperation::None
+        }
+    }
+
+    /// The element set with `setDragImage`, and the point of its image that is
+    /// under the cursor.
+    pub fn drag_image(&self) -> Option<(DomRoot<Element>, i32, i32)> {
+        let (x, y) = self.drag_image_hot_spot.get();
+        self.drag_image.get().map(|element| (element, x, y))
+    }
+
+    pub fn items(&self) -> Vec<DomRoot<DataTransferItem>> {
+        if self.mode.get().is_none() {
+            return vec![];
+        }
+        self.items
+            .borrow()
+            .iter()
+            .map(|item| DomRoot::from_ref(&**item))
+            .collect()
+    }
+
+    pub fn push_item(&self, item: &DataTransferItem) {
+        self.items.borrow_mut().push(Dom::from_ref(item));
+    }
+
+    /// Removes the items matching the predicate from the drag data store,
+    /// which disables them.
+    pub fn remove_items<F>(&self, predicate: F)
+    where
+        F: Fn(usize, &DataTransferItem) -> bool,
+    {
+        let mut index = 0;
+        self.items.borrow_mut().retain(|item| {
+            let remove = predicate(index, item);
+            index += 1;
+            if remove {
+                item.disable();
+            }
+            !remove
+        });
+    }
+
+    /// The string item of the given type, if any.
+    fn string_item(&self, type_: &str) -> Option<DomRoot<DataTransferItem>> {
+        self.items
+            .borrow()
+            .iter()
+            .find(|item| item.is_string() && &*item.type_() == type_)
+            .map(|item| DomRoot::from_ref(&**item))
+    }
+}
+
+/// Normalizes the format given to `getData`, `setData` and `clearData`, and
+/// returns whether the data should be converted to a URL.
+fn normalize_format(format: &str) -> (String, bool) {
+    let format = format.to_ascii_lowercase();
+    match &*format {
+        "text" => ("text/plain".to_owned(), false),
+        "url" => ("text/uri-list".to_owned(), true),
+        _ => (format, false),
+    }
+}
+
+impl DataTransferMethods for DataTransfer {
+    // https://html.spec.whatwg.org/multipage/#dom-datatransfer-dropeffect
+    fn DropEffect(&self) -> DOMString {
+        self.drop_effect.borrow().clone()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-datatransfer-dropeffect
+    fn SetDropEffect(&self, value: DOMString) {
+        if DROP_EFFECTS.contains(&&*value) {
+            *self.drop_effect.borrow_mut() = value;
+        }
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-datatransfer-effectallowed
+    fn EffectAllowed(&self) -> DOMString {
+        self.effect_allowed.borrow().clone()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-datatransfer-effectallowed
+    fn SetEffectAllowed(&self, value: DOMString) {
+        if self.mode.get() == Some(DataTransferMode::ReadWrite) &&
+            EFFECTS_ALLOWED.contains(&&*value)
+        {
+            *self.effect_allowed.borrow_mut() = value;
+        }
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-datatransfer-items
+    fn Items(&self) -> DomRoot<DataTransferItemList> {
+        self.item_list
+            .or_init(|| DataTransferItemList::new(&self.global().as_window(), self))
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-datatransfer-setdragimage
+    fn SetDragImage(&self, image: &Element, x: i32, y: i32) {
+        // Step 1.
+        if self.mode.get() != Some(DataTransferMode::ReadWrite) {
+            return;
+        }
+
+        // Steps 2-4.
+        self.drag_image.set(Some(image));
+        self.drag_image_hot_spot.set((x, y));
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-datatransfer-types
+    #[allow(unsafe_code)]
+    fn Types(&self, cx: JSContext) -> JSVal {
+        let mut types: Vec<DOMString> = vec![];
+        let mut has_files = false;
+        for item in self.items() {
+            if item.is_string() {
+                types.push(item.type_());
+            } else {
+                has_files = true;
+            }
+        }
+        if has_files {
+            types.push(DOMString::from("Files"));
+        }
+
+        // TODO: This should be a frozen array that stays the same object
+        // until the drag data store changes.
+        unsafe {
+            rooted!(in(*cx) let mut types_value = UndefinedValue());
+            types.to_jsval(*cx, types_value.handle_mut());
+            types_value.get()
+        }
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-datatransfer-getdata
+    fn GetData(&self, format: DOMString) -> DOMString {
+        // Steps 1-2.
+        match self.mode.get() {
+            Some(DataTransferMode::ReadWrite) | Some(DataTransferMode::ReadOnly) => {},
+            Some(DataTransferMode::Protected) | None => return DOMString::new(),
+        }
+
+        // Steps 3-6.
+        let (format, convert_to_url) = normalize_format(&format);
+
+        // Steps 7-8.
+        let item = match self.string_item(&format) {
+            Some(item) => item,
+            None => return DOMString::new(),
+        };
+        let data = match item.string() {
+            Some(data) => data,
+            None => return DOMString::new(),
+        };
+
+        // Step 9.
+        if convert_to_url {
+            return data
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty() && !line.starts_with('#'))
+                .map_or(DOMString::new(), DOMString::from);
+        }
+
+        // Step 10.
+        data
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-datatransfer-setdata
+    fn SetData(&self, format: DOMString, data: DOMString) {
+        // Step 1-2.
+        if self.mode.get() != Some(DataTransferMode::ReadWrite) {
+            return;
+        }
+
+        // Steps 3-4.
+        let (format, _) = normalize_format(&format);
+
+        // Step 5.
+        self.remove_items(|_, item| item.is_string() && &*item.type_() == &*format);
+
+        // Step 6.
+        let item = DataTransferItem::new_string(
+            &self.global().as_window(),
+            self,
+            DOMString::from(format),
+            data,
+        );
+        self.push_item(&item);
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-datatransfer-cleardata
+    fn ClearData(&self, format: Option<DOMString>) {
+        // Steps 1-2.
+        if self.mode.get() != Some(DataTransferMode::ReadWrite) {
+            return;
+        }
+
+        match format {
+            // Step 3.
+            None => self.remove_items(|_, item| item.is_string()),
+            // Step 4.
+            Some(format) => {
+                let (format, _) = normalize_format(&format);
+                self.remove_items(|_, item| item.is_string() && &*item.type_() == &*format);
+            },
+        }
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-datatransfer-files
+    fn Files(&self) -> DomRoot<FileList> {
+        // Steps 1-2.
+        let files = match self.mode.get() {
+            Some(DataTransferMode::ReadWrite) | Some(DataTransferMode::ReadOnly) => {
+                self.items().iter().filter_map(|item| item.file()).collect()
+            },
+            Some(DataTransferMode::Protected) | None => vec![],
+        };
+
+        // Step 3.
+        FileList::new(&self.global().as_window(), files)
+    }
+}
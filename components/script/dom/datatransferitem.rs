@@ -0,0 +1,227 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::bindings::callback::ExceptionHandling;
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::DataTransferItemBinding;
+use crate::dom::bindings::codegen::Bindings::DataTransferItemBinding::DataTransferItemMethods;
+use crate::dom::bindings::codegen::Bindings::DataTransferItemBinding::FunctionStringCallback;
+use crate::dom::bindings::refcounted::Trusted;
+use crate::dom::bindings::reflector::{reflect_dom_object, DomObject, Reflector};
+use crate::dom::bindings::root::{Dom, DomRoot, MutNullableDom};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::datatransfer::{DataTransfer, DataTransferMode};
+use crate::dom::file::File;
+use crate::dom::window::Window;
+use crate::task_source::TaskSource;
+use dom_struct::dom_struct;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+/// <https://html.spec.whatwg.org/multipage/#the-drag-data-item-kind>
+#[must_root]
+#[derive(JSTraceable, MallocSizeOf)]
+enum DataTransferItemData {
+    /// A plain Unicode string.
+    String(DOMString),
+    /// A file, or `None` for a file dragged from the embedder whose contents
+    /// aren't available until it is dropped.
+    File(Option<Dom<File>>),
+}
+
+#[dom_struct]
+pub struct DataTransferItem {
+    reflector_: Reflector,
+    /// The `DataTransfer` whose drag data store holds the item, or `None` once
+    /// the item is removed from it.
+    data_transfer: MutNullableDom<DataTransfer>,
+    /// <https://html.spec.whatwg.org/multipage/#the-drag-data-item-type-string>
+    type_: DOMString,
+    data: DataTransferItemData,
+    /// The callbacks passed to `getAsString`, in the order their tasks are
+    /// queued.
+    #[ignore_malloc_size_of = "Rc"]
+    string_callbacks: DomRefCell<VecDeque<Rc<FunctionStringCallback>>>,
+}
+
+impl DataTransferItem {
+    #[allow(unrooted_must_root)]
+    fn new_inherited(
+        data_transfer: &DataTransfer,
+        type_: DOMString,
+        data: DataTransferItemData,
+    ) -> DataTransferItem {
+        DataTransferItem {
+            reflector_: Reflector::new(),
+            data_transfer: MutNullableDom::new(Some(data_transfer)),
+            type_,
+            data,
+            string_callbacks: DomRefCell::new(VecDeque::new()),
+        }
+    }
+
+    #[allow(unrooted_must_root)]
+    fn new(
+        window: &Window,
+        data_transfer: &DataTransfer,
+        type_: DOMString,
+        data: DataTransferItemData,
+    ) -> DomRoot<DataTransferItem> {
+        reflect_dom_object(
+            Box::new(DataTransferItem::new_inherited(data_transfer, type_, data)),
+            window,
+            DataTransferItemBinding::Wrap,
+        )
+    }
+
+    pub fn new_string(
+        window: &Window,
+        data_transfer: &DataTransfer,
+        type_: DOMString,
+        data: DOMString,
+    ) -> DomRoot<DataTransferItem> {
+        DataTransferItem::new(
+            window,
+            data_transfer,
+            type_,
+            DataTransferItemData::String(data),
+        )
+    }
+
+    pub fn new_file(
+        window: &Window,
+        data_transfer: &DataTransfer,
+        type_: DOMString,
+        file: Option<&File>,
+    ) -> DomRoot<DataTransferItem> {
+        DataTransferItem::new(
+            window,
+            data_transfer,
+            type_,
+            DataTransferItemData::File(file.map(Dom::from_ref)),
+        )
+    }
+
+    pub fn type_(&self) -> DOMString {
+        self.type_.clone()
+    }
+
+    pub fn is_string(&self) -> bool {
+        match self.data {
+            DataTransferItemData::String(_) => true,
+            DataTransferItemData::File(_) => false,
+        }
+    }
+
+    pub fn string(&self) -> Option<DOMString> {
+        match self.data {
+            DataTransferItemData::String(ref string) => Some(string.clone()),
+            DataTransferItemData::File(_) => None,
+        }
+    }
+
+    pub fn file(&self) -> Option<DomRoot<File>> {
+        match self.data {
+            DataTransferItemData::String(_) => None,
+            DataTransferItemData::File(ref file) => {
+                file.as_ref().map(|file| DomRoot::from_ref(&**file))
+            },
+        }
+    }
+
+    /// Disables the item once it is removed from the drag data store.
+    ///
+    /// <https://html.spec.whatwg.org/multipage/#the-datatransferitem-interface>
+    pub fn disable(&self) {
+        self.data_transfer.set(None);
+    }
+
+    /// Whether the data of the item can be read, which is only the case while
+    /// the drag data store is in read/write or read-only mode.
+    fn is_readable(&self) -> bool {
+        match self
+            .data_transfer
+            .get()
+            .and_then(|data_transfer| data_transfer.mode())
+        {
+            Some(DataTransferMode::ReadWrite) | Some(DataTransferMode::ReadOnly) => true,
+            Some(DataTransferMode::Protected) | None => false,
+        }
+    }
+}
+
+impl DataTransferItemMethods for DataTransferItem {
+    // https://html.spec.whatwg.org/multipage/#dom-datatransferitem-kind
+    fn Kind(&self) -> DOMString {
+        // Step 1.
+        if self.data_transfer.get().is_none() {
+            return DOMString::new();
+        }
+
+        // Step 2.
+        DOMString::from(if self.is_string() { "string" } else { "file" })
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-datatransferitem-type
+    fn Type(&self) -> DOMString {
+        // Step 1.
+        if self.data_transfer.get().is_none() {
+            return DOMString::new();
+        }
+
+        // Step 2.
+        self.type_.clone()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-datatransferitem-getasstring
+    fn GetAsString(&self, callback: Option<Rc<FunctionStringCallback>>) {
+        // Step 1.
+        let callback = match callback {
+            Some(callback) => callback,
+            None => return,
+        };
+
+        // Steps 2-3.
+        if !self.is_readable() {
+            return;
+        }
+
+        // Step 4.
+        let data = match self.string() {
+            Some(data) => data,
+            None => return,
+        };
+
+        // Step 5.
+        self.string_callbacks.borrow_mut().push_back(callback);
+        let this = Trusted::new(self);
+        let data = data.to_string();
+        let global = self.global();
+        let (task_source, canceller) = global
+            .as_window()
+            .task_manager()
+            .dom_manipulation_task_source_with_canceller();
+        let _ = task_source.queue_with_canceller(
+            task!(invoke_get_as_string_callback: move || {
+                let this = this.root();
+                let callback = this.string_callbacks.borrow_mut().pop_front();
+                if let Some(callback) = callback {
+                    let _ = callback.Call__(DOMString::from(data), ExceptionHandling::Report);
+                }
+            }),
+            &canceller,
+        );
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-datatransferitem-getasfile
+    fn GetAsFile(&self) -> Option<DomRoot<File>> {
+        // Steps 1-2.
+        if !self.is_readable() {
+            return None;
+        }
+
+        // Steps 3-4.
+        self.file()
+    }
+}
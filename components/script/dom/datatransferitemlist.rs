@@ -0,0 +1,134 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::bindings::codegen::Bindings::DataTransferItemListBinding;
+use crate::dom::bindings::codegen::Bindings::DataTransferItemListBinding::DataTransferItemListMethods;
+use crate::dom::bindings::error::{Error, ErrorResult, Fallible};
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::reflector::{reflect_dom_object, DomObject, Reflector};
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::blob::Blob;
+use crate::dom::datatransfer::{DataTransfer, DataTransferMode};
+use crate::dom::datatransferitem::DataTransferItem;
+use crate::dom::file::File;
+use crate::dom::window::Window;
+use dom_struct::dom_struct;
+
+#[dom_struct]
+pub struct DataTransferItemList {
+    reflector_: Reflector,
+    data_transfer: Dom<DataTransfer>,
+}
+
+impl DataTransferItemList {
+    fn new_inherited(data_transfer: &DataTransfer) -> DataTransferItemList {
+        DataTransferItemList {
+            reflector_: Reflector::new(),
+            data_transfer: Dom::from_ref(data_transfer),
+        }
+    }
+
+    pub fn new(window: &Window, data_transfer: &DataTransfer) -> DomRoot<DataTransferItemList> {
+        reflect_dom_object(
+            Box::new(DataTransferItemList::new_inherited(data_transfer)),
+            window,
+            DataTransferItemListBinding::Wrap,
+        )
+    }
+
+    fn is_read_write(&self) -> bool {
+        self.data_transfer.mode() == Some(DataTransferMode::ReadWrite)
+    }
+}
+
+impl DataTransferItemListMethods for DataTransferItemList {
+    // https://html.spec.whatwg.org/multipage/#dom-datatransferitemlist-length
+    fn Length(&self) -> u32 {
+        self.data_transfer.items().len() as u32
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-datatransferitemlist-item
+    fn IndexedGetter(&self, index: u32) -> Option<DomRoot<DataTransferItem>> {
+        self.data_transfer.items().into_iter().nth(index as usize)
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-datatransferitemlist-add
+    fn Add(
+        &self,
+        data: DOMString,
+        type_: DOMString,
+    ) -> Fallible<Option<DomRoot<DataTransferItem>>> {
+        // Step 1.
+        if !self.is_read_write() {
+            return Ok(None);
+        }
+
+        // Step 2.
+        let type_ = DOMString::from(type_.to_ascii_lowercase());
+        if self
+            .data_transfer
+            .items()
+            .iter()
+            .any(|item| item.is_string() && item.type_() == type_)
+        {
+            return Err(Error::NotSupported);
+        }
+        let item = DataTransferItem::new_string(
+            &self.global().as_window(),
+            &self.data_transfer,
+            type_,
+            data,
+        );
+        self.data_transfer.push_item(&item);
+
+        // Step 3.
+        Ok(Some(item))
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-datatransferitemlist-add
+    fn Add_(&self, data: &File) -> Fallible<Option<DomRoot<DataTransferItem>>> {
+        // Step 1.
+        if !self.is_read_write() {
+            return Ok(None);
+        }
+
+        // Step 2.
+        let type_ = DOMString::from(data.upcast::<Blob>().type_string().to_ascii_lowercase());
+        let item = DataTransferItem::new_file(
+            &self.global().as_window(),
+            &self.data_transfer,
+            type_,
+            Some(data),
+        );
+        self.data_transfer.push_item(&item);
+
+        // Step 3.
+        Ok(Some(item))
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-datatransferitemlist-remove
+    fn Remove(&self, index: u32) -> ErrorResult {
+        // Step 1.
+        if !self.is_read_write() {
+            return Err(Error::InvalidState);
+        }
+
+        // Step 2.
+        self.data_transfer
+            .remove_items(|item_index, _| item_index == index as usize);
+        Ok(())
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-datatransferitemlist-clear
+    fn Clear(&self) {
+        // Step 1.
+        if !self.is_read_write() {
+            return;
+        }
+
+        // Step 2.
+        self.data_transfer.remove_items(|_, _| true);
+    }
+}
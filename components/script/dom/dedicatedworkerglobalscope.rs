@@ -22,6 +22,7 @@ use crate::dom::event::{Event, EventBubbles, EventCancelable, EventStatus};
 use crate::dom::eventtarget::EventTarget;
 use crate::dom::globalscope::GlobalScope;
 use crate::dom::messageevent::MessageEvent;
+use crate::dom::webglrenderingcontext::WebGLCommandSender;
 use crate::dom::worker::{TrustedWorkerAddress, Worker};
 use crate::dom::workerglobalscope::WorkerGlobalScope;
 use crate::fetch::load_whole_resource;
@@ -36,10 +37,10 @@ use devtools_traits::DevtoolScriptControlMsg;
 use dom_struct::dom_struct;
 use ipc_channel::ipc::{self, IpcReceiver, IpcSender};
 use ipc_channel::router::ROUTER;
-use js::jsapi::JSContext;
 use js::jsapi::JS_AddInterruptCallback;
+use js::jsapi::{JSContext, JSObject};
 use js::jsval::UndefinedValue;
-use js::rust::HandleValue;
+use js::rust::{CustomAutoRooterGuard, HandleValue};
 use msg::constellation_msg::{PipelineId, TopLevelBrowsingContextId};
 use net_traits::image_cache::ImageCache;
 use net_traits::request::{CredentialsMode, Destination, ParserMetadata};
@@ -180,6 +181,8 @@ pub struct DedicatedWorkerGlobalScope {
     parent_sender: Box<dyn ScriptChan + Send>,
     #[ignore_malloc_size_of = "Arc"]
     image_cache: Arc<dyn ImageCache>,
+    #[ignore_malloc_size_of = "Defined in canvas_traits"]
+    webgl_chan: Option<WebGLCommandSender>,
 }
 
 impl WorkerEventLoopMethods for DedicatedWorkerGlobalScope {
@@ -232,6 +235,7 @@ impl DedicatedWorkerGlobalScope {
         timer_event_port: Receiver<(TrustedWorkerAddress, TimerEvent)>,
         closing: Arc<AtomicBool>,
         image_cache: Arc<dyn ImageCache>,
+        webgl_chan: Option<WebGLCommandSender>,
     ) -> DedicatedWorkerGlobalScope {
         DedicatedWorkerGlobalScope {
             workerglobalscope: WorkerGlobalScope::new_inherited(
@@ -250,6 +254,7 @@ impl DedicatedWorkerGlobalScope {
             parent_sender: parent_sender,
             worker: DomRefCell::new(None),
             image_cache: image_cache,
+            webgl_chan: webgl_chan,
         }
     }
 
@@ -268,6 +273,7 @@ impl DedicatedWorkerGlobalScope {
         timer_event_port: Receiver<(TrustedWorkerAddress, TimerEvent)>,
         closing: Arc<AtomicBool>,
         image_cache: Arc<dyn ImageCache>,
+        webgl_chan: Option<WebGLCommandSender>,
     ) -> DomRoot<DedicatedWorkerGlobalScope> {
         let cx = runtime.cx();
         let scope = Box::new(DedicatedWorkerGlobalScope::new_inherited(
@@ -284,6 +290,7 @@ impl DedicatedWorkerGlobalScope {
             timer_event_port,
             closing,
             image_cache,
+            webgl_chan,
         ));
         unsafe { DedicatedWorkerGlobalScopeBinding::Wrap(SafeJSContext::from_ptr(cx), scope) }
     }
@@ -303,6 +310,7 @@ impl DedicatedWorkerGlobalScope {
         worker_type: WorkerType,
         closing: Arc<AtomicBool>,
         image_cache: Arc<dyn ImageCache>,
+        webgl_chan: Option<WebGLCommandSender>,
     ) {
         let serialized_worker_url = worker_url.to_string();
         let name = format!("WebWorker for {}", serialized_worker_url);
@@ -375,6 +383,7 @@ impl DedicatedWorkerGlobalScope {
                     timer_rx,
                     closing,
                     image_cache,
+                    webgl_chan,
                 );
                 // FIXME(njn): workers currently don't have a unique ID suitable for using in reporter
                 // registration (#6631), so we instead use a random number and cross our fingers.
@@ -444,6 +453,10 @@ impl DedicatedWorkerGlobalScope {
         self.image_cache.clone()
     }
 
+    pub(crate) fn webgl_chan(&self) -> Option<WebGLCommandSender> {
+        self.webgl_chan.clone()
+    }
+
     pub fn script_chan(&self) -> Box<dyn ScriptChan + Send> {
         Box::new(WorkerThreadWorkerChan {
             sender: self.own_sender.clone(),
@@ -561,8 +574,13 @@ unsafe extern "C" fn interrupt_callback(cx: *mut JSContext) -> bool {
 
 impl DedicatedWorkerGlobalScopeMethods for DedicatedWorkerGlobalScope {
     // https://html.spec.whatwg.org/multipage/#dom-dedicatedworkerglobalscope-postmessage
-    fn PostMessage(&self, cx: SafeJSContext, message: HandleValue) -> ErrorResult {
-        let data = StructuredCloneData::write(*cx, message)?;
+    fn PostMessage(
+        &self,
+        cx: SafeJSContext,
+        message: HandleValue,
+        transfer: CustomAutoRooterGuard<Vec<*mut JSObject>>,
+    ) -> ErrorResult {
+        let data = StructuredCloneData::write_with_transfer(*cx, message, &transfer)?;
         let worker = self.worker.borrow().as_ref().unwrap().clone();
         let pipeline_id = self.upcast::<GlobalScope>().pipeline_id();
         let task = Box::new(task!(post_worker_message: move || {
@@ -2,14 +2,17 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
+use crate::clipboard_provider::ClipboardProvider;
 use crate::compartments::{AlreadyInCompartment, InCompartment};
 use crate::document_loader::{DocumentLoader, LoadType};
 use crate::dom::activation::{synthetic_click_activation, ActivationSource};
+use crate::dom::animation::{Animation, AnimationOrigin};
 use crate::dom::attr::Attr;
 use crate::dom::beforeunloadevent::BeforeUnloadEvent;
 use crate::dom::bindings::callback::ExceptionHandling;
 use crate::dom::bindings::cell::DomRefCell;
 use crate::dom::bindings::codegen::Bindings::BeforeUnloadEventBinding::BeforeUnloadEventBinding::BeforeUnloadEventMethods;
+use crate::dom::bindings::codegen::Bindings::DataTransferBinding::DataTransferMethods;
 use crate::dom::bindings::codegen::Bindings::DocumentBinding;
 use crate::dom::bindings::codegen::Bindings::DocumentBinding::ElementCreationOptions;
 use crate::dom::bindings::codegen::Bindings::DocumentBinding::{
@@ -38,16 +41,22 @@ use crate::dom::bindings::xmlname::{
     namespace_from_domstring, validate_and_extract, xml_name_type,
 };
 use crate::dom::cdatasection::CDATASection;
+use crate::dom::clipboardevent::{ClipboardEvent, ClipboardEventType};
 use crate::dom::closeevent::CloseEvent;
 use crate::dom::comment::Comment;
 use crate::dom::compositionevent::CompositionEvent;
+use crate::dom::cssanimation::CSSAnimation;
 use crate::dom::cssstylesheet::CSSStyleSheet;
+use crate::dom::csstransition::CSSTransition;
 use crate::dom::customelementregistry::CustomElementDefinition;
 use crate::dom::customevent::CustomEvent;
+use crate::dom::datatransfer::{DataTransfer, DataTransferMode};
 use crate::dom::documentfragment::DocumentFragment;
 use crate::dom::documentorshadowroot::{DocumentOrShadowRoot, StyleSheetInDocument};
+use crate::dom::documenttimeline::DocumentTimeline;
 use crate::dom::documenttype::DocumentType;
 use crate::dom::domimplementation::DOMImplementation;
+use crate::dom::dragevent::DragEvent;
 use crate::dom::element::CustomElementCreationMode;
 use crate::dom::element::{
     Element, ElementCreator, ElementPerformFullscreenEnter, ElementPerformFullscreenExit,
@@ -56,6 +65,7 @@ use crate::dom::errorevent::ErrorEvent;
 use crate::dom::event::{Event, EventBubbles, EventCancelable, EventDefault, EventStatus};
 use crate::dom::eventtarget::EventTarget;
 use crate::dom::focusevent::FocusEvent;
+use crate::dom::fontfaceset::FontFaceSet;
 use crate::dom::globalscope::GlobalScope;
 use crate::dom::hashchangeevent::HashChangeEvent;
 use crate::dom::htmlanchorelement::HTMLAnchorElement;
@@ -70,6 +80,7 @@ use crate::dom::htmlheadelement::HTMLHeadElement;
 use crate::dom::htmlhtmlelement::HTMLHtmlElement;
 use crate::dom::htmliframeelement::HTMLIFrameElement;
 use crate::dom::htmlimageelement::HTMLImageElement;
+use crate::dom::htmlinputelement::{HTMLInputElement, InputType};
 use crate::dom::htmlscriptelement::{HTMLScriptElement, ScriptResult};
 use crate::dom::htmltitleelement::HTMLTitleElement;
 use crate::dom::keyboardevent::KeyboardEvent;
@@ -81,6 +92,9 @@ use crate::dom::node::{LayoutNodeHelpers, Node, NodeDamage, NodeFlags, ShadowInc
 use crate::dom::nodeiterator::NodeIterator;
 use crate::dom::nodelist::NodeList;
 use crate::dom::pagetransitionevent::PageTransitionEvent;
+use crate::dom::pointerevent::{
+    touch_pointer_id, PointerEvent, PointerProperties, PointerType, MOUSE_POINTER_ID,
+};
 use crate::dom::popstateevent::PopStateEvent;
 use crate::dom::processinginstruction::ProcessingInstruction;
 use crate::dom::progressevent::ProgressEvent;
@@ -113,13 +127,15 @@ use canvas_traits::webgl::{self, WebGLContextId, WebGLMsg};
 use cookie::Cookie;
 use devtools_traits::ScriptToDevtoolsControlMsg;
 use dom_struct::dom_struct;
-use embedder_traits::EmbedderMsg;
+use embedder_traits::{DragOperation, EmbedderMsg};
 use encoding_rs::{Encoding, UTF_8};
 use euclid::default::Point2D;
+use euclid::{Size2D, Vector2D};
+use gfx_traits::WebFontLoadResult;
 use html5ever::{LocalName, Namespace, QualName};
 use hyper_serde::Serde;
 use ipc_channel::ipc::{self, IpcSender};
-use js::jsapi::{JSObject, JSRuntime};
+use js::jsapi::{JSObject, JSRuntime, JS_GetRuntime};
 use keyboard_types::{Key, KeyState, Modifiers};
 use metrics::{
     InteractiveFlag, InteractiveMetrics, InteractiveWindow, ProfilerMetadataFactory,
@@ -138,8 +154,10 @@ use percent_encoding::percent_decode;
 use profile_traits::ipc as profile_ipc;
 use profile_traits::time::{TimerMetadata, TimerMetadataFrameType, TimerMetadataReflowType};
 use ref_slice::ref_slice;
-use script_layout_interface::message::{Msg, ReflowGoal};
+use script_layout_interface::message::{Msg, ReflowGoal, RunningAnimationKind};
 use script_traits::{AnimationState, DocumentActivity, MouseButton, MouseEventType};
+use script_traits::{DragData, DragEventType, DragImage};
+use script_traits::{EventResult, TouchBehavior};
 use script_traits::{
     MsDuration, ScriptMsg, TouchEventType, TouchId, UntrustedNodeAddress, WheelDelta,
 };
@@ -166,6 +184,7 @@ use style::shared_lock::SharedRwLock as StyleSharedRwLock;
 use style::str::{split_html_space_chars, str_join};
 use style::stylesheet_set::DocumentStylesheetSet;
 use style::stylesheets::{Origin, OriginSet, Stylesheet};
+use style::values::computed::TouchAction;
 use url::Host;
 use uuid::Uuid;
 
@@ -178,8 +197,12 @@ const SPURIOUS_ANIMATION_FRAME_THRESHOLD: u8 = 5;
 /// The amount of time between fake `requestAnimationFrame()`s.
 const FAKE_REQUEST_ANIMATION_FRAME_DELAY: u64 = 16;
 
+/// The distance, in CSS pixels, the mouse has to move with the button pressed
+/// over a draggable element to start a drag-and-drop operation.
+const DRAG_DISTANCE_THRESHOLD: f32 = 5.;
+
 pub enum TouchEventResult {
-    Processed(bool),
+    Processed(EventResult),
     Forwarded,
 }
 
@@ -199,6 +222,19 @@ impl FireMouseEventType {
     }
 }
 
+/// The state of a pointer that is active in a document.
+///
+/// <https://w3c.github.io/pointerevents/#dfn-active-pointer>
+#[derive(JSTraceable, MallocSizeOf)]
+struct ActivePointer {
+    pointer_type: PointerType,
+    is_primary: bool,
+    /// The pressed buttons, as in `MouseEvent.buttons`.
+    buttons: u16,
+    /// The last position of the pointer, in client coordinates.
+    client_point: Point2D<f32>,
+}
+
 #[derive(Clone, Copy, Debug, JSTraceable, MallocSizeOf, PartialEq)]
 pub enum IsHTMLDocument {
     HTMLDocument,
@@ -315,6 +351,29 @@ pub struct Document {
     needs_paint: Cell<bool>,
     /// <http://w3c.github.io/touch-events/#dfn-active-touch-point>
     active_touch_points: DomRefCell<Vec<Dom<Touch>>>,
+    /// <https://w3c.github.io/pointerevents/#dfn-active-pointer>
+    active_pointers: DomRefCell<HashMap<i32, ActivePointer>>,
+    /// <https://w3c.github.io/pointerevents/#dfn-pointer-capture-target-override>
+    pointer_capture_targets: DomRefCell<HashMap<i32, Dom<Element>>>,
+    /// <https://w3c.github.io/pointerevents/#dfn-pending-pointer-capture-target-override>
+    pending_pointer_capture_targets: DomRefCell<HashMap<i32, Dom<Element>>>,
+    /// Whether the compatibility mouse events are not fired until all mouse
+    /// buttons are released, because `pointerdown` was canceled.
+    mouse_events_suppressed: Cell<bool>,
+    /// The draggable element the mouse button was pressed on, which becomes
+    /// the source node of a drag-and-drop operation if the mouse moves far
+    /// enough from `drag_candidate_point` before the button is released.
+    drag_candidate: MutNullableDom<Element>,
+    drag_candidate_point: Cell<Point2D<f32>>,
+    /// <https://html.spec.whatwg.org/multipage/#source-node>
+    drag_source: MutNullableDom<Element>,
+    /// <https://html.spec.whatwg.org/multipage/#immediate-user-selection>
+    drag_immediate_user_selection: MutNullableDom<Element>,
+    /// <https://html.spec.whatwg.org/multipage/#current-target-element>
+    drag_current_target: MutNullableDom<Element>,
+    /// <https://html.spec.whatwg.org/multipage/#current-drag-operation>
+    #[ignore_malloc_size_of = "Defined in embedder_traits"]
+    drag_operation: Cell<DragOperation>,
     /// Navigation Timing properties:
     /// <https://w3c.github.io/navigation-timing/#sec-PerformanceNavigationTiming>
     dom_loading: Cell<u64>,
@@ -398,6 +457,18 @@ pub struct Document {
     media_controls: DomRefCell<HashMap<String, Dom<ShadowRoot>>>,
     /// List of all WebGL context IDs that need flushing.
     dirty_webgl_contexts: DomRefCell<HashSet<WebGLContextId>>,
+    /// https://drafts.csswg.org/web-animations/#document-default-document-timeline
+    timeline: MutNullableDom<DocumentTimeline>,
+    /// Animations created by script that aren't idle.
+    script_animations: DomRefCell<Vec<Dom<Animation>>>,
+    /// Reflections of the CSS animations and transitions running in layout,
+    /// so that they keep their identity across calls to `getAnimations()`.
+    css_animations: DomRefCell<Vec<Dom<Animation>>>,
+    /// https://drafts.csswg.org/css-font-loading/#font-source
+    fonts: MutNullableDom<FontFaceSet>,
+    /// The names of the custom properties registered with
+    /// `CSS.registerProperty`, without the `--` prefix.
+    registered_custom_properties: DomRefCell<HashSet<Atom>>,
 }
 
 #[derive(JSTraceable, MallocSizeOf)]
@@ -827,6 +898,14 @@ impl Document {
             .map(DomRoot::upcast)
     }
 
+    /// Settles the font face of this document that the font cache just
+    /// finished loading, if any.
+    pub fn handle_web_font_loaded(&self, result: WebFontLoadResult) {
+        if let Some(fonts) = self.fonts.get() {
+            fonts.handle_web_font_loaded(result);
+        }
+    }
+
     // https://html.spec.whatwg.org/multipage/#current-document-readiness
     pub fn set_ready_state(&self, state: DocumentReadyState) {
         match state {
@@ -843,6 +922,12 @@ impl Document {
 
         self.upcast::<EventTarget>()
             .fire_event(atom!("readystatechange"));
+
+        if state == DocumentReadyState::Complete {
+            if let Some(fonts) = self.fonts.get() {
+                fonts.handle_document_loaded();
+            }
+        }
     }
 
     /// Return whether scripting is enabled or not
@@ -939,7 +1024,7 @@ impl Document {
     pub fn handle_mouse_event(
         &self,
         js_runtime: *mut JSRuntime,
-        _button: MouseButton,
+        button: MouseButton,
         client_point: Point2D<f32>,
         mouse_event_type: MouseEventType,
         node_address: Option<UntrustedNodeAddress>,
@@ -975,6 +1060,18 @@ impl Document {
             self.begin_focus_transaction();
         }
 
+        let mut suppress_mouse_event = false;
+        if let MouseEventType::MouseDown | MouseEventType::MouseUp = mouse_event_type {
+            if pref!(dom.pointer_events.enabled) {
+                suppress_mouse_event = self.dispatch_mouse_button_pointer_event(
+                    &el,
+                    client_point,
+                    button,
+                    pressed_mouse_buttons,
+                );
+            }
+        }
+
         // https://w3c.github.io/uievents/#event-type-click
         let client_x = client_point.x as i32;
         let client_y = client_point.y as i32;
@@ -1012,19 +1109,44 @@ impl Document {
                     a.enter_formal_activation_state();
                 }
 
-                let target = node.upcast();
-                event.fire(target);
+                if !suppress_mouse_event {
+                    let target = node.upcast();
+                    event.fire(target);
+                }
             },
             MouseEventType::MouseUp => {
                 if let Some(a) = activatable {
                     a.exit_formal_activation_state();
                 }
 
-                let target = node.upcast();
-                event.fire(target);
+                if !suppress_mouse_event {
+                    let target = node.upcast();
+                    event.fire(target);
+                }
             },
         }
 
+        // https://html.spec.whatwg.org/multipage/#drag-and-drop-processing-model
+        if pref!(dom.drag_and_drop.enabled) {
+            match mouse_event_type {
+                MouseEventType::MouseDown => {
+                    let candidate = match button {
+                        MouseButton::Left if !suppress_mouse_event && !event.DefaultPrevented() => {
+                            node.inclusive_ancestors(ShadowIncluding::No)
+                                .filter_map(DomRoot::downcast::<HTMLElement>)
+                                .find(|element| element.is_draggable())
+                        },
+                        _ => None,
+                    };
+                    self.drag_candidate
+                        .set(candidate.as_ref().map(|element| element.upcast()));
+                    self.drag_candidate_point.set(client_point);
+                },
+                MouseEventType::MouseUp => self.drag_candidate.set(None),
+                MouseEventType::Click => {},
+            }
+        }
+
         if let MouseEventType::Click = mouse_event_type {
             self.commit_focus_transaction(FocusType::Element);
             self.maybe_fire_dblclick(client_point, node, pressed_mouse_buttons);
@@ -1136,6 +1258,7 @@ impl Document {
         prev_mouse_over_target: &MutNullableDom<Element>,
         node_address: Option<UntrustedNodeAddress>,
         pressed_mouse_buttons: u16,
+        coalesced_client_points: &[Point2D<f32>],
     ) {
         let client_point = match client_point {
             None => {
@@ -1161,12 +1284,57 @@ impl Document {
             None => return,
         };
 
-        self.fire_mouse_event(
-            client_point,
-            new_target.upcast(),
-            FireMouseEventType::Move,
-            pressed_mouse_buttons,
-        );
+        if let Some(candidate) = self.drag_candidate.get() {
+            if pressed_mouse_buttons & 1 == 0 {
+                self.drag_candidate.set(None);
+            } else if (client_point - self.drag_candidate_point.get()).length() >=
+                DRAG_DISTANCE_THRESHOLD
+            {
+                self.drag_candidate.set(None);
+                if self.start_drag(&candidate, client_point) {
+                    return;
+                }
+            }
+        }
+
+        // Boundary pointer events are only fired for the capture target while
+        // the mouse is captured.
+        let mut fire_boundary_pointer_events = false;
+        if pref!(dom.pointer_events.enabled) {
+            self.update_active_pointer(
+                MOUSE_POINTER_ID,
+                PointerType::Mouse,
+                true,
+                client_point,
+                pressed_mouse_buttons,
+            );
+            let coalesced_events: Vec<_> = coalesced_client_points
+                .iter()
+                .chain(Some(&client_point))
+                .filter_map(|point| {
+                    self.new_pointer_event("pointermove", MOUSE_POINTER_ID, *point, -1, None, &[])
+                })
+                .collect();
+            let target = self.pointer_event_target(MOUSE_POINTER_ID, new_target);
+            fire_boundary_pointer_events = &target == new_target;
+            self.fire_pointer_event(
+                "pointermove",
+                target.upcast(),
+                MOUSE_POINTER_ID,
+                -1,
+                None,
+                &coalesced_events,
+            );
+        }
+
+        if !self.mouse_events_suppressed.get() {
+            self.fire_mouse_event(
+                client_point,
+                new_target.upcast(),
+                FireMouseEventType::Move,
+                pressed_mouse_buttons,
+            );
+        }
 
         // Nothing more to do here, mousemove is sent,
         // and the element under the mouse hasn't changed.
@@ -1198,6 +1366,27 @@ impl Document {
                 }
             }
 
+            if fire_boundary_pointer_events {
+                self.fire_pointer_event(
+                    "pointerout",
+                    old_target.upcast(),
+                    MOUSE_POINTER_ID,
+                    -1,
+                    maybe_new_target.as_ref().map(|target| target.upcast()),
+                    &[],
+                );
+                for element in pointer_boundary_elements(&old_target, maybe_new_target.as_deref()) {
+                    self.fire_pointer_event(
+                        "pointerleave",
+                        element.upcast(),
+                        MOUSE_POINTER_ID,
+                        -1,
+                        maybe_new_target.as_ref().map(|target| target.upcast()),
+                        &[],
+                    );
+                }
+            }
+
             // Remove hover state to old target and its parents
             self.fire_mouse_event(
                 client_point,
@@ -1223,6 +1412,31 @@ impl Document {
                 element.set_hover_state(true);
             }
 
+            if fire_boundary_pointer_events {
+                let old_target = prev_mouse_over_target.get();
+                self.fire_pointer_event(
+                    "pointerover",
+                    new_target.upcast(),
+                    MOUSE_POINTER_ID,
+                    -1,
+                    old_target.as_ref().map(|target| target.upcast()),
+                    &[],
+                );
+                for element in pointer_boundary_elements(new_target, old_target.as_deref())
+                    .iter()
+                    .rev()
+                {
+                    self.fire_pointer_event(
+                        "pointerenter",
+                        element.upcast(),
+                        MOUSE_POINTER_ID,
+                        -1,
+                        old_target.as_ref().map(|target| target.upcast()),
+                        &[],
+                    );
+                }
+            }
+
             self.fire_mouse_event(
                 client_point,
                 &new_target.upcast(),
@@ -1316,6 +1530,19 @@ impl Document {
             None => return TouchEventResult::Forwarded,
         };
 
+        if pref!(dom.pointer_events.enabled) {
+            self.dispatch_touch_pointer_events(
+                &el,
+                event_type,
+                touch_pointer_id(identifier),
+                point,
+            );
+        }
+        let touch_behavior = match event_type {
+            TouchEventType::Down => self.allowed_touch_behavior(&el),
+            _ => TouchBehavior::auto(),
+        };
+
         let target = DomRoot::upcast::<EventTarget>(el);
         let window = &*self.window;
 
@@ -1392,9 +1619,821 @@ impl Document {
         window.reflow(ReflowGoal::Full, ReflowReason::MouseEvent);
 
         match result {
-            EventStatus::Canceled => TouchEventResult::Processed(false),
-            EventStatus::NotCanceled => TouchEventResult::Processed(true),
+            EventStatus::Canceled => TouchEventResult::Processed(EventResult::DefaultPrevented),
+            EventStatus::NotCanceled => {
+                TouchEventResult::Processed(EventResult::DefaultAllowed(touch_behavior))
+            },
+        }
+    }
+
+    /// The default touch behaviors allowed by the `touch-action` of an element
+    /// and of its ancestors.
+    ///
+    /// <https://w3c.github.io/pointerevents/#determining-supported-touch-behavior>
+    fn allowed_touch_behavior(&self, target: &Element) -> TouchBehavior {
+        let mut behavior = TouchBehavior::auto();
+        for element in target
+            .upcast::<Node>()
+            .inclusive_ancestors(ShadowIncluding::No)
+            .filter_map(DomRoot::downcast::<Element>)
+        {
+            let touch_action = match element.style() {
+                Some(style) => style.get_box().clone_touch_action(),
+                None => continue,
+            };
+            if touch_action.intersects(TouchAction::AUTO | TouchAction::MANIPULATION) {
+                continue;
+            }
+            behavior.pan_x &= touch_action.contains(TouchAction::PAN_X);
+            behavior.pan_y &= touch_action.contains(TouchAction::PAN_Y);
+            behavior.pinch_zoom = false;
+        }
+        behavior
+    }
+
+    /// Updates the state of an active pointer, adding it if needed, and
+    /// returns the buttons that were pressed before.
+    fn update_active_pointer(
+        &self,
+        pointer_id: i32,
+        pointer_type: PointerType,
+        is_primary: bool,
+        client_point: Point2D<f32>,
+        buttons: u16,
+    ) -> u16 {
+        let mut active_pointers = self.active_pointers.borrow_mut();
+        let pointer = active_pointers
+            .entry(pointer_id)
+            .or_insert_with(|| ActivePointer {
+                pointer_type,
+                is_primary,
+                buttons: 0,
+                client_point,
+            });
+        pointer.client_point = client_point;
+        mem::replace(&mut pointer.buttons, buttons)
+    }
+
+    /// Creates a trusted pointer event for an active pointer.
+    fn new_pointer_event(
+        &self,
+        event_type: &str,
+        pointer_id: i32,
+        client_point: Point2D<f32>,
+        button: i16,
+        related_target: Option<&EventTarget>,
+        coalesced_events: &[DomRoot<PointerEvent>],
+    ) -> Option<DomRoot<PointerEvent>> {
+        let (properties, buttons) = {
+            let active_pointers = self.active_pointers.borrow();
+            let pointer = active_pointers.get(&pointer_id)?;
+            (
+                PointerProperties::new(pointer_id, pointer.pointer_type, pointer.is_primary),
+                pointer.buttons,
+            )
+        };
+        let (bubbles, cancelable) = match event_type {
+            "pointerenter" | "pointerleave" => {
+                (EventBubbles::DoesNotBubble, EventCancelable::NotCancelable)
+            },
+            "pointercancel" | "gotpointercapture" | "lostpointercapture" => {
+                (EventBubbles::Bubbles, EventCancelable::NotCancelable)
+            },
+            _ => (EventBubbles::Bubbles, EventCancelable::Cancelable),
+        };
+        let client_x = client_point.x.to_i32().unwrap_or(0);
+        let client_y = client_point.y.to_i32().unwrap_or(0);
+        let event = PointerEvent::new(
+            &self.window,
+            DOMString::from(event_type),
+            bubbles,
+            cancelable,
+            Some(&self.window),
+            0,
+            client_x,
+            client_y,
+            client_x,
+            client_y, // TODO: Get real screen coordinates?
+            false,
+            false,
+            false,
+            false,
+            button,
+            buttons,
+            related_target,
+            properties,
+            coalesced_events,
+            &[],
+        );
+        event.upcast::<Event>().set_trusted(true);
+        Some(event)
+    }
+
+    /// Fires a trusted pointer event for an active pointer at its last
+    /// position.
+    fn fire_pointer_event(
+        &self,
+        event_type: &str,
+        target: &EventTarget,
+        pointer_id: i32,
+        button: i16,
+        related_target: Option<&EventTarget>,
+        coalesced_events: &[DomRoot<PointerEvent>],
+    ) -> EventStatus {
+        let client_point = match self.active_pointers.borrow().get(&pointer_id) {
+            Some(pointer) => pointer.client_point,
+            None => return EventStatus::NotCanceled,
+        };
+        match self.new_pointer_event(
+            event_type,
+            pointer_id,
+            client_point,
+            button,
+            related_target,
+            coalesced_events,
+        ) {
+            Some(event) => event.upcast::<Event>().fire(target),
+            None => EventStatus::NotCanceled,
+        }
+    }
+
+    /// Processes the pending pointer capture of a pointer, and returns the
+    /// element the next pointer event of that pointer is targeted at.
+    fn pointer_event_target(&self, pointer_id: i32, hit_target: &Element) -> DomRoot<Element> {
+        self.process_pending_pointer_capture(pointer_id);
+        self.pointer_capture_targets
+            .borrow()
+            .get(&pointer_id)
+            .map(|target| DomRoot::from_ref(&**target))
+            .unwrap_or_else(|| DomRoot::from_ref(hit_target))
+    }
+
+    /// Fires the pointer event for a mouse button press or release, and
+    /// returns whether the compatibility mouse event must not be fired.
+    ///
+    /// <https://w3c.github.io/pointerevents/#chorded-button-interactions>
+    fn dispatch_mouse_button_pointer_event(
+        &self,
+        hit_target: &Element,
+        client_point: Point2D<f32>,
+        button: MouseButton,
+        pressed_mouse_buttons: u16,
+    ) -> bool {
+        let previous_buttons = self.update_active_pointer(
+            MOUSE_POINTER_ID,
+            PointerType::Mouse,
+            true,
+            client_point,
+            pressed_mouse_buttons,
+        );
+        let event_type = match (previous_buttons, pressed_mouse_buttons) {
+            (0, 0) => return self.mouse_events_suppressed.get(),
+            (0, _) => "pointerdown",
+            (_, 0) => "pointerup",
+            _ => "pointermove",
+        };
+        let button = match button {
+            MouseButton::Left => 0,
+            MouseButton::Middle => 1,
+            MouseButton::Right => 2,
+        };
+
+        let target = self.pointer_event_target(MOUSE_POINTER_ID, hit_target);
+        let status = self.fire_pointer_event(
+            event_type,
+            target.upcast(),
+            MOUSE_POINTER_ID,
+            button,
+            None,
+            &[],
+        );
+
+        // https://w3c.github.io/pointerevents/#compatibility-mapping-with-mouse-events
+        if event_type == "pointerdown" && status == EventStatus::Canceled {
+            self.mouse_events_suppressed.set(true);
+        }
+        let suppressed = self.mouse_events_suppressed.get();
+        if event_type == "pointerup" {
+            self.implicitly_release_pointer_capture(MOUSE_POINTER_ID);
+            self.mouse_events_suppressed.set(false);
+        }
+        suppressed
+    }
+
+    /// Fires the pointer events for a touch point, which is implicitly
+    /// captured by the element it touched first.
+    fn dispatch_touch_pointer_events(
+        &self,
+        hit_target: &Element,
+        event_type: TouchEventType,
+        pointer_id: i32,
+        client_point: Point2D<f32>,
+    ) {
+        if let TouchEventType::Down = event_type {
+            let is_primary = !self
+                .active_pointers
+                .borrow()
+                .values()
+                .any(|pointer| pointer.pointer_type == PointerType::Touch);
+            self.update_active_pointer(pointer_id, PointerType::Touch, is_primary, client_point, 1);
+            self.fire_pointer_event(
+                "pointerover",
+                hit_target.upcast(),
+                pointer_id,
+                -1,
+                None,
+                &[],
+            );
+            for element in pointer_boundary_elements(hit_target, None).iter().rev() {
+                self.fire_pointer_event(
+                    "pointerenter",
+                    element.upcast(),
+                    pointer_id,
+                    -1,
+                    None,
+                    &[],
+                );
+            }
+            let target = self.pointer_event_target(pointer_id, hit_target);
+            self.fire_pointer_event("pointerdown", target.upcast(), pointer_id, 0, None, &[]);
+
+            // https://w3c.github.io/pointerevents/#implicit-pointer-capture
+            self.pending_pointer_capture_targets
+                .borrow_mut()
+                .insert(pointer_id, Dom::from_ref(hit_target));
+            return;
+        }
+
+        if !self.active_pointers.borrow().contains_key(&pointer_id) {
+            return;
+        }
+        let (event_type, button, buttons) = match event_type {
+            TouchEventType::Move => ("pointermove", -1, 1),
+            TouchEventType::Up => ("pointerup", 0, 0),
+            TouchEventType::Cancel | TouchEventType::Down => ("pointercancel", -1, 0),
+        };
+        self.update_active_pointer(pointer_id, PointerType::Touch, false, client_point, buttons);
+        let target = self.pointer_event_target(pointer_id, hit_target);
+        self.fire_pointer_event(event_type, target.upcast(), pointer_id, button, None, &[]);
+        if buttons != 0 {
+            return;
+        }
+
+        // The touch point is gone, so it leaves the element it was over.
+        self.implicitly_release_pointer_capture(pointer_id);
+        self.fire_pointer_event("pointerout", target.upcast(), pointer_id, -1, None, &[]);
+        for element in pointer_boundary_elements(&target, None) {
+            self.fire_pointer_event("pointerleave", element.upcast(), pointer_id, -1, None, &[]);
+        }
+        self.active_pointers.borrow_mut().remove(&pointer_id);
+    }
+
+    /// <https://w3c.github.io/pointerevents/#process-pending-pointer-capture>
+    fn process_pending_pointer_capture(&self, pointer_id: i32) {
+        let current_target = self
+            .pointer_capture_targets
+            .borrow()
+            .get(&pointer_id)
+            .map(|target| DomRoot::from_ref(&**target));
+        let pending_target = self
+            .pending_pointer_capture_targets
+            .borrow()
+            .get(&pointer_id)
+            .map(|target| DomRoot::from_ref(&**target));
+
+        // Step 1.
+        if current_target == pending_target {
+            return;
+        }
+
+        // Step 2.
+        if let Some(current_target) = current_target {
+            self.pointer_capture_targets
+                .borrow_mut()
+                .remove(&pointer_id);
+            let target = if current_target.upcast::<Node>().is_connected() {
+                DomRoot::upcast::<EventTarget>(current_target)
+            } else {
+                DomRoot::from_ref(self.upcast::<EventTarget>())
+            };
+            self.fire_pointer_event("lostpointercapture", &target, pointer_id, -1, None, &[]);
+        }
+
+        // Step 3.
+        if let Some(pending_target) = pending_target {
+            self.pointer_capture_targets
+                .borrow_mut()
+                .insert(pointer_id, Dom::from_ref(&*pending_target));
+            self.fire_pointer_event(
+                "gotpointercapture",
+                pending_target.upcast(),
+                pointer_id,
+                -1,
+                None,
+                &[],
+            );
+        }
+    }
+
+    /// <https://w3c.github.io/pointerevents/#implicit-release-of-pointer-capture>
+    fn implicitly_release_pointer_capture(&self, pointer_id: i32) {
+        self.pending_pointer_capture_targets
+            .borrow_mut()
+            .remove(&pointer_id);
+        self.process_pending_pointer_capture(pointer_id);
+    }
+
+    /// <https://w3c.github.io/pointerevents/#setting-pointer-capture>
+    pub fn set_pointer_capture(&self, element: &Element, pointer_id: i32) -> ErrorResult {
+        // Step 1.
+        let buttons = match self.active_pointers.borrow().get(&pointer_id) {
+            Some(pointer) => pointer.buttons,
+            None => return Err(Error::NotFound),
+        };
+        // Step 2.
+        if !element.upcast::<Node>().is_connected() {
+            return Err(Error::InvalidState);
+        }
+        // Step 5.
+        if buttons != 0 {
+            self.pending_pointer_capture_targets
+                .borrow_mut()
+                .insert(pointer_id, Dom::from_ref(element));
+        }
+        Ok(())
+    }
+
+    /// <https://w3c.github.io/pointerevents/#releasing-pointer-capture>
+    pub fn release_pointer_capture(&self, element: &Element, pointer_id: i32) -> ErrorResult {
+        // Step 1.
+        if !self.active_pointers.borrow().contains_key(&pointer_id) {
+            return Err(Error::NotFound);
+        }
+        // Steps 2 and 3.
+        if self.has_pointer_capture(element, pointer_id) {
+            self.pending_pointer_capture_targets
+                .borrow_mut()
+                .remove(&pointer_id);
+        }
+        Ok(())
+    }
+
+    /// <https://w3c.github.io/pointerevents/#dom-element-haspointercapture>
+    pub fn has_pointer_capture(&self, element: &Element, pointer_id: i32) -> bool {
+        self.pending_pointer_capture_targets
+            .borrow()
+            .get(&pointer_id)
+            .map_or(false, |target| &**target == element)
+    }
+
+    /// Starts a drag-and-drop operation from the given source node, and
+    /// returns whether `dragstart` didn't cancel it.
+    ///
+    /// <https://html.spec.whatwg.org/multipage/#drag-and-drop-processing-model>
+    fn start_drag(&self, source: &Element, client_point: Point2D<f32>) -> bool {
+        // Step 2.
+        let data_transfer = DataTransfer::new(
+            &self.window,
+            DataTransferMode::ReadWrite,
+            DOMString::from("uninitialized"),
+        );
+
+        // Step 3, dragging a link or an image drags its URL.
+        let url = match source.downcast::<HTMLImageElement>() {
+            Some(image) => image.get_url(),
+            None if source.is::<HTMLAnchorElement>() => self
+                .base_url()
+                .join(&source.get_string_attribute(&local_name!("href")))
+                .ok(),
+            None => None,
+        };
+        if let Some(url) = url {
+            let url = DOMString::from(url.into_string());
+            data_transfer.SetData(DOMString::from("text/uri-list"), url.clone());
+            data_transfer.SetData(DOMString::from("text/plain"), url);
+        }
+
+        // Step 9.
+        let status = self.fire_drag_event(
+            "dragstart",
+            source.upcast(),
+            &data_transfer,
+            Some(client_point),
+            None,
+        );
+        if status == EventStatus::Canceled {
+            data_transfer.disassociate();
+            return false;
+        }
+
+        // https://w3c.github.io/pointerevents/#the-pointercancel-event
+        if pref!(dom.pointer_events.enabled) {
+            let target = self.pointer_event_target(MOUSE_POINTER_ID, source);
+            self.fire_pointer_event(
+                "pointercancel",
+                target.upcast(),
+                MOUSE_POINTER_ID,
+                -1,
+                None,
+                &[],
+            );
+            self.implicitly_release_pointer_capture(MOUSE_POINTER_ID);
+            self.active_pointers.borrow_mut().remove(&MOUSE_POINTER_ID);
+            self.mouse_events_suppressed.set(false);
+        }
+
+        // Step 10.
+        let image = self.drag_image(source, &data_transfer, client_point);
+        let data = data_transfer.drag_data();
+        data_transfer.disassociate();
+        self.drag_source.set(Some(source));
+        let msg = ScriptMsg::StartDrag(data, image);
+        let _ = self
+            .window
+            .upcast::<GlobalScope>()
+            .script_to_constellation_chan()
+            .send(msg);
+        true
+    }
+
+    /// The image drawn under the cursor during a drag-and-drop operation,
+    /// which is the image set with `setDragImage` or the dragged image.
+    ///
+    /// TODO: Render other elements into drag images.
+    fn drag_image(
+        &self,
+        source: &Element,
+        data_transfer: &DataTransfer,
+        client_point: Point2D<f32>,
+    ) -> Option<DragImage> {
+        let (element, hot_spot, size) = match data_transfer.drag_image() {
+            Some((element, x, y)) => (element, Vector2D::new(x as f32, y as f32), None),
+            None => {
+                let rect = source.upcast::<Node>().bounding_content_box_or_zero();
+                let hot_spot = Vector2D::new(
+                    client_point.x - rect.origin.x.to_f32_px(),
+                    client_point.y - rect.origin.y.to_f32_px(),
+                );
+                let size = Size2D::new(rect.size.width.to_f32_px(), rect.size.height.to_f32_px());
+                (DomRoot::from_ref(source), hot_spot, Some(size))
+            },
+        };
+        let image = element.downcast::<HTMLImageElement>()?.current_image()?;
+        Some(DragImage {
+            key: image.id?,
+            size: size.unwrap_or_else(|| Size2D::new(image.width as f32, image.height as f32)),
+            hot_spot,
+        })
+    }
+
+    /// Fires a drag-and-drop event, whose `dataTransfer` is associated with
+    /// the drag data store.
+    ///
+    /// <https://html.spec.whatwg.org/multipage/#fire-a-dnd-event>
+    fn fire_drag_event(
+        &self,
+        event_type: &str,
+        target: &EventTarget,
+        data_transfer: &DataTransfer,
+        client_point: Option<Point2D<f32>>,
+        related_target: Option<&EventTarget>,
+    ) -> EventStatus {
+        let cancelable = match event_type {
+            "dragleave" | "dragend" => EventCancelable::NotCancelable,
+            _ => EventCancelable::Cancelable,
+        };
+        let client_point = client_point.unwrap_or_else(Point2D::zero);
+        let client_x = client_point.x.to_i32().unwrap_or(0);
+        let client_y = client_point.y.to_i32().unwrap_or(0);
+        let event = DragEvent::new(
+            &self.window,
+            DOMString::from(event_type),
+            EventBubbles::Bubbles,
+            cancelable,
+            Some(&self.window),
+            0i32,
+            client_x,
+            client_y,
+            client_x,
+            client_y,
+            false,
+            false,
+            false,
+            false,
+            0i16,
+            0,
+            related_target,
+            Some(data_transfer),
+        );
+        event.upcast::<Event>().fire(target)
+    }
+
+    /// Whether the element accepts dropped files without script canceling
+    /// `dragover`.
+    fn accepts_dropped_files(&self, element: &Element) -> bool {
+        element
+            .downcast::<HTMLInputElement>()
+            .map_or(false, |input| {
+                input.input_type() == InputType::File && !element.disabled_state()
+            })
+    }
+
+    /// Processes a drag-and-drop event sent by the compositor.
+    ///
+    /// <https://html.spec.whatwg.org/multipage/#drag-and-drop-processing-model>
+    #[allow(unsafe_code)]
+    pub fn handle_drag_event(
+        &self,
+        js_runtime: *mut JSRuntime,
+        event_type: DragEventType,
+        client_point: Option<Point2D<f32>>,
+        node_address: Option<UntrustedNodeAddress>,
+        data: DragData,
+    ) {
+        if !pref!(dom.drag_and_drop.enabled) {
+            return;
+        }
+
+        let selection = node_address.and_then(|address| {
+            let node = unsafe { node::from_untrusted_node_address(js_runtime, address) };
+            node.inclusive_ancestors(ShadowIncluding::No)
+                .filter_map(DomRoot::downcast::<Element>)
+                .next()
+        });
+
+        match event_type {
+            DragEventType::Drag => {
+                if let Some(source) = self.drag_source.get() {
+                    let data_transfer = DataTransfer::new_from_drag_data(
+                        &self.window,
+                        &data,
+                        DataTransferMode::Protected,
+                    );
+                    self.fire_drag_event(
+                        "drag",
+                        source.upcast(),
+                        &data_transfer,
+                        client_point,
+                        None,
+                    );
+                    data_transfer.disassociate();
+                }
+            },
+            DragEventType::Over => self.handle_drag_over(selection.as_deref(), client_point, &data),
+            DragEventType::Leave => {
+                self.drag_immediate_user_selection.set(None);
+                self.drag_operation.set(DragOperation::None);
+                if let Some(current_target) = self.drag_current_target.take() {
+                    let data_transfer = DataTransfer::new_from_drag_data(
+                        &self.window,
+                        &data,
+                        DataTransferMode::Protected,
+                    );
+                    self.fire_drag_event(
+                        "dragleave",
+                        current_target.upcast(),
+                        &data_transfer,
+                        client_point,
+                        None,
+                    );
+                    data_transfer.disassociate();
+                }
+            },
+            DragEventType::Drop => self.handle_drop(client_point, &data),
+            DragEventType::End(operation) => {
+                if let Some(source) = self.drag_source.take() {
+                    let data_transfer = DataTransfer::new_from_drag_data(
+                        &self.window,
+                        &data,
+                        DataTransferMode::Protected,
+                    );
+                    data_transfer.set_drop_effect(operation);
+                    self.fire_drag_event(
+                        "dragend",
+                        source.upcast(),
+                        &data_transfer,
+                        client_point,
+                        None,
+                    );
+                    data_transfer.disassociate();
+                }
+            },
+        }
+
+        self.window
+            .reflow(ReflowGoal::Full, ReflowReason::MouseEvent);
+    }
+
+    /// Fires the events of a drag moving over the given element, and tells
+    /// the compositor what dropping on it would do.
+    ///
+    /// <https://html.spec.whatwg.org/multipage/#drag-and-drop-processing-model>
+    fn handle_drag_over(
+        &self,
+        selection: Option<&Element>,
+        client_point: Option<Point2D<f32>>,
+        data: &DragData,
+    ) {
+        // Step 4.
+        if self.drag_immediate_user_selection.get().as_deref() != selection {
+            self.drag_immediate_user_selection.set(selection);
+            let previous_target = self.drag_current_target.get();
+            let new_target = match selection {
+                None => None,
+                Some(selection) => {
+                    let data_transfer = DataTransfer::new_from_drag_data(
+                        &self.window,
+                        data,
+                        DataTransferMode::Protected,
+                    );
+                    data_transfer.initialize_drop_effect();
+                    let status = self.fire_drag_event(
+                        "dragenter",
+                        selection.upcast(),
+                        &data_transfer,
+                        client_point,
+                        None,
+                    );
+                    let new_target = if status == EventStatus::Canceled ||
+                        self.accepts_dropped_files(selection)
+                    {
+                        Some(DomRoot::from_ref(selection))
+                    } else {
+                        match self.GetBody() {
+                            Some(ref body) if previous_target.as_deref() == Some(body.upcast()) => {
+                                previous_target.clone()
+                            },
+                            Some(body) => {
+                                self.fire_drag_event(
+                                    "dragenter",
+                                    body.upcast(),
+                                    &data_transfer,
+                                    client_point,
+                                    None,
+                                );
+                                Some(DomRoot::upcast(body))
+                            },
+                            None => None,
+                        }
+                    };
+                    data_transfer.disassociate();
+                    new_target
+                },
+            };
+
+            // Step 5.
+            if previous_target != new_target {
+                if let Some(ref previous_target) = previous_target {
+                    let data_transfer = DataTransfer::new_from_drag_data(
+                        &self.window,
+                        data,
+                        DataTransferMode::Protected,
+                    );
+                    self.fire_drag_event(
+                        "dragleave",
+                        previous_target.upcast(),
+                        &data_transfer,
+                        client_point,
+                        new_target.as_ref().map(|target| target.upcast()),
+                    );
+                    data_transfer.disassociate();
+                }
+            }
+            self.drag_current_target.set(new_target.as_deref());
+        }
+
+        // Step 6.
+        let operation = match self.drag_current_target.get() {
+            Some(current_target) => {
+                let data_transfer = DataTransfer::new_from_drag_data(
+                    &self.window,
+                    data,
+                    DataTransferMode::Protected,
+                );
+                data_transfer.initialize_drop_effect();
+                let status = self.fire_drag_event(
+                    "dragover",
+                    current_target.upcast(),
+                    &data_transfer,
+                    client_point,
+                    None,
+                );
+                let operation = if status == EventStatus::Canceled {
+                    data_transfer.drag_operation()
+                } else if self.accepts_dropped_files(&current_target) &&
+                    !data.files.is_empty() &&
+                    data_transfer.allows(DragOperation::Copy)
+                {
+                    DragOperation::Copy
+                } else {
+                    DragOperation::None
+                };
+                data_transfer.disassociate();
+                operation
+            },
+            None => DragOperation::None,
+        };
+        self.drag_operation.set(operation);
+
+        let msg = ScriptMsg::DragOverProcessed(operation);
+        let _ = self
+            .window
+            .upcast::<GlobalScope>()
+            .script_to_constellation_chan()
+            .send(msg);
+    }
+
+    /// Fires `drop` at the current target element, whose default action
+    /// selects the dropped files of file upload controls.
+    ///
+    /// <https://html.spec.whatwg.org/multipage/#drag-and-drop-processing-model>
+    fn handle_drop(&self, client_point: Option<Point2D<f32>>, data: &DragData) {
+        self.drag_immediate_user_selection.set(None);
+        let operation = self.drag_operation.replace(DragOperation::None);
+        let current_target = match self.drag_current_target.take() {
+            Some(current_target) => current_target,
+            None => return,
+        };
+
+        let data_transfer =
+            DataTransfer::new_from_drag_data(&self.window, data, DataTransferMode::ReadOnly);
+        data_transfer.set_drop_effect(operation);
+        let status = self.fire_drag_event(
+            "drop",
+            current_target.upcast(),
+            &data_transfer,
+            client_point,
+            None,
+        );
+        if status == EventStatus::NotCanceled {
+            if let Some(input) = current_target.downcast::<HTMLInputElement>() {
+                let files = data_transfer
+                    .items()
+                    .iter()
+                    .filter_map(|item| item.file())
+                    .collect();
+                input.drop_files(files);
+            }
+        }
+        data_transfer.disassociate();
+    }
+
+    /// Fires the event of a clipboard action the user triggered, and returns
+    /// whether the default action of the clipboard event should run.
+    ///
+    /// <https://w3c.github.io/clipboard-apis/#fire-a-clipboard-event>
+    pub fn fire_clipboard_event(&self, action: ClipboardEventType) -> bool {
+        if !pref!(dom.clipboard.enabled) {
+            return true;
+        }
+
+        // Steps 1-2 and 4-5.
+        let mut clipboard = self
+            .window
+            .upcast::<GlobalScope>()
+            .script_to_constellation_chan()
+            .clone();
+        let clipboard_data = match action {
+            ClipboardEventType::Copy | ClipboardEventType::Cut => DataTransfer::new(
+                &self.window,
+                DataTransferMode::ReadWrite,
+                DOMString::from("none"),
+            ),
+            ClipboardEventType::Paste => DataTransfer::new_from_clipboard_data(
+                &self.window,
+                clipboard.clipboard_data(),
+                DataTransferMode::ReadOnly,
+            ),
+        };
+
+        // Step 3.
+        let target = match (self.get_focused_element(), self.GetBody()) {
+            (Some(focused), _) => DomRoot::upcast::<EventTarget>(focused),
+            (None, Some(body)) => DomRoot::upcast::<EventTarget>(body),
+            (None, None) => DomRoot::from_ref(self.upcast::<EventTarget>()),
+        };
+
+        // Steps 6-7.
+        let event = ClipboardEvent::new(
+            &self.window,
+            Atom::from(action.as_str()),
+            EventBubbles::Bubbles,
+            EventCancelable::Cancelable,
+            Some(&clipboard_data),
+        );
+        let status = event.upcast::<Event>().fire(&target);
+
+        // https://w3c.github.io/clipboard-apis/#copy-action Step 2.1
+        // https://w3c.github.io/clipboard-apis/#cut-action Step 2.1
+        if status == EventStatus::Canceled && action != ClipboardEventType::Paste {
+            let data = clipboard_data.clipboard_data();
+            if !data.representations.is_empty() {
+                clipboard.set_clipboard_data(data);
+            }
         }
+        clipboard_data.disassociate();
+
+        status == EventStatus::NotCanceled
     }
 
     /// The entry point for all key processing for web content
@@ -1428,6 +2467,18 @@ impl Document {
         event.fire(target);
         let mut cancel_state = event.get_cancel_state();
 
+        // Text controls fire the clipboard events of the shortcuts they handle
+        // themselves, and mark the key event as handled.
+        let clipboard_action = if cancel_state == EventDefault::Allowed {
+            ClipboardEventType::from_shortcut(
+                keyboard_event.state,
+                keyboard_event.key.clone(),
+                keyboard_event.modifiers,
+            )
+        } else {
+            None
+        };
+
         // https://w3c.github.io/uievents/#keys-cancelable-keys
         if keyboard_event.state == KeyState::Down &&
             keyboard_event.key.legacy_charcode() != 0 &&
@@ -1456,6 +2507,10 @@ impl Document {
         }
 
         if cancel_state == EventDefault::Allowed {
+            if let Some(action) = clipboard_action {
+                self.fire_clipboard_event(action);
+            }
+
             let msg = EmbedderMsg::Keyboard(keyboard_event.clone());
             self.send_to_embedder(msg);
 
@@ -1479,7 +2534,7 @@ impl Document {
                             ActivationSource::NotFromClick,
                         )
                     }
-                }
+                },
                 Key::Enter if keyboard_event.state == KeyState::Up => {
                     let maybe_elem = target.downcast::<Element>();
                     if let Some(el) = maybe_elem {
@@ -1610,6 +2665,12 @@ impl Document {
         }
     }
 
+    /// Records a custom property registered with `CSS.registerProperty`.
+    /// Returns false if a property with the same name was already registered.
+    pub fn add_registered_custom_property(&self, name: Atom) -> bool {
+        self.registered_custom_properties.borrow_mut().insert(name)
+    }
+
     /// <https://html.spec.whatwg.org/multipage/#dom-window-requestanimationframe>
     pub fn request_animation_frame(&self, callback: AnimationFrameCallback) -> u32 {
         let ident = self.animation_frame_ident.get() + 1;
@@ -2494,6 +3555,124 @@ impl Document {
         }
     }
 
+    pub fn add_script_animation(&self, animation: &Animation) {
+        let mut animations = self.script_animations.borrow_mut();
+        if !animations.iter().any(|other| &**other == animation) {
+            animations.push(Dom::from_ref(animation));
+        }
+    }
+
+    pub fn remove_script_animation(&self, animation: &Animation) {
+        self.script_animations
+            .borrow_mut()
+            .retain(|other| &**other != animation);
+    }
+
+    /// Returns the relevant animations targeting the given element, and its
+    /// descendants if `subtree` is true, or the whole document if no element
+    /// is given. CSS transitions come first, then CSS animations, then the
+    /// animations created by script.
+    ///
+    /// <https://drafts.csswg.org/web-animations/#dom-animatable-getanimations>
+    #[allow(unsafe_code)]
+    pub fn get_animations(&self, root: Option<&Element>, subtree: bool) -> Vec<DomRoot<Animation>> {
+        // Make sure layout has started the animations of the latest styles.
+        self.window.reflow(ReflowGoal::Full, ReflowReason::Query);
+
+        let layout_node = match root {
+            Some(root) if !subtree => Some(root.upcast::<Node>().to_opaque()),
+            _ => None,
+        };
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let infos = match self
+            .window
+            .layout_chan()
+            .send(Msg::GetAnimations(layout_node, sender))
+        {
+            Ok(()) => receiver.recv().unwrap_or_default(),
+            Err(_) => vec![],
+        };
+
+        let js_runtime = unsafe { JS_GetRuntime(*self.window.get_cx()) };
+        let mut transitions = vec![];
+        let mut animations = vec![];
+        for info in &infos {
+            let origin = match info.kind {
+                RunningAnimationKind::Transition => AnimationOrigin::CssTransition,
+                RunningAnimationKind::CssAnimation => AnimationOrigin::CssAnimation,
+                RunningAnimationKind::ScriptAnimation => continue,
+            };
+            let node = unsafe { node::from_untrusted_node_address(js_runtime, info.node) };
+            let target = match DomRoot::downcast::<Element>(node) {
+                Some(target) => target,
+                None => continue,
+            };
+            if !is_animation_target_included(&target, root, subtree) {
+                continue;
+            }
+
+            let existing = self
+                .css_animations
+                .borrow()
+                .iter()
+                .find(|animation| {
+                    animation.origin() == origin &&
+                        *animation.name() == info.name &&
+                        animation.target().as_ref() == Some(&target)
+                })
+                .map(|animation| DomRoot::from_ref(&**animation));
+            let animation = match existing {
+                Some(animation) => {
+                    animation.reflect_layout_state(info);
+                    animation
+                },
+                None => {
+                    let animation = match origin {
+                        AnimationOrigin::CssTransition => {
+                            DomRoot::upcast(CSSTransition::new(&self.window, &target, info))
+                        },
+                        _ => DomRoot::upcast(CSSAnimation::new(&self.window, &target, info)),
+                    };
+                    self.css_animations
+                        .borrow_mut()
+                        .push(Dom::from_ref(&*animation));
+                    animation
+                },
+            };
+            match origin {
+                AnimationOrigin::CssTransition => transitions.push(animation),
+                _ => animations.push(animation),
+            }
+        }
+
+        // Forget about the CSS animations that stopped running in layout.
+        if root.is_none() {
+            self.css_animations.borrow_mut().retain(|animation| {
+                transitions
+                    .iter()
+                    .chain(animations.iter())
+                    .any(|running| **running == **animation)
+            });
+        }
+
+        let script_animations = self
+            .script_animations
+            .borrow()
+            .iter()
+            .filter(|animation| {
+                animation.is_relevant() &&
+                    animation.target().map_or(false, |target| {
+                        is_animation_target_included(&target, root, subtree)
+                    })
+            })
+            .map(|animation| DomRoot::from_ref(&**animation))
+            .collect::<Vec<_>>();
+
+        transitions.extend(animations);
+        transitions.extend(script_animations);
+        transitions
+    }
+
     pub fn add_dirty_canvas(&self, context_id: WebGLContextId) {
         self.dirty_webgl_contexts.borrow_mut().insert(context_id);
     }
@@ -2530,6 +3709,7 @@ pub trait LayoutDocumentHelpers {
     unsafe fn shadow_roots(&self) -> Vec<LayoutDom<ShadowRoot>>;
     unsafe fn shadow_roots_styles_changed(&self) -> bool;
     unsafe fn flush_shadow_roots_stylesheets(&self);
+    unsafe fn element_with_id_for_layout(&self, id: &Atom) -> Option<LayoutDom<Element>>;
 }
 
 #[allow(unsafe_code)]
@@ -2594,6 +3774,17 @@ impl LayoutDocumentHelpers for LayoutDom<Document> {
     unsafe fn flush_shadow_roots_stylesheets(&self) {
         (*self.unsafe_get()).flush_shadow_roots_stylesheets()
     }
+
+    #[inline]
+    #[allow(unrooted_must_root)]
+    unsafe fn element_with_id_for_layout(&self, id: &Atom) -> Option<LayoutDom<Element>> {
+        (*self.unsafe_get())
+            .id_map
+            .borrow_for_layout()
+            .get(id)
+            .and_then(|elements| elements.first())
+            .map(|element| element.to_layout())
+    }
 }
 
 // https://html.spec.whatwg.org/multipage/#is-a-registrable-domain-suffix-of-or-is-equal-to
@@ -2768,6 +3959,16 @@ impl Document {
             pending_restyles: DomRefCell::new(HashMap::new()),
             needs_paint: Cell::new(false),
             active_touch_points: DomRefCell::new(Vec::new()),
+            active_pointers: DomRefCell::new(HashMap::new()),
+            pointer_capture_targets: DomRefCell::new(HashMap::new()),
+            pending_pointer_capture_targets: DomRefCell::new(HashMap::new()),
+            mouse_events_suppressed: Cell::new(false),
+            drag_candidate: Default::default(),
+            drag_candidate_point: Cell::new(Point2D::zero()),
+            drag_source: Default::default(),
+            drag_immediate_user_selection: Default::default(),
+            drag_current_target: Default::default(),
+            drag_operation: Cell::new(DragOperation::None),
             dom_loading: Cell::new(Default::default()),
             dom_interactive: Cell::new(Default::default()),
             dom_content_loaded_event_start: Cell::new(Default::default()),
@@ -2806,6 +4007,11 @@ impl Document {
             shadow_roots_styles_changed: Cell::new(false),
             media_controls: DomRefCell::new(HashMap::new()),
             dirty_webgl_contexts: DomRefCell::new(HashSet::new()),
+            timeline: Default::default(),
+            script_animations: DomRefCell::new(vec![]),
+            css_animations: DomRefCell::new(vec![]),
+            fonts: Default::default(),
+            registered_custom_properties: DomRefCell::new(HashSet::new()),
         }
     }
 
@@ -2961,7 +4167,9 @@ impl Document {
         let window_size = self.window().window_size();
         let viewport_size = window_size.initial_viewport;
         let device_pixel_ratio = window_size.device_pixel_ratio;
-        Device::new(MediaType::screen(), viewport_size, device_pixel_ratio)
+        let mut device = Device::new(MediaType::screen(), viewport_size, device_pixel_ratio);
+        device.set_media_preferences(self.window().media_preferences());
+        device
     }
 
     pub fn salvageable(&self) -> bool {
@@ -3327,6 +4535,10 @@ impl Document {
             ))
             .unwrap();
 
+        if pref!(dom.fontface.enabled) {
+            self.Fonts().add_css_font_faces(&sheet);
+        }
+
         DocumentOrShadowRoot::add_stylesheet(
             owner,
             StylesheetSetRef::Document(stylesheets),
@@ -3344,6 +4556,10 @@ impl Document {
             .send(Msg::RemoveStylesheet(s.clone()))
             .unwrap();
 
+        if let Some(fonts) = self.fonts.get() {
+            fonts.remove_css_font_faces(s);
+        }
+
         DocumentOrShadowRoot::remove_stylesheet(
             owner,
             s,
@@ -4615,6 +5831,51 @@ impl DocumentMethods for Document {
             None => Err(Error::InvalidAccess),
         }
     }
+
+    // https://drafts.csswg.org/web-animations/#dom-document-timeline
+    fn Timeline(&self) -> DomRoot<DocumentTimeline> {
+        self.timeline.or_init(|| DocumentTimeline::new(self, 0.))
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-document-getanimations
+    fn GetAnimations(&self) -> Vec<DomRoot<Animation>> {
+        self.get_animations(None, true)
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-document-fonts
+    fn Fonts(&self) -> DomRoot<FontFaceSet> {
+        self.fonts.or_init(|| FontFaceSet::new(&self.window))
+    }
+}
+
+/// The inclusive ancestors of `target` that aren't inclusive ancestors of
+/// `other`, from the innermost to the outermost, which get `pointerleave` or
+/// `pointerenter` events when a pointer moves between them.
+fn pointer_boundary_elements(target: &Element, other: Option<&Element>) -> Vec<DomRoot<Element>> {
+    target
+        .upcast::<Node>()
+        .inclusive_ancestors(ShadowIncluding::No)
+        .filter_map(DomRoot::downcast::<Element>)
+        .take_while(|element| {
+            other.map_or(true, |other| {
+                !element
+                    .upcast::<Node>()
+                    .is_inclusive_ancestor_of(other.upcast())
+            })
+        })
+        .collect()
+}
+
+/// Whether an animation targeting the given element is returned when querying
+/// the animations of `root`, or of the whole document if `root` is `None`.
+fn is_animation_target_included(target: &Element, root: Option<&Element>, subtree: bool) -> bool {
+    match root {
+        None => target.upcast::<Node>().is_connected(),
+        Some(root) if subtree => root
+            .upcast::<Node>()
+            .is_inclusive_ancestor_of(target.upcast()),
+        Some(root) => root == target,
+    }
 }
 
 fn update_with_current_time_ms(marker: &Cell<u64>) {
@@ -0,0 +1,66 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::animationtimeline::AnimationTimeline;
+use crate::dom::bindings::codegen::Bindings::DocumentTimelineBinding::{
+    self, DocumentTimelineOptions,
+};
+use crate::dom::bindings::codegen::Bindings::PerformanceBinding::PerformanceMethods;
+use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
+use crate::dom::bindings::error::Fallible;
+use crate::dom::bindings::reflector::reflect_dom_object;
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::document::Document;
+use crate::dom::window::Window;
+use dom_struct::dom_struct;
+
+#[dom_struct]
+pub struct DocumentTimeline {
+    timeline: AnimationTimeline,
+    document: Dom<Document>,
+    /// The zero time of this timeline, relative to the time origin of the
+    /// document, in milliseconds.
+    origin_time: f64,
+}
+
+impl DocumentTimeline {
+    fn new_inherited(document: &Document, origin_time: f64) -> DocumentTimeline {
+        DocumentTimeline {
+            timeline: AnimationTimeline::new_inherited(),
+            document: Dom::from_ref(document),
+            origin_time,
+        }
+    }
+
+    pub fn new(document: &Document, origin_time: f64) -> DomRoot<DocumentTimeline> {
+        reflect_dom_object(
+            Box::new(DocumentTimeline::new_inherited(document, origin_time)),
+            document.window(),
+            DocumentTimelineBinding::Wrap,
+        )
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-documenttimeline-documenttimeline
+    pub fn Constructor(
+        window: &Window,
+        options: &DocumentTimelineOptions,
+    ) -> Fallible<DomRoot<DocumentTimeline>> {
+        Ok(DocumentTimeline::new(
+            &window.Document(),
+            *options.originTime,
+        ))
+    }
+
+    /// <https://drafts.csswg.org/web-animations/#document-timelines>
+    ///
+    /// A document timeline is inactive while its document is not fully
+    /// active.
+    pub fn current_time(&self) -> Option<f64> {
+        if !self.document.is_fully_active() {
+            return None;
+        }
+        let now = *self.document.window().Performance().Now();
+        Some(now - self.origin_time)
+    }
+}
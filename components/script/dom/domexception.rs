@@ -38,6 +38,8 @@ pub enum DOMErrorName {
     DataCloneError = DOMExceptionConstants::DATA_CLONE_ERR,
     NotReadableError,
     OperationError,
+    NotAllowedError,
+    EncodingError,
 }
 
 impl DOMErrorName {
@@ -66,6 +68,8 @@ impl DOMErrorName {
             "DataCloneError" => Some(DOMErrorName::DataCloneError),
             "NotReadableError" => Some(DOMErrorName::NotReadableError),
             "OperationError" => Some(DOMErrorName::OperationError),
+            "NotAllowedError" => Some(DOMErrorName::NotAllowedError),
+            "EncodingError" => Some(DOMErrorName::EncodingError),
             _ => None,
         }
     }
@@ -112,6 +116,10 @@ impl DOMException {
             DOMErrorName::OperationError => {
                 "The operation failed for an operation-specific reason."
             },
+            DOMErrorName::NotAllowedError => {
+                "The request is not allowed by the user agent or the platform in the current context."
+            },
+            DOMErrorName::EncodingError => "The encoding operation failed.",
         };
 
         (
@@ -0,0 +1,122 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::bindings::codegen::Bindings::DragEventBinding;
+use crate::dom::bindings::codegen::Bindings::DragEventBinding::DragEventMethods;
+use crate::dom::bindings::codegen::Bindings::MouseEventBinding::MouseEventMethods;
+use crate::dom::bindings::error::Fallible;
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::reflector::reflect_dom_object;
+use crate::dom::bindings::root::{DomRoot, MutNullableDom};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::datatransfer::DataTransfer;
+use crate::dom::event::{EventBubbles, EventCancelable};
+use crate::dom::eventtarget::EventTarget;
+use crate::dom::mouseevent::MouseEvent;
+use crate::dom::window::Window;
+use dom_struct::dom_struct;
+
+#[dom_struct]
+pub struct DragEvent {
+    mouseevent: MouseEvent,
+    data_transfer: MutNullableDom<DataTransfer>,
+}
+
+impl DragEvent {
+    fn new_inherited(data_transfer: Option<&DataTransfer>) -> DragEvent {
+        DragEvent {
+            mouseevent: MouseEvent::new_inherited(),
+            data_transfer: MutNullableDom::new(data_transfer),
+        }
+    }
+
+    pub fn new(
+        window: &Window,
+        type_: DOMString,
+        can_bubble: EventBubbles,
+        cancelable: EventCancelable,
+        view: Option<&Window>,
+        detail: i32,
+        screen_x: i32,
+        screen_y: i32,
+        client_x: i32,
+        client_y: i32,
+        ctrl_key: bool,
+        alt_key: bool,
+        shift_key: bool,
+        meta_key: bool,
+        button: i16,
+        buttons: u16,
+        related_target: Option<&EventTarget>,
+        data_transfer: Option<&DataTransfer>,
+    ) -> DomRoot<DragEvent> {
+        let ev = reflect_dom_object(
+            Box::new(DragEvent::new_inherited(data_transfer)),
+            window,
+            DragEventBinding::Wrap,
+        );
+        let mouseevent = ev.upcast::<MouseEvent>();
+        mouseevent.InitMouseEvent(
+            type_,
+            bool::from(can_bubble),
+            bool::from(cancelable),
+            view,
+            detail,
+            screen_x,
+            screen_y,
+            client_x,
+            client_y,
+            ctrl_key,
+            alt_key,
+            shift_key,
+            meta_key,
+            button,
+            related_target,
+        );
+        mouseevent.set_buttons(buttons);
+        ev
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-dragevent
+    pub fn Constructor(
+        window: &Window,
+        type_: DOMString,
+        init: &DragEventBinding::DragEventInit,
+    ) -> Fallible<DomRoot<DragEvent>> {
+        let mouse_init = &init.parent;
+        let event = DragEvent::new(
+            window,
+            type_,
+            EventBubbles::from(mouse_init.parent.parent.parent.bubbles),
+            EventCancelable::from(mouse_init.parent.parent.parent.cancelable),
+            mouse_init.parent.parent.view.as_deref(),
+            mouse_init.parent.parent.detail,
+            mouse_init.screenX,
+            mouse_init.screenY,
+            mouse_init.clientX,
+            mouse_init.clientY,
+            mouse_init.parent.ctrlKey,
+            mouse_init.parent.altKey,
+            mouse_init.parent.shiftKey,
+            mouse_init.parent.metaKey,
+            mouse_init.button,
+            mouse_init.buttons,
+            mouse_init.relatedTarget.as_deref(),
+            init.dataTransfer.as_deref(),
+        );
+        Ok(event)
+    }
+}
+
+impl DragEventMethods for DragEvent {
+    // https://html.spec.whatwg.org/multipage/#dom-dragevent-datatransfer
+    fn GetDataTransfer(&self) -> Option<DomRoot<DataTransfer>> {
+        self.data_transfer.get()
+    }
+
+    // https://dom.spec.whatwg.org/#dom-event-istrusted
+    fn IsTrusted(&self) -> bool {
+        self.mouseevent.IsTrusted()
+    }
+}
@@ -0,0 +1,218 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::audionode::AudioNode;
+use crate::dom::audioparam::AudioParam;
+use crate::dom::baseaudiocontext::BaseAudioContext;
+use crate::dom::bindings::codegen::Bindings::AudioNodeBinding::{
+    ChannelCountMode, ChannelInterpretation,
+};
+use crate::dom::bindings::codegen::Bindings::AudioParamBinding::AutomationRate;
+use crate::dom::bindings::codegen::Bindings::DynamicsCompressorNodeBinding::{
+    self, DynamicsCompressorNodeMethods, DynamicsCompressorOptions,
+};
+use crate::dom::bindings::error::{Error, Fallible};
+use crate::dom::bindings::num::Finite;
+use crate::dom::bindings::refcounted::Trusted;
+use crate::dom::bindings::reflector::reflect_dom_object;
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::window::Window;
+use crate::task_source::TaskSource;
+use dom_struct::dom_struct;
+use ipc_channel::ipc::{self, IpcReceiver};
+use ipc_channel::router::ROUTER;
+use servo_media::audio::dynamics_compressor_node::DynamicsCompressorNodeOptions;
+use servo_media::audio::node::AudioNodeInit;
+use servo_media::audio::param::ParamType;
+use std::cell::Cell;
+
+#[dom_struct]
+pub struct DynamicsCompressorNode {
+    node: AudioNode,
+    threshold: Dom<AudioParam>,
+    knee: Dom<AudioParam>,
+    ratio: Dom<AudioParam>,
+    attack: Dom<AudioParam>,
+    release: Dom<AudioParam>,
+    /// The gain reduction of the last render quantum, in decibels.
+    reduction: Cell<f32>,
+}
+
+impl DynamicsCompressorNode {
+    #[allow(unrooted_must_root)]
+    pub fn new_inherited(
+        window: &Window,
+        context: &BaseAudioContext,
+        options: &DynamicsCompressorOptions,
+    ) -> Fallible<(DynamicsCompressorNode, IpcReceiver<f32>)> {
+        let node_options = options.parent.unwrap_or(
+            2,
+            ChannelCountMode::Clamped_max,
+            ChannelInterpretation::Speakers,
+        );
+        if node_options.mode == ChannelCountMode::Max {
+            return Err(Error::NotSupported);
+        }
+        if node_options.count > 2 || node_options.count == 0 {
+            return Err(Error::NotSupported);
+        }
+
+        let (send, rcv) = ipc::channel().unwrap();
+        let callback = move |reduction| {
+            let _ = send.send(reduction);
+        };
+
+        let node = AudioNode::new_inherited(
+            AudioNodeInit::DynamicsCompressorNode(options.into(), Box::new(callback)),
+            context,
+            node_options,
+            1, // inputs
+            1, // outputs
+        )?;
+        let threshold = AudioParam::new(
+            window,
+            context,
+            node.node_id(),
+            ParamType::Threshold,
+            AutomationRate::K_rate,
+            *options.threshold, // default value
+            -100.,              // min value
+            0.,                 // max value
+        );
+        let knee = AudioParam::new(
+            window,
+            context,
+            node.node_id(),
+            ParamType::Knee,
+            AutomationRate::K_rate,
+            *options.knee, // default value
+            0.,            // min value
+            40.,           // max value
+        );
+        let ratio = AudioParam::new(
+            window,
+            context,
+            node.node_id(),
+            ParamType::Ratio,
+            AutomationRate::K_rate,
+            *options.ratio, // default value
+            1.,             // min value
+            20.,            // max value
+        );
+        let attack = AudioParam::new(
+            window,
+            context,
+            node.node_id(),
+            ParamType::Attack,
+            AutomationRate::K_rate,
+            *options.attack, // default value
+            0.,              // min value
+            1.,              // max value
+        );
+        let release = AudioParam::new(
+            window,
+            context,
+            node.node_id(),
+            ParamType::Release,
+            AutomationRate::K_rate,
+            *options.release, // default value
+            0.,               // min value
+            1.,               // max value
+        );
+        Ok((
+            DynamicsCompressorNode {
+                node,
+                threshold: Dom::from_ref(&threshold),
+                knee: Dom::from_ref(&knee),
+                ratio: Dom::from_ref(&ratio),
+                attack: Dom::from_ref(&attack),
+                release: Dom::from_ref(&release),
+                reduction: Cell::new(0.),
+            },
+            rcv,
+        ))
+    }
+
+    #[allow(unrooted_must_root)]
+    pub fn new(
+        window: &Window,
+        context: &BaseAudioContext,
+        options: &DynamicsCompressorOptions,
+    ) -> Fallible<DomRoot<DynamicsCompressorNode>> {
+        let (node, recv) = DynamicsCompressorNode::new_inherited(window, context, options)?;
+        let object =
+            reflect_dom_object(Box::new(node), window, DynamicsCompressorNodeBinding::Wrap);
+        let (source, canceller) = window
+            .task_manager()
+            .dom_manipulation_task_source_with_canceller();
+        let this = Trusted::new(&*object);
+
+        ROUTER.add_route(
+            recv.to_opaque(),
+            Box::new(move |reduction| {
+                let this = this.clone();
+                let _ = source.queue_with_canceller(
+                    task!(update_compressor_reduction: move || {
+                        let this = this.root();
+                        this.reduction.set(reduction.to().unwrap());
+                    }),
+                    &canceller,
+                );
+            }),
+        );
+        Ok(object)
+    }
+
+    pub fn Constructor(
+        window: &Window,
+        context: &BaseAudioContext,
+        options: &DynamicsCompressorOptions,
+    ) -> Fallible<DomRoot<DynamicsCompressorNode>> {
+        DynamicsCompressorNode::new(window, context, options)
+    }
+}
+
+impl DynamicsCompressorNodeMethods for DynamicsCompressorNode {
+    // https://webaudio.github.io/web-audio-api/#dom-dynamicscompressornode-threshold
+    fn Threshold(&self) -> DomRoot<AudioParam> {
+        DomRoot::from_ref(&self.threshold)
+    }
+
+    // https://webaudio.github.io/web-audio-api/#dom-dynamicscompressornode-knee
+    fn Knee(&self) -> DomRoot<AudioParam> {
+        DomRoot::from_ref(&self.knee)
+    }
+
+    // https://webaudio.github.io/web-audio-api/#dom-dynamicscompressornode-ratio
+    fn Ratio(&self) -> DomRoot<AudioParam> {
+        DomRoot::from_ref(&self.ratio)
+    }
+
+    // https://webaudio.github.io/web-audio-api/#dom-dynamicscompressornode-reduction
+    fn Reduction(&self) -> Finite<f32> {
+        Finite::wrap(self.reduction.get())
+    }
+
+    // https://webaudio.github.io/web-audio-api/#dom-dynamicscompressornode-attack
+    fn Attack(&self) -> DomRoot<AudioParam> {
+        DomRoot::from_ref(&self.attack)
+    }
+
+    // https://webaudio.github.io/web-audio-api/#dom-dynamicscompressornode-release
+    fn Release(&self) -> DomRoot<AudioParam> {
+        DomRoot::from_ref(&self.release)
+    }
+}
+
+impl<'a> From<&'a DynamicsCompressorOptions> for DynamicsCompressorNodeOptions {
+    fn from(options: &'a DynamicsCompressorOptions) -> Self {
+        Self {
+            threshold: *options.threshold,
+            knee: *options.knee,
+            ratio: *options.ratio,
+            attack: *options.attack,
+            release: *options.release,
+        }
+    }
+}
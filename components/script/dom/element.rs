@@ -5,8 +5,12 @@
 //! Element nodes.
 
 use crate::dom::activation::Activatable;
+use crate::dom::animation::Animation;
+use crate::dom::animationeffect::EffectTimingValues;
 use crate::dom::attr::{Attr, AttrHelpersForLayout};
 use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::AnimatableBinding::GetAnimationsOptions;
+use crate::dom::bindings::codegen::Bindings::AnimationBinding::AnimationMethods;
 use crate::dom::bindings::codegen::Bindings::AttrBinding::AttrMethods;
 use crate::dom::bindings::codegen::Bindings::DocumentBinding::DocumentMethods;
 use crate::dom::bindings::codegen::Bindings::ElementBinding;
@@ -14,11 +18,13 @@ use crate::dom::bindings::codegen::Bindings::ElementBinding::ElementMethods;
 use crate::dom::bindings::codegen::Bindings::EventBinding::EventMethods;
 use crate::dom::bindings::codegen::Bindings::FunctionBinding::Function;
 use crate::dom::bindings::codegen::Bindings::HTMLTemplateElementBinding::HTMLTemplateElementMethods;
+use crate::dom::bindings::codegen::Bindings::KeyframeEffectBinding::CompositeOperation;
 use crate::dom::bindings::codegen::Bindings::NodeBinding::NodeMethods;
 use crate::dom::bindings::codegen::Bindings::ShadowRootBinding::ShadowRootBinding::ShadowRootMethods;
 use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
 use crate::dom::bindings::codegen::Bindings::WindowBinding::{ScrollBehavior, ScrollToOptions};
 use crate::dom::bindings::codegen::UnionTypes::NodeOrString;
+use crate::dom::bindings::codegen::UnionTypes::UnrestrictedDoubleOrKeyframeAnimationOptions;
 use crate::dom::bindings::conversions::DerivedFrom;
 use crate::dom::bindings::error::{Error, ErrorResult, Fallible};
 use crate::dom::bindings::inheritance::{Castable, ElementTypeId, HTMLElementTypeId, NodeTypeId};
@@ -69,6 +75,7 @@ use crate::dom::htmltablesectionelement::{
 };
 use crate::dom::htmltemplateelement::HTMLTemplateElement;
 use crate::dom::htmltextareaelement::{HTMLTextAreaElement, LayoutHTMLTextAreaElementHelpers};
+use crate::dom::keyframeeffect::{process_keyframes, KeyframeEffect};
 use crate::dom::mutationobserver::{Mutation, MutationObserver};
 use crate::dom::namednodemap::NamedNodeMap;
 use crate::dom::node::{document_from_node, window_from_node};
@@ -79,10 +86,12 @@ use crate::dom::promise::Promise;
 use crate::dom::raredata::ElementRareData;
 use crate::dom::servoparser::ServoParser;
 use crate::dom::shadowroot::{IsUserAgentWidget, ShadowRoot};
+use crate::dom::svgelement::{LayoutSVGElementHelpers, SVGElement};
 use crate::dom::text::Text;
 use crate::dom::validation::Validatable;
 use crate::dom::virtualmethods::{vtable_for, VirtualMethods};
 use crate::dom::window::ReflowReason;
+use crate::script_runtime::JSContext;
 use crate::script_thread::ScriptThread;
 use crate::stylesheet_loader::StylesheetOwner;
 use crate::task::TaskOnce;
@@ -93,7 +102,7 @@ use html5ever::serialize::SerializeOpts;
 use html5ever::serialize::TraversalScope;
 use html5ever::serialize::TraversalScope::{ChildrenOnly, IncludeNode};
 use html5ever::{LocalName, Namespace, Prefix, QualName};
-use js::jsapi::Heap;
+use js::jsapi::{Heap, JSObject};
 use js::jsval::JSVal;
 use msg::constellation_msg::InputMethodType;
 use net_traits::request::CorsSettings;
@@ -956,6 +965,15 @@ impl LayoutElementHelpers for LayoutDom<Element> {
                 PropertyDeclaration::BorderRightWidth(width_value),
             ));
         }
+
+        if let Some(this) = self.downcast::<SVGElement>() {
+            if let Some(block) = this.get_presentation_attributes() {
+                hints.push(ApplicableDeclarationBlock::from_declarations(
+                    block,
+                    CascadeLevel::PresHints,
+                ));
+            }
+        }
     }
 
     #[allow(unsafe_code)]
@@ -2660,12 +2678,70 @@ impl ElementMethods for Element {
         doc.enter_fullscreen(self)
     }
 
+    // https://w3c.github.io/pointerevents/#dom-element-setpointercapture
+    fn SetPointerCapture(&self, pointer_id: i32) -> ErrorResult {
+        document_from_node(self).set_pointer_capture(self, pointer_id)
+    }
+
+    // https://w3c.github.io/pointerevents/#dom-element-releasepointercapture
+    fn ReleasePointerCapture(&self, pointer_id: i32) -> ErrorResult {
+        document_from_node(self).release_pointer_capture(self, pointer_id)
+    }
+
+    // https://w3c.github.io/pointerevents/#dom-element-haspointercapture
+    fn HasPointerCapture(&self, pointer_id: i32) -> bool {
+        document_from_node(self).has_pointer_capture(self, pointer_id)
+    }
+
     // XXX Hidden under dom.shadowdom.enabled pref. Only exposed to be able
     //     to test partial Shadow DOM support for UA widgets.
     // https://dom.spec.whatwg.org/#dom-element-attachshadow
     fn AttachShadow(&self) -> Fallible<DomRoot<ShadowRoot>> {
         self.attach_shadow(IsUserAgentWidget::No)
     }
+
+    // https://drafts.csswg.org/web-animations/#dom-animatable-animate
+    fn Animate(
+        &self,
+        cx: JSContext,
+        keyframes: *mut JSObject,
+        options: UnrestrictedDoubleOrKeyframeAnimationOptions,
+    ) -> Fallible<DomRoot<Animation>> {
+        let window = window_from_node(self);
+        let document = window.Document();
+        let (timing, composite, id) = match options {
+            UnrestrictedDoubleOrKeyframeAnimationOptions::UnrestrictedDouble(duration) => (
+                EffectTimingValues::from_duration(&document, duration)?,
+                CompositeOperation::Replace,
+                DOMString::new(),
+            ),
+            UnrestrictedDoubleOrKeyframeAnimationOptions::KeyframeAnimationOptions(options) => (
+                EffectTimingValues::from_dictionary(&document, &options.parent.parent)?,
+                options.parent.composite,
+                options.id,
+            ),
+        };
+
+        // Step 1.
+        let keyframes = process_keyframes(cx, &document, keyframes)?;
+        let effect = KeyframeEffect::new(&window, Some(self), keyframes, timing, composite);
+
+        // Steps 2 to 4.
+        let timeline = document.Timeline();
+        let animation = Animation::new(&window, Some(effect.upcast()), Some(timeline.upcast()));
+        animation.SetId(id);
+
+        // Step 5.
+        animation.Play()?;
+
+        // Step 6.
+        Ok(animation)
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-animatable-getanimations
+    fn GetAnimations(&self, options: &GetAnimationsOptions) -> Vec<DomRoot<Animation>> {
+        document_from_node(self).get_animations(Some(self), options.subtree)
+    }
 }
 
 impl VirtualMethods for Element {
@@ -0,0 +1,501 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::DocumentBinding::DocumentMethods;
+use crate::dom::bindings::codegen::Bindings::FontFaceBinding::{
+    self, FontFaceDescriptors, FontFaceLoadStatus, FontFaceMethods,
+};
+use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
+use crate::dom::bindings::codegen::UnionTypes::StringOrArrayBufferOrArrayBufferView;
+use crate::dom::bindings::error::{Error, ErrorResult, Fallible};
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::reflector::{reflect_dom_object, DomObject, Reflector};
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::document::Document;
+use crate::dom::fontfaceset::FontFaceSet;
+use crate::dom::promise::Promise;
+use crate::dom::window::Window;
+use cssparser::{Parser, ParserInput, UnicodeRange};
+use dom_struct::dom_struct;
+use script_layout_interface::message::WebFontSource;
+use servo_arc::Arc;
+use std::cell::Cell;
+use std::rc::Rc;
+use style::font_face::{EffectiveSources, Source};
+use style::parser::{Parse, ParserContext};
+use style::properties::{parse_one_declaration_into, PropertyId, SourcePropertyDeclaration};
+use style::stylesheets::{CssRuleType, Stylesheet};
+use style::values::computed::font::FamilyName;
+use style_traits::{ParsingMode, ToCss};
+
+/// Where the data of a font face comes from.
+#[derive(JSTraceable, MallocSizeOf)]
+enum FontFaceSource {
+    /// The sources of a `src` descriptor, tried in order.
+    Sources(#[ignore_malloc_size_of = "Defined in style"] EffectiveSources),
+    /// Font data given to the constructor.
+    Data(Vec<u8>),
+    /// The font face failed to parse and can't be loaded.
+    None,
+}
+
+#[dom_struct]
+pub struct FontFace {
+    reflector_: Reflector,
+    family: DomRefCell<DOMString>,
+    style: DomRefCell<DOMString>,
+    weight: DomRefCell<DOMString>,
+    stretch: DomRefCell<DOMString>,
+    unicode_range: DomRefCell<DOMString>,
+    variant: DomRefCell<DOMString>,
+    feature_settings: DomRefCell<DOMString>,
+    variation_settings: DomRefCell<DOMString>,
+    display: DomRefCell<DOMString>,
+    status: Cell<FontFaceLoadStatus>,
+    source: DomRefCell<FontFaceSource>,
+    /// <https://drafts.csswg.org/css-font-loading/#dom-fontface-fontstatuspromise-slot>
+    #[ignore_malloc_size_of = "promises are hard"]
+    font_status_promise: Rc<Promise>,
+    /// The style sheet of the `@font-face` rule this font face is connected
+    /// to, if any.
+    ///
+    /// <https://drafts.csswg.org/css-font-loading/#css-connected>
+    #[ignore_malloc_size_of = "Arc"]
+    css_stylesheet: Option<Arc<Stylesheet>>,
+    /// The font face sets this font face is in, which are told about its
+    /// status changes.
+    sets: DomRefCell<Vec<Dom<FontFaceSet>>>,
+}
+
+impl FontFace {
+    fn new_inherited(
+        window: &Window,
+        family: DOMString,
+        source: FontFaceSource,
+        css_stylesheet: Option<Arc<Stylesheet>>,
+    ) -> FontFace {
+        FontFace {
+            reflector_: Reflector::new(),
+            family: DomRefCell::new(family),
+            style: DomRefCell::new(DOMString::from("normal")),
+            weight: DomRefCell::new(DOMString::from("normal")),
+            stretch: DomRefCell::new(DOMString::from("normal")),
+            unicode_range: DomRefCell::new(DOMString::from("U+0-10FFFF")),
+            variant: DomRefCell::new(DOMString::from("normal")),
+            feature_settings: DomRefCell::new(DOMString::from("normal")),
+            variation_settings: DomRefCell::new(DOMString::from("normal")),
+            display: DomRefCell::new(DOMString::from("auto")),
+            status: Cell::new(FontFaceLoadStatus::Unloaded),
+            source: DomRefCell::new(source),
+            font_status_promise: Promise::new(window.upcast()),
+            css_stylesheet,
+            sets: DomRefCell::new(vec![]),
+        }
+    }
+
+    fn new(
+        window: &Window,
+        family: DOMString,
+        source: FontFaceSource,
+        css_stylesheet: Option<Arc<Stylesheet>>,
+    ) -> DomRoot<FontFace> {
+        reflect_dom_object(
+            Box::new(FontFace::new_inherited(
+                window,
+                family,
+                source,
+                css_stylesheet,
+            )),
+            window,
+            FontFaceBinding::Wrap,
+        )
+    }
+
+    /// Creates the font face connected to an `@font-face` rule of the given
+    /// style sheet. Layout starts loading these as soon as the style sheet is
+    /// added, so they are created in the loading state.
+    pub fn new_css_connected(
+        window: &Window,
+        family: &FamilyName,
+        sources: EffectiveSources,
+        stylesheet: Arc<Stylesheet>,
+    ) -> DomRoot<FontFace> {
+        let face = FontFace::new(
+            window,
+            DOMString::from(family.to_css_string()),
+            FontFaceSource::Sources(sources),
+            Some(stylesheet),
+        );
+        face.status.set(FontFaceLoadStatus::Loading);
+        face
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontface-fontface
+    #[allow(unsafe_code)]
+    pub fn Constructor(
+        window: &Window,
+        family: DOMString,
+        mut source: StringOrArrayBufferOrArrayBufferView,
+        descriptors: &FontFaceDescriptors,
+    ) -> Fallible<DomRoot<FontFace>> {
+        let document = window.Document();
+        let face = FontFace::new(window, family, FontFaceSource::None, None);
+
+        // Step 2.
+        let mut descriptors_are_valid = true;
+        if face.family_name().is_none() {
+            *face.family.borrow_mut() = DOMString::new();
+            descriptors_are_valid = false;
+        }
+        let fields = [
+            (&face.style, "font-style", &descriptors.style),
+            (&face.weight, "font-weight", &descriptors.weight),
+            (&face.stretch, "font-stretch", &descriptors.stretch),
+            (
+                &face.unicode_range,
+                "unicode-range",
+                &descriptors.unicodeRange,
+            ),
+            (&face.variant, "font-variant", &descriptors.variant),
+            (
+                &face.feature_settings,
+                "font-feature-settings",
+                &descriptors.featureSettings,
+            ),
+            (
+                &face.variation_settings,
+                "font-variation-settings",
+                &descriptors.variationSettings,
+            ),
+            (&face.display, "font-display", &descriptors.display),
+        ];
+        for &(field, name, value) in fields.iter() {
+            if is_valid_descriptor(&document, name, value) {
+                *field.borrow_mut() = value.clone();
+            } else {
+                *field.borrow_mut() = DOMString::new();
+                descriptors_are_valid = false;
+            }
+        }
+        if !descriptors_are_valid {
+            face.fail(Error::Syntax);
+            return Ok(face);
+        }
+
+        match source {
+            // Step 4.
+            StringOrArrayBufferOrArrayBufferView::String(ref src) => {
+                match parse_descriptor::<Vec<Source>>(&document, src) {
+                    Some(sources) => {
+                        *face.source.borrow_mut() =
+                            FontFaceSource::Sources(EffectiveSources::from_sources(&sources));
+                    },
+                    None => face.fail(Error::Syntax),
+                }
+            },
+            // Step 5.
+            StringOrArrayBufferOrArrayBufferView::ArrayBuffer(ref mut buffer) => unsafe {
+                *face.source.borrow_mut() = FontFaceSource::Data(buffer.as_slice().to_vec());
+                face.start_load();
+            },
+            StringOrArrayBufferOrArrayBufferView::ArrayBufferView(ref mut view) => unsafe {
+                *face.source.borrow_mut() = FontFaceSource::Data(view.as_slice().to_vec());
+                face.start_load();
+            },
+        }
+        Ok(face)
+    }
+
+    pub fn status(&self) -> FontFaceLoadStatus {
+        self.status.get()
+    }
+
+    pub fn is_css_connected_to(&self, stylesheet: &Arc<Stylesheet>) -> bool {
+        self.css_stylesheet
+            .as_ref()
+            .map_or(false, |s| Arc::ptr_eq(s, stylesheet))
+    }
+
+    pub fn is_css_connected(&self) -> bool {
+        self.css_stylesheet.is_some()
+    }
+
+    /// The family name of this font face, if it parses.
+    pub fn family_name(&self) -> Option<FamilyName> {
+        let document = self.global().as_window().Document();
+        parse_descriptor::<FamilyName>(&document, &self.family.borrow())
+    }
+
+    /// Whether this font face is registered in the font cache under the given
+    /// lowercased family name.
+    pub fn has_family(&self, lowercase_family_name: &str) -> bool {
+        self.family_name().map_or(false, |family| {
+            family.name.to_lowercase() == lowercase_family_name
+        })
+    }
+
+    pub fn add_to_set(&self, set: &FontFaceSet) {
+        self.sets.borrow_mut().push(Dom::from_ref(set));
+    }
+
+    pub fn remove_from_set(&self, set: &FontFaceSet) {
+        self.sets.borrow_mut().retain(|s| &**s != set);
+    }
+
+    /// Sets the status of this font face to error and rejects its promise.
+    fn fail(&self, error: Error) {
+        self.status.set(FontFaceLoadStatus::Error);
+        self.font_status_promise.reject_error(error);
+    }
+
+    /// Switches this font face to the loading state and asks layout to load
+    /// it.
+    ///
+    /// <https://drafts.csswg.org/css-font-loading/#font-face-load>
+    fn start_load(&self) {
+        let family = match self.family_name() {
+            Some(family) => family,
+            None => return self.fail(Error::Syntax),
+        };
+        let source = match *self.source.borrow() {
+            FontFaceSource::Sources(ref sources) => WebFontSource::Sources(sources.clone()),
+            FontFaceSource::Data(ref data) => WebFontSource::Data(data.clone()),
+            FontFaceSource::None => return,
+        };
+        self.status.set(FontFaceLoadStatus::Loading);
+        let sets: Vec<_> = self
+            .sets
+            .borrow()
+            .iter()
+            .map(|s| DomRoot::from_ref(&**s))
+            .collect();
+        for set in sets {
+            set.font_face_started_loading(self);
+        }
+        let document = self.global().as_window().Document();
+        document.Fonts().request_load(self, family, source);
+    }
+
+    /// Settles this font face once the font cache is done with it.
+    pub fn finish_load(&self, loaded: bool) {
+        if self.status.get() != FontFaceLoadStatus::Loading {
+            return;
+        }
+        if loaded {
+            self.status.set(FontFaceLoadStatus::Loaded);
+            self.font_status_promise
+                .resolve_native(&DomRoot::from_ref(self));
+        } else {
+            let error = match *self.source.borrow() {
+                FontFaceSource::Data(_) => Error::Syntax,
+                _ => Error::Network,
+            };
+            self.fail(error);
+        }
+        let sets: Vec<_> = self
+            .sets
+            .borrow()
+            .iter()
+            .map(|s| DomRoot::from_ref(&**s))
+            .collect();
+        for set in sets {
+            set.font_face_settled(self);
+        }
+    }
+
+    fn set_descriptor(
+        &self,
+        field: &DomRefCell<DOMString>,
+        name: &str,
+        value: DOMString,
+    ) -> ErrorResult {
+        let document = self.global().as_window().Document();
+        if !is_valid_descriptor(&document, name, &value) {
+            return Err(Error::Syntax);
+        }
+        // TODO: Update the `@font-face` rule of CSS-connected font faces.
+        *field.borrow_mut() = value;
+        Ok(())
+    }
+}
+
+/// Parses the value of an `@font-face` descriptor of type `T`.
+fn parse_descriptor<T: Parse>(document: &Document, value: &str) -> Option<T> {
+    let url = document.base_url();
+    let context = ParserContext::new_for_cssom(
+        &url,
+        Some(CssRuleType::FontFace),
+        ParsingMode::DEFAULT,
+        document.quirks_mode(),
+        None,
+        None,
+    );
+    let mut input = ParserInput::new(value);
+    let mut parser = Parser::new(&mut input);
+    parser.parse_entirely(|p| T::parse(&context, p)).ok()
+}
+
+/// Returns whether `value` is valid for the `@font-face` descriptor `name`.
+///
+/// Most descriptors share their grammar with the property of the same name,
+/// so they are checked against it. Descriptors whose property the style
+/// system doesn't know about are accepted as they are.
+fn is_valid_descriptor(document: &Document, name: &str, value: &str) -> bool {
+    match name {
+        "font-display" => match_ignore_ascii_case! { value.trim(),
+            "auto" | "block" | "swap" | "fallback" | "optional" => true,
+            _ => false,
+        },
+        "unicode-range" => {
+            let mut input = ParserInput::new(value);
+            let mut parser = Parser::new(&mut input);
+            parser
+                .parse_entirely(|p| p.parse_comma_separated(UnicodeRange::parse))
+                .is_ok()
+        },
+        // These accept a range of two values of the property.
+        "font-weight" | "font-stretch" => {
+            let values: Vec<_> = value.split_whitespace().collect();
+            (values.len() == 1 || values.len() == 2) &&
+                values
+                    .iter()
+                    .all(|value| is_valid_as_property(document, name, value))
+        },
+        _ => is_valid_as_property(document, name, value),
+    }
+}
+
+fn is_valid_as_property(document: &Document, name: &str, value: &str) -> bool {
+    let id = match PropertyId::parse_enabled_for_all_content(name) {
+        Ok(id) => id,
+        Err(..) => return true,
+    };
+    parse_one_declaration_into(
+        &mut SourcePropertyDeclaration::new(),
+        id,
+        value,
+        &document.base_url(),
+        None,
+        ParsingMode::DEFAULT,
+        document.quirks_mode(),
+    )
+    .is_ok()
+}
+
+impl FontFaceMethods for FontFace {
+    // https://drafts.csswg.org/css-font-loading/#dom-fontface-family
+    fn Family(&self) -> DOMString {
+        self.family.borrow().clone()
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontface-family
+    fn SetFamily(&self, value: DOMString) -> ErrorResult {
+        let document = self.global().as_window().Document();
+        if parse_descriptor::<FamilyName>(&document, &value).is_none() {
+            return Err(Error::Syntax);
+        }
+        *self.family.borrow_mut() = value;
+        Ok(())
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontface-style
+    fn Style(&self) -> DOMString {
+        self.style.borrow().clone()
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontface-style
+    fn SetStyle(&self, value: DOMString) -> ErrorResult {
+        self.set_descriptor(&self.style, "font-style", value)
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontface-weight
+    fn Weight(&self) -> DOMString {
+        self.weight.borrow().clone()
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontface-weight
+    fn SetWeight(&self, value: DOMString) -> ErrorResult {
+        self.set_descriptor(&self.weight, "font-weight", value)
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontface-stretch
+    fn Stretch(&self) -> DOMString {
+        self.stretch.borrow().clone()
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontface-stretch
+    fn SetStretch(&self, value: DOMString) -> ErrorResult {
+        self.set_descriptor(&self.stretch, "font-stretch", value)
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontface-unicoderange
+    fn UnicodeRange(&self) -> DOMString {
+        self.unicode_range.borrow().clone()
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontface-unicoderange
+    fn SetUnicodeRange(&self, value: DOMString) -> ErrorResult {
+        self.set_descriptor(&self.unicode_range, "unicode-range", value)
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontface-variant
+    fn Variant(&self) -> DOMString {
+        self.variant.borrow().clone()
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontface-variant
+    fn SetVariant(&self, value: DOMString) -> ErrorResult {
+        self.set_descriptor(&self.variant, "font-variant", value)
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontface-featuresettings
+    fn FeatureSettings(&self) -> DOMString {
+        self.feature_settings.borrow().clone()
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontface-featuresettings
+    fn SetFeatureSettings(&self, value: DOMString) -> ErrorResult {
+        self.set_descriptor(&self.feature_settings, "font-feature-settings", value)
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontface-variationsettings
+    fn VariationSettings(&self) -> DOMString {
+        self.variation_settings.borrow().clone()
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontface-variationsettings
+    fn SetVariationSettings(&self, value: DOMString) -> ErrorResult {
+        self.set_descriptor(&self.variation_settings, "font-variation-settings", value)
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontface-display
+    fn Display(&self) -> DOMString {
+        self.display.borrow().clone()
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontface-display
+    fn SetDisplay(&self, value: DOMString) -> ErrorResult {
+        self.set_descriptor(&self.display, "font-display", value)
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontface-status
+    fn Status(&self) -> FontFaceLoadStatus {
+        self.status.get()
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontface-load
+    fn Load(&self) -> Rc<Promise> {
+        if self.status.get() == FontFaceLoadStatus::Unloaded {
+            self.start_load();
+        }
+        self.font_status_promise.clone()
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontface-loaded
+    fn Loaded(&self) -> Rc<Promise> {
+        self.font_status_promise.clone()
+    }
+}
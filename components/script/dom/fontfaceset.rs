@@ -0,0 +1,512 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::compartments::InCompartment;
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::DocumentBinding::{
+    DocumentMethods, DocumentReadyState,
+};
+use crate::dom::bindings::codegen::Bindings::FontFaceBinding::{
+    FontFaceLoadStatus, FontFaceMethods,
+};
+use crate::dom::bindings::codegen::Bindings::FontFaceSetBinding::{
+    self, FontFaceSetLoadStatus, FontFaceSetMethods,
+};
+use crate::dom::bindings::error::{Error, Fallible};
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::refcounted::Trusted;
+use crate::dom::bindings::reflector::{reflect_dom_object, DomObject};
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::event::{Event, EventBubbles, EventCancelable};
+use crate::dom::eventtarget::EventTarget;
+use crate::dom::fontface::FontFace;
+use crate::dom::fontfacesetloadevent::FontFaceSetLoadEvent;
+use crate::dom::promise::Promise;
+use crate::dom::window::Window;
+use crate::task_source::TaskSource;
+use dom_struct::dom_struct;
+use gfx_traits::WebFontLoadResult;
+use script_layout_interface::message::{Msg, WebFontSource};
+use servo_arc::Arc;
+use servo_atoms::Atom;
+use std::rc::Rc;
+use style::properties::{parse_one_declaration_into, PropertyDeclaration};
+use style::properties::{Importance, PropertyDeclarationBlock, PropertyId};
+use style::properties::{ShorthandId, SourcePropertyDeclaration};
+use style::stylesheets::{Stylesheet, StylesheetInDocument};
+use style::values::computed::font::{FamilyName, SingleFontFamily};
+use style::values::specified::font::FontFamily;
+use style_traits::ParsingMode;
+
+/// A promise returned by `FontFaceSet.load()`, settled once all the font
+/// faces it is waiting for are.
+#[derive(JSTraceable, MallocSizeOf)]
+#[must_root]
+struct PendingSetLoad {
+    #[ignore_malloc_size_of = "promises are hard"]
+    promise: Rc<Promise>,
+    font_faces: Vec<Dom<FontFace>>,
+}
+
+#[dom_struct]
+pub struct FontFaceSet {
+    eventtarget: EventTarget,
+    /// The font faces connected to `@font-face` rules, in document order.
+    css_font_faces: DomRefCell<Vec<Dom<FontFace>>>,
+    /// The font faces added by script, in insertion order.
+    non_css_font_faces: DomRefCell<Vec<Dom<FontFace>>>,
+    /// <https://drafts.csswg.org/css-font-loading/#dom-fontfaceset-loadingfonts-slot>
+    loading_fonts: DomRefCell<Vec<Dom<FontFace>>>,
+    /// <https://drafts.csswg.org/css-font-loading/#dom-fontfaceset-loadedfonts-slot>
+    loaded_fonts: DomRefCell<Vec<Dom<FontFace>>>,
+    /// <https://drafts.csswg.org/css-font-loading/#dom-fontfaceset-failedfonts-slot>
+    failed_fonts: DomRefCell<Vec<Dom<FontFace>>>,
+    /// <https://drafts.csswg.org/css-font-loading/#dom-fontfaceset-readypromise-slot>
+    #[ignore_malloc_size_of = "promises are hard"]
+    ready_promise: DomRefCell<Rc<Promise>>,
+    /// The font faces of this document that the font cache is loading, in the
+    /// order the loads were requested, which is the order they complete in.
+    pending_font_cache_loads: DomRefCell<Vec<Dom<FontFace>>>,
+    pending_set_loads: DomRefCell<Vec<PendingSetLoad>>,
+}
+
+impl FontFaceSet {
+    fn new_inherited(window: &Window) -> FontFaceSet {
+        FontFaceSet {
+            eventtarget: EventTarget::new_inherited(),
+            css_font_faces: DomRefCell::new(vec![]),
+            non_css_font_faces: DomRefCell::new(vec![]),
+            loading_fonts: DomRefCell::new(vec![]),
+            loaded_fonts: DomRefCell::new(vec![]),
+            failed_fonts: DomRefCell::new(vec![]),
+            ready_promise: DomRefCell::new(Promise::new(window.upcast())),
+            pending_font_cache_loads: DomRefCell::new(vec![]),
+            pending_set_loads: DomRefCell::new(vec![]),
+        }
+    }
+
+    pub fn new(window: &Window) -> DomRoot<FontFaceSet> {
+        let set = reflect_dom_object(
+            Box::new(FontFaceSet::new_inherited(window)),
+            window,
+            FontFaceSetBinding::Wrap,
+        );
+        set.switch_to_loaded_if_possible();
+        set
+    }
+
+    fn font_faces(&self) -> Vec<DomRoot<FontFace>> {
+        self.css_font_faces
+            .borrow()
+            .iter()
+            .chain(self.non_css_font_faces.borrow().iter())
+            .map(|face| DomRoot::from_ref(&**face))
+            .collect()
+    }
+
+    /// Adds the font faces of the `@font-face` rules of a style sheet that was
+    /// just added to the document, mirroring what layout does with it.
+    pub fn add_css_font_faces(&self, stylesheet: &Arc<Stylesheet>) {
+        let window = self.global();
+        let window = window.as_window();
+        let document = window.Document();
+        let device = document.device();
+        let guard = document.style_shared_lock().read();
+        if !stylesheet.is_effective_for_device(&device, &guard) {
+            return;
+        }
+        let mut new_faces = vec![];
+        stylesheet.effective_font_face_rules(&device, &guard, |rule| {
+            if let Some(font_face) = rule.font_face() {
+                new_faces.push(FontFace::new_css_connected(
+                    window,
+                    font_face.family(),
+                    font_face.effective_sources(),
+                    stylesheet.clone(),
+                ));
+            }
+        });
+        for face in new_faces {
+            self.css_font_faces.borrow_mut().push(Dom::from_ref(&*face));
+            self.pending_font_cache_loads
+                .borrow_mut()
+                .push(Dom::from_ref(&*face));
+            face.add_to_set(self);
+            self.font_face_started_loading(&face);
+        }
+    }
+
+    /// Removes the font faces connected to the `@font-face` rules of a style
+    /// sheet that was removed from the document.
+    pub fn remove_css_font_faces(&self, stylesheet: &Arc<Stylesheet>) {
+        let removed: Vec<_> = self
+            .css_font_faces
+            .borrow()
+            .iter()
+            .filter(|face| face.is_css_connected_to(stylesheet))
+            .map(|face| DomRoot::from_ref(&**face))
+            .collect();
+        for face in removed {
+            self.remove_font_face(&face);
+        }
+    }
+
+    fn remove_font_face(&self, face: &FontFace) {
+        for list in &[
+            &self.css_font_faces,
+            &self.non_css_font_faces,
+            &self.loading_fonts,
+            &self.loaded_fonts,
+            &self.failed_fonts,
+        ] {
+            list.borrow_mut().retain(|other| &**other != face);
+        }
+        face.remove_from_set(self);
+        self.switch_to_loaded_if_possible();
+    }
+
+    /// Asks layout to load a font face created by script.
+    pub fn request_load(&self, face: &FontFace, family: FamilyName, source: WebFontSource) {
+        self.pending_font_cache_loads
+            .borrow_mut()
+            .push(Dom::from_ref(face));
+        let window = self.global();
+        let _ = window
+            .as_window()
+            .layout_chan()
+            .send(Msg::LoadWebFont(family, source));
+    }
+
+    /// Settles the oldest font face waiting on the font cache for the family
+    /// that just finished loading.
+    #[allow(unrooted_must_root)]
+    pub fn handle_web_font_loaded(&self, result: WebFontLoadResult) {
+        let face = {
+            let mut pending = self.pending_font_cache_loads.borrow_mut();
+            match pending
+                .iter()
+                .position(|face| face.has_family(&result.family_name))
+            {
+                Some(index) => DomRoot::from_ref(&*pending.remove(index)),
+                None => return,
+            }
+        };
+        face.finish_load(result.loaded);
+    }
+
+    /// <https://drafts.csswg.org/css-font-loading/#fontfaceset-pending-on-the-environment>
+    fn is_pending_on_the_environment(&self) -> bool {
+        let window = self.global();
+        window.as_window().Document().ReadyState() != DocumentReadyState::Complete
+    }
+
+    /// Called when the document is done loading, since font face sets are
+    /// pending on the environment until then.
+    pub fn handle_document_loaded(&self) {
+        self.switch_to_loaded_if_possible();
+    }
+
+    /// Called when a font face of this set starts loading.
+    ///
+    /// <https://drafts.csswg.org/css-font-loading/#fontfaceset-switch-the-fontfaceset-to-loading>
+    pub fn font_face_started_loading(&self, face: &FontFace) {
+        let was_loading = !self.loading_fonts.borrow().is_empty();
+        self.loading_fonts.borrow_mut().push(Dom::from_ref(face));
+        if was_loading {
+            return;
+        }
+        if self.ready_promise.borrow().is_fulfilled() {
+            *self.ready_promise.borrow_mut() = Promise::new(&self.global());
+        }
+        self.queue_load_event(atom!("loading"), vec![]);
+    }
+
+    /// Called when a font face of this set finishes loading, successfully or
+    /// not.
+    pub fn font_face_settled(&self, face: &FontFace) {
+        let was_loading = {
+            let mut loading_fonts = self.loading_fonts.borrow_mut();
+            let len = loading_fonts.len();
+            loading_fonts.retain(|other| &**other != face);
+            loading_fonts.len() != len
+        };
+        if was_loading {
+            let list = if face.status() == FontFaceLoadStatus::Loaded {
+                &self.loaded_fonts
+            } else {
+                &self.failed_fonts
+            };
+            list.borrow_mut().push(Dom::from_ref(face));
+        }
+        self.settle_pending_set_loads();
+        self.switch_to_loaded_if_possible();
+    }
+
+    /// <https://drafts.csswg.org/css-font-loading/#fontfaceset-switch-the-fontfaceset-to-loaded>
+    fn switch_to_loaded_if_possible(&self) {
+        if !self.loading_fonts.borrow().is_empty() || self.is_pending_on_the_environment() {
+            return;
+        }
+        if self.ready_promise.borrow().is_fulfilled() {
+            return;
+        }
+        self.ready_promise
+            .borrow()
+            .resolve_native(&DomRoot::from_ref(self));
+        let loaded_fonts: Vec<_> = self
+            .loaded_fonts
+            .borrow()
+            .iter()
+            .map(|face| DomRoot::from_ref(&**face))
+            .collect();
+        let failed_fonts: Vec<_> = self
+            .failed_fonts
+            .borrow()
+            .iter()
+            .map(|face| DomRoot::from_ref(&**face))
+            .collect();
+        self.loaded_fonts.borrow_mut().clear();
+        self.failed_fonts.borrow_mut().clear();
+        self.queue_load_event(atom!("loadingdone"), loaded_fonts);
+        if !failed_fonts.is_empty() {
+            self.queue_load_event(atom!("loadingerror"), failed_fonts);
+        }
+    }
+
+    #[allow(unrooted_must_root)]
+    fn settle_pending_set_loads(&self) {
+        let settled: Vec<_> = {
+            let mut pending_set_loads = self.pending_set_loads.borrow_mut();
+            let (settled, pending): (Vec<_>, Vec<_>) =
+                pending_set_loads.drain(..).partition(|load| {
+                    load.font_faces.iter().all(|face| {
+                        face.status() == FontFaceLoadStatus::Loaded ||
+                            face.status() == FontFaceLoadStatus::Error
+                    })
+                });
+            *pending_set_loads = pending;
+            settled
+        };
+        for load in settled {
+            let faces: Vec<_> = load
+                .font_faces
+                .iter()
+                .map(|face| DomRoot::from_ref(&**face))
+                .collect();
+            if faces
+                .iter()
+                .any(|face| face.status() == FontFaceLoadStatus::Error)
+            {
+                load.promise.reject_error(Error::Network);
+            } else {
+                load.promise.resolve_native(&faces);
+            }
+        }
+    }
+
+    fn queue_load_event(&self, type_: Atom, font_faces: Vec<DomRoot<FontFace>>) {
+        let this = Trusted::new(self);
+        let font_faces: Vec<_> = font_faces
+            .iter()
+            .map(|face| Trusted::new(&**face))
+            .collect();
+        let global = self.global();
+        let window = global.as_window();
+        let _ = window.task_manager().dom_manipulation_task_source().queue(
+            task!(fire_font_face_set_load_event: move || {
+                let this = this.root();
+                let font_faces: Vec<_> = font_faces.iter().map(|face| face.root()).collect();
+                let event = FontFaceSetLoadEvent::new(
+                    this.global().as_window(),
+                    type_,
+                    EventBubbles::DoesNotBubble,
+                    EventCancelable::NotCancelable,
+                    font_faces,
+                );
+                event.upcast::<Event>().fire(this.upcast());
+            }),
+            window.upcast(),
+        );
+    }
+
+    /// <https://drafts.csswg.org/css-font-loading/#find-the-matching-font-faces>
+    ///
+    /// Font faces are only matched by family, since the font cache doesn't
+    /// know about the other descriptors. The text is ignored for the same
+    /// reason with respect to `unicode-range`.
+    fn find_matching_font_faces(&self, font: &str) -> Fallible<Vec<DomRoot<FontFace>>> {
+        // Step 1.
+        let window = self.global();
+        let document = window.as_window().Document();
+        let mut declarations = SourcePropertyDeclaration::new();
+        parse_one_declaration_into(
+            &mut declarations,
+            PropertyId::Shorthand(ShorthandId::Font),
+            font,
+            &document.base_url(),
+            None,
+            ParsingMode::DEFAULT,
+            document.quirks_mode(),
+        )
+        .map_err(|()| Error::Syntax)?;
+        let mut block = PropertyDeclarationBlock::new();
+        block.extend(declarations.drain(), Importance::Normal);
+
+        // Step 2.
+        let families: Vec<_> = block
+            .declarations()
+            .iter()
+            .filter_map(|declaration| match *declaration {
+                PropertyDeclaration::FontFamily(FontFamily::Values(ref list)) => Some(
+                    list.iter()
+                        .filter_map(|family| match *family {
+                            SingleFontFamily::FamilyName(ref name) => {
+                                Some(name.name.to_lowercase())
+                            },
+                            SingleFontFamily::Generic(_) => None,
+                        })
+                        .collect::<Vec<_>>(),
+                ),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        if families.is_empty() {
+            return Err(Error::Syntax);
+        }
+
+        // Steps 3-5.
+        let font_faces = self.font_faces();
+        Ok(families
+            .iter()
+            .flat_map(|family| {
+                font_faces
+                    .iter()
+                    .filter(move |face| face.has_family(family))
+                    .cloned()
+            })
+            .collect())
+    }
+}
+
+impl FontFaceSetMethods for FontFaceSet {
+    // https://drafts.csswg.org/css-font-loading/#dom-fontfaceset-add
+    fn Add(&self, font: &FontFace) -> Fallible<DomRoot<FontFaceSet>> {
+        // Step 1.
+        if self.Has(font) {
+            return Ok(DomRoot::from_ref(self));
+        }
+        // Step 2.
+        if font.is_css_connected() {
+            return Err(Error::InvalidModification);
+        }
+        // Step 3.
+        self.non_css_font_faces
+            .borrow_mut()
+            .push(Dom::from_ref(font));
+        font.add_to_set(self);
+        // Step 4.
+        if font.status() == FontFaceLoadStatus::Loading {
+            self.font_face_started_loading(font);
+        }
+        Ok(DomRoot::from_ref(self))
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontfaceset-delete
+    fn Delete(&self, font: &FontFace) -> bool {
+        // Step 1.
+        if font.is_css_connected() {
+            return false;
+        }
+        // Steps 2-3.
+        let present = self.Has(font);
+        self.remove_font_face(font);
+        present
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontfaceset-clear
+    fn Clear(&self) {
+        let faces: Vec<_> = self
+            .non_css_font_faces
+            .borrow()
+            .iter()
+            .map(|face| DomRoot::from_ref(&**face))
+            .collect();
+        for face in faces {
+            self.remove_font_face(&face);
+        }
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontfaceset-has
+    fn Has(&self, font: &FontFace) -> bool {
+        self.font_faces().iter().any(|face| &**face == font)
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontfaceset-size
+    fn Size(&self) -> u32 {
+        (self.css_font_faces.borrow().len() + self.non_css_font_faces.borrow().len()) as u32
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontfaceset-onloading
+    event_handler!(loading, GetOnloading, SetOnloading);
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontfaceset-onloadingdone
+    event_handler!(loadingdone, GetOnloadingdone, SetOnloadingdone);
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontfaceset-onloadingerror
+    event_handler!(loadingerror, GetOnloadingerror, SetOnloadingerror);
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontfaceset-load
+    #[allow(unrooted_must_root)]
+    fn Load(&self, font: DOMString, _text: DOMString, comp: InCompartment) -> Rc<Promise> {
+        // Step 1.
+        let promise = Promise::new_in_current_compartment(&self.global(), comp);
+        // Step 3.
+        let font_faces = match self.find_matching_font_faces(&font) {
+            Ok(font_faces) => font_faces,
+            Err(error) => {
+                promise.reject_error(error);
+                return promise;
+            },
+        };
+        // Step 4.
+        for face in &font_faces {
+            face.Load();
+        }
+        self.pending_set_loads.borrow_mut().push(PendingSetLoad {
+            promise: promise.clone(),
+            font_faces: font_faces
+                .iter()
+                .map(|face| Dom::from_ref(&**face))
+                .collect(),
+        });
+        self.settle_pending_set_loads();
+        promise
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontfaceset-check
+    fn Check(&self, font: DOMString, _text: DOMString) -> Fallible<bool> {
+        // Steps 1-4.
+        let font_faces = self.find_matching_font_faces(&font)?;
+        // Steps 5-6.
+        Ok(font_faces
+            .iter()
+            .all(|face| face.Status() == FontFaceLoadStatus::Loaded))
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontfaceset-ready
+    fn Ready(&self) -> Rc<Promise> {
+        self.ready_promise.borrow().clone()
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontfaceset-status
+    fn Status(&self) -> FontFaceSetLoadStatus {
+        if self.loading_fonts.borrow().is_empty() {
+            FontFaceSetLoadStatus::Loaded
+        } else {
+            FontFaceSetLoadStatus::Loading
+        }
+    }
+}
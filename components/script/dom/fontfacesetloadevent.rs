@@ -0,0 +1,93 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::bindings::codegen::Bindings::EventBinding::EventMethods;
+use crate::dom::bindings::codegen::Bindings::FontFaceSetLoadEventBinding;
+use crate::dom::bindings::codegen::Bindings::FontFaceSetLoadEventBinding::FontFaceSetLoadEventMethods;
+use crate::dom::bindings::error::Fallible;
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::reflector::reflect_dom_object;
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::event::{Event, EventBubbles, EventCancelable};
+use crate::dom::fontface::FontFace;
+use crate::dom::window::Window;
+use crate::script_runtime::JSContext;
+use dom_struct::dom_struct;
+use js::conversions::ToJSValConvertible;
+use js::jsval::{JSVal, UndefinedValue};
+use servo_atoms::Atom;
+
+#[dom_struct]
+pub struct FontFaceSetLoadEvent {
+    event: Event,
+    font_faces: Vec<Dom<FontFace>>,
+}
+
+impl FontFaceSetLoadEvent {
+    fn new_inherited(font_faces: &[DomRoot<FontFace>]) -> FontFaceSetLoadEvent {
+        FontFaceSetLoadEvent {
+            event: Event::new_inherited(),
+            font_faces: font_faces
+                .iter()
+                .map(|face| Dom::from_ref(&**face))
+                .collect(),
+        }
+    }
+
+    pub fn new(
+        window: &Window,
+        type_: Atom,
+        bubbles: EventBubbles,
+        cancelable: EventCancelable,
+        font_faces: Vec<DomRoot<FontFace>>,
+    ) -> DomRoot<FontFaceSetLoadEvent> {
+        let ev = reflect_dom_object(
+            Box::new(FontFaceSetLoadEvent::new_inherited(&font_faces)),
+            window,
+            FontFaceSetLoadEventBinding::Wrap,
+        );
+        ev.upcast::<Event>()
+            .init_event(type_, bool::from(bubbles), bool::from(cancelable));
+        ev
+    }
+
+    // https://drafts.csswg.org/css-font-loading/#dom-fontfacesetloadevent-fontfacesetloadevent
+    pub fn Constructor(
+        window: &Window,
+        type_: DOMString,
+        init: &FontFaceSetLoadEventBinding::FontFaceSetLoadEventInit,
+    ) -> Fallible<DomRoot<FontFaceSetLoadEvent>> {
+        Ok(FontFaceSetLoadEvent::new(
+            window,
+            Atom::from(type_),
+            EventBubbles::from(init.parent.bubbles),
+            EventCancelable::from(init.parent.cancelable),
+            init.fontfaces.clone(),
+        ))
+    }
+}
+
+impl FontFaceSetLoadEventMethods for FontFaceSetLoadEvent {
+    // https://drafts.csswg.org/css-font-loading/#dom-fontfacesetloadevent-fontfaces
+    #[allow(unsafe_code)]
+    fn Fontfaces(&self, cx: JSContext) -> JSVal {
+        let font_faces: Vec<_> = self
+            .font_faces
+            .iter()
+            .map(|face| DomRoot::from_ref(&**face))
+            .collect();
+        // TODO: This should be a frozen array that stays the same object.
+        unsafe {
+            rooted!(in(*cx) let mut font_faces_value = UndefinedValue());
+            font_faces.to_jsval(*cx, font_faces_value.handle_mut());
+            font_faces_value.get()
+        }
+    }
+
+    // https://dom.spec.whatwg.org/#dom-event-istrusted
+    fn IsTrusted(&self) -> bool {
+        self.event.IsTrusted()
+    }
+}
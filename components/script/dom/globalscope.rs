@@ -22,6 +22,7 @@ use crate::dom::eventsource::EventSource;
 use crate::dom::eventtarget::EventTarget;
 use crate::dom::paintworkletglobalscope::PaintWorkletGlobalScope;
 use crate::dom::performance::Performance;
+use crate::dom::webglrenderingcontext::WebGLCommandSender;
 use crate::dom::window::Window;
 use crate::dom::workerglobalscope::WorkerGlobalScope;
 use crate::dom::workletglobalscope::WorkletGlobalScope;
@@ -405,6 +406,17 @@ impl GlobalScope {
         unreachable!();
     }
 
+    /// The channel to the WebGL thread, if this global can create WebGL contexts.
+    pub(crate) fn webgl_chan(&self) -> Option<WebGLCommandSender> {
+        if let Some(window) = self.downcast::<Window>() {
+            return window.webgl_chan();
+        }
+        if let Some(worker) = self.downcast::<DedicatedWorkerGlobalScope>() {
+            return worker.webgl_chan();
+        }
+        None
+    }
+
     /// Get the [base url](https://html.spec.whatwg.org/multipage/#api-base-url)
     /// for this global scope.
     pub fn api_base_url(&self) -> ServoUrl {
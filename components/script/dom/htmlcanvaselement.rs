@@ -3,15 +3,19 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
 use crate::dom::attr::Attr;
+use crate::dom::bindings::callback::ExceptionHandling;
 use crate::dom::bindings::cell::DomRefCell;
 use crate::dom::bindings::codegen::Bindings::HTMLCanvasElementBinding;
 use crate::dom::bindings::codegen::Bindings::HTMLCanvasElementBinding::{
-    HTMLCanvasElementMethods, RenderingContext,
+    BlobCallback, HTMLCanvasElementMethods, RenderingContext,
 };
 use crate::dom::bindings::codegen::Bindings::WebGLRenderingContextBinding::WebGLContextAttributes;
+use crate::dom::bindings::codegen::UnionTypes::HTMLCanvasElementOrOffscreenCanvas;
 use crate::dom::bindings::conversions::ConversionResult;
-use crate::dom::bindings::error::{Error, Fallible};
+use crate::dom::bindings::error::{Error, ErrorResult, Fallible};
 use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::num::Finite;
+use crate::dom::bindings::refcounted::Trusted;
 use crate::dom::bindings::reflector::DomObject;
 use crate::dom::bindings::root::{Dom, DomRoot, LayoutDom};
 use crate::dom::bindings::str::{DOMString, USVString};
@@ -22,28 +26,36 @@ use crate::dom::document::Document;
 use crate::dom::element::{AttributeMutation, Element, RawLayoutElementHelpers};
 use crate::dom::globalscope::GlobalScope;
 use crate::dom::htmlelement::HTMLElement;
-use crate::dom::node::{window_from_node, Node};
+use crate::dom::mediastream::MediaStream;
+use crate::dom::mediastreamtrack::MediaStreamTrack;
+use crate::dom::node::{window_from_node, Node, NodeDamage};
+use crate::dom::offscreencanvas::{LayoutOffscreenCanvasHelpers, OffscreenCanvas};
 use crate::dom::virtualmethods::VirtualMethods;
 use crate::dom::webgl2renderingcontext::WebGL2RenderingContext;
 use crate::dom::webglrenderingcontext::{
     LayoutCanvasWebGLRenderingContextHelpers, WebGLRenderingContext,
 };
+use crate::image_encoder::{self, EncodedFile, EncodedImageType};
 use crate::script_runtime::JSContext;
+use crate::task_source::{TaskSource, TaskSourceName};
 use base64;
 use canvas_traits::canvas::{CanvasId, CanvasMsg, FromScriptMsg};
 use canvas_traits::webgl::{GLContextAttributes, WebGLVersion};
 use dom_struct::dom_struct;
 use euclid::default::{Rect, Size2D};
 use html5ever::{LocalName, Prefix};
-use image::png::PNGEncoder;
-use image::ColorType;
 use ipc_channel::ipc::IpcSharedMemory;
 use js::error::throw_type_error;
 use js::rust::HandleValue;
 use profile_traits::ipc;
 use script_layout_interface::{HTMLCanvasData, HTMLCanvasDataSource};
 use servo_config::pref;
-use std::cell::Ref;
+use servo_media::streams::registry::MediaStreamId;
+use servo_media::streams::MediaStreamType;
+use servo_media::ServoMedia;
+use std::cell::{Cell, Ref};
+use std::collections::HashMap;
+use std::rc::Rc;
 use style::attr::{AttrValue, LengthOrPercentageOrAuto};
 
 const DEFAULT_WIDTH: u32 = 300;
@@ -55,12 +67,31 @@ pub enum CanvasContext {
     Context2d(Dom<CanvasRenderingContext2D>),
     WebGL(Dom<WebGLRenderingContext>),
     WebGL2(Dom<WebGL2RenderingContext>),
+    Placeholder(Dom<OffscreenCanvas>),
+}
+
+/// A video track fed with the frames of a canvas.
+#[derive(JSTraceable, MallocSizeOf)]
+struct CanvasCapture {
+    #[ignore_malloc_size_of = "defined in servo-media"]
+    id: MediaStreamId,
+    /// The minimum time between two frames, from `frameRequestRate`.
+    min_frame_interval_ns: Option<u64>,
+    last_frame_ns: Cell<Option<u64>>,
 }
 
 #[dom_struct]
 pub struct HTMLCanvasElement {
     htmlelement: HTMLElement,
     context: DomRefCell<Option<CanvasContext>>,
+    /// Callbacks of `toBlob()` calls whose serialization is in progress.
+    #[ignore_malloc_size_of = "Rc"]
+    blob_callbacks: DomRefCell<HashMap<usize, Rc<BlobCallback>>>,
+    next_blob_callback_id: Cell<usize>,
+    /// The streams created by `captureStream()`.
+    captures: DomRefCell<Vec<CanvasCapture>>,
+    /// Whether a task to capture the next frame is queued.
+    frame_capture_pending: Cell<bool>,
 }
 
 impl HTMLCanvasElement {
@@ -72,6 +103,10 @@ impl HTMLCanvasElement {
         HTMLCanvasElement {
             htmlelement: HTMLElement::new_inherited(local_name, prefix, document),
             context: DomRefCell::new(None),
+            blob_callbacks: DomRefCell::new(HashMap::new()),
+            next_blob_callback_id: Cell::new(0),
+            captures: DomRefCell::new(Vec::new()),
+            frame_capture_pending: Cell::new(false),
         }
     }
 
@@ -97,6 +132,7 @@ impl HTMLCanvasElement {
                 CanvasContext::Context2d(ref context) => context.set_bitmap_dimensions(size),
                 CanvasContext::WebGL(ref context) => context.recreate(size),
                 CanvasContext::WebGL2(ref context) => context.recreate(size),
+                CanvasContext::Placeholder(_) => {},
             }
         }
     }
@@ -108,9 +144,95 @@ impl HTMLCanvasElement {
     pub fn origin_is_clean(&self) -> bool {
         match *self.context.borrow() {
             Some(CanvasContext::Context2d(ref context)) => context.origin_is_clean(),
+            Some(CanvasContext::Placeholder(ref canvas)) => canvas.origin_is_clean(),
             _ => true,
         }
     }
+
+    /// The bitmap in RGBA8 format without premultiplied alpha, or `None` if
+    /// it can't be read back.
+    fn get_image_data(&self) -> Option<Vec<u8>> {
+        let data = match *self.context.borrow() {
+            Some(CanvasContext::Context2d(ref context)) => {
+                context.get_rect(Rect::from_size(self.get_size()))
+            },
+            Some(CanvasContext::WebGL(ref context)) => context.get_image_data(self.get_size())?,
+            Some(CanvasContext::WebGL2(ref context)) => {
+                context.base_context().get_image_data(self.get_size())?
+            },
+            Some(CanvasContext::Placeholder(ref canvas)) => canvas.get_image_data(),
+            None => {
+                // Each pixel is fully-transparent black.
+                vec![0; (self.Width() * self.Height() * 4) as usize]
+            },
+        };
+        Some(data)
+    }
+
+    fn invoke_blob_callback(&self, id: usize, file: Option<EncodedFile>) {
+        let callback = self
+            .blob_callbacks
+            .borrow_mut()
+            .remove(&id)
+            .expect("Unknown blob callback");
+        let blob = file.map(|file| file.into_blob(&self.global()));
+        let _ = callback.Call__(blob.as_ref().map(|blob| &**blob), ExceptionHandling::Report);
+    }
+
+    /// Called by the rendering contexts when the bitmap changes, so that
+    /// the streams capturing this canvas get a new frame.
+    pub fn content_changed(&self) {
+        if self.captures.borrow().is_empty() || self.frame_capture_pending.get() {
+            return;
+        }
+        self.frame_capture_pending.set(true);
+        let this = Trusted::new(self);
+        let global = self.global();
+        let task_source = global.dom_manipulation_task_source();
+        let canceller = global.task_canceller(TaskSourceName::DOMManipulation);
+        let _ = task_source.queue_with_canceller(
+            task!(capture_canvas_frame: move || {
+                this.root().capture_frame();
+            }),
+            &canceller,
+        );
+    }
+
+    // https://w3c.github.io/mediacapture-fromelement/#dom-htmlcanvaselement-capturestream
+    fn capture_frame(&self) {
+        self.frame_capture_pending.set(false);
+        // Frames stop being captured once the canvas is tainted.
+        if !self.origin_is_clean() || !self.is_valid() {
+            return;
+        }
+        let now = time::precise_time_ns();
+        let captures = self.captures.borrow();
+        let due: Vec<_> = captures
+            .iter()
+            .filter(|capture| {
+                match (capture.min_frame_interval_ns, capture.last_frame_ns.get()) {
+                    // TODO: a rate of zero only captures frames on
+                    // `CanvasCaptureMediaStreamTrack.requestFrame()`.
+                    (Some(0), _) => false,
+                    (Some(interval), Some(last)) => now - last >= interval,
+                    _ => true,
+                }
+            })
+            .collect();
+        if due.is_empty() {
+            return;
+        }
+        let data = match self.get_image_data() {
+            Some(data) => data,
+            None => return,
+        };
+        let size = self.get_size();
+        let media = ServoMedia::get().unwrap();
+        for capture in due {
+            capture.last_frame_ns.set(Some(now));
+            media.push_video_frame(&capture.id, size.width, size.height, data.clone());
+        }
+    }
 }
 
 pub trait LayoutHTMLCanvasElementHelpers {
@@ -135,6 +257,9 @@ impl LayoutHTMLCanvasElementHelpers for LayoutDom<HTMLCanvasElement> {
                 Some(&CanvasContext::WebGL2(ref context)) => {
                     context.to_layout().canvas_data_source()
                 },
+                Some(&CanvasContext::Placeholder(ref canvas)) => {
+                    canvas.to_layout().canvas_data_source()
+                },
                 None => HTMLCanvasDataSource::Image(None),
             };
 
@@ -177,11 +302,12 @@ impl LayoutHTMLCanvasElementHelpers for LayoutDom<HTMLCanvasElement> {
     fn get_canvas_id_for_layout(&self) -> CanvasId {
         unsafe {
             let canvas = &*self.unsafe_get();
-            if let &Some(CanvasContext::Context2d(ref context)) = canvas.context.borrow_for_layout()
-            {
-                context.to_layout().get_canvas_id()
-            } else {
-                CanvasId(0)
+            match canvas.context.borrow_for_layout() {
+                &Some(CanvasContext::Context2d(ref context)) => context.to_layout().get_canvas_id(),
+                &Some(CanvasContext::Placeholder(ref canvas)) => {
+                    canvas.to_layout().get_canvas_id_for_layout()
+                },
+                _ => CanvasId(0),
             }
         }
     }
@@ -220,7 +346,14 @@ impl HTMLCanvasElement {
         let window = window_from_node(self);
         let size = self.get_size();
         let attrs = Self::get_gl_attributes(cx, options)?;
-        let context = WebGLRenderingContext::new(&window, self, WebGLVersion::WebGL1, size, attrs)?;
+        let canvas = HTMLCanvasElementOrOffscreenCanvas::HTMLCanvasElement(DomRoot::from_ref(self));
+        let context = WebGLRenderingContext::new(
+            window.upcast(),
+            &canvas,
+            WebGLVersion::WebGL1,
+            size,
+            attrs,
+        )?;
         *self.context.borrow_mut() = Some(CanvasContext::WebGL(Dom::from_ref(&*context)));
         Some(context)
     }
@@ -242,7 +375,8 @@ impl HTMLCanvasElement {
         let window = window_from_node(self);
         let size = self.get_size();
         let attrs = Self::get_gl_attributes(cx, options)?;
-        let context = WebGL2RenderingContext::new(&window, self, size, attrs)?;
+        let canvas = HTMLCanvasElementOrOffscreenCanvas::HTMLCanvasElement(DomRoot::from_ref(self));
+        let context = WebGL2RenderingContext::new(window.upcast(), &canvas, size, attrs)?;
         *self.context.borrow_mut() = Some(CanvasContext::WebGL2(Dom::from_ref(&*context)));
         Some(context)
     }
@@ -257,7 +391,7 @@ impl HTMLCanvasElement {
     }
 
     #[allow(unsafe_code)]
-    fn get_gl_attributes(cx: JSContext, options: HandleValue) -> Option<GLContextAttributes> {
+    pub fn get_gl_attributes(cx: JSContext, options: HandleValue) -> Option<GLContextAttributes> {
         unsafe {
             match WebGLContextAttributes::new(cx, options) {
                 Ok(ConversionResult::Success(ref attrs)) => Some(From::from(attrs)),
@@ -304,6 +438,7 @@ impl HTMLCanvasElement {
                 // TODO: add a method in WebGL2RenderingContext to get the pixels.
                 return None;
             },
+            Some(&CanvasContext::Placeholder(ref canvas)) => canvas.fetch_placeholder_pixels(),
             None => None,
         };
 
@@ -330,8 +465,12 @@ impl HTMLCanvasElementMethods for HTMLCanvasElement {
         cx: JSContext,
         id: DOMString,
         options: HandleValue,
-    ) -> Option<RenderingContext> {
-        match &*id {
+    ) -> Fallible<Option<RenderingContext>> {
+        if let Some(CanvasContext::Placeholder(_)) = *self.context.borrow() {
+            return Err(Error::InvalidState);
+        }
+
+        let context = match &*id {
             "2d" => self
                 .get_or_init_2d_context()
                 .map(RenderingContext::CanvasRenderingContext2D),
@@ -342,15 +481,41 @@ impl HTMLCanvasElementMethods for HTMLCanvasElement {
                 .get_or_init_webgl2_context(cx, options)
                 .map(RenderingContext::WebGL2RenderingContext),
             _ => None,
+        };
+        Ok(context)
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-canvas-transfercontroltooffscreen
+    fn TransferControlToOffscreen(&self) -> Fallible<DomRoot<OffscreenCanvas>> {
+        // Step 1.
+        if self.context.borrow().is_some() {
+            return Err(Error::InvalidState);
         }
+
+        // Steps 2-3.
+        let offscreen_canvas = OffscreenCanvas::new(
+            &self.global(),
+            self.Width() as u64,
+            self.Height() as u64,
+            Some(self),
+        );
+
+        // Step 4.
+        *self.context.borrow_mut() = Some(CanvasContext::Placeholder(Dom::from_ref(
+            &*offscreen_canvas,
+        )));
+        self.upcast::<Node>().dirty(NodeDamage::OtherNodeDamage);
+
+        // Step 5.
+        Ok(offscreen_canvas)
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-canvas-todataurl
     fn ToDataURL(
         &self,
         _context: JSContext,
-        _mime_type: Option<DOMString>,
-        _quality: HandleValue,
+        mime_type: Option<DOMString>,
+        quality: HandleValue,
     ) -> Fallible<USVString> {
         // Step 1.
         if !self.origin_is_clean() {
@@ -363,41 +528,113 @@ impl HTMLCanvasElementMethods for HTMLCanvasElement {
         }
 
         // Step 3.
-        let file = match *self.context.borrow() {
-            Some(CanvasContext::Context2d(ref context)) => {
-                context.get_rect(Rect::from_size(self.get_size()))
-            },
-            Some(CanvasContext::WebGL(ref context)) => {
-                match context.get_image_data(self.get_size()) {
-                    Some(data) => data,
-                    None => return Ok(USVString("data:,".into())),
-                }
-            },
-            Some(CanvasContext::WebGL2(ref context)) => {
-                match context.base_context().get_image_data(self.get_size()) {
-                    Some(data) => data,
-                    None => return Ok(USVString("data:,".into())),
-                }
-            },
-            None => {
-                // Each pixel is fully-transparent black.
-                vec![0; (self.Width() * self.Height() * 4) as usize]
-            },
+        let data = match self.get_image_data() {
+            Some(data) => data,
+            None => return Ok(USVString("data:,".into())),
         };
-
-        // FIXME: Only handle image/png for now.
-        let mut png = Vec::new();
-        // FIXME(nox): https://github.com/PistonDevelopers/image-png/issues/86
-        // FIXME(nox): https://github.com/PistonDevelopers/image-png/issues/87
-        PNGEncoder::new(&mut png)
-            .encode(&file, self.Width(), self.Height(), ColorType::RGBA(8))
-            .unwrap();
-        let mut url = "data:image/png;base64,".to_owned();
+        let image_type = EncodedImageType::from_mime_type(mime_type.as_ref().map(|t| &**t));
+        let file = match image_encoder::encode(
+            &data,
+            self.get_size(),
+            image_type,
+            image_encoder::quality_from_value(quality),
+        ) {
+            Ok(file) => file,
+            Err(()) => return Ok(USVString("data:,".into())),
+        };
+        let mut url = format!("data:{};base64,", image_type.as_mime_type());
         // FIXME(nox): Should this use base64::URL_SAFE?
         // FIXME(nox): https://github.com/alicemaz/rust-base64/pull/56
-        base64::encode_config_buf(&png, base64::STANDARD, &mut url);
+        base64::encode_config_buf(&file, base64::STANDARD, &mut url);
         Ok(USVString(url))
     }
+
+    // https://html.spec.whatwg.org/multipage/#dom-canvas-toblob
+    fn ToBlob(
+        &self,
+        _context: JSContext,
+        callback: Rc<BlobCallback>,
+        mime_type: Option<DOMString>,
+        quality: HandleValue,
+    ) -> ErrorResult {
+        // Step 1.
+        if !self.origin_is_clean() {
+            return Err(Error::Security);
+        }
+
+        // Step 2.
+        let data = if self.Width() == 0 || self.Height() == 0 {
+            None
+        } else {
+            self.get_image_data()
+        };
+
+        let id = self.next_blob_callback_id.get();
+        self.next_blob_callback_id.set(id + 1);
+        self.blob_callbacks.borrow_mut().insert(id, callback);
+        let this = Trusted::new(self);
+
+        // Steps 3-4.
+        let global = self.global();
+        match data {
+            Some(data) => image_encoder::encode_to_blob_in_parallel(
+                &global,
+                data,
+                self.get_size(),
+                EncodedImageType::from_mime_type(mime_type.as_ref().map(|t| &**t)),
+                image_encoder::quality_from_value(quality),
+                move |file| this.root().invoke_blob_callback(id, file),
+            ),
+            None => {
+                let task_source = global.dom_manipulation_task_source();
+                let canceller = global.task_canceller(TaskSourceName::DOMManipulation);
+                let _ = task_source.queue_with_canceller(
+                    task!(invoke_null_blob_callback: move || {
+                        this.root().invoke_blob_callback(id, None);
+                    }),
+                    &canceller,
+                );
+            },
+        }
+        Ok(())
+    }
+
+    // https://w3c.github.io/mediacapture-fromelement/#dom-htmlcanvaselement-capturestream
+    fn CaptureStream(
+        &self,
+        frame_request_rate: Option<Finite<f64>>,
+    ) -> Fallible<DomRoot<MediaStream>> {
+        // Step 1.
+        if !self.origin_is_clean() {
+            return Err(Error::Security);
+        }
+
+        // Step 3.
+        let min_frame_interval_ns = match frame_request_rate {
+            Some(rate) if *rate < 0. => return Err(Error::NotSupported),
+            Some(rate) if *rate == 0. => Some(0),
+            Some(rate) => Some((1e9 / *rate) as u64),
+            None => None,
+        };
+
+        // Steps 2 and 4.
+        let global = self.global();
+        let id = ServoMedia::get()
+            .unwrap()
+            .create_capture_stream(MediaStreamType::Video);
+        let stream = MediaStream::new(&global);
+        let track = MediaStreamTrack::new(&global, id, MediaStreamType::Video);
+        stream.add_track(&track);
+        self.captures.borrow_mut().push(CanvasCapture {
+            id,
+            min_frame_interval_ns,
+            last_frame_ns: Cell::new(None),
+        });
+
+        // The current content of the canvas is the first frame.
+        self.content_changed();
+        Ok(stream)
+    }
 }
 
 impl VirtualMethods for HTMLCanvasElement {
@@ -171,6 +171,18 @@ impl HTMLElementMethods for HTMLElement {
     // https://html.spec.whatwg.org/multipage/#dom-hidden
     make_bool_setter!(SetHidden, "hidden");
 
+    // https://html.spec.whatwg.org/multipage/#dom-draggable
+    fn Draggable(&self) -> bool {
+        self.is_draggable()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-draggable
+    fn SetDraggable(&self, value: bool) {
+        let value = if value { "true" } else { "false" };
+        self.upcast::<Element>()
+            .set_string_attribute(&local_name!("draggable"), DOMString::from(value));
+    }
+
     // https://html.spec.whatwg.org/multipage/#globaleventhandlers
     global_event_handlers!(NoOnload);
 
@@ -664,6 +676,27 @@ impl HTMLElement {
         }
     }
 
+    // https://html.spec.whatwg.org/multipage/#concept-draggable
+    pub fn is_draggable(&self) -> bool {
+        let element = self.upcast::<Element>();
+        let draggable = element.get_string_attribute(&local_name!("draggable"));
+        match &*draggable.to_ascii_lowercase() {
+            "true" => true,
+            "false" => false,
+            // Images and links are draggable by default.
+            _ => match self.upcast::<Node>().type_id() {
+                NodeTypeId::Element(ElementTypeId::HTMLElement(type_id)) => match type_id {
+                    HTMLElementTypeId::HTMLImageElement => true,
+                    HTMLElementTypeId::HTMLAnchorElement => {
+                        element.has_attribute(&local_name!("href"))
+                    },
+                    _ => false,
+                },
+                _ => false,
+            },
+        }
+    }
+
     pub fn supported_prop_names_custom_attr(&self) -> Vec<DOMString> {
         let element = self.upcast::<Element>();
         element
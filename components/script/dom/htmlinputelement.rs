@@ -16,6 +16,7 @@ use crate::dom::bindings::inheritance::Castable;
 use crate::dom::bindings::reflector::DomObject;
 use crate::dom::bindings::root::{Dom, DomRoot, LayoutDom, MutNullableDom};
 use crate::dom::bindings::str::{DOMString, USVString};
+use crate::dom::clipboardevent::ClipboardEventType;
 use crate::dom::compositionevent::CompositionEvent;
 use crate::dom::document::Document;
 use crate::dom::element::{
@@ -50,6 +51,7 @@ use caseless::compatibility_caseless_match_str;
 use dom_struct::dom_struct;
 use embedder_traits::FilterPattern;
 use html5ever::{LocalName, Prefix};
+use keyboard_types::KeyState;
 use msg::constellation_msg::InputMethodType;
 use net_traits::blob_url_store::get_blob_origin;
 use net_traits::filemanager_thread::FileManagerThreadMsg;
@@ -1073,7 +1075,6 @@ impl HTMLInputElement {
         let mut error = None;
 
         let filter = filter_from_accept(&self.Accept());
-        let target = self.upcast::<EventTarget>();
 
         if self.Multiple() {
             let opt_test_paths =
@@ -1124,12 +1125,35 @@ impl HTMLInputElement {
         if let Some(err) = error {
             debug!("Input file select error: {:?}", err);
         } else {
-            let filelist = FileList::new(&window, files);
-            self.filelist.set(Some(&filelist));
+            self.set_selected_files(files);
+        }
+    }
 
-            target.fire_bubbling_event(atom!("input"));
-            target.fire_bubbling_event(atom!("change"));
+    /// Selects the files dropped on a file upload control, as if the user had
+    /// picked them.
+    ///
+    /// <https://html.spec.whatwg.org/multipage/#file-upload-state-(type=file)>
+    pub fn drop_files(&self, mut files: Vec<DomRoot<File>>) {
+        if self.input_type() != InputType::File ||
+            self.upcast::<Element>().disabled_state() ||
+            files.is_empty()
+        {
+            return;
+        }
+        if !self.Multiple() {
+            files.truncate(1);
         }
+        self.set_selected_files(files);
+    }
+
+    fn set_selected_files(&self, files: Vec<DomRoot<File>>) {
+        let window = window_from_node(self);
+        let filelist = FileList::new(&window, files);
+        self.filelist.set(Some(&filelist));
+
+        let target = self.upcast::<EventTarget>();
+        target.fire_bubbling_event(atom!("input"));
+        target.fire_bubbling_event(atom!("change"));
     }
 
     // https://html.spec.whatwg.org/multipage/#value-sanitization-algorithm
@@ -1501,6 +1525,18 @@ impl VirtualMethods for HTMLInputElement {
             self.input_type().is_textual_or_password()
         {
             if let Some(keyevent) = event.downcast::<KeyboardEvent>() {
+                // The clipboard events can cancel the clipboard shortcuts.
+                if let Some(action) = ClipboardEventType::from_shortcut(
+                    KeyState::Down,
+                    keyevent.key(),
+                    keyevent.modifiers(),
+                ) {
+                    if !document_from_node(self).fire_clipboard_event(action) {
+                        event.mark_as_handled();
+                        return;
+                    }
+                }
+
                 // This can't be inlined, as holding on to textinput.borrow_mut()
                 // during self.implicit_submission will cause a panic.
                 let action = self.textinput.borrow_mut().handle_keydown(keyevent);
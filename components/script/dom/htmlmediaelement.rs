@@ -79,6 +79,7 @@ use net_traits::{CoreResourceMsg, FetchChannels, FetchMetadata, FetchResponseLis
 use net_traits::{NetworkError, ResourceFetchTiming, ResourceTimingType};
 use script_layout_interface::HTMLMediaData;
 use servo_config::pref;
+use servo_media::player::audio::AudioRenderer;
 use servo_media::player::frame::{Frame, FrameRenderer};
 use servo_media::player::{PlaybackState, Player, PlayerError, PlayerEvent, SeekLock, StreamType};
 use servo_media::{ClientContextId, ServoMedia, SupportsMediaType};
@@ -326,6 +327,10 @@ pub struct HTMLMediaElement {
     player: DomRefCell<Option<Arc<Mutex<dyn Player>>>>,
     #[ignore_malloc_size_of = "Arc"]
     frame_renderer: Arc<Mutex<MediaFrameRenderer>>,
+    /// The renderer the player hands decoded audio to, when it is routed
+    /// to a MediaElementAudioSourceNode rather than to the audio sink.
+    #[ignore_malloc_size_of = "servo_media"]
+    audio_renderer: DomRefCell<Option<Arc<Mutex<dyn AudioRenderer>>>>,
     /// https://html.spec.whatwg.org/multipage/#show-poster-flag
     show_poster: Cell<bool>,
     /// https://html.spec.whatwg.org/multipage/#dom-media-duration
@@ -413,6 +418,7 @@ impl HTMLMediaElement {
             frame_renderer: Arc::new(Mutex::new(MediaFrameRenderer::new(
                 document.window().get_webrender_api_sender(),
             ))),
+            audio_renderer: Default::default(),
             show_poster: Cell::new(true),
             duration: Cell::new(f64::NAN),
             playback_position: Cell::new(0.),
@@ -1308,6 +1314,29 @@ impl HTMLMediaElement {
         }
     }
 
+    /// Whether the audio of this element is routed to a MediaElementAudioSourceNode.
+    pub fn has_audio_renderer(&self) -> bool {
+        self.audio_renderer.borrow().is_some()
+    }
+
+    /// Route the decoded audio of this element to `audio_renderer`,
+    /// reloading the current resource so that its player picks it up.
+    pub fn set_audio_renderer(&self, audio_renderer: Arc<Mutex<dyn AudioRenderer>>) {
+        *self.audio_renderer.borrow_mut() = Some(audio_renderer);
+        let has_player = match *self.player.borrow() {
+            Some(ref player) => {
+                if let Err(e) = player.lock().unwrap().stop() {
+                    eprintln!("Could not stop player {:?}", e);
+                }
+                true
+            },
+            None => false,
+        };
+        if has_player {
+            self.media_element_load_algorithm();
+        }
+    }
+
     fn setup_media_player(&self, resource: &Resource) -> Result<(), ()> {
         let stream_type = match *resource {
             Resource::Object => {
@@ -1329,6 +1358,7 @@ impl HTMLMediaElement {
             HTMLMediaElementTypeId::HTMLAudioElement => None,
             HTMLMediaElementTypeId::HTMLVideoElement => Some(self.frame_renderer.clone()),
         };
+        let audio_renderer = self.audio_renderer.borrow().as_ref().map(|r| r.clone());
 
         let pipeline_id = window
             .pipeline_id()
@@ -1340,6 +1370,7 @@ impl HTMLMediaElement {
             stream_type,
             action_sender,
             renderer,
+            audio_renderer,
             Box::new(window.get_player_context()),
         );
 
@@ -13,6 +13,7 @@ use crate::dom::bindings::error::ErrorResult;
 use crate::dom::bindings::inheritance::Castable;
 use crate::dom::bindings::root::{DomRoot, LayoutDom, MutNullableDom};
 use crate::dom::bindings::str::DOMString;
+use crate::dom::clipboardevent::ClipboardEventType;
 use crate::dom::compositionevent::CompositionEvent;
 use crate::dom::document::Document;
 use crate::dom::element::RawLayoutElementHelpers;
@@ -36,6 +37,7 @@ use crate::textinput::{
 };
 use dom_struct::dom_struct;
 use html5ever::{LocalName, Prefix};
+use keyboard_types::KeyState;
 use script_traits::ScriptToConstellationChan;
 use std::cell::Cell;
 use std::default::Default;
@@ -552,6 +554,18 @@ impl VirtualMethods for HTMLTextAreaElement {
             document_from_node(self).request_focus(self.upcast());
         } else if event.type_() == atom!("keydown") && !event.DefaultPrevented() {
             if let Some(kevent) = event.downcast::<KeyboardEvent>() {
+                // The clipboard events can cancel the clipboard shortcuts.
+                if let Some(action) = ClipboardEventType::from_shortcut(
+                    KeyState::Down,
+                    kevent.key(),
+                    kevent.modifiers(),
+                ) {
+                    if !document_from_node(self).fire_clipboard_event(action) {
+                        event.mark_as_handled();
+                        return;
+                    }
+                }
+
                 // This can't be inlined, as holding on to textinput.borrow_mut()
                 // during self.implicit_submission will cause a panic.
                 let action = self.textinput.borrow_mut().handle_keydown(kevent);
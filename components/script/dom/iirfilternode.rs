@@ -0,0 +1,155 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::audionode::AudioNode;
+use crate::dom::baseaudiocontext::BaseAudioContext;
+use crate::dom::bindings::codegen::Bindings::AudioNodeBinding::AudioNodeMethods;
+use crate::dom::bindings::codegen::Bindings::AudioNodeBinding::{
+    ChannelCountMode, ChannelInterpretation,
+};
+use crate::dom::bindings::codegen::Bindings::BaseAudioContextBinding::BaseAudioContextMethods;
+use crate::dom::bindings::codegen::Bindings::IIRFilterNodeBinding::{
+    self, IIRFilterNodeMethods, IIRFilterOptions,
+};
+use crate::dom::bindings::error::{Error, Fallible};
+use crate::dom::bindings::reflector::reflect_dom_object;
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::window::Window;
+use dom_struct::dom_struct;
+use js::rust::CustomAutoRooterGuard;
+use js::typedarray::Float32Array;
+use servo_media::audio::iir_filter_node::IIRFilterNodeOptions;
+use servo_media::audio::node::AudioNodeInit;
+use std::f32;
+use std::f64::consts::PI;
+
+/// The largest number of coefficients of each side of the filter.
+const MAX_COEFFICIENTS: usize = 20;
+
+#[dom_struct]
+pub struct IIRFilterNode {
+    node: AudioNode,
+    feedforward: Vec<f64>,
+    feedback: Vec<f64>,
+}
+
+impl IIRFilterNode {
+    #[allow(unrooted_must_root)]
+    pub fn new_inherited(
+        _: &Window,
+        context: &BaseAudioContext,
+        options: &IIRFilterOptions,
+    ) -> Fallible<IIRFilterNode> {
+        let node_options =
+            options
+                .parent
+                .unwrap_or(2, ChannelCountMode::Max, ChannelInterpretation::Speakers);
+        let feedforward: Vec<f64> = options.feedforward.iter().map(|value| **value).collect();
+        let feedback: Vec<f64> = options.feedback.iter().map(|value| **value).collect();
+        if feedforward.is_empty() || feedforward.len() > MAX_COEFFICIENTS {
+            return Err(Error::NotSupported);
+        }
+        if feedforward.iter().all(|value| *value == 0.) {
+            return Err(Error::InvalidState);
+        }
+        if feedback.is_empty() || feedback.len() > MAX_COEFFICIENTS {
+            return Err(Error::NotSupported);
+        }
+        if feedback[0] == 0. {
+            return Err(Error::InvalidState);
+        }
+        let node = AudioNode::new_inherited(
+            AudioNodeInit::IIRFilterNode(IIRFilterNodeOptions {
+                feedforward: feedforward.clone(),
+                feedback: feedback.clone(),
+            }),
+            context,
+            node_options,
+            1, // inputs
+            1, // outputs
+        )?;
+        Ok(IIRFilterNode {
+            node,
+            feedforward,
+            feedback,
+        })
+    }
+
+    #[allow(unrooted_must_root)]
+    pub fn new(
+        window: &Window,
+        context: &BaseAudioContext,
+        options: &IIRFilterOptions,
+    ) -> Fallible<DomRoot<IIRFilterNode>> {
+        let node = IIRFilterNode::new_inherited(window, context, options)?;
+        Ok(reflect_dom_object(
+            Box::new(node),
+            window,
+            IIRFilterNodeBinding::Wrap,
+        ))
+    }
+
+    pub fn Constructor(
+        window: &Window,
+        context: &BaseAudioContext,
+        options: &IIRFilterOptions,
+    ) -> Fallible<DomRoot<IIRFilterNode>> {
+        IIRFilterNode::new(window, context, options)
+    }
+}
+
+/// Evaluates the polynomial `coefficients[0] + coefficients[1] * z^-1 + ...`
+/// at `z = e^(i * omega)`, and returns its real and imaginary parts.
+fn evaluate_polynomial(coefficients: &[f64], omega: f64) -> (f64, f64) {
+    coefficients
+        .iter()
+        .enumerate()
+        .fold((0., 0.), |(re, im), (k, coefficient)| {
+            let angle = -omega * k as f64;
+            (
+                re + coefficient * angle.cos(),
+                im + coefficient * angle.sin(),
+            )
+        })
+}
+
+impl IIRFilterNodeMethods for IIRFilterNode {
+    #[allow(unsafe_code)]
+    // https://webaudio.github.io/web-audio-api/#dom-iirfilternode-getfrequencyresponse
+    fn GetFrequencyResponse(
+        &self,
+        frequency_hz: CustomAutoRooterGuard<Float32Array>,
+        mut mag_response: CustomAutoRooterGuard<Float32Array>,
+        mut phase_response: CustomAutoRooterGuard<Float32Array>,
+    ) -> Fallible<()> {
+        // Invariant to maintain: No JS code that may touch the arrays should
+        // run whilst we're writing to them.
+        let frequency_hz = unsafe { frequency_hz.as_slice() };
+        let mag_response = unsafe { mag_response.as_mut_slice() };
+        let phase_response = unsafe { phase_response.as_mut_slice() };
+        if mag_response.len() != frequency_hz.len() || phase_response.len() != frequency_hz.len() {
+            return Err(Error::InvalidAccess);
+        }
+
+        let nyquist = *self.node.Context().SampleRate() / 2.;
+        for (i, frequency) in frequency_hz.iter().enumerate() {
+            if !(*frequency >= 0. && *frequency <= nyquist) {
+                mag_response[i] = f32::NAN;
+                phase_response[i] = f32::NAN;
+                continue;
+            }
+            let omega = PI * *frequency as f64 / nyquist as f64;
+            let (numerator_re, numerator_im) = evaluate_polynomial(&self.feedforward, omega);
+            let (denominator_re, denominator_im) = evaluate_polynomial(&self.feedback, omega);
+            mag_response[i] =
+                (numerator_re.hypot(numerator_im) / denominator_re.hypot(denominator_im)) as f32;
+            // The argument of the numerator times the conjugate of the
+            // denominator, which stays within [-pi, pi].
+            phase_response[i] = (numerator_im * denominator_re - numerator_re * denominator_im)
+                .atan2(numerator_re * denominator_re + numerator_im * denominator_im)
+                as f32;
+        }
+        Ok(())
+    }
+}
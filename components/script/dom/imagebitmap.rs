@@ -0,0 +1,438 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::compartments::InCompartment;
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::ImageBitmapBinding;
+use crate::dom::bindings::codegen::Bindings::ImageBitmapBinding::{
+    ImageBitmapMethods, ImageBitmapOptions, ImageBitmapSource,
+};
+use crate::dom::bindings::codegen::Bindings::ImageBitmapBinding::{
+    ImageOrientation, PremultiplyAlpha, ResizeQuality,
+};
+use crate::dom::bindings::error::Error;
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::refcounted::TrustedPromise;
+use crate::dom::bindings::reflector::{reflect_dom_object, DomObject, Reflector};
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::htmlcanvaselement::utils as canvas_utils;
+use crate::dom::promise::Promise;
+use crate::dom::window::Window;
+use crate::task_source::{TaskSource, TaskSourceName};
+use crate::unpremultiplytable::UNPREMULTIPLY_TABLE;
+use dom_struct::dom_struct;
+use euclid::default::{Rect, Size2D};
+use image::imageops::{self, FilterType};
+use image::RgbaImage;
+use net_traits::image::base::{load_from_memory, Image};
+use net_traits::image_cache::{CorsStatus, ImageResponse};
+use pixels::PixelFormat;
+use std::cell::Cell;
+use std::rc::Rc;
+use std::thread;
+
+#[dom_struct]
+pub struct ImageBitmap {
+    reflector_: Reflector,
+    width: Cell<u32>,
+    height: Cell<u32>,
+    /// The bitmap data in BGRA8 format, or `None` once the bitmap has been
+    /// detached by `close()` or by being transferred.
+    bitmap_data: DomRefCell<Option<Vec<u8>>>,
+    /// Whether the color components of the bitmap data are premultiplied by alpha.
+    premultiplied: bool,
+    /// <https://html.spec.whatwg.org/multipage/#concept-imagebitmap-origin-clean>
+    origin_clean: bool,
+}
+
+impl ImageBitmap {
+    fn new_inherited(
+        size: Size2D<u32>,
+        bitmap_data: Vec<u8>,
+        premultiplied: bool,
+        origin_clean: bool,
+    ) -> ImageBitmap {
+        ImageBitmap {
+            reflector_: Reflector::new(),
+            width: Cell::new(size.width),
+            height: Cell::new(size.height),
+            bitmap_data: DomRefCell::new(Some(bitmap_data)),
+            premultiplied,
+            origin_clean,
+        }
+    }
+
+    pub fn new(
+        global: &GlobalScope,
+        size: Size2D<u32>,
+        bitmap_data: Vec<u8>,
+        premultiplied: bool,
+        origin_clean: bool,
+    ) -> DomRoot<ImageBitmap> {
+        reflect_dom_object(
+            Box::new(ImageBitmap::new_inherited(
+                size,
+                bitmap_data,
+                premultiplied,
+                origin_clean,
+            )),
+            global,
+            ImageBitmapBinding::Wrap,
+        )
+    }
+
+    pub fn get_size(&self) -> Size2D<u32> {
+        Size2D::new(self.width.get(), self.height.get())
+    }
+
+    pub fn is_detached(&self) -> bool {
+        self.bitmap_data.borrow().is_none()
+    }
+
+    pub fn is_premultiplied(&self) -> bool {
+        self.premultiplied
+    }
+
+    pub fn origin_is_clean(&self) -> bool {
+        self.origin_clean
+    }
+
+    /// A copy of the bitmap data, unless the bitmap is detached.
+    pub fn bitmap_data(&self) -> Option<Vec<u8>> {
+        self.bitmap_data.borrow().clone()
+    }
+
+    /// A copy of the bitmap data with premultiplied alpha, as the canvas
+    /// expects it, unless the bitmap is detached.
+    pub fn premultiplied_bitmap_data(&self) -> Option<Vec<u8>> {
+        let mut data = self.bitmap_data()?;
+        if !self.premultiplied {
+            pixels::rgba8_premultiply_inplace(&mut data);
+        }
+        Some(data)
+    }
+
+    /// Drops the bitmap data, leaving the bitmap with a width and a height of zero.
+    pub fn detach(&self) {
+        *self.bitmap_data.borrow_mut() = None;
+        self.width.set(0);
+        self.height.set(0);
+    }
+}
+
+impl ImageBitmapMethods for ImageBitmap {
+    // https://html.spec.whatwg.org/multipage/#dom-imagebitmap-width
+    fn Width(&self) -> u32 {
+        self.width.get()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-imagebitmap-height
+    fn Height(&self) -> u32 {
+        self.height.get()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-imagebitmap-close
+    fn Close(&self) {
+        self.detach();
+    }
+}
+
+/// The options of a `createImageBitmap()` call that shape the bitmap data.
+/// They don't need the script thread, so they can be applied right after
+/// decoding a blob.
+#[derive(Clone, Copy)]
+struct BitmapFormatting {
+    /// The source rectangle, if the bitmap is cropped, normalized so that its
+    /// size is positive.
+    source_rect: Option<Rect<i32>>,
+    resize_width: Option<u32>,
+    resize_height: Option<u32>,
+    resize_quality: ResizeQuality,
+    flip_y: bool,
+    premultiply_alpha: PremultiplyAlpha,
+}
+
+impl BitmapFormatting {
+    fn new(crop: Option<Rect<i32>>, options: &ImageBitmapOptions) -> BitmapFormatting {
+        BitmapFormatting {
+            source_rect: crop.map(|rect| {
+                let mut rect = rect;
+                if rect.size.width < 0 {
+                    rect.origin.x += rect.size.width;
+                    rect.size.width = -rect.size.width;
+                }
+                if rect.size.height < 0 {
+                    rect.origin.y += rect.size.height;
+                    rect.size.height = -rect.size.height;
+                }
+                rect
+            }),
+            resize_width: options.resizeWidth,
+            resize_height: options.resizeHeight,
+            resize_quality: options.resizeQuality,
+            flip_y: options.imageOrientation == ImageOrientation::FlipY,
+            premultiply_alpha: options.premultiplyAlpha,
+        }
+    }
+
+    /// Returns the formatted bitmap data, its size and whether it is
+    /// premultiplied.
+    ///
+    /// <https://html.spec.whatwg.org/multipage/#cropped-to-the-source-rectangle-with-formatting>
+    fn apply(
+        &self,
+        data: Vec<u8>,
+        size: Size2D<u32>,
+        premultiplied: bool,
+    ) -> (Vec<u8>, Size2D<u32>, bool) {
+        // Step 1.
+        let source_rect = self
+            .source_rect
+            .unwrap_or_else(|| Rect::from_size(size.to_i32()));
+        let source_size = source_rect.size.to_u32();
+
+        // Steps 2-4.
+        let output_size = match (self.resize_width, self.resize_height) {
+            (Some(width), Some(height)) => Size2D::new(width, height),
+            (Some(width), None) => Size2D::new(
+                width,
+                (source_size.height as f64 * width as f64 / source_size.width as f64).ceil() as u32,
+            ),
+            (None, Some(height)) => Size2D::new(
+                (source_size.width as f64 * height as f64 / source_size.height as f64).ceil()
+                    as u32,
+                height,
+            ),
+            (None, None) => source_size,
+        };
+
+        // Steps 5-6.
+        let mut data = if source_rect == Rect::from_size(size.to_i32()) {
+            data
+        } else {
+            crop(&data, size, source_rect)
+        };
+
+        // Step 7.
+        if output_size != source_size {
+            let filter = match self.resize_quality {
+                ResizeQuality::Pixelated => FilterType::Nearest,
+                ResizeQuality::Low => FilterType::Triangle,
+                ResizeQuality::Medium => FilterType::CatmullRom,
+                ResizeQuality::High => FilterType::Lanczos3,
+            };
+            let image = RgbaImage::from_raw(source_size.width, source_size.height, data)
+                .expect("Bitmap data doesn't match its size");
+            data =
+                imageops::resize(&image, output_size.width, output_size.height, filter).into_raw();
+        }
+
+        // Step 8.
+        if self.flip_y {
+            flip_y(&mut data, output_size);
+        }
+
+        // Step 9.
+        let premultiplied = match self.premultiply_alpha {
+            PremultiplyAlpha::Premultiply => {
+                if !premultiplied {
+                    pixels::rgba8_premultiply_inplace(&mut data);
+                }
+                true
+            },
+            PremultiplyAlpha::None => {
+                if premultiplied {
+                    unpremultiply_inplace(&mut data);
+                }
+                false
+            },
+            PremultiplyAlpha::Default => premultiplied,
+        };
+
+        (data, output_size, premultiplied)
+    }
+}
+
+/// Copies the `rect` area of the bitmap, which is transparent black wherever
+/// it falls outside of the bitmap.
+fn crop(data: &[u8], size: Size2D<u32>, rect: Rect<i32>) -> Vec<u8> {
+    let mut output = vec![0; rect.size.width as usize * rect.size.height as usize * 4];
+    let intersection = match Rect::from_size(size.to_i32()).intersection(&rect) {
+        Some(intersection) => intersection,
+        None => return output,
+    };
+    let row_length = intersection.size.width as usize * 4;
+    for y in intersection.min_y()..intersection.max_y() {
+        let source = (y as usize * size.width as usize + intersection.min_x() as usize) * 4;
+        let destination = ((y - rect.origin.y) as usize * rect.size.width as usize +
+            (intersection.min_x() - rect.origin.x) as usize) *
+            4;
+        output[destination..destination + row_length]
+            .copy_from_slice(&data[source..source + row_length]);
+    }
+    output
+}
+
+fn flip_y(data: &mut [u8], size: Size2D<u32>) {
+    let row_length = size.width as usize * 4;
+    let height = size.height as usize;
+    for y in 0..height / 2 {
+        let (top, bottom) = data.split_at_mut((height - 1 - y) * row_length);
+        top[y * row_length..(y + 1) * row_length].swap_with_slice(&mut bottom[..row_length]);
+    }
+}
+
+pub fn unpremultiply_inplace(pixels: &mut [u8]) {
+    for pixel in pixels.chunks_mut(4) {
+        let alpha = pixel[3] as usize;
+        for component in &mut pixel[..3] {
+            *component = UNPREMULTIPLY_TABLE[256 * alpha + *component as usize];
+        }
+    }
+}
+
+/// The bitmap data of a decoded image, if it is in a format a bitmap can hold.
+fn image_bitmap_data(image: &Image) -> Option<(Vec<u8>, Size2D<u32>)> {
+    match image.format {
+        PixelFormat::BGRA8 => Some((image.bytes.to_vec(), Size2D::new(image.width, image.height))),
+        _ => None,
+    }
+}
+
+/// <https://html.spec.whatwg.org/multipage/#dom-createimagebitmap>
+pub fn create_image_bitmap(
+    global: &GlobalScope,
+    image: ImageBitmapSource,
+    crop: Option<Rect<i32>>,
+    options: &ImageBitmapOptions,
+    comp: InCompartment,
+) -> Rc<Promise> {
+    let promise = Promise::new_in_current_compartment(global, comp);
+
+    // Step 1.
+    if let Some(rect) = crop {
+        if rect.size.width == 0 || rect.size.height == 0 {
+            promise.reject_error(Error::Range(
+                "The source width and height must not be zero".to_owned(),
+            ));
+            return promise;
+        }
+    }
+
+    // Step 2.
+    if options.resizeWidth == Some(0) || options.resizeHeight == Some(0) {
+        promise.reject_error(Error::InvalidState);
+        return promise;
+    }
+
+    let formatting = BitmapFormatting::new(crop, options);
+
+    // Steps 3-6.
+    let source = match image {
+        ImageBitmapSource::HTMLImageElement(ref image) => image
+            .current_image()
+            .and_then(|img| image_bitmap_data(&img))
+            .map(|(data, size)| (data, size, false, image.same_origin(global.origin()))),
+        ImageBitmapSource::HTMLCanvasElement(ref canvas) => {
+            canvas.fetch_all_data().map(|(data, size)| {
+                let data = data
+                    .map(|data| data.to_vec())
+                    .unwrap_or_else(|| vec![0; size.area() as usize * 4]);
+                (data, size, true, canvas.origin_is_clean())
+            })
+        },
+        ImageBitmapSource::ImageBitmap(ref bitmap) => bitmap.bitmap_data().map(|data| {
+            (
+                data,
+                bitmap.get_size(),
+                bitmap.is_premultiplied(),
+                bitmap.origin_is_clean(),
+            )
+        }),
+        ImageBitmapSource::CSSStyleValue(ref value) => global
+            .downcast::<Window>()
+            .and_then(|window| {
+                let url = value.get_url(global.api_base_url())?;
+                match canvas_utils::request_image_from_cache(window, url, None) {
+                    ImageResponse::Loaded(img, _) => image_bitmap_data(&img),
+                    _ => None,
+                }
+            })
+            .map(|(data, size)| (data, size, false, true)),
+        ImageBitmapSource::ImageData(ref image_data) => {
+            let mut data = image_data.to_shared_memory().to_vec();
+            pixels::rgba8_byte_swap_colors_inplace(&mut data);
+            Some((data, image_data.get_size(), false, true))
+        },
+        ImageBitmapSource::Blob(ref blob) => {
+            decode_blob_in_parallel(
+                global,
+                &promise,
+                blob.get_bytes().unwrap_or(vec![]),
+                formatting,
+            );
+            return promise;
+        },
+    };
+
+    let (data, size, premultiplied, origin_clean) = match source {
+        Some(source) => source,
+        None => {
+            promise.reject_error(Error::InvalidState);
+            return promise;
+        },
+    };
+    if size.width == 0 || size.height == 0 {
+        promise.reject_error(Error::InvalidState);
+        return promise;
+    }
+
+    let (data, size, premultiplied) = formatting.apply(data, size, premultiplied);
+    let bitmap = ImageBitmap::new(global, size, data, premultiplied, origin_clean);
+    promise.resolve_native(&bitmap);
+    promise
+}
+
+/// Decodes and formats the image data of a blob on a separate thread, then
+/// settles the promise of `createImageBitmap()` from a task.
+fn decode_blob_in_parallel(
+    global: &GlobalScope,
+    promise: &Rc<Promise>,
+    bytes: Vec<u8>,
+    formatting: BitmapFormatting,
+) {
+    let trusted_promise = TrustedPromise::new(promise.clone());
+    let task_source = global.dom_manipulation_task_source();
+    let canceller = global.task_canceller(TaskSourceName::DOMManipulation);
+
+    thread::Builder::new()
+        .name("ImageBitmap decoder".to_owned())
+        .spawn(move || {
+            let bitmap = load_from_memory(&bytes, CorsStatus::Safe)
+                .and_then(|img| image_bitmap_data(&img))
+                .filter(|&(_, size)| size.width != 0 && size.height != 0)
+                .map(|(data, size)| formatting.apply(data, size, false));
+            let _ = task_source.queue_with_canceller(
+                task!(resolve_image_bitmap: move || {
+                    let promise = trusted_promise.root();
+                    match bitmap {
+                        Some((data, size, premultiplied)) => {
+                            let bitmap = ImageBitmap::new(
+                                &promise.global(),
+                                size,
+                                data,
+                                premultiplied,
+                                true,
+                            );
+                            promise.resolve_native(&bitmap);
+                        },
+                        None => promise.reject_error(Error::InvalidState),
+                    }
+                }),
+                &canceller,
+            );
+        })
+        .expect("Thread spawning failed");
+}
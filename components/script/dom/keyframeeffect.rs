@@ -0,0 +1,555 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::animationeffect::{parse_easing, AnimationEffect, EffectTimingValues};
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::KeyframeEffectBinding::{
+    self, CompositeOperation, KeyframeEffectMethods,
+};
+use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
+use crate::dom::bindings::codegen::UnionTypes::UnrestrictedDoubleOrKeyframeEffectOptions;
+use crate::dom::bindings::conversions::{is_array_like, jsid_to_string, StringificationBehavior};
+use crate::dom::bindings::error::{Error, ErrorResult, Fallible};
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::reflector::{reflect_dom_object, DomObject};
+use crate::dom::bindings::root::{DomRoot, MutNullableDom};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::bindings::trace::RootedTraceableBox;
+use crate::dom::bindings::utils::set_dictionary_property;
+use crate::dom::document::Document;
+use crate::dom::element::Element;
+use crate::dom::window::Window;
+use crate::script_runtime::JSContext;
+use cssparser::SourceLocation;
+use dom_struct::dom_struct;
+use js::conversions::{ConversionResult, FromJSValConvertible, ToJSValConvertible};
+use js::jsapi::{Heap, JSObject, JS_NewPlainObject, JSITER_OWNONLY};
+use js::jsval::{JSVal, ObjectValue, UndefinedValue};
+use js::rust::wrappers::{GetPropertyKeys, JS_GetPropertyById};
+use js::rust::{HandleObject, HandleValue, IdVector};
+use servo_arc::Arc;
+use std::cell::Cell;
+use std::ptr::NonNull;
+use style::properties::{parse_one_declaration_into, SourcePropertyDeclaration};
+use style::properties::{Importance, PropertyDeclarationBlock, PropertyId};
+use style::shared_lock::Locked;
+use style::stylesheets::keyframes_rule::KeyframesAnimation;
+use style::stylesheets::keyframes_rule::{Keyframe, KeyframePercentage, KeyframeSelector};
+use style_traits::ParsingMode;
+
+/// A keyframe of a keyframe effect, as specified by script.
+///
+/// <https://drafts.csswg.org/web-animations/#keyframes-section>
+#[derive(Clone, JSTraceable, MallocSizeOf)]
+pub struct EffectKeyframe {
+    /// The offset of this keyframe as specified, if any.
+    pub offset: Option<f64>,
+    /// The offset of this keyframe, with missing offsets filled in.
+    pub computed_offset: f64,
+    pub easing: DOMString,
+    /// The CSS property names and values of this keyframe.
+    pub values: Vec<(DOMString, DOMString)>,
+}
+
+impl EffectKeyframe {
+    fn new() -> EffectKeyframe {
+        EffectKeyframe {
+            offset: None,
+            computed_offset: 0.,
+            easing: DOMString::from("linear"),
+            values: vec![],
+        }
+    }
+}
+
+#[dom_struct]
+pub struct KeyframeEffect {
+    effect: AnimationEffect,
+    target: MutNullableDom<Element>,
+    composite: Cell<CompositeOperation>,
+    keyframes: DomRefCell<Vec<EffectKeyframe>>,
+}
+
+impl KeyframeEffect {
+    fn new_inherited(
+        target: Option<&Element>,
+        keyframes: Vec<EffectKeyframe>,
+        timing: EffectTimingValues,
+        composite: CompositeOperation,
+    ) -> KeyframeEffect {
+        KeyframeEffect {
+            effect: AnimationEffect::new_inherited(timing),
+            target: MutNullableDom::new(target),
+            composite: Cell::new(composite),
+            keyframes: DomRefCell::new(keyframes),
+        }
+    }
+
+    pub fn new(
+        window: &Window,
+        target: Option<&Element>,
+        keyframes: Vec<EffectKeyframe>,
+        timing: EffectTimingValues,
+        composite: CompositeOperation,
+    ) -> DomRoot<KeyframeEffect> {
+        reflect_dom_object(
+            Box::new(KeyframeEffect::new_inherited(
+                target, keyframes, timing, composite,
+            )),
+            window,
+            KeyframeEffectBinding::Wrap,
+        )
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-keyframeeffect-keyframeeffect
+    pub fn Constructor(
+        cx: JSContext,
+        window: &Window,
+        target: Option<&Element>,
+        keyframes: *mut JSObject,
+        options: UnrestrictedDoubleOrKeyframeEffectOptions,
+    ) -> Fallible<DomRoot<KeyframeEffect>> {
+        let document = window.Document();
+        let (timing, composite) = match options {
+            UnrestrictedDoubleOrKeyframeEffectOptions::UnrestrictedDouble(duration) => (
+                EffectTimingValues::from_duration(&document, duration)?,
+                CompositeOperation::Replace,
+            ),
+            UnrestrictedDoubleOrKeyframeEffectOptions::KeyframeEffectOptions(ref options) => (
+                EffectTimingValues::from_dictionary(&document, &options.parent)?,
+                options.composite,
+            ),
+        };
+        let keyframes = process_keyframes(cx, &document, keyframes)?;
+        Ok(KeyframeEffect::new(
+            window, target, keyframes, timing, composite,
+        ))
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-keyframeeffect-keyframeeffect-source
+    pub fn Constructor_(
+        window: &Window,
+        source: &KeyframeEffect,
+    ) -> Fallible<DomRoot<KeyframeEffect>> {
+        Ok(KeyframeEffect::new(
+            window,
+            source.target.get().as_ref().map(|target| &**target),
+            source.keyframes.borrow().clone(),
+            source.upcast::<AnimationEffect>().timing(),
+            source.composite.get(),
+        ))
+    }
+
+    pub fn target(&self) -> Option<DomRoot<Element>> {
+        self.target.get()
+    }
+
+    /// Builds the keyframes of this effect as used by the style system, or
+    /// returns `None` if no keyframe animates a property.
+    pub fn style_animation(&self, document: &Document) -> Option<KeyframesAnimation> {
+        let lock = document.style_shared_lock();
+        let url = document.base_url();
+        let quirks_mode = document.quirks_mode();
+        let parse_into = |block: &mut PropertyDeclarationBlock, id: PropertyId, value: &str| {
+            let mut declarations = SourcePropertyDeclaration::new();
+            let result = parse_one_declaration_into(
+                &mut declarations,
+                id,
+                value,
+                &url,
+                None,
+                ParsingMode::DEFAULT,
+                quirks_mode,
+            );
+            // Invalid values are ignored, like in a style sheet.
+            if result.is_ok() {
+                block.extend(declarations.drain(), Importance::Normal);
+            }
+        };
+
+        let keyframes: Vec<Arc<Locked<Keyframe>>> = self
+            .keyframes
+            .borrow()
+            .iter()
+            .map(|keyframe| {
+                let mut block = PropertyDeclarationBlock::new();
+                for &(ref name, ref value) in &keyframe.values {
+                    if let Ok(id) = PropertyId::parse_enabled_for_all_content(name) {
+                        parse_into(&mut block, id, value);
+                    }
+                }
+                if &*keyframe.easing != "linear" {
+                    let id = PropertyId::parse_enabled_for_all_content("animation-timing-function")
+                        .unwrap();
+                    parse_into(&mut block, id, &keyframe.easing);
+                }
+                Arc::new(lock.wrap(Keyframe {
+                    selector: KeyframeSelector::from_percentage(KeyframePercentage::new(
+                        keyframe.computed_offset as f32,
+                    )),
+                    block: Arc::new(lock.wrap(block)),
+                    source_location: SourceLocation { line: 0, column: 0 },
+                }))
+            })
+            .collect();
+
+        let animation = KeyframesAnimation::from_keyframes(&keyframes, None, &lock.read());
+        if animation.steps.is_empty() {
+            return None;
+        }
+        Some(animation)
+    }
+}
+
+impl KeyframeEffectMethods for KeyframeEffect {
+    // https://drafts.csswg.org/web-animations/#dom-keyframeeffect-target
+    fn GetTarget(&self) -> Option<DomRoot<Element>> {
+        self.target.get()
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-keyframeeffect-target
+    fn SetTarget(&self, target: Option<&Element>) {
+        let old_target = self.target.get();
+        self.target.set(target);
+        if let Some(animation) = self.upcast::<AnimationEffect>().animation() {
+            animation.effect_target_changed(old_target.as_ref().map(|target| &**target));
+        }
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-keyframeeffect-composite
+    fn Composite(&self) -> CompositeOperation {
+        self.composite.get()
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-keyframeeffect-composite
+    fn SetComposite(&self, composite: CompositeOperation) {
+        self.composite.set(composite);
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-keyframeeffect-getkeyframes
+    #[allow(unsafe_code)]
+    fn GetKeyframes(&self, cx: JSContext) -> Fallible<Vec<NonNull<JSObject>>> {
+        let mut result = vec![];
+        for keyframe in self.keyframes.borrow().iter() {
+            unsafe {
+                rooted!(in(*cx) let object = JS_NewPlainObject(*cx));
+                if object.is_null() {
+                    return Err(Error::JSFailed);
+                }
+                set_property(cx, object.handle(), "offset", &keyframe.offset)?;
+                set_property(
+                    cx,
+                    object.handle(),
+                    "computedOffset",
+                    &keyframe.computed_offset,
+                )?;
+                set_property(cx, object.handle(), "easing", &keyframe.easing)?;
+                set_property(cx, object.handle(), "composite", &DOMString::from("auto"))?;
+                for &(ref name, ref value) in &keyframe.values {
+                    set_property(cx, object.handle(), &idl_attribute_name(name), value)?;
+                }
+                result.push(NonNull::new_unchecked(object.get()));
+            }
+        }
+        Ok(result)
+    }
+
+    // https://drafts.csswg.org/web-animations/#dom-keyframeeffect-setkeyframes
+    fn SetKeyframes(&self, cx: JSContext, keyframes: *mut JSObject) -> ErrorResult {
+        let document = self.global().as_window().Document();
+        *self.keyframes.borrow_mut() = process_keyframes(cx, &document, keyframes)?;
+        if let Some(animation) = self.upcast::<AnimationEffect>().animation() {
+            animation.timing_updated();
+        }
+        Ok(())
+    }
+}
+
+/// <https://drafts.csswg.org/web-animations/#idl-attribute-name-to-animation-property-name>
+///
+/// Returns `None` for members that don't name a CSS property.
+fn animation_property_name(name: &str) -> Option<String> {
+    if name == "cssFloat" {
+        return Some("float".to_owned());
+    }
+    if name == "float" || name.contains('-') {
+        return None;
+    }
+    let mut property = String::with_capacity(name.len());
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            property.push('-');
+            property.push(c.to_ascii_lowercase());
+        } else {
+            property.push(c);
+        }
+    }
+    match PropertyId::parse_enabled_for_all_content(&property) {
+        Ok(..) => Some(property),
+        Err(..) => None,
+    }
+}
+
+/// <https://drafts.csswg.org/web-animations/#animation-property-name-to-idl-attribute-name>
+fn idl_attribute_name(property: &str) -> String {
+    if property == "float" {
+        return "cssFloat".to_owned();
+    }
+    let mut name = String::with_capacity(property.len());
+    let mut uppercase_next = false;
+    for c in property.chars() {
+        if c == '-' {
+            uppercase_next = true;
+        } else if uppercase_next {
+            name.push(c.to_ascii_uppercase());
+            uppercase_next = false;
+        } else {
+            name.push(c);
+        }
+    }
+    name
+}
+
+#[allow(unsafe_code)]
+unsafe fn convert<T, C>(cx: JSContext, value: HandleValue, config: C) -> Fallible<T>
+where
+    T: FromJSValConvertible<Config = C>,
+{
+    match T::from_jsval(*cx, value, config) {
+        Ok(ConversionResult::Success(value)) => Ok(value),
+        Ok(ConversionResult::Failure(error)) => Err(Error::Type(error.into_owned())),
+        Err(()) => Err(Error::JSFailed),
+    }
+}
+
+/// Converts a value that may be a single value or a list of values.
+#[allow(unsafe_code)]
+unsafe fn convert_list<T, C>(cx: JSContext, value: HandleValue, config: C) -> Fallible<Vec<T>>
+where
+    T: FromJSValConvertible<Config = C>,
+    C: Clone,
+{
+    if value.is_object() && is_array_like(*cx, value) {
+        convert(cx, value, config)
+    } else {
+        Ok(vec![convert(cx, value, config)?])
+    }
+}
+
+#[allow(unsafe_code)]
+unsafe fn set_property<T: ToJSValConvertible>(
+    cx: JSContext,
+    object: HandleObject,
+    name: &str,
+    value: &T,
+) -> ErrorResult {
+    rooted!(in(*cx) let mut js_value = UndefinedValue());
+    value.to_jsval(*cx, js_value.handle_mut());
+    set_dictionary_property(*cx, object, name, js_value.handle()).map_err(|()| Error::JSFailed)
+}
+
+/// Calls `f` with the name and value of every enumerable own string-keyed
+/// property of `object`.
+#[allow(unsafe_code)]
+unsafe fn for_each_property<F>(cx: JSContext, object: HandleObject, mut f: F) -> ErrorResult
+where
+    F: FnMut(DOMString, HandleValue) -> ErrorResult,
+{
+    let ids = IdVector::new(*cx);
+    if !GetPropertyKeys(*cx, object, JSITER_OWNONLY, ids.get()) {
+        return Err(Error::JSFailed);
+    }
+    for id in &*ids {
+        rooted!(in(*cx) let id = *id);
+        let name = match jsid_to_string(*cx, id.handle()) {
+            Some(name) => name,
+            None => continue,
+        };
+        rooted!(in(*cx) let mut value = UndefinedValue());
+        if !JS_GetPropertyById(*cx, object, id.handle(), value.handle_mut()) {
+            return Err(Error::JSFailed);
+        }
+        f(name, value.handle())?;
+    }
+    Ok(())
+}
+
+/// <https://drafts.csswg.org/web-animations/#processing-a-keyframes-argument>
+#[allow(unsafe_code)]
+pub fn process_keyframes(
+    cx: JSContext,
+    document: &Document,
+    object: *mut JSObject,
+) -> Fallible<Vec<EffectKeyframe>> {
+    // Step 1.
+    if object.is_null() {
+        return Ok(vec![]);
+    }
+
+    let mut keyframes = unsafe {
+        rooted!(in(*cx) let value = ObjectValue(object));
+        if is_array_like(*cx, value.handle()) {
+            process_keyframe_list(cx, value.handle())?
+        } else {
+            rooted!(in(*cx) let object = object);
+            process_property_indexed_keyframes(cx, object.handle())?
+        }
+    };
+
+    let mut previous_offset = 0.;
+    for keyframe in &keyframes {
+        if let Some(offset) = keyframe.offset {
+            if !offset.is_finite() || offset < 0. || offset > 1. {
+                return Err(Error::Type(
+                    "Keyframe offsets must be between 0 and 1".to_owned(),
+                ));
+            }
+            if offset < previous_offset {
+                return Err(Error::Type(
+                    "Keyframe offsets must be in ascending order".to_owned(),
+                ));
+            }
+            previous_offset = offset;
+        }
+        parse_easing(document, &keyframe.easing)?;
+    }
+
+    compute_missing_offsets(&mut keyframes);
+    Ok(keyframes)
+}
+
+/// Processes keyframes given as a list of keyframe objects.
+#[allow(unsafe_code)]
+unsafe fn process_keyframe_list(
+    cx: JSContext,
+    value: HandleValue,
+) -> Fallible<Vec<EffectKeyframe>> {
+    let values: Vec<RootedTraceableBox<Heap<JSVal>>> = convert(cx, value, ())?;
+    let mut keyframes = Vec::with_capacity(values.len());
+    for value in values {
+        let value = value.handle();
+        let mut keyframe = EffectKeyframe::new();
+        if value.is_null_or_undefined() {
+            keyframes.push(keyframe);
+            continue;
+        }
+        if !value.is_object() {
+            return Err(Error::Type("Keyframes must be objects".to_owned()));
+        }
+        rooted!(in(*cx) let object = value.to_object());
+        for_each_property(cx, object.handle(), |name, value| {
+            match &*name {
+                "offset" => keyframe.offset = convert(cx, value, ())?,
+                "easing" => keyframe.easing = convert(cx, value, StringificationBehavior::Default)?,
+                "composite" => {},
+                name => {
+                    if let Some(property) = animation_property_name(name) {
+                        let value = convert(cx, value, StringificationBehavior::Default)?;
+                        keyframe.values.push((DOMString::from(property), value));
+                    }
+                },
+            }
+            Ok(())
+        })?;
+        keyframes.push(keyframe);
+    }
+    Ok(keyframes)
+}
+
+/// Processes keyframes given as a single object with lists of values for each
+/// property, which are spread evenly over the duration of the effect.
+#[allow(unsafe_code)]
+unsafe fn process_property_indexed_keyframes(
+    cx: JSContext,
+    object: HandleObject,
+) -> Fallible<Vec<EffectKeyframe>> {
+    let mut offsets: Vec<Option<f64>> = vec![];
+    let mut easings: Vec<DOMString> = vec![];
+    let mut properties: Vec<(DOMString, Vec<DOMString>)> = vec![];
+    for_each_property(cx, object, |name, value| {
+        match &*name {
+            "offset" => offsets = convert_list(cx, value, ())?,
+            "easing" => easings = convert_list(cx, value, StringificationBehavior::Default)?,
+            "composite" => {},
+            name => {
+                if let Some(property) = animation_property_name(name) {
+                    let values = convert_list(cx, value, StringificationBehavior::Default)?;
+                    properties.push((DOMString::from(property), values));
+                }
+            },
+        }
+        Ok(())
+    })?;
+
+    let mut keyframes: Vec<EffectKeyframe> = vec![];
+    for (property, values) in properties {
+        let count = values.len();
+        for (index, value) in values.into_iter().enumerate() {
+            let offset = if count == 1 {
+                1.
+            } else {
+                index as f64 / (count - 1) as f64
+            };
+            match keyframes
+                .iter()
+                .position(|keyframe| keyframe.computed_offset >= offset)
+            {
+                Some(position) if keyframes[position].computed_offset == offset => {
+                    keyframes[position].values.push((property.clone(), value));
+                },
+                position => {
+                    let mut keyframe = EffectKeyframe::new();
+                    keyframe.computed_offset = offset;
+                    keyframe.values.push((property.clone(), value));
+                    keyframes.insert(position.unwrap_or(keyframes.len()), keyframe);
+                },
+            }
+        }
+    }
+
+    for (keyframe, offset) in keyframes.iter_mut().zip(offsets) {
+        keyframe.offset = offset;
+    }
+    if !easings.is_empty() {
+        for (index, keyframe) in keyframes.iter_mut().enumerate() {
+            keyframe.easing = easings[index % easings.len()].clone();
+        }
+    }
+    Ok(keyframes)
+}
+
+/// <https://drafts.csswg.org/web-animations/#compute-missing-keyframe-offsets>
+fn compute_missing_offsets(keyframes: &mut [EffectKeyframe]) {
+    let count = keyframes.len();
+    if count == 0 {
+        return;
+    }
+
+    let mut offsets: Vec<Option<f64>> = keyframes.iter().map(|keyframe| keyframe.offset).collect();
+    if offsets[0].is_none() {
+        offsets[0] = Some(if count > 1 { 0. } else { 1. });
+    }
+    if offsets[count - 1].is_none() {
+        offsets[count - 1] = Some(1.);
+    }
+
+    // Space the keyframes without an offset evenly between their neighbours.
+    let mut previous = 0;
+    for index in 1..count {
+        let offset = match offsets[index] {
+            Some(offset) => offset,
+            None => continue,
+        };
+        let start = offsets[previous].unwrap();
+        let span = (index - previous) as f64;
+        for missing in previous + 1..index {
+            offsets[missing] = Some(start + (offset - start) * (missing - previous) as f64 / span);
+        }
+        previous = index;
+    }
+
+    for (keyframe, offset) in keyframes.iter_mut().zip(offsets) {
+        keyframe.computed_offset = offset.unwrap();
+    }
+}
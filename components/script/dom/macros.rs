@@ -480,6 +480,16 @@ macro_rules! global_event_handlers(
         event_handler!(transitionend, GetOntransitionend, SetOntransitionend);
         event_handler!(volumechange, GetOnvolumechange, SetOnvolumechange);
         event_handler!(waiting, GetOnwaiting, SetOnwaiting);
+        event_handler!(gotpointercapture, GetOngotpointercapture, SetOngotpointercapture);
+        event_handler!(lostpointercapture, GetOnlostpointercapture, SetOnlostpointercapture);
+        event_handler!(pointerdown, GetOnpointerdown, SetOnpointerdown);
+        event_handler!(pointermove, GetOnpointermove, SetOnpointermove);
+        event_handler!(pointerup, GetOnpointerup, SetOnpointerup);
+        event_handler!(pointercancel, GetOnpointercancel, SetOnpointercancel);
+        event_handler!(pointerover, GetOnpointerover, SetOnpointerover);
+        event_handler!(pointerout, GetOnpointerout, SetOnpointerout);
+        event_handler!(pointerenter, GetOnpointerenter, SetOnpointerenter);
+        event_handler!(pointerleave, GetOnpointerleave, SetOnpointerleave);
     )
 );
 
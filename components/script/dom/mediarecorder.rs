@@ -0,0 +1,409 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::MediaRecorderBinding::{
+    self, MediaRecorderMethods, MediaRecorderOptions, RecordingState,
+};
+use crate::dom::bindings::error::{Error, ErrorResult, Fallible};
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::refcounted::Trusted;
+use crate::dom::bindings::reflector::{reflect_dom_object, DomObject};
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::blob::{Blob, BlobImpl};
+use crate::dom::blobevent::BlobEvent;
+use crate::dom::event::Event;
+use crate::dom::eventtarget::EventTarget;
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::mediastream::MediaStream;
+use crate::dom::window::Window;
+use crate::task_source::TaskSource;
+use dom_struct::dom_struct;
+use ipc_channel::ipc;
+use ipc_channel::router::ROUTER;
+use mime::Mime;
+use servo_atoms::Atom;
+use servo_media::recorder::{Recorder, RecorderEvent, RecorderOptions};
+use servo_media::streams::MediaStreamType;
+use servo_media::{ClientContextId, ServoMedia};
+use std::cell::Cell;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// The containers recordings can be encoded to, with their codecs.
+const SUPPORTED_TYPES: &[(&str, &[&str])] = &[
+    ("video/webm", &["vp8", "vp9", "opus", "vorbis"]),
+    ("audio/webm", &["opus", "vorbis"]),
+    ("audio/ogg", &["opus", "vorbis"]),
+];
+
+const DEFAULT_AUDIO_BITS_PER_SECOND: u32 = 128_000;
+const DEFAULT_VIDEO_BITS_PER_SECOND: u32 = 2_500_000;
+
+#[dom_struct]
+pub struct MediaRecorder {
+    eventtarget: EventTarget,
+    stream: Dom<MediaStream>,
+    mime_type: DomRefCell<DOMString>,
+    state: Cell<RecordingState>,
+    /// The bitrate requested with `bitsPerSecond`, to be split
+    /// between the tracks when recording starts.
+    bits_per_second: Option<u32>,
+    audio_bits_per_second: Cell<u32>,
+    video_bits_per_second: Cell<u32>,
+    #[ignore_malloc_size_of = "servo_media"]
+    recorder: DomRefCell<Option<Arc<Mutex<dyn Recorder>>>>,
+    /// Incremented each time recording starts, so that a finished recording
+    /// doesn't release the recorder of the next one.
+    generation_id: Cell<u32>,
+}
+
+impl MediaRecorder {
+    fn new_inherited(stream: &MediaStream, options: &MediaRecorderOptions) -> MediaRecorder {
+        MediaRecorder {
+            eventtarget: EventTarget::new_inherited(),
+            stream: Dom::from_ref(stream),
+            mime_type: DomRefCell::new(options.mimeType.clone()),
+            state: Cell::new(RecordingState::Inactive),
+            bits_per_second: options.bitsPerSecond,
+            audio_bits_per_second: Cell::new(
+                options
+                    .audioBitsPerSecond
+                    .unwrap_or(DEFAULT_AUDIO_BITS_PER_SECOND),
+            ),
+            video_bits_per_second: Cell::new(
+                options
+                    .videoBitsPerSecond
+                    .unwrap_or(DEFAULT_VIDEO_BITS_PER_SECOND),
+            ),
+            recorder: Default::default(),
+            generation_id: Cell::new(0),
+        }
+    }
+
+    pub fn new(
+        global: &GlobalScope,
+        stream: &MediaStream,
+        options: &MediaRecorderOptions,
+    ) -> DomRoot<MediaRecorder> {
+        reflect_dom_object(
+            Box::new(MediaRecorder::new_inherited(stream, options)),
+            global,
+            MediaRecorderBinding::Wrap,
+        )
+    }
+
+    // https://w3c.github.io/mediacapture-record/#dom-mediarecorder-constructor
+    pub fn Constructor(
+        window: &Window,
+        stream: &MediaStream,
+        options: &MediaRecorderOptions,
+    ) -> Fallible<DomRoot<MediaRecorder>> {
+        // Step 2.
+        if !options.mimeType.is_empty() && !is_type_supported(&options.mimeType) {
+            return Err(Error::NotSupported);
+        }
+        // Steps 3-11.
+        Ok(MediaRecorder::new(&window.global(), stream, options))
+    }
+
+    fn has_tracks_of_type(&self, ty: MediaStreamType) -> bool {
+        self.stream
+            .get_tracks()
+            .iter()
+            .any(|track| track.ty() == ty)
+    }
+
+    /// Choose the container of the recording and split the requested
+    /// bitrate between the tracks, once they are known.
+    fn configure_encoding(&self) {
+        let has_video = self.has_tracks_of_type(MediaStreamType::Video);
+        if self.mime_type.borrow().is_empty() {
+            let mime_type = if has_video { "video/webm" } else { "audio/ogg" };
+            *self.mime_type.borrow_mut() = DOMString::from(mime_type);
+        }
+        if let Some(bits_per_second) = self.bits_per_second {
+            let audio_bits_per_second = if has_video {
+                (bits_per_second / 10).min(DEFAULT_AUDIO_BITS_PER_SECOND)
+            } else {
+                bits_per_second
+            };
+            self.audio_bits_per_second.set(audio_bits_per_second);
+            self.video_bits_per_second
+                .set(bits_per_second - audio_bits_per_second);
+        }
+    }
+
+    fn setup_recorder(&self, timeslice: Option<u32>) -> ErrorResult {
+        let window = DomRoot::downcast::<Window>(self.global()).unwrap();
+        let pipeline_id = window
+            .pipeline_id()
+            .expect("Cannot create recorder outside of a pipeline");
+        let client_context_id =
+            ClientContextId::build(pipeline_id.namespace_id.0, pipeline_id.index.0.get());
+        let tracks = self
+            .stream
+            .get_tracks()
+            .iter()
+            .map(|track| track.id())
+            .collect();
+        let options = RecorderOptions {
+            mime_type: self.mime_type.borrow().to_string(),
+            audio_bits_per_second: self.audio_bits_per_second.get(),
+            video_bits_per_second: self.video_bits_per_second.get(),
+            timeslice: timeslice.map(|timeslice| Duration::from_millis(timeslice as u64)),
+        };
+
+        let (event_sender, event_receiver) = ipc::channel::<RecorderEvent>().unwrap();
+        let recorder = ServoMedia::get()
+            .unwrap()
+            .create_recorder(&client_context_id, tracks, options, event_sender)
+            .map_err(|_| Error::NotSupported)?;
+        *self.recorder.borrow_mut() = Some(recorder);
+
+        let generation_id = self.generation_id.get() + 1;
+        self.generation_id.set(generation_id);
+        let this = Trusted::new(self);
+        let (task_source, canceller) = window
+            .task_manager()
+            .dom_manipulation_task_source_with_canceller();
+        ROUTER.add_route(
+            event_receiver.to_opaque(),
+            Box::new(move |message| {
+                let event = message.to().unwrap();
+                let this = this.clone();
+                if let Err(err) = task_source.queue_with_canceller(
+                    task!(handle_recorder_event: move || {
+                        this.root().handle_recorder_event(event, generation_id);
+                    }),
+                    &canceller,
+                ) {
+                    warn!("Could not queue recorder event handler task {:?}", err);
+                }
+            }),
+        );
+        Ok(())
+    }
+
+    fn handle_recorder_event(&self, event: RecorderEvent, generation_id: u32) {
+        match event {
+            RecorderEvent::Data(data, timecode) => {
+                let global = self.global();
+                let blob = Blob::new(
+                    &global,
+                    BlobImpl::new_from_bytes(data),
+                    self.mime_type.borrow().to_string(),
+                );
+                let event = BlobEvent::new(
+                    &global,
+                    atom!("dataavailable"),
+                    false,
+                    false,
+                    &blob,
+                    timecode,
+                );
+                event.upcast::<Event>().fire(self.upcast());
+            },
+            RecorderEvent::Error(error) => {
+                warn!("Recording error {:?}", error);
+                if generation_id != self.generation_id.get() ||
+                    self.state.get() == RecordingState::Inactive
+                {
+                    return;
+                }
+                // The recorder flushes the data it gathered, then stops.
+                self.state.set(RecordingState::Inactive);
+                self.upcast::<EventTarget>().fire_event(atom!("error"));
+                self.with_recorder(|recorder| recorder.stop());
+            },
+            RecorderEvent::Stopped => {
+                if generation_id == self.generation_id.get() {
+                    self.recorder.borrow_mut().take();
+                }
+                self.upcast::<EventTarget>().fire_event(atom!("stop"));
+            },
+        }
+    }
+
+    fn with_recorder<F, E>(&self, f: F)
+    where
+        F: FnOnce(&mut dyn Recorder) -> Result<(), E>,
+        E: std::fmt::Debug,
+    {
+        if let Some(ref recorder) = *self.recorder.borrow() {
+            if let Err(e) = f(&mut *recorder.lock().unwrap()) {
+                warn!("Recorder error {:?}", e);
+            }
+        }
+    }
+
+    fn queue_simple_event(&self, name: Atom) {
+        let window = DomRoot::downcast::<Window>(self.global()).unwrap();
+        window
+            .task_manager()
+            .dom_manipulation_task_source()
+            .queue_simple_event(self.upcast(), name, &window);
+    }
+}
+
+impl MediaRecorderMethods for MediaRecorder {
+    // https://w3c.github.io/mediacapture-record/#dom-mediarecorder-stream
+    fn Stream(&self) -> DomRoot<MediaStream> {
+        DomRoot::from_ref(&*self.stream)
+    }
+
+    // https://w3c.github.io/mediacapture-record/#dom-mediarecorder-mimetype
+    fn MimeType(&self) -> DOMString {
+        self.mime_type.borrow().clone()
+    }
+
+    // https://w3c.github.io/mediacapture-record/#dom-mediarecorder-state
+    fn State(&self) -> RecordingState {
+        self.state.get()
+    }
+
+    // https://w3c.github.io/mediacapture-record/#dom-mediarecorder-onstart
+    event_handler!(start, GetOnstart, SetOnstart);
+
+    // https://w3c.github.io/mediacapture-record/#dom-mediarecorder-onstop
+    event_handler!(stop, GetOnstop, SetOnstop);
+
+    // https://w3c.github.io/mediacapture-record/#dom-mediarecorder-ondataavailable
+    event_handler!(dataavailable, GetOndataavailable, SetOndataavailable);
+
+    // https://w3c.github.io/mediacapture-record/#dom-mediarecorder-onpause
+    event_handler!(pause, GetOnpause, SetOnpause);
+
+    // https://w3c.github.io/mediacapture-record/#dom-mediarecorder-onresume
+    event_handler!(resume, GetOnresume, SetOnresume);
+
+    // https://w3c.github.io/mediacapture-record/#dom-mediarecorder-onerror
+    event_handler!(error, GetOnerror, SetOnerror);
+
+    // https://w3c.github.io/mediacapture-record/#dom-mediarecorder-videobitspersecond
+    fn VideoBitsPerSecond(&self) -> u32 {
+        self.video_bits_per_second.get()
+    }
+
+    // https://w3c.github.io/mediacapture-record/#dom-mediarecorder-audiobitspersecond
+    fn AudioBitsPerSecond(&self) -> u32 {
+        self.audio_bits_per_second.get()
+    }
+
+    // https://w3c.github.io/mediacapture-record/#dom-mediarecorder-start
+    fn Start(&self, timeslice: Option<u32>) -> ErrorResult {
+        // Step 1.
+        if self.state.get() != RecordingState::Inactive {
+            return Err(Error::InvalidState);
+        }
+
+        // Step 2: an empty stream is inactive.
+        if self.stream.get_tracks().is_empty() {
+            return Err(Error::NotSupported);
+        }
+
+        // Steps 3-4.
+        self.configure_encoding();
+        if !is_type_supported(&self.mime_type.borrow()) {
+            return Err(Error::NotSupported);
+        }
+
+        // Steps 5-6.
+        self.setup_recorder(timeslice)?;
+        self.state.set(RecordingState::Recording);
+        self.with_recorder(|recorder| recorder.start());
+
+        // Step 7.
+        self.queue_simple_event(atom!("start"));
+        Ok(())
+    }
+
+    // https://w3c.github.io/mediacapture-record/#dom-mediarecorder-stop
+    fn Stop(&self) -> ErrorResult {
+        // Step 1.
+        if self.state.get() == RecordingState::Inactive {
+            return Ok(());
+        }
+
+        // Steps 2-3: the recorder sends the remaining data, then
+        // reports that it stopped.
+        self.state.set(RecordingState::Inactive);
+        self.with_recorder(|recorder| recorder.stop());
+        Ok(())
+    }
+
+    // https://w3c.github.io/mediacapture-record/#dom-mediarecorder-pause
+    fn Pause(&self) -> ErrorResult {
+        // Step 1.
+        match self.state.get() {
+            RecordingState::Inactive => return Err(Error::InvalidState),
+            RecordingState::Paused => return Ok(()),
+            RecordingState::Recording => {},
+        }
+
+        // Steps 2-3.
+        self.state.set(RecordingState::Paused);
+        self.with_recorder(|recorder| recorder.pause());
+        self.queue_simple_event(atom!("pause"));
+        Ok(())
+    }
+
+    // https://w3c.github.io/mediacapture-record/#dom-mediarecorder-resume
+    fn Resume(&self) -> ErrorResult {
+        // Step 1.
+        match self.state.get() {
+            RecordingState::Inactive => return Err(Error::InvalidState),
+            RecordingState::Recording => return Ok(()),
+            RecordingState::Paused => {},
+        }
+
+        // Steps 2-3.
+        self.state.set(RecordingState::Recording);
+        self.with_recorder(|recorder| recorder.resume());
+        self.queue_simple_event(atom!("resume"));
+        Ok(())
+    }
+
+    // https://w3c.github.io/mediacapture-record/#dom-mediarecorder-requestdata
+    fn RequestData(&self) -> ErrorResult {
+        // Step 1.
+        if self.state.get() == RecordingState::Inactive {
+            return Err(Error::InvalidState);
+        }
+
+        // Step 2.
+        self.with_recorder(|recorder| recorder.request_data());
+        Ok(())
+    }
+}
+
+impl MediaRecorder {
+    // https://w3c.github.io/mediacapture-record/#dom-mediarecorder-istypesupported
+    pub fn IsTypeSupported(_: &Window, type_: DOMString) -> bool {
+        is_type_supported(&type_)
+    }
+}
+
+/// Whether recordings can be encoded to the MIME type `type_`,
+/// including the codecs it lists, if any.
+fn is_type_supported(type_: &str) -> bool {
+    let mime = match type_.parse::<Mime>() {
+        Ok(mime) => mime,
+        Err(_) => return false,
+    };
+    let essence = format!("{}/{}", mime.type_(), mime.subtype()).to_ascii_lowercase();
+    let codecs = match SUPPORTED_TYPES.iter().find(|&&(ty, _)| ty == essence) {
+        Some(&(_, codecs)) => codecs,
+        None => return false,
+    };
+    match mime.get_param("codecs") {
+        Some(requested) => requested
+            .as_str()
+            .split(',')
+            .map(|codec| codec.trim().to_ascii_lowercase())
+            .all(|codec| codecs.contains(&&*codec)),
+        None => true,
+    }
+}
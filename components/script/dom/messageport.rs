@@ -0,0 +1,192 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::compartments::enter_realm;
+use crate::dom::audioworkletglobalscope::{AudioWorkletProcessorId, AudioWorkletTask};
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::EventHandlerBinding::EventHandlerNonNull;
+use crate::dom::bindings::codegen::Bindings::MessagePortBinding::{self, MessagePortMethods};
+use crate::dom::bindings::error::ErrorResult;
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::refcounted::Trusted;
+use crate::dom::bindings::reflector::{reflect_dom_object, DomObject};
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::bindings::structuredclone::StructuredCloneData;
+use crate::dom::eventtarget::EventTarget;
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::messageevent::MessageEvent;
+use crate::dom::window::Window;
+use crate::dom::worklet::WorkletExecutor;
+use crate::dom::workletglobalscope::{WorkletGlobalScope, WorkletTask};
+use crate::script_runtime::JSContext;
+use crate::task_source::TaskSource;
+use dom_struct::dom_struct;
+use js::jsapi::JSObject;
+use js::jsval::UndefinedValue;
+use js::rust::{CustomAutoRooterGuard, HandleValue};
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+/// The port a `MessagePort` is entangled with.
+///
+/// Ports are only created in pairs by audio worklet nodes for now,
+/// with one end in the script thread and the other one in the
+/// global scope of the audio worklet.
+#[derive(JSTraceable)]
+pub enum EntangledPort {
+    /// The port of an `AudioWorkletProcessor`, which is reached
+    /// by scheduling a task in its worklet.
+    AudioWorkletProcessor(WorkletExecutor, AudioWorkletProcessorId),
+    /// The port of an `AudioWorkletNode`, which is reached by queuing
+    /// a task in the script thread.
+    AudioWorkletNode(Trusted<MessagePort>),
+}
+
+#[dom_struct]
+/// <https://html.spec.whatwg.org/multipage/#messageport>
+pub struct MessagePort {
+    eventtarget: EventTarget,
+    #[ignore_malloc_size_of = "channels are hard"]
+    entangled_port: DomRefCell<Option<EntangledPort>>,
+    /// <https://html.spec.whatwg.org/multipage/#port-message-queue>
+    #[ignore_malloc_size_of = "StructuredCloneData"]
+    port_message_queue: DomRefCell<VecDeque<StructuredCloneData>>,
+    /// Whether the port message queue is enabled.
+    enabled: Cell<bool>,
+    closed: Cell<bool>,
+}
+
+impl MessagePort {
+    fn new_inherited(entangled_port: Option<EntangledPort>) -> MessagePort {
+        MessagePort {
+            eventtarget: EventTarget::new_inherited(),
+            entangled_port: DomRefCell::new(entangled_port),
+            port_message_queue: Default::default(),
+            enabled: Cell::new(false),
+            closed: Cell::new(false),
+        }
+    }
+
+    pub fn new(
+        global: &GlobalScope,
+        entangled_port: Option<EntangledPort>,
+    ) -> DomRoot<MessagePort> {
+        reflect_dom_object(
+            Box::new(MessagePort::new_inherited(entangled_port)),
+            global,
+            MessagePortBinding::Wrap,
+        )
+    }
+
+    /// Entangle this port with `entangled_port`.
+    pub fn entangle(&self, entangled_port: EntangledPort) {
+        *self.entangled_port.borrow_mut() = Some(entangled_port);
+    }
+
+    /// Receive a message sent through the entangled port.
+    pub fn handle_message(&self, data: StructuredCloneData) {
+        if self.closed.get() {
+            return;
+        }
+        self.port_message_queue.borrow_mut().push_back(data);
+        if self.enabled.get() {
+            self.deliver_pending_messages();
+        }
+    }
+
+    /// Dispatch the messages of the port message queue, if it is enabled.
+    pub fn deliver_pending_messages(&self) {
+        if !self.enabled.get() {
+            return;
+        }
+        loop {
+            // The queue musn't be borrowed while the event is dispatched.
+            let data = match self.port_message_queue.borrow_mut().pop_front() {
+                Some(data) => data,
+                None => return,
+            };
+            let global = self.global();
+            let _ac = enter_realm(self);
+            rooted!(in(*global.get_cx()) let mut message = UndefinedValue());
+            data.read(&global, message.handle_mut());
+            MessageEvent::dispatch_jsval(self.upcast(), &global, message.handle(), None, None);
+        }
+    }
+}
+
+impl MessagePortMethods for MessagePort {
+    /// <https://html.spec.whatwg.org/multipage/#dom-messageport-postmessage>
+    fn PostMessage(
+        &self,
+        cx: JSContext,
+        message: HandleValue,
+        transfer: CustomAutoRooterGuard<Vec<*mut JSObject>>,
+    ) -> ErrorResult {
+        let data = StructuredCloneData::write_with_transfer(*cx, message, &transfer)?;
+
+        // A disentangled port silently drops the message.
+        match *self.entangled_port.borrow() {
+            Some(EntangledPort::AudioWorkletProcessor(ref executor, id)) => {
+                let task = AudioWorkletTask::PostMessage(id, data);
+                executor.schedule_a_worklet_task(WorkletTask::Audio(task));
+            },
+            Some(EntangledPort::AudioWorkletNode(ref port)) => {
+                let port = port.clone();
+                let global = self.global();
+                let worklet_global = global
+                    .downcast::<WorkletGlobalScope>()
+                    .expect("The port of a processor lives in a worklet.");
+                worklet_global.run_in_script_thread(task!(post_message: move || {
+                    port.root().handle_message(data);
+                }));
+            },
+            None => {},
+        }
+        Ok(())
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-messageport-start>
+    fn Start(&self) {
+        if self.enabled.replace(true) {
+            return;
+        }
+        // The messages received so far are dispatched in a task of their own.
+        // Worklet global scopes deliver them once the current worklet task is over.
+        let global = self.global();
+        if let Some(window) = global.downcast::<Window>() {
+            let this = Trusted::new(self);
+            let _ = window.task_manager().dom_manipulation_task_source().queue(
+                task!(deliver_port_messages: move || {
+                    this.root().deliver_pending_messages();
+                }),
+                window.upcast(),
+            );
+        }
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-messageport-close>
+    fn Close(&self) {
+        self.closed.set(true);
+        self.entangled_port.borrow_mut().take();
+        self.port_message_queue.borrow_mut().clear();
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#handler-messageport-onmessage>
+    fn GetOnmessage(&self) -> Option<Rc<EventHandlerNonNull>> {
+        self.upcast::<EventTarget>()
+            .get_event_handler_common("message")
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#handler-messageport-onmessage>
+    fn SetOnmessage(&self, listener: Option<Rc<EventHandlerNonNull>>) {
+        self.upcast::<EventTarget>()
+            .set_event_handler_common("message", listener);
+        // Setting the handler implicitly starts the port.
+        self.Start();
+    }
+
+    // https://html.spec.whatwg.org/multipage/#handler-messageport-onmessageerror
+    event_handler!(messageerror, GetOnmessageerror, SetOnmessageerror);
+}
@@ -213,6 +213,10 @@ pub mod abstractworker;
 pub mod abstractworkerglobalscope;
 pub mod activation;
 pub mod analysernode;
+pub mod animation;
+pub mod animationeffect;
+pub mod animationplaybackevent;
+pub mod animationtimeline;
 pub mod attr;
 pub mod audiobuffer;
 pub mod audiobuffersourcenode;
@@ -221,14 +225,20 @@ pub mod audiodestinationnode;
 pub mod audiolistener;
 pub mod audionode;
 pub mod audioparam;
+pub mod audioparammap;
 pub mod audioscheduledsourcenode;
 pub mod audiotrack;
 pub mod audiotracklist;
+pub mod audioworklet;
+pub mod audioworkletglobalscope;
+pub mod audioworkletnode;
+pub mod audioworkletprocessor;
 pub mod baseaudiocontext;
 pub mod beforeunloadevent;
 pub mod bindings;
 pub mod biquadfilternode;
 pub mod blob;
+pub mod blobevent;
 pub mod bluetooth;
 pub mod bluetoothadvertisingevent;
 pub mod bluetoothcharacteristicproperties;
@@ -247,22 +257,31 @@ pub mod channelmergernode;
 pub mod channelsplitternode;
 pub mod characterdata;
 pub mod client;
+pub mod clipboard;
+pub mod clipboardevent;
+pub mod clipboarditem;
 pub mod closeevent;
 pub mod comment;
 pub mod compositionevent;
 pub mod console;
 pub mod constantsourcenode;
+pub mod convolvernode;
 mod create;
 pub mod crypto;
 pub mod css;
+pub mod cssanimation;
 pub mod cssconditionrule;
+pub mod csscontainerrule;
 pub mod cssfontfacerule;
 pub mod cssgroupingrule;
 pub mod cssimportrule;
 pub mod csskeyframerule;
 pub mod csskeyframesrule;
+pub mod csslayerblockrule;
+pub mod csslayerstatementrule;
 pub mod cssmediarule;
 pub mod cssnamespacerule;
+pub mod csspropertyrule;
 pub mod cssrule;
 pub mod cssrulelist;
 pub mod cssstyledeclaration;
@@ -270,15 +289,21 @@ pub mod cssstylerule;
 pub mod cssstylesheet;
 pub mod cssstylevalue;
 pub mod csssupportsrule;
+pub mod csstransition;
 pub mod cssviewportrule;
 pub mod customelementregistry;
 pub mod customevent;
+pub mod datatransfer;
+pub mod datatransferitem;
+pub mod datatransferitemlist;
 pub mod dedicatedworkerglobalscope;
+pub mod delaynode;
 pub mod dissimilaroriginlocation;
 pub mod dissimilaroriginwindow;
 pub mod document;
 pub mod documentfragment;
 pub mod documentorshadowroot;
+pub mod documenttimeline;
 pub mod documenttype;
 pub mod domexception;
 pub mod domimplementation;
@@ -293,6 +318,8 @@ pub mod domrectreadonly;
 pub mod domstringlist;
 pub mod domstringmap;
 pub mod domtokenlist;
+pub mod dragevent;
+pub mod dynamicscompressornode;
 pub mod element;
 pub mod errorevent;
 pub mod event;
@@ -306,6 +333,9 @@ pub mod filelist;
 pub mod filereader;
 pub mod filereadersync;
 pub mod focusevent;
+pub mod fontface;
+pub mod fontfaceset;
+pub mod fontfacesetloadevent;
 pub mod formdata;
 pub mod formdataevent;
 pub mod gainnode;
@@ -389,19 +419,27 @@ pub mod htmltrackelement;
 pub mod htmlulistelement;
 pub mod htmlunknownelement;
 pub mod htmlvideoelement;
+pub mod iirfilternode;
+pub mod imagebitmap;
 pub mod imagedata;
 pub mod inputevent;
 pub mod keyboardevent;
+pub mod keyframeeffect;
 pub mod location;
 pub mod mediadevices;
+pub mod mediaelementaudiosourcenode;
 pub mod mediaerror;
 pub mod mediafragmentparser;
 pub mod medialist;
 pub mod mediaquerylist;
 pub mod mediaquerylistevent;
+pub mod mediarecorder;
 pub mod mediastream;
+pub mod mediastreamaudiodestinationnode;
+pub mod mediastreamaudiosourcenode;
 pub mod mediastreamtrack;
 pub mod messageevent;
+pub mod messageport;
 pub mod mimetype;
 pub mod mimetypearray;
 pub mod mouseevent;
@@ -424,6 +462,7 @@ pub mod paintrenderingcontext2d;
 pub mod paintsize;
 pub mod paintworkletglobalscope;
 pub mod pannernode;
+pub mod path2d;
 pub mod performance;
 pub mod performanceentry;
 pub mod performancemark;
@@ -438,6 +477,7 @@ pub mod permissions;
 pub mod permissionstatus;
 pub mod plugin;
 pub mod pluginarray;
+pub mod pointerevent;
 pub mod popstateevent;
 pub mod processinginstruction;
 pub mod progressevent;
@@ -467,9 +507,29 @@ pub mod storageevent;
 pub mod stylepropertymapreadonly;
 pub mod stylesheet;
 pub mod stylesheetlist;
+pub mod svgcircleelement;
+pub mod svgclippathelement;
+pub mod svgdefselement;
 pub mod svgelement;
+pub mod svgellipseelement;
+pub mod svggelement;
+pub mod svggeometryelement;
+pub mod svggradientelement;
 pub mod svggraphicselement;
+pub mod svglineargradientelement;
+pub mod svglineelement;
+pub mod svgpathelement;
+pub mod svgpolygonelement;
+pub mod svgpolylineelement;
+pub mod svgradialgradientelement;
+pub mod svgrectelement;
+pub mod svgstopelement;
 pub mod svgsvgelement;
+pub mod svgtextcontentelement;
+pub mod svgtextelement;
+pub mod svgtextpositioningelement;
+pub mod svgtspanelement;
+pub mod svguseelement;
 pub mod testbinding;
 pub mod testbindingiterable;
 pub mod testbindingpairiterable;
@@ -512,6 +572,7 @@ pub mod vrfieldofview;
 pub mod vrframedata;
 pub mod vrpose;
 pub mod vrstageparameters;
+pub mod waveshapernode;
 pub mod webgl_extensions;
 pub use self::webgl_extensions::ext::*;
 pub mod webgl2renderingcontext;
@@ -10,6 +10,7 @@ use crate::dom::bindings::reflector::{reflect_dom_object, DomObject, Reflector};
 use crate::dom::bindings::root::{DomRoot, MutNullableDom};
 use crate::dom::bindings::str::DOMString;
 use crate::dom::bluetooth::Bluetooth;
+use crate::dom::clipboard::Clipboard;
 use crate::dom::gamepadlist::GamepadList;
 use crate::dom::mediadevices::MediaDevices;
 use crate::dom::mimetypearray::MimeTypeArray;
@@ -34,6 +35,7 @@ pub struct Navigator {
     mediadevices: MutNullableDom<MediaDevices>,
     gamepads: MutNullableDom<GamepadList>,
     permissions: MutNullableDom<Permissions>,
+    clipboard: MutNullableDom<Clipboard>,
 }
 
 impl Navigator {
@@ -48,6 +50,7 @@ impl Navigator {
             mediadevices: Default::default(),
             gamepads: Default::default(),
             permissions: Default::default(),
+            clipboard: Default::default(),
         }
     }
 
@@ -186,4 +189,9 @@ impl NavigatorMethods for Navigator {
         self.mediadevices
             .or_init(|| MediaDevices::new(&self.global()))
     }
+
+    /// https://w3c.github.io/clipboard-apis/#dom-navigator-clipboard
+    fn Clipboard(&self) -> DomRoot<Clipboard> {
+        self.clipboard.or_init(|| Clipboard::new(&self.global()))
+    }
 }
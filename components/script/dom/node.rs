@@ -21,9 +21,14 @@ use crate::dom::bindings::codegen::InheritTypes::DocumentFragmentTypeId;
 use crate::dom::bindings::codegen::UnionTypes::NodeOrString;
 use crate::dom::bindings::conversions::{self, DerivedFrom};
 use crate::dom::bindings::error::{Error, ErrorResult, Fallible};
+use crate::dom::bindings::inheritance::TextTypeId;
 use crate::dom::bindings::inheritance::{Castable, CharacterDataTypeId, ElementTypeId};
 use crate::dom::bindings::inheritance::{EventTargetTypeId, HTMLElementTypeId, NodeTypeId};
-use crate::dom::bindings::inheritance::{SVGElementTypeId, SVGGraphicsElementTypeId, TextTypeId};
+use crate::dom::bindings::inheritance::{SVGElementTypeId, SVGGeometryElementTypeId};
+use crate::dom::bindings::inheritance::{SVGGradientElementTypeId, SVGGraphicsElementTypeId};
+use crate::dom::bindings::inheritance::{
+    SVGTextContentElementTypeId, SVGTextPositioningElementTypeId,
+};
 use crate::dom::bindings::reflector::{reflect_dom_object, DomObject};
 use crate::dom::bindings::root::{Dom, DomRoot, DomSlice, LayoutDom, MutNullableDom};
 use crate::dom::bindings::str::{DOMString, USVString};
@@ -3222,9 +3227,79 @@ impl Into<LayoutElementType> for ElementTypeId {
             ElementTypeId::HTMLElement(HTMLElementTypeId::HTMLTextAreaElement) => {
                 LayoutElementType::HTMLTextAreaElement
             },
+            ElementTypeId::SVGElement(SVGElementTypeId::SVGGraphicsElement(
+                SVGGraphicsElementTypeId::SVGGeometryElement(
+                    SVGGeometryElementTypeId::SVGCircleElement,
+                ),
+            )) => LayoutElementType::SVGCircleElement,
+            ElementTypeId::SVGElement(SVGElementTypeId::SVGClipPathElement) => {
+                LayoutElementType::SVGClipPathElement
+            },
+            ElementTypeId::SVGElement(SVGElementTypeId::SVGGraphicsElement(
+                SVGGraphicsElementTypeId::SVGDefsElement,
+            )) => LayoutElementType::SVGDefsElement,
+            ElementTypeId::SVGElement(SVGElementTypeId::SVGGraphicsElement(
+                SVGGraphicsElementTypeId::SVGGeometryElement(
+                    SVGGeometryElementTypeId::SVGEllipseElement,
+                ),
+            )) => LayoutElementType::SVGEllipseElement,
+            ElementTypeId::SVGElement(SVGElementTypeId::SVGGraphicsElement(
+                SVGGraphicsElementTypeId::SVGGElement,
+            )) => LayoutElementType::SVGGElement,
+            ElementTypeId::SVGElement(SVGElementTypeId::SVGGraphicsElement(
+                SVGGraphicsElementTypeId::SVGGeometryElement(
+                    SVGGeometryElementTypeId::SVGLineElement,
+                ),
+            )) => LayoutElementType::SVGLineElement,
+            ElementTypeId::SVGElement(SVGElementTypeId::SVGGradientElement(
+                SVGGradientElementTypeId::SVGLinearGradientElement,
+            )) => LayoutElementType::SVGLinearGradientElement,
+            ElementTypeId::SVGElement(SVGElementTypeId::SVGGraphicsElement(
+                SVGGraphicsElementTypeId::SVGGeometryElement(
+                    SVGGeometryElementTypeId::SVGPathElement,
+                ),
+            )) => LayoutElementType::SVGPathElement,
+            ElementTypeId::SVGElement(SVGElementTypeId::SVGGraphicsElement(
+                SVGGraphicsElementTypeId::SVGGeometryElement(
+                    SVGGeometryElementTypeId::SVGPolygonElement,
+                ),
+            )) => LayoutElementType::SVGPolygonElement,
+            ElementTypeId::SVGElement(SVGElementTypeId::SVGGraphicsElement(
+                SVGGraphicsElementTypeId::SVGGeometryElement(
+                    SVGGeometryElementTypeId::SVGPolylineElement,
+                ),
+            )) => LayoutElementType::SVGPolylineElement,
+            ElementTypeId::SVGElement(SVGElementTypeId::SVGGradientElement(
+                SVGGradientElementTypeId::SVGRadialGradientElement,
+            )) => LayoutElementType::SVGRadialGradientElement,
+            ElementTypeId::SVGElement(SVGElementTypeId::SVGGraphicsElement(
+                SVGGraphicsElementTypeId::SVGGeometryElement(
+                    SVGGeometryElementTypeId::SVGRectElement,
+                ),
+            )) => LayoutElementType::SVGRectElement,
+            ElementTypeId::SVGElement(SVGElementTypeId::SVGStopElement) => {
+                LayoutElementType::SVGStopElement
+            },
             ElementTypeId::SVGElement(SVGElementTypeId::SVGGraphicsElement(
                 SVGGraphicsElementTypeId::SVGSVGElement,
             )) => LayoutElementType::SVGSVGElement,
+            ElementTypeId::SVGElement(SVGElementTypeId::SVGGraphicsElement(
+                SVGGraphicsElementTypeId::SVGTextContentElement(
+                    SVGTextContentElementTypeId::SVGTextPositioningElement(
+                        SVGTextPositioningElementTypeId::SVGTSpanElement,
+                    ),
+                ),
+            )) => LayoutElementType::SVGTSpanElement,
+            ElementTypeId::SVGElement(SVGElementTypeId::SVGGraphicsElement(
+                SVGGraphicsElementTypeId::SVGTextContentElement(
+                    SVGTextContentElementTypeId::SVGTextPositioningElement(
+                        SVGTextPositioningElementTypeId::SVGTextElement,
+                    ),
+                ),
+            )) => LayoutElementType::SVGTextElement,
+            ElementTypeId::SVGElement(SVGElementTypeId::SVGGraphicsElement(
+                SVGGraphicsElementTypeId::SVGUseElement,
+            )) => LayoutElementType::SVGUseElement,
             _ => LayoutElementType::Element,
         }
     }
@@ -2,67 +2,196 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
+use crate::compartments::InCompartment;
 use crate::dom::bindings::cell::DomRefCell;
 use crate::dom::bindings::codegen::Bindings::OffscreenCanvasBinding::{
-    OffscreenCanvasMethods, OffscreenRenderingContext, Wrap as OffscreenCanvasWrap,
+    ImageEncodeOptions, OffscreenCanvasMethods, OffscreenRenderingContext,
+    Wrap as OffscreenCanvasWrap,
 };
-use crate::dom::bindings::error::Fallible;
+use crate::dom::bindings::codegen::UnionTypes::HTMLCanvasElementOrOffscreenCanvas;
+use crate::dom::bindings::error::{Error, Fallible};
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::refcounted::{Trusted, TrustedPromise};
 use crate::dom::bindings::reflector::reflect_dom_object;
 use crate::dom::bindings::reflector::DomObject;
-use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::bindings::root::{Dom, DomRoot, LayoutDom};
 use crate::dom::bindings::str::DOMString;
+use crate::dom::canvasrenderingcontext2d;
 use crate::dom::eventtarget::EventTarget;
 use crate::dom::globalscope::GlobalScope;
 use crate::dom::htmlcanvaselement::HTMLCanvasElement;
+use crate::dom::imagebitmap::{self, ImageBitmap};
+use crate::dom::node::{Node, NodeDamage};
 use crate::dom::offscreencanvasrenderingcontext2d::OffscreenCanvasRenderingContext2D;
+use crate::dom::promise::Promise;
+use crate::dom::webgl2renderingcontext::WebGL2RenderingContext;
+use crate::dom::webglrenderingcontext::{
+    LayoutCanvasWebGLRenderingContextHelpers, WebGLRenderingContext,
+};
+use crate::image_encoder::{self, EncodedImageType};
 use crate::script_runtime::JSContext;
+use crate::task::TaskCanceller;
+use crate::task_source::dom_manipulation::DOMManipulationTaskSource;
+use crate::task_source::{TaskSource, TaskSourceName};
+use canvas_traits::canvas::{CanvasId, CanvasMsg, FromScriptMsg};
+use canvas_traits::webgl::WebGLVersion;
 use dom_struct::dom_struct;
 use euclid::default::Size2D;
+use ipc_channel::ipc::{IpcSender, IpcSharedMemory};
 use js::rust::HandleValue;
+use profile_traits::ipc;
 use ref_filter_map;
+use script_layout_interface::HTMLCanvasDataSource;
+use servo_config::pref;
 use std::cell::Cell;
 use std::cell::Ref;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 #[must_root]
 #[derive(Clone, JSTraceable, MallocSizeOf)]
 pub enum OffscreenCanvasContext {
     OffscreenContext2d(Dom<OffscreenCanvasRenderingContext2D>),
-    //WebGL(Dom<WebGLRenderingContext>),
-    //WebGL2(Dom<WebGL2RenderingContext>),
+    WebGL(Dom<WebGLRenderingContext>),
+    WebGL2(Dom<WebGL2RenderingContext>),
+}
+
+/// The canvas of the canvas paint thread that an offscreen canvas created by
+/// `transferControlToOffscreen()` draws into, and that its placeholder canvas
+/// element displays.
+#[derive(Clone, JSTraceable, MallocSizeOf)]
+pub struct PlaceholderCanvas {
+    #[ignore_malloc_size_of = "Defined in ipc-channel"]
+    pub ipc_renderer: IpcSender<CanvasMsg>,
+    pub canvas_id: CanvasId,
+}
+
+/// Lets an offscreen canvas that was transferred to another thread tell its
+/// placeholder canvas element that it needs to be displayed again.
+pub struct PlaceholderUpdater {
+    element: Trusted<HTMLCanvasElement>,
+    task_source: DOMManipulationTaskSource,
+    canceller: TaskCanceller,
+    /// Whether an update was queued and didn't run yet.
+    update_pending: Arc<AtomicBool>,
+}
+
+unsafe_no_jsmanaged_fields!(PlaceholderUpdater);
+
+impl PlaceholderUpdater {
+    fn new(element: &HTMLCanvasElement) -> PlaceholderUpdater {
+        let global = element.global();
+        PlaceholderUpdater {
+            element: Trusted::new(element),
+            task_source: global.dom_manipulation_task_source(),
+            canceller: global.task_canceller(TaskSourceName::DOMManipulation),
+            update_pending: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    fn update(&self) {
+        if self.update_pending.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let element = self.element.clone();
+        let update_pending = self.update_pending.clone();
+        let _ = self.task_source.queue_with_canceller(
+            task!(update_placeholder_canvas: move || {
+                update_pending.store(false, Ordering::SeqCst);
+                element.root().upcast::<Node>().dirty(NodeDamage::OtherNodeDamage);
+            }),
+            &self.canceller,
+        );
+    }
+}
+
+/// What an offscreen canvas hands over to the canvas it is transferred to.
+pub struct TransferredOffscreenCanvas {
+    width: u64,
+    height: u64,
+    placeholder_canvas: Option<PlaceholderCanvas>,
+    placeholder_updater: Option<PlaceholderUpdater>,
 }
 
 #[dom_struct]
 pub struct OffscreenCanvas {
     eventtarget: EventTarget,
-    height: Cell<u64>,
     width: Cell<u64>,
+    height: Cell<u64>,
     context: DomRefCell<Option<OffscreenCanvasContext>>,
+    /// The placeholder canvas element, while this canvas lives on its thread.
     placeholder: Option<Dom<HTMLCanvasElement>>,
+    placeholder_canvas: Option<PlaceholderCanvas>,
+    /// How to update the placeholder canvas element from another thread.
+    #[ignore_malloc_size_of = "Trusted"]
+    placeholder_updater: DomRefCell<Option<PlaceholderUpdater>>,
+    /// Whether this canvas was transferred to another thread.
+    detached: Cell<bool>,
 }
 
 impl OffscreenCanvas {
     pub fn new_inherited(
-        height: u64,
         width: u64,
+        height: u64,
         placeholder: Option<&HTMLCanvasElement>,
+        placeholder_canvas: Option<PlaceholderCanvas>,
+        placeholder_updater: Option<PlaceholderUpdater>,
     ) -> OffscreenCanvas {
         OffscreenCanvas {
             eventtarget: EventTarget::new_inherited(),
-            height: Cell::new(height),
             width: Cell::new(width),
+            height: Cell::new(height),
             context: DomRefCell::new(None),
             placeholder: placeholder.map(Dom::from_ref),
+            placeholder_canvas,
+            placeholder_updater: DomRefCell::new(placeholder_updater),
+            detached: Cell::new(false),
         }
     }
 
     pub fn new(
         global: &GlobalScope,
-        height: u64,
         width: u64,
+        height: u64,
         placeholder: Option<&HTMLCanvasElement>,
+    ) -> DomRoot<OffscreenCanvas> {
+        // The placeholder displays the canvas this one draws into from the
+        // start, wherever it ends up being drawn from.
+        let placeholder_canvas = placeholder.map(|_| {
+            let (ipc_renderer, canvas_id) =
+                canvasrenderingcontext2d::create_canvas(global, Size2D::new(width, height));
+            PlaceholderCanvas {
+                ipc_renderer,
+                canvas_id,
+            }
+        });
+        reflect_dom_object(
+            Box::new(OffscreenCanvas::new_inherited(
+                width,
+                height,
+                placeholder,
+                placeholder_canvas,
+                None,
+            )),
+            global,
+            OffscreenCanvasWrap,
+        )
+    }
+
+    /// Creates the canvas that an offscreen canvas was transferred to.
+    pub fn new_transferred(
+        global: &GlobalScope,
+        transferred: TransferredOffscreenCanvas,
     ) -> DomRoot<OffscreenCanvas> {
         reflect_dom_object(
-            Box::new(OffscreenCanvas::new_inherited(height, width, placeholder)),
+            Box::new(OffscreenCanvas::new_inherited(
+                transferred.width,
+                transferred.height,
+                None,
+                transferred.placeholder_canvas,
+                transferred.placeholder_updater,
+            )),
             global,
             OffscreenCanvasWrap,
         )
@@ -70,10 +199,10 @@ impl OffscreenCanvas {
 
     pub fn Constructor(
         global: &GlobalScope,
-        height: u64,
         width: u64,
+        height: u64,
     ) -> Fallible<DomRoot<OffscreenCanvas>> {
-        let offscreencanvas = OffscreenCanvas::new(global, height, width, None);
+        let offscreencanvas = OffscreenCanvas::new(global, width, height, None);
         Ok(offscreencanvas)
     }
 
@@ -81,15 +210,35 @@ impl OffscreenCanvas {
         Size2D::new(self.Width(), self.Height())
     }
 
+    fn get_webgl_size(&self) -> Size2D<u32> {
+        let size = self.get_size();
+        Size2D::new(size.width as u32, size.height as u32)
+    }
+
     pub fn context(&self) -> Option<Ref<OffscreenCanvasContext>> {
         ref_filter_map::ref_filter_map(self.context.borrow(), |ctx| ctx.as_ref())
     }
 
+    pub fn placeholder(&self) -> Option<DomRoot<HTMLCanvasElement>> {
+        self.placeholder
+            .as_ref()
+            .map(|placeholder| DomRoot::from_ref(&**placeholder))
+    }
+
+    pub fn placeholder_canvas(&self) -> Option<&PlaceholderCanvas> {
+        self.placeholder_canvas.as_ref()
+    }
+
+    pub fn is_detached(&self) -> bool {
+        self.detached.get()
+    }
+
     #[allow(unsafe_code)]
     fn get_or_init_2d_context(&self) -> Option<DomRoot<OffscreenCanvasRenderingContext2D>> {
         if let Some(ctx) = self.context() {
             return match *ctx {
                 OffscreenCanvasContext::OffscreenContext2d(ref ctx) => Some(DomRoot::from_ref(ctx)),
+                _ => None,
             };
         }
         let size = self.get_size();
@@ -104,28 +253,229 @@ impl OffscreenCanvas {
         ));
         Some(context)
     }
+
+    fn get_or_init_webgl_context(
+        &self,
+        cx: JSContext,
+        options: HandleValue,
+    ) -> Option<DomRoot<WebGLRenderingContext>> {
+        if let Some(ctx) = self.context() {
+            return match *ctx {
+                OffscreenCanvasContext::WebGL(ref ctx) => Some(DomRoot::from_ref(ctx)),
+                _ => None,
+            };
+        }
+        let attrs = HTMLCanvasElement::get_gl_attributes(cx, options)?;
+        let canvas = HTMLCanvasElementOrOffscreenCanvas::OffscreenCanvas(DomRoot::from_ref(self));
+        let context = WebGLRenderingContext::new(
+            &self.global(),
+            &canvas,
+            WebGLVersion::WebGL1,
+            self.get_webgl_size(),
+            attrs,
+        )?;
+        *self.context.borrow_mut() = Some(OffscreenCanvasContext::WebGL(Dom::from_ref(&*context)));
+        Some(context)
+    }
+
+    fn get_or_init_webgl2_context(
+        &self,
+        cx: JSContext,
+        options: HandleValue,
+    ) -> Option<DomRoot<WebGL2RenderingContext>> {
+        if !pref!(dom.webgl2.enabled) {
+            return None;
+        }
+        if let Some(ctx) = self.context() {
+            return match *ctx {
+                OffscreenCanvasContext::WebGL2(ref ctx) => Some(DomRoot::from_ref(ctx)),
+                _ => None,
+            };
+        }
+        let attrs = HTMLCanvasElement::get_gl_attributes(cx, options)?;
+        let canvas = HTMLCanvasElementOrOffscreenCanvas::OffscreenCanvas(DomRoot::from_ref(self));
+        let context =
+            WebGL2RenderingContext::new(&self.global(), &canvas, self.get_webgl_size(), attrs)?;
+        *self.context.borrow_mut() = Some(OffscreenCanvasContext::WebGL2(Dom::from_ref(&*context)));
+        Some(context)
+    }
+
+    /// Resizes the bitmap after the width or height changed.
+    fn recreate_context(&self) {
+        let size = self.get_webgl_size();
+        match *self.context.borrow() {
+            Some(OffscreenCanvasContext::OffscreenContext2d(ref context)) => {
+                context.set_bitmap_dimensions(size)
+            },
+            Some(OffscreenCanvasContext::WebGL(ref context)) => context.recreate(size),
+            Some(OffscreenCanvasContext::WebGL2(ref context)) => {
+                context.base_context().recreate(size)
+            },
+            None => {
+                if let Some(ref placeholder_canvas) = self.placeholder_canvas {
+                    placeholder_canvas
+                        .ipc_renderer
+                        .send(CanvasMsg::Recreate(size, placeholder_canvas.canvas_id))
+                        .unwrap();
+                }
+            },
+        }
+    }
+
+    pub fn origin_is_clean(&self) -> bool {
+        match *self.context.borrow() {
+            Some(OffscreenCanvasContext::OffscreenContext2d(ref context)) => {
+                context.origin_is_clean()
+            },
+            _ => true,
+        }
+    }
+
+    /// Tells the placeholder canvas element, if any, that it needs to be
+    /// displayed again after this canvas was drawn into.
+    pub fn mark_as_dirty(&self) {
+        if let Some(ref placeholder) = self.placeholder {
+            placeholder
+                .upcast::<Node>()
+                .dirty(NodeDamage::OtherNodeDamage);
+        } else if let Some(ref placeholder_updater) = *self.placeholder_updater.borrow() {
+            placeholder_updater.update();
+        }
+    }
+
+    /// The pixels of the canvas displayed by the placeholder canvas element,
+    /// in BGRA8 format with premultiplied alpha.
+    pub fn fetch_placeholder_pixels(&self) -> Option<IpcSharedMemory> {
+        let placeholder_canvas = self.placeholder_canvas.as_ref()?;
+        let (sender, receiver) = ipc::channel(self.global().time_profiler_chan().clone()).unwrap();
+        let msg = CanvasMsg::FromScript(
+            FromScriptMsg::SendPixels(sender),
+            placeholder_canvas.canvas_id,
+        );
+        placeholder_canvas.ipc_renderer.send(msg).unwrap();
+        Some(receiver.recv().unwrap())
+    }
+
+    /// The bitmap in RGBA8 format without premultiplied alpha.
+    pub fn get_image_data(&self) -> Vec<u8> {
+        let size = self.get_size();
+        let data = match *self.context.borrow() {
+            Some(OffscreenCanvasContext::OffscreenContext2d(ref context)) => {
+                let (sender, receiver) =
+                    ipc::channel(self.global().time_profiler_chan().clone()).unwrap();
+                let msg = CanvasMsg::FromScript(
+                    FromScriptMsg::SendPixels(sender),
+                    context.get_canvas_id(),
+                );
+                context.get_ipc_renderer().send(msg).unwrap();
+                Some(receiver.recv().unwrap().to_vec())
+            },
+            Some(OffscreenCanvasContext::WebGL(ref context)) => {
+                return context
+                    .get_image_data(self.get_webgl_size())
+                    .unwrap_or_else(|| vec![0; (size.width * size.height * 4) as usize]);
+            },
+            Some(OffscreenCanvasContext::WebGL2(ref context)) => {
+                return context
+                    .base_context()
+                    .get_image_data(self.get_webgl_size())
+                    .unwrap_or_else(|| vec![0; (size.width * size.height * 4) as usize]);
+            },
+            None => self.fetch_placeholder_pixels().map(|data| data.to_vec()),
+        };
+        match data {
+            Some(mut data) => {
+                imagebitmap::unpremultiply_inplace(&mut data);
+                pixels::rgba8_byte_swap_colors_inplace(&mut data);
+                data
+            },
+            // Each pixel is fully-transparent black.
+            None => vec![0; (size.width * size.height * 4) as usize],
+        }
+    }
+
+    /// Detaches this canvas, and returns what the canvas it is transferred to
+    /// takes over.
+    pub fn transfer(&self) -> TransferredOffscreenCanvas {
+        self.detached.set(true);
+        let placeholder_updater = match self.placeholder {
+            Some(ref placeholder) => Some(PlaceholderUpdater::new(placeholder)),
+            None => self.placeholder_updater.borrow_mut().take(),
+        };
+        TransferredOffscreenCanvas {
+            width: self.Width(),
+            height: self.Height(),
+            placeholder_canvas: self.placeholder_canvas.clone(),
+            placeholder_updater,
+        }
+    }
+}
+
+pub trait LayoutOffscreenCanvasHelpers {
+    #[allow(unsafe_code)]
+    unsafe fn canvas_data_source(&self) -> HTMLCanvasDataSource;
+    #[allow(unsafe_code)]
+    unsafe fn get_canvas_id_for_layout(&self) -> CanvasId;
+}
+
+impl LayoutOffscreenCanvasHelpers for LayoutDom<OffscreenCanvas> {
+    #[allow(unsafe_code)]
+    unsafe fn canvas_data_source(&self) -> HTMLCanvasDataSource {
+        let canvas = &*self.unsafe_get();
+        match canvas.context.borrow_for_layout().as_ref() {
+            Some(&OffscreenCanvasContext::WebGL(ref context)) => {
+                context.to_layout().canvas_data_source()
+            },
+            Some(&OffscreenCanvasContext::WebGL2(ref context)) => {
+                context.to_layout().canvas_data_source()
+            },
+            _ => HTMLCanvasDataSource::Image(
+                canvas
+                    .placeholder_canvas
+                    .as_ref()
+                    .map(|placeholder_canvas| placeholder_canvas.ipc_renderer.clone()),
+            ),
+        }
+    }
+
+    #[allow(unsafe_code)]
+    unsafe fn get_canvas_id_for_layout(&self) -> CanvasId {
+        let canvas = &*self.unsafe_get();
+        canvas
+            .placeholder_canvas
+            .as_ref()
+            .map_or(CanvasId(0), |placeholder_canvas| {
+                placeholder_canvas.canvas_id
+            })
+    }
 }
 
 impl OffscreenCanvasMethods for OffscreenCanvas {
     // https://html.spec.whatwg.org/multipage/#dom-offscreencanvas-getcontext
     fn GetContext(
         &self,
-        _cx: JSContext,
+        cx: JSContext,
         id: DOMString,
-        _options: HandleValue,
-    ) -> Option<OffscreenRenderingContext> {
-        match &*id {
+        options: HandleValue,
+    ) -> Fallible<Option<OffscreenRenderingContext>> {
+        // Step 1.
+        if self.detached.get() {
+            return Err(Error::InvalidState);
+        }
+
+        let context = match &*id {
             "2d" => self
                 .get_or_init_2d_context()
                 .map(OffscreenRenderingContext::OffscreenCanvasRenderingContext2D),
-            /*"webgl" | "experimental-webgl" => self
+            "webgl" | "experimental-webgl" => self
                 .get_or_init_webgl_context(cx, options)
                 .map(OffscreenRenderingContext::WebGLRenderingContext),
             "webgl2" | "experimental-webgl2" => self
                 .get_or_init_webgl2_context(cx, options)
-                .map(OffscreenRenderingContext::WebGL2RenderingContext),*/
+                .map(OffscreenRenderingContext::WebGL2RenderingContext),
             _ => None,
-        }
+        };
+        Ok(context)
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-offscreencanvas-width
@@ -136,6 +486,7 @@ impl OffscreenCanvasMethods for OffscreenCanvas {
     // https://html.spec.whatwg.org/multipage/#dom-offscreencanvas-width
     fn SetWidth(&self, value: u64) {
         self.width.set(value);
+        self.recreate_context();
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-offscreencanvas-height
@@ -146,5 +497,90 @@ impl OffscreenCanvasMethods for OffscreenCanvas {
     // https://html.spec.whatwg.org/multipage/#dom-offscreencanvas-height
     fn SetHeight(&self, value: u64) {
         self.height.set(value);
+        self.recreate_context();
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-offscreencanvas-transfertoimagebitmap
+    fn TransferToImageBitmap(&self) -> Fallible<DomRoot<ImageBitmap>> {
+        // Step 1.
+        if self.detached.get() {
+            return Err(Error::InvalidState);
+        }
+
+        // Step 2.
+        let context = self.context().ok_or(Error::InvalidState)?;
+
+        // Steps 3-5.
+        let size = self.get_webgl_size();
+        let (data, origin_clean) = match *context {
+            OffscreenCanvasContext::OffscreenContext2d(ref context) => {
+                let (sender, receiver) =
+                    ipc::channel(self.global().time_profiler_chan().clone()).unwrap();
+                let msg = CanvasMsg::FromScript(
+                    FromScriptMsg::TransferPixels(sender),
+                    context.get_canvas_id(),
+                );
+                context.get_ipc_renderer().send(msg).unwrap();
+                (receiver.recv().unwrap().to_vec(), context.origin_is_clean())
+            },
+            OffscreenCanvasContext::WebGL(_) | OffscreenCanvasContext::WebGL2(_) => {
+                let mut data = self.get_image_data();
+                pixels::rgba8_byte_swap_and_premultiply_inplace(&mut data);
+                (data, true)
+            },
+        };
+        Ok(ImageBitmap::new(
+            &self.global(),
+            size,
+            data,
+            true,
+            origin_clean,
+        ))
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-offscreencanvas-converttoblob
+    fn ConvertToBlob(&self, options: &ImageEncodeOptions, comp: InCompartment) -> Rc<Promise> {
+        let global = self.global();
+        let promise = Promise::new_in_current_compartment(&global, comp);
+
+        // Step 1.
+        if self.detached.get() {
+            promise.reject_error(Error::InvalidState);
+            return promise;
+        }
+
+        // Step 2.
+        if !self.origin_is_clean() {
+            promise.reject_error(Error::Security);
+            return promise;
+        }
+
+        // Step 3.
+        let size = self.get_size();
+        if size.width == 0 || size.height == 0 {
+            promise.reject_error(Error::IndexSize);
+            return promise;
+        }
+
+        // Step 4.
+        let data = self.get_image_data();
+
+        // Steps 5-6.
+        let trusted_promise = TrustedPromise::new(promise.clone());
+        image_encoder::encode_to_blob_in_parallel(
+            &global,
+            data,
+            Size2D::new(size.width as u32, size.height as u32),
+            EncodedImageType::from_mime_type(Some(&*options.type_)),
+            options.quality,
+            move |file| {
+                let promise = trusted_promise.root();
+                match file {
+                    Some(file) => promise.resolve_native(&file.into_blob(&promise.global())),
+                    None => promise.reject_error(Error::Encoding),
+                }
+            },
+        );
+        promise
     }
 }
@@ -3,10 +3,14 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
 use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::CanvasRenderingContext2DBinding::CanvasDirection;
 use crate::dom::bindings::codegen::Bindings::CanvasRenderingContext2DBinding::CanvasFillRule;
 use crate::dom::bindings::codegen::Bindings::CanvasRenderingContext2DBinding::CanvasImageSource;
 use crate::dom::bindings::codegen::Bindings::CanvasRenderingContext2DBinding::CanvasLineCap;
 use crate::dom::bindings::codegen::Bindings::CanvasRenderingContext2DBinding::CanvasLineJoin;
+use crate::dom::bindings::codegen::Bindings::CanvasRenderingContext2DBinding::CanvasTextAlign;
+use crate::dom::bindings::codegen::Bindings::CanvasRenderingContext2DBinding::CanvasTextBaseline;
+use crate::dom::bindings::codegen::Bindings::CanvasRenderingContext2DBinding::ImageSmoothingQuality;
 use crate::dom::bindings::codegen::Bindings::OffscreenCanvasRenderingContext2DBinding;
 use crate::dom::bindings::codegen::Bindings::OffscreenCanvasRenderingContext2DBinding::OffscreenCanvasRenderingContext2DMethods;
 use crate::dom::bindings::codegen::UnionTypes::StringOrCanvasGradientOrCanvasPattern;
@@ -23,9 +27,12 @@ use crate::dom::globalscope::GlobalScope;
 use crate::dom::htmlcanvaselement::HTMLCanvasElement;
 use crate::dom::imagedata::ImageData;
 use crate::dom::offscreencanvas::OffscreenCanvas;
+use crate::dom::path2d::Path2D;
 use crate::dom::textmetrics::TextMetrics;
+use canvas_traits::canvas::{CanvasId, CanvasMsg};
 use dom_struct::dom_struct;
 use euclid::default::Size2D;
+use ipc_channel::ipc::IpcSender;
 
 #[dom_struct]
 pub struct OffscreenCanvasRenderingContext2D {
@@ -46,10 +53,20 @@ impl OffscreenCanvasRenderingContext2D {
             reflector_: Reflector::new(),
             canvas: canvas.map(Dom::from_ref),
             htmlcanvas: htmlcanvas.map(Dom::from_ref),
-            canvas_state: DomRefCell::new(CanvasState::new(
-                global,
-                Size2D::new(size.width as u64, size.height as u64),
-            )),
+            canvas_state: DomRefCell::new(
+                match canvas.and_then(|canvas| canvas.placeholder_canvas()) {
+                    // A canvas transferred from an element keeps drawing into
+                    // the canvas its placeholder displays.
+                    Some(placeholder_canvas) => CanvasState::new_with_canvas(
+                        global,
+                        placeholder_canvas.ipc_renderer.clone(),
+                        placeholder_canvas.canvas_id,
+                    ),
+                    None => {
+                        CanvasState::new(global, Size2D::new(size.width as u64, size.height as u64))
+                    },
+                },
+            ),
         }
     }
 
@@ -71,6 +88,28 @@ impl OffscreenCanvasRenderingContext2D {
             OffscreenCanvasRenderingContext2DBinding::Wrap,
         )
     }
+
+    pub fn get_canvas_id(&self) -> CanvasId {
+        self.canvas_state.borrow().get_canvas_id()
+    }
+
+    pub fn get_ipc_renderer(&self) -> IpcSender<CanvasMsg> {
+        self.canvas_state.borrow().get_ipc_renderer()
+    }
+
+    pub fn origin_is_clean(&self) -> bool {
+        self.canvas_state.borrow().origin_is_clean()
+    }
+
+    pub fn set_bitmap_dimensions(&self, size: Size2D<u32>) {
+        self.canvas_state.borrow().set_bitmap_dimensions(size);
+    }
+
+    fn mark_as_dirty(&self) {
+        if let Some(ref canvas) = self.canvas {
+            canvas.mark_as_dirty();
+        }
+    }
 }
 
 impl OffscreenCanvasRenderingContext2DMethods for OffscreenCanvasRenderingContext2D {
@@ -82,16 +121,19 @@ impl OffscreenCanvasRenderingContext2DMethods for OffscreenCanvasRenderingContex
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-fillrect
     fn FillRect(&self, x: f64, y: f64, width: f64, height: f64) {
         self.canvas_state.borrow().FillRect(x, y, width, height);
+        self.mark_as_dirty();
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-clearrect
     fn ClearRect(&self, x: f64, y: f64, width: f64, height: f64) {
         self.canvas_state.borrow().ClearRect(x, y, width, height);
+        self.mark_as_dirty();
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-strokerect
     fn StrokeRect(&self, x: f64, y: f64, width: f64, height: f64) {
         self.canvas_state.borrow().StrokeRect(x, y, width, height);
+        self.mark_as_dirty();
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-shadowoffsetx
@@ -240,14 +282,99 @@ impl OffscreenCanvasRenderingContext2DMethods for OffscreenCanvasRenderingContex
         self.canvas_state.borrow().SetImageSmoothingEnabled(value)
     }
 
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-imagesmoothingquality
+    fn ImageSmoothingQuality(&self) -> ImageSmoothingQuality {
+        self.canvas_state.borrow().ImageSmoothingQuality()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-imagesmoothingquality
+    fn SetImageSmoothingQuality(&self, value: ImageSmoothingQuality) {
+        self.canvas_state.borrow().SetImageSmoothingQuality(value)
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-filter
+    fn Filter(&self) -> DOMString {
+        self.canvas_state.borrow().Filter()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-filter
+    fn SetFilter(&self, value: DOMString) {
+        self.canvas_state.borrow().SetFilter(value)
+    }
+
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-filltext
     fn FillText(&self, text: DOMString, x: f64, y: f64, max_width: Option<f64>) {
-        self.canvas_state.borrow().FillText(text, x, y, max_width)
+        self.canvas_state.borrow().FillText(
+            self.htmlcanvas.as_ref().map(|c| &**c),
+            text,
+            x,
+            y,
+            max_width,
+        );
+        self.mark_as_dirty();
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-stroketext
+    fn StrokeText(&self, text: DOMString, x: f64, y: f64, max_width: Option<f64>) {
+        self.canvas_state.borrow().StrokeText(
+            self.htmlcanvas.as_ref().map(|c| &**c),
+            text,
+            x,
+            y,
+            max_width,
+        );
+        self.mark_as_dirty();
     }
 
     // https://html.spec.whatwg.org/multipage/#textmetrics
     fn MeasureText(&self, text: DOMString) -> DomRoot<TextMetrics> {
-        self.canvas_state.borrow().MeasureText(&self.global(), text)
+        self.canvas_state.borrow().MeasureText(
+            &self.global(),
+            self.htmlcanvas.as_ref().map(|c| &**c),
+            text,
+        )
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-font
+    fn Font(&self) -> DOMString {
+        self.canvas_state.borrow().Font()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-font
+    fn SetFont(&self, value: DOMString) {
+        self.canvas_state
+            .borrow()
+            .SetFont(self.htmlcanvas.as_ref().map(|c| &**c), value)
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-textalign
+    fn TextAlign(&self) -> CanvasTextAlign {
+        self.canvas_state.borrow().TextAlign()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-textalign
+    fn SetTextAlign(&self, value: CanvasTextAlign) {
+        self.canvas_state.borrow().SetTextAlign(value)
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-textbaseline
+    fn TextBaseline(&self) -> CanvasTextBaseline {
+        self.canvas_state.borrow().TextBaseline()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-textbaseline
+    fn SetTextBaseline(&self, value: CanvasTextBaseline) {
+        self.canvas_state.borrow().SetTextBaseline(value)
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-direction
+    fn Direction(&self) -> CanvasDirection {
+        self.canvas_state.borrow().Direction()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-direction
+    fn SetDirection(&self, value: CanvasDirection) {
+        self.canvas_state.borrow().SetDirection(value)
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-linewidth
@@ -290,6 +417,26 @@ impl OffscreenCanvasRenderingContext2DMethods for OffscreenCanvasRenderingContex
         self.canvas_state.borrow().SetMiterLimit(limit)
     }
 
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-setlinedash
+    fn SetLineDash(&self, segments: Vec<f64>) {
+        self.canvas_state.borrow().SetLineDash(segments)
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-getlinedash
+    fn GetLineDash(&self) -> Vec<f64> {
+        self.canvas_state.borrow().GetLineDash()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-linedashoffset
+    fn LineDashOffset(&self) -> f64 {
+        self.canvas_state.borrow().LineDashOffset()
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-linedashoffset
+    fn SetLineDashOffset(&self, offset: f64) {
+        self.canvas_state.borrow().SetLineDashOffset(offset)
+    }
+
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-createimagedata
     fn CreateImageData(&self, sw: i32, sh: i32) -> Fallible<DomRoot<ImageData>> {
         self.canvas_state
@@ -323,7 +470,8 @@ impl OffscreenCanvasRenderingContext2DMethods for OffscreenCanvasRenderingContex
             imagedata,
             dx,
             dy,
-        )
+        );
+        self.mark_as_dirty();
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-putimagedata
@@ -347,14 +495,20 @@ impl OffscreenCanvasRenderingContext2DMethods for OffscreenCanvasRenderingContex
             dirty_y,
             dirty_width,
             dirty_height,
-        )
+        );
+        self.mark_as_dirty();
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-drawimage
     fn DrawImage(&self, image: CanvasImageSource, dx: f64, dy: f64) -> ErrorResult {
-        self.canvas_state
-            .borrow()
-            .DrawImage(self.htmlcanvas.as_ref().map(|c| &**c), image, dx, dy)
+        let result = self.canvas_state.borrow().DrawImage(
+            self.htmlcanvas.as_ref().map(|c| &**c),
+            image,
+            dx,
+            dy,
+        );
+        self.mark_as_dirty();
+        result
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-drawimage
@@ -366,14 +520,16 @@ impl OffscreenCanvasRenderingContext2DMethods for OffscreenCanvasRenderingContex
         dw: f64,
         dh: f64,
     ) -> ErrorResult {
-        self.canvas_state.borrow().DrawImage_(
+        let result = self.canvas_state.borrow().DrawImage_(
             self.htmlcanvas.as_ref().map(|c| &**c),
             image,
             dx,
             dy,
             dw,
             dh,
-        )
+        );
+        self.mark_as_dirty();
+        result
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-drawimage
@@ -389,7 +545,7 @@ impl OffscreenCanvasRenderingContext2DMethods for OffscreenCanvasRenderingContex
         dw: f64,
         dh: f64,
     ) -> ErrorResult {
-        self.canvas_state.borrow().DrawImage__(
+        let result = self.canvas_state.borrow().DrawImage__(
             self.htmlcanvas.as_ref().map(|c| &**c),
             image,
             sx,
@@ -400,7 +556,9 @@ impl OffscreenCanvasRenderingContext2DMethods for OffscreenCanvasRenderingContex
             dy,
             dw,
             dh,
-        )
+        );
+        self.mark_as_dirty();
+        result
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-beginpath
@@ -410,12 +568,26 @@ impl OffscreenCanvasRenderingContext2DMethods for OffscreenCanvasRenderingContex
 
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-fill
     fn Fill(&self, fill_rule: CanvasFillRule) {
-        self.canvas_state.borrow().Fill(fill_rule)
+        self.canvas_state.borrow().Fill(fill_rule);
+        self.mark_as_dirty();
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-fill
+    fn Fill_(&self, path: &Path2D, fill_rule: CanvasFillRule) {
+        self.canvas_state.borrow().Fill_(path, fill_rule);
+        self.mark_as_dirty();
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-stroke
     fn Stroke(&self) {
-        self.canvas_state.borrow().Stroke()
+        self.canvas_state.borrow().Stroke();
+        self.mark_as_dirty();
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-stroke
+    fn Stroke_(&self, path: &Path2D) {
+        self.canvas_state.borrow().Stroke_(path);
+        self.mark_as_dirty();
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-clip
@@ -423,6 +595,11 @@ impl OffscreenCanvasRenderingContext2DMethods for OffscreenCanvasRenderingContex
         self.canvas_state.borrow().Clip(fill_rule)
     }
 
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-clip
+    fn Clip_(&self, path: &Path2D, fill_rule: CanvasFillRule) {
+        self.canvas_state.borrow().Clip_(path, fill_rule)
+    }
+
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-ispointinpath
     fn IsPointInPath(&self, x: f64, y: f64, fill_rule: CanvasFillRule) -> bool {
         self.canvas_state
@@ -430,6 +607,27 @@ impl OffscreenCanvasRenderingContext2DMethods for OffscreenCanvasRenderingContex
             .IsPointInPath(&self.global(), x, y, fill_rule)
     }
 
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-ispointinpath
+    fn IsPointInPath_(&self, path: &Path2D, x: f64, y: f64, fill_rule: CanvasFillRule) -> bool {
+        self.canvas_state
+            .borrow()
+            .IsPointInPath_(&self.global(), path, x, y, fill_rule)
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-ispointinstroke
+    fn IsPointInStroke(&self, x: f64, y: f64) -> bool {
+        self.canvas_state
+            .borrow()
+            .IsPointInStroke(&self.global(), x, y)
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-ispointinstroke
+    fn IsPointInStroke_(&self, path: &Path2D, x: f64, y: f64) -> bool {
+        self.canvas_state
+            .borrow()
+            .IsPointInStroke_(&self.global(), path, x, y)
+    }
+
     // https://html.spec.whatwg.org/multipage/#dom-context-2d-scale
     fn Scale(&self, x: f64, y: f64) {
         self.canvas_state.borrow().Scale(x, y)
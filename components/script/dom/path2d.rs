@@ -0,0 +1,386 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::DOMMatrixBinding::DOMMatrixInit;
+use crate::dom::bindings::codegen::Bindings::Path2DBinding;
+use crate::dom::bindings::codegen::Bindings::Path2DBinding::Path2DMethods;
+use crate::dom::bindings::codegen::UnionTypes::Path2DOrString;
+use crate::dom::bindings::error::{Error, ErrorResult, Fallible};
+use crate::dom::bindings::reflector::{reflect_dom_object, Reflector};
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::dommatrixreadonly::dommatrixinit_to_matrix;
+use crate::dom::globalscope::GlobalScope;
+use canvas_traits::canvas::PathSegment;
+use dom_struct::dom_struct;
+use euclid::default::{Point2D, Rect, Size2D, Transform2D};
+use std::f32::consts::PI;
+use style::values::specified::svg_path::{PathCommand, SVGPathData};
+
+// https://html.spec.whatwg.org/multipage/#path2d-objects
+#[dom_struct]
+pub struct Path2D {
+    reflector_: Reflector,
+    segments: DomRefCell<Vec<PathSegment>>,
+}
+
+impl Path2D {
+    fn new_inherited(segments: Vec<PathSegment>) -> Path2D {
+        Path2D {
+            reflector_: Reflector::new(),
+            segments: DomRefCell::new(segments),
+        }
+    }
+
+    pub fn new(global: &GlobalScope, segments: Vec<PathSegment>) -> DomRoot<Path2D> {
+        reflect_dom_object(
+            Box::new(Path2D::new_inherited(segments)),
+            global,
+            Path2DBinding::Wrap,
+        )
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-path2d
+    #[allow(non_snake_case)]
+    pub fn Constructor(
+        global: &GlobalScope,
+        path: Option<Path2DOrString>,
+    ) -> Fallible<DomRoot<Path2D>> {
+        let segments = match path {
+            None => vec![],
+            Some(Path2DOrString::Path2D(path)) => path.segments(),
+            Some(Path2DOrString::String(path)) => {
+                svg_path_to_segments(&SVGPathData::parse_normalized_commands(&path))
+            },
+        };
+        Ok(Path2D::new(global, segments))
+    }
+
+    /// The segments making up this path, to be replayed by the canvas thread.
+    pub fn segments(&self) -> Vec<PathSegment> {
+        self.segments.borrow().clone()
+    }
+
+    fn push(&self, segment: PathSegment) {
+        self.segments.borrow_mut().push(segment);
+    }
+}
+
+impl Path2DMethods for Path2D {
+    // https://html.spec.whatwg.org/multipage/#dom-path2d-addpath
+    fn AddPath(&self, path: &Path2D, transform: &DOMMatrixInit) -> ErrorResult {
+        let (_, matrix) = dommatrixinit_to_matrix(transform)?;
+        let components = [
+            matrix.m11, matrix.m12, matrix.m21, matrix.m22, matrix.m41, matrix.m42,
+        ];
+        if !components.iter().all(|c| c.is_finite()) {
+            return Ok(());
+        }
+        let transform = Transform2D::row_major(
+            matrix.m11, matrix.m12, matrix.m21, matrix.m22, matrix.m41, matrix.m42,
+        )
+        .cast();
+        self.push(PathSegment::AddPath(path.segments(), transform));
+        Ok(())
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-closepath
+    fn ClosePath(&self) {
+        self.push(PathSegment::ClosePath);
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-moveto
+    fn MoveTo(&self, x: f64, y: f64) {
+        if !(x.is_finite() && y.is_finite()) {
+            return;
+        }
+        self.push(PathSegment::MoveTo(Point2D::new(x as f32, y as f32)));
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-lineto
+    fn LineTo(&self, x: f64, y: f64) {
+        if !(x.is_finite() && y.is_finite()) {
+            return;
+        }
+        self.push(PathSegment::LineTo(Point2D::new(x as f32, y as f32)));
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-quadraticcurveto
+    fn QuadraticCurveTo(&self, cpx: f64, cpy: f64, x: f64, y: f64) {
+        if !(cpx.is_finite() && cpy.is_finite() && x.is_finite() && y.is_finite()) {
+            return;
+        }
+        self.push(PathSegment::QuadraticCurveTo(
+            Point2D::new(cpx as f32, cpy as f32),
+            Point2D::new(x as f32, y as f32),
+        ));
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-beziercurveto
+    fn BezierCurveTo(&self, cp1x: f64, cp1y: f64, cp2x: f64, cp2y: f64, x: f64, y: f64) {
+        if !([cp1x, cp1y, cp2x, cp2y, x, y].iter().all(|x| x.is_finite())) {
+            return;
+        }
+        self.push(PathSegment::BezierCurveTo(
+            Point2D::new(cp1x as f32, cp1y as f32),
+            Point2D::new(cp2x as f32, cp2y as f32),
+            Point2D::new(x as f32, y as f32),
+        ));
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-arcto
+    fn ArcTo(&self, cp1x: f64, cp1y: f64, cp2x: f64, cp2y: f64, r: f64) -> ErrorResult {
+        if !([cp1x, cp1y, cp2x, cp2y, r].iter().all(|x| x.is_finite())) {
+            return Ok(());
+        }
+        if r < 0.0 {
+            return Err(Error::IndexSize);
+        }
+        self.push(PathSegment::ArcTo(
+            Point2D::new(cp1x as f32, cp1y as f32),
+            Point2D::new(cp2x as f32, cp2y as f32),
+            r as f32,
+        ));
+        Ok(())
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-rect
+    fn Rect(&self, x: f64, y: f64, width: f64, height: f64) {
+        if !([x, y, width, height].iter().all(|val| val.is_finite())) {
+            return;
+        }
+        self.push(PathSegment::Rect(Rect::new(
+            Point2D::new(x as f32, y as f32),
+            Size2D::new(width as f32, height as f32),
+        )));
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-arc
+    fn Arc(&self, x: f64, y: f64, r: f64, start: f64, end: f64, ccw: bool) -> ErrorResult {
+        if !([x, y, r, start, end].iter().all(|x| x.is_finite())) {
+            return Ok(());
+        }
+        if r < 0.0 {
+            return Err(Error::IndexSize);
+        }
+        self.push(PathSegment::Arc(
+            Point2D::new(x as f32, y as f32),
+            r as f32,
+            start as f32,
+            end as f32,
+            ccw,
+        ));
+        Ok(())
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-context-2d-ellipse
+    fn Ellipse(
+        &self,
+        x: f64,
+        y: f64,
+        rx: f64,
+        ry: f64,
+        rotation: f64,
+        start: f64,
+        end: f64,
+        ccw: bool,
+    ) -> ErrorResult {
+        if !([x, y, rx, ry, rotation, start, end]
+            .iter()
+            .all(|x| x.is_finite()))
+        {
+            return Ok(());
+        }
+        if rx < 0.0 || ry < 0.0 {
+            return Err(Error::IndexSize);
+        }
+        self.push(PathSegment::Ellipse(
+            Point2D::new(x as f32, y as f32),
+            rx as f32,
+            ry as f32,
+            rotation as f32,
+            start as f32,
+            end as f32,
+            ccw,
+        ));
+        Ok(())
+    }
+}
+
+/// Converts absolute SVG path commands into canvas path segments.
+fn svg_path_to_segments(commands: &[PathCommand]) -> Vec<PathSegment> {
+    let mut segments = vec![];
+    let mut current = Point2D::zero();
+    let mut subpath_start = Point2D::zero();
+    // The control point to reflect for the following smooth curve command, if
+    // the previous command was a curve of the same kind.
+    let mut last_cubic_control = None;
+    let mut last_quad_control = None;
+
+    for command in commands {
+        let mut cubic_control = None;
+        let mut quad_control = None;
+        match *command {
+            PathCommand::Unknown => break,
+            PathCommand::MoveTo { point, .. } => {
+                current = Point2D::new(point.x(), point.y());
+                subpath_start = current;
+                segments.push(PathSegment::MoveTo(current));
+            },
+            PathCommand::LineTo { point, .. } => {
+                current = Point2D::new(point.x(), point.y());
+                segments.push(PathSegment::LineTo(current));
+            },
+            PathCommand::HorizontalLineTo { x, .. } => {
+                current = Point2D::new(x, current.y);
+                segments.push(PathSegment::LineTo(current));
+            },
+            PathCommand::VerticalLineTo { y, .. } => {
+                current = Point2D::new(current.x, y);
+                segments.push(PathSegment::LineTo(current));
+            },
+            PathCommand::CurveTo {
+                control1,
+                control2,
+                point,
+                ..
+            } => {
+                let control1 = Point2D::new(control1.x(), control1.y());
+                let control2 = Point2D::new(control2.x(), control2.y());
+                current = Point2D::new(point.x(), point.y());
+                cubic_control = Some(control2);
+                segments.push(PathSegment::BezierCurveTo(control1, control2, current));
+            },
+            PathCommand::SmoothCurveTo {
+                control2, point, ..
+            } => {
+                let control1 = reflect(last_cubic_control, current);
+                let control2 = Point2D::new(control2.x(), control2.y());
+                current = Point2D::new(point.x(), point.y());
+                cubic_control = Some(control2);
+                segments.push(PathSegment::BezierCurveTo(control1, control2, current));
+            },
+            PathCommand::QuadBezierCurveTo {
+                control1, point, ..
+            } => {
+                let control1 = Point2D::new(control1.x(), control1.y());
+                current = Point2D::new(point.x(), point.y());
+                quad_control = Some(control1);
+                segments.push(PathSegment::QuadraticCurveTo(control1, current));
+            },
+            PathCommand::SmoothQuadBezierCurveTo { point, .. } => {
+                let control1 = reflect(last_quad_control, current);
+                current = Point2D::new(point.x(), point.y());
+                quad_control = Some(control1);
+                segments.push(PathSegment::QuadraticCurveTo(control1, current));
+            },
+            PathCommand::EllipticalArc {
+                rx,
+                ry,
+                angle,
+                large_arc_flag,
+                sweep_flag,
+                point,
+                ..
+            } => {
+                let to = Point2D::new(point.x(), point.y());
+                segments.extend(svg_arc_to_segment(
+                    current,
+                    to,
+                    rx,
+                    ry,
+                    angle,
+                    large_arc_flag.is_set(),
+                    sweep_flag.is_set(),
+                ));
+                current = to;
+            },
+            PathCommand::ClosePath => {
+                current = subpath_start;
+                segments.push(PathSegment::ClosePath);
+            },
+        }
+        last_cubic_control = cubic_control;
+        last_quad_control = quad_control;
+    }
+
+    segments
+}
+
+/// Reflects `control` about `current`, or returns `current` if there is no
+/// previous control point.
+fn reflect(control: Option<Point2D<f32>>, current: Point2D<f32>) -> Point2D<f32> {
+    match control {
+        Some(control) => Point2D::new(2. * current.x - control.x, 2. * current.y - control.y),
+        None => current,
+    }
+}
+
+/// Converts an SVG arc from endpoint to center parameterization.
+///
+/// https://www.w3.org/TR/SVG/implnote.html#ArcConversionEndpointToCenter
+fn svg_arc_to_segment(
+    from: Point2D<f32>,
+    to: Point2D<f32>,
+    rx: f32,
+    ry: f32,
+    angle: f32,
+    large_arc: bool,
+    sweep: bool,
+) -> Option<PathSegment> {
+    if from == to {
+        return None;
+    }
+    let (mut rx, mut ry) = (rx.abs(), ry.abs());
+    if rx == 0. || ry == 0. {
+        return Some(PathSegment::LineTo(to));
+    }
+
+    let phi = angle.to_radians();
+    let (sin_phi, cos_phi) = phi.sin_cos();
+    let dx = (from.x - to.x) / 2.;
+    let dy = (from.y - to.y) / 2.;
+    let x1 = cos_phi * dx + sin_phi * dy;
+    let y1 = -sin_phi * dx + cos_phi * dy;
+
+    // Scale up radii that are too small to reach the end point.
+    let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+    if lambda > 1. {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+
+    let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+    let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+    let mut coefficient = (numerator / denominator).max(0.).sqrt();
+    if large_arc == sweep {
+        coefficient = -coefficient;
+    }
+    let cx1 = coefficient * rx * y1 / ry;
+    let cy1 = -coefficient * ry * x1 / rx;
+
+    let center = Point2D::new(
+        cos_phi * cx1 - sin_phi * cy1 + (from.x + to.x) / 2.,
+        sin_phi * cx1 + cos_phi * cy1 + (from.y + to.y) / 2.,
+    );
+
+    let start = ((y1 - cy1) / ry).atan2((x1 - cx1) / rx);
+    let end = ((-y1 - cy1) / ry).atan2((-x1 - cx1) / rx);
+    let mut delta = end - start;
+    if sweep && delta < 0. {
+        delta += 2. * PI;
+    } else if !sweep && delta > 0. {
+        delta -= 2. * PI;
+    }
+
+    Some(PathSegment::Ellipse(
+        center,
+        rx,
+        ry,
+        phi,
+        start,
+        start + delta,
+        !sweep,
+    ))
+}
@@ -259,21 +259,8 @@ impl PermissionAlgorithm for Permissions {
         match status.State() {
             // Step 3.
             PermissionState::Prompt => {
-                let perm_name = status.get_query();
-
                 let globalscope = GlobalScope::current().expect("No current global object");
-
-                // https://w3c.github.io/permissions/#request-permission-to-use (Step 3 - 4)
-                let state = prompt_user(
-                    &format!("{} {} ?", REQUEST_DIALOG_MESSAGE, perm_name.clone()),
-                    globalscope.is_headless(),
-                );
-
-                globalscope
-                    .as_window()
-                    .permission_state_invocation_results()
-                    .borrow_mut()
-                    .insert(perm_name.to_string(), state);
+                request_permission_to_use(status.get_query(), &globalscope);
             },
 
             // Step 2.
@@ -343,6 +330,33 @@ pub fn get_descriptor_permission_state(
     state
 }
 
+// https://w3c.github.io/permissions/#request-permission-to-use
+pub fn request_permission_to_use(
+    permission_name: PermissionName,
+    env_settings_obj: &GlobalScope,
+) -> PermissionState {
+    // Steps 1-2.
+    let state = get_descriptor_permission_state(permission_name, Some(env_settings_obj));
+    if state != PermissionState::Prompt {
+        return state;
+    }
+
+    // Steps 3-4.
+    let state = prompt_user(
+        &format!("{} {} ?", REQUEST_DIALOG_MESSAGE, permission_name),
+        env_settings_obj.is_headless(),
+    );
+
+    env_settings_obj
+        .as_window()
+        .permission_state_invocation_results()
+        .borrow_mut()
+        .insert(permission_name.to_string(), state);
+
+    // Step 5.
+    state
+}
+
 #[cfg(target_os = "linux")]
 fn prompt_user(message: &str, headless: bool) -> PermissionState {
     if headless {
@@ -390,5 +404,9 @@ fn allowed_in_nonsecure_contexts(permission_name: &PermissionName) -> bool {
         PermissionName::Bluetooth => false,
         // https://storage.spec.whatwg.org/#dom-permissionname-persistent-storage
         PermissionName::Persistent_storage => false,
+        // https://w3c.github.io/clipboard-apis/#dom-permissionname-clipboard-read
+        PermissionName::Clipboard_read => false,
+        // https://w3c.github.io/clipboard-apis/#dom-permissionname-clipboard-write
+        PermissionName::Clipboard_write => false,
     }
 }
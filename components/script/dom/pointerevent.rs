@@ -0,0 +1,284 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::bindings::codegen::Bindings::MouseEventBinding::MouseEventMethods;
+use crate::dom::bindings::codegen::Bindings::PointerEventBinding;
+use crate::dom::bindings::codegen::Bindings::PointerEventBinding::PointerEventMethods;
+use crate::dom::bindings::error::Fallible;
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::num::Finite;
+use crate::dom::bindings::reflector::reflect_dom_object;
+use crate::dom::bindings::root::{Dom, DomRoot};
+use crate::dom::bindings::str::DOMString;
+use crate::dom::event::{EventBubbles, EventCancelable};
+use crate::dom::eventtarget::EventTarget;
+use crate::dom::mouseevent::MouseEvent;
+use crate::dom::window::Window;
+use dom_struct::dom_struct;
+
+/// The pointer identifier of the mouse.
+///
+/// <https://w3c.github.io/pointerevents/#dom-pointerevent-pointerid>
+pub const MOUSE_POINTER_ID: i32 = 1;
+
+/// The pointer identifier of a touch point, which must not clash with the
+/// identifier of the mouse.
+pub fn touch_pointer_id(identifier: i32) -> i32 {
+    identifier.wrapping_add(MOUSE_POINTER_ID + 1)
+}
+
+/// The kind of input device a pointer event comes from.
+///
+/// <https://w3c.github.io/pointerevents/#dom-pointerevent-pointertype>
+#[derive(Clone, Copy, Debug, JSTraceable, MallocSizeOf, PartialEq)]
+pub enum PointerType {
+    Mouse,
+    Touch,
+}
+
+impl PointerType {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            PointerType::Mouse => "mouse",
+            PointerType::Touch => "touch",
+        }
+    }
+}
+
+/// The properties of a pointer event that describe the pointer itself.
+#[derive(Clone, JSTraceable, MallocSizeOf)]
+pub struct PointerProperties {
+    pub pointer_id: i32,
+    pub width: f64,
+    pub height: f64,
+    pub pressure: f32,
+    pub tangential_pressure: f32,
+    pub tilt_x: i32,
+    pub tilt_y: i32,
+    pub twist: i32,
+    pub pointer_type: DOMString,
+    pub is_primary: bool,
+}
+
+impl PointerProperties {
+    /// The properties of a pointer of the given type, without pressure, tilt
+    /// or contact geometry information.
+    pub fn new(pointer_id: i32, pointer_type: PointerType, is_primary: bool) -> Self {
+        PointerProperties {
+            pointer_id,
+            width: 1.,
+            height: 1.,
+            pressure: 0.,
+            tangential_pressure: 0.,
+            tilt_x: 0,
+            tilt_y: 0,
+            twist: 0,
+            pointer_type: DOMString::from(pointer_type.as_str()),
+            is_primary,
+        }
+    }
+}
+
+#[dom_struct]
+pub struct PointerEvent {
+    mouseevent: MouseEvent,
+    properties: PointerProperties,
+    coalesced_events: Vec<Dom<PointerEvent>>,
+    predicted_events: Vec<Dom<PointerEvent>>,
+}
+
+impl PointerEvent {
+    fn new_inherited(
+        properties: PointerProperties,
+        coalesced_events: &[DomRoot<PointerEvent>],
+        predicted_events: &[DomRoot<PointerEvent>],
+    ) -> PointerEvent {
+        PointerEvent {
+            mouseevent: MouseEvent::new_inherited(),
+            properties,
+            coalesced_events: coalesced_events
+                .iter()
+                .map(|event| Dom::from_ref(&**event))
+                .collect(),
+            predicted_events: predicted_events
+                .iter()
+                .map(|event| Dom::from_ref(&**event))
+                .collect(),
+        }
+    }
+
+    pub fn new(
+        window: &Window,
+        type_: DOMString,
+        can_bubble: EventBubbles,
+        cancelable: EventCancelable,
+        view: Option<&Window>,
+        detail: i32,
+        screen_x: i32,
+        screen_y: i32,
+        client_x: i32,
+        client_y: i32,
+        ctrl_key: bool,
+        alt_key: bool,
+        shift_key: bool,
+        meta_key: bool,
+        button: i16,
+        buttons: u16,
+        related_target: Option<&EventTarget>,
+        properties: PointerProperties,
+        coalesced_events: &[DomRoot<PointerEvent>],
+        predicted_events: &[DomRoot<PointerEvent>],
+    ) -> DomRoot<PointerEvent> {
+        let ev = reflect_dom_object(
+            Box::new(PointerEvent::new_inherited(
+                properties,
+                coalesced_events,
+                predicted_events,
+            )),
+            window,
+            PointerEventBinding::Wrap,
+        );
+        let mouseevent = ev.upcast::<MouseEvent>();
+        mouseevent.InitMouseEvent(
+            type_,
+            bool::from(can_bubble),
+            bool::from(cancelable),
+            view,
+            detail,
+            screen_x,
+            screen_y,
+            client_x,
+            client_y,
+            ctrl_key,
+            alt_key,
+            shift_key,
+            meta_key,
+            button,
+            related_target,
+        );
+        mouseevent.set_buttons(buttons);
+        ev
+    }
+
+    // https://w3c.github.io/pointerevents/#dom-pointerevent-constructor
+    pub fn Constructor(
+        window: &Window,
+        type_: DOMString,
+        init: &PointerEventBinding::PointerEventInit,
+    ) -> Fallible<DomRoot<PointerEvent>> {
+        let mouse_init = &init.parent;
+        let properties = PointerProperties {
+            pointer_id: init.pointerId,
+            width: *init.width,
+            height: *init.height,
+            pressure: *init.pressure,
+            tangential_pressure: *init.tangentialPressure,
+            tilt_x: init.tiltX,
+            tilt_y: init.tiltY,
+            twist: init.twist,
+            pointer_type: init.pointerType.clone(),
+            is_primary: init.isPrimary,
+        };
+        let event = PointerEvent::new(
+            window,
+            type_,
+            EventBubbles::from(mouse_init.parent.parent.parent.bubbles),
+            EventCancelable::from(mouse_init.parent.parent.parent.cancelable),
+            mouse_init.parent.parent.view.as_deref(),
+            mouse_init.parent.parent.detail,
+            mouse_init.screenX,
+            mouse_init.screenY,
+            mouse_init.clientX,
+            mouse_init.clientY,
+            mouse_init.parent.ctrlKey,
+            mouse_init.parent.altKey,
+            mouse_init.parent.shiftKey,
+            mouse_init.parent.metaKey,
+            mouse_init.button,
+            mouse_init.buttons,
+            mouse_init.relatedTarget.as_deref(),
+            properties,
+            &init.coalescedEvents,
+            &init.predictedEvents,
+        );
+        Ok(event)
+    }
+
+    pub fn pointer_id(&self) -> i32 {
+        self.properties.pointer_id
+    }
+}
+
+impl PointerEventMethods for PointerEvent {
+    // https://w3c.github.io/pointerevents/#dom-pointerevent-pointerid
+    fn PointerId(&self) -> i32 {
+        self.properties.pointer_id
+    }
+
+    // https://w3c.github.io/pointerevents/#dom-pointerevent-width
+    fn Width(&self) -> Finite<f64> {
+        Finite::wrap(self.properties.width)
+    }
+
+    // https://w3c.github.io/pointerevents/#dom-pointerevent-height
+    fn Height(&self) -> Finite<f64> {
+        Finite::wrap(self.properties.height)
+    }
+
+    // https://w3c.github.io/pointerevents/#dom-pointerevent-pressure
+    fn Pressure(&self) -> Finite<f32> {
+        Finite::wrap(self.properties.pressure)
+    }
+
+    // https://w3c.github.io/pointerevents/#dom-pointerevent-tangentialpressure
+    fn TangentialPressure(&self) -> Finite<f32> {
+        Finite::wrap(self.properties.tangential_pressure)
+    }
+
+    // https://w3c.github.io/pointerevents/#dom-pointerevent-tiltx
+    fn TiltX(&self) -> i32 {
+        self.properties.tilt_x
+    }
+
+    // https://w3c.github.io/pointerevents/#dom-pointerevent-tilty
+    fn TiltY(&self) -> i32 {
+        self.properties.tilt_y
+    }
+
+    // https://w3c.github.io/pointerevents/#dom-pointerevent-twist
+    fn Twist(&self) -> i32 {
+        self.properties.twist
+    }
+
+    // https://w3c.github.io/pointerevents/#dom-pointerevent-pointertype
+    fn PointerType(&self) -> DOMString {
+        self.properties.pointer_type.clone()
+    }
+
+    // https://w3c.github.io/pointerevents/#dom-pointerevent-isprimary
+    fn IsPrimary(&self) -> bool {
+        self.properties.is_primary
+    }
+
+    // https://w3c.github.io/pointerevents/#dom-pointerevent-getcoalescedevents
+    fn GetCoalescedEvents(&self) -> Vec<DomRoot<PointerEvent>> {
+        self.coalesced_events
+            .iter()
+            .map(|event| DomRoot::from_ref(&**event))
+            .collect()
+    }
+
+    // https://w3c.github.io/pointerevents/#dom-pointerevent-getpredictedevents
+    fn GetPredictedEvents(&self) -> Vec<DomRoot<PointerEvent>> {
+        self.predicted_events
+            .iter()
+            .map(|event| DomRoot::from_ref(&**event))
+            .collect()
+    }
+
+    // https://dom.spec.whatwg.org/#dom-event-istrusted
+    fn IsTrusted(&self) -> bool {
+        self.mouseevent.IsTrusted()
+    }
+}
@@ -0,0 +1,43 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::bindings::codegen::Bindings::SVGCircleElementBinding;
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::document::Document;
+use crate::dom::node::Node;
+use crate::dom::svggeometryelement::SVGGeometryElement;
+use dom_struct::dom_struct;
+use html5ever::{LocalName, Prefix};
+
+#[dom_struct]
+pub struct SVGCircleElement {
+    svggeometryelement: SVGGeometryElement,
+}
+
+impl SVGCircleElement {
+    fn new_inherited(
+        local_name: LocalName,
+        prefix: Option<Prefix>,
+        document: &Document,
+    ) -> SVGCircleElement {
+        SVGCircleElement {
+            svggeometryelement: SVGGeometryElement::new_inherited(local_name, prefix, document),
+        }
+    }
+
+    #[allow(unrooted_must_root)]
+    pub fn new(
+        local_name: LocalName,
+        prefix: Option<Prefix>,
+        document: &Document,
+    ) -> DomRoot<SVGCircleElement> {
+        Node::reflect_node(
+            Box::new(SVGCircleElement::new_inherited(
+                local_name, prefix, document,
+            )),
+            document,
+            SVGCircleElementBinding::Wrap,
+        )
+    }
+}
@@ -2,20 +2,40 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
+use crate::dom::attr::Attr;
+use crate::dom::bindings::cell::DomRefCell;
 use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::root::LayoutDom;
 use crate::dom::document::Document;
-use crate::dom::element::Element;
+use crate::dom::element::{AttributeMutation, Element};
+use crate::dom::node::{window_from_node, Node};
 use crate::dom::virtualmethods::VirtualMethods;
 use dom_struct::dom_struct;
 use html5ever::{LocalName, Prefix};
+use servo_arc::Arc;
 use style::element_state::ElementState;
+use style::properties::{parse_one_declaration_into, SourcePropertyDeclaration};
+use style::properties::{Importance, PropertyDeclarationBlock, PropertyId};
+use style::shared_lock::Locked;
+use style_traits::ParsingMode;
 
 #[dom_struct]
 pub struct SVGElement {
     element: Element,
+    /// The declarations of the presentation attributes of this element.
+    #[ignore_malloc_size_of = "Arc"]
+    presentation_attributes: DomRefCell<Option<Arc<Locked<PropertyDeclarationBlock>>>>,
 }
 
 impl SVGElement {
+    pub fn new_inherited(
+        tag_name: LocalName,
+        prefix: Option<Prefix>,
+        document: &Document,
+    ) -> SVGElement {
+        SVGElement::new_inherited_with_state(ElementState::empty(), tag_name, prefix, document)
+    }
+
     pub fn new_inherited_with_state(
         state: ElementState,
         tag_name: LocalName,
@@ -24,6 +44,107 @@ impl SVGElement {
     ) -> SVGElement {
         SVGElement {
             element: Element::new_inherited_with_state(state, tag_name, ns!(svg), prefix, document),
+            presentation_attributes: DomRefCell::new(None),
+        }
+    }
+
+    /// https://svgwg.org/svg2-draft/styling.html#PresentationAttributes
+    fn is_presentation_attribute(&self, name: &LocalName) -> bool {
+        match *name {
+            local_name!("clip-rule") |
+            local_name!("color") |
+            local_name!("display") |
+            local_name!("fill") |
+            local_name!("fill-opacity") |
+            local_name!("fill-rule") |
+            local_name!("font-family") |
+            local_name!("font-size") |
+            local_name!("font-style") |
+            local_name!("font-weight") |
+            local_name!("marker-end") |
+            local_name!("marker-mid") |
+            local_name!("marker-start") |
+            local_name!("opacity") |
+            local_name!("stop-color") |
+            local_name!("stop-opacity") |
+            local_name!("stroke") |
+            local_name!("stroke-dasharray") |
+            local_name!("stroke-dashoffset") |
+            local_name!("stroke-linecap") |
+            local_name!("stroke-linejoin") |
+            local_name!("stroke-miterlimit") |
+            local_name!("stroke-opacity") |
+            local_name!("stroke-width") |
+            local_name!("visibility") => true,
+            // Geometry properties are presentation attributes only on the elements they apply to.
+            _ => match (self.upcast::<Element>().local_name(), name) {
+                (&local_name!("rect"), &local_name!("x")) |
+                (&local_name!("rect"), &local_name!("y")) |
+                (&local_name!("rect"), &local_name!("width")) |
+                (&local_name!("rect"), &local_name!("height")) |
+                (&local_name!("rect"), &local_name!("rx")) |
+                (&local_name!("rect"), &local_name!("ry")) |
+                (&local_name!("circle"), &local_name!("cx")) |
+                (&local_name!("circle"), &local_name!("cy")) |
+                (&local_name!("circle"), &local_name!("r")) |
+                (&local_name!("ellipse"), &local_name!("cx")) |
+                (&local_name!("ellipse"), &local_name!("cy")) |
+                (&local_name!("ellipse"), &local_name!("rx")) |
+                (&local_name!("ellipse"), &local_name!("ry")) => true,
+                _ => false,
+            },
+        }
+    }
+
+    /// Parses the presentation attributes of this element into a declaration block.
+    fn parse_presentation_attributes(&self) -> Option<Arc<Locked<PropertyDeclarationBlock>>> {
+        let element = self.upcast::<Element>();
+        let document = self.upcast::<Node>().owner_doc();
+        let window = window_from_node(self);
+        let mut block = PropertyDeclarationBlock::new();
+
+        for attr in element.attrs().iter() {
+            if attr.namespace() != &ns!() || !self.is_presentation_attribute(attr.local_name()) {
+                continue;
+            }
+            let id = match PropertyId::parse_enabled_for_all_content(attr.local_name()) {
+                Ok(id) => id,
+                Err(..) => continue,
+            };
+            let mut declarations = SourcePropertyDeclaration::new();
+            let result = parse_one_declaration_into(
+                &mut declarations,
+                id,
+                &attr.value(),
+                &document.base_url(),
+                window.css_error_reporter(),
+                ParsingMode::ALLOW_UNITLESS_LENGTH,
+                document.quirks_mode(),
+            );
+            if result.is_ok() {
+                block.extend(declarations.drain(), Importance::Normal);
+            }
+        }
+
+        if block.len() == 0 {
+            return None;
+        }
+        Some(Arc::new(document.style_shared_lock().wrap(block)))
+    }
+}
+
+pub trait LayoutSVGElementHelpers {
+    fn get_presentation_attributes(&self) -> Option<Arc<Locked<PropertyDeclarationBlock>>>;
+}
+
+impl LayoutSVGElementHelpers for LayoutDom<SVGElement> {
+    #[allow(unsafe_code)]
+    fn get_presentation_attributes(&self) -> Option<Arc<Locked<PropertyDeclarationBlock>>> {
+        unsafe {
+            (*self.unsafe_get())
+                .presentation_attributes
+                .borrow_for_layout()
+                .clone()
         }
     }
 }
@@ -32,4 +153,21 @@ impl VirtualMethods for SVGElement {
     fn super_type(&self) -> Option<&dyn VirtualMethods> {
         Some(self.upcast::<Element>() as &dyn VirtualMethods)
     }
+
+    fn attribute_affects_presentational_hints(&self, attr: &Attr) -> bool {
+        if attr.namespace() == &ns!() && self.is_presentation_attribute(attr.local_name()) {
+            return true;
+        }
+
+        self.super_type()
+            .unwrap()
+            .attribute_affects_presentational_hints(attr)
+    }
+
+    fn attribute_mutated(&self, attr: &Attr, mutation: AttributeMutation) {
+        self.super_type().unwrap().attribute_mutated(attr, mutation);
+        if attr.namespace() == &ns!() && self.is_presentation_attribute(attr.local_name()) {
+            *self.presentation_attributes.borrow_mut() = self.parse_presentation_attributes();
+        }
+    }
 }
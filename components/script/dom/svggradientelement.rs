@@ -0,0 +1,48 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::document::Document;
+use crate::dom::svgelement::SVGElement;
+use crate::dom::virtualmethods::VirtualMethods;
+use dom_struct::dom_struct;
+use html5ever::{LocalName, Prefix};
+use style::element_state::ElementState;
+
+#[dom_struct]
+pub struct SVGGradientElement {
+    svgelement: SVGElement,
+}
+
+impl SVGGradientElement {
+    pub fn new_inherited(
+        tag_name: LocalName,
+        prefix: Option<Prefix>,
+        document: &Document,
+    ) -> SVGGradientElement {
+        SVGGradientElement::new_inherited_with_state(
+            ElementState::empty(),
+            tag_name,
+            prefix,
+            document,
+        )
+    }
+
+    pub fn new_inherited_with_state(
+        state: ElementState,
+        tag_name: LocalName,
+        prefix: Option<Prefix>,
+        document: &Document,
+    ) -> SVGGradientElement {
+        SVGGradientElement {
+            svgelement: SVGElement::new_inherited_with_state(state, tag_name, prefix, document),
+        }
+    }
+}
+
+impl VirtualMethods for SVGGradientElement {
+    fn super_type(&self) -> Option<&dyn VirtualMethods> {
+        Some(self.upcast::<SVGElement>() as &dyn VirtualMethods)
+    }
+}
@@ -446,7 +446,7 @@ impl VRDisplayMethods for VRDisplay {
         vec![VRLayer {
             leftBounds: Some(bounds_to_vec(&layer.left_bounds)),
             rightBounds: Some(bounds_to_vec(&layer.right_bounds)),
-            source: self.layer_ctx.get().map(|ctx| ctx.Canvas()),
+            source: self.layer_ctx.get().and_then(|ctx| ctx.canvas_element()),
         }]
     }
 }
@@ -0,0 +1,162 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::dom::audionode::AudioNode;
+use crate::dom::baseaudiocontext::BaseAudioContext;
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::AudioNodeBinding::{
+    ChannelCountMode, ChannelInterpretation,
+};
+use crate::dom::bindings::codegen::Bindings::WaveShaperNodeBinding::{
+    self, OverSampleType, WaveShaperNodeMethods, WaveShaperOptions,
+};
+use crate::dom::bindings::error::{Error, Fallible};
+use crate::dom::bindings::reflector::reflect_dom_object;
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::window::Window;
+use crate::script_runtime::JSContext;
+use dom_struct::dom_struct;
+use js::jsapi::{Heap, JSObject};
+use js::rust::CustomAutoRooterGuard;
+use js::typedarray::{CreateWith, Float32Array};
+use servo_media::audio::node::{AudioNodeInit, AudioNodeMessage};
+use servo_media::audio::wave_shaper_node::OverSampleType as ServoMediaOverSampleType;
+use servo_media::audio::wave_shaper_node::{WaveShaperNodeMessage, WaveShaperNodeOptions};
+use std::cell::Cell;
+use std::ptr::{self, NonNull};
+
+#[dom_struct]
+pub struct WaveShaperNode {
+    node: AudioNode,
+    /// The internal copy of the curve.
+    curve: DomRefCell<Option<Vec<f32>>>,
+    /// The Float32Array returned by the curve getter, created lazily.
+    #[ignore_malloc_size_of = "mozjs"]
+    curve_array: Heap<*mut JSObject>,
+    oversample: Cell<OverSampleType>,
+}
+
+impl WaveShaperNode {
+    #[allow(unrooted_must_root)]
+    pub fn new_inherited(
+        _: &Window,
+        context: &BaseAudioContext,
+        options: &WaveShaperOptions,
+    ) -> Fallible<WaveShaperNode> {
+        let node_options =
+            options
+                .parent
+                .unwrap_or(2, ChannelCountMode::Max, ChannelInterpretation::Speakers);
+        let curve: Option<Vec<f32>> = options
+            .curve
+            .as_ref()
+            .map(|curve| curve.iter().map(|value| **value).collect());
+        if curve.as_ref().map_or(false, |curve| curve.len() < 2) {
+            return Err(Error::InvalidState);
+        }
+        let node = AudioNode::new_inherited(
+            AudioNodeInit::WaveShaperNode(WaveShaperNodeOptions {
+                curve: curve.clone(),
+                oversample: options.oversample.into(),
+            }),
+            context,
+            node_options,
+            1, // inputs
+            1, // outputs
+        )?;
+        Ok(WaveShaperNode {
+            node,
+            curve: DomRefCell::new(curve),
+            curve_array: Heap::default(),
+            oversample: Cell::new(options.oversample),
+        })
+    }
+
+    #[allow(unrooted_must_root)]
+    pub fn new(
+        window: &Window,
+        context: &BaseAudioContext,
+        options: &WaveShaperOptions,
+    ) -> Fallible<DomRoot<WaveShaperNode>> {
+        let node = WaveShaperNode::new_inherited(window, context, options)?;
+        Ok(reflect_dom_object(
+            Box::new(node),
+            window,
+            WaveShaperNodeBinding::Wrap,
+        ))
+    }
+
+    pub fn Constructor(
+        window: &Window,
+        context: &BaseAudioContext,
+        options: &WaveShaperOptions,
+    ) -> Fallible<DomRoot<WaveShaperNode>> {
+        WaveShaperNode::new(window, context, options)
+    }
+}
+
+impl WaveShaperNodeMethods for WaveShaperNode {
+    // https://webaudio.github.io/web-audio-api/#dom-waveshapernode-curve
+    #[allow(unsafe_code)]
+    fn GetCurve(&self, cx: JSContext) -> Fallible<Option<NonNull<JSObject>>> {
+        let curve = self.curve.borrow();
+        let curve = match *curve {
+            Some(ref curve) => curve,
+            None => return Ok(None),
+        };
+        if self.curve_array.get().is_null() {
+            rooted!(in (*cx) let mut array = ptr::null_mut::<JSObject>());
+            unsafe {
+                Float32Array::create(*cx, CreateWith::Slice(curve), array.handle_mut())
+                    .map_err(|_| Error::JSFailed)?;
+            }
+            self.curve_array.set(array.get());
+        }
+        Ok(NonNull::new(self.curve_array.get()))
+    }
+
+    // https://webaudio.github.io/web-audio-api/#dom-waveshapernode-curve
+    #[allow(unsafe_code)]
+    fn SetCurve(&self, curve: Option<CustomAutoRooterGuard<Float32Array>>) -> Fallible<()> {
+        // The curve is copied, so that later changes to the array have no
+        // effect.
+        let curve = curve.map(|curve| unsafe { curve.as_slice().to_vec() });
+        if curve.as_ref().map_or(false, |curve| curve.len() < 2) {
+            return Err(Error::InvalidState);
+        }
+        if curve.is_some() && self.curve.borrow().is_some() {
+            return Err(Error::InvalidState);
+        }
+
+        self.curve_array.set(ptr::null_mut());
+        *self.curve.borrow_mut() = curve.clone();
+        self.node.message(AudioNodeMessage::WaveShaperNode(
+            WaveShaperNodeMessage::SetCurve(curve),
+        ));
+        Ok(())
+    }
+
+    // https://webaudio.github.io/web-audio-api/#dom-waveshapernode-oversample
+    fn Oversample(&self) -> OverSampleType {
+        self.oversample.get()
+    }
+
+    // https://webaudio.github.io/web-audio-api/#dom-waveshapernode-oversample
+    fn SetOversample(&self, oversample: OverSampleType) {
+        self.oversample.set(oversample);
+        self.node.message(AudioNodeMessage::WaveShaperNode(
+            WaveShaperNodeMessage::SetOverSample(oversample.into()),
+        ));
+    }
+}
+
+impl From<OverSampleType> for ServoMediaOverSampleType {
+    fn from(oversample: OverSampleType) -> ServoMediaOverSampleType {
+        match oversample {
+            OverSampleType::None => ServoMediaOverSampleType::None,
+            OverSampleType::_2x => ServoMediaOverSampleType::Double,
+            OverSampleType::_4x => ServoMediaOverSampleType::Quadruple,
+        }
+    }
+}
@@ -9,6 +9,7 @@ use crate::dom::bindings::codegen::Bindings::WebGLRenderingContextBinding::WebGL
 use crate::dom::bindings::codegen::Bindings::WebGLRenderingContextBinding::WebGLRenderingContextMethods;
 use crate::dom::bindings::codegen::UnionTypes::ArrayBufferViewOrArrayBuffer;
 use crate::dom::bindings::codegen::UnionTypes::Float32ArrayOrUnrestrictedFloatSequence;
+use crate::dom::bindings::codegen::UnionTypes::HTMLCanvasElementOrOffscreenCanvas;
 use crate::dom::bindings::codegen::UnionTypes::ImageDataOrHTMLImageElementOrHTMLCanvasElementOrHTMLVideoElement;
 use crate::dom::bindings::codegen::UnionTypes::Int32ArrayOrLongSequence;
 use crate::dom::bindings::conversions::ToJSValConvertible;
@@ -16,7 +17,7 @@ use crate::dom::bindings::error::{ErrorResult, Fallible};
 use crate::dom::bindings::reflector::{reflect_dom_object, Reflector};
 use crate::dom::bindings::root::{Dom, DomRoot, LayoutDom, MutNullableDom};
 use crate::dom::bindings::str::DOMString;
-use crate::dom::htmlcanvaselement::HTMLCanvasElement;
+use crate::dom::globalscope::GlobalScope;
 use crate::dom::htmliframeelement::HTMLIFrameElement;
 use crate::dom::webglactiveinfo::WebGLActiveInfo;
 use crate::dom::webglbuffer::WebGLBuffer;
@@ -33,7 +34,6 @@ use crate::dom::webglshaderprecisionformat::WebGLShaderPrecisionFormat;
 use crate::dom::webglsync::WebGLSync;
 use crate::dom::webgltexture::WebGLTexture;
 use crate::dom::webgluniformlocation::WebGLUniformLocation;
-use crate::dom::window::Window;
 use crate::script_runtime::JSContext;
 use canvas_traits::webgl::WebGLError::*;
 /// https://www.khronos.org/registry/webgl/specs/latest/2.0/webgl.idl
@@ -58,12 +58,12 @@ pub struct WebGL2RenderingContext {
 
 impl WebGL2RenderingContext {
     fn new_inherited(
-        window: &Window,
-        canvas: &HTMLCanvasElement,
+        global: &GlobalScope,
+        canvas: &HTMLCanvasElementOrOffscreenCanvas,
         size: Size2D<u32>,
         attrs: GLContextAttributes,
     ) -> Option<WebGL2RenderingContext> {
-        let base = WebGLRenderingContext::new(window, canvas, WebGLVersion::WebGL2, size, attrs)?;
+        let base = WebGLRenderingContext::new(global, canvas, WebGLVersion::WebGL2, size, attrs)?;
 
         let samplers = (0..base.limits().max_combined_texture_image_units)
             .map(|_| Default::default())
@@ -81,13 +81,13 @@ impl WebGL2RenderingContext {
 
     #[allow(unrooted_must_root)]
     pub fn new(
-        window: &Window,
-        canvas: &HTMLCanvasElement,
+        global: &GlobalScope,
+        canvas: &HTMLCanvasElementOrOffscreenCanvas,
         size: Size2D<u32>,
         attrs: GLContextAttributes,
     ) -> Option<DomRoot<WebGL2RenderingContext>> {
-        WebGL2RenderingContext::new_inherited(window, canvas, size, attrs).map(|ctx| {
-            reflect_dom_object(Box::new(ctx), window, WebGL2RenderingContextBinding::Wrap)
+        WebGL2RenderingContext::new_inherited(global, canvas, size, attrs).map(|ctx| {
+            reflect_dom_object(Box::new(ctx), global, WebGL2RenderingContextBinding::Wrap)
         })
     }
 }
@@ -104,7 +104,7 @@ impl WebGL2RenderingContext {
 
 impl WebGL2RenderingContextMethods for WebGL2RenderingContext {
     /// https://www.khronos.org/registry/webgl/specs/latest/1.0/#5.14.1
-    fn Canvas(&self) -> DomRoot<HTMLCanvasElement> {
+    fn Canvas(&self) -> HTMLCanvasElementOrOffscreenCanvas {
         self.base.Canvas()
     }
 
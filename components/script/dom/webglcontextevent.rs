@@ -12,6 +12,7 @@ use crate::dom::bindings::reflector::reflect_dom_object;
 use crate::dom::bindings::root::DomRoot;
 use crate::dom::bindings::str::DOMString;
 use crate::dom::event::{Event, EventBubbles, EventCancelable};
+use crate::dom::globalscope::GlobalScope;
 use crate::dom::window::Window;
 use dom_struct::dom_struct;
 use servo_atoms::Atom;
@@ -55,7 +56,7 @@ impl WebGLContextEvent {
     }
 
     pub fn new(
-        window: &Window,
+        global: &GlobalScope,
         type_: Atom,
         bubbles: EventBubbles,
         cancelable: EventCancelable,
@@ -63,7 +64,7 @@ impl WebGLContextEvent {
     ) -> DomRoot<WebGLContextEvent> {
         let event = reflect_dom_object(
             Box::new(WebGLContextEvent::new_inherited(status_message)),
-            window,
+            global,
             WebGLContextEventBinding::Wrap,
         );
 
@@ -76,7 +77,7 @@ impl WebGLContextEvent {
     }
 
     pub fn Constructor(
-        window: &Window,
+        global: &GlobalScope,
         type_: DOMString,
         init: &WebGLContextEventInit,
     ) -> Fallible<DomRoot<WebGLContextEvent>> {
@@ -90,7 +91,7 @@ impl WebGLContextEvent {
         let cancelable = EventCancelable::from(init.parent.cancelable);
 
         Ok(WebGLContextEvent::new(
-            window,
+            global,
             Atom::from(type_),
             bubbles,
             cancelable,
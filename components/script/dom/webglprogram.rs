@@ -300,7 +300,7 @@ impl WebGLProgram {
             .get(index as usize)
             .ok_or(WebGLError::InvalidValue)?;
         Ok(WebGLActiveInfo::new(
-            self.global().as_window(),
+            &self.global(),
             data.size.unwrap_or(1),
             data.type_,
             data.name().into(),
@@ -317,7 +317,7 @@ impl WebGLProgram {
             .get(index as usize)
             .ok_or(WebGLError::InvalidValue)?;
         Ok(WebGLActiveInfo::new(
-            self.global().as_window(),
+            &self.global(),
             data.size,
             data.type_,
             data.name.clone().into(),
@@ -394,7 +394,7 @@ impl WebGLProgram {
         let location = receiver.recv().unwrap();
 
         Ok(Some(WebGLUniformLocation::new(
-            self.global().as_window(),
+            &self.global(),
             location,
             self.id,
             self.link_generation.get(),
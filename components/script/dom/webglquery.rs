@@ -172,8 +172,6 @@ impl WebGLQuery {
 
             let global = self.global();
             global
-                .as_window()
-                .task_manager()
                 .dom_manipulation_task_source()
                 .queue(task, global.upcast())
                 .unwrap();
@@ -10,21 +10,28 @@ use crate::dom::bindings::codegen::Bindings::WebGLRenderingContextBinding::TexIm
 use crate::dom::bindings::codegen::Bindings::WebGLRenderingContextBinding::WebGLContextAttributes;
 use crate::dom::bindings::codegen::Bindings::WebGLRenderingContextBinding::WebGLRenderingContextConstants as constants;
 use crate::dom::bindings::codegen::Bindings::WebGLRenderingContextBinding::WebGLRenderingContextMethods;
+use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
+use crate::dom::bindings::codegen::InterfaceObjectMap::Globals;
 use crate::dom::bindings::codegen::UnionTypes::ArrayBufferViewOrArrayBuffer;
 use crate::dom::bindings::codegen::UnionTypes::Float32ArrayOrUnrestrictedFloatSequence;
+use crate::dom::bindings::codegen::UnionTypes::HTMLCanvasElementOrOffscreenCanvas;
 use crate::dom::bindings::codegen::UnionTypes::Int32ArrayOrLongSequence;
 use crate::dom::bindings::conversions::{DerivedFrom, ToJSValConvertible};
 use crate::dom::bindings::error::{Error, ErrorResult, Fallible};
 use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::interface::is_exposed_in;
 use crate::dom::bindings::reflector::{reflect_dom_object, DomObject, Reflector};
 use crate::dom::bindings::root::{Dom, DomOnceCell, DomRoot, LayoutDom, MutNullableDom};
 use crate::dom::bindings::str::DOMString;
 use crate::dom::element::cors_setting_for_element;
 use crate::dom::event::{Event, EventBubbles, EventCancelable};
+use crate::dom::eventtarget::EventTarget;
+use crate::dom::globalscope::GlobalScope;
 use crate::dom::htmlcanvaselement::utils as canvas_utils;
 use crate::dom::htmlcanvaselement::HTMLCanvasElement;
 use crate::dom::htmliframeelement::HTMLIFrameElement;
-use crate::dom::node::{document_from_node, window_from_node, Node, NodeDamage};
+use crate::dom::node::{document_from_node, Node, NodeDamage};
+use crate::dom::offscreencanvas::OffscreenCanvas;
 use crate::dom::promise::Promise;
 use crate::dom::webgl_extensions::WebGLExtensions;
 use crate::dom::webgl_validations::tex_image_2d::{
@@ -67,7 +74,7 @@ use ipc_channel::ipc::{self, IpcSharedMemory};
 use js::jsapi::{JSContext, JSObject, Type};
 use js::jsval::{BooleanValue, DoubleValue, Int32Value, JSVal, UInt32Value};
 use js::jsval::{NullValue, ObjectValue, UndefinedValue};
-use js::rust::CustomAutoRooterGuard;
+use js::rust::{CustomAutoRooterGuard, HandleObject};
 use js::typedarray::{
     ArrayBufferView, CreateWith, Float32, Float32Array, Int32, Int32Array, Uint32Array,
 };
@@ -123,6 +130,14 @@ bitflags! {
     }
 }
 
+/// The canvas a context draws into.
+#[must_root]
+#[derive(JSTraceable, MallocSizeOf)]
+enum WebGLCanvas {
+    HTMLCanvasElement(Dom<HTMLCanvasElement>),
+    OffscreenCanvas(Dom<OffscreenCanvas>),
+}
+
 #[dom_struct]
 pub struct WebGLRenderingContext {
     reflector_: Reflector,
@@ -135,7 +150,7 @@ pub struct WebGLRenderingContext {
     glsl_version: WebGLSLVersion,
     #[ignore_malloc_size_of = "Defined in offscreen_gl_context"]
     limits: GLLimits,
-    canvas: Dom<HTMLCanvasElement>,
+    canvas: WebGLCanvas,
     #[ignore_malloc_size_of = "Defined in canvas_traits"]
     last_error: Cell<Option<WebGLError>>,
     texture_packing_alignment: Cell<u8>,
@@ -164,9 +179,15 @@ pub struct WebGLRenderingContext {
 }
 
 impl WebGLRenderingContext {
+    /// Workers can only get a WebGL context from an OffscreenCanvas, so the
+    /// WebGL interfaces are only exposed there along with it.
+    pub fn is_exposed(_: SafeJSContext, obj: HandleObject) -> bool {
+        is_exposed_in(obj, Globals::WINDOW) || pref!(dom.offscreen_canvas.enabled)
+    }
+
     pub fn new_inherited(
-        window: &Window,
-        canvas: &HTMLCanvasElement,
+        global: &GlobalScope,
+        canvas: &HTMLCanvasElementOrOffscreenCanvas,
         webgl_version: WebGLVersion,
         size: Size2D<u32>,
         attrs: GLContextAttributes,
@@ -175,7 +196,7 @@ impl WebGLRenderingContext {
             return Err("WebGL context creation error forced by pref `webgl.testing.context_creation_error`".into());
         }
 
-        let webgl_chan = match window.webgl_chan() {
+        let webgl_chan = match global.webgl_chan() {
             Some(chan) => chan,
             None => return Err("WebGL initialization failed early on".into()),
         };
@@ -190,16 +211,20 @@ impl WebGLRenderingContext {
             let max_combined_texture_image_units = ctx_data.limits.max_combined_texture_image_units;
             Self {
                 reflector_: Reflector::new(),
-                webgl_sender: WebGLMessageSender::new(
-                    ctx_data.sender,
-                    window.get_event_loop_waker(),
-                ),
+                webgl_sender: webgl_chan.message_sender(ctx_data.sender),
                 webrender_image: ctx_data.image_key,
                 share_mode: ctx_data.share_mode,
                 webgl_version,
                 glsl_version: ctx_data.glsl_version,
                 limits: ctx_data.limits,
-                canvas: Dom::from_ref(canvas),
+                canvas: match *canvas {
+                    HTMLCanvasElementOrOffscreenCanvas::HTMLCanvasElement(ref canvas) => {
+                        WebGLCanvas::HTMLCanvasElement(Dom::from_ref(&**canvas))
+                    },
+                    HTMLCanvasElementOrOffscreenCanvas::OffscreenCanvas(ref canvas) => {
+                        WebGLCanvas::OffscreenCanvas(Dom::from_ref(&**canvas))
+                    },
+                },
                 last_error: Cell::new(None),
                 texture_packing_alignment: Cell::new(4),
                 texture_unpacking_settings: Cell::new(TextureUnpacking::CONVERT_COLORSPACE),
@@ -227,28 +252,36 @@ impl WebGLRenderingContext {
 
     #[allow(unrooted_must_root)]
     pub fn new(
-        window: &Window,
-        canvas: &HTMLCanvasElement,
+        global: &GlobalScope,
+        canvas: &HTMLCanvasElementOrOffscreenCanvas,
         webgl_version: WebGLVersion,
         size: Size2D<u32>,
         attrs: GLContextAttributes,
     ) -> Option<DomRoot<WebGLRenderingContext>> {
-        match WebGLRenderingContext::new_inherited(window, canvas, webgl_version, size, attrs) {
+        match WebGLRenderingContext::new_inherited(global, canvas, webgl_version, size, attrs) {
             Ok(ctx) => Some(reflect_dom_object(
                 Box::new(ctx),
-                window,
+                global,
                 WebGLRenderingContextBinding::Wrap,
             )),
             Err(msg) => {
                 error!("Couldn't create WebGLRenderingContext: {}", msg);
                 let event = WebGLContextEvent::new(
-                    window,
+                    global,
                     atom!("webglcontextcreationerror"),
                     EventBubbles::DoesNotBubble,
                     EventCancelable::Cancelable,
                     DOMString::from(msg),
                 );
-                event.upcast::<Event>().fire(canvas.upcast());
+                let target = match *canvas {
+                    HTMLCanvasElementOrOffscreenCanvas::HTMLCanvasElement(ref canvas) => {
+                        canvas.upcast::<EventTarget>()
+                    },
+                    HTMLCanvasElementOrOffscreenCanvas::OffscreenCanvas(ref canvas) => {
+                        canvas.upcast::<EventTarget>()
+                    },
+                };
+                event.upcast::<Event>().fire(target);
                 None
             },
         }
@@ -451,18 +484,31 @@ impl WebGLRenderingContext {
         }
     }
 
+    /// The canvas element that displays what this context draws, which is
+    /// the placeholder of its offscreen canvas if it has one.
+    pub fn canvas_element(&self) -> Option<DomRoot<HTMLCanvasElement>> {
+        match self.canvas {
+            WebGLCanvas::HTMLCanvasElement(ref canvas) => Some(DomRoot::from_ref(&**canvas)),
+            WebGLCanvas::OffscreenCanvas(ref canvas) => canvas.placeholder(),
+        }
+    }
+
     fn mark_as_dirty(&self) {
         // If we have a bound framebuffer, then don't mark the canvas as dirty.
         if self.bound_framebuffer.get().is_some() {
             return;
         }
 
-        self.canvas
-            .upcast::<Node>()
-            .dirty(NodeDamage::OtherNodeDamage);
+        let canvas = match self.canvas_element() {
+            Some(canvas) => canvas,
+            None => return,
+        };
 
-        let document = document_from_node(&*self.canvas);
+        canvas.upcast::<Node>().dirty(NodeDamage::OtherNodeDamage);
+
+        let document = document_from_node(&*canvas);
         document.add_dirty_canvas(self.context_id());
+        canvas.content_changed();
     }
 
     fn vertex_attrib(&self, indx: u32, x: f32, y: f32, z: f32, w: f32) {
@@ -552,6 +598,18 @@ impl WebGLRenderingContext {
 
     fn get_image_pixels(&self, source: TexImageSource) -> Fallible<Option<TexPixels>> {
         Ok(Some(match source {
+            TexImageSource::ImageBitmap(bitmap) => {
+                if !bitmap.origin_is_clean() {
+                    return Err(Error::Security);
+                }
+                let data = bitmap.bitmap_data().ok_or(Error::InvalidState)?;
+                TexPixels::new(
+                    IpcSharedMemory::from_bytes(&data),
+                    bitmap.get_size(),
+                    PixelFormat::BGRA8,
+                    bitmap.is_premultiplied(),
+                )
+            },
             TexImageSource::ImageData(image_data) => TexPixels::new(
                 image_data.to_shared_memory(),
                 image_data.get_size(),
@@ -559,8 +617,9 @@ impl WebGLRenderingContext {
                 false,
             ),
             TexImageSource::HTMLImageElement(image) => {
-                let document = document_from_node(&*self.canvas);
-                if !image.same_origin(document.origin()) {
+                let global = self.global();
+                let window = global.as_window();
+                if !image.same_origin(window.Document().origin()) {
                     return Err(Error::Security);
                 }
 
@@ -569,11 +628,10 @@ impl WebGLRenderingContext {
                     None => return Ok(None),
                 };
 
-                let window = window_from_node(&*self.canvas);
                 let cors_setting = cors_setting_for_element(image.upcast());
 
                 let img =
-                    match canvas_utils::request_image_from_cache(&window, img_url, cors_setting) {
+                    match canvas_utils::request_image_from_cache(window, img_url, cors_setting) {
                         ImageResponse::Loaded(img, _) => img,
                         ImageResponse::PlaceholderLoaded(_, _) |
                         ImageResponse::None |
@@ -1126,8 +1184,15 @@ impl Drop for WebGLRenderingContext {
 
 impl WebGLRenderingContextMethods for WebGLRenderingContext {
     // https://www.khronos.org/registry/webgl/specs/latest/1.0/#5.14.1
-    fn Canvas(&self) -> DomRoot<HTMLCanvasElement> {
-        DomRoot::from_ref(&*self.canvas)
+    fn Canvas(&self) -> HTMLCanvasElementOrOffscreenCanvas {
+        match self.canvas {
+            WebGLCanvas::HTMLCanvasElement(ref canvas) => {
+                HTMLCanvasElementOrOffscreenCanvas::HTMLCanvasElement(DomRoot::from_ref(&**canvas))
+            },
+            WebGLCanvas::OffscreenCanvas(ref canvas) => {
+                HTMLCanvasElementOrOffscreenCanvas::OffscreenCanvas(DomRoot::from_ref(&**canvas))
+            },
+        }
     }
 
     // https://www.khronos.org/registry/webgl/specs/latest/1.0/#5.14.11
@@ -2633,7 +2698,7 @@ impl WebGLRenderingContextMethods for WebGLRenderingContext {
 
         let (range_min, range_max, precision) = receiver.recv().unwrap();
         Some(WebGLShaderPrecisionFormat::new(
-            self.global().as_window(),
+            &self.global(),
             range_min,
             range_max,
             precision,
@@ -4347,16 +4412,31 @@ impl TexPixels {
 }
 
 #[derive(JSTraceable)]
-pub(crate) struct WebGLCommandSender {
+pub struct WebGLCommandSender {
     sender: WebGLChan,
     waker: Option<Box<dyn EventLoopWaker>>,
 }
 
+impl Clone for WebGLCommandSender {
+    fn clone(&self) -> WebGLCommandSender {
+        WebGLCommandSender {
+            sender: self.sender.clone(),
+            waker: self.waker.as_ref().map(|w| (*w).clone_box()),
+        }
+    }
+}
+
 impl WebGLCommandSender {
     pub fn new(sender: WebGLChan, waker: Option<Box<dyn EventLoopWaker>>) -> WebGLCommandSender {
         WebGLCommandSender { sender, waker }
     }
 
+    /// A sender for the messages of a context created through this sender,
+    /// which wakes up the same event loop.
+    pub(crate) fn message_sender(&self, sender: WebGLMsgSender) -> WebGLMessageSender {
+        WebGLMessageSender::new(sender, self.waker.as_ref().map(|w| (*w).clone_box()))
+    }
+
     pub fn send(&self, msg: WebGLMsg) -> WebGLSendResult {
         let result = self.sender.send(msg);
         if let Some(ref waker) = self.waker {
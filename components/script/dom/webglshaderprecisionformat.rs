@@ -9,7 +9,7 @@ use crate::dom::bindings::codegen::Bindings::WebGLShaderPrecisionFormatBinding;
 use crate::dom::bindings::codegen::Bindings::WebGLShaderPrecisionFormatBinding::WebGLShaderPrecisionFormatMethods;
 use crate::dom::bindings::reflector::{reflect_dom_object, Reflector};
 use crate::dom::bindings::root::DomRoot;
-use crate::dom::window::Window;
+use crate::dom::globalscope::GlobalScope;
 use dom_struct::dom_struct;
 
 #[dom_struct]
@@ -31,7 +31,7 @@ impl WebGLShaderPrecisionFormat {
     }
 
     pub fn new(
-        window: &Window,
+        global: &GlobalScope,
         range_min: i32,
         range_max: i32,
         precision: i32,
@@ -40,7 +40,7 @@ impl WebGLShaderPrecisionFormat {
             Box::new(WebGLShaderPrecisionFormat::new_inherited(
                 range_min, range_max, precision,
             )),
-            window,
+            global,
             WebGLShaderPrecisionFormatBinding::Wrap,
         )
     }
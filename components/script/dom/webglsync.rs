@@ -73,8 +73,6 @@ impl WebGLSync {
                     this.client_wait_status.set(Some(receiver.recv().unwrap()));
                 });
                 global
-                    .as_window()
-                    .task_manager()
                     .dom_manipulation_task_source()
                     .queue(task, global.upcast())
                     .unwrap();
@@ -111,8 +109,6 @@ impl WebGLSync {
                     this.sync_status.set(Some(receiver.recv().unwrap()));
                 });
                 global
-                    .as_window()
-                    .task_manager()
                     .dom_manipulation_task_source()
                     .queue(task, global.upcast())
                     .unwrap();
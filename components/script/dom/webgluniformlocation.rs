@@ -6,7 +6,7 @@
 use crate::dom::bindings::codegen::Bindings::WebGLUniformLocationBinding;
 use crate::dom::bindings::reflector::{reflect_dom_object, Reflector};
 use crate::dom::bindings::root::DomRoot;
-use crate::dom::window::Window;
+use crate::dom::globalscope::GlobalScope;
 use canvas_traits::webgl::WebGLProgramId;
 use dom_struct::dom_struct;
 
@@ -39,7 +39,7 @@ impl WebGLUniformLocation {
     }
 
     pub fn new(
-        window: &Window,
+        global: &GlobalScope,
         id: i32,
         program_id: WebGLProgramId,
         link_generation: u64,
@@ -54,7 +54,7 @@ impl WebGLUniformLocation {
                 size,
                 type_,
             )),
-            window,
+            global,
             WebGLUniformLocationBinding::Wrap,
         )
     }
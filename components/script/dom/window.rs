@@ -9,6 +9,9 @@ use crate::dom::bindings::codegen::Bindings::DocumentBinding::{
 };
 use crate::dom::bindings::codegen::Bindings::FunctionBinding::Function;
 use crate::dom::bindings::codegen::Bindings::HistoryBinding::HistoryBinding::HistoryMethods;
+use crate::dom::bindings::codegen::Bindings::ImageBitmapBinding::{
+    ImageBitmapOptions, ImageBitmapSource,
+};
 use crate::dom::bindings::codegen::Bindings::MediaQueryListBinding::MediaQueryListBinding::MediaQueryListMethods;
 use crate::dom::bindings::codegen::Bindings::PermissionStatusBinding::PermissionState;
 use crate::dom::bindings::codegen::Bindings::RequestBinding::RequestInit;
@@ -39,6 +42,7 @@ use crate::dom::eventtarget::EventTarget;
 use crate::dom::globalscope::GlobalScope;
 use crate::dom::hashchangeevent::HashChangeEvent;
 use crate::dom::history::History;
+use crate::dom::imagebitmap::create_image_bitmap;
 use crate::dom::location::Location;
 use crate::dom::mediaquerylist::{MediaQueryList, MediaQueryListMatchState};
 use crate::dom::mediaquerylistevent::MediaQueryListEvent;
@@ -126,11 +130,12 @@ use style::dom::OpaqueNode;
 use style::error_reporting::{ContextualParseError, ParseErrorReporter};
 use style::media_queries;
 use style::parser::ParserContext as CssParserContext;
-use style::properties::{ComputedValues, PropertyId};
+use style::properties::style_structs::Font;
+use style::properties::{ComputedValues, PropertyId, ShorthandId};
 use style::selector_parser::PseudoElement;
 use style::str::HTML_SPACE_CHARACTERS;
 use style::stylesheets::CssRuleType;
-use style_traits::{CSSPixel, DevicePixel, ParsingMode};
+use style_traits::{CSSPixel, DevicePixel, MediaPreferences, ParsingMode};
 use url::Position;
 use webrender_api::units::{DeviceIntPoint, DeviceIntSize, LayoutPixel};
 use webrender_api::{DocumentId, ExternalScrollId, RenderApiSender};
@@ -155,6 +160,7 @@ pub enum ReflowReason {
     Timer,
     Viewport,
     WindowResize,
+    MediaPreferencesChanged,
     DOMContentLoaded,
     DocumentLoaded,
     StylesheetLoaded,
@@ -222,6 +228,9 @@ pub struct Window {
     /// The current size of the window, in pixels.
     window_size: Cell<WindowSizeData>,
 
+    /// The preferences of the user that media queries can test for.
+    media_preferences: Cell<MediaPreferences>,
+
     /// A handle for communicating messages to the bluetooth thread.
     #[ignore_malloc_size_of = "channels are hard"]
     bluetooth_thread: IpcSender<BluetoothRequest>,
@@ -1255,6 +1264,31 @@ impl WindowMethods for Window {
         fetch::Fetch(&self.upcast(), input, init, comp)
     }
 
+    // https://html.spec.whatwg.org/multipage/#dom-createimagebitmap
+    fn CreateImageBitmap(
+        &self,
+        image: ImageBitmapSource,
+        options: &ImageBitmapOptions,
+        comp: InCompartment,
+    ) -> Rc<Promise> {
+        create_image_bitmap(self.upcast(), image, None, options, comp)
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-createimagebitmap
+    fn CreateImageBitmap_(
+        &self,
+        image: ImageBitmapSource,
+        sx: i32,
+        sy: i32,
+        sw: i32,
+        sh: i32,
+        options: &ImageBitmapOptions,
+        comp: InCompartment,
+    ) -> Rc<Promise> {
+        let crop = UntypedRect::new(UntypedPoint2D::new(sx, sy), Size2D::new(sw, sh));
+        create_image_bitmap(self.upcast(), image, Some(crop), options, comp)
+    }
+
     fn TestRunner(&self) -> DomRoot<TestRunner> {
         self.test_runner.or_init(|| TestRunner::new(self.upcast()))
     }
@@ -1536,6 +1570,7 @@ impl Window {
             document: self.Document().upcast::<Node>().to_trusted_node_address(),
             stylesheets_changed,
             window_size: self.window_size.get(),
+            media_preferences: self.media_preferences.get(),
             reflow_goal,
             script_join_chan: join_chan,
             dom_count: self.Document().dom_count(),
@@ -1770,6 +1805,22 @@ impl Window {
         DOMString::from(resolved)
     }
 
+    pub fn resolved_font_style_query(
+        &self,
+        node: &Node,
+        value: String,
+    ) -> Option<servo_arc::Arc<Font>> {
+        let id = PropertyId::Shorthand(ShorthandId::Font);
+        if !self.layout_reflow(QueryMsg::ResolvedFontStyleQuery(
+            node.to_trusted_node_address(),
+            id,
+            value,
+        )) {
+            return None;
+        }
+        self.layout_rpc.resolved_font_style()
+    }
+
     #[allow(unsafe_code)]
     pub fn offset_parent_query(&self, node: &Node) -> (Option<DomRoot<Element>>, UntypedRect<Au>) {
         if !self.layout_reflow(QueryMsg::OffsetParentQuery(node.to_opaque())) {
@@ -1933,6 +1984,14 @@ impl Window {
         self.window_size.get()
     }
 
+    pub fn set_media_preferences(&self, media_preferences: MediaPreferences) {
+        self.media_preferences.set(media_preferences);
+    }
+
+    pub fn media_preferences(&self) -> MediaPreferences {
+        self.media_preferences.get()
+    }
+
     pub fn get_url(&self) -> ServoUrl {
         self.Document().url()
     }
@@ -2147,6 +2206,7 @@ impl Window {
         pipelineid: PipelineId,
         parent_info: Option<PipelineId>,
         window_size: WindowSizeData,
+        media_preferences: MediaPreferences,
         origin: MutableOrigin,
         navigation_start: u64,
         navigation_start_precise: u64,
@@ -2218,6 +2278,7 @@ impl Window {
             layout_chan,
             layout_rpc,
             window_size: Cell::new(window_size),
+            media_preferences: Cell::new(media_preferences),
             current_viewport: Cell::new(Rect::zero()),
             suppress_reflow: Cell::new(true),
             pending_reflow_count: Default::default(),
@@ -2296,6 +2357,7 @@ fn debug_reflow_events(id: PipelineId, reflow_goal: &ReflowGoal, reason: &Reflow
             &QueryMsg::NodeScrollGeometryQuery(_n) => "\tNodeScrollGeometryQuery",
             &QueryMsg::NodeScrollIdQuery(_n) => "\tNodeScrollIdQuery",
             &QueryMsg::ResolvedStyleQuery(_, _, _) => "\tResolvedStyleQuery",
+            &QueryMsg::ResolvedFontStyleQuery(..) => "\tResolvedFontStyleQuery",
             &QueryMsg::OffsetParentQuery(_n) => "\tOffsetParentQuery",
             &QueryMsg::StyleQuery(_n) => "\tStyleQuery",
             &QueryMsg::TextIndexQuery(..) => "\tTextIndexQuery",
@@ -2313,6 +2375,7 @@ fn debug_reflow_events(id: PipelineId, reflow_goal: &ReflowGoal, reason: &Reflow
         ReflowReason::Timer => "\tTimer",
         ReflowReason::Viewport => "\tViewport",
         ReflowReason::WindowResize => "\tWindowResize",
+        ReflowReason::MediaPreferencesChanged => "\tMediaPreferencesChanged",
         ReflowReason::DOMContentLoaded => "\tDOMContentLoaded",
         ReflowReason::DocumentLoaded => "\tDocumentLoaded",
         ReflowReason::StylesheetLoaded => "\tStylesheetLoaded",
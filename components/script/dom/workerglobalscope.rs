@@ -5,6 +5,9 @@
 use crate::compartments::InCompartment;
 use crate::dom::bindings::cell::DomRefCell;
 use crate::dom::bindings::codegen::Bindings::FunctionBinding::Function;
+use crate::dom::bindings::codegen::Bindings::ImageBitmapBinding::{
+    ImageBitmapOptions, ImageBitmapSource,
+};
 use crate::dom::bindings::codegen::Bindings::RequestBinding::RequestInit;
 use crate::dom::bindings::codegen::Bindings::WorkerBinding::WorkerType;
 use crate::dom::bindings::codegen::Bindings::WorkerGlobalScopeBinding::WorkerGlobalScopeMethods;
@@ -19,6 +22,7 @@ use crate::dom::bindings::trace::RootedTraceableBox;
 use crate::dom::crypto::Crypto;
 use crate::dom::dedicatedworkerglobalscope::DedicatedWorkerGlobalScope;
 use crate::dom::globalscope::GlobalScope;
+use crate::dom::imagebitmap::create_image_bitmap;
 use crate::dom::performance::Performance;
 use crate::dom::promise::Promise;
 use crate::dom::serviceworkerglobalscope::ServiceWorkerGlobalScope;
@@ -39,6 +43,7 @@ use crate::timers::{IsInterval, TimerCallback};
 use crossbeam_channel::Receiver;
 use devtools_traits::{DevtoolScriptControlMsg, WorkerId};
 use dom_struct::dom_struct;
+use euclid::default::{Point2D, Rect, Size2D};
 use ipc_channel::ipc::IpcSender;
 use js::jsapi::JSAutoRealm;
 use js::jsval::UndefinedValue;
@@ -378,6 +383,31 @@ impl WorkerGlobalScopeMethods for WorkerGlobalScope {
         fetch::Fetch(self.upcast(), input, init, comp)
     }
 
+    // https://html.spec.whatwg.org/multipage/#dom-createimagebitmap
+    fn CreateImageBitmap(
+        &self,
+        image: ImageBitmapSource,
+        options: &ImageBitmapOptions,
+        comp: InCompartment,
+    ) -> Rc<Promise> {
+        create_image_bitmap(self.upcast(), image, None, options, comp)
+    }
+
+    // https://html.spec.whatwg.org/multipage/#dom-createimagebitmap
+    fn CreateImageBitmap_(
+        &self,
+        image: ImageBitmapSource,
+        sx: i32,
+        sy: i32,
+        sw: i32,
+        sh: i32,
+        options: &ImageBitmapOptions,
+        comp: InCompartment,
+    ) -> Rc<Promise> {
+        let crop = Rect::new(Point2D::new(sx, sy), Size2D::new(sw, sh));
+        create_image_bitmap(self.upcast(), image, Some(crop), options, comp)
+    }
+
     // https://w3c.github.io/hr-time/#the-performance-attribute
     fn Performance(&self) -> DomRoot<Performance> {
         self.performance.or_init(|| {
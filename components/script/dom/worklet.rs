@@ -11,6 +11,8 @@
 //! a backup thread, not on the primary worklet thread.
 
 use crate::compartments::InCompartment;
+use crate::dom::audioworkletglobalscope::{AudioParamDescriptorData, AudioWorkletTask};
+use crate::dom::bindings::cell::DomRefCell;
 use crate::dom::bindings::codegen::Bindings::RequestBinding::RequestCredentials;
 use crate::dom::bindings::codegen::Bindings::WindowBinding::WindowBinding::WindowMethods;
 use crate::dom::bindings::codegen::Bindings::WorkletBinding::WorkletMethods;
@@ -68,7 +70,6 @@ use swapper::Swapper;
 use uuid::Uuid;
 
 // Magic numbers
-const WORKLET_THREAD_POOL_SIZE: u32 = 3;
 const MIN_GC_THRESHOLD: u32 = 1_000_000;
 
 #[dom_struct]
@@ -78,15 +79,20 @@ pub struct Worklet {
     window: Dom<Window>,
     worklet_id: WorkletId,
     global_type: WorkletGlobalScopeType,
+    /// The thread running this worklet's global scope, for worklets which
+    /// don't run in the thread pool shared by the script thread.
+    #[ignore_malloc_size_of = "channels are hard"]
+    dedicated_thread_pool: DomRefCell<Option<Rc<WorkletThreadPool>>>,
 }
 
 impl Worklet {
-    fn new_inherited(window: &Window, global_type: WorkletGlobalScopeType) -> Worklet {
+    pub fn new_inherited(window: &Window, global_type: WorkletGlobalScopeType) -> Worklet {
         Worklet {
             reflector: Reflector::new(),
             window: Dom::from_ref(window),
             worklet_id: WorkletId::new(),
             global_type: global_type,
+            dedicated_thread_pool: Default::default(),
         }
     }
 
@@ -107,6 +113,19 @@ impl Worklet {
     pub fn worklet_global_scope_type(&self) -> WorkletGlobalScopeType {
         self.global_type
     }
+
+    /// The thread pool running this worklet's global scopes.
+    /// Worklets whose global scopes keep state between tasks get a pool
+    /// of their own, with a single thread, created on first use.
+    pub fn thread_pool(&self) -> Rc<WorkletThreadPool> {
+        if !self.global_type.needs_dedicated_thread() {
+            return ScriptThread::worklet_thread_pool();
+        }
+        self.dedicated_thread_pool
+            .borrow_mut()
+            .get_or_insert_with(ScriptThread::dedicated_worklet_thread_pool)
+            .clone()
+    }
 }
 
 impl WorkletMethods for Worklet {
@@ -134,9 +153,9 @@ impl WorkletMethods for Worklet {
         debug!("Adding Worklet module {}.", module_url_record);
 
         // Steps 6-12 in parallel.
-        let pending_tasks_struct = PendingTasksStruct::new();
         let global = self.window.upcast::<GlobalScope>();
-        let pool = ScriptThread::worklet_thread_pool();
+        let pool = self.thread_pool();
+        let pending_tasks_struct = PendingTasksStruct::new(pool.control_senders.len());
 
         pool.fetch_and_invoke_a_worklet_script(
             global.pipeline_id(),
@@ -158,6 +177,10 @@ impl WorkletMethods for Worklet {
 
 impl Drop for Worklet {
     fn drop(&mut self) {
+        // A dedicated thread pool quits when it is dropped.
+        if self.global_type.needs_dedicated_thread() {
+            return;
+        }
         let script_thread = ScriptThread::worklet_thread_pool();
         script_thread.exit_worklet(self.worklet_id);
     }
@@ -180,10 +203,8 @@ impl WorkletId {
 struct PendingTasksStruct(Arc<AtomicIsize>);
 
 impl PendingTasksStruct {
-    fn new() -> PendingTasksStruct {
-        PendingTasksStruct(Arc::new(AtomicIsize::new(
-            WORKLET_THREAD_POOL_SIZE as isize,
-        )))
+    fn new(thread_count: usize) -> PendingTasksStruct {
+        PendingTasksStruct(Arc::new(AtomicIsize::new(thread_count as isize)))
     }
 
     fn set_counter_to(&self, value: isize) -> isize {
@@ -233,6 +254,13 @@ impl PendingTasksStruct {
 /// when a worklet adds a module. It is dropped when the script thread
 /// is dropped, and asks each of the worklet threads to quit.
 ///
+/// Since each thread has its own copy of a worklet's global scope,
+/// this only works for worklets whose global scopes are interchangeable.
+/// Audio worklets keep the state of their processors between render
+/// quanta, so each of them gets a pool with a single thread, which
+/// plays every role, and performs its script loading and GC between
+/// tasks.
+///
 /// The layout thread can end up blocking on the primary worklet thread
 /// (e.g. when invoking a paint callback), so it is important to avoid
 /// deadlock by making sure the primary worklet thread doesn't end up
@@ -250,10 +278,8 @@ pub struct WorkletThreadPool {
     primary_sender: Sender<WorkletData>,
     hot_backup_sender: Sender<WorkletData>,
     cold_backup_sender: Sender<WorkletData>,
-    // Channels to send control messages to the three threads.
-    control_sender_0: Sender<WorkletControl>,
-    control_sender_1: Sender<WorkletControl>,
-    control_sender_2: Sender<WorkletControl>,
+    // Channels to send control messages to each thread.
+    control_senders: Vec<Sender<WorkletControl>>,
 }
 
 impl Drop for WorkletThreadPool {
@@ -284,9 +310,30 @@ impl WorkletThreadPool {
             primary_sender: primary_sender,
             hot_backup_sender: hot_backup_sender,
             cold_backup_sender: cold_backup_sender,
-            control_sender_0: WorkletThread::spawn(primary_role, init.clone()),
-            control_sender_1: WorkletThread::spawn(hot_backup_role, init.clone()),
-            control_sender_2: WorkletThread::spawn(cold_backup_role, init),
+            control_senders: vec![
+                WorkletThread::spawn(primary_role, init.clone()),
+                WorkletThread::spawn(hot_backup_role, init.clone()),
+                WorkletThread::spawn(cold_backup_role, init),
+            ],
+        }
+    }
+
+    /// Create a new thread pool with a single thread, which plays every role.
+    /// When the thread pool is dropped, the thread will be asked to quit.
+    pub fn spawn_single_thread(global_init: WorkletGlobalScopeInit) -> WorkletThreadPool {
+        let role = WorkletThreadRole::new_sole();
+        let sender = role.sender.clone();
+        let init = WorkletThreadInit {
+            primary_sender: sender.clone(),
+            hot_backup_sender: sender.clone(),
+            cold_backup_sender: sender.clone(),
+            global_init: global_init,
+        };
+        WorkletThreadPool {
+            primary_sender: sender.clone(),
+            hot_backup_sender: sender.clone(),
+            cold_backup_sender: sender,
+            control_senders: vec![WorkletThread::spawn(role, init)],
         }
     }
 
@@ -307,11 +354,7 @@ impl WorkletThreadPool {
         promise: &Rc<Promise>,
     ) {
         // Send each thread a control message asking it to load the script.
-        for sender in &[
-            &self.control_sender_0,
-            &self.control_sender_1,
-            &self.control_sender_2,
-        ] {
+        for sender in &self.control_senders {
             let _ = sender.send(WorkletControl::FetchAndInvokeAWorkletScript {
                 pipeline_id: pipeline_id,
                 worklet_id: worklet_id,
@@ -328,11 +371,7 @@ impl WorkletThreadPool {
     }
 
     pub(crate) fn exit_worklet(&self, worklet_id: WorkletId) {
-        for sender in &[
-            &self.control_sender_0,
-            &self.control_sender_1,
-            &self.control_sender_2,
-        ] {
+        for sender in &self.control_senders {
             let _ = sender.send(WorkletControl::ExitWorklet(worklet_id));
         }
         self.wake_threads();
@@ -346,6 +385,24 @@ impl WorkletThreadPool {
         receiver.recv().expect("Test worklet has died?")
     }
 
+    /// Look up the parameter descriptors of a processor registered in an audio worklet.
+    pub fn audio_worklet_lookup(
+        &self,
+        id: WorkletId,
+        name: String,
+    ) -> Option<Vec<AudioParamDescriptorData>> {
+        let (sender, receiver) = unbounded();
+        let task = AudioWorkletTask::LookUpParameterDescriptors(name, sender);
+        let msg = WorkletData::Task(id, WorkletTask::Audio(task));
+        let _ = self.primary_sender.send(msg);
+        receiver.recv().ok().and_then(|descriptors| descriptors)
+    }
+
+    /// An executor of tasks in the global scope of the worklet `id`.
+    pub fn executor(&self, id: WorkletId) -> WorkletExecutor {
+        WorkletExecutor::new(id, self.primary_sender.clone())
+    }
+
     fn wake_threads(&self) {
         // If any of the threads are blocked waiting on data, wake them up.
         let _ = self.cold_backup_sender.send(WorkletData::WakeUp);
@@ -388,6 +445,7 @@ enum WorkletControl {
 struct WorkletThreadRole {
     receiver: Receiver<WorkletData>,
     sender: Sender<WorkletData>,
+    is_primary: bool,
     is_hot_backup: bool,
     is_cold_backup: bool,
 }
@@ -398,10 +456,19 @@ impl WorkletThreadRole {
         WorkletThreadRole {
             sender: sender,
             receiver: receiver,
+            is_primary: !is_hot_backup && !is_cold_backup,
             is_hot_backup: is_hot_backup,
             is_cold_backup: is_cold_backup,
         }
     }
+
+    /// The role of the only thread of a pool, which is both the primary
+    /// and the cold backup, and is never swapped.
+    fn new_sole() -> WorkletThreadRole {
+        let mut role = WorkletThreadRole::new(false, true);
+        role.is_primary = true;
+        role
+    }
 }
 
 /// Data to initialize a worklet thread.
@@ -525,13 +592,20 @@ impl WorkletThread {
             // otherwise if there are outstanding control messages,
             // try to become the cold backup.
             if self.role.is_cold_backup {
+                let mut processed_control = false;
                 if let Some(control) = self.control_buffer.take() {
                     self.process_control(control);
+                    processed_control = true;
                 }
                 while let Ok(control) = self.control_receiver.try_recv() {
                     self.process_control(control);
+                    processed_control = true;
+                }
+                // If we are also the primary, don't delay the next task
+                // unless there was script loading to clean up after.
+                if processed_control || !self.role.is_primary {
+                    self.gc();
                 }
-                self.gc();
             } else if self.control_buffer.is_none() {
                 if let Ok(control) = self.control_receiver.try_recv() {
                     self.control_buffer = Some(control);
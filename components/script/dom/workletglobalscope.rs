@@ -2,6 +2,8 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
+use crate::dom::audioworkletglobalscope::AudioWorkletGlobalScope;
+use crate::dom::audioworkletglobalscope::AudioWorkletTask;
 use crate::dom::bindings::inheritance::Castable;
 use crate::dom::bindings::root::DomRoot;
 use crate::dom::globalscope::GlobalScope;
@@ -11,7 +13,10 @@ use crate::dom::testworkletglobalscope::TestWorkletGlobalScope;
 use crate::dom::testworkletglobalscope::TestWorkletTask;
 use crate::dom::worklet::WorkletExecutor;
 use crate::script_runtime::JSContext;
+use crate::script_runtime::{CommonScriptMsg, ScriptThreadEventCategory};
 use crate::script_thread::MainThreadScriptMsg;
+use crate::task::TaskBox;
+use crate::task_source::TaskSourceName;
 use crossbeam_channel::Sender;
 use devtools_traits::ScriptToDevtoolsControlMsg;
 use dom_struct::dom_struct;
@@ -112,6 +117,22 @@ impl WorkletGlobalScope {
             .expect("Worklet thread outlived script thread.");
     }
 
+    /// Run a task in the main script thread.
+    pub fn run_in_script_thread<T>(&self, task: T)
+    where
+        T: TaskBox + 'static,
+    {
+        let msg = CommonScriptMsg::Task(
+            ScriptThreadEventCategory::WorkletEvent,
+            Box::new(task),
+            None,
+            TaskSourceName::DOMManipulation,
+        );
+        self.to_script_thread_sender
+            .send(MainThreadScriptMsg::Common(msg))
+            .expect("Worklet thread outlived script thread.");
+    }
+
     /// The base URL of this global.
     pub fn base_url(&self) -> ServoUrl {
         self.base_url.clone()
@@ -133,6 +154,10 @@ impl WorkletGlobalScope {
                 Some(global) => global.perform_a_worklet_task(task),
                 None => warn!("This is not a paint worklet."),
             },
+            WorkletTask::Audio(task) => match self.downcast::<AudioWorkletGlobalScope>() {
+                Some(global) => global.perform_a_worklet_task(task),
+                None => warn!("This is not an audio worklet."),
+            },
         }
     }
 }
@@ -169,6 +194,8 @@ pub enum WorkletGlobalScopeType {
     Test,
     /// A paint worklet
     Paint,
+    /// An audio worklet, rendering at the given sample rate
+    Audio(f32),
 }
 
 impl WorkletGlobalScopeType {
@@ -196,6 +223,25 @@ impl WorkletGlobalScopeType {
                 executor,
                 init,
             )),
+            WorkletGlobalScopeType::Audio(sample_rate) => {
+                DomRoot::upcast(AudioWorkletGlobalScope::new(
+                    runtime,
+                    pipeline_id,
+                    base_url,
+                    executor,
+                    init,
+                    sample_rate,
+                ))
+            },
+        }
+    }
+
+    /// Whether the global scopes of this type keep state between tasks,
+    /// so they must always be run by the same thread.
+    pub fn needs_dedicated_thread(&self) -> bool {
+        match *self {
+            WorkletGlobalScopeType::Test | WorkletGlobalScopeType::Paint => false,
+            WorkletGlobalScopeType::Audio(_) => true,
         }
     }
 }
@@ -204,4 +250,5 @@ impl WorkletGlobalScopeType {
 pub enum WorkletTask {
     Test(TestWorkletTask),
     Paint(PaintWorkletTask),
+    Audio(AudioWorkletTask),
 }
@@ -333,11 +333,9 @@ impl XRSession {
 
         // If the canvas element is attached to the DOM, it is now dirty,
         // and we need to trigger a reflow.
-        base_layer
-            .Context()
-            .Canvas()
-            .upcast::<Node>()
-            .dirty(NodeDamage::OtherNodeDamage);
+        if let Some(canvas) = base_layer.Context().canvas_element() {
+            canvas.upcast::<Node>().dirty(NodeDamage::OtherNodeDamage);
+        }
     }
 }
 
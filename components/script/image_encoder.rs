@@ -0,0 +1,315 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Serialization of canvas bitmaps as image files, for `toDataURL()`,
+//! `toBlob()` and `convertToBlob()`.
+//!
+//! <https://html.spec.whatwg.org/multipage/#a-serialisation-of-the-bitmap-as-a-file>
+
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::blob::{Blob, BlobImpl};
+use crate::dom::globalscope::GlobalScope;
+use crate::task_source::{TaskSource, TaskSourceName};
+use crossbeam_channel::{unbounded, Sender};
+use euclid::default::Size2D;
+use image::jpeg::JPEGEncoder;
+use image::png::PNGEncoder;
+use image::ColorType;
+use js::rust::HandleValue;
+use net_traits::blob_url_store::{get_blob_origin, BlobBuf};
+use net_traits::filemanager_thread::FileManagerThreadMsg;
+use net_traits::{CoreResourceMsg, IpcSend};
+use std::thread;
+use uuid::Uuid;
+
+/// The quality of JPEG files when none, or an invalid one, is requested.
+const DEFAULT_JPEG_QUALITY: f64 = 0.92;
+
+/// The largest width or height of a WebP image.
+const WEBP_MAX_DIMENSION: u32 = 1 << 14;
+
+lazy_static! {
+    /// The thread that bitmaps are encoded on, shared by every script thread
+    /// so that serializing a canvas doesn't start a new thread each time.
+    static ref ENCODER_THREAD: Sender<Box<dyn FnOnce() + Send>> = {
+        let (sender, receiver) = unbounded::<Box<dyn FnOnce() + Send>>();
+        thread::Builder::new()
+            .name("Canvas encoder".to_owned())
+            .spawn(move || {
+                for job in receiver.iter() {
+                    job();
+                }
+            })
+            .expect("Thread spawning failed");
+        sender
+    };
+}
+
+/// An image format a bitmap can be serialized to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EncodedImageType {
+    Png,
+    Jpeg,
+    Webp,
+}
+
+impl EncodedImageType {
+    /// The format for the MIME type given to a serialization method. Missing
+    /// and unsupported types fall back to PNG.
+    pub fn from_mime_type(mime_type: Option<&str>) -> EncodedImageType {
+        match mime_type.map(|mime_type| mime_type.to_ascii_lowercase()) {
+            Some(ref mime_type) if mime_type == "image/jpeg" => EncodedImageType::Jpeg,
+            Some(ref mime_type) if mime_type == "image/webp" => EncodedImageType::Webp,
+            _ => EncodedImageType::Png,
+        }
+    }
+
+    pub fn as_mime_type(&self) -> &'static str {
+        match *self {
+            EncodedImageType::Png => "image/png",
+            EncodedImageType::Jpeg => "image/jpeg",
+            EncodedImageType::Webp => "image/webp",
+        }
+    }
+}
+
+/// An encoded file that was handed over to the file manager.
+pub struct EncodedFile {
+    id: Uuid,
+    bytes: Vec<u8>,
+    image_type: EncodedImageType,
+}
+
+impl EncodedFile {
+    /// A blob backed by the file, which keeps the bytes cached.
+    pub fn into_blob(self, global: &GlobalScope) -> DomRoot<Blob> {
+        Blob::new(
+            global,
+            BlobImpl::new_from_promoted_bytes(self.id, self.bytes),
+            self.image_type.as_mime_type().to_owned(),
+        )
+    }
+}
+
+/// The quality passed to `toDataURL()` or `toBlob()`, if it is a number.
+pub fn quality_from_value(value: HandleValue) -> Option<f64> {
+    if value.is_number() {
+        Some(value.to_number())
+    } else {
+        None
+    }
+}
+
+/// Encodes `pixels`, in RGBA8 format without premultiplied alpha. `quality`
+/// only applies to JPEG files, and is ignored unless it is between 0 and 1.
+/// WebP files are always lossless, so it is ignored for them.
+pub fn encode(
+    pixels: &[u8],
+    size: Size2D<u32>,
+    image_type: EncodedImageType,
+    quality: Option<f64>,
+) -> Result<Vec<u8>, ()> {
+    if size.width == 0 || size.height == 0 {
+        return Err(());
+    }
+
+    let mut file = Vec::new();
+    match image_type {
+        EncodedImageType::Png => {
+            // FIXME(nox): https://github.com/PistonDevelopers/image-png/issues/86
+            // FIXME(nox): https://github.com/PistonDevelopers/image-png/issues/87
+            PNGEncoder::new(&mut file)
+                .encode(pixels, size.width, size.height, ColorType::RGBA(8))
+                .map_err(|_| ())?;
+        },
+        EncodedImageType::Jpeg => {
+            // JPEG has no alpha channel, so the bitmap is composited onto an
+            // opaque black background.
+            let rgb: Vec<u8> = pixels
+                .chunks(4)
+                .flat_map(|pixel| {
+                    let alpha = pixel[3];
+                    (0..3).map(move |i| pixels::multiply_u8_color(pixel[i], alpha))
+                })
+                .collect();
+            let quality = quality
+                .filter(|quality| *quality >= 0. && *quality <= 1.)
+                .unwrap_or(DEFAULT_JPEG_QUALITY);
+            JPEGEncoder::new_with_quality(&mut file, (quality * 100.).round().max(1.) as u8)
+                .encode(&rgb, size.width, size.height, ColorType::RGB(8))
+                .map_err(|_| ())?;
+        },
+        EncodedImageType::Webp => {
+            file = encode_webp_lossless(pixels, size)?;
+        },
+    }
+    Ok(file)
+}
+
+/// Encodes the bitmap on the encoder thread and hands the file over to the
+/// file manager, then runs `callback` from a task with the file, or `None` if the
+/// bitmap couldn't be encoded.
+pub fn encode_to_blob_in_parallel<F>(
+    global: &GlobalScope,
+    pixels: Vec<u8>,
+    size: Size2D<u32>,
+    image_type: EncodedImageType,
+    quality: Option<f64>,
+    callback: F,
+) where
+    F: FnOnce(Option<EncodedFile>) + Send + 'static,
+{
+    let task_source = global.dom_manipulation_task_source();
+    let canceller = global.task_canceller(TaskSourceName::DOMManipulation);
+    let resource_threads = global.resource_threads().sender();
+    let origin = get_blob_origin(&global.get_url());
+
+    let job = move || {
+        let file = encode(&pixels, size, image_type, quality)
+            .ok()
+            .map(|bytes| {
+                let id = Uuid::new_v4();
+                let blob_buf = BlobBuf {
+                    filename: None,
+                    type_string: image_type.as_mime_type().to_owned(),
+                    size: bytes.len() as u64,
+                    bytes: bytes.clone(),
+                };
+                let msg = FileManagerThreadMsg::PromoteMemory(id, blob_buf, false, origin);
+                let _ = resource_threads.send(CoreResourceMsg::ToFileManager(msg));
+                EncodedFile {
+                    id,
+                    bytes,
+                    image_type,
+                }
+            });
+        let _ = task_source.queue_with_canceller(
+            task!(serialize_bitmap_as_file: move || {
+                callback(file);
+            }),
+            &canceller,
+        );
+    };
+    let _ = ENCODER_THREAD.send(Box::new(job));
+}
+
+/// The order in which the lengths of the code length code are stored.
+const CODE_LENGTH_CODE_ORDER: [usize; 19] = [
+    17, 18, 0, 1, 2, 3, 4, 5, 16, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+];
+
+/// Writes bits least significant first, as the WebP lossless bitstream expects.
+struct BitWriter {
+    bytes: Vec<u8>,
+    bits: u64,
+    used: u32,
+}
+
+impl BitWriter {
+    fn new() -> BitWriter {
+        BitWriter {
+            bytes: vec![],
+            bits: 0,
+            used: 0,
+        }
+    }
+
+    fn write(&mut self, value: u32, count: u32) {
+        self.bits |= (value as u64) << self.used;
+        self.used += count;
+        while self.used >= 8 {
+            self.bytes.push(self.bits as u8);
+            self.bits >>= 8;
+            self.used -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.used > 0 {
+            self.bytes.push(self.bits as u8);
+        }
+        self.bytes
+    }
+}
+
+/// Writes a prefix code where the first `used` symbols of an alphabet of
+/// `alphabet_size` symbols all have 8-bit codes, so that each of them is
+/// stored as is.
+fn write_flat_prefix_code(writer: &mut BitWriter, used: usize, alphabet_size: usize) {
+    // A normal code, whose code lengths are themselves coded with 1-bit codes
+    // for lengths 0 and 8.
+    writer.write(0, 1);
+    let num_code_lengths = CODE_LENGTH_CODE_ORDER
+        .iter()
+        .position(|&length| length == 8)
+        .unwrap() +
+        1;
+    writer.write(num_code_lengths as u32 - 4, 4);
+    for &length in &CODE_LENGTH_CODE_ORDER[..num_code_lengths] {
+        writer.write((length == 0 || length == 8) as u32, 3);
+    }
+    // The lengths of the whole alphabet follow.
+    writer.write(0, 1);
+    for symbol in 0..alphabet_size {
+        writer.write((symbol < used) as u32, 1);
+    }
+}
+
+/// Encodes a lossless WebP file, without any transform or compression, which
+/// every decoder supports. There is no quality to trade for size.
+///
+/// <https://developers.google.com/speed/webp/docs/webp_lossless_bitstream_specification>
+fn encode_webp_lossless(pixels: &[u8], size: Size2D<u32>) -> Result<Vec<u8>, ()> {
+    if size.width > WEBP_MAX_DIMENSION || size.height > WEBP_MAX_DIMENSION {
+        return Err(());
+    }
+
+    let mut writer = BitWriter::new();
+    // The header.
+    writer.write(0x2f, 8);
+    writer.write(size.width - 1, 14);
+    writer.write(size.height - 1, 14);
+    let alpha_is_used = pixels.chunks(4).any(|pixel| pixel[3] != 255);
+    writer.write(alpha_is_used as u32, 1);
+    writer.write(0, 3);
+
+    // No transform, no color cache and a single prefix code group.
+    writer.write(0, 1);
+    writer.write(0, 1);
+    writer.write(0, 1);
+
+    // Green and length prefix codes, then red, blue and alpha.
+    write_flat_prefix_code(&mut writer, 256, 256 + 24);
+    for _ in 0..3 {
+        write_flat_prefix_code(&mut writer, 256, 256);
+    }
+    // A simple code with a single distance symbol, since there are no
+    // backward references.
+    writer.write(1, 1);
+    writer.write(0, 1);
+    writer.write(0, 1);
+    writer.write(0, 1);
+
+    // Codes are stored most significant bit first.
+    for pixel in pixels.chunks(4) {
+        for &component in &[pixel[1], pixel[0], pixel[2], pixel[3]] {
+            writer.write(component.reverse_bits() as u32, 8);
+        }
+    }
+
+    let mut chunk = writer.finish();
+    let chunk_size = chunk.len() as u32;
+    if chunk.len() % 2 == 1 {
+        chunk.push(0);
+    }
+
+    let mut file = Vec::with_capacity(20 + chunk.len());
+    file.extend_from_slice(b"RIFF");
+    file.extend_from_slice(&(12 + chunk.len() as u32).to_le_bytes());
+    file.extend_from_slice(b"WEBP");
+    file.extend_from_slice(b"VP8L");
+    file.extend_from_slice(&chunk_size.to_le_bytes());
+    file.extend_from_slice(&chunk);
+    Ok(file)
+}
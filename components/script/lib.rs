@@ -57,6 +57,7 @@ pub mod document_loader;
 mod dom;
 mod compartments;
 pub mod fetch;
+mod image_encoder;
 mod image_listener;
 mod layout_image;
 mod mem;
@@ -96,6 +96,7 @@ use devtools_traits::{ScriptToDevtoolsControlMsg, WorkerId};
 use embedder_traits::{EmbedderMsg, EventLoopWaker};
 use euclid::default::{Point2D, Rect};
 use euclid::Vector2D;
+use gfx_traits::WebFontLoadResult;
 use headers::ReferrerPolicy as ReferrerPolicyHeader;
 use headers::{HeaderMapExt, LastModified};
 use hyper_serde::Serde;
@@ -129,8 +130,8 @@ use profile_traits::time::{self as profile_time, profile, ProfilerCategory};
 use script_layout_interface::message::{self, LayoutThreadInit, Msg, ReflowGoal};
 use script_traits::webdriver_msg::WebDriverScriptCommand;
 use script_traits::CompositorEvent::{
-    CompositionEvent, KeyboardEvent, MouseButtonEvent, MouseMoveEvent, ResizeEvent, TouchEvent,
-    WheelEvent,
+    CompositionEvent, DragEvent, KeyboardEvent, MouseButtonEvent, MouseMoveEvent, ResizeEvent,
+    TouchEvent, WheelEvent,
 };
 use script_traits::{CompositorEvent, ConstellationControlMsg};
 use script_traits::{
@@ -140,7 +141,8 @@ use script_traits::{InitialScriptState, JsEvalResult, LayoutMsg, LoadData, LoadO
 use script_traits::{MouseButton, MouseEventType, NewLayoutInfo};
 use script_traits::{Painter, ProgressiveWebMetricType, ScriptMsg, ScriptThreadFactory};
 use script_traits::{ScriptToConstellationChan, TimerEvent, TimerSchedulerMsg};
-use script_traits::{TimerSource, TouchEventType, TouchId, UntrustedNodeAddress, WheelDelta};
+use script_traits::{TimerSource, TouchBehavior, TouchEventType, TouchId};
+use script_traits::{UntrustedNodeAddress, WheelDelta};
 use script_traits::{UpdatePipelineIdReason, WindowSizeData, WindowSizeType};
 use servo_atoms::Atom;
 use servo_url::{ImmutableOrigin, MutableOrigin, ServoUrl};
@@ -160,6 +162,7 @@ use std::thread;
 use std::time::{Duration, SystemTime};
 use style::dom::OpaqueNode;
 use style::thread_state::{self, ThreadState};
+use style_traits::MediaPreferences;
 use time::{at_utc, get_time, precise_time_ns, Timespec};
 use url::Position;
 use webrender_api::units::LayoutPixel;
@@ -605,6 +608,10 @@ pub struct ScriptThread {
     /// The topmost element over the mouse.
     topmost_mouse_over_target: MutNullableDom<Element>,
 
+    /// The positions of the mouse move events that were coalesced into the
+    /// next mouse move event to be handled, used for `getCoalescedEvents()`.
+    coalesced_mouse_moves: DomRefCell<HashMap<PipelineId, Vec<Point2D<f32>>>>,
+
     /// List of pipelines that have been owned and closed by this script thread.
     closed_pipelines: DomRefCell<HashSet<PipelineId>>,
 
@@ -685,6 +692,9 @@ pub struct ScriptThread {
     /// Application window's GL Context for Media player
     player_context: WindowGLContext,
 
+    /// The preferences of the user that media queries can test for.
+    media_preferences: Cell<MediaPreferences>,
+
     /// A mechanism to force the compositor's event loop to process events.
     event_loop_waker: Option<Box<dyn EventLoopWaker>>,
 
@@ -1108,24 +1118,40 @@ impl ScriptThread {
                 .worklet_thread_pool
                 .borrow_mut()
                 .get_or_insert_with(|| {
-                    let init = WorkletGlobalScopeInit {
-                        to_script_thread_sender: script_thread.chan.0.clone(),
-                        resource_threads: script_thread.resource_threads.clone(),
-                        mem_profiler_chan: script_thread.mem_profiler_chan.clone(),
-                        time_profiler_chan: script_thread.time_profiler_chan.clone(),
-                        devtools_chan: script_thread.devtools_chan.clone(),
-                        to_constellation_sender: script_thread.script_sender.clone(),
-                        scheduler_chan: script_thread.scheduler_chan.clone(),
-                        image_cache: script_thread.image_cache.clone(),
-                        is_headless: script_thread.headless,
-                        user_agent: script_thread.user_agent.clone(),
-                    };
-                    Rc::new(WorkletThreadPool::spawn(init))
+                    Rc::new(WorkletThreadPool::spawn(
+                        script_thread.worklet_global_scope_init(),
+                    ))
                 })
                 .clone()
         })
     }
 
+    /// Spawn a worklet thread pool with a single thread, for a worklet
+    /// whose global scope can't be shared among the threads of a pool.
+    pub fn dedicated_worklet_thread_pool() -> Rc<WorkletThreadPool> {
+        SCRIPT_THREAD_ROOT.with(|root| {
+            let script_thread = unsafe { &*root.get().unwrap() };
+            Rc::new(WorkletThreadPool::spawn_single_thread(
+                script_thread.worklet_global_scope_init(),
+            ))
+        })
+    }
+
+    fn worklet_global_scope_init(&self) -> WorkletGlobalScopeInit {
+        WorkletGlobalScopeInit {
+            to_script_thread_sender: self.chan.0.clone(),
+            resource_threads: self.resource_threads.clone(),
+            mem_profiler_chan: self.mem_profiler_chan.clone(),
+            time_profiler_chan: self.time_profiler_chan.clone(),
+            devtools_chan: self.devtools_chan.clone(),
+            to_constellation_sender: self.script_sender.clone(),
+            scheduler_chan: self.scheduler_chan.clone(),
+            image_cache: self.image_cache.clone(),
+            is_headless: self.headless,
+            user_agent: self.user_agent.clone(),
+        }
+    }
+
     fn handle_register_paint_worklet(
         &self,
         pipeline_id: PipelineId,
@@ -1316,6 +1342,7 @@ impl ScriptThread {
 
             js_runtime: Rc::new(runtime),
             topmost_mouse_over_target: MutNullableDom::new(Default::default()),
+            coalesced_mouse_moves: Default::default(),
             closed_pipelines: DomRefCell::new(HashSet::new()),
 
             scheduler_chan: state.scheduler_chan,
@@ -1357,6 +1384,7 @@ impl ScriptThread {
             replace_surrogates,
             user_agent,
             player_context: state.player_context,
+            media_preferences: Cell::new(state.media_preferences),
             event_loop_waker: state.event_loop_waker,
 
             node_ids: Default::default(),
@@ -1495,7 +1523,22 @@ impl ScriptThread {
                             mouse_move_event_index = Some(sequential.len());
                             sequential.push(event);
                         },
-                        Some(index) => sequential[index] = event,
+                        Some(index) => {
+                            if let FromConstellation(ConstellationControlMsg::SendEvent(
+                                id,
+                                MouseMoveEvent(point, ..),
+                            )) = sequential[index]
+                            {
+                                if let Some(point) = point {
+                                    self.coalesced_mouse_moves
+                                        .borrow_mut()
+                                        .entry(id)
+                                        .or_default()
+                                        .push(point);
+                                }
+                            }
+                            sequential[index] = event;
+                        },
                     }
                 },
                 FromScript(MainThreadScriptMsg::Inactive) => {
@@ -1691,7 +1734,7 @@ impl ScriptThread {
                     TickAllAnimations(id) => Some(id),
                     // FIXME https://github.com/servo/servo/issues/15079
                     TransitionEnd(..) => None,
-                    WebFontLoaded(id) => Some(id),
+                    WebFontLoaded(id, ..) => Some(id),
                     DispatchIFrameLoadEvent {
                         target: _,
                         parent: id,
@@ -1703,6 +1746,7 @@ impl ScriptThread {
                     WebVREvents(id, ..) => Some(id),
                     PaintMetric(..) => None,
                     ExitFullScreen(id, ..) => Some(id),
+                    MediaPreferencesChanged(id, ..) => Some(id),
                 }
             },
             MixedMessage::FromDevtools(_) => None,
@@ -1900,8 +1944,8 @@ impl ScriptThread {
             ConstellationControlMsg::TransitionEnd(unsafe_node, name, duration) => {
                 self.handle_transition_event(unsafe_node, name, duration)
             },
-            ConstellationControlMsg::WebFontLoaded(pipeline_id) => {
-                self.handle_web_font_loaded(pipeline_id)
+            ConstellationControlMsg::WebFontLoaded(pipeline_id, result) => {
+                self.handle_web_font_loaded(pipeline_id, result)
             },
             ConstellationControlMsg::DispatchIFrameLoadEvent {
                 target: browsing_context_id,
@@ -1929,6 +1973,9 @@ impl ScriptThread {
             ConstellationControlMsg::PaintMetric(pipeline_id, metric_type, metric_value) => {
                 self.handle_paint_metric(pipeline_id, metric_type, metric_value)
             },
+            ConstellationControlMsg::MediaPreferencesChanged(pipeline_id, media_preferences) => {
+                self.handle_media_preferences_changed(pipeline_id, media_preferences)
+            },
             msg @ ConstellationControlMsg::AttachLayout(..) |
             msg @ ConstellationControlMsg::Viewport(..) |
             msg @ ConstellationControlMsg::SetScrollState(..) |
@@ -2303,6 +2350,26 @@ impl ScriptThread {
         }
     }
 
+    fn handle_media_preferences_changed(
+        &self,
+        id: PipelineId,
+        media_preferences: MediaPreferences,
+    ) {
+        // Documents created from now on in this script thread get the new
+        // preferences too.
+        self.media_preferences.set(media_preferences);
+        let window = match { self.documents.borrow().find_window(id) } {
+            Some(window) => window,
+            None => return,
+        };
+        window.set_media_preferences(media_preferences);
+        window.force_reflow(ReflowGoal::Full, ReflowReason::MediaPreferencesChanged);
+
+        // https://html.spec.whatwg.org/multipage/#event-loop-processing-model
+        // Step 7.7 - evaluate media queries and report changes
+        window.evaluate_media_queries_and_report_changes();
+    }
+
     fn handle_viewport(&self, id: PipelineId, rect: Rect<f32>) {
         let document = self.documents.borrow().find_document(id);
         if let Some(document) = document {
@@ -2806,6 +2873,7 @@ impl ScriptThread {
         debug!("Exiting pipeline {}.", id);
 
         self.closed_pipelines.borrow_mut().insert(id);
+        self.coalesced_mouse_moves.borrow_mut().remove(&id);
 
         // Check if the exit message is for an in progress load.
         let idx = self
@@ -2955,10 +3023,11 @@ impl ScriptThread {
     }
 
     /// Handles a Web font being loaded. Does nothing if the page no longer exists.
-    fn handle_web_font_loaded(&self, pipeline_id: PipelineId) {
+    fn handle_web_font_loaded(&self, pipeline_id: PipelineId, result: WebFontLoadResult) {
         let document = self.documents.borrow().find_document(pipeline_id);
         if let Some(document) = document {
             self.rebuild_and_force_reflow(&document, ReflowReason::WebFontLoaded);
+            document.handle_web_font_loaded(result);
         }
     }
 
@@ -3201,6 +3270,7 @@ impl ScriptThread {
             incomplete.pipeline_id,
             incomplete.parent_info,
             incomplete.window_size,
+            self.media_preferences.get(),
             origin.clone(),
             incomplete.navigation_start,
             incomplete.navigation_start_precise,
@@ -3440,12 +3510,18 @@ impl ScriptThread {
                 // Get the previous target temporarily
                 let prev_mouse_over_target = self.topmost_mouse_over_target.get();
 
+                let coalesced_points = self
+                    .coalesced_mouse_moves
+                    .borrow_mut()
+                    .remove(&pipeline_id)
+                    .unwrap_or_default();
                 document.handle_mouse_move_event(
                     self.js_runtime.rt(),
                     point,
                     &self.topmost_mouse_over_target,
                     node_address,
                     pressed_mouse_buttons,
+                    &coalesced_points,
                 );
 
                 // Short-circuit if nothing changed
@@ -3503,13 +3579,8 @@ impl ScriptThread {
                     node_address,
                 );
                 match (event_type, touch_result) {
-                    (TouchEventType::Down, TouchEventResult::Processed(handled)) => {
-                        let result = if handled {
-                            // TODO: Wait to see if preventDefault is called on the first touchmove event.
-                            EventResult::DefaultAllowed
-                        } else {
-                            EventResult::DefaultPrevented
-                        };
+                    (TouchEventType::Down, TouchEventResult::Processed(result)) => {
+                        // TODO: Wait to see if preventDefault is called on the first touchmove event.
                         let message = ScriptMsg::TouchEventProcessed(result);
                         self.script_sender.send((pipeline_id, message)).unwrap();
                     },
@@ -3538,6 +3609,20 @@ impl ScriptThread {
                 };
                 document.dispatch_composition_event(composition_event);
             },
+
+            DragEvent(event_type, point, node_address, data) => {
+                let document = match { self.documents.borrow().find_document(pipeline_id) } {
+                    Some(document) => document,
+                    None => return warn!("Message sent to closed pipeline {}.", pipeline_id),
+                };
+                document.handle_drag_event(
+                    self.js_runtime.rt(),
+                    event_type,
+                    point,
+                    node_address,
+                    data,
+                );
+            },
         }
 
         ScriptThread::set_user_interacting(false);
@@ -3580,7 +3665,9 @@ impl ScriptThread {
             Some(document) => document,
             None => {
                 warn!("Message sent to closed pipeline {}.", pipeline_id);
-                return TouchEventResult::Processed(true);
+                return TouchEventResult::Processed(EventResult::DefaultAllowed(
+                    TouchBehavior::auto(),
+                ));
             },
         };
         document.handle_touch_event(
@@ -39,7 +39,7 @@ use std::sync::Mutex;
 use style::media_queries::MediaList;
 use style::parser::ParserContext;
 use style::shared_lock::{Locked, SharedRwLock};
-use style::stylesheets::import_rule::ImportSheet;
+use style::stylesheets::import_rule::{ImportLayer, ImportSheet};
 use style::stylesheets::StylesheetLoader as StyleStylesheetLoader;
 use style::stylesheets::{
     CssRules, ImportRule, Namespaces, Origin, Stylesheet, StylesheetContents,
@@ -362,6 +362,7 @@ impl<'a> StyleStylesheetLoader for StylesheetLoader<'a> {
         context: &ParserContext,
         lock: &SharedRwLock,
         media: Arc<Locked<MediaList>>,
+        layer: Option<ImportLayer>,
     ) -> Arc<Locked<ImportRule>> {
         let sheet = Arc::new(Stylesheet {
             contents: StylesheetContents {
@@ -383,6 +384,7 @@ impl<'a> StyleStylesheetLoader for StylesheetLoader<'a> {
             url,
             source_location,
             stylesheet,
+            layer,
         };
 
         let url = match import.url.url().cloned() {
@@ -15,6 +15,10 @@ pub mod area {
     pub use crate::dom::htmlareaelement::{Area, Shape};
 }
 
+pub mod image_encoder {
+    pub use crate::image_encoder::{encode, EncodedImageType};
+}
+
 pub mod size_of {
     use crate::dom::characterdata::CharacterData;
     use crate::dom::element::Element;
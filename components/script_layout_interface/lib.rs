@@ -98,7 +98,23 @@ pub enum LayoutElementType {
     HTMLTableRowElement,
     HTMLTableSectionElement,
     HTMLTextAreaElement,
+    SVGCircleElement,
+    SVGClipPathElement,
+    SVGDefsElement,
+    SVGEllipseElement,
+    SVGGElement,
+    SVGLineElement,
+    SVGLinearGradientElement,
+    SVGPathElement,
+    SVGPolygonElement,
+    SVGPolylineElement,
+    SVGRadialGradientElement,
+    SVGRectElement,
+    SVGStopElement,
     SVGSVGElement,
+    SVGTSpanElement,
+    SVGTextElement,
+    SVGUseElement,
 }
 
 pub enum HTMLCanvasDataSource {
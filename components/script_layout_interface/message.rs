@@ -21,11 +21,16 @@ use servo_atoms::Atom;
 use servo_url::ServoUrl;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
+use style::animation::Animation;
 use style::context::QuirksMode;
 use style::dom::OpaqueNode;
+use style::font_face::EffectiveSources;
 use style::properties::PropertyId;
+use style::properties_and_values::PropertyRegistration;
 use style::selector_parser::PseudoElement;
 use style::stylesheets::Stylesheet;
+use style::values::computed::font::FamilyName;
+use style_traits::MediaPreferences;
 
 /// Asynchronous messages that script can send to layout.
 pub enum Msg {
@@ -103,6 +108,81 @@ pub enum Msg {
 
     /// Request the current number of animations that are running.
     GetRunningAnimations(IpcSender<usize>),
+
+    /// Starts or updates an animation created through the Web Animations API.
+    /// The start time of the animation is relative to the time this message
+    /// is handled, since script and layout don't share a clock.
+    UpdateScriptAnimation(Animation),
+
+    /// Cancels the keyframes animation with the given name running on a node.
+    CancelAnimation(OpaqueNode, Atom),
+
+    /// Pauses or resumes the keyframes animation with the given name running on
+    /// a node.
+    SetAnimationPaused(OpaqueNode, Atom, bool),
+
+    /// Cancels the transition of the given property running on a node.
+    CancelTransition(OpaqueNode, Atom),
+
+    /// Seeks the transition of the given property running on a node to the
+    /// given time since its start, in seconds, and pauses or resumes it.
+    SetTransitionTime(OpaqueNode, Atom, f64, bool),
+
+    /// Requests the animations running on a node, or on the whole document if
+    /// no node is given.
+    GetAnimations(Option<OpaqueNode>, Sender<Vec<RunningAnimationInfo>>),
+
+    /// Asks the font cache to load a font face created through the CSS Font
+    /// Loading API. The result is reported back to script as a
+    /// `WebFontLoaded` message once the load finishes.
+    LoadWebFont(FamilyName, WebFontSource),
+
+    /// Registers a custom property through `CSS.registerProperty`.
+    RegisterCustomProperty(PropertyRegistration),
+}
+
+/// Where the data of a font face loaded through the CSS Font Loading API
+/// comes from.
+pub enum WebFontSource {
+    /// A list of `src` sources to try in order.
+    Sources(EffectiveSources),
+    /// Font data that script already has in memory.
+    Data(Vec<u8>),
+}
+
+/// The kind of an animation running in layout.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RunningAnimationKind {
+    /// A CSS transition.
+    Transition,
+    /// A CSS animation.
+    CssAnimation,
+    /// An animation created through the Web Animations API.
+    ScriptAnimation,
+}
+
+/// A snapshot of an animation running in layout, so that script can reflect CSS
+/// animations and transitions. All times are in seconds.
+#[derive(Clone, Debug)]
+pub struct RunningAnimationInfo {
+    /// The node this animation runs on.
+    pub node: UntrustedNodeAddress,
+    /// What kind of animation this is.
+    pub kind: RunningAnimationKind,
+    /// The animation name, or the transitioned property for transitions.
+    pub name: Atom,
+    /// The time the current iteration started at.
+    pub started_at: f64,
+    /// The duration of a single iteration.
+    pub duration: f64,
+    /// The index of the current iteration.
+    pub current_iteration: f64,
+    /// The number of iterations, or `None` if the animation repeats forever.
+    pub iterations: Option<f64>,
+    /// The progress of the current iteration if the animation is paused.
+    pub paused_progress: Option<f64>,
+    /// The time this snapshot was taken at.
+    pub now: f64,
 }
 
 #[derive(Debug, PartialEq)]
@@ -126,6 +206,7 @@ pub enum QueryMsg {
     // garbage values such as `0xdeadbeef as *const _`, this is unsound.
     NodeScrollIdQuery(TrustedNodeAddress),
     ResolvedStyleQuery(TrustedNodeAddress, Option<PseudoElement>, PropertyId),
+    ResolvedFontStyleQuery(TrustedNodeAddress, PropertyId, String),
     StyleQuery(TrustedNodeAddress),
     ElementInnerTextQuery(TrustedNodeAddress),
 }
@@ -154,6 +235,7 @@ impl ReflowGoal {
                 QueryMsg::NodeScrollGeometryQuery(_) |
                 QueryMsg::NodeScrollIdQuery(_) |
                 QueryMsg::ResolvedStyleQuery(..) |
+                QueryMsg::ResolvedFontStyleQuery(..) |
                 QueryMsg::OffsetParentQuery(_) |
                 QueryMsg::StyleQuery(_) => false,
             },
@@ -175,6 +257,7 @@ impl ReflowGoal {
                 QueryMsg::NodeScrollGeometryQuery(_) |
                 QueryMsg::NodeScrollIdQuery(_) |
                 QueryMsg::ResolvedStyleQuery(..) |
+                QueryMsg::ResolvedFontStyleQuery(..) |
                 QueryMsg::OffsetParentQuery(_) |
                 QueryMsg::StyleQuery(_) => false,
             },
@@ -207,6 +290,8 @@ pub struct ScriptReflow {
     pub stylesheets_changed: bool,
     /// The current window size.
     pub window_size: WindowSizeData,
+    /// The preferences of the user that media queries can test for.
+    pub media_preferences: MediaPreferences,
     /// The channel that we send a notification to.
     pub script_join_chan: Sender<ReflowComplete>,
     /// The goal of this reflow.
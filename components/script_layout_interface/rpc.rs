@@ -6,6 +6,7 @@ use app_units::Au;
 use euclid::default::Rect;
 use script_traits::UntrustedNodeAddress;
 use servo_arc::Arc;
+use style::properties::style_structs::Font;
 use style::properties::ComputedValues;
 use webrender_api::ExternalScrollId;
 
@@ -30,6 +31,9 @@ pub trait LayoutRPC {
     fn node_scroll_id(&self) -> NodeScrollIdResponse;
     /// Query layout for the resolved value of a given CSS property
     fn resolved_style(&self) -> ResolvedStyleResponse;
+    /// Query layout for the resolved font style of a given `font` value, as used by the 2D
+    /// canvas `font` attribute.
+    fn resolved_font_style(&self) -> Option<Arc<Font>>;
     fn offset_parent(&self) -> OffsetParentResponse;
     /// Requests the styles for an element. Contains a `None` value if the element is in a `display:
     /// none` subtree.
@@ -16,6 +16,7 @@ use msg::constellation_msg::{BrowsingContextId, PipelineId};
 use net_traits::image::base::{Image, ImageMetadata};
 use range::Range;
 use servo_arc::Arc;
+use servo_atoms::Atom;
 use servo_url::ServoUrl;
 use std::fmt::Debug;
 use std::sync::Arc as StdArc;
@@ -287,6 +288,9 @@ pub trait ThreadSafeLayoutNode:
 
     fn svg_data(&self) -> Option<SVGSVGData>;
 
+    /// Returns the first element of the document of this node with the given id, if any.
+    fn element_with_id(&self, id: &Atom) -> Option<Self>;
+
     fn media_data(&self) -> Option<HTMLMediaData>;
 
     /// If this node is an iframe element, returns its browsing context ID. If this node is
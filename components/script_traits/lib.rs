@@ -24,9 +24,9 @@ use bluetooth_traits::BluetoothRequest;
 use canvas_traits::webgl::WebGLPipeline;
 use crossbeam_channel::{Receiver, RecvTimeoutError, Sender};
 use devtools_traits::{DevtoolScriptControlMsg, ScriptToDevtoolsControlMsg, WorkerId};
-use embedder_traits::{Cursor, EventLoopWaker};
+use embedder_traits::{Cursor, DragOperation, EventLoopWaker};
 use euclid::{default::Point2D, Length, Rect, Scale, Size2D, UnknownUnit, Vector2D};
-use gfx_traits::Epoch;
+use gfx_traits::{Epoch, WebFontLoadResult};
 use http::HeaderMap;
 use hyper::Method;
 use ipc_channel::ipc::{IpcReceiver, IpcSender};
@@ -38,6 +38,7 @@ use media::WindowGLContext;
 use msg::constellation_msg::BackgroundHangMonitorRegister;
 use msg::constellation_msg::{BrowsingContextId, HistoryStateId, PipelineId};
 use msg::constellation_msg::{PipelineNamespaceId, TopLevelBrowsingContextId, TraversalDirection};
+use net_traits::filemanager_thread::SelectedFile;
 use net_traits::image::base::Image;
 use net_traits::image_cache::ImageCache;
 use net_traits::request::Referrer;
@@ -53,11 +54,13 @@ use servo_url::ServoUrl;
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt;
+use std::path::PathBuf;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::time::Duration;
 use style_traits::CSSPixel;
 use style_traits::SpeculativePainter;
+use style_traits::{ColorScheme, MediaPreferences};
 use webrender_api::units::{DeviceIntSize, DevicePixel, LayoutPixel};
 use webrender_api::{DocumentId, ExternalScrollId, ImageKey, RenderApiSender};
 use webvr_traits::{WebVREvent, WebVRMsg};
@@ -67,7 +70,7 @@ pub use crate::script_msg::{
     ServiceWorkerMsg,
 };
 pub use crate::script_msg::{
-    EventResult, IFrameSize, IFrameSizeMsg, LayoutMsg, LogEntry, ScriptMsg,
+    EventResult, IFrameSize, IFrameSizeMsg, LayoutMsg, LogEntry, ScriptMsg, TouchBehavior,
 };
 
 /// The address of a node. Layout sends these back. They must be validated via
@@ -282,6 +285,8 @@ pub enum ConstellationControlMsg {
     ResizeInactive(PipelineId, WindowSizeData),
     /// Window switched from fullscreen mode.
     ExitFullScreen(PipelineId),
+    /// The preferences of the user that media queries can test for changed.
+    MediaPreferencesChanged(PipelineId, MediaPreferences),
     /// Notifies the script that the document associated with this pipeline should 'unload'.
     UnloadDocument(PipelineId),
     /// Notifies the script that a pipeline should be closed.
@@ -351,7 +356,7 @@ pub enum ConstellationControlMsg {
     TransitionEnd(UntrustedNodeAddress, String, f64),
     /// Notifies the script thread that a new Web font has been loaded, and thus the page should be
     /// reflowed.
-    WebFontLoaded(PipelineId),
+    WebFontLoaded(PipelineId, WebFontLoadResult),
     /// Cause a `load` event to be dispatched at the appropriate iframe element.
     DispatchIFrameLoadEvent {
         /// The frame that has been marked as loaded.
@@ -417,6 +422,7 @@ impl fmt::Debug for ConstellationControlMsg {
             WebVREvents(..) => "WebVREvents",
             PaintMetric(..) => "PaintMetric",
             ExitFullScreen(..) => "ExitFullScreen",
+            MediaPreferencesChanged(..) => "MediaPreferencesChanged",
         };
         write!(formatter, "ConstellationControlMsg::{}", variant)
     }
@@ -510,6 +516,61 @@ pub struct WheelDelta {
     pub mode: WheelMode,
 }
 
+/// The data of a drag-and-drop operation, as it is sent to the documents the
+/// drag passes over.
+///
+/// <https://html.spec.whatwg.org/multipage/#drag-data-store>
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct DragData {
+    /// The text items of the drag data store, as `(type, data)` pairs.
+    pub strings: Vec<(String, String)>,
+    /// The files dragged from the embedder.
+    pub files: Vec<DraggedFile>,
+    /// <https://html.spec.whatwg.org/multipage/#dom-datatransfer-effectallowed>
+    pub effect_allowed: String,
+}
+
+/// A file dragged from the embedder.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum DraggedFile {
+    /// A file that wasn't dropped yet, of which documents only know the MIME
+    /// type. The constellation registers it with the file manager once it is
+    /// dropped on a document.
+    Pending(String),
+    /// A file registered with the file manager.
+    Selected(SelectedFile),
+}
+
+/// The image shown under the cursor during a drag-and-drop operation.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct DragImage {
+    /// The key of the image, which is already known by WebRender.
+    pub key: ImageKey,
+    /// The size of the image, in CSS pixels.
+    pub size: Size2D<f32, CSSPixel>,
+    /// The point of the image that is under the cursor, in CSS pixels.
+    pub hot_spot: Vector2D<f32, CSSPixel>,
+}
+
+/// The kind of drag-and-drop event sent to a document.
+///
+/// <https://html.spec.whatwg.org/multipage/#drag-and-drop-processing-model>
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum DragEventType {
+    /// The drag moved, which fires `drag` at the source node of the document
+    /// the drag started in.
+    Drag,
+    /// The drag moved over the document.
+    Over,
+    /// The drag left the document, or was canceled while over it.
+    Leave,
+    /// The user dropped the data on the document.
+    Drop,
+    /// The drag-and-drop operation that started in the document ended, with
+    /// the given operation.
+    End(DragOperation),
+}
+
 /// Events from the compositor that the script thread needs to know about
 #[derive(Debug, Deserialize, Serialize)]
 pub enum CompositorEvent {
@@ -545,6 +606,13 @@ pub enum CompositorEvent {
     KeyboardEvent(KeyboardEvent),
     /// An event from the IME is dispatched.
     CompositionEvent(CompositionEvent),
+    /// A drag-and-drop operation moved over, left, or dropped on the document.
+    DragEvent(
+        DragEventType,
+        Option<Point2D<f32>>,
+        Option<UntrustedNodeAddress>,
+        DragData,
+    ),
 }
 
 /// Requests a TimerEvent-Message be sent after the given duration.
@@ -643,6 +711,8 @@ pub struct InitialScriptState {
     pub devtools_chan: Option<IpcSender<ScriptToDevtoolsControlMsg>>,
     /// Information about the initial window size.
     pub window_size: WindowSizeData,
+    /// The preferences of the user that media queries can test for.
+    pub media_preferences: MediaPreferences,
     /// The ID of the pipeline namespace for this script thread.
     pub pipeline_namespace_id: PipelineNamespaceId,
     /// A ping will be sent on this channel once the script thread shuts down.
@@ -759,6 +829,62 @@ pub struct ScrollState {
     pub scroll_offset: Vector2D<f32, LayoutPixel>,
 }
 
+/// How strictly a scroll container snaps to its snap positions along an axis.
+///
+/// <https://drafts.csswg.org/css-scroll-snap-1/#snap-strictness>
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum ScrollSnapStrictness {
+    /// The scroll container must rest on a snap position.
+    Mandatory,
+    /// The scroll container rests on a snap position if it's close enough to one.
+    Proximity,
+}
+
+/// Whether scrolls that reach the edge of a scroll container chain to its ancestors.
+///
+/// <https://drafts.csswg.org/css-overscroll-1/#overscroll-behavior-properties>
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum OverscrollBehavior {
+    /// Scrolls chain to the nearest ancestor scroll container.
+    Auto,
+    /// Scrolls don't chain, but the scroll container may show overscroll effects.
+    Contain,
+    /// Scrolls don't chain, and the scroll container shows no overscroll effects.
+    None,
+}
+
+/// How a scroll container snaps and chains scrolls along one axis.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ScrollAxisBehavior {
+    /// How strictly the scroll container snaps, or `None` if it doesn't snap along this axis.
+    pub snap_strictness: Option<ScrollSnapStrictness>,
+    /// The scroll positions that align a snap area with the snapport, in ascending order.
+    pub snap_positions: Vec<f32>,
+    /// Whether scrolls chain to the ancestors of the scroll container.
+    pub overscroll_behavior: OverscrollBehavior,
+}
+
+/// A scroll container that input events can scroll, which the compositor needs to know
+/// about to snap scrolls and to stop them from chaining.
+///
+/// Scroll positions are the distances scrolled from the start of the scrollable overflow,
+/// so unlike scroll offsets they are positive.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ScrollContainerInfo {
+    /// The ID of the scroll root of the scroll container.
+    pub scroll_id: ExternalScrollId,
+    /// The ID of the scroll root of the nearest ancestor scroll container, if any.
+    pub parent_scroll_id: Option<ExternalScrollId>,
+    /// The scrollport, relative to the initial containing block when nothing is scrolled.
+    pub scrollport: Rect<f32, LayoutPixel>,
+    /// The largest scroll position along each axis.
+    pub max_scroll_position: Vector2D<f32, LayoutPixel>,
+    /// How the scroll container snaps and chains horizontal scrolls.
+    pub x: ScrollAxisBehavior,
+    /// How the scroll container snaps and chains vertical scrolls.
+    pub y: ScrollAxisBehavior,
+}
+
 /// Data about the window size.
 #[derive(Clone, Copy, Debug, Deserialize, MallocSizeOf, Serialize)]
 pub struct WindowSizeData {
@@ -863,6 +989,9 @@ pub enum ConstellationMsg {
     SelectBrowser(TopLevelBrowsingContextId),
     /// Forward an event to the script task of the given pipeline.
     ForwardEvent(PipelineId, CompositorEvent),
+    /// Forward a drop event to the script task of the given pipeline, once
+    /// the files at the given paths are registered with the file manager.
+    ForwardDropEvent(PipelineId, CompositorEvent, Vec<PathBuf>),
     /// Requesting a change to the onscreen cursor.
     SetCursor(Cursor),
     /// Enable the sampling profiler, with a given sampling rate and max total sampling duration.
@@ -871,6 +1000,10 @@ pub enum ConstellationMsg {
     DisableProfiler,
     /// Request to exit from fullscreen mode
     ExitFullScreen(TopLevelBrowsingContextId),
+    /// The color scheme the user prefers changed.
+    ColorSchemeChanged(Option<ColorScheme>),
+    /// The user started or stopped preferring reduced motion.
+    ReducedMotionChanged(bool),
 }
 
 impl fmt::Debug for ConstellationMsg {
@@ -897,10 +1030,13 @@ impl fmt::Debug for ConstellationMsg {
             SendError(..) => "SendError",
             SelectBrowser(..) => "SelectBrowser",
             ForwardEvent(..) => "ForwardEvent",
+            ForwardDropEvent(..) => "ForwardDropEvent",
             SetCursor(..) => "SetCursor",
             EnableProfiler(..) => "EnableProfiler",
             DisableProfiler => "DisableProfiler",
             ExitFullScreen(..) => "ExitFullScreen",
+            ColorSchemeChanged(..) => "ColorSchemeChanged",
+            ReducedMotionChanged(..) => "ReducedMotionChanged",
         };
         write!(formatter, "ConstellationMsg::{}", variant)
     }
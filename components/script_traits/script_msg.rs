@@ -5,15 +5,18 @@
 use crate::AnimationState;
 use crate::AuxiliaryBrowsingContextLoadInfo;
 use crate::DocumentState;
+use crate::DragData;
+use crate::DragImage;
 use crate::IFrameLoadInfoWithData;
 use crate::LayoutControlMsg;
 use crate::LoadData;
+use crate::ScrollContainerInfo;
 use crate::WindowSizeType;
 use crate::WorkerGlobalScopeInit;
 use crate::WorkerScriptLoadOrigin;
 use canvas_traits::canvas::{CanvasId, CanvasMsg};
 use devtools_traits::{ScriptToDevtoolsControlMsg, WorkerId};
-use embedder_traits::EmbedderMsg;
+use embedder_traits::{DragOperation, EmbedderMsg};
 use euclid::default::Size2D as UntypedSize2D;
 use euclid::Size2D;
 use gfx_traits::Epoch;
@@ -60,6 +63,9 @@ pub enum LayoutMsg {
     PendingPaintMetric(PipelineId, Epoch),
     /// Notifies the constellation that the viewport has been constrained in some manner
     ViewportConstrained(PipelineId, ViewportConstraints),
+    /// Inform the constellation of the scroll containers of the pipeline that input events
+    /// can scroll.
+    ScrollContainers(PipelineId, Vec<ScrollContainerInfo>),
 }
 
 impl fmt::Debug for LayoutMsg {
@@ -70,6 +76,7 @@ impl fmt::Debug for LayoutMsg {
             IFrameSizes(..) => "IFrameSizes",
             PendingPaintMetric(..) => "PendingPaintMetric",
             ViewportConstrained(..) => "ViewportConstrained",
+            ScrollContainers(..) => "ScrollContainers",
         };
         write!(formatter, "LayoutMsg::{}", variant)
     }
@@ -78,12 +85,39 @@ impl fmt::Debug for LayoutMsg {
 /// Whether a DOM event was prevented by web content
 #[derive(Debug, Deserialize, Serialize)]
 pub enum EventResult {
-    /// Allowed by web content
-    DefaultAllowed,
+    /// Allowed by web content, with the default touch behaviors that the
+    /// `touch-action` of the target allows.
+    DefaultAllowed(TouchBehavior),
     /// Prevented by web content
     DefaultPrevented,
 }
 
+/// The default touch behaviors the compositor may perform for a touch
+/// sequence, as determined by the `touch-action` of the element it started on
+/// and of its ancestors.
+///
+/// <https://w3c.github.io/pointerevents/#determining-supported-touch-behavior>
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct TouchBehavior {
+    /// Whether the touch sequence may scroll horizontally.
+    pub pan_x: bool,
+    /// Whether the touch sequence may scroll vertically.
+    pub pan_y: bool,
+    /// Whether the touch sequence may zoom with a pinch gesture.
+    pub pinch_zoom: bool,
+}
+
+impl TouchBehavior {
+    /// All default touch behaviors, as for `touch-action: auto`.
+    pub fn auto() -> TouchBehavior {
+        TouchBehavior {
+            pan_x: true,
+            pan_y: true,
+            pinch_zoom: true,
+        }
+    }
+}
+
 /// A log entry reported to the constellation
 /// We don't report all log entries, just serious ones.
 /// We need a separate type for this because `LogLevel` isn't serializable.
@@ -201,6 +235,11 @@ pub enum ScriptMsg {
     SetFinalUrl(ServoUrl),
     /// Script has handled a touch event, and either prevented or allowed default actions.
     TouchEventProcessed(EventResult),
+    /// The user started dragging data out of the document.
+    StartDrag(DragData, Option<DragImage>),
+    /// Script has handled a drag moving over the document, and determined the
+    /// operation that dropping at the current position would perform.
+    DragOverProcessed(DragOperation),
     /// A log entry, with the top-level browsing context id and thread name
     LogEntry(Option<String>, LogEntry),
     /// Discard the document.
@@ -253,6 +292,8 @@ impl fmt::Debug for ScriptMsg {
             SetDocumentState(..) => "SetDocumentState",
             SetFinalUrl(..) => "SetFinalUrl",
             TouchEventProcessed(..) => "TouchEventProcessed",
+            StartDrag(..) => "StartDrag",
+            DragOverProcessed(..) => "DragOverProcessed",
             LogEntry(..) => "LogEntry",
             DiscardDocument => "DiscardDocument",
             DiscardTopLevelBrowsingContext => "DiscardTopLevelBrowsingContext",
@@ -262,6 +262,20 @@ impl From<Specificity> for u32 {
     }
 }
 
+/// Returns the specificity of a selector whose nesting selectors (`&`) have
+/// been replaced, given the specificity of the selector itself, the
+/// specificity each nesting selector stands for, and how many of them there
+/// were.
+///
+/// https://drafts.csswg.org/css-nesting/#nest-selector
+pub(crate) fn nested_specificity(own: u32, parent: u32, count: usize) -> u32 {
+    let mut specificity = Specificity::from(own);
+    for _ in 0..count {
+        specificity += Specificity::from(parent);
+    }
+    specificity.into()
+}
+
 fn specificity<Impl>(iter: slice::Iter<Component<Impl>>) -> u32
 where
     Impl: SelectorImpl,
@@ -326,6 +340,10 @@ where
             Component::NonTSPseudoClass(..) => {
                 specificity.class_like_selectors += 1;
             },
+            Component::ParentSelector => {
+                // Accounted for when the nesting selector gets replaced, see
+                // nested_specificity.
+            },
             Component::ExplicitUniversalType |
             Component::ExplicitAnyNamespace |
             Component::ExplicitNoNamespace |
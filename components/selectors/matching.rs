@@ -787,7 +787,7 @@ where
                     })
                 })
         },
-        Component::Scope => match context.shared.scope_element {
+        Component::Scope | Component::ParentSelector => match context.shared.scope_element {
             Some(ref scope_element) => element.opaque() == *scope_element,
             None => element.is_root(),
         },
@@ -6,7 +6,7 @@ use crate::attr::{AttrSelectorOperator, AttrSelectorWithOptionalNamespace};
 use crate::attr::{NamespaceConstraint, ParsedAttrSelectorOperation};
 use crate::attr::{ParsedCaseSensitivity, SELECTOR_WHITESPACE};
 use crate::bloom::BLOOM_HASH_MASK;
-use crate::builder::{SelectorBuilder, SelectorFlags, SpecificityAndFlags};
+use crate::builder::{nested_specificity, SelectorBuilder, SelectorFlags, SpecificityAndFlags};
 use crate::context::QuirksMode;
 use crate::sink::Push;
 pub use crate::visitor::{SelectorVisitor, Visit};
@@ -19,7 +19,7 @@ use servo_arc::ThinArc;
 use smallvec::SmallVec;
 use std::borrow::{Borrow, Cow};
 use std::fmt::{self, Debug, Display, Write};
-use std::iter::Rev;
+use std::iter::{self, Rev};
 use std::slice;
 use thin_slice::ThinBoxedSlice;
 
@@ -228,6 +228,11 @@ pub trait Parser<'i> {
         false
     }
 
+    /// Whether to parse the nesting selector (`&`).
+    fn parse_parent_selector(&self) -> bool {
+        false
+    }
+
     /// This function can return an "Err" pseudo-element in order to support CSS2.1
     /// pseudo-elements.
     fn parse_non_ts_pseudo_class(
@@ -322,6 +327,40 @@ impl<Impl: SelectorImpl> SelectorList<Impl> {
         }
     }
 
+    /// Parse a comma-separated list of selectors nested inside a style rule.
+    /// <https://drafts.csswg.org/css-nesting/#syntax>
+    ///
+    /// Each selector may start with a combinator, and is made relative to the
+    /// parent rule with an implicit `&` if it doesn't contain one already.
+    pub fn parse_nested<'i, 't, P>(
+        parser: &P,
+        input: &mut CssParser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, P::Error>>
+    where
+        P: Parser<'i, Impl = Impl>,
+    {
+        let mut values = SmallVec::new();
+        loop {
+            values.push(input.parse_until_before(Delimiter::Comma, |input| {
+                parse_nested_selector(parser, input)
+            })?);
+            match input.next() {
+                Err(_) => return Ok(SelectorList(values)),
+                Ok(&Token::Comma) => continue,
+                Ok(_) => unreachable!(),
+            }
+        }
+    }
+
+    /// Returns a list with just the nesting selector (`&`).
+    pub fn parent_selector() -> Self {
+        let mut builder = SelectorBuilder::default();
+        builder.push_simple_selector(Component::ParentSelector);
+        let mut values = SmallVec::new();
+        values.push(Selector(builder.build(false, false, false)));
+        SelectorList(values)
+    }
+
     /// Creates a SelectorList from a Vec of selectors. Used in tests.
     pub fn from_vec(v: Vec<Selector<Impl>>) -> Self {
         SelectorList(SmallVec::from_vec(v))
@@ -774,6 +813,145 @@ impl<Impl: SelectorImpl> Selector<Impl> {
         self.0.slice.len()
     }
 
+    /// Whether this selector contains the nesting selector (`&`).
+    #[inline]
+    pub fn has_parent_selector(&self) -> bool {
+        self.iter_raw_match_order()
+            .any(|c| matches!(*c, Component::ParentSelector))
+    }
+
+    /// Returns the compound selectors of this selector in parse order (from
+    /// left to right), each along with the combinator to its right.
+    fn compounds_in_parse_order(&self) -> SmallVec<[(&[Component<Impl>], Option<Combinator>); 4]> {
+        let combinators = self
+            .iter_raw_match_order()
+            .rev()
+            .filter_map(|x| x.as_combinator())
+            .map(Some)
+            .chain(iter::once(None));
+        self.0
+            .slice
+            .split(|x| x.is_combinator())
+            .rev()
+            .zip(combinators)
+            .collect()
+    }
+
+    /// Returns a copy of this selector with `&` and `combinator` prepended
+    /// to it.
+    fn prepend_parent_selector(&self, combinator: Combinator) -> Self {
+        let mut builder = SelectorBuilder::default();
+        builder.push_simple_selector(Component::ParentSelector);
+        builder.push_combinator(combinator);
+        for (compound, combinator) in self.compounds_in_parse_order() {
+            for simple in compound {
+                builder.push_simple_selector(simple.clone());
+            }
+            if let Some(combinator) = combinator {
+                builder.push_combinator(combinator);
+            }
+        }
+        Selector(builder.build_with_specificity_and_flags(self.0.header.header))
+    }
+
+    /// Returns the selectors that result from replacing the nesting selector
+    /// (`&`) with each of `parents`, or with every combination of them if the
+    /// nesting selector appears more than once.
+    ///
+    /// The resulting selectors all get the specificity of `:is(<parents>)` for
+    /// each nesting selector, as described in:
+    ///
+    /// https://drafts.csswg.org/css-nesting/#nest-selector
+    ///
+    /// Pseudo-elements can't be represented by the nesting selector, so
+    /// parents that have any are skipped.
+    pub fn replace_parent_selector(&self, parents: &[Selector<Impl>]) -> Vec<Self> {
+        let count = self
+            .iter_raw_match_order()
+            .filter(|c| matches!(**c, Component::ParentSelector))
+            .count();
+        if count == 0 {
+            return vec![self.clone()];
+        }
+
+        let parents = parents
+            .iter()
+            .filter(|p| !p.has_pseudo_element() && !p.is_slotted() && !p.is_part())
+            .collect::<SmallVec<[&Selector<Impl>; 4]>>();
+        let parent_specificity = match parents.iter().map(|p| p.specificity()).max() {
+            Some(specificity) => specificity,
+            None => return vec![],
+        };
+        let spec = SpecificityAndFlags {
+            specificity: nested_specificity(self.specificity(), parent_specificity, count),
+            flags: self.0.header.header.flags,
+        };
+
+        let compounds = self.compounds_in_parse_order();
+        let mut choices = vec![0; count];
+        let mut result = vec![];
+        loop {
+            let mut builder = SelectorBuilder::default();
+            let mut next_choice = 0;
+            for &(compound, combinator) in compounds.iter() {
+                // The compound selectors to the left of the rightmost one of
+                // each parent go before this compound selector.
+                let mut choice = next_choice;
+                for _ in compound
+                    .iter()
+                    .filter(|c| matches!(**c, Component::ParentSelector))
+                {
+                    let parent = parents[choices[choice]];
+                    choice += 1;
+                    for (parent_compound, parent_combinator) in parent.compounds_in_parse_order() {
+                        let parent_combinator = match parent_combinator {
+                            Some(c) => c,
+                            None => break,
+                        };
+                        for simple in parent_compound {
+                            builder.push_simple_selector(simple.clone());
+                        }
+                        builder.push_combinator(parent_combinator);
+                    }
+                }
+
+                // And the rightmost one gets merged into it.
+                for simple in compound {
+                    if let Component::ParentSelector = *simple {
+                        let parent = parents[choices[next_choice]];
+                        next_choice += 1;
+                        let last = parent.iter_raw_match_order().as_slice();
+                        let last = last.split(|x| x.is_combinator()).next().unwrap_or(&[]);
+                        for simple in last {
+                            builder.push_simple_selector(simple.clone());
+                        }
+                        continue;
+                    }
+                    builder.push_simple_selector(simple.clone());
+                }
+
+                if let Some(combinator) = combinator {
+                    builder.push_combinator(combinator);
+                }
+            }
+            result.push(Selector(builder.build_with_specificity_and_flags(spec)));
+
+            // Move on to the next combination of parents.
+            let mut i = 0;
+            loop {
+                if i == count {
+                    return result;
+                }
+                choices[i] += 1;
+                if choices[i] < parents.len() {
+                    break;
+                }
+                choices[i] = 0;
+                i += 1;
+            }
+        }
+    }
+
     /// Returns the address on the heap of the ThinArc for memory reporting.
     pub fn thin_arc_heap_ptr(&self) -> *const ::std::os::raw::c_void {
         self.0.heap_ptr()
@@ -991,6 +1169,15 @@ pub enum Component<Impl: SelectorImpl> {
     Root,
     Empty,
     Scope,
+    /// The nesting selector (`&`), which represents the elements matched by
+    /// the parent style rule:
+    ///
+    /// https://drafts.csswg.org/css-nesting/#nest-selector
+    ///
+    /// It gets replaced before matching in nested style rules (see
+    /// `Selector::replace_parent_selector`). Elsewhere it matches like
+    /// `:scope`.
+    ParentSelector,
     NthChild(i32, i32),
     NthLastChild(i32, i32),
     NthOfType(i32, i32),
@@ -1370,6 +1557,7 @@ impl<Impl: SelectorImpl> ToCss for Component<Impl> {
             Root => dest.write_str(":root"),
             Empty => dest.write_str(":empty"),
             Scope => dest.write_str(":scope"),
+            ParentSelector => dest.write_char('&'),
             Host(ref selector) => {
                 dest.write_str(":host")?;
                 if let Some(ref selector) = *selector {
@@ -1539,6 +1727,34 @@ where
     Ok(Selector(builder.build(has_pseudo_element, slotted, part)))
 }
 
+/// Parses a selector nested inside a style rule, which may start with a
+/// combinator, making it relative to the parent rule if needed.
+fn parse_nested_selector<'i, 't, P, Impl>(
+    parser: &P,
+    input: &mut CssParser<'i, 't>,
+) -> Result<Selector<Impl>, ParseError<'i, P::Error>>
+where
+    P: Parser<'i, Impl = Impl>,
+    Impl: SelectorImpl,
+{
+    let start = input.state();
+    let leading_combinator = match input.next() {
+        Ok(&Token::Delim('>')) => Some(Combinator::Child),
+        Ok(&Token::Delim('+')) => Some(Combinator::NextSibling),
+        Ok(&Token::Delim('~')) => Some(Combinator::LaterSibling),
+        _ => {
+            input.reset(&start);
+            None
+        },
+    };
+
+    let selector = parse_selector(parser, input)?;
+    if leading_combinator.is_none() && selector.has_parent_selector() {
+        return Ok(selector);
+    }
+    Ok(selector.prepend_parent_selector(leading_combinator.unwrap_or(Combinator::Descendant)))
+}
+
 impl<Impl: SelectorImpl> Selector<Impl> {
     /// Parse a selector, without any pseudo-element.
     #[inline]
@@ -2160,6 +2376,14 @@ where
             let class = Component::Class(class.as_ref().into());
             SimpleSelectorParseResult::SimpleSelector(class)
         },
+        Token::Delim('&') if P::parse_parent_selector(parser) => {
+            if state.intersects(
+                SelectorParsingState::AFTER_PSEUDO | SelectorParsingState::INSIDE_NEGATION,
+            ) {
+                return Err(input.new_custom_error(SelectorParseErrorKind::InvalidState));
+            }
+            SimpleSelectorParseResult::SimpleSelector(Component::ParentSelector)
+        },
         Token::SquareBracketBlock => {
             if state.intersects(SelectorParsingState::AFTER_PSEUDO) {
                 return Err(input.new_custom_error(SelectorParseErrorKind::InvalidState));
@@ -2434,6 +2658,10 @@ pub mod tests {
             true
         }
 
+        fn parse_parent_selector(&self) -> bool {
+            true
+        }
+
         fn parse_non_ts_pseudo_class(
             &self,
             location: SourceLocation,
@@ -3107,6 +3335,62 @@ pub mod tests {
         assert_eq!(iter.next_sequence(), None);
     }
 
+    fn parse_nested<'i>(
+        input: &'i str,
+    ) -> Result<SelectorList<DummySelectorImpl>, SelectorParseError<'i>> {
+        let mut parser_input = ParserInput::new(input);
+        SelectorList::parse_nested(
+            &DummyParser::default(),
+            &mut CssParser::new(&mut parser_input),
+        )
+    }
+
+    fn parse_list<'i>(
+        input: &'i str,
+    ) -> Result<SelectorList<DummySelectorImpl>, SelectorParseError<'i>> {
+        let mut parser_input = ParserInput::new(input);
+        SelectorList::parse(
+            &DummyParser::default(),
+            &mut CssParser::new(&mut parser_input),
+        )
+    }
+
+    #[test]
+    fn test_nesting() {
+        let nested = parse_nested(".foo, > .bar, &:hover, .baz &").unwrap();
+        let serialized = nested
+            .0
+            .iter()
+            .map(|s| s.to_css_string())
+            .collect::<Vec<_>>();
+        assert_eq!(serialized, ["& .foo", "& > .bar", "&:hover", ".baz &"]);
+        assert!(parse_nested(":not(&)").is_err());
+        assert!(parse_nested("::before&").is_err());
+
+        let parents = parse_list("#a, div, p::before").unwrap();
+        let replaced = nested.0[0].replace_parent_selector(&parents.0);
+        let serialized = replaced
+            .iter()
+            .map(|s| s.to_css_string())
+            .collect::<Vec<_>>();
+        assert_eq!(serialized, ["#a .foo", "div .foo"]);
+        // The nesting selector gets the specificity of the most specific
+        // parent, like :is() would.
+        assert!(replaced
+            .iter()
+            .all(|s| s.specificity() == specificity(1, 1, 0)));
+
+        let parents = parse("ul > li").unwrap();
+        let replaced = nested.0[2].replace_parent_selector(&parents.0);
+        assert_eq!(replaced[0].to_css_string(), "ul > li:hover");
+        assert_eq!(replaced[0].specificity(), specificity(0, 1, 2));
+
+        let replaced = parse_nested("& + &").unwrap().0[0]
+            .replace_parent_selector(&parse_list(".a, .b").unwrap().0);
+        assert_eq!(replaced.len(), 4);
+        assert_eq!(replaced[1].to_css_string(), ".b + .a");
+    }
+
     struct TestVisitor {
         seen: Vec<String>,
     }
@@ -629,6 +629,10 @@ where
                     .on_touch_event(event_type, identifier, location);
             },
 
+            WindowEvent::FileDrag(event) => {
+                self.compositor.on_file_drag_event(event);
+            },
+
             WindowEvent::Wheel(delta, location) => {
                 self.compositor.on_wheel_event(delta, location);
             },
@@ -741,6 +745,26 @@ where
                     );
                 }
             },
+
+            WindowEvent::ColorSchemeChanged(color_scheme) => {
+                let msg = ConstellationMsg::ColorSchemeChanged(color_scheme);
+                if let Err(e) = self.constellation_chan.send(msg) {
+                    warn!(
+                        "Sending color scheme change to constellation failed ({:?}).",
+                        e
+                    );
+                }
+            },
+
+            WindowEvent::ReducedMotionChanged(reduced_motion) => {
+                let msg = ConstellationMsg::ReducedMotionChanged(reduced_motion);
+                if let Err(e) = self.constellation_chan.send(msg) {
+                    warn!(
+                        "Sending reduced motion change to constellation failed ({:?}).",
+                        e
+                    );
+                }
+            },
         }
     }
 
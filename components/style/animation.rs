@@ -10,19 +10,24 @@
 
 use crate::bezier::Bezier;
 use crate::context::SharedStyleContext;
+use crate::custom_properties::{Name, VariableValue};
 use crate::dom::{OpaqueNode, TElement};
 use crate::font_metrics::FontMetricsProvider;
 use crate::properties::animated_properties::AnimatedProperty;
 use crate::properties::longhands::animation_direction::computed_value::single_value::T as AnimationDirection;
 use crate::properties::longhands::animation_play_state::computed_value::single_value::T as AnimationPlayState;
 use crate::properties::{self, CascadeMode, ComputedValues, LonghandId};
+use crate::properties_and_values::ComputedRegisteredValue;
 use crate::rule_tree::CascadeLevel;
 use crate::stylesheets::keyframes_rule::{KeyframesAnimation, KeyframesStep, KeyframesStepValue};
 use crate::timer::Timer;
+use crate::values::animated::{Animate, Procedure};
 use crate::values::computed::box_::TransitionProperty;
+use crate::values::computed::Context;
 use crate::values::computed::Time;
 use crate::values::computed::TimingFunction;
 use crate::values::generics::box_::AnimationIterationCount;
+use crate::values::generics::easing::TimingKeyword;
 use crate::values::generics::easing::{StepPosition, TimingFunction as GenericTimingFunction};
 use crate::Atom;
 #[cfg(feature = "servo")]
@@ -56,6 +61,19 @@ pub enum KeyframesRunningState {
     Running,
 }
 
+/// Where a keyframes animation comes from.
+#[derive(Clone, Debug)]
+pub enum KeyframesAnimationOrigin {
+    /// An animation started by the `animation-*` properties of the element
+    /// style, looked up by name in its `animation-name` list.
+    Css,
+    /// An animation created by script through the Web Animations API. These
+    /// don't depend on the `animation-*` properties, and carry the easing of
+    /// their effect, which is applied to the whole iteration progress. Their
+    /// cascade style is refreshed every time their element is restyled.
+    Script(TimingFunction),
+}
+
 /// This structure represents the current keyframe animation state, i.e., the
 /// duration, the current and maximum iteration count, and the state (either
 /// playing or paused).
@@ -81,6 +99,8 @@ pub struct KeyframesAnimationState {
     /// The original cascade style, needed to compute the generated keyframes of
     /// the animation.
     pub cascade_style: Arc<ComputedValues>,
+    /// Whether this animation comes from CSS or from script.
+    pub origin: KeyframesAnimationOrigin,
 }
 
 impl KeyframesAnimationState {
@@ -139,6 +159,13 @@ impl KeyframesAnimationState {
             self, other
         );
 
+        // Script animations run their own timing model, so every update
+        // carries the complete, up-to-date state.
+        if let KeyframesAnimationOrigin::Script(..) = other.origin {
+            *self = other.clone();
+            return;
+        }
+
         // NB: We shall not touch the started_at field, since we don't want to
         // restart the animation.
         let old_started_at = self.started_at;
@@ -181,6 +208,23 @@ impl KeyframesAnimationState {
         self.current_direction = old_direction;
         self.started_at = new_started_at;
     }
+
+    /// Pauses or resumes this animation, keeping its progress.
+    pub fn set_paused(&mut self, paused: bool, timer: &Timer) {
+        use self::KeyframesRunningState::*;
+
+        match (paused, self.running_state.clone()) {
+            (true, Running) => {
+                let progress = (timer.seconds() - self.started_at) / self.duration;
+                self.running_state = Paused(progress);
+            },
+            (false, Paused(progress)) => {
+                self.started_at = timer.seconds() - (self.duration * progress);
+                self.running_state = Running;
+            },
+            _ => {},
+        }
+    }
 }
 
 impl fmt::Debug for KeyframesAnimationState {
@@ -195,17 +239,50 @@ impl fmt::Debug for KeyframesAnimationState {
             .field("current_direction", &self.current_direction)
             .field("expired", &self.expired)
             .field("cascade_style", &())
+            .field("origin", &self.origin)
             .finish()
     }
 }
 
+/// The state of a transition, which script can pause, seek or cancel through
+/// the `CSSTransition` it is reflected as.
+#[derive(Clone, Debug)]
+pub struct TransitionState {
+    /// The time this transition started at, as returned by `Timer::seconds()`.
+    pub started_at: f64,
+    /// Whether this transition is running or paused.
+    pub running_state: KeyframesRunningState,
+    /// Whether this transition was canceled, and should be removed without
+    /// reaching its end.
+    pub expired: bool,
+}
+
+impl TransitionState {
+    /// The time the style of this transition is computed at.
+    pub fn current_time(&self, duration: f64, timer: &Timer) -> f64 {
+        match self.running_state {
+            KeyframesRunningState::Running => timer.seconds(),
+            KeyframesRunningState::Paused(progress) => self.started_at + duration * progress,
+        }
+    }
+
+    /// Pauses or resumes this transition at the given time since its start, in
+    /// seconds.
+    pub fn set_time(&mut self, time: f64, paused: bool, duration: f64, timer: &Timer) {
+        if paused {
+            self.running_state = KeyframesRunningState::Paused(time / duration);
+        } else {
+            self.started_at = timer.seconds() - time;
+            self.running_state = KeyframesRunningState::Running;
+        }
+    }
+}
+
 /// State relating to an animation.
 #[derive(Clone, Debug)]
 pub enum Animation {
     /// A transition is just a single frame triggered at a time, with a reflow.
-    ///
-    /// the f64 field is the start time as returned by `time::precise_time_s()`.
-    Transition(OpaqueNode, f64, AnimationFrame),
+    Transition(OpaqueNode, TransitionState, AnimationFrame),
     /// A keyframes animation is identified by a name, and can have a
     /// node-dependent state (i.e. iteration count, etc.).
     ///
@@ -223,7 +300,7 @@ impl Animation {
     #[inline]
     pub fn is_expired(&self) -> bool {
         match *self {
-            Animation::Transition(..) => false,
+            Animation::Transition(_, ref state, _) => state.expired,
             Animation::Keyframes(_, _, _, ref state) => state.expired,
         }
     }
@@ -245,6 +322,15 @@ impl Animation {
             Animation::Keyframes(..) => false,
         }
     }
+
+    /// The name of this animation: the animation name for keyframes
+    /// animations, and the property name for transitions.
+    pub fn name(&self) -> &str {
+        match *self {
+            Animation::Transition(_, _, ref frame) => frame.property_animation.property_name(),
+            Animation::Keyframes(_, _, ref name, _) => &*name,
+        }
+    }
 }
 
 /// A single animation frame of a single property.
@@ -257,18 +343,125 @@ pub struct AnimationFrame {
     pub duration: f64,
 }
 
+/// An animation of a custom property.
+#[derive(Clone, Debug)]
+struct CustomPropertyAnimation {
+    name: Name,
+    /// The name of the property, including the `--` prefix.
+    property_name: String,
+    from: Option<Arc<VariableValue>>,
+    to: Option<Arc<VariableValue>>,
+    /// The typed values to interpolate between, if the property is registered
+    /// with a syntax whose values can be interpolated. Otherwise the property
+    /// animates discretely.
+    interpolated: Option<(ComputedRegisteredValue, ComputedRegisteredValue)>,
+}
+
+impl CustomPropertyAnimation {
+    /// Creates an animation of the custom property `name` between two styles,
+    /// or `None` if the property doesn't change. When `interpolated_only` is
+    /// true, only properties that can be interpolated are animated.
+    fn new(
+        context: &SharedStyleContext,
+        name: &Name,
+        old_style: &ComputedValues,
+        new_style: &ComputedValues,
+        interpolated_only: bool,
+    ) -> Option<Self> {
+        let from = old_style
+            .custom_properties()
+            .and_then(|map| map.get(name))
+            .cloned();
+        let to = new_style
+            .custom_properties()
+            .and_then(|map| map.get(name))
+            .cloned();
+        if from == to {
+            return None;
+        }
+
+        let interpolated = match (&from, &to) {
+            (&Some(ref from), &Some(ref to)) => Self::registered_values(context, name, from, to),
+            _ => None,
+        };
+        if interpolated_only && interpolated.is_none() {
+            return None;
+        }
+
+        Some(CustomPropertyAnimation {
+            name: name.clone(),
+            property_name: format!("--{}", name),
+            from,
+            to,
+            interpolated,
+        })
+    }
+
+    /// Computes the typed values of a registered custom property, if both can
+    /// be interpolated.
+    fn registered_values(
+        context: &SharedStyleContext,
+        name: &Name,
+        from: &VariableValue,
+        to: &VariableValue,
+    ) -> Option<(ComputedRegisteredValue, ComputedRegisteredValue)> {
+        let registration = context.stylist.custom_property_registry().get(name)?;
+        if registration.syntax.is_universal() {
+            return None;
+        }
+
+        // Values in the custom properties map are already computed, so they
+        // don't depend on the element.
+        Context::for_media_query_evaluation(
+            context.stylist.device(),
+            context.quirks_mode(),
+            |computed_context| {
+                let from = registration.compute(from, computed_context).ok()?;
+                let to = registration.compute(to, computed_context).ok()?;
+                from.animate(&to, Procedure::Interpolate { progress: 0.5 })
+                    .ok()?;
+                Some((from, to))
+            },
+        )
+    }
+
+    fn update(&self, style: &mut ComputedValues, progress: f64) {
+        let interpolated = self.interpolated.as_ref().and_then(|&(ref from, ref to)| {
+            from.animate(to, Procedure::Interpolate { progress })
+                .ok()?
+                .to_variable_value()
+        });
+        let value = match interpolated {
+            Some(value) => Some(value),
+            None if progress < 0.5 => self.from.clone(),
+            None => self.to.clone(),
+        };
+        style.set_custom_property(&self.name, value);
+    }
+}
+
+/// The property a `PropertyAnimation` animates.
+#[derive(Clone, Debug)]
+enum AnimatingProperty {
+    Longhand(AnimatedProperty),
+    Custom(CustomPropertyAnimation),
+}
+
 /// Represents an animation for a given property.
 #[derive(Clone, Debug)]
 pub struct PropertyAnimation {
-    property: AnimatedProperty,
+    property: AnimatingProperty,
     timing_function: TimingFunction,
     duration: Time, // TODO: isn't this just repeated?
 }
 
 impl PropertyAnimation {
     /// Returns the given property name.
-    pub fn property_name(&self) -> &'static str {
-        self.property.name()
+    pub fn property_name(&self) -> &str {
+        match self.property {
+            AnimatingProperty::Longhand(ref property) => property.name(),
+            AnimatingProperty::Custom(ref property) => &property.property_name,
+        }
     }
 
     /// Creates a new property animation for the given transition index and old
@@ -276,6 +469,7 @@ impl PropertyAnimation {
     /// the property did not animate) to one (for a single transition property)
     /// to arbitrarily many (for `all`).
     pub fn from_transition(
+        context: &SharedStyleContext,
         transition_index: usize,
         old_style: &ComputedValues,
         new_style: &mut ComputedValues,
@@ -287,7 +481,25 @@ impl PropertyAnimation {
         let duration = box_style.transition_duration_mod(transition_index);
 
         match transition_property {
-            TransitionProperty::Custom(..) | TransitionProperty::Unsupported(..) => result,
+            TransitionProperty::Unsupported(..) => result,
+            // Only registered custom properties that can be interpolated
+            // transition, as custom properties otherwise animate discretely.
+            TransitionProperty::Custom(ref name) => {
+                let animation = PropertyAnimation::from_custom_property(
+                    context,
+                    name,
+                    timing_function,
+                    duration,
+                    old_style,
+                    new_style,
+                    /* interpolated_only = */ true,
+                );
+
+                if let Some(animation) = animation {
+                    result.push(animation);
+                }
+                result
+            },
             TransitionProperty::Shorthand(ref shorthand_id) => shorthand_id
                 .longhands()
                 .filter_map(|longhand| {
@@ -327,7 +539,7 @@ impl PropertyAnimation {
         let animated_property = AnimatedProperty::from_longhand(longhand, old_style, new_style)?;
 
         let property_animation = PropertyAnimation {
-            property: animated_property,
+            property: AnimatingProperty::Longhand(animated_property),
             timing_function: timing_function,
             duration: duration,
         };
@@ -339,66 +551,112 @@ impl PropertyAnimation {
         }
     }
 
-    /// Update the given animation at a given point of progress.
-    pub fn update(&self, style: &mut ComputedValues, time: f64) {
-        let epsilon = 1. / (200. * (self.duration.seconds() as f64));
-        let progress = match self.timing_function {
-            GenericTimingFunction::CubicBezier { x1, y1, x2, y2 } => {
-                Bezier::new(x1, y1, x2, y2).solve(time, epsilon)
-            },
-            GenericTimingFunction::Steps(steps, pos) => {
-                let mut current_step = (time * (steps as f64)).floor() as i32;
-
-                if pos == StepPosition::Start ||
-                    pos == StepPosition::JumpStart ||
-                    pos == StepPosition::JumpBoth
-                {
-                    current_step = current_step + 1;
-                }
-
-                // FIXME: We should update current_step according to the "before flag".
-                // In order to get the before flag, we have to know the current animation phase
-                // and whether the iteration is reversed. For now, we skip this calculation.
-                // (i.e. Treat before_flag is unset,)
-                // https://drafts.csswg.org/css-easing/#step-timing-function-algo
-
-                if time >= 0.0 && current_step < 0 {
-                    current_step = 0;
-                }
+    fn from_custom_property(
+        context: &SharedStyleContext,
+        name: &Name,
+        timing_function: TimingFunction,
+        duration: Time,
+        old_style: &ComputedValues,
+        new_style: &ComputedValues,
+        interpolated_only: bool,
+    ) -> Option<PropertyAnimation> {
+        let animation =
+            CustomPropertyAnimation::new(context, name, old_style, new_style, interpolated_only)?;
 
-                let jumps = match pos {
-                    StepPosition::JumpBoth => steps + 1,
-                    StepPosition::JumpNone => steps - 1,
-                    StepPosition::JumpStart |
-                    StepPosition::JumpEnd |
-                    StepPosition::Start |
-                    StepPosition::End => steps,
-                };
+        let property_animation = PropertyAnimation {
+            property: AnimatingProperty::Custom(animation),
+            timing_function: timing_function,
+            duration: duration,
+        };
 
-                if time <= 1.0 && current_step > jumps {
-                    current_step = jumps;
-                }
+        if property_animation.does_animate() {
+            Some(property_animation)
+        } else {
+            None
+        }
+    }
 
-                (current_step as f64) / (jumps as f64)
-            },
-            GenericTimingFunction::Keyword(keyword) => {
-                let (x1, x2, y1, y2) = keyword.to_bezier();
-                Bezier::new(x1, x2, y1, y2).solve(time, epsilon)
-            },
-        };
+    /// Update the given animation at a given point of progress.
+    pub fn update(&self, style: &mut ComputedValues, time: f64) {
+        let epsilon = 1. / (200. * (self.duration.seconds() as f64));
+        let progress = timing_function_output(&self.timing_function, time, epsilon);
 
-        self.property.update(style, progress);
+        match self.property {
+            AnimatingProperty::Longhand(ref property) => property.update(style, progress),
+            AnimatingProperty::Custom(ref property) => property.update(style, progress),
+        }
     }
 
     #[inline]
     fn does_animate(&self) -> bool {
-        self.property.does_animate() && self.duration.seconds() != 0.0
+        let property_animates = match self.property {
+            AnimatingProperty::Longhand(ref property) => property.does_animate(),
+            AnimatingProperty::Custom(ref property) => property.from != property.to,
+        };
+        property_animates && self.duration.seconds() != 0.0
     }
 
     /// Whether this animation has the same end value as another one.
     #[inline]
     pub fn has_the_same_end_value_as(&self, other: &Self) -> bool {
-        self.property.has_the_same_end_value_as(&other.property)
+        match (&self.property, &other.property) {
+            (&AnimatingProperty::Longhand(ref this), &AnimatingProperty::Longhand(ref other)) => {
+                this.has_the_same_end_value_as(other)
+            },
+            (&AnimatingProperty::Custom(ref this), &AnimatingProperty::Custom(ref other)) => {
+                this.name == other.name && this.to == other.to
+            },
+            _ => false,
+        }
+    }
+}
+
+/// Computes the output progress of a timing function for a given input
+/// progress, with the given precision for cubic bézier curves.
+pub fn timing_function_output(timing_function: &TimingFunction, time: f64, epsilon: f64) -> f64 {
+    match *timing_function {
+        GenericTimingFunction::CubicBezier { x1, y1, x2, y2 } => {
+            Bezier::new(x1, y1, x2, y2).solve(time, epsilon)
+        },
+        GenericTimingFunction::Steps(steps, pos) => {
+            let mut current_step = (time * (steps as f64)).floor() as i32;
+
+            if pos == StepPosition::Start ||
+                pos == StepPosition::JumpStart ||
+                pos == StepPosition::JumpBoth
+            {
+                current_step = current_step + 1;
+            }
+
+            // FIXME: We should update current_step according to the "before flag".
+            // In order to get the before flag, we have to know the current animation phase
+            // and whether the iteration is reversed. For now, we skip this calculation.
+            // (i.e. Treat before_flag is unset,)
+            // https://drafts.csswg.org/css-easing/#step-timing-function-algo
+
+            if time >= 0.0 && current_step < 0 {
+                current_step = 0;
+            }
+
+            let jumps = match pos {
+                StepPosition::JumpBoth => steps + 1,
+                StepPosition::JumpNone => steps - 1,
+                StepPosition::JumpStart |
+                StepPosition::JumpEnd |
+                StepPosition::Start |
+                StepPosition::End => steps,
+            };
+
+            if time <= 1.0 && current_step > jumps {
+                current_step = jumps;
+            }
+
+            (current_step as f64) / (jumps as f64)
+        },
+        GenericTimingFunction::Keyword(keyword) => {
+            let (x1, x2, y1, y2) = keyword.to_bezier();
+            Bezier::new(x1, x2, y1, y2).solve(time, epsilon)
+        },
     }
 }
 
@@ -406,18 +664,18 @@ impl PropertyAnimation {
 /// the given style difference. This is called from the layout worker threads.
 /// Returns true if any animations were kicked off and false otherwise.
 pub fn start_transitions_if_applicable(
+    context: &SharedStyleContext,
     new_animations_sender: &Sender<Animation>,
     opaque_node: OpaqueNode,
     old_style: &ComputedValues,
     new_style: &mut Arc<ComputedValues>,
-    timer: &Timer,
     possibly_expired_animations: &[PropertyAnimation],
 ) -> bool {
     let mut had_animations = false;
     for i in 0..new_style.get_box().transition_property_count() {
         // Create any property animations, if applicable.
         let property_animations =
-            PropertyAnimation::from_transition(i, old_style, Arc::make_mut(new_style));
+            PropertyAnimation::from_transition(context, i, old_style, Arc::make_mut(new_style));
         for property_animation in property_animations {
             // Set the property to the initial value.
             //
@@ -446,12 +704,16 @@ pub fn start_transitions_if_applicable(
 
             // Kick off the animation.
             let box_style = new_style.get_box();
-            let now = timer.seconds();
+            let now = context.timer.seconds();
             let start_time = now + (box_style.transition_delay_mod(i).seconds() as f64);
             new_animations_sender
                 .send(Animation::Transition(
                     opaque_node,
-                    start_time,
+                    TransitionState {
+                        started_at: start_time,
+                        running_state: KeyframesRunningState::Running,
+                        expired: false,
+                    },
                     AnimationFrame {
                         duration: box_style.transition_duration_mod(i).seconds() as f64,
                         property_animation,
@@ -509,6 +771,7 @@ where
                 CascadeMode::Unvisited {
                     visited_rules: None,
                 },
+                context.stylist.custom_property_registry(),
                 context.quirks_mode(),
                 /* rule_cache = */ None,
                 &mut Default::default(),
@@ -601,6 +864,7 @@ where
                     current_direction: initial_direction,
                     expired: false,
                     cascade_style: new_style.clone(),
+                    origin: KeyframesAnimationOrigin::Css,
                 },
             ))
             .unwrap();
@@ -665,11 +929,11 @@ where
     debug_assert!(!animation.is_expired());
 
     match *animation {
-        Animation::Transition(_, start_time, ref frame) => {
-            let now = context.timer.seconds();
+        Animation::Transition(_, ref state, ref frame) => {
+            let now = state.current_time(frame.duration, &context.timer);
             let mut new_style = (*style).clone();
             let updated_style =
-                update_style_for_animation_frame(&mut new_style, now, start_time, frame);
+                update_style_for_animation_frame(&mut new_style, now, state.started_at, frame);
             if updated_style {
                 *style = new_style
             }
@@ -691,17 +955,32 @@ where
 
             debug_assert!(!animation.steps.is_empty());
 
-            let maybe_index = style
-                .get_box()
-                .animation_name_iter()
-                .position(|animation_name| Some(name) == animation_name.as_atom());
-
-            let index = match maybe_index {
-                Some(index) => index,
-                None => return AnimationUpdate::AnimationCanceled,
+            // Script animations aren't driven by the `animation-*` properties,
+            // so they use the duration of their state, and have no timing
+            // function between keyframes unless a keyframe declares one.
+            let (total_duration, mut timing_function) = match state.origin {
+                KeyframesAnimationOrigin::Css => {
+                    let maybe_index = style
+                        .get_box()
+                        .animation_name_iter()
+                        .position(|animation_name| Some(name) == animation_name.as_atom());
+
+                    let index = match maybe_index {
+                        Some(index) => index,
+                        None => return AnimationUpdate::AnimationCanceled,
+                    };
+
+                    let box_style = style.get_box();
+                    (
+                        box_style.animation_duration_mod(index).seconds() as f64,
+                        box_style.animation_timing_function_mod(index),
+                    )
+                },
+                KeyframesAnimationOrigin::Script(..) => (
+                    duration,
+                    GenericTimingFunction::Keyword(TimingKeyword::Linear),
+                ),
             };
-
-            let total_duration = style.get_box().animation_duration_mod(index).seconds() as f64;
             if total_duration == 0. {
                 return AnimationUpdate::AnimationCanceled;
             }
@@ -715,6 +994,13 @@ where
                 total_progress = 1.;
             }
 
+            // The easing of a script animation effect transforms the progress
+            // of the whole iteration.
+            if let KeyframesAnimationOrigin::Script(ref easing) = state.origin {
+                let epsilon = 1. / (200. * total_duration);
+                total_progress = timing_function_output(easing, total_progress, epsilon);
+            }
+
             // Get the target and the last keyframe position.
             let last_keyframe_position;
             let target_keyframe_position;
@@ -775,7 +1061,16 @@ where
                 },
                 _ => unreachable!(),
             };
-            let relative_progress = (now - last_keyframe_ended_at) / relative_duration;
+            let relative_progress = match state.origin {
+                KeyframesAnimationOrigin::Css => (now - last_keyframe_ended_at) / relative_duration,
+                // The eased progress of script animations doesn't map linearly
+                // to time, so interpolate in progress space instead.
+                KeyframesAnimationOrigin::Script(..) => {
+                    let last_keyframe_offset =
+                        (last_keyframe_ended_at - state.started_at) / total_duration;
+                    (total_progress - last_keyframe_offset) / relative_timespan as f64
+                },
+            };
 
             // TODO: How could we optimise it? Is it such a big deal?
             let from_style = compute_style_for_animation_step::<E>(
@@ -788,7 +1083,6 @@ where
 
             // NB: The spec says that the timing function can be overwritten
             // from the keyframe style.
-            let mut timing_function = style.get_box().animation_timing_function_mod(index);
             if last_keyframe.declared_timing_function {
                 // NB: animation_timing_function can never be empty, always has
                 // at least the default value (`ease`).
@@ -836,6 +1130,25 @@ where
                 }
             }
 
+            // Custom properties animate discretely unless they're registered
+            // with a syntax that can be interpolated.
+            for custom_property in animation.custom_properties_changed.iter() {
+                let animation = PropertyAnimation::from_custom_property(
+                    context,
+                    custom_property,
+                    timing_function,
+                    Time::from_seconds(relative_duration as f32),
+                    &from_style,
+                    &target_style,
+                    /* interpolated_only = */ false,
+                );
+
+                if let Some(property_animation) = animation {
+                    debug!("update_style_for_animation: {:?}", property_animation);
+                    property_animation.update(Arc::make_mut(&mut new_style), relative_progress);
+                }
+            }
+
             debug!(
                 "update_style_for_animation: got style change in animation \"{}\"",
                 name
@@ -7,6 +7,7 @@
 use crate::properties::PropertyDeclarationBlock;
 use crate::rule_tree::{CascadeLevel, ShadowCascadeOrder, StyleSource};
 use crate::shared_lock::Locked;
+use crate::stylesheets::layer_rule::LayerOrder;
 use servo_arc::Arc;
 use smallvec::SmallVec;
 use std::fmt::{self, Debug};
@@ -107,6 +108,8 @@ pub struct ApplicableDeclarationBlock {
     bits: ApplicableDeclarationBits,
     /// The specificity of the selector this block is represented by.
     pub specificity: u32,
+    /// The order of the cascade layer this block is in.
+    pub layer_order: LayerOrder,
 }
 
 impl ApplicableDeclarationBlock {
@@ -121,6 +124,7 @@ impl ApplicableDeclarationBlock {
             source: StyleSource::from_declarations(declarations),
             bits: ApplicableDeclarationBits::new(0, level, 0),
             specificity: 0,
+            layer_order: LayerOrder::root(),
         }
     }
 
@@ -132,11 +136,13 @@ impl ApplicableDeclarationBlock {
         level: CascadeLevel,
         specificity: u32,
         shadow_cascade_order: ShadowCascadeOrder,
+        layer_order: LayerOrder,
     ) -> Self {
         ApplicableDeclarationBlock {
             source,
             bits: ApplicableDeclarationBits::new(order, level, shadow_cascade_order),
             specificity,
+            layer_order,
         }
     }
 
@@ -155,9 +161,9 @@ impl ApplicableDeclarationBlock {
     /// Convenience method to consume self and return the right thing for the
     /// rule tree to iterate over.
     #[inline]
-    pub fn for_rule_tree(self) -> (StyleSource, CascadeLevel, ShadowCascadeOrder) {
+    pub fn for_rule_tree(self) -> (StyleSource, CascadeLevel, ShadowCascadeOrder, LayerOrder) {
         let level = self.level();
         let cascade_order = self.bits.shadow_cascade_order();
-        (self.source, level, cascade_order)
+        (self.source, level, cascade_order, self.layer_order)
     }
 }
@@ -8,6 +8,7 @@
 
 use crate::hash::map::Entry;
 use crate::properties::{CSSWideKeyword, CustomDeclaration, CustomDeclarationValue};
+use crate::properties_and_values::CustomPropertyRegistry;
 use crate::selector_map::{PrecomputedHashMap, PrecomputedHashSet, PrecomputedHasher};
 use crate::stylesheets::{Origin, PerOrigin};
 use crate::Atom;
@@ -495,6 +496,7 @@ pub struct CustomPropertiesBuilder<'a> {
     may_have_cycles: bool,
     custom_properties: Option<CustomPropertiesMap>,
     inherited: Option<&'a Arc<CustomPropertiesMap>>,
+    registry: &'a CustomPropertyRegistry,
     environment: &'a CssEnvironment,
 }
 
@@ -502,6 +504,7 @@ impl<'a> CustomPropertiesBuilder<'a> {
     /// Create a new builder, inheriting from a given custom properties map.
     pub fn new(
         inherited: Option<&'a Arc<CustomPropertiesMap>>,
+        registry: &'a CustomPropertyRegistry,
         environment: &'a CssEnvironment,
     ) -> Self {
         Self {
@@ -510,6 +513,7 @@ impl<'a> CustomPropertiesBuilder<'a> {
             may_have_cycles: false,
             custom_properties: None,
             inherited,
+            registry,
             environment,
         }
     }
@@ -572,22 +576,33 @@ impl<'a> CustomPropertiesBuilder<'a> {
                         self.reverted.borrow_mut_for_origin(&origin).insert(name);
                     }
                 },
-                CSSWideKeyword::Initial => {
+                // The initial value of registered properties is put back in
+                // `build`.
+                CSSWideKeyword::Initial | CSSWideKeyword::Unset => {
                     map.remove(name);
                 },
                 // handled in value_may_affect_style
-                CSSWideKeyword::Unset | CSSWideKeyword::Inherit => unreachable!(),
+                CSSWideKeyword::Inherit => unreachable!(),
             },
         }
     }
 
     fn value_may_affect_style(&self, name: &Name, value: &CustomDeclarationValue) -> bool {
         match *value {
+            CustomDeclarationValue::CSSWideKeyword(CSSWideKeyword::Unset)
+                if self.registry.get(name).map_or(false, |r| !r.inherits) =>
+            {
+                // 'unset' means 'initial' for registered properties that
+                // don't inherit.
+            },
             CustomDeclarationValue::CSSWideKeyword(CSSWideKeyword::Unset) |
             CustomDeclarationValue::CSSWideKeyword(CSSWideKeyword::Inherit) => {
                 // Custom properties are inherited by default. So
                 // explicit 'inherit' or 'unset' means we can just use
                 // any existing value in the inherited CustomPropertiesMap.
+                //
+                // Since the property has been seen, registered properties
+                // that don't inherit won't be reset in `build` either.
                 return false;
             },
             _ => {},
@@ -618,6 +633,46 @@ impl<'a> CustomPropertiesBuilder<'a> {
         true
     }
 
+    /// Gives their initial value to the registered properties that don't
+    /// inherit and haven't been specified, and to the ones that have no value
+    /// at all.
+    fn apply_registered_initial_values(&mut self) {
+        let registry = self.registry;
+        for registration in registry.iter() {
+            let name = &registration.name;
+            let reset = !registration.inherits && !self.seen.contains(&name);
+            let current_value = match self.custom_properties {
+                Some(ref map) => map.get(name),
+                None => self.inherited.and_then(|map| map.get(name)),
+            };
+            if !reset && current_value.is_some() {
+                continue;
+            }
+
+            let initial_value = registration.initial_value.as_ref();
+            if current_value == initial_value {
+                continue;
+            }
+
+            if self.custom_properties.is_none() {
+                self.custom_properties = Some(match self.inherited {
+                    Some(inherited) => (**inherited).clone(),
+                    None => CustomPropertiesMap::default(),
+                });
+            }
+
+            let map = self.custom_properties.as_mut().unwrap();
+            match initial_value {
+                Some(initial_value) => {
+                    map.insert(name.clone(), initial_value.clone());
+                },
+                None => {
+                    map.remove(name);
+                },
+            }
+        }
+    }
+
     /// Returns the final map of applicable custom properties.
     ///
     /// If there was any specified property, we've created a new map and now we
@@ -625,6 +680,8 @@ impl<'a> CustomPropertiesBuilder<'a> {
     ///
     /// Otherwise, just use the inherited custom properties map.
     pub fn build(mut self) -> Option<Arc<CustomPropertiesMap>> {
+        self.apply_registered_initial_values();
+
         let mut map = match self.custom_properties.take() {
             Some(m) => m,
             None => return self.inherited.cloned(),
@@ -964,7 +1021,7 @@ fn substitute_block<'i>(
                     Ok(())
                 })?;
                 set_position_at_next_iteration = true
-            }
+            },
             Token::Function(_) |
             Token::ParenthesisBlock |
             Token::CurlyBracketBlock |
@@ -22,7 +22,9 @@ use crate::shared_lock::Locked;
 use crate::stylist::CascadeData;
 use crate::traversal_flags::TraversalFlags;
 use crate::{Atom, LocalName, Namespace, WeakAtom};
+use app_units::Au;
 use atomic_refcell::{AtomicRef, AtomicRefCell, AtomicRefMut};
+use euclid::default::Size2D;
 use selectors::matching::{ElementSelectorFlags, QuirksMode, VisitedHandlingMode};
 use selectors::sink::Push;
 use selectors::Element as SelectorsElement;
@@ -718,6 +720,14 @@ pub trait TElement:
         self.get_data().map(|x| x.borrow_mut())
     }
 
+    /// Returns the size of the content box of this element as of the last
+    /// layout, if it is a query container that has already been laid out.
+    ///
+    /// This is used to evaluate `@container` rules.
+    fn query_container_size(&self) -> Option<Size2D<Au>> {
+        None
+    }
+
     /// Whether we should skip any root- or item-based display property
     /// blockification on this element.  (This function exists so that Gecko
     /// native anonymous content can opt out of this style fixup.)
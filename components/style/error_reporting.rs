@@ -40,6 +40,8 @@ pub enum ContextualParseError<'a> {
     UnsupportedViewportDescriptorDeclaration(&'a str, ParseError<'a>),
     /// A counter style descriptor declaration was not recognized.
     UnsupportedCounterStyleDescriptorDeclaration(&'a str, ParseError<'a>),
+    /// A property descriptor declaration was not recognized.
+    UnsupportedPropertyDescriptor(&'a str, ParseError<'a>),
     /// A counter style rule had no symbols.
     InvalidCounterStyleWithoutSymbols(String),
     /// A counter style rule had less than two symbols.
@@ -183,6 +185,14 @@ impl<'a> fmt::Display for ContextualParseError<'a> {
                 )?;
                 parse_error_to_str(err, f)
             },
+            ContextualParseError::UnsupportedPropertyDescriptor(decl, ref err) => {
+                write!(
+                    f,
+                    "Unsupported @property descriptor declaration: '{}', ",
+                    decl
+                )?;
+                parse_error_to_str(err, f)
+            },
             ContextualParseError::InvalidCounterStyleWithoutSymbols(ref system) => write!(
                 f,
                 "Invalid @counter-style rule: 'system: {}' without 'symbols'",
@@ -323,13 +323,13 @@ pub struct FontFace<'a>(&'a FontFaceRuleData);
 pub struct EffectiveSources(Vec<Source>);
 
 #[cfg(feature = "servo")]
-impl<'a> FontFace<'a> {
-    /// Returns the list of effective sources for that font-face, that is the
+impl EffectiveSources {
+    /// Returns the effective sources out of a list of sources, that is the
     /// sources which don't list any format hint, or the ones which list at
     /// least "truetype" or "opentype".
-    pub fn effective_sources(&self) -> EffectiveSources {
+    pub fn from_sources(sources: &[Source]) -> Self {
         EffectiveSources(
-            self.sources()
+            sources
                 .iter()
                 .rev()
                 .filter(|source| {
@@ -352,6 +352,14 @@ impl<'a> FontFace<'a> {
     }
 }
 
+#[cfg(feature = "servo")]
+impl<'a> FontFace<'a> {
+    /// Returns the list of effective sources for that font-face.
+    pub fn effective_sources(&self) -> EffectiveSources {
+        EffectiveSources::from_sources(self.sources())
+    }
+}
+
 #[cfg(feature = "servo")]
 impl Iterator for EffectiveSources {
     type Item = Source;
@@ -442,10 +442,34 @@ impl StylesheetInvalidationSet {
                     }
                 }
             },
-            Document(..) | Namespace(..) | Import(..) | Media(..) | Supports(..) => {
+            Document(..) | Namespace(..) | Media(..) | Supports(..) | Container(..) => {
                 // Do nothing, relevant nested rules are visited as part of the
                 // iteration.
             },
+            Import(ref lock) => {
+                if lock.read_with(guard).layer.is_some() {
+                    debug!(
+                        " > Found layered @import rule, marking the whole tree \
+                         invalid."
+                    );
+                    // The layer may change the order of existing layers.
+                    self.fully_invalid = true;
+                }
+                // Otherwise, do nothing, relevant nested rules are visited as
+                // part of the iteration.
+            },
+            LayerBlock(..) | LayerStatement(..) => {
+                debug!(" > Found @layer rule, marking the whole tree invalid.");
+                // Layer rules may change the order of existing layers, and
+                // hence the cascade order of rules elsewhere.
+                self.fully_invalid = true;
+            },
+            Property(..) => {
+                debug!(" > Found @property rule, marking the whole tree invalid.");
+                // Registering a custom property changes how its values, and
+                // the values that reference it, are computed.
+                self.fully_invalid = true;
+            },
             FontFace(..) => {
                 // Do nothing, @font-face doesn't affect computed style
                 // information. We'll restyle when the font face loads, if
@@ -464,11 +464,11 @@ trait PrivateMatchMethods: TElement {
         // to its old value if it did trigger a transition.
         if let Some(ref values) = *old_values {
             animation::start_transitions_if_applicable(
+                shared_context,
                 new_animations_sender,
                 this_opaque,
                 &values,
                 new_values,
-                &shared_context.timer,
                 &possibly_expired_animations,
             );
         }
@@ -618,6 +618,7 @@ trait PrivateMatchMethods: TElement {
             return;
         }
 
+        let cascade_style = style.clone();
         let mut all_running_animations = context.running_animations.write();
         for mut running_animation in all_running_animations.get_mut(&this_opaque).unwrap() {
             if let Animation::Transition(_, _, ref frame) = *running_animation {
@@ -625,6 +626,14 @@ trait PrivateMatchMethods: TElement {
                 continue;
             }
 
+            // Script animations are created without knowing the style of
+            // their element, so keep the style they start from up to date.
+            if let Animation::Keyframes(_, _, _, ref mut state) = *running_animation {
+                if let animation::KeyframesAnimationOrigin::Script(..) = state.origin {
+                    state.cascade_style = cascade_style.clone();
+                }
+            }
+
             let update = animation::update_style_for_animation::<Self>(
                 context,
                 &mut running_animation,
@@ -5,11 +5,14 @@
 //! A media query condition:
 //!
 //! https://drafts.csswg.org/mediaqueries-4/#typedef-media-condition
+//!
+//! The same syntax is shared with container query conditions.
 
 use super::{Device, MediaFeatureExpression};
 use crate::context::QuirksMode;
 use crate::parser::ParserContext;
 use cssparser::{Parser, Token};
+use malloc_size_of::{MallocSizeOf, MallocSizeOfOps};
 use std::fmt::{self, Write};
 use style_traits::{CssWriter, ParseError, StyleParseErrorKind, ToCss};
 
@@ -28,38 +31,76 @@ enum AllowOr {
     No,
 }
 
-/// Represents a media condition.
-#[derive(Clone, Debug, MallocSizeOf, PartialEq, ToShmem)]
-pub enum MediaCondition {
-    /// A simple media feature expression, implicitly parenthesized.
-    Feature(MediaFeatureExpression),
+/// A feature expression that can be found in a query condition, like a media
+/// feature or a container size feature.
+pub trait QueryFeature: Sized {
+    /// Parses a feature expression, whose opening parenthesis has already
+    /// been consumed.
+    fn parse_in_parenthesis_block<'i, 't>(
+        context: &ParserContext,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i>>;
+}
+
+/// Represents a query condition, generic over the kind of feature expressions
+/// it contains.
+///
+/// https://drafts.csswg.org/mediaqueries-4/#typedef-media-condition
+/// https://drafts.csswg.org/css-contain-3/#typedef-container-condition
+#[derive(Clone, Debug, PartialEq, ToShmem)]
+pub enum QueryCondition<F> {
+    /// A simple feature expression, implicitly parenthesized.
+    Feature(F),
     /// A negation of a condition.
-    Not(Box<MediaCondition>),
+    Not(Box<QueryCondition<F>>),
     /// A set of joint operations.
-    Operation(Box<[MediaCondition]>, Operator),
+    Operation(Box<[QueryCondition<F>]>, Operator),
     /// A condition wrapped in parenthesis.
-    InParens(Box<MediaCondition>),
+    InParens(Box<QueryCondition<F>>),
+}
+
+/// Represents a media condition.
+pub type MediaCondition = QueryCondition<MediaFeatureExpression>;
+
+impl QueryFeature for MediaFeatureExpression {
+    fn parse_in_parenthesis_block<'i, 't>(
+        context: &ParserContext,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i>> {
+        MediaFeatureExpression::parse_in_parenthesis_block(context, input)
+    }
+}
+
+// Implemented by hand since the derived bounds would be recursive.
+impl<F: MallocSizeOf> MallocSizeOf for QueryCondition<F> {
+    fn size_of(&self, ops: &mut MallocSizeOfOps) -> usize {
+        match *self {
+            QueryCondition::Feature(ref f) => f.size_of(ops),
+            QueryCondition::Not(ref c) | QueryCondition::InParens(ref c) => c.size_of(ops),
+            QueryCondition::Operation(ref list, _) => list.size_of(ops),
+        }
+    }
 }
 
-impl ToCss for MediaCondition {
+impl<F: ToCss> ToCss for QueryCondition<F> {
     fn to_css<W>(&self, dest: &mut CssWriter<W>) -> fmt::Result
     where
         W: fmt::Write,
     {
         match *self {
-            // NOTE(emilio): MediaFeatureExpression already includes the
+            // NOTE(emilio): Feature expressions already include the
             // parenthesis.
-            MediaCondition::Feature(ref f) => f.to_css(dest),
-            MediaCondition::Not(ref c) => {
+            QueryCondition::Feature(ref f) => f.to_css(dest),
+            QueryCondition::Not(ref c) => {
                 dest.write_str("not ")?;
                 c.to_css(dest)
             },
-            MediaCondition::InParens(ref c) => {
+            QueryCondition::InParens(ref c) => {
                 dest.write_char('(')?;
                 c.to_css(dest)?;
                 dest.write_char(')')
             },
-            MediaCondition::Operation(ref list, op) => {
+            QueryCondition::Operation(ref list, op) => {
                 let mut iter = list.iter();
                 iter.next().unwrap().to_css(dest)?;
                 for item in iter {
@@ -74,8 +115,8 @@ impl ToCss for MediaCondition {
     }
 }
 
-impl MediaCondition {
-    /// Parse a single media condition.
+impl<F: QueryFeature> QueryCondition<F> {
+    /// Parse a single condition.
     pub fn parse<'i, 't>(
         context: &ParserContext,
         input: &mut Parser<'i, 't>,
@@ -83,7 +124,7 @@ impl MediaCondition {
         Self::parse_internal(context, input, AllowOr::Yes)
     }
 
-    /// Parse a single media condition, disallowing `or` expressions.
+    /// Parse a single condition, disallowing `or` expressions.
     ///
     /// To be used from the legacy media query syntax.
     pub fn parse_disallow_or<'i, 't>(
@@ -109,7 +150,7 @@ impl MediaCondition {
 
         if is_negation {
             let inner_condition = Self::parse_in_parens(context, input)?;
-            return Ok(MediaCondition::Not(Box::new(inner_condition)));
+            return Ok(QueryCondition::Not(Box::new(inner_condition)));
         }
 
         // ParenthesisBlock.
@@ -134,7 +175,7 @@ impl MediaCondition {
 
         loop {
             if input.try(|i| i.expect_ident_matching(delim)).is_err() {
-                return Ok(MediaCondition::Operation(
+                return Ok(QueryCondition::Operation(
                     conditions.into_boxed_slice(),
                     operator,
                 ));
@@ -144,7 +185,7 @@ impl MediaCondition {
         }
     }
 
-    /// Parse a media condition in parentheses.
+    /// Parse a condition in parentheses.
     pub fn parse_in_parens<'i, 't>(
         context: &ParserContext,
         input: &mut Parser<'i, 't>,
@@ -160,26 +201,37 @@ impl MediaCondition {
         input.parse_nested_block(|input| {
             // Base case.
             if let Ok(inner) = input.try(|i| Self::parse(context, i)) {
-                return Ok(MediaCondition::InParens(Box::new(inner)));
+                return Ok(QueryCondition::InParens(Box::new(inner)));
             }
-            let expr = MediaFeatureExpression::parse_in_parenthesis_block(context, input)?;
-            Ok(MediaCondition::Feature(expr))
+            let expr = F::parse_in_parenthesis_block(context, input)?;
+            Ok(QueryCondition::Feature(expr))
         })
     }
 
-    /// Whether this condition matches the device and quirks mode.
-    pub fn matches(&self, device: &Device, quirks_mode: QuirksMode) -> bool {
+    /// Evaluates this condition, using `eval` to evaluate each of the feature
+    /// expressions.
+    pub fn matches_with<E>(&self, eval: &mut E) -> bool
+    where
+        E: FnMut(&F) -> bool,
+    {
         match *self {
-            MediaCondition::Feature(ref f) => f.matches(device, quirks_mode),
-            MediaCondition::InParens(ref c) => c.matches(device, quirks_mode),
-            MediaCondition::Not(ref c) => !c.matches(device, quirks_mode),
-            MediaCondition::Operation(ref conditions, op) => {
+            QueryCondition::Feature(ref f) => eval(f),
+            QueryCondition::InParens(ref c) => c.matches_with(eval),
+            QueryCondition::Not(ref c) => !c.matches_with(eval),
+            QueryCondition::Operation(ref conditions, op) => {
                 let mut iter = conditions.iter();
                 match op {
-                    Operator::And => iter.all(|c| c.matches(device, quirks_mode)),
-                    Operator::Or => iter.any(|c| c.matches(device, quirks_mode)),
+                    Operator::And => iter.all(|c| c.matches_with(eval)),
+                    Operator::Or => iter.any(|c| c.matches_with(eval)),
                 }
             },
         }
     }
 }
+
+impl MediaCondition {
+    /// Whether this condition matches the device and quirks mode.
+    pub fn matches(&self, device: &Device, quirks_mode: QuirksMode) -> bool {
+        self.matches_with(&mut |f: &MediaFeatureExpression| f.matches(device, quirks_mode))
+    }
+}
@@ -13,7 +13,7 @@ mod media_query;
 pub mod media_feature;
 pub mod media_feature_expression;
 
-pub use self::media_condition::MediaCondition;
+pub use self::media_condition::{MediaCondition, QueryCondition, QueryFeature};
 pub use self::media_feature_expression::MediaFeatureExpression;
 pub use self::media_list::MediaList;
 pub use self::media_query::{MediaQuery, MediaQueryType, MediaType, Qualifier};
@@ -5,7 +5,7 @@
 //! The main cascading algorithm of the style system.
 
 use crate::context::QuirksMode;
-use crate::custom_properties::CustomPropertiesBuilder;
+use crate::custom_properties::{CustomPropertiesBuilder, CustomPropertiesMap};
 use crate::dom::TElement;
 use crate::font_metrics::FontMetricsProvider;
 use crate::logical_geometry::WritingMode;
@@ -14,6 +14,7 @@ use crate::properties::{ComputedValues, StyleBuilder};
 use crate::properties::{LonghandId, LonghandIdSet, CSSWideKeyword};
 use crate::properties::{PropertyDeclaration, PropertyDeclarationId, DeclarationImportanceIterator};
 use crate::properties::CASCADE_PROPERTY;
+use crate::properties_and_values::CustomPropertyRegistry;
 use crate::rule_cache::{RuleCache, RuleCacheConditions};
 use crate::rule_tree::{CascadeLevel, StrongRuleNode};
 use crate::selector_parser::PseudoElement;
@@ -83,6 +84,7 @@ pub fn cascade<E>(
     layout_parent_style: Option<&ComputedValues>,
     visited_rules: Option<&StrongRuleNode>,
     font_metrics_provider: &dyn FontMetricsProvider,
+    registry: &CustomPropertyRegistry,
     quirks_mode: QuirksMode,
     rule_cache: Option<&RuleCache>,
     rule_cache_conditions: &mut RuleCacheConditions,
@@ -101,6 +103,7 @@ where
         layout_parent_style,
         font_metrics_provider,
         CascadeMode::Unvisited { visited_rules },
+        registry,
         quirks_mode,
         rule_cache,
         rule_cache_conditions,
@@ -118,6 +121,7 @@ fn cascade_rules<E>(
     layout_parent_style: Option<&ComputedValues>,
     font_metrics_provider: &dyn FontMetricsProvider,
     cascade_mode: CascadeMode,
+    registry: &CustomPropertyRegistry,
     quirks_mode: QuirksMode,
     rule_cache: Option<&RuleCache>,
     rule_cache_conditions: &mut RuleCacheConditions,
@@ -179,6 +183,7 @@ where
         layout_parent_style,
         font_metrics_provider,
         cascade_mode,
+        registry,
         quirks_mode,
         rule_cache,
         rule_cache_conditions,
@@ -215,6 +220,7 @@ pub fn apply_declarations<'a, E, F, I>(
     layout_parent_style: Option<&ComputedValues>,
     font_metrics_provider: &dyn FontMetricsProvider,
     cascade_mode: CascadeMode,
+    registry: &CustomPropertyRegistry,
     quirks_mode: QuirksMode,
     rule_cache: Option<&RuleCache>,
     rule_cache_conditions: &mut RuleCacheConditions,
@@ -246,6 +252,7 @@ where
     let custom_properties = {
         let mut builder = CustomPropertiesBuilder::new(
             inherited_style.custom_properties(),
+            registry,
             device.environment(),
         );
 
@@ -287,11 +294,14 @@ where
         cascade
             .apply_properties::<EarlyProperties, _>(ApplyResetProperties::Yes, declarations.iter().cloned());
 
+        cascade.compute_registered_custom_properties(registry, inherited_style.custom_properties());
+
         cascade.compute_visited_style_if_needed(
             element,
             parent_style,
             parent_style_ignoring_first_line,
             layout_parent_style,
+            registry,
             guards,
         );
 
@@ -576,12 +586,92 @@ impl<'a, 'b: 'a> Cascade<'a, 'b> {
         self.context.builder.writing_mode = writing_mode;
     }
 
+    /// Computes the values of the registered custom properties against their
+    /// syntax, now that the font properties that relative lengths depend on
+    /// are known.
+    ///
+    /// Values that don't match the syntax are invalid at computed-value time,
+    /// and behave like `unset`.
+    ///
+    /// https://drafts.css-houdini.org/css-properties-values-api/#calculation-of-computed-values
+    fn compute_registered_custom_properties(
+        &mut self,
+        registry: &CustomPropertyRegistry,
+        inherited: Option<&Arc<CustomPropertiesMap>>,
+    ) {
+        if registry.is_empty() {
+            return;
+        }
+
+        let custom_properties = match self.context.builder.custom_properties {
+            Some(ref map) => map.clone(),
+            None => return,
+        };
+
+        // If nothing was specified, the inherited values are already computed.
+        if inherited.map_or(false, |inherited| {
+            Arc::ptr_eq(inherited, &custom_properties)
+        }) {
+            return;
+        }
+
+        let mut computed_map = None;
+        for (name, value) in custom_properties.iter() {
+            let registration = match registry.get(name) {
+                Some(registration) => registration,
+                None => continue,
+            };
+            if registration.syntax.is_universal() {
+                continue;
+            }
+            let inherited_value = inherited.and_then(|map| map.get(name));
+            if inherited_value.map_or(false, |inherited| Arc::ptr_eq(inherited, value)) {
+                continue;
+            }
+
+            let computed = registration
+                .compute(value, self.context)
+                .ok()
+                .and_then(|computed| computed.to_variable_value());
+            let computed = match computed {
+                Some(computed) => Some(computed),
+                None if registration.inherits && inherited_value.is_some() => {
+                    inherited_value.cloned()
+                },
+                None => registration
+                    .initial_value
+                    .as_ref()
+                    .and_then(|initial| registration.compute(initial, self.context).ok())
+                    .and_then(|computed| computed.to_variable_value()),
+            };
+
+            if computed.as_ref() == Some(value) {
+                continue;
+            }
+
+            let map = computed_map.get_or_insert_with(|| (*custom_properties).clone());
+            match computed {
+                Some(computed) => {
+                    map.insert(name.clone(), computed);
+                },
+                None => {
+                    map.remove(name);
+                },
+            }
+        }
+
+        if let Some(map) = computed_map {
+            self.context.builder.custom_properties = Some(Arc::new(map));
+        }
+    }
+
     fn compute_visited_style_if_needed<E>(
         &mut self,
         element: Option<E>,
         parent_style: Option<&ComputedValues>,
         parent_style_ignoring_first_line: Option<&ComputedValues>,
         layout_parent_style: Option<&ComputedValues>,
+        registry: &CustomPropertyRegistry,
         guards: &StylesheetGuards,
     ) where
         E: TElement,
@@ -622,6 +712,7 @@ impl<'a, 'b: 'a> Cascade<'a, 'b> {
             visited_parent!(layout_parent_style),
             self.context.font_metrics_provider,
             CascadeMode::Visited { writing_mode },
+            registry,
             self.context.quirks_mode,
             // The rule cache doesn't care about caching :visited
             // styles, we cache the unvisited style instead. We still do
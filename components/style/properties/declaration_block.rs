@@ -12,13 +12,14 @@ use crate::custom_properties::{CssEnvironment, CustomPropertiesBuilder};
 use crate::error_reporting::{ContextualParseError, ParseErrorReporter};
 use crate::parser::ParserContext;
 use crate::properties::animated_properties::{AnimationValue, AnimationValueMap};
+use crate::properties_and_values::CustomPropertyRegistry;
 use crate::selector_parser::SelectorImpl;
 use crate::shared_lock::Locked;
 use crate::str::{CssString, CssStringBorrow, CssStringWriter};
 use crate::stylesheets::{CssRuleType, Origin, UrlExtraData};
 use crate::values::computed::Context;
 use cssparser::{parse_important, CowRcStr, DeclarationListParser, ParserInput};
-use cssparser::{AtRuleParser, DeclarationParser, Delimiter, ParseErrorKind, Parser};
+use cssparser::{AtRuleParser, DeclarationParser, Delimiter, ParseErrorKind, Parser, Token};
 use itertools::Itertools;
 use selectors::SelectorList;
 use smallbitvec::{self, SmallBitVec};
@@ -885,7 +886,10 @@ impl PropertyDeclarationBlock {
         inherited_custom_properties: Option<&Arc<crate::custom_properties::CustomPropertiesMap>>,
         environment: &CssEnvironment,
     ) -> Option<Arc<crate::custom_properties::CustomPropertiesMap>> {
-        let mut builder = CustomPropertiesBuilder::new(inherited_custom_properties, environment);
+        // Registered custom properties are not taken into account here.
+        let registry = CustomPropertyRegistry::default();
+        let mut builder =
+            CustomPropertiesBuilder::new(inherited_custom_properties, &registry, environment);
 
         for declaration in self.normal_declaration_iter() {
             if let PropertyDeclaration::Custom(ref declaration) = *declaration {
@@ -1476,3 +1480,95 @@ pub fn parse_property_declaration_list(
 
     block
 }
+
+/// Parse the contents of a style rule, which may contain nested rules as well
+/// as declarations, and return a property declaration block.
+///
+/// `parse_nested_rule` is called wherever there isn't a valid declaration, and
+/// returns whether it consumed a rule (even an invalid one). If it didn't, the
+/// input is skipped up to the next semicolon.
+///
+/// https://drafts.csswg.org/css-nesting/#syntax
+pub fn parse_property_declaration_list_with_nested_rules<'i, 't, F>(
+    context: &ParserContext,
+    input: &mut Parser<'i, 't>,
+    selectors: Option<&SelectorList<SelectorImpl>>,
+    mut parse_nested_rule: F,
+) -> PropertyDeclarationBlock
+where
+    F: FnMut(&mut Parser<'i, 't>) -> bool,
+{
+    let mut declarations = SourcePropertyDeclaration::new();
+    let mut block = PropertyDeclarationBlock::new();
+    let mut parser = PropertyDeclarationParser {
+        context,
+        last_parsed_property_id: None,
+        declarations: &mut declarations,
+    };
+    let mut errors = SmallParseErrorVec::new();
+    loop {
+        let start = input.state();
+        let name = match input.next_including_whitespace_and_comments() {
+            Err(..) => break,
+            Ok(&Token::WhiteSpace(..)) | Ok(&Token::Comment(..)) | Ok(&Token::Semicolon) => {
+                continue
+            },
+            Ok(&Token::Ident(ref name)) => Some(name.clone()),
+            Ok(..) => None,
+        };
+
+        let error = match name {
+            Some(name) => {
+                let result = input.parse_until_after(Delimiter::Semicolon, |input| {
+                    input.expect_colon()?;
+                    parser.parse_value(name, input)
+                });
+                match result {
+                    Ok(importance) => {
+                        block.extend(parser.declarations.drain(), importance);
+                        continue;
+                    },
+                    Err(error) => {
+                        parser.declarations.clear();
+
+                        // Something like `div:hover { .. }` starts like a
+                        // declaration, but is a nested rule.
+                        let end = input.state();
+                        input.reset(&start);
+                        if parse_nested_rule(input) {
+                            continue;
+                        }
+                        input.reset(&end);
+                        error
+                    },
+                }
+            },
+            None => {
+                input.reset(&start);
+                if parse_nested_rule(input) {
+                    continue;
+                }
+                parser.last_parsed_property_id = None;
+                let result: Result<(), ParseError> = input
+                    .parse_until_after(Delimiter::Semicolon, |input| {
+                        Err(input.new_error_for_next_token())
+                    });
+                match result {
+                    Ok(()) => continue,
+                    Err(error) => error,
+                }
+            },
+        };
+
+        if context.error_reporting_enabled() {
+            let property = parser.last_parsed_property_id.take();
+            errors.push((error, input.slice_from(start.position()), property));
+        }
+    }
+
+    if !errors.is_empty() {
+        report_css_errors(context, &block, selectors, &mut errors)
+    }
+
+    block
+}
@@ -453,7 +453,8 @@ ${helpers.predefined_type(
     "scroll-snap-align",
     "ScrollSnapAlign",
     "computed::ScrollSnapAlign::none()",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     gecko_pref="layout.css.scroll-snap-v1.enabled",
     spec="https://drafts.csswg.org/css-scroll-snap-1/#scroll-snap-align",
     animation_value_type="discrete",
@@ -463,7 +464,8 @@ ${helpers.predefined_type(
     "scroll-snap-type",
     "ScrollSnapType",
     "computed::ScrollSnapType::none()",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     spec="https://drafts.csswg.org/css-scroll-snap-1/#scroll-snap-type",
     animation_value_type="discrete",
 )}
@@ -473,7 +475,8 @@ ${helpers.predefined_type(
         "overscroll-behavior-" + axis,
         "OverscrollBehavior",
         "computed::OverscrollBehavior::Auto",
-        engines="gecko",
+        engines="gecko servo-2013 servo-2020",
+        servo_2020_pref="layout.2020.unimplemented",
         needs_context=False,
         gecko_pref="layout.css.overscroll-behavior.enabled",
         spec="https://wicg.github.io/overscroll-behavior/#overscroll-behavior-properties",
@@ -612,12 +615,31 @@ ${helpers.predefined_type(
     "contain",
     "Contain",
     "specified::Contain::empty()",
-    engines="gecko",
+    engines="gecko servo-2013",
     animation_value_type="none",
     flags="CREATES_STACKING_CONTEXT FIXPOS_CB",
     gecko_pref="layout.css.contain.enabled",
     spec="https://drafts.csswg.org/css-contain/#contain-property",
-    enabled_in="chrome",
+    enabled_in="chrome" if engine == "gecko" else "content",
+    servo_restyle_damage="rebuild_and_reflow",
+)}
+
+${helpers.single_keyword(
+    "container-type",
+    "normal size inline-size",
+    engines="servo-2013",
+    animation_value_type="none",
+    spec="https://drafts.csswg.org/css-contain-3/#container-type",
+    servo_restyle_damage="rebuild_and_reflow",
+)}
+
+${helpers.predefined_type(
+    "container-name",
+    "ContainerName",
+    "computed::ContainerName::none()",
+    engines="servo-2013",
+    animation_value_type="none",
+    spec="https://drafts.csswg.org/css-contain-3/#container-name",
 )}
 
 // Non-standard
@@ -695,7 +717,7 @@ ${helpers.predefined_type(
     "touch-action",
     "TouchAction",
     "computed::TouchAction::auto()",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
     gecko_pref="layout.css.touch_action.enabled",
     animation_value_type="discrete",
     spec="https://compat.spec.whatwg.org/#touch-action",
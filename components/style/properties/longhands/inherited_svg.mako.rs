@@ -49,9 +49,11 @@ ${helpers.predefined_type(
     "fill",
     "SVGPaint",
     "crate::values::computed::SVGPaint::black()",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     animation_value_type="IntermediateSVGPaint",
     boxed=True,
+    servo_restyle_damage="rebuild_and_reflow",
     spec="https://www.w3.org/TR/SVG2/painting.html#SpecifyingFillPaint",
 )}
 
@@ -59,8 +61,10 @@ ${helpers.predefined_type(
     "fill-opacity",
     "SVGOpacity",
     "Default::default()",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     animation_value_type="ComputedValue",
+    servo_restyle_damage="rebuild_and_reflow",
     spec="https://svgwg.org/svg2-draft/painting.html#FillOpacity",
 )}
 
@@ -68,9 +72,11 @@ ${helpers.predefined_type(
     "fill-rule",
     "FillRule",
     "Default::default()",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     needs_context=False,
     animation_value_type="discrete",
+    servo_restyle_damage="rebuild_and_reflow",
     spec="https://www.w3.org/TR/SVG11/painting.html#FillRuleProperty",
 )}
 
@@ -86,9 +92,11 @@ ${helpers.predefined_type(
     "stroke",
     "SVGPaint",
     "Default::default()",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     animation_value_type="IntermediateSVGPaint",
     boxed=True,
+    servo_restyle_damage="rebuild_and_reflow",
     spec="https://www.w3.org/TR/SVG2/painting.html#SpecifyingStrokePaint",
 )}
 
@@ -96,24 +104,30 @@ ${helpers.predefined_type(
     "stroke-width",
     "SVGWidth",
     "computed::SVGWidth::one()",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     animation_value_type="crate::values::computed::SVGWidth",
+    servo_restyle_damage="rebuild_and_reflow",
     spec="https://www.w3.org/TR/SVG2/painting.html#StrokeWidth",
 )}
 
 ${helpers.single_keyword(
     "stroke-linecap",
     "butt round square",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     animation_value_type="discrete",
+    servo_restyle_damage="rebuild_and_reflow",
     spec="https://www.w3.org/TR/SVG11/painting.html#StrokeLinecapProperty",
 )}
 
 ${helpers.single_keyword(
     "stroke-linejoin",
     "miter round bevel",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     animation_value_type="discrete",
+    servo_restyle_damage="rebuild_and_reflow",
     spec="https://www.w3.org/TR/SVG11/painting.html#StrokeLinejoinProperty",
 )}
 
@@ -121,8 +135,10 @@ ${helpers.predefined_type(
     "stroke-miterlimit",
     "NonNegativeNumber",
     "From::from(4.0)",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     animation_value_type="crate::values::computed::NonNegativeNumber",
+    servo_restyle_damage="rebuild_and_reflow",
     spec="https://www.w3.org/TR/SVG2/painting.html#StrokeMiterlimitProperty",
 )}
 
@@ -130,8 +146,10 @@ ${helpers.predefined_type(
     "stroke-opacity",
     "SVGOpacity",
     "Default::default()",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     animation_value_type="ComputedValue",
+    servo_restyle_damage="rebuild_and_reflow",
     spec="https://svgwg.org/svg2-draft/painting.html#StrokeOpacity",
 )}
 
@@ -139,8 +157,10 @@ ${helpers.predefined_type(
     "stroke-dasharray",
     "SVGStrokeDashArray",
     "Default::default()",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     animation_value_type="crate::values::computed::SVGStrokeDashArray",
+    servo_restyle_damage="rebuild_and_reflow",
     spec="https://www.w3.org/TR/SVG2/painting.html#StrokeDashing",
 )}
 
@@ -148,8 +168,10 @@ ${helpers.predefined_type(
     "stroke-dashoffset",
     "SVGLength",
     "computed::SVGLength::zero()",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     animation_value_type="ComputedValue",
+    servo_restyle_damage="rebuild_and_reflow",
     spec="https://www.w3.org/TR/SVG2/painting.html#StrokeDashing",
 )}
 
@@ -158,9 +180,11 @@ ${helpers.predefined_type(
     "clip-rule",
     "FillRule",
     "Default::default()",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     needs_context=False,
     animation_value_type="discrete",
+    servo_restyle_damage="rebuild_and_reflow",
     spec="https://www.w3.org/TR/SVG11/masking.html#ClipRuleProperty",
 )}
 
@@ -168,7 +192,8 @@ ${helpers.predefined_type(
     "marker-start",
     "url::UrlOrNone",
     "computed::url::UrlOrNone::none()",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     animation_value_type="discrete",
     spec="https://www.w3.org/TR/SVG2/painting.html#VertexMarkerProperties",
 )}
@@ -177,7 +202,8 @@ ${helpers.predefined_type(
     "marker-mid",
     "url::UrlOrNone",
     "computed::url::UrlOrNone::none()",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     animation_value_type="discrete",
     spec="https://www.w3.org/TR/SVG2/painting.html#VertexMarkerProperties",
 )}
@@ -186,7 +212,8 @@ ${helpers.predefined_type(
     "marker-end",
     "url::UrlOrNone",
     "computed::url::UrlOrNone::none()",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     animation_value_type="discrete",
     spec="https://www.w3.org/TR/SVG2/painting.html#VertexMarkerProperties",
 )}
@@ -195,7 +222,8 @@ ${helpers.predefined_type(
     "paint-order",
     "SVGPaintOrder",
     "computed::SVGPaintOrder::normal()",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     animation_value_type="discrete",
     spec="https://www.w3.org/TR/SVG2/painting.html#PaintOrder",
 )}
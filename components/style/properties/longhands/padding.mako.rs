@@ -33,7 +33,8 @@
         "scroll-padding-%s" % side[0],
         "NonNegativeLengthPercentageOrAuto",
         "computed::NonNegativeLengthPercentageOrAuto::auto()",
-        engines="gecko",
+        engines="gecko servo-2013 servo-2020",
+        servo_2020_pref="layout.2020.unimplemented",
         gecko_pref="layout.css.scroll-snap-v1.enabled",
         logical=side[1],
         logical_group="scroll-padding",
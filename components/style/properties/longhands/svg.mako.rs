@@ -20,8 +20,10 @@ ${helpers.predefined_type(
     "stop-color",
     "Color",
     "RGBA::new(0, 0, 0, 255).into()",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     animation_value_type="AnimatedRGBA",
+    servo_restyle_damage="rebuild_and_reflow",
     spec="https://www.w3.org/TR/SVGTiny12/painting.html#StopColorProperty",
 )}
 
@@ -29,8 +31,10 @@ ${helpers.predefined_type(
     "stop-opacity",
     "Opacity",
     "1.0",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     animation_value_type="ComputedValue",
+    servo_restyle_damage="rebuild_and_reflow",
     spec="https://svgwg.org/svg2-draft/pservers.html#StopOpacityProperty",
 )}
 
@@ -77,16 +81,19 @@ ${helpers.predefined_type(
     "clip-path",
     "basic_shape::ClippingShape",
     "generics::basic_shape::ShapeSource::None",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     animation_value_type="basic_shape::ClippingShape",
     flags="CREATES_STACKING_CONTEXT",
+    servo_restyle_damage="rebuild_and_reflow",
     spec="https://drafts.fxtf.org/css-masking/#propdef-clip-path",
 )}
 
 ${helpers.single_keyword(
     "mask-mode",
     "match-source alpha luminance",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     gecko_enum_prefix="StyleMaskMode",
     vector=True,
     animation_value_type="discrete",
@@ -97,7 +104,8 @@ ${helpers.predefined_type(
     "mask-repeat",
     "BackgroundRepeat",
     "computed::BackgroundRepeat::repeat()",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     initial_specified_value="specified::BackgroundRepeat::repeat()",
     extra_prefixes="webkit",
     animation_value_type="discrete",
@@ -110,7 +118,8 @@ ${helpers.predefined_type(
         "mask-position-" + axis,
         "position::" + direction + "Position",
         "computed::LengthPercentage::zero()",
-        engines="gecko",
+        engines="gecko servo-2013 servo-2020",
+        servo_2020_pref="layout.2020.unimplemented",
         extra_prefixes="webkit",
         initial_specified_value="specified::PositionComponent::Center",
         spec="https://drafts.fxtf.org/css-masking/#propdef-mask-position",
@@ -123,7 +132,8 @@ ${helpers.predefined_type(
 ${helpers.single_keyword(
     "mask-clip",
     "border-box content-box padding-box",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     extra_gecko_values="fill-box stroke-box view-box no-clip",
     vector=True,
     extra_prefixes="webkit",
@@ -136,7 +146,8 @@ ${helpers.single_keyword(
 ${helpers.single_keyword(
     "mask-origin",
     "border-box content-box padding-box",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     extra_gecko_values="fill-box stroke-box view-box",
     vector=True,
     extra_prefixes="webkit",
@@ -150,7 +161,8 @@ ${helpers.predefined_type(
     "mask-size",
     "background::BackgroundSize",
     "computed::BackgroundSize::auto()",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     initial_specified_value="specified::BackgroundSize::auto()",
     extra_prefixes="webkit",
     spec="https://drafts.fxtf.org/css-masking/#propdef-mask-size",
@@ -162,7 +174,8 @@ ${helpers.predefined_type(
 ${helpers.single_keyword(
     "mask-composite",
     "add subtract intersect exclude",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     vector=True,
     extra_prefixes="webkit",
     animation_value_type="discrete",
@@ -172,7 +185,8 @@ ${helpers.single_keyword(
 ${helpers.predefined_type(
     "mask-image",
     "ImageLayer",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     initial_value="computed::ImageLayer::none()",
     initial_specified_value="specified::ImageLayer::none()",
     parse_method="parse_with_cors_anonymous",
@@ -187,8 +201,10 @@ ${helpers.predefined_type(
     "x",
     "LengthPercentage",
     "computed::LengthPercentage::zero()",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     animation_value_type="ComputedValue",
+    servo_restyle_damage="rebuild_and_reflow",
     spec="https://svgwg.org/svg2-draft/geometry.html#X",
 )}
 
@@ -196,8 +212,10 @@ ${helpers.predefined_type(
     "y",
     "LengthPercentage",
     "computed::LengthPercentage::zero()",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     animation_value_type="ComputedValue",
+    servo_restyle_damage="rebuild_and_reflow",
     spec="https://svgwg.org/svg2-draft/geometry.html#Y",
 )}
 
@@ -205,8 +223,10 @@ ${helpers.predefined_type(
     "cx",
     "LengthPercentage",
     "computed::LengthPercentage::zero()",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     animation_value_type="ComputedValue",
+    servo_restyle_damage="rebuild_and_reflow",
     spec="https://svgwg.org/svg2-draft/geometry.html#CX",
 )}
 
@@ -214,8 +234,10 @@ ${helpers.predefined_type(
     "cy",
     "LengthPercentage",
     "computed::LengthPercentage::zero()",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     animation_value_type="ComputedValue",
+    servo_restyle_damage="rebuild_and_reflow",
     spec="https://svgwg.org/svg2-draft/geometry.html#CY",
 )}
 
@@ -223,8 +245,10 @@ ${helpers.predefined_type(
     "rx",
     "NonNegativeLengthPercentageOrAuto",
     "computed::NonNegativeLengthPercentageOrAuto::auto()",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     animation_value_type="LengthPercentageOrAuto",
+    servo_restyle_damage="rebuild_and_reflow",
     spec="https://svgwg.org/svg2-draft/geometry.html#RX",
 )}
 
@@ -232,8 +256,10 @@ ${helpers.predefined_type(
     "ry",
     "NonNegativeLengthPercentageOrAuto",
     "computed::NonNegativeLengthPercentageOrAuto::auto()",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     animation_value_type="LengthPercentageOrAuto",
+    servo_restyle_damage="rebuild_and_reflow",
     spec="https://svgwg.org/svg2-draft/geometry.html#RY",
 )}
 
@@ -241,7 +267,9 @@ ${helpers.predefined_type(
     "r",
     "NonNegativeLengthPercentage",
     "computed::NonNegativeLengthPercentage::zero()",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     animation_value_type="LengthPercentage",
+    servo_restyle_damage="rebuild_and_reflow",
     spec="https://svgwg.org/svg2-draft/geometry.html#R",
 )}
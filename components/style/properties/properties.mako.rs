@@ -2283,10 +2283,13 @@ impl PropertyDeclaration {
 
     /// Returns true if this property declaration is for one of the animatable
     /// properties.
+    ///
+    /// Custom properties are always animatable, discretely unless they're
+    /// registered with a syntax that can be interpolated.
     pub fn is_animatable(&self) -> bool {
         match self.id() {
             PropertyDeclarationId::Longhand(id) => id.is_animatable(),
-            PropertyDeclarationId::Custom(..) => false,
+            PropertyDeclarationId::Custom(..) => true,
         }
     }
 
@@ -2892,6 +2895,37 @@ impl ComputedValues {
         self.custom_properties.as_ref()
     }
 
+    /// Sets the computed value of a given custom property, or removes it if
+    /// `value` is `None`. Used to animate custom properties.
+    pub fn set_custom_property(
+        &mut self,
+        name: &crate::custom_properties::Name,
+        value: Option<Arc<crate::custom_properties::VariableValue>>,
+    ) {
+        let mut map = match self.custom_properties {
+            Some(ref map) => (**map).clone(),
+            None => {
+                if value.is_none() {
+                    return;
+                }
+                crate::custom_properties::CustomPropertiesMap::default()
+            },
+        };
+        match value {
+            Some(value) => {
+                map.insert(name.clone(), value);
+            },
+            None => {
+                map.remove(name);
+            },
+        }
+        self.custom_properties = if map.is_empty() {
+            None
+        } else {
+            Some(Arc::new(map))
+        };
+    }
+
 % for prop in data.longhands:
     /// Gets the computed value of a given property.
     #[inline(always)]
@@ -3093,6 +3127,31 @@ impl ComputedValuesInner {
         self.get_column().is_multicol()
     }
 
+    % if engine == "servo-2013":
+    /// Whether this style applies size containment in the inline axis, either
+    /// through `contain: size` or by making the element a query container.
+    ///
+    /// https://drafts.csswg.org/css-contain-3/#containment-inline-size
+    #[inline]
+    pub fn has_inline_size_containment(&self) -> bool {
+        use crate::computed_values::container_type::T as ContainerType;
+        let box_style = self.get_box();
+        box_style.contain.contains(computed::Contain::SIZE) ||
+            box_style.container_type != ContainerType::Normal
+    }
+
+    /// Whether this style applies size containment in the block axis.
+    ///
+    /// https://drafts.csswg.org/css-contain-3/#containment-size
+    #[inline]
+    pub fn has_block_size_containment(&self) -> bool {
+        use crate::computed_values::container_type::T as ContainerType;
+        let box_style = self.get_box();
+        box_style.contain.contains(computed::Contain::SIZE) ||
+            box_style.container_type == ContainerType::Size
+    }
+    % endif
+
     /// Get the logical computed inline size.
     #[inline]
     pub fn content_inline_size(&self) -> computed::Size {
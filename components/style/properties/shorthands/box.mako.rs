@@ -312,7 +312,8 @@ ${helpers.two_properties_shorthand(
     "overscroll-behavior-x",
     "overscroll-behavior-y",
     "specified::OverscrollBehavior::parse",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     needs_context=False,
     gecko_pref="layout.css.overscroll-behavior.enabled",
     spec="https://wicg.github.io/overscroll-behavior/#overscroll-behavior-properties",
@@ -366,6 +367,46 @@ ${helpers.two_properties_shorthand(
     }
 </%helpers:shorthand>
 
+<%helpers:shorthand
+    name="container"
+    engines="servo-2013"
+    sub_properties="container-name container-type"
+    spec="https://drafts.csswg.org/css-contain-3/#container-shorthand"
+>
+    use crate::parser::Parse;
+    use crate::properties::longhands::container_type;
+    use crate::values::specified::ContainerName;
+
+    pub fn parse_value<'i, 't>(
+        context: &ParserContext,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Longhands, ParseError<'i>> {
+        // <'container-name'> [ / <'container-type'> ]?
+        let container_name = ContainerName::parse(context, input)?;
+        let container_type = if input.try(|i| i.expect_delim('/')).is_ok() {
+            container_type::parse(context, input)?
+        } else {
+            container_type::get_initial_specified_value()
+        };
+
+        Ok(expanded! {
+            container_name: container_name,
+            container_type: container_type,
+        })
+    }
+
+    impl<'a> ToCss for LonghandsToSerialize<'a>  {
+        fn to_css<W>(&self, dest: &mut CssWriter<W>) -> fmt::Result where W: fmt::Write {
+            self.container_name.to_css(dest)?;
+            if *self.container_type != container_type::get_initial_specified_value() {
+                dest.write_str(" / ")?;
+                self.container_type.to_css(dest)?;
+            }
+            Ok(())
+        }
+    }
+</%helpers:shorthand>
+
 <%helpers:shorthand name="offset"
                     engines="gecko"
                     sub_properties="offset-path offset-distance offset-rotate offset-anchor"
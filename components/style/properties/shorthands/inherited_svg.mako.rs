@@ -6,7 +6,8 @@
 
 <%helpers:shorthand
     name="marker"
-    engines="gecko"
+    engines="gecko servo-2013 servo-2020"
+    servo_2020_pref="layout.2020.unimplemented"
     sub_properties="marker-start marker-end marker-mid"
     spec="https://www.w3.org/TR/SVG2/painting.html#MarkerShorthand"
 >
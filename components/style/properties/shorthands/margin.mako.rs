@@ -36,7 +36,8 @@ ${helpers.four_sides_shorthand(
     "scroll-margin",
     "scroll-margin-%s",
     "specified::Length::parse",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     spec="https://drafts.csswg.org/css-scroll-snap-1/#propdef-scroll-margin",
     gecko_pref="layout.css.scroll-snap-v1.enabled",
 )}
@@ -46,7 +47,8 @@ ${helpers.two_properties_shorthand(
     "scroll-margin-block-start",
     "scroll-margin-block-end",
     "specified::Length::parse",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     spec="https://drafts.csswg.org/css-scroll-snap-1/#propdef-scroll-margin-block",
     gecko_pref="layout.css.scroll-snap-v1.enabled",
 )}
@@ -56,7 +58,8 @@ ${helpers.two_properties_shorthand(
     "scroll-margin-inline-start",
     "scroll-margin-inline-end",
     "specified::Length::parse",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     spec="https://drafts.csswg.org/css-scroll-snap-1/#propdef-scroll-margin-inline",
     gecko_pref="layout.css.scroll-snap-v1.enabled",
 )}
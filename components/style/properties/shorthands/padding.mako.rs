@@ -35,7 +35,8 @@ ${helpers.four_sides_shorthand(
     "scroll-padding",
     "scroll-padding-%s",
     "specified::NonNegativeLengthPercentageOrAuto::parse",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     gecko_pref="layout.css.scroll-snap-v1.enabled",
     spec="https://drafts.csswg.org/css-scroll-snap-1/#propdef-scroll-padding"
 )}
@@ -45,7 +46,8 @@ ${helpers.two_properties_shorthand(
     "scroll-padding-block-start",
     "scroll-padding-block-end",
     "specified::NonNegativeLengthPercentageOrAuto::parse",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     gecko_pref="layout.css.scroll-snap-v1.enabled",
     spec="https://drafts.csswg.org/css-scroll-snap-1/#propdef-scroll-padding-block"
 )}
@@ -55,7 +57,8 @@ ${helpers.two_properties_shorthand(
     "scroll-padding-inline-start",
     "scroll-padding-inline-end",
     "specified::NonNegativeLengthPercentageOrAuto::parse",
-    engines="gecko",
+    engines="gecko servo-2013 servo-2020",
+    servo_2020_pref="layout.2020.unimplemented",
     gecko_pref="layout.css.scroll-snap-v1.enabled",
     spec="https://drafts.csswg.org/css-scroll-snap-1/#propdef-scroll-padding-inline"
 )}
@@ -4,7 +4,8 @@
 
 <%namespace name="helpers" file="/helpers.mako.rs" />
 
-<%helpers:shorthand name="mask" engines="gecko" extra_prefixes="webkit"
+<%helpers:shorthand name="mask" engines="gecko servo-2013 servo-2020" extra_prefixes="webkit"
+                    servo_2020_pref="layout.2020.unimplemented"
                     flags="SHORTHAND_IN_GETCS"
                     sub_properties="mask-mode mask-repeat mask-clip mask-origin mask-composite mask-position-x
                                     mask-position-y mask-size mask-image"
@@ -195,7 +196,8 @@
     }
 </%helpers:shorthand>
 
-<%helpers:shorthand name="mask-position" engines="gecko" extra_prefixes="webkit"
+<%helpers:shorthand name="mask-position" engines="gecko servo-2013 servo-2020" extra_prefixes="webkit"
+                    servo_2020_pref="layout.2020.unimplemented"
                     flags="SHORTHAND_IN_GETCS"
                     sub_properties="mask-position-x mask-position-y"
                     spec="https://drafts.csswg.org/css-masks-4/#the-mask-position">
@@ -0,0 +1,675 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Support for [registered custom properties][spec]: the syntax they are
+//! registered with, and their typed computed values, which unlike the token
+//! streams of unregistered custom properties can be interpolated.
+//!
+//! [spec]: https://drafts.css-houdini.org/css-properties-values-api/
+
+use crate::context::QuirksMode;
+use crate::custom_properties::{Name, VariableValue};
+use crate::parser::{Parse, ParserContext};
+use crate::selector_map::PrecomputedHashMap;
+use crate::str::char_is_whitespace;
+use crate::stylesheets::{CssRuleType, Origin, UrlExtraData};
+use crate::values::animated::{Animate, Procedure, ToAnimatedValue};
+use crate::values::computed::{self, Context, ToComputedValue};
+use crate::values::specified;
+use crate::values::{CSSFloat, CustomIdent};
+use cssparser::{Parser, ParserInput, Token, RGBA};
+use servo_arc::Arc;
+use smallvec::SmallVec;
+use std::fmt::{self, Write};
+use style_traits::{CssWriter, ParseError, ParsingMode, StyleParseErrorKind, ToCss};
+
+/// A data type name that can appear in a syntax string, like `<length>`.
+///
+/// <https://drafts.css-houdini.org/css-properties-values-api/#supported-names>
+#[derive(Clone, Copy, Debug, Eq, MallocSizeOf, PartialEq, ToShmem)]
+pub enum DataType {
+    /// `<length>`
+    Length,
+    /// `<number>`
+    Number,
+    /// `<percentage>`
+    Percentage,
+    /// `<length-percentage>`
+    LengthPercentage,
+    /// `<color>`
+    Color,
+    /// `<integer>`
+    Integer,
+    /// `<angle>`
+    Angle,
+    /// `<time>`
+    Time,
+    /// `<resolution>`
+    Resolution,
+    /// `<custom-ident>`
+    CustomIdent,
+}
+
+impl DataType {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "length" => DataType::Length,
+            "number" => DataType::Number,
+            "percentage" => DataType::Percentage,
+            "length-percentage" => DataType::LengthPercentage,
+            "color" => DataType::Color,
+            "integer" => DataType::Integer,
+            "angle" => DataType::Angle,
+            "time" => DataType::Time,
+            "resolution" => DataType::Resolution,
+            "custom-ident" => DataType::CustomIdent,
+            _ => return None,
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        match *self {
+            DataType::Length => "length",
+            DataType::Number => "number",
+            DataType::Percentage => "percentage",
+            DataType::LengthPercentage => "length-percentage",
+            DataType::Color => "color",
+            DataType::Integer => "integer",
+            DataType::Angle => "angle",
+            DataType::Time => "time",
+            DataType::Resolution => "resolution",
+            DataType::CustomIdent => "custom-ident",
+        }
+    }
+}
+
+/// What a syntax component matches: either a value of a given data type, or
+/// a literal identifier.
+#[derive(Clone, Debug, Eq, MallocSizeOf, PartialEq, ToShmem)]
+pub enum SyntaxComponentName {
+    /// A data type name, like `<length>`.
+    DataType(DataType),
+    /// An identifier, that only matches itself.
+    Ident(CustomIdent),
+}
+
+/// A multiplier, that makes a syntax component match a list of values.
+#[derive(Clone, Copy, Debug, Eq, MallocSizeOf, PartialEq, ToShmem)]
+pub enum Multiplier {
+    /// `+`: a space-separated list.
+    Space,
+    /// `#`: a comma-separated list.
+    Comma,
+}
+
+/// A single component of a syntax string, like `<length>+`.
+#[derive(Clone, Debug, Eq, MallocSizeOf, PartialEq, ToShmem)]
+pub struct SyntaxComponent {
+    /// What this component matches.
+    pub name: SyntaxComponentName,
+    /// The multiplier of this component, if any.
+    pub multiplier: Option<Multiplier>,
+}
+
+impl SyntaxComponent {
+    fn parse(component: &str) -> Result<Self, ()> {
+        let component = component.trim_matches(char_is_whitespace);
+        let (name, multiplier) = if component.ends_with('+') {
+            (&component[..component.len() - 1], Some(Multiplier::Space))
+        } else if component.ends_with('#') {
+            (&component[..component.len() - 1], Some(Multiplier::Comma))
+        } else {
+            (component, None)
+        };
+
+        let name = if name.len() > 2 && name.starts_with('<') && name.ends_with('>') {
+            SyntaxComponentName::DataType(DataType::from_name(&name[1..name.len() - 1]).ok_or(())?)
+        } else {
+            let mut input = ParserInput::new(name);
+            let mut input = Parser::new(&mut input);
+            let ident = input
+                .parse_entirely(|input| {
+                    let location = input.current_source_location();
+                    let ident = input.expect_ident()?;
+                    CustomIdent::from_ident(location, ident, &[])
+                })
+                .map_err(|_| ())?;
+            SyntaxComponentName::Ident(ident)
+        };
+
+        Ok(SyntaxComponent { name, multiplier })
+    }
+
+    fn parse_single_value<'i, 't>(
+        &self,
+        context: &ParserContext,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<SpecifiedComponentValue, ParseError<'i>> {
+        let data_type = match self.name {
+            SyntaxComponentName::DataType(data_type) => data_type,
+            SyntaxComponentName::Ident(ref expected) => {
+                let location = input.current_source_location();
+                let ident = input.expect_ident()?;
+                if &**ident != &*expected.0 {
+                    return Err(location.new_custom_error(StyleParseErrorKind::UnspecifiedError));
+                }
+                return Ok(SpecifiedComponentValue::Ident(expected.clone()));
+            },
+        };
+
+        Ok(match data_type {
+            DataType::Length => {
+                SpecifiedComponentValue::Length(specified::Length::parse(context, input)?)
+            },
+            DataType::Number => {
+                SpecifiedComponentValue::Number(specified::Number::parse(context, input)?)
+            },
+            DataType::Percentage => {
+                SpecifiedComponentValue::Percentage(specified::Percentage::parse(context, input)?)
+            },
+            DataType::LengthPercentage => SpecifiedComponentValue::LengthPercentage(
+                specified::LengthPercentage::parse(context, input)?,
+            ),
+            DataType::Color => {
+                SpecifiedComponentValue::Color(specified::Color::parse(context, input)?)
+            },
+            DataType::Integer => {
+                SpecifiedComponentValue::Integer(specified::Integer::parse(context, input)?)
+            },
+            DataType::Angle => {
+                SpecifiedComponentValue::Angle(specified::Angle::parse(context, input)?)
+            },
+            DataType::Time => {
+                SpecifiedComponentValue::Time(specified::Time::parse(context, input)?)
+            },
+            DataType::Resolution => {
+                SpecifiedComponentValue::Resolution(specified::Resolution::parse(context, input)?)
+            },
+            DataType::CustomIdent => {
+                let location = input.current_source_location();
+                let ident = input.expect_ident()?;
+                SpecifiedComponentValue::Ident(CustomIdent::from_ident(location, ident, &[])?)
+            },
+        })
+    }
+
+    fn parse_value<'i, 't>(
+        &self,
+        context: &ParserContext,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<SmallVec<[SpecifiedComponentValue; 1]>, ParseError<'i>> {
+        let mut values = SmallVec::new();
+        match self.multiplier {
+            None => values.push(self.parse_single_value(context, input)?),
+            Some(Multiplier::Space) => loop {
+                values.push(self.parse_single_value(context, input)?);
+                if input.is_exhausted() {
+                    break;
+                }
+            },
+            Some(Multiplier::Comma) => {
+                values.extend(
+                    input.parse_comma_separated(|input| self.parse_single_value(context, input))?,
+                );
+            },
+        }
+        Ok(values)
+    }
+}
+
+impl ToCss for SyntaxComponent {
+    fn to_css<W>(&self, dest: &mut CssWriter<W>) -> fmt::Result
+    where
+        W: Write,
+    {
+        match self.name {
+            SyntaxComponentName::DataType(data_type) => {
+                dest.write_char('<')?;
+                dest.write_str(data_type.name())?;
+                dest.write_char('>')?;
+            },
+            SyntaxComponentName::Ident(ref ident) => ident.to_css(dest)?,
+        }
+        match self.multiplier {
+            Some(Multiplier::Space) => dest.write_char('+'),
+            Some(Multiplier::Comma) => dest.write_char('#'),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The syntax of a registered custom property, as a list of alternative
+/// components. No components mean the universal syntax, `*`, which accepts
+/// any value.
+///
+/// <https://drafts.css-houdini.org/css-properties-values-api/#syntax-strings>
+#[derive(Clone, Debug, Eq, MallocSizeOf, PartialEq, ToShmem)]
+pub struct Syntax(Box<[SyntaxComponent]>);
+
+impl Syntax {
+    /// The universal syntax definition, `*`.
+    pub fn universal() -> Self {
+        Syntax(Vec::new().into_boxed_slice())
+    }
+
+    /// Whether this is the universal syntax definition.
+    #[inline]
+    pub fn is_universal(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Parses a syntax string.
+    pub fn parse(syntax: &str) -> Result<Self, ()> {
+        let syntax = syntax.trim_matches(char_is_whitespace);
+        if syntax == "*" {
+            return Ok(Self::universal());
+        }
+        let components = syntax
+            .split('|')
+            .map(SyntaxComponent::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Syntax(components.into_boxed_slice()))
+    }
+
+    /// Parses a value matching this syntax, trying each component in order.
+    fn parse_value<'i, 't>(
+        &self,
+        context: &ParserContext,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<SpecifiedRegisteredValue, ParseError<'i>> {
+        for component in self.0.iter() {
+            let result = input
+                .try(|input| input.parse_entirely(|input| component.parse_value(context, input)));
+            if let Ok(values) = result {
+                return Ok(SpecifiedRegisteredValue {
+                    values,
+                    multiplier: component.multiplier,
+                });
+            }
+        }
+        Err(input.new_custom_error(StyleParseErrorKind::UnspecifiedError))
+    }
+
+    fn parse_value_from_css(
+        &self,
+        context: &ParserContext,
+        css: &str,
+    ) -> Result<SpecifiedRegisteredValue, ()> {
+        let mut input = ParserInput::new(css);
+        self.parse_value(context, &mut Parser::new(&mut input))
+            .map_err(|_| ())
+    }
+}
+
+impl ToCss for Syntax {
+    fn to_css<W>(&self, dest: &mut CssWriter<W>) -> fmt::Result
+    where
+        W: Write,
+    {
+        if self.is_universal() {
+            return dest.write_char('*');
+        }
+        for (i, component) in self.0.iter().enumerate() {
+            if i != 0 {
+                dest.write_str(" | ")?;
+            }
+            component.to_css(dest)?;
+        }
+        Ok(())
+    }
+}
+
+/// A specified value matching a single syntax component, without multiplier.
+enum SpecifiedComponentValue {
+    Length(specified::Length),
+    Number(specified::Number),
+    Percentage(specified::Percentage),
+    LengthPercentage(specified::LengthPercentage),
+    Color(specified::Color),
+    Integer(specified::Integer),
+    Angle(specified::Angle),
+    Time(specified::Time),
+    Resolution(specified::Resolution),
+    Ident(CustomIdent),
+}
+
+impl SpecifiedComponentValue {
+    fn to_computed_value(&self, context: &Context) -> ComputedComponentValue {
+        match *self {
+            SpecifiedComponentValue::Length(ref v) => {
+                ComputedComponentValue::Length(v.to_computed_value(context))
+            },
+            SpecifiedComponentValue::Number(ref v) => {
+                ComputedComponentValue::Number(v.to_computed_value(context))
+            },
+            SpecifiedComponentValue::Percentage(ref v) => {
+                ComputedComponentValue::Percentage(v.to_computed_value(context))
+            },
+            SpecifiedComponentValue::LengthPercentage(ref v) => {
+                ComputedComponentValue::LengthPercentage(v.to_computed_value(context))
+            },
+            SpecifiedComponentValue::Color(ref v) => {
+                ComputedComponentValue::Color(v.to_computed_value(context))
+            },
+            SpecifiedComponentValue::Integer(ref v) => {
+                ComputedComponentValue::Integer(v.to_computed_value(context))
+            },
+            SpecifiedComponentValue::Angle(ref v) => {
+                ComputedComponentValue::Angle(v.to_computed_value(context))
+            },
+            SpecifiedComponentValue::Time(ref v) => {
+                ComputedComponentValue::Time(v.to_computed_value(context))
+            },
+            SpecifiedComponentValue::Resolution(ref v) => {
+                ComputedComponentValue::Resolution(v.to_dppx())
+            },
+            SpecifiedComponentValue::Ident(ref v) => ComputedComponentValue::Ident(v.clone()),
+        }
+    }
+}
+
+/// A specified value of a registered custom property.
+struct SpecifiedRegisteredValue {
+    values: SmallVec<[SpecifiedComponentValue; 1]>,
+    multiplier: Option<Multiplier>,
+}
+
+impl SpecifiedRegisteredValue {
+    fn to_computed_value(&self, context: &Context) -> ComputedRegisteredValue {
+        ComputedRegisteredValue {
+            values: self
+                .values
+                .iter()
+                .map(|v| v.to_computed_value(context))
+                .collect(),
+            multiplier: self.multiplier,
+        }
+    }
+}
+
+/// A computed value matching a single syntax component, without multiplier.
+#[derive(Clone, Debug, PartialEq)]
+enum ComputedComponentValue {
+    Length(computed::Length),
+    Number(CSSFloat),
+    Percentage(computed::Percentage),
+    LengthPercentage(computed::LengthPercentage),
+    Color(computed::Color),
+    Integer(i32),
+    Angle(computed::Angle),
+    Time(computed::Time),
+    /// A resolution, in dppx.
+    Resolution(CSSFloat),
+    Ident(CustomIdent),
+}
+
+impl Animate for ComputedComponentValue {
+    fn animate(&self, other: &Self, procedure: Procedure) -> Result<Self, ()> {
+        use self::ComputedComponentValue::*;
+
+        Ok(match (self, other) {
+            (&Length(ref a), &Length(ref b)) => Length(a.animate(b, procedure)?),
+            (&Number(a), &Number(b)) => Number(a.animate(&b, procedure)?),
+            (&Percentage(ref a), &Percentage(ref b)) => Percentage(a.animate(b, procedure)?),
+            (&LengthPercentage(ref a), &LengthPercentage(ref b)) => {
+                LengthPercentage(a.animate(b, procedure)?)
+            },
+            (&Color(ref a), &Color(ref b)) => {
+                // Colors that depend on currentcolor have no serialization we
+                // could store in the custom properties map, so only numeric
+                // colors are interpolated.
+                if !a.is_numeric() || !b.is_numeric() {
+                    return Err(());
+                }
+                let a = a.to_rgba(RGBA::transparent()).to_animated_value();
+                let b = b.to_rgba(RGBA::transparent()).to_animated_value();
+                Color(computed::Color::rgba(RGBA::from_animated_value(
+                    a.animate(&b, procedure)?,
+                )))
+            },
+            (&Integer(a), &Integer(b)) => Integer(a.animate(&b, procedure)?),
+            (&Angle(ref a), &Angle(ref b)) => Angle(a.animate(b, procedure)?),
+            (&Time(ref a), &Time(ref b)) => Time(computed::Time::from_seconds(
+                a.seconds().animate(&b.seconds(), procedure)?,
+            )),
+            (&Resolution(a), &Resolution(b)) => Resolution(a.animate(&b, procedure)?),
+            (&Ident(ref a), &Ident(ref b)) if a == b => Ident(a.clone()),
+            _ => return Err(()),
+        })
+    }
+}
+
+impl ToCss for ComputedComponentValue {
+    fn to_css<W>(&self, dest: &mut CssWriter<W>) -> fmt::Result
+    where
+        W: Write,
+    {
+        match *self {
+            ComputedComponentValue::Length(ref v) => v.to_css(dest),
+            ComputedComponentValue::Number(ref v) => v.to_css(dest),
+            ComputedComponentValue::Percentage(ref v) => v.to_css(dest),
+            ComputedComponentValue::LengthPercentage(ref v) => v.to_css(dest),
+            ComputedComponentValue::Color(ref v) => v.to_css(dest),
+            ComputedComponentValue::Integer(ref v) => v.to_css(dest),
+            ComputedComponentValue::Angle(ref v) => v.to_css(dest),
+            ComputedComponentValue::Time(ref v) => v.to_css(dest),
+            ComputedComponentValue::Resolution(ref v) => {
+                v.to_css(dest)?;
+                dest.write_str("dppx")
+            },
+            ComputedComponentValue::Ident(ref v) => v.to_css(dest),
+        }
+    }
+}
+
+/// The computed value of a registered custom property with a syntax other
+/// than the universal one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComputedRegisteredValue {
+    values: SmallVec<[ComputedComponentValue; 1]>,
+    multiplier: Option<Multiplier>,
+}
+
+impl ComputedRegisteredValue {
+    /// Returns this value as the token stream that is stored in the custom
+    /// properties map, and substituted into var() references.
+    pub fn to_variable_value(&self) -> Option<Arc<VariableValue>> {
+        let css = self.to_css_string();
+        let mut input = ParserInput::new(&css);
+        VariableValue::parse(&mut Parser::new(&mut input)).ok()
+    }
+}
+
+impl Animate for ComputedRegisteredValue {
+    fn animate(&self, other: &Self, procedure: Procedure) -> Result<Self, ()> {
+        if self.multiplier != other.multiplier || self.values.len() != other.values.len() {
+            return Err(());
+        }
+        Ok(ComputedRegisteredValue {
+            values: self
+                .values
+                .iter()
+                .zip(other.values.iter())
+                .map(|(a, b)| a.animate(b, procedure))
+                .collect::<Result<_, _>>()?,
+            multiplier: self.multiplier,
+        })
+    }
+}
+
+impl ToCss for ComputedRegisteredValue {
+    fn to_css<W>(&self, dest: &mut CssWriter<W>) -> fmt::Result
+    where
+        W: Write,
+    {
+        let separator = match self.multiplier {
+            Some(Multiplier::Comma) => ", ",
+            Some(Multiplier::Space) | None => " ",
+        };
+        for (i, value) in self.values.iter().enumerate() {
+            if i != 0 {
+                dest.write_str(separator)?;
+            }
+            value.to_css(dest)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns whether a value can be computed without looking at the element it
+/// applies to, that is, whether it doesn't contain references or relative
+/// units.
+///
+/// <https://drafts.css-houdini.org/css-properties-values-api/#computationally-independent>
+fn is_computationally_independent<'i, 't>(input: &mut Parser<'i, 't>) -> bool {
+    loop {
+        let token = match input.next() {
+            Ok(token) => token.clone(),
+            Err(..) => return true,
+        };
+        let independent = match token {
+            Token::Dimension { ref unit, .. } => match_ignore_ascii_case! { unit,
+                "em" | "ex" | "ch" | "rem" | "cap" | "ic" | "lh" | "rlh" |
+                "vw" | "vh" | "vmin" | "vmax" => false,
+                _ => true,
+            },
+            Token::Function(ref name)
+                if name.eq_ignore_ascii_case("var") || name.eq_ignore_ascii_case("env") =>
+            {
+                false
+            },
+            Token::Function(..) |
+            Token::ParenthesisBlock |
+            Token::SquareBracketBlock |
+            Token::CurlyBracketBlock => input
+                .parse_nested_block(|input| {
+                    Ok::<_, ParseError<'i>>(is_computationally_independent(input))
+                })
+                .unwrap_or(false),
+            _ => true,
+        };
+        if !independent {
+            return false;
+        }
+    }
+}
+
+/// A registered custom property, either from an `@property` rule or from
+/// `CSS.registerProperty`.
+///
+/// <https://drafts.css-houdini.org/css-properties-values-api/#registered-custom-property>
+#[derive(Clone, Debug, ToShmem)]
+pub struct PropertyRegistration {
+    /// The name of the property, without the `--` prefix.
+    pub name: Name,
+    /// The syntax values of the property must match.
+    pub syntax: Syntax,
+    /// Whether the property inherits.
+    pub inherits: bool,
+    /// The initial value of the property. It can only be missing with the
+    /// universal syntax, in which case the initial value is the
+    /// guaranteed-invalid value.
+    pub initial_value: Option<Arc<VariableValue>>,
+    /// The url data to parse values of the property with.
+    pub url_data: UrlExtraData,
+}
+
+impl PropertyRegistration {
+    /// Creates a registration, validating its initial value, which has to
+    /// match the syntax and be computationally independent unless the syntax
+    /// is the universal one.
+    pub fn new(
+        name: Name,
+        syntax: Syntax,
+        inherits: bool,
+        initial_value: Option<Arc<VariableValue>>,
+        url_data: UrlExtraData,
+        quirks_mode: QuirksMode,
+    ) -> Result<Self, ()> {
+        if !syntax.is_universal() {
+            let css = initial_value.as_ref().ok_or(())?.to_css_string();
+
+            let mut input = ParserInput::new(&css);
+            if !is_computationally_independent(&mut Parser::new(&mut input)) {
+                return Err(());
+            }
+
+            let context = parser_context(&url_data, quirks_mode);
+            syntax.parse_value_from_css(&context, &css)?;
+        }
+
+        Ok(Self {
+            name,
+            syntax,
+            inherits,
+            initial_value,
+            url_data,
+        })
+    }
+
+    /// Computes a value of this property, whose references have already been
+    /// substituted. Fails if the value doesn't match the syntax, which makes
+    /// the declaration invalid at computed-value time.
+    pub fn compute(
+        &self,
+        value: &VariableValue,
+        context: &Context,
+    ) -> Result<ComputedRegisteredValue, ()> {
+        debug_assert!(!self.syntax.is_universal());
+        let parser_context = parser_context(&self.url_data, context.quirks_mode);
+        let specified = self
+            .syntax
+            .parse_value_from_css(&parser_context, &value.to_css_string())?;
+        Ok(specified.to_computed_value(context))
+    }
+}
+
+fn parser_context(url_data: &UrlExtraData, quirks_mode: QuirksMode) -> ParserContext {
+    ParserContext::new(
+        Origin::Author,
+        url_data,
+        Some(CssRuleType::Style),
+        ParsingMode::DEFAULT,
+        quirks_mode,
+        None,
+        None,
+    )
+}
+
+/// The set of registered custom properties of a document, indexed by name.
+#[derive(Clone, Debug, Default)]
+pub struct CustomPropertyRegistry {
+    registrations: PrecomputedHashMap<Name, Arc<PropertyRegistration>>,
+}
+
+impl CustomPropertyRegistry {
+    /// Returns the registration of a given custom property, if any.
+    #[inline]
+    pub fn get(&self, name: &Name) -> Option<&Arc<PropertyRegistration>> {
+        self.registrations.get(name)
+    }
+
+    /// Whether no custom property is registered.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.registrations.is_empty()
+    }
+
+    /// Iterates over all the registrations.
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<PropertyRegistration>> {
+        self.registrations.values()
+    }
+
+    /// Registers a property, replacing any previous registration of the same
+    /// name.
+    pub fn register(&mut self, registration: Arc<PropertyRegistration>) {
+        self.registrations
+            .insert(registration.name.clone(), registration);
+    }
+
+    /// Removes all the registrations.
+    pub fn clear(&mut self) {
+        self.registrations.clear();
+    }
+}
@@ -12,7 +12,7 @@ use crate::selector_map::SelectorMap;
 use crate::selector_parser::PseudoElement;
 use crate::shared_lock::Locked;
 use crate::stylesheets::Origin;
-use crate::stylist::{AuthorStylesEnabled, Rule, RuleInclusion, Stylist};
+use crate::stylist::{AuthorStylesEnabled, CascadeData, Rule, RuleInclusion, Stylist};
 use selectors::matching::{ElementSelectorFlags, MatchingContext, MatchingMode};
 use servo_arc::ArcBorrow;
 use smallvec::SmallVec;
@@ -56,7 +56,8 @@ pub fn containing_shadow_ignoring_svg_use<E: TElement>(
 
 #[inline]
 fn sort_rules_from(rules: &mut ApplicableDeclarationList, start: usize) {
-    rules[start..].sort_unstable_by_key(|block| (block.specificity, block.source_order()));
+    rules[start..]
+        .sort_unstable_by_key(|block| (block.layer_order, block.specificity, block.source_order()));
 }
 
 /// An object that we use with all the intermediate state needed for the
@@ -151,7 +152,7 @@ where
             None => return,
         };
 
-        self.collect_rules_internal(None, map, cascade_level);
+        self.collect_rules_internal(None, map, cascade_level, cascade_data);
     }
 
     fn collect_user_agent_rules(&mut self) {
@@ -195,9 +196,10 @@ where
         shadow_host: E,
         map: &SelectorMap<Rule>,
         cascade_level: CascadeLevel,
+        cascade_data: &CascadeData,
     ) {
         debug_assert!(shadow_host.shadow_root().is_some());
-        self.collect_rules_internal(Some(shadow_host), map, cascade_level);
+        self.collect_rules_internal(Some(shadow_host), map, cascade_level, cascade_data);
         self.shadow_cascade_order += 1;
     }
 
@@ -207,9 +209,11 @@ where
         shadow_host: Option<E>,
         map: &SelectorMap<Rule>,
         cascade_level: CascadeLevel,
+        cascade_data: &CascadeData,
     ) {
         let element = self.element;
         let rule_hash_target = self.rule_hash_target;
+        let stylist = self.stylist;
         let rules = &mut self.rules;
         let flags_setter = &mut self.flags_setter;
         let shadow_cascade_order = self.shadow_cascade_order;
@@ -223,6 +227,8 @@ where
                 flags_setter,
                 cascade_level,
                 shadow_cascade_order,
+                cascade_data,
+                stylist,
             );
         });
         sort_rules_from(rules, start);
@@ -257,6 +263,7 @@ where
                 shadow.host(),
                 slotted_rules,
                 CascadeLevel::InnerShadowNormal,
+                data,
             );
         }
     }
@@ -274,10 +281,18 @@ where
 
         self.matches_document_author_rules = false;
 
-        let cascade_data = containing_shadow.style_data();
+        let cascade_data = match containing_shadow.style_data() {
+            Some(d) => d,
+            None => return,
+        };
         let host = containing_shadow.host();
-        if let Some(map) = cascade_data.and_then(|data| data.normal_rules(self.pseudo_element)) {
-            self.collect_rules_in_shadow_tree(host, map, CascadeLevel::SameTreeAuthorNormal);
+        if let Some(map) = cascade_data.normal_rules(self.pseudo_element) {
+            self.collect_rules_in_shadow_tree(
+                host,
+                map,
+                CascadeLevel::SameTreeAuthorNormal,
+                cascade_data,
+            );
         }
     }
 
@@ -308,6 +323,7 @@ where
             rule_hash_target,
             host_rules,
             CascadeLevel::InnerShadowNormal,
+            style_data,
         );
     }
 
@@ -331,16 +347,19 @@ where
 
         let host = shadow.host();
         let containing_shadow = host.containing_shadow();
-        let part_rules = match containing_shadow {
-            Some(shadow) => shadow
-                .style_data()
-                .and_then(|data| data.part_rules(self.pseudo_element)),
-            None => self
-                .stylist
-                .cascade_data()
-                .borrow_for_origin(Origin::Author)
-                .part_rules(self.pseudo_element),
+        let cascade_data = match containing_shadow {
+            Some(shadow) => shadow.style_data(),
+            None => Some(
+                self.stylist
+                    .cascade_data()
+                    .borrow_for_origin(Origin::Author),
+            ),
+        };
+        let cascade_data = match cascade_data {
+            Some(d) => d,
+            None => return,
         };
+        let part_rules = cascade_data.part_rules(self.pseudo_element);
 
         // TODO(emilio): SameTreeAuthorNormal is a bit of a lie here, we may
         // need an OuterTreeAuthorNormal cascade level or such, and change the
@@ -353,6 +372,7 @@ where
             let containing_host = containing_shadow.map(|s| s.host());
             let element = self.element;
             let rule_hash_target = self.rule_hash_target;
+            let stylist = self.stylist;
             let rules = &mut self.rules;
             let flags_setter = &mut self.flags_setter;
             let shadow_cascade_order = self.shadow_cascade_order;
@@ -369,6 +389,8 @@ where
                             flags_setter,
                             cascade_level,
                             shadow_cascade_order,
+                            cascade_data,
+                            stylist,
                         );
                     }
                 });
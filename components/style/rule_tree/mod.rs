@@ -12,12 +12,14 @@ use crate::gecko::selector_parser::PseudoElement;
 use crate::hash::{self, FxHashMap};
 use crate::properties::{Importance, LonghandIdSet, PropertyDeclarationBlock};
 use crate::shared_lock::{Locked, SharedRwLockReadGuard, StylesheetGuards};
+use crate::stylesheets::layer_rule::LayerOrder;
 use crate::stylesheets::{Origin, StyleRule};
 use crate::thread_state;
 use malloc_size_of::{MallocShallowSizeOf, MallocSizeOf, MallocSizeOfOps};
 use parking_lot::RwLock;
 use servo_arc::{Arc, ArcBorrow, ArcUnion, ArcUnionBorrow};
 use smallvec::SmallVec;
+use std::cmp::Reverse;
 use std::io::{self, Write};
 use std::mem;
 use std::ptr;
@@ -209,13 +211,16 @@ impl RuleTree {
     /// !important rules are detected and inserted into the appropriate position
     /// in the rule tree. This allows selector matching to ignore importance,
     /// while still maintaining the appropriate cascade order in the rule tree.
+    ///
+    /// The order of cascade layers is reversed for !important rules, see:
+    /// https://drafts.csswg.org/css-cascade-5/#cascade-layering
     pub fn insert_ordered_rules_with_important<'a, I>(
         &self,
         iter: I,
         guards: &StylesheetGuards,
     ) -> StrongRuleNode
     where
-        I: Iterator<Item = (StyleSource, CascadeLevel, ShadowCascadeOrder)>,
+        I: Iterator<Item = (StyleSource, CascadeLevel, ShadowCascadeOrder, LayerOrder)>,
     {
         use self::CascadeLevel::*;
         let mut current = self.root.clone();
@@ -224,16 +229,17 @@ impl RuleTree {
         let mut found_important = false;
         let mut important_style_attr = None;
 
-        let mut important_same_tree = SmallVec::<[StyleSource; 4]>::new();
-        let mut important_inner_shadow = SmallVec::<[SmallVec<[StyleSource; 4]>; 4]>::new();
+        let mut important_same_tree = SmallVec::<[(StyleSource, LayerOrder); 4]>::new();
+        let mut important_inner_shadow =
+            SmallVec::<[SmallVec<[(StyleSource, LayerOrder); 4]>; 4]>::new();
         important_inner_shadow.push(SmallVec::new());
 
-        let mut important_user = SmallVec::<[StyleSource; 4]>::new();
-        let mut important_ua = SmallVec::<[StyleSource; 4]>::new();
+        let mut important_user = SmallVec::<[(StyleSource, LayerOrder); 4]>::new();
+        let mut important_ua = SmallVec::<[(StyleSource, LayerOrder); 4]>::new();
         let mut transition = None;
 
         let mut last_cascade_order = 0;
-        for (source, level, shadow_cascade_order) in iter {
+        for (source, level, shadow_cascade_order, layer_order) in iter {
             debug_assert!(level >= last_level, "Not really ordered");
             debug_assert!(!level.is_important(), "Important levels handled internally");
             let any_important = {
@@ -258,11 +264,11 @@ impl RuleTree {
                         important_inner_shadow
                             .last_mut()
                             .unwrap()
-                            .push(source.clone())
+                            .push((source.clone(), layer_order))
                     },
-                    SameTreeAuthorNormal => important_same_tree.push(source.clone()),
-                    UANormal => important_ua.push(source.clone()),
-                    UserNormal => important_user.push(source.clone()),
+                    SameTreeAuthorNormal => important_same_tree.push((source.clone(), layer_order)),
+                    UANormal => important_ua.push((source.clone(), layer_order)),
+                    UserNormal => important_user.push((source.clone(), layer_order)),
                     StyleAttributeNormal => {
                         debug_assert!(important_style_attr.is_none());
                         important_style_attr = Some(source.clone());
@@ -302,8 +308,13 @@ impl RuleTree {
         // Insert important declarations, in order of increasing importance,
         // followed by any transition rule.
         //
+        // Earlier layers win over later ones for important declarations, so
+        // reverse the layer order, keeping the relative order of the rules in
+        // the same layer (hence the stable sort).
+        //
 
-        for source in important_same_tree.drain() {
+        important_same_tree.sort_by_key(|&(_, layer_order)| Reverse(layer_order));
+        for (source, _) in important_same_tree.drain() {
             current = current.ensure_child(self.root.downgrade(), source, SameTreeAuthorImportant);
         }
 
@@ -312,16 +323,19 @@ impl RuleTree {
         }
 
         for mut list in important_inner_shadow.drain().rev() {
-            for source in list.drain() {
+            list.sort_by_key(|&(_, layer_order)| Reverse(layer_order));
+            for (source, _) in list.drain() {
                 current = current.ensure_child(self.root.downgrade(), source, InnerShadowImportant);
             }
         }
 
-        for source in important_user.drain() {
+        important_user.sort_by_key(|&(_, layer_order)| Reverse(layer_order));
+        for (source, _) in important_user.drain() {
             current = current.ensure_child(self.root.downgrade(), source, UserImportant);
         }
 
-        for source in important_ua.drain() {
+        important_ua.sort_by_key(|&(_, layer_order)| Reverse(layer_order));
+        for (source, _) in important_ua.drain() {
             current = current.ensure_child(self.root.downgrade(), source, UAImportant);
         }
 
@@ -12,7 +12,7 @@ use crate::hash::map as hash_map;
 use crate::hash::{HashMap, HashSet};
 use crate::rule_tree::{CascadeLevel, ShadowCascadeOrder};
 use crate::selector_parser::SelectorImpl;
-use crate::stylist::Rule;
+use crate::stylist::{CascadeData, ContainerConditionId, Rule, Stylist};
 use crate::{Atom, LocalName, Namespace, WeakAtom};
 use fallible::FallibleVec;
 use hashglobe::FailedAllocationError;
@@ -172,6 +172,8 @@ impl SelectorMap<Rule> {
         flags_setter: &mut F,
         cascade_level: CascadeLevel,
         shadow_cascade_order: ShadowCascadeOrder,
+        cascade_data: &CascadeData,
+        stylist: &Stylist,
     ) where
         E: TElement,
         F: FnMut(&E, ElementSelectorFlags),
@@ -191,6 +193,8 @@ impl SelectorMap<Rule> {
                 flags_setter,
                 cascade_level,
                 shadow_cascade_order,
+                cascade_data,
+                stylist,
             );
         }
 
@@ -204,6 +208,8 @@ impl SelectorMap<Rule> {
                     flags_setter,
                     cascade_level,
                     shadow_cascade_order,
+                    cascade_data,
+                    stylist,
                 )
             }
         }
@@ -218,6 +224,8 @@ impl SelectorMap<Rule> {
                     flags_setter,
                     cascade_level,
                     shadow_cascade_order,
+                    cascade_data,
+                    stylist,
                 )
             }
         });
@@ -231,6 +239,8 @@ impl SelectorMap<Rule> {
                 flags_setter,
                 cascade_level,
                 shadow_cascade_order,
+                cascade_data,
+                stylist,
             )
         }
 
@@ -243,6 +253,8 @@ impl SelectorMap<Rule> {
                 flags_setter,
                 cascade_level,
                 shadow_cascade_order,
+                cascade_data,
+                stylist,
             )
         }
 
@@ -254,6 +266,8 @@ impl SelectorMap<Rule> {
             flags_setter,
             cascade_level,
             shadow_cascade_order,
+            cascade_data,
+            stylist,
         );
     }
 
@@ -266,6 +280,8 @@ impl SelectorMap<Rule> {
         flags_setter: &mut F,
         cascade_level: CascadeLevel,
         shadow_cascade_order: ShadowCascadeOrder,
+        cascade_data: &CascadeData,
+        stylist: &Stylist,
     ) where
         E: TElement,
         F: FnMut(&E, ElementSelectorFlags),
@@ -278,10 +294,18 @@ impl SelectorMap<Rule> {
                 &element,
                 context,
                 flags_setter,
-            ) {
-                matching_rules.push(
-                    rule.to_applicable_declaration_block(cascade_level, shadow_cascade_order),
-                );
+            ) && (rule.container_condition_id == ContainerConditionId::none() ||
+                cascade_data.container_condition_matches(
+                    rule.container_condition_id,
+                    stylist,
+                    element,
+                ))
+            {
+                matching_rules.push(rule.to_applicable_declaration_block(
+                    cascade_level,
+                    shadow_cascade_order,
+                    cascade_data,
+                ));
             }
         }
     }
@@ -7,11 +7,11 @@
 use crate::custom_properties::CssEnvironment;
 use crate::media_queries::media_feature::{AllowsRanges, ParsingRequirements};
 use crate::media_queries::media_feature::{Evaluator, MediaFeatureDescription};
-use crate::media_queries::media_feature_expression::RangeOrOperator;
+use crate::media_queries::media_feature_expression::{AspectRatio, RangeOrOperator};
 use crate::media_queries::MediaType;
 use crate::properties::ComputedValues;
 use crate::values::computed::font::FontSize;
-use crate::values::computed::CSSPixelLength;
+use crate::values::computed::{CSSPixelLength, Resolution};
 use crate::values::KeyframesName;
 use app_units::Au;
 use cssparser::RGBA;
@@ -19,7 +19,7 @@ use euclid::default::Size2D as UntypedSize2D;
 use euclid::{Scale, Size2D};
 use std::sync::atomic::{AtomicBool, AtomicIsize, Ordering};
 use style_traits::viewport::ViewportConstraints;
-use style_traits::{CSSPixel, DevicePixel};
+use style_traits::{CSSPixel, ColorScheme, DevicePixel, MediaPreferences};
 
 /// A device is a structure that represents the current media a given document
 /// is displayed in.
@@ -54,6 +54,8 @@ pub struct Device {
     /// The CssEnvironment object responsible of getting CSS environment
     /// variables.
     environment: CssEnvironment,
+    /// The preferences of the user, as set by the embedder.
+    media_preferences: MediaPreferences,
 }
 
 impl Device {
@@ -72,6 +74,7 @@ impl Device {
             used_root_font_size: AtomicBool::new(false),
             used_viewport_units: AtomicBool::new(false),
             environment: CssEnvironment,
+            media_preferences: MediaPreferences::default(),
         }
     }
 
@@ -150,6 +153,16 @@ impl Device {
         self.viewport_size = constraints.size;
     }
 
+    /// Returns the preferences of the user that media queries can test for.
+    pub fn media_preferences(&self) -> MediaPreferences {
+        self.media_preferences
+    }
+
+    /// Set the preferences of the user that media queries can test for.
+    pub fn set_media_preferences(&mut self, media_preferences: MediaPreferences) {
+        self.media_preferences = media_preferences;
+    }
+
     /// Return the media type of the current device.
     pub fn media_type(&self) -> MediaType {
         self.media_type.clone()
@@ -179,6 +192,72 @@ fn eval_width(
     )
 }
 
+/// https://drafts.csswg.org/mediaqueries-4/#height
+fn eval_height(
+    device: &Device,
+    value: Option<CSSPixelLength>,
+    range_or_operator: Option<RangeOrOperator>,
+) -> bool {
+    RangeOrOperator::evaluate(
+        range_or_operator,
+        value.map(Au::from),
+        device.au_viewport_size().height,
+    )
+}
+
+/// https://drafts.csswg.org/mediaqueries-4/#aspect-ratio
+fn eval_aspect_ratio(
+    device: &Device,
+    query_value: Option<AspectRatio>,
+    range_or_operator: Option<RangeOrOperator>,
+) -> bool {
+    let query_value = match query_value {
+        Some(v) => v,
+        None => return true,
+    };
+
+    let size = device.au_viewport_size();
+    let value = AspectRatio(size.width.0 as f32, size.height.0 as f32);
+    RangeOrOperator::evaluate_with_query_value(range_or_operator, query_value, value)
+}
+
+#[derive(Clone, Copy, Debug, FromPrimitive, Parse, ToCss)]
+#[repr(u8)]
+enum Orientation {
+    Landscape,
+    Portrait,
+}
+
+/// https://drafts.csswg.org/mediaqueries-4/#orientation
+fn eval_orientation(device: &Device, value: Option<Orientation>) -> bool {
+    let query_orientation = match value {
+        Some(v) => v,
+        None => return true,
+    };
+
+    let size = device.au_viewport_size();
+
+    // Per spec, square viewports should be 'portrait'
+    let is_landscape = size.width > size.height;
+    match query_orientation {
+        Orientation::Landscape => is_landscape,
+        Orientation::Portrait => !is_landscape,
+    }
+}
+
+/// https://drafts.csswg.org/mediaqueries-4/#resolution
+fn eval_resolution(
+    device: &Device,
+    query_value: Option<Resolution>,
+    range_or_operator: Option<RangeOrOperator>,
+) -> bool {
+    RangeOrOperator::evaluate(
+        range_or_operator,
+        query_value.map(|r| r.dppx()),
+        device.device_pixel_ratio().get(),
+    )
+}
+
 #[derive(Clone, Copy, Debug, FromPrimitive, Parse, ToCss)]
 #[repr(u8)]
 enum Scan {
@@ -193,20 +272,169 @@ fn eval_scan(_: &Device, _: Option<Scan>) -> bool {
     false
 }
 
+#[derive(Clone, Copy, Debug, FromPrimitive, Parse, ToCss)]
+#[repr(u8)]
+enum Hover {
+    None,
+    Hover,
+}
+
+/// https://drafts.csswg.org/mediaqueries-4/#hover
+fn eval_hover(_: &Device, query_value: Option<Hover>) -> bool {
+    // FIXME: Servo doesn't know about the input devices of the embedder yet,
+    // so assume there is a mouse, which can hover.
+    let can_hover = true;
+    let query_value = match query_value {
+        Some(v) => v,
+        None => return can_hover,
+    };
+
+    match query_value {
+        Hover::None => !can_hover,
+        Hover::Hover => can_hover,
+    }
+}
+
+#[derive(Clone, Copy, Debug, FromPrimitive, Parse, ToCss)]
+#[repr(u8)]
+enum Pointer {
+    None,
+    Coarse,
+    Fine,
+}
+
+/// https://drafts.csswg.org/mediaqueries-4/#pointer
+fn eval_pointer(_: &Device, query_value: Option<Pointer>) -> bool {
+    // See the note in eval_hover.
+    let query_value = match query_value {
+        Some(v) => v,
+        None => return true,
+    };
+
+    match query_value {
+        Pointer::None | Pointer::Coarse => false,
+        Pointer::Fine => true,
+    }
+}
+
+#[derive(Clone, Copy, Debug, FromPrimitive, Parse, ToCss)]
+#[repr(u8)]
+enum PrefersColorScheme {
+    Light,
+    Dark,
+    NoPreference,
+}
+
+/// https://drafts.csswg.org/mediaqueries-5/#prefers-color-scheme
+fn eval_prefers_color_scheme(device: &Device, query_value: Option<PrefersColorScheme>) -> bool {
+    let color_scheme = device.media_preferences().color_scheme;
+    let query_value = match query_value {
+        Some(v) => v,
+        None => return color_scheme.is_some(),
+    };
+
+    match query_value {
+        PrefersColorScheme::Light => color_scheme == Some(ColorScheme::Light),
+        PrefersColorScheme::Dark => color_scheme == Some(ColorScheme::Dark),
+        PrefersColorScheme::NoPreference => color_scheme.is_none(),
+    }
+}
+
+#[derive(Clone, Copy, Debug, FromPrimitive, Parse, ToCss)]
+#[repr(u8)]
+enum PrefersReducedMotion {
+    NoPreference,
+    Reduce,
+}
+
+/// https://drafts.csswg.org/mediaqueries-5/#prefers-reduced-motion
+fn eval_prefers_reduced_motion(device: &Device, query_value: Option<PrefersReducedMotion>) -> bool {
+    let prefers_reduced = device.media_preferences().reduced_motion;
+    let query_value = match query_value {
+        Some(v) => v,
+        None => return prefers_reduced,
+    };
+
+    match query_value {
+        PrefersReducedMotion::NoPreference => !prefers_reduced,
+        PrefersReducedMotion::Reduce => prefers_reduced,
+    }
+}
+
 lazy_static! {
     /// A list with all the media features that Servo supports.
-    pub static ref MEDIA_FEATURES: [MediaFeatureDescription; 2] = [
+    pub static ref MEDIA_FEATURES: [MediaFeatureDescription; 12] = [
         feature!(
             atom!("width"),
             AllowsRanges::Yes,
             Evaluator::Length(eval_width),
             ParsingRequirements::empty(),
         ),
+        feature!(
+            atom!("height"),
+            AllowsRanges::Yes,
+            Evaluator::Length(eval_height),
+            ParsingRequirements::empty(),
+        ),
+        feature!(
+            atom!("aspect-ratio"),
+            AllowsRanges::Yes,
+            Evaluator::NumberRatio(eval_aspect_ratio),
+            ParsingRequirements::empty(),
+        ),
+        feature!(
+            atom!("orientation"),
+            AllowsRanges::No,
+            keyword_evaluator!(eval_orientation, Orientation),
+            ParsingRequirements::empty(),
+        ),
+        feature!(
+            atom!("resolution"),
+            AllowsRanges::Yes,
+            Evaluator::Resolution(eval_resolution),
+            ParsingRequirements::empty(),
+        ),
         feature!(
             atom!("scan"),
             AllowsRanges::No,
             keyword_evaluator!(eval_scan, Scan),
             ParsingRequirements::empty(),
         ),
+        feature!(
+            atom!("hover"),
+            AllowsRanges::No,
+            keyword_evaluator!(eval_hover, Hover),
+            ParsingRequirements::empty(),
+        ),
+        feature!(
+            atom!("any-hover"),
+            AllowsRanges::No,
+            keyword_evaluator!(eval_hover, Hover),
+            ParsingRequirements::empty(),
+        ),
+        feature!(
+            atom!("pointer"),
+            AllowsRanges::No,
+            keyword_evaluator!(eval_pointer, Pointer),
+            ParsingRequirements::empty(),
+        ),
+        feature!(
+            atom!("any-pointer"),
+            AllowsRanges::No,
+            keyword_evaluator!(eval_pointer, Pointer),
+            ParsingRequirements::empty(),
+        ),
+        feature!(
+            atom!("prefers-color-scheme"),
+            AllowsRanges::No,
+            keyword_evaluator!(eval_prefers_color_scheme, PrefersColorScheme),
+            ParsingRequirements::empty(),
+        ),
+        feature!(
+            atom!("prefers-reduced-motion"),
+            AllowsRanges::No,
+            keyword_evaluator!(eval_prefers_reduced_motion, PrefersReducedMotion),
+            ParsingRequirements::empty(),
+        ),
     ];
 }
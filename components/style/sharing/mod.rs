@@ -689,6 +689,15 @@ impl<E: TElement> StyleSharingCache<E> {
             return None;
         }
 
+        // `@container` rules depend on the size of the ancestors of the
+        // element, so only siblings are guaranteed to match the same ones.
+        if shared.stylist.has_container_queries() &&
+            target.element.traversal_parent() != candidate.element.traversal_parent()
+        {
+            trace!("Miss: Container queries");
+            return None;
+        }
+
         if target.local_name() != candidate.element.local_name() {
             trace!("Miss: Local Name");
             return None;
@@ -0,0 +1,507 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! A [`@container`][container] rule.
+//!
+//! [container]: https://drafts.csswg.org/css-contain-3/#container-rule
+
+use crate::context::QuirksMode;
+use crate::dom::TElement;
+use crate::logical_geometry::WritingMode;
+use crate::media_queries::media_feature_expression::consume_operation_or_colon;
+use crate::media_queries::media_feature_expression::{AspectRatio, Range, RangeOrOperator};
+use crate::media_queries::{Device, QueryCondition, QueryFeature};
+use crate::parser::{Parse, ParserContext};
+use crate::shared_lock::{DeepCloneParams, DeepCloneWithLock, Locked};
+use crate::shared_lock::{SharedRwLock, SharedRwLockReadGuard, ToCssWithGuard};
+use crate::str::{starts_with_ignore_ascii_case, CssStringWriter};
+use crate::stylesheets::CssRules;
+use crate::values::computed::{self, ToComputedValue};
+use crate::values::specified::{ContainerName, Integer, Length};
+use crate::values::{CSSFloat, CustomIdent};
+use app_units::Au;
+use cssparser::{Parser, SourceLocation};
+use euclid::default::Size2D;
+#[cfg(feature = "gecko")]
+use malloc_size_of::{MallocSizeOfOps, MallocUnconditionalShallowSizeOf};
+use servo_arc::Arc;
+use std::fmt::{self, Write};
+use style_traits::{CssWriter, ParseError, StyleParseErrorKind, ToCss};
+
+/// A [`@container`][container] rule.
+///
+/// [container]: https://drafts.csswg.org/css-contain-3/#container-rule
+#[derive(Debug, ToShmem)]
+pub struct ContainerRule {
+    /// The container query, with the name of the container it applies to.
+    pub condition: Arc<ContainerCondition>,
+    /// The nested rules of this container rule.
+    pub rules: Arc<Locked<CssRules>>,
+    /// The source position where this rule was found.
+    pub source_location: SourceLocation,
+}
+
+impl ContainerRule {
+    /// Measure heap usage.
+    #[cfg(feature = "gecko")]
+    pub fn size_of(&self, guard: &SharedRwLockReadGuard, ops: &mut MallocSizeOfOps) -> usize {
+        // Measurement of other fields may be added later.
+        self.rules.unconditional_shallow_size_of(ops) +
+            self.rules.read_with(guard).size_of(guard, ops)
+    }
+}
+
+impl ToCssWithGuard for ContainerRule {
+    fn to_css(&self, guard: &SharedRwLockReadGuard, dest: &mut CssStringWriter) -> fmt::Result {
+        dest.write_str("@container ")?;
+        self.condition.to_css(&mut CssWriter::new(dest))?;
+        self.rules.read_with(guard).to_css_block(guard, dest)
+    }
+}
+
+impl DeepCloneWithLock for ContainerRule {
+    fn deep_clone_with_lock(
+        &self,
+        lock: &SharedRwLock,
+        guard: &SharedRwLockReadGuard,
+        params: &DeepCloneParams,
+    ) -> Self {
+        let rules = self.rules.read_with(guard);
+        ContainerRule {
+            condition: self.condition.clone(),
+            rules: Arc::new(lock.wrap(rules.deep_clone_with_lock(lock, guard, params))),
+            source_location: self.source_location.clone(),
+        }
+    }
+}
+
+/// A container condition, along with the optional name of the container it
+/// should be evaluated against.
+///
+/// <https://drafts.csswg.org/css-contain-3/#container-rule>
+#[derive(Clone, Debug, MallocSizeOf, PartialEq, ToShmem)]
+pub struct ContainerCondition {
+    name: Option<CustomIdent>,
+    condition: QueryCondition<ContainerFeatureExpression>,
+}
+
+impl ToCss for ContainerCondition {
+    fn to_css<W>(&self, dest: &mut CssWriter<W>) -> fmt::Result
+    where
+        W: Write,
+    {
+        if let Some(ref name) = self.name {
+            name.to_css(dest)?;
+            dest.write_char(' ')?;
+        }
+        self.condition.to_css(dest)
+    }
+}
+
+/// The size and writing mode of a query container, as of the last layout.
+struct ContainerInfo {
+    /// The physical size of the content box of the container.
+    size: Size2D<Au>,
+    /// The writing mode of the container.
+    writing_mode: WritingMode,
+    /// Whether the container can be queried in the block axis, that is,
+    /// whether it is a `size` container rather than an `inline-size` one.
+    has_block_size: bool,
+}
+
+impl ContainerCondition {
+    /// Parses `<container-name>? <container-condition>`.
+    pub fn parse<'i, 't>(
+        context: &ParserContext,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i>> {
+        let name = input.try(ContainerName::parse_ident).ok();
+        let condition = QueryCondition::parse(context, input)?;
+        Ok(Self { name, condition })
+    }
+
+    /// The name of the container this condition applies to, if any.
+    pub fn name(&self) -> Option<&CustomIdent> {
+        self.name.as_ref()
+    }
+
+    /// The container query itself.
+    pub fn query(&self) -> &QueryCondition<ContainerFeatureExpression> {
+        &self.condition
+    }
+
+    /// Returns whether this condition matches for the given element, that is,
+    /// whether its query container exists, has been laid out, and its size
+    /// matches the condition.
+    pub fn matches<E>(&self, device: &Device, quirks_mode: QuirksMode, element: E) -> bool
+    where
+        E: TElement,
+    {
+        match self.find_container(element) {
+            Some(info) => self.matches_container(device, quirks_mode, &info),
+            None => false,
+        }
+    }
+
+    /// Returns whether this condition matches a container with the given
+    /// content box size and writing mode, to be able to test the evaluation
+    /// of container queries without an element tree.
+    pub fn matches_size_for_testing(
+        &self,
+        device: &Device,
+        quirks_mode: QuirksMode,
+        size: Size2D<Au>,
+        writing_mode: WritingMode,
+        has_block_size: bool,
+    ) -> bool {
+        let info = ContainerInfo {
+            size,
+            writing_mode,
+            has_block_size,
+        };
+        self.matches_container(device, quirks_mode, &info)
+    }
+
+    fn matches_container(
+        &self,
+        device: &Device,
+        quirks_mode: QuirksMode,
+        info: &ContainerInfo,
+    ) -> bool {
+        self.condition
+            .matches_with(&mut |f: &ContainerFeatureExpression| {
+                f.matches(device, quirks_mode, info)
+            })
+    }
+
+    /// Finds the nearest ancestor of `element` that is a query container with
+    /// the right name, and returns its size if it has already been laid out.
+    #[cfg(feature = "servo-layout-2013")]
+    fn find_container<E>(&self, element: E) -> Option<ContainerInfo>
+    where
+        E: TElement,
+    {
+        use crate::computed_values::container_type::T as ContainerType;
+
+        let mut current = element.traversal_parent();
+        while let Some(ancestor) = current {
+            current = ancestor.traversal_parent();
+
+            let data = match ancestor.borrow_data() {
+                Some(data) => data,
+                None => continue,
+            };
+            let style = match data.styles.get_primary() {
+                Some(style) => style,
+                None => continue,
+            };
+
+            let box_style = style.get_box();
+            if box_style.container_type == ContainerType::Normal {
+                continue;
+            }
+            if let Some(ref name) = self.name {
+                if !box_style.container_name.0.iter().any(|n| n == name) {
+                    continue;
+                }
+            }
+
+            // A container that hasn't been laid out yet can't match anything.
+            let size = ancestor.query_container_size()?;
+            return Some(ContainerInfo {
+                size,
+                writing_mode: style.writing_mode,
+                has_block_size: box_style.container_type == ContainerType::Size,
+            });
+        }
+        None
+    }
+
+    /// Query containers are only supported by Servo's 2013 layout engine.
+    #[cfg(not(feature = "servo-layout-2013"))]
+    fn find_container<E>(&self, _: E) -> Option<ContainerInfo>
+    where
+        E: TElement,
+    {
+        None
+    }
+}
+
+/// A size feature that can be queried on a container.
+///
+/// <https://drafts.csswg.org/css-contain-3/#container-size-query>
+#[derive(Clone, Copy, Debug, Eq, MallocSizeOf, PartialEq, ToShmem)]
+enum ContainerSizeFeature {
+    Width,
+    Height,
+    InlineSize,
+    BlockSize,
+    AspectRatio,
+    Orientation,
+}
+
+impl ContainerSizeFeature {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match_ignore_ascii_case! { name,
+            "width" => ContainerSizeFeature::Width,
+            "height" => ContainerSizeFeature::Height,
+            "inline-size" => ContainerSizeFeature::InlineSize,
+            "block-size" => ContainerSizeFeature::BlockSize,
+            "aspect-ratio" => ContainerSizeFeature::AspectRatio,
+            "orientation" => ContainerSizeFeature::Orientation,
+            _ => return None,
+        })
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ContainerSizeFeature::Width => "width",
+            ContainerSizeFeature::Height => "height",
+            ContainerSizeFeature::InlineSize => "inline-size",
+            ContainerSizeFeature::BlockSize => "block-size",
+            ContainerSizeFeature::AspectRatio => "aspect-ratio",
+            ContainerSizeFeature::Orientation => "orientation",
+        }
+    }
+
+    fn allows_ranges(self) -> bool {
+        self != ContainerSizeFeature::Orientation
+    }
+}
+
+/// The `orientation` container feature values.
+#[derive(Clone, Copy, Debug, Eq, MallocSizeOf, Parse, PartialEq, ToCss, ToShmem)]
+enum Orientation {
+    Portrait,
+    Landscape,
+}
+
+/// A value found in a container size feature expression.
+#[derive(Clone, Debug, MallocSizeOf, PartialEq, ToShmem)]
+enum ContainerFeatureValue {
+    Length(Length),
+    Ratio(AspectRatio),
+    Orientation(Orientation),
+}
+
+impl ContainerFeatureValue {
+    fn parse<'i, 't>(
+        feature: ContainerSizeFeature,
+        context: &ParserContext,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i>> {
+        Ok(match feature {
+            ContainerSizeFeature::Width |
+            ContainerSizeFeature::Height |
+            ContainerSizeFeature::InlineSize |
+            ContainerSizeFeature::BlockSize => {
+                ContainerFeatureValue::Length(Length::parse_non_negative(context, input)?)
+            },
+            ContainerSizeFeature::AspectRatio => {
+                let a = Integer::parse_positive(context, input)?;
+                input.expect_delim('/')?;
+                let b = Integer::parse_positive(context, input)?;
+                ContainerFeatureValue::Ratio(AspectRatio(
+                    a.value() as CSSFloat,
+                    b.value() as CSSFloat,
+                ))
+            },
+            ContainerSizeFeature::Orientation => {
+                ContainerFeatureValue::Orientation(Orientation::parse(context, input)?)
+            },
+        })
+    }
+}
+
+impl ToCss for ContainerFeatureValue {
+    fn to_css<W>(&self, dest: &mut CssWriter<W>) -> fmt::Result
+    where
+        W: Write,
+    {
+        match *self {
+            ContainerFeatureValue::Length(ref l) => l.to_css(dest),
+            ContainerFeatureValue::Ratio(ref r) => r.to_css(dest),
+            ContainerFeatureValue::Orientation(ref o) => o.to_css(dest),
+        }
+    }
+}
+
+/// A container size feature expression, like `(width > 400px)` or
+/// `(min-inline-size: 20em)`.
+#[derive(Clone, Debug, MallocSizeOf, PartialEq, ToShmem)]
+pub struct ContainerFeatureExpression {
+    feature: ContainerSizeFeature,
+    value: Option<ContainerFeatureValue>,
+    range_or_operator: Option<RangeOrOperator>,
+}
+
+impl ToCss for ContainerFeatureExpression {
+    fn to_css<W>(&self, dest: &mut CssWriter<W>) -> fmt::Result
+    where
+        W: Write,
+    {
+        dest.write_char('(')?;
+
+        if let Some(RangeOrOperator::Range(range)) = self.range_or_operator {
+            match range {
+                Range::Min => dest.write_str("min-")?,
+                Range::Max => dest.write_str("max-")?,
+            }
+        }
+
+        dest.write_str(self.feature.name())?;
+
+        if let Some(RangeOrOperator::Operator(op)) = self.range_or_operator {
+            dest.write_char(' ')?;
+            op.to_css(dest)?;
+            dest.write_char(' ')?;
+        } else if self.value.is_some() {
+            dest.write_str(": ")?;
+        }
+
+        if let Some(ref value) = self.value {
+            value.to_css(dest)?;
+        }
+
+        dest.write_char(')')
+    }
+}
+
+impl QueryFeature for ContainerFeatureExpression {
+    fn parse_in_parenthesis_block<'i, 't>(
+        context: &ParserContext,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i>> {
+        let (feature, range) = {
+            let location = input.current_source_location();
+            let ident = input.expect_ident()?;
+
+            let mut name = &**ident;
+            let range = if starts_with_ignore_ascii_case(name, "min-") {
+                name = &name[4..];
+                Some(Range::Min)
+            } else if starts_with_ignore_ascii_case(name, "max-") {
+                name = &name[4..];
+                Some(Range::Max)
+            } else {
+                None
+            };
+
+            let feature = match ContainerSizeFeature::from_name(name) {
+                Some(feature) if range.is_none() || feature.allows_ranges() => feature,
+                _ => {
+                    return Err(location.new_custom_error(
+                        StyleParseErrorKind::MediaQueryExpectedFeatureName(ident.clone()),
+                    ));
+                },
+            };
+            (feature, range)
+        };
+
+        let operator = match input.try(consume_operation_or_colon) {
+            Ok(operator) => operator,
+            Err(..) => {
+                // A boolean context query, like `(width)`.
+                if range.is_some() {
+                    return Err(
+                        input.new_custom_error(StyleParseErrorKind::RangedExpressionWithNoValue)
+                    );
+                }
+                return Ok(Self {
+                    feature,
+                    value: None,
+                    range_or_operator: None,
+                });
+            },
+        };
+
+        let range_or_operator = match (range, operator) {
+            (Some(..), Some(..)) => {
+                return Err(
+                    input.new_custom_error(StyleParseErrorKind::MediaQueryUnexpectedOperator)
+                );
+            },
+            (Some(range), None) => Some(RangeOrOperator::Range(range)),
+            (None, Some(operator)) => {
+                if !feature.allows_ranges() {
+                    return Err(
+                        input.new_custom_error(StyleParseErrorKind::MediaQueryUnexpectedOperator)
+                    );
+                }
+                Some(RangeOrOperator::Operator(operator))
+            },
+            (None, None) => None,
+        };
+
+        let value = ContainerFeatureValue::parse(feature, context, input).map_err(|err| {
+            err.location
+                .new_custom_error(StyleParseErrorKind::MediaQueryExpectedFeatureValue)
+        })?;
+
+        Ok(Self {
+            feature,
+            value: Some(value),
+            range_or_operator,
+        })
+    }
+}
+
+impl ContainerFeatureExpression {
+    /// Evaluates this expression against the given container.
+    ///
+    /// Features in the block axis of an `inline-size` container are unknown,
+    /// and thus never match.
+    fn matches(&self, device: &Device, quirks_mode: QuirksMode, info: &ContainerInfo) -> bool {
+        let vertical = info.writing_mode.is_vertical();
+        let (inline_size, block_size) = if vertical {
+            (info.size.height, info.size.width)
+        } else {
+            (info.size.width, info.size.height)
+        };
+
+        let length = match self.feature {
+            ContainerSizeFeature::Width if !vertical || info.has_block_size => info.size.width,
+            ContainerSizeFeature::Height if vertical || info.has_block_size => info.size.height,
+            ContainerSizeFeature::InlineSize => inline_size,
+            ContainerSizeFeature::BlockSize if info.has_block_size => block_size,
+            ContainerSizeFeature::AspectRatio | ContainerSizeFeature::Orientation
+                if info.has_block_size =>
+            {
+                return self.matches_ratio(info.size);
+            },
+            _ => return false,
+        };
+
+        let query_value = match self.value {
+            Some(ContainerFeatureValue::Length(ref specified)) => Some(Au::from(
+                computed::Context::for_media_query_evaluation(device, quirks_mode, |context| {
+                    specified.to_computed_value(context)
+                }),
+            )),
+            Some(..) => unreachable!("Unexpected value for a length feature"),
+            None => None,
+        };
+        RangeOrOperator::evaluate(self.range_or_operator, query_value, length)
+    }
+
+    fn matches_ratio(&self, size: Size2D<Au>) -> bool {
+        match self.value {
+            Some(ContainerFeatureValue::Ratio(query_value)) => {
+                let value = AspectRatio(size.width.0 as f32, size.height.0 as f32);
+                RangeOrOperator::evaluate_with_query_value(
+                    self.range_or_operator,
+                    query_value,
+                    value,
+                )
+            },
+            // Per spec, square containers are `portrait`.
+            Some(ContainerFeatureValue::Orientation(orientation)) => match orientation {
+                Orientation::Portrait => size.height >= size.width,
+                Orientation::Landscape => size.width > size.height,
+            },
+            Some(..) => unreachable!("Unexpected value for a ratio feature"),
+            None => true,
+        }
+    }
+}
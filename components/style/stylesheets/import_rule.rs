@@ -8,15 +8,17 @@
 
 use crate::context::QuirksMode;
 use crate::media_queries::MediaList;
+use crate::parser::{Parse, ParserContext};
 use crate::shared_lock::{DeepCloneParams, DeepCloneWithLock};
 use crate::shared_lock::{SharedRwLock, SharedRwLockReadGuard, ToCssWithGuard};
 use crate::str::CssStringWriter;
+use crate::stylesheets::layer_rule::LayerName;
 use crate::stylesheets::{CssRule, Origin, StylesheetInDocument};
 use crate::values::CssUrl;
-use cssparser::SourceLocation;
+use cssparser::{Parser, SourceLocation};
 use std::fmt::{self, Write};
 use std::mem::ManuallyDrop;
-use style_traits::{CssWriter, ToCss};
+use style_traits::{CssWriter, ParseError, ToCss};
 use to_shmem::{SharedMemoryBuilder, ToShmem};
 
 /// With asynchronous stylesheet parsing, we can't synchronously create a
@@ -166,6 +168,46 @@ impl DeepCloneWithLock for ImportSheet {
     }
 }
 
+/// The layer an `@import` rule puts its stylesheet in.
+///
+/// <https://drafts.csswg.org/css-cascade-5/#at-import>
+#[derive(Clone, Debug)]
+pub struct ImportLayer {
+    /// The layer name, or `None` for an anonymous layer.
+    pub name: Option<LayerName>,
+}
+
+impl Parse for ImportLayer {
+    fn parse<'i, 't>(
+        context: &ParserContext,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i>> {
+        if input.try(|i| i.expect_ident_matching("layer")).is_ok() {
+            return Ok(ImportLayer { name: None });
+        }
+
+        input.expect_function_matching("layer")?;
+        let name = input.parse_nested_block(|input| LayerName::parse(context, input))?;
+        Ok(ImportLayer { name: Some(name) })
+    }
+}
+
+impl ToCss for ImportLayer {
+    fn to_css<W>(&self, dest: &mut CssWriter<W>) -> fmt::Result
+    where
+        W: Write,
+    {
+        match self.name {
+            None => dest.write_str("layer"),
+            Some(ref name) => {
+                dest.write_str("layer(")?;
+                name.to_css(dest)?;
+                dest.write_char(')')
+            },
+        }
+    }
+}
+
 /// The [`@import`][import] at-rule.
 ///
 /// [import]: https://drafts.csswg.org/css-cascade-3/#at-import
@@ -179,6 +221,9 @@ pub struct ImportRule {
     /// ImportSheet just has stub behavior until it appears.
     pub stylesheet: ImportSheet,
 
+    /// The layer this rule's stylesheet is put in, if any.
+    pub layer: Option<ImportLayer>,
+
     /// The line and column of the rule's source code.
     pub source_location: SourceLocation,
 }
@@ -199,6 +244,7 @@ impl DeepCloneWithLock for ImportRule {
         ImportRule {
             url: self.url.clone(),
             stylesheet: self.stylesheet.deep_clone_with_lock(lock, guard, params),
+            layer: self.layer.clone(),
             source_location: self.source_location.clone(),
         }
     }
@@ -209,6 +255,11 @@ impl ToCssWithGuard for ImportRule {
         dest.write_str("@import ")?;
         self.url.to_css(&mut CssWriter::new(dest))?;
 
+        if let Some(ref layer) = self.layer {
+            dest.write_char(' ')?;
+            layer.to_css(&mut CssWriter::new(dest))?;
+        }
+
         match self.stylesheet.media(guard) {
             Some(media) if !media.is_empty() => {
                 dest.write_str(" ")?;
@@ -4,6 +4,7 @@
 
 //! Keyframes: https://drafts.csswg.org/css-animations/#keyframes
 
+use crate::custom_properties::Name;
 use crate::error_reporting::ContextualParseError;
 use crate::parser::ParserContext;
 use crate::properties::longhands::transition_timing_function::single_value::SpecifiedValue as SpecifiedTimingFunction;
@@ -160,6 +161,12 @@ impl KeyframeSelector {
         &self.0
     }
 
+    /// Create a keyframe selector for a single offset, as used by keyframes
+    /// that come from script instead of a stylesheet.
+    pub fn from_percentage(percentage: KeyframePercentage) -> KeyframeSelector {
+        KeyframeSelector(vec![percentage])
+    }
+
     /// A dummy public function so we can write a unit test for this.
     pub fn new_for_unit_testing(percentages: Vec<KeyframePercentage>) -> KeyframeSelector {
         KeyframeSelector(percentages)
@@ -353,16 +360,20 @@ pub struct KeyframesAnimation {
     pub steps: Vec<KeyframesStep>,
     /// The properties that change in this animation.
     pub properties_changed: LonghandIdSet,
+    /// The custom properties that change in this animation.
+    pub custom_properties_changed: Vec<Name>,
     /// Vendor prefix type the @keyframes has.
     pub vendor_prefix: Option<VendorPrefix>,
 }
 
-/// Get all the animated properties in a keyframes animation.
+/// Get all the animated properties in a keyframes animation, and the custom
+/// properties it changes.
 fn get_animated_properties(
     keyframes: &[Arc<Locked<Keyframe>>],
     guard: &SharedRwLockReadGuard,
-) -> LonghandIdSet {
+) -> (LonghandIdSet, Vec<Name>) {
     let mut ret = LonghandIdSet::new();
+    let mut custom_properties = vec![];
     // NB: declarations are already deduplicated, so we don't have to check for
     // it here.
     for keyframe in keyframes {
@@ -377,7 +388,13 @@ fn get_animated_properties(
         for declaration in block.normal_declaration_iter() {
             let longhand_id = match declaration.id() {
                 PropertyDeclarationId::Longhand(id) => id,
-                _ => continue,
+                PropertyDeclarationId::Custom(name) => {
+                    // Declarations are only deduplicated within a keyframe.
+                    if !custom_properties.contains(name) {
+                        custom_properties.push(name.clone());
+                    }
+                    continue;
+                },
             };
 
             if longhand_id == LonghandId::Display {
@@ -392,7 +409,7 @@ fn get_animated_properties(
         }
     }
 
-    ret
+    (ret, custom_properties)
 }
 
 impl KeyframesAnimation {
@@ -400,7 +417,7 @@ impl KeyframesAnimation {
     ///
     /// This will return a keyframe animation with empty steps and
     /// properties_changed if the list of keyframes is empty, or there are no
-    /// animated properties obtained from the keyframes.
+    /// animated properties or custom properties obtained from the keyframes.
     ///
     /// Otherwise, this will compute and sort the steps used for the animation,
     /// and return the animation object.
@@ -412,6 +429,7 @@ impl KeyframesAnimation {
         let mut result = KeyframesAnimation {
             steps: vec![],
             properties_changed: LonghandIdSet::new(),
+            custom_properties_changed: vec![],
             vendor_prefix,
         };
 
@@ -419,8 +437,11 @@ impl KeyframesAnimation {
             return result;
         }
 
-        result.properties_changed = get_animated_properties(keyframes, guard);
-        if result.properties_changed.is_empty() {
+        let (properties_changed, custom_properties_changed) =
+            get_animated_properties(keyframes, guard);
+        result.properties_changed = properties_changed;
+        result.custom_properties_changed = custom_properties_changed;
+        if result.properties_changed.is_empty() && result.custom_properties_changed.is_empty() {
             return result;
         }
 
@@ -0,0 +1,195 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! A [`@layer`][layer] rule.
+//!
+//! [layer]: https://drafts.csswg.org/css-cascade-5/#layering
+
+use crate::parser::{Parse, ParserContext};
+use crate::shared_lock::{DeepCloneParams, DeepCloneWithLock, Locked};
+use crate::shared_lock::{SharedRwLock, SharedRwLockReadGuard, ToCssWithGuard};
+use crate::str::CssStringWriter;
+use crate::stylesheets::CssRules;
+use crate::values::CustomIdent;
+use cssparser::{Parser, SourceLocation, Token};
+#[cfg(feature = "gecko")]
+use malloc_size_of::{MallocSizeOfOps, MallocUnconditionalShallowSizeOf};
+use servo_arc::Arc;
+use smallvec::SmallVec;
+use std::fmt::{self, Write};
+use style_traits::{CssWriter, ParseError, ToCss};
+
+/// The order of a given layer in the cascade.
+///
+/// We use 16 bits, which should be plenty, so that this can be cheaply stored
+/// alongside applicable declarations.
+#[derive(Clone, Copy, Debug, Eq, Hash, MallocSizeOf, Ord, PartialEq, PartialOrd)]
+pub struct LayerOrder(u16);
+
+impl LayerOrder {
+    /// The order of the root layer, that is, of the rules that are not in any
+    /// layer, and thus win over all the layered rules.
+    #[inline]
+    pub fn root() -> Self {
+        LayerOrder(std::u16::MAX)
+    }
+
+    /// The order of the first layer.
+    #[inline]
+    pub fn first() -> Self {
+        LayerOrder(0)
+    }
+
+    /// Increments the layer order, saturating before reaching the root one.
+    #[inline]
+    pub fn inc(&mut self) {
+        if self.0 != std::u16::MAX - 1 {
+            self.0 += 1;
+        }
+    }
+}
+
+/// A `<layer-name>`, a list of identifiers separated by dots.
+///
+/// <https://drafts.csswg.org/css-cascade-5/#typedef-layer-name>
+#[derive(Clone, Debug, Eq, PartialEq, ToShmem)]
+pub struct LayerName(pub SmallVec<[CustomIdent; 1]>);
+
+impl LayerName {
+    /// Returns the individual identifiers of this name, from the outermost to
+    /// the innermost layer.
+    #[inline]
+    pub fn layer_names(&self) -> &[CustomIdent] {
+        &self.0
+    }
+}
+
+impl Parse for LayerName {
+    fn parse<'i, 't>(
+        _: &ParserContext,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i>> {
+        let mut result = SmallVec::new();
+        let location = input.current_source_location();
+        result.push(CustomIdent::from_ident(
+            location,
+            input.expect_ident()?,
+            &[],
+        )?);
+        loop {
+            // No whitespace is allowed around the dots.
+            let ident = input.try(|input| -> Result<CustomIdent, ParseError<'i>> {
+                let location = input.current_source_location();
+                match *input.next_including_whitespace()? {
+                    Token::Delim('.') => {},
+                    ref t => return Err(location.new_unexpected_token_error(t.clone())),
+                }
+                let location = input.current_source_location();
+                match *input.next_including_whitespace()? {
+                    Token::Ident(ref ident) => CustomIdent::from_ident(location, ident, &[]),
+                    ref t => Err(location.new_unexpected_token_error(t.clone())),
+                }
+            });
+            match ident {
+                Ok(ident) => result.push(ident),
+                Err(..) => break,
+            }
+        }
+        Ok(LayerName(result))
+    }
+}
+
+impl ToCss for LayerName {
+    fn to_css<W>(&self, dest: &mut CssWriter<W>) -> fmt::Result
+    where
+        W: Write,
+    {
+        let mut first = true;
+        for name in self.0.iter() {
+            if !first {
+                dest.write_char('.')?;
+            }
+            first = false;
+            name.to_css(dest)?;
+        }
+        Ok(())
+    }
+}
+
+/// A [`@layer`][layer] block rule, with an optional name.
+///
+/// [layer]: https://drafts.csswg.org/css-cascade-5/#layer-block
+#[derive(Debug, ToShmem)]
+pub struct LayerBlockRule {
+    /// The name of the layer, or `None` for an anonymous layer.
+    pub name: Option<LayerName>,
+    /// The nested rules of this layer.
+    pub rules: Arc<Locked<CssRules>>,
+    /// The source position where this rule was found.
+    pub source_location: SourceLocation,
+}
+
+impl LayerBlockRule {
+    /// Measure heap usage.
+    #[cfg(feature = "gecko")]
+    pub fn size_of(&self, guard: &SharedRwLockReadGuard, ops: &mut MallocSizeOfOps) -> usize {
+        // Measurement of other fields may be added later.
+        self.rules.unconditional_shallow_size_of(ops) +
+            self.rules.read_with(guard).size_of(guard, ops)
+    }
+}
+
+impl ToCssWithGuard for LayerBlockRule {
+    fn to_css(&self, guard: &SharedRwLockReadGuard, dest: &mut CssStringWriter) -> fmt::Result {
+        dest.write_str("@layer")?;
+        if let Some(ref name) = self.name {
+            dest.write_char(' ')?;
+            name.to_css(&mut CssWriter::new(dest))?;
+        }
+        self.rules.read_with(guard).to_css_block(guard, dest)
+    }
+}
+
+impl DeepCloneWithLock for LayerBlockRule {
+    fn deep_clone_with_lock(
+        &self,
+        lock: &SharedRwLock,
+        guard: &SharedRwLockReadGuard,
+        params: &DeepCloneParams,
+    ) -> Self {
+        let rules = self.rules.read_with(guard);
+        LayerBlockRule {
+            name: self.name.clone(),
+            rules: Arc::new(lock.wrap(rules.deep_clone_with_lock(lock, guard, params))),
+            source_location: self.source_location.clone(),
+        }
+    }
+}
+
+/// A [`@layer`][layer] statement rule, which declares the order of one or
+/// more layers.
+///
+/// [layer]: https://drafts.csswg.org/css-cascade-5/#layer-empty
+#[derive(Clone, Debug, ToShmem)]
+pub struct LayerStatementRule {
+    /// The list of layers, in order.
+    pub names: Vec<LayerName>,
+    /// The source position where this rule was found.
+    pub source_location: SourceLocation,
+}
+
+impl ToCssWithGuard for LayerStatementRule {
+    fn to_css(&self, _: &SharedRwLockReadGuard, dest: &mut CssStringWriter) -> fmt::Result {
+        dest.write_str("@layer ")?;
+        let mut first = true;
+        for name in self.names.iter() {
+            if !first {
+                dest.write_str(", ")?;
+            }
+            first = false;
+            name.to_css(&mut CssWriter::new(dest))?;
+        }
+        dest.write_char(';')
+    }
+}
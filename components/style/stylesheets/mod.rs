@@ -4,17 +4,20 @@
 
 //! Style sheets and their CSS rules.
 
+pub mod container_rule;
 mod counter_style_rule;
 mod document_rule;
 mod font_face_rule;
 pub mod font_feature_values_rule;
 pub mod import_rule;
 pub mod keyframes_rule;
+pub mod layer_rule;
 mod loader;
 mod media_rule;
 mod namespace_rule;
 pub mod origin;
 mod page_rule;
+pub mod property_rule;
 mod rule_list;
 mod rule_parser;
 mod rules_iterator;
@@ -31,6 +34,7 @@ use crate::parser::ParserContext;
 use crate::shared_lock::{DeepCloneParams, DeepCloneWithLock, Locked};
 use crate::shared_lock::{SharedRwLock, SharedRwLockReadGuard, ToCssWithGuard};
 use crate::str::CssStringWriter;
+use crate::stylesheets::rule_parser::parse_layer_rule;
 use cssparser::{parse_one_rule, Parser, ParserInput};
 #[cfg(feature = "gecko")]
 use malloc_size_of::{MallocSizeOfOps, MallocUnconditionalShallowSizeOf};
@@ -42,20 +46,23 @@ use style_traits::ParsingMode;
 #[cfg(feature = "gecko")]
 use to_shmem::{SharedMemoryBuilder, ToShmem};
 
+pub use self::container_rule::ContainerRule;
 pub use self::counter_style_rule::CounterStyleRule;
 pub use self::document_rule::DocumentRule;
 pub use self::font_face_rule::FontFaceRule;
 pub use self::font_feature_values_rule::FontFeatureValuesRule;
 pub use self::import_rule::ImportRule;
 pub use self::keyframes_rule::KeyframesRule;
+pub use self::layer_rule::{LayerBlockRule, LayerStatementRule};
 pub use self::loader::StylesheetLoader;
 pub use self::media_rule::MediaRule;
 pub use self::namespace_rule::NamespaceRule;
 pub use self::origin::{Origin, OriginSet, OriginSetIterator, PerOrigin, PerOriginIter};
 pub use self::page_rule::PageRule;
+pub use self::property_rule::PropertyRule;
 pub use self::rule_list::{CssRules, CssRulesHelpers};
 pub use self::rule_parser::{InsertRuleContext, State, TopLevelRuleParser};
-pub use self::rules_iterator::{AllRules, EffectiveRules};
+pub use self::rules_iterator::{AllRules, EffectiveRules, EffectiveRulesIterator};
 pub use self::rules_iterator::{NestedRuleIterationCondition, RulesIterator};
 pub use self::style_rule::StyleRule;
 pub use self::stylesheet::{DocumentStyleSheet, Namespaces, Stylesheet};
@@ -248,6 +255,10 @@ pub enum CssRule {
     Supports(Arc<Locked<SupportsRule>>),
     Page(Arc<Locked<PageRule>>),
     Document(Arc<Locked<DocumentRule>>),
+    LayerBlock(Arc<Locked<LayerBlockRule>>),
+    LayerStatement(Arc<Locked<LayerStatementRule>>),
+    Container(Arc<Locked<ContainerRule>>),
+    Property(Arc<Locked<PropertyRule>>),
 }
 
 impl CssRule {
@@ -288,6 +299,18 @@ impl CssRule {
             CssRule::Document(ref lock) => {
                 lock.unconditional_shallow_size_of(ops) + lock.read_with(guard).size_of(guard, ops)
             },
+
+            CssRule::LayerBlock(ref lock) => {
+                lock.unconditional_shallow_size_of(ops) + lock.read_with(guard).size_of(guard, ops)
+            },
+
+            CssRule::LayerStatement(_) => 0,
+
+            CssRule::Container(ref lock) => {
+                lock.unconditional_shallow_size_of(ops) + lock.read_with(guard).size_of(guard, ops)
+            },
+
+            CssRule::Property(_) => 0,
         }
     }
 }
@@ -318,6 +341,14 @@ pub enum CssRuleType {
     FontFeatureValues = 14,
     // https://drafts.csswg.org/css-device-adapt/#css-rule-interface
     Viewport = 15,
+    // https://drafts.csswg.org/css-cascade-5/#the-csslayerblockrule-interface
+    LayerBlock = 16,
+    // https://drafts.csswg.org/css-cascade-5/#the-csslayerstatementrule-interface
+    LayerStatement = 17,
+    // https://drafts.csswg.org/css-contain-3/#the-csscontainerrule-interface
+    Container = 18,
+    // https://drafts.css-houdini.org/css-properties-values-api/#the-css-property-rule-interface
+    Property = 19,
 }
 
 #[allow(missing_docs)]
@@ -344,6 +375,10 @@ impl CssRule {
             CssRule::Supports(_) => CssRuleType::Supports,
             CssRule::Page(_) => CssRuleType::Page,
             CssRule::Document(_) => CssRuleType::Document,
+            CssRule::LayerBlock(_) => CssRuleType::LayerBlock,
+            CssRule::LayerStatement(_) => CssRuleType::LayerStatement,
+            CssRule::Container(_) => CssRuleType::Container,
+            CssRule::Property(_) => CssRuleType::Property,
         }
     }
 
@@ -367,6 +402,7 @@ impl CssRule {
         parent_stylesheet_contents: &StylesheetContents,
         shared_lock: &SharedRwLock,
         state: State,
+        in_style_rule: bool,
         loader: Option<&dyn StylesheetLoader>,
     ) -> Result<Self, RulesMutateError> {
         let url_data = parent_stylesheet_contents.url_data.read();
@@ -394,10 +430,18 @@ impl CssRule {
             dom_error: None,
             namespaces: &mut *guard,
             insert_rule_context: Some(insert_rule_context),
+            in_style_rule,
         };
 
-        parse_one_rule(&mut input, &mut rule_parser)
-            .map_err(|_| rule_parser.dom_error.unwrap_or(RulesMutateError::Syntax))
+        let result = match parse_layer_rule(&mut input, &mut rule_parser) {
+            Some(Ok(rule)) => {
+                input.skip_whitespace();
+                input.expect_exhausted().map(|()| rule).map_err(|_| ())
+            },
+            Some(Err(..)) => Err(()),
+            None => parse_one_rule(&mut input, &mut rule_parser).map_err(|_| ()),
+        };
+        result.map_err(|()| rule_parser.dom_error.unwrap_or(RulesMutateError::Syntax))
     }
 }
 
@@ -472,6 +516,26 @@ impl DeepCloneWithLock for CssRule {
                     lock.wrap(rule.deep_clone_with_lock(lock, guard, params)),
                 ))
             },
+            CssRule::LayerBlock(ref arc) => {
+                let rule = arc.read_with(guard);
+                CssRule::LayerBlock(Arc::new(
+                    lock.wrap(rule.deep_clone_with_lock(lock, guard, params)),
+                ))
+            },
+            CssRule::LayerStatement(ref arc) => {
+                let rule = arc.read_with(guard);
+                CssRule::LayerStatement(Arc::new(lock.wrap(rule.clone())))
+            },
+            CssRule::Container(ref arc) => {
+                let rule = arc.read_with(guard);
+                CssRule::Container(Arc::new(
+                    lock.wrap(rule.deep_clone_with_lock(lock, guard, params)),
+                ))
+            },
+            CssRule::Property(ref arc) => {
+                let rule = arc.read_with(guard);
+                CssRule::Property(Arc::new(lock.wrap(rule.clone())))
+            },
         }
     }
 }
@@ -492,6 +556,10 @@ impl ToCssWithGuard for CssRule {
             CssRule::Supports(ref lock) => lock.read_with(guard).to_css(guard, dest),
             CssRule::Page(ref lock) => lock.read_with(guard).to_css(guard, dest),
             CssRule::Document(ref lock) => lock.read_with(guard).to_css(guard, dest),
+            CssRule::LayerBlock(ref lock) => lock.read_with(guard).to_css(guard, dest),
+            CssRule::LayerStatement(ref lock) => lock.read_with(guard).to_css(guard, dest),
+            CssRule::Container(ref lock) => lock.read_with(guard).to_css(guard, dest),
+            CssRule::Property(ref lock) => lock.read_with(guard).to_css(guard, dest),
         }
     }
 }
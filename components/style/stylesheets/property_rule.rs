@@ -0,0 +1,164 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! The [`@property`][property] rule.
+//!
+//! [property]: https://drafts.css-houdini.org/css-properties-values-api/#at-property-rule
+
+use crate::custom_properties::{Name, VariableValue};
+use crate::error_reporting::ContextualParseError;
+use crate::parser::ParserContext;
+use crate::properties_and_values::{PropertyRegistration, Syntax};
+use crate::shared_lock::{SharedRwLockReadGuard, ToCssWithGuard};
+use crate::str::CssStringWriter;
+use crate::values::serialize_atom_name;
+use cssparser::{serialize_string, AtRuleParser, CowRcStr, DeclarationListParser};
+use cssparser::{DeclarationParser, Parser, SourceLocation};
+use selectors::parser::SelectorParseErrorKind;
+use servo_arc::Arc;
+use std::fmt::{self, Write};
+use style_traits::{CssWriter, ParseError, StyleParseErrorKind, ToCss};
+
+/// A [`@property`][property] rule, that registers a custom property.
+///
+/// Invalid rules are dropped at parse time, so this always holds a valid
+/// registration.
+///
+/// [property]: https://drafts.css-houdini.org/css-properties-values-api/#at-property-rule
+#[derive(Clone, Debug, ToShmem)]
+pub struct PropertyRule {
+    /// The custom property registration of this rule.
+    pub registration: Arc<PropertyRegistration>,
+    /// The source position this rule was found at.
+    pub source_location: SourceLocation,
+}
+
+/// The descriptors found in the block of a `@property` rule.
+#[derive(Default)]
+struct PropertyDescriptors {
+    syntax: Option<Syntax>,
+    inherits: Option<bool>,
+    initial_value: Option<Arc<VariableValue>>,
+}
+
+struct PropertyRuleParser<'a> {
+    descriptors: &'a mut PropertyDescriptors,
+}
+
+/// Default methods reject all at rules.
+impl<'a, 'i> AtRuleParser<'i> for PropertyRuleParser<'a> {
+    type PreludeNoBlock = ();
+    type PreludeBlock = ();
+    type AtRule = ();
+    type Error = StyleParseErrorKind<'i>;
+}
+
+impl<'a, 'i> DeclarationParser<'i> for PropertyRuleParser<'a> {
+    type Declaration = ();
+    type Error = StyleParseErrorKind<'i>;
+
+    fn parse_value<'t>(
+        &mut self,
+        name: CowRcStr<'i>,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<(), ParseError<'i>> {
+        match_ignore_ascii_case! { &*name,
+            "syntax" => {
+                let location = input.current_source_location();
+                let syntax = input.expect_string()?;
+                let syntax = Syntax::parse(syntax).map_err(|()| {
+                    location.new_custom_error(StyleParseErrorKind::UnspecifiedError)
+                })?;
+                self.descriptors.syntax = Some(syntax);
+            },
+            "inherits" => {
+                let location = input.current_source_location();
+                let ident = input.expect_ident()?;
+                let inherits = match_ignore_ascii_case! { &**ident,
+                    "true" => true,
+                    "false" => false,
+                    _ => return Err(location.new_custom_error(
+                        SelectorParseErrorKind::UnexpectedIdent(ident.clone())
+                    )),
+                };
+                self.descriptors.inherits = Some(inherits);
+            },
+            "initial-value" => {
+                self.descriptors.initial_value = Some(VariableValue::parse(input)?);
+            },
+            _ => return Err(input.new_custom_error(SelectorParseErrorKind::UnexpectedIdent(name.clone()))),
+        }
+        Ok(())
+    }
+}
+
+impl PropertyRule {
+    /// Parses the block of a `@property` rule for the custom property `name`.
+    ///
+    /// Fails if the `syntax` or `inherits` descriptors are missing, or if the
+    /// initial value is not valid for the syntax.
+    pub fn parse_block<'i, 't>(
+        context: &ParserContext,
+        input: &mut Parser<'i, 't>,
+        name: Name,
+        source_location: SourceLocation,
+    ) -> Result<Self, ParseError<'i>> {
+        let mut descriptors = PropertyDescriptors::default();
+        {
+            let parser = PropertyRuleParser {
+                descriptors: &mut descriptors,
+            };
+            let mut iter = DeclarationListParser::new(input, parser);
+            while let Some(declaration) = iter.next() {
+                if let Err((error, slice)) = declaration {
+                    let location = error.location;
+                    let error = ContextualParseError::UnsupportedPropertyDescriptor(slice, error);
+                    context.log_css_error(location, error);
+                }
+            }
+        }
+
+        let registration = match (descriptors.syntax, descriptors.inherits) {
+            (Some(syntax), Some(inherits)) => PropertyRegistration::new(
+                name,
+                syntax,
+                inherits,
+                descriptors.initial_value,
+                context.url_data.clone(),
+                context.quirks_mode,
+            )
+            .ok(),
+            _ => None,
+        };
+
+        match registration {
+            Some(registration) => Ok(PropertyRule {
+                registration: Arc::new(registration),
+                source_location,
+            }),
+            None => Err(input.new_custom_error(StyleParseErrorKind::UnspecifiedError)),
+        }
+    }
+}
+
+impl ToCssWithGuard for PropertyRule {
+    fn to_css(&self, _guard: &SharedRwLockReadGuard, dest: &mut CssStringWriter) -> fmt::Result {
+        let registration = &*self.registration;
+        dest.write_str("@property --")?;
+        serialize_atom_name(&registration.name, dest)?;
+        dest.write_str(" { syntax: ")?;
+        serialize_string(&registration.syntax.to_css_string(), dest)?;
+        dest.write_str("; inherits: ")?;
+        dest.write_str(if registration.inherits {
+            "true"
+        } else {
+            "false"
+        })?;
+        if let Some(ref initial_value) = registration.initial_value {
+            dest.write_str("; initial-value: ")?;
+            initial_value.to_css(&mut CssWriter::new(dest))?;
+        }
+        dest.write_str("; }")
+    }
+}
@@ -127,6 +127,7 @@ pub trait CssRulesHelpers {
         parent_stylesheet_contents: &StylesheetContents,
         index: usize,
         nested: bool,
+        in_style_rule: bool,
         loader: Option<&dyn StylesheetLoader>,
     ) -> Result<CssRule, RulesMutateError>;
 }
@@ -139,6 +140,7 @@ impl CssRulesHelpers for RawOffsetArc<Locked<CssRules>> {
         parent_stylesheet_contents: &StylesheetContents,
         index: usize,
         nested: bool,
+        in_style_rule: bool,
         loader: Option<&dyn StylesheetLoader>,
     ) -> Result<CssRule, RulesMutateError> {
         let new_rule = {
@@ -175,6 +177,7 @@ impl CssRulesHelpers for RawOffsetArc<Locked<CssRules>> {
                 parent_stylesheet_contents,
                 lock,
                 state,
+                in_style_rule,
                 loader,
             )?
         };
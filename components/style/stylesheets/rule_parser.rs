@@ -5,28 +5,37 @@
 //! Parsing of the stylesheet contents.
 
 use crate::counter_style::{parse_counter_style_body, parse_counter_style_name_definition};
+use crate::custom_properties::{self, Name};
 use crate::error_reporting::ContextualParseError;
 use crate::font_face::parse_font_face_block;
 use crate::media_queries::MediaList;
 use crate::parser::{Parse, ParserContext};
-use crate::properties::parse_property_declaration_list;
+use crate::properties::parse_property_declaration_list_with_nested_rules;
+use crate::properties::{parse_property_declaration_list, PropertyDeclarationBlock};
 use crate::selector_parser::{SelectorImpl, SelectorParser};
 use crate::shared_lock::{Locked, SharedRwLock};
 use crate::str::starts_with_ignore_ascii_case;
+use crate::stylesheets::container_rule::ContainerCondition;
 use crate::stylesheets::document_rule::DocumentCondition;
 use crate::stylesheets::font_feature_values_rule::parse_family_name_list;
+use crate::stylesheets::import_rule::ImportLayer;
 use crate::stylesheets::keyframes_rule::parse_keyframe_list;
+use crate::stylesheets::layer_rule::LayerName;
 use crate::stylesheets::stylesheet::Namespaces;
 use crate::stylesheets::supports_rule::SupportsCondition;
 use crate::stylesheets::viewport_rule;
+use crate::stylesheets::PropertyRule;
+use crate::stylesheets::{ContainerRule, StyleRule, SupportsRule, ViewportRule};
 use crate::stylesheets::{CorsMode, DocumentRule, FontFeatureValuesRule, KeyframesRule, MediaRule};
 use crate::stylesheets::{CssRule, CssRuleType, CssRules, RulesMutateError, StylesheetLoader};
-use crate::stylesheets::{NamespaceRule, PageRule, StyleRule, SupportsRule, ViewportRule};
+use crate::stylesheets::{LayerBlockRule, LayerStatementRule, NamespaceRule, PageRule};
 use crate::values::computed::font::FamilyName;
 use crate::values::{CssUrl, CustomIdent, KeyframesName};
 use crate::{Namespace, Prefix};
+use cssparser::Token;
 use cssparser::{AtRuleParser, AtRuleType, Parser, QualifiedRuleParser, RuleListParser};
-use cssparser::{BasicParseError, BasicParseErrorKind, CowRcStr, SourceLocation};
+use cssparser::{BasicParseError, BasicParseErrorKind, CowRcStr, Delimiter, SourceLocation};
+use selectors::parser::SelectorParseErrorKind;
 use selectors::SelectorList;
 use servo_arc::Arc;
 use style_traits::{ParseError, StyleParseErrorKind};
@@ -62,6 +71,9 @@ pub struct TopLevelRuleParser<'a> {
     pub namespaces: &'a mut Namespaces,
     /// The info we need insert a rule in a list.
     pub insert_rule_context: Option<InsertRuleContext<'a>>,
+    /// Whether the rules we're parsing are nested in a style rule, which is
+    /// the case when inserting rules into a style rule from CSSOM.
+    pub in_style_rule: bool,
 }
 
 impl<'b> TopLevelRuleParser<'b> {
@@ -70,6 +82,7 @@ impl<'b> TopLevelRuleParser<'b> {
             shared_lock: self.shared_lock,
             context: &self.context,
             namespaces: &self.namespaces,
+            in_style_rule: self.in_style_rule,
         }
     }
 
@@ -125,12 +138,14 @@ impl<'b> TopLevelRuleParser<'b> {
 pub enum State {
     /// We haven't started parsing rules.
     Start = 1,
+    /// We're parsing `@layer` statement rules before any `@import` rule.
+    EarlyLayers = 2,
     /// We're parsing `@import` rules.
-    Imports = 2,
+    Imports = 3,
     /// We're parsing `@namespace` rules.
-    Namespaces = 3,
+    Namespaces = 4,
     /// We're parsing the main body of the stylesheet.
-    Body = 4,
+    Body = 5,
 }
 
 #[derive(Clone, Debug, MallocSizeOf, ToShmem)]
@@ -162,14 +177,30 @@ pub enum AtRuleBlockPrelude {
     Page,
     /// A @document rule, with its conditional.
     Document(DocumentCondition),
+    /// A @layer block rule, with its name if it's not anonymous.
+    Layer(Option<LayerName>),
+    /// A @container rule, with its container condition.
+    Container(Arc<ContainerCondition>),
+    /// A @property rule, with the name of the custom property it registers.
+    Property(Name),
+}
+
+/// The prelude of a rule nested in a style rule.
+enum NestedPrelude {
+    /// A style rule, with its selectors.
+    Style(SelectorList<SelectorImpl>),
+    /// An at-rule with a block.
+    AtRule(AtRuleBlockPrelude),
 }
 
 /// A rule prelude for at-rule without block.
 pub enum AtRuleNonBlockPrelude {
     /// A @import rule prelude.
-    Import(CssUrl, Arc<Locked<MediaList>>),
+    Import(CssUrl, Arc<Locked<MediaList>>, Option<ImportLayer>),
     /// A @namespace rule prelude.
     Namespace(Option<Prefix>, Namespace),
+    /// A @layer statement rule prelude, with its list of layer names.
+    Layer(Vec<LayerName>),
 }
 
 impl<'a, 'i> AtRuleParser<'i> for TopLevelRuleParser<'a> {
@@ -199,12 +230,35 @@ impl<'a, 'i> AtRuleParser<'i> for TopLevelRuleParser<'a> {
                 let url_string = input.expect_url_or_string()?.as_ref().to_owned();
                 let url = CssUrl::parse_from_string(url_string, &self.context, CorsMode::None);
 
+                let layer = input.try(|input| ImportLayer::parse(&self.context, input)).ok();
+
                 let media = MediaList::parse(&self.context, input);
                 let media = Arc::new(self.shared_lock.wrap(media));
 
-                let prelude = AtRuleNonBlockPrelude::Import(url, media);
+                let prelude = AtRuleNonBlockPrelude::Import(url, media, layer);
                 return Ok(AtRuleType::WithoutBlock(prelude));
             },
+            "layer" => {
+                let prelude = AtRuleParser::parse_prelude(&mut self.nested(), name, input)?;
+
+                // Layer statements are allowed before @import rules, but
+                // layer blocks are part of the body. A single name may still
+                // turn out to start a block, which `parse_block` checks.
+                let is_statement = match prelude {
+                    AtRuleType::WithoutBlock(..) |
+                    AtRuleType::WithBlock(AtRuleBlockPrelude::Layer(Some(..))) => true,
+                    AtRuleType::WithBlock(..) => false,
+                };
+                let new_state = if is_statement && self.state <= State::EarlyLayers {
+                    State::EarlyLayers
+                } else {
+                    State::Body
+                };
+                if !self.check_state(new_state) {
+                    return Err(input.new_custom_error(StyleParseErrorKind::UnspecifiedError));
+                }
+                return Ok(prelude);
+            },
             "namespace" => {
                 if !self.check_state(State::Namespaces) {
                     return Err(input.new_custom_error(StyleParseErrorKind::UnexpectedNamespaceRule))
@@ -246,6 +300,11 @@ impl<'a, 'i> AtRuleParser<'i> for TopLevelRuleParser<'a> {
         location: SourceLocation,
         input: &mut Parser<'i, 't>,
     ) -> Result<CssRule, ParseError<'i>> {
+        if let AtRuleBlockPrelude::Layer(..) = prelude {
+            if !self.check_state(State::Body) {
+                return Err(input.new_custom_error(StyleParseErrorKind::UnspecifiedError));
+            }
+        }
         AtRuleParser::parse_block(&mut self.nested(), prelude, location, input).map(|rule| {
             self.state = State::Body;
             rule
@@ -259,7 +318,7 @@ impl<'a, 'i> AtRuleParser<'i> for TopLevelRuleParser<'a> {
         source_location: SourceLocation,
     ) -> CssRule {
         match prelude {
-            AtRuleNonBlockPrelude::Import(url, media) => {
+            AtRuleNonBlockPrelude::Import(url, media, layer) => {
                 let loader = self
                     .loader
                     .expect("Expected a stylesheet loader for @import");
@@ -270,6 +329,7 @@ impl<'a, 'i> AtRuleParser<'i> for TopLevelRuleParser<'a> {
                     &self.context,
                     &self.shared_lock,
                     media,
+                    layer,
                 );
 
                 self.state = State::Imports;
@@ -291,6 +351,14 @@ impl<'a, 'i> AtRuleParser<'i> for TopLevelRuleParser<'a> {
                     source_location,
                 })))
             },
+            AtRuleNonBlockPrelude::Layer(..) => {
+                if self.state <= State::EarlyLayers {
+                    self.state = State::EarlyLayers;
+                } else {
+                    self.state = State::Body;
+                }
+                AtRuleParser::rule_without_block(&mut self.nested(), prelude, source_location)
+            },
         }
     }
 }
@@ -328,11 +396,90 @@ impl<'a, 'i> QualifiedRuleParser<'i> for TopLevelRuleParser<'a> {
     }
 }
 
+/// Parses an `@layer` rule with `parser`, if that's the next rule in `input`,
+/// or returns `None` without consuming any input otherwise.
+///
+/// `@layer foo;` and `@layer foo { .. }` are different rules, but
+/// `RuleListParser` needs to know whether a block follows before the end of
+/// the prelude, so we parse these rules ourselves and look at the token that
+/// follows the prelude instead.
+///
+/// FIXME: This should go away in favor of the regular `AtRuleParser` methods
+/// once cssparser lets the parser choose between `parse_block` and
+/// `rule_without_block` after seeing the token that follows the prelude,
+/// which needs a newer cssparser than the one we use.
+pub fn parse_layer_rule<'i, 't, P>(
+    input: &mut Parser<'i, 't>,
+    parser: &mut P,
+) -> Option<Result<CssRule, (ParseError<'i>, &'i str)>>
+where
+    P: AtRuleParser<
+        'i,
+        PreludeNoBlock = AtRuleNonBlockPrelude,
+        PreludeBlock = AtRuleBlockPrelude,
+        AtRule = CssRule,
+        Error = StyleParseErrorKind<'i>,
+    >,
+{
+    let before = input.state();
+    input.skip_whitespace();
+    let start = input.state();
+    let name = match input.next_including_whitespace_and_comments() {
+        Ok(&Token::AtKeyword(ref name)) if name.eq_ignore_ascii_case("layer") => name.clone(),
+        _ => {
+            input.reset(&before);
+            return None;
+        },
+    };
+
+    let location = input.current_source_location();
+    let prelude = input.parse_until_before(
+        Delimiter::Semicolon | Delimiter::CurlyBracketBlock,
+        |input| parser.parse_prelude(name, input),
+    );
+    let prelude = match prelude {
+        Ok(prelude) => prelude,
+        Err(error) => {
+            let end = input.position();
+            let _ = input.next();
+            return Some(Err((error, input.slice(start.position()..end))));
+        },
+    };
+
+    let next = input.next().map(|token| token.clone());
+    let is_block = match next {
+        Ok(Token::CurlyBracketBlock) => true,
+        _ => false,
+    };
+    let result = match (prelude, is_block) {
+        (AtRuleType::WithBlock(prelude), true) => {
+            input.parse_nested_block(|input| parser.parse_block(prelude, location, input))
+        },
+        (AtRuleType::WithoutBlock(prelude), false) => {
+            Ok(parser.rule_without_block(prelude, location))
+        },
+        // A single name followed by a semicolon is a statement.
+        (AtRuleType::WithBlock(AtRuleBlockPrelude::Layer(Some(name))), false) => {
+            let prelude = AtRuleNonBlockPrelude::Layer(vec![name]);
+            Ok(parser.rule_without_block(prelude, location))
+        },
+        _ => Err(match next {
+            Ok(token) => input.new_unexpected_token_error(token),
+            Err(error) => error.into(),
+        }),
+    };
+    Some(result.map_err(|error| (error, input.slice_from(start.position()))))
+}
+
 #[derive(Clone)] // shallow, relatively cheap .clone
 struct NestedRuleParser<'a, 'b: 'a> {
     shared_lock: &'a SharedRwLock,
     context: &'a ParserContext<'b>,
     namespaces: &'a Namespaces,
+    /// Whether we're parsing rules nested in a style rule, whose selectors
+    /// are relative to the parent rule, and whose conditional group rules may
+    /// contain declarations.
+    in_style_rule: bool,
 }
 
 impl<'a, 'b> NestedRuleParser<'a, 'b> {
@@ -341,17 +488,44 @@ impl<'a, 'b> NestedRuleParser<'a, 'b> {
         input: &mut Parser,
         rule_type: CssRuleType,
     ) -> Arc<Locked<CssRules>> {
+        if self.in_style_rule {
+            // Declarations directly inside a conditional group rule nested in
+            // a style rule apply as if they were in a `& { .. }` rule.
+            //
+            // https://drafts.csswg.org/css-nesting/#conditionals
+            let source_location = input.current_source_location();
+            let (declarations, mut rules) = self.parse_style_rule_body(input, rule_type, None);
+            if !declarations.declarations().is_empty() {
+                let rule = StyleRule {
+                    selectors: SelectorList::parent_selector(),
+                    block: Arc::new(self.shared_lock.wrap(declarations)),
+                    rules: None,
+                    source_location,
+                };
+                rules.insert(0, CssRule::Style(Arc::new(self.shared_lock.wrap(rule))));
+            }
+            return CssRules::new(rules, self.shared_lock);
+        }
+
         let context = ParserContext::new_with_rule_type(self.context, rule_type, self.namespaces);
 
         let nested_parser = NestedRuleParser {
             shared_lock: self.shared_lock,
             context: &context,
             namespaces: self.namespaces,
+            in_style_rule: false,
         };
 
         let mut iter = RuleListParser::new_for_nested_rule(input, nested_parser);
         let mut rules = Vec::new();
-        while let Some(result) = iter.next() {
+        loop {
+            let result = match parse_layer_rule(iter.input, &mut iter.parser) {
+                Some(result) => result,
+                None => match iter.next() {
+                    Some(result) => result,
+                    None => break,
+                },
+            };
             match result {
                 Ok(rule) => rules.push(rule),
                 Err((error, slice)) => {
@@ -363,6 +537,100 @@ impl<'a, 'b> NestedRuleParser<'a, 'b> {
         }
         CssRules::new(rules, self.shared_lock)
     }
+
+    /// Parses the contents of a style rule, or of a rule of type `rule_type`
+    /// nested in one, which may contain both declarations and nested rules.
+    ///
+    /// https://drafts.csswg.org/css-nesting/#syntax
+    fn parse_style_rule_body<'i, 't>(
+        &self,
+        input: &mut Parser<'i, 't>,
+        rule_type: CssRuleType,
+        selectors: Option<&SelectorList<SelectorImpl>>,
+    ) -> (PropertyDeclarationBlock, Vec<CssRule>) {
+        let style_context =
+            ParserContext::new_with_rule_type(self.context, CssRuleType::Style, self.namespaces);
+        let context = ParserContext::new_with_rule_type(self.context, rule_type, self.namespaces);
+
+        let mut nested_parser = NestedRuleParser {
+            shared_lock: self.shared_lock,
+            context: &context,
+            namespaces: self.namespaces,
+            in_style_rule: true,
+        };
+
+        let mut rules = Vec::new();
+        let declarations = parse_property_declaration_list_with_nested_rules(
+            &style_context,
+            input,
+            selectors,
+            |input| nested_parser.parse_rule_in_style_rule(input, &mut rules),
+        );
+        (declarations, rules)
+    }
+
+    /// Parses a style rule or an at-rule nested in a style rule into `rules`.
+    ///
+    /// Returns false, without consuming any input, if there's no rule to
+    /// parse, that is, if there's no block before the next semicolon.
+    fn parse_rule_in_style_rule<'i, 't>(
+        &mut self,
+        input: &mut Parser<'i, 't>,
+        rules: &mut Vec<CssRule>,
+    ) -> bool {
+        let start = input.state();
+        let at_rule_name = match input.next() {
+            Ok(&Token::AtKeyword(ref name)) => Some(name.clone()),
+            _ => {
+                input.reset(&start);
+                None
+            },
+        };
+
+        let prelude = input.parse_until_before(
+            Delimiter::CurlyBracketBlock | Delimiter::Semicolon,
+            |input| match at_rule_name {
+                Some(name) => match AtRuleParser::parse_prelude(self, name.clone(), input)? {
+                    AtRuleType::WithBlock(prelude) => Ok(NestedPrelude::AtRule(prelude)),
+                    AtRuleType::WithoutBlock(..) => {
+                        Err(input.new_custom_error(StyleParseErrorKind::UnsupportedAtRule(name)))
+                    },
+                },
+                None => QualifiedRuleParser::parse_prelude(self, input).map(NestedPrelude::Style),
+            },
+        );
+
+        match input.next() {
+            Ok(&Token::CurlyBracketBlock) => {},
+            _ => {
+                input.reset(&start);
+                return false;
+            },
+        }
+
+        let source_location = start.source_location();
+        let result = prelude.and_then(|prelude| {
+            input.parse_nested_block(|input| match prelude {
+                NestedPrelude::Style(selectors) => {
+                    QualifiedRuleParser::parse_block(self, selectors, source_location, input)
+                },
+                NestedPrelude::AtRule(prelude) => {
+                    AtRuleParser::parse_block(self, prelude, source_location, input)
+                },
+            })
+        });
+
+        match result {
+            Ok(rule) => rules.push(rule),
+            Err(error) => {
+                let location = error.location;
+                let error =
+                    ContextualParseError::InvalidRule(input.slice_from(start.position()), error);
+                self.context.log_css_error(location, error);
+            },
+        }
+        true
+    }
 }
 
 impl<'a, 'b, 'i> AtRuleParser<'i> for NestedRuleParser<'a, 'b> {
@@ -376,6 +644,20 @@ impl<'a, 'b, 'i> AtRuleParser<'i> for NestedRuleParser<'a, 'b> {
         name: CowRcStr<'i>,
         input: &mut Parser<'i, 't>,
     ) -> Result<AtRuleType<AtRuleNonBlockPrelude, AtRuleBlockPrelude>, ParseError<'i>> {
+        // Only conditional group rules and @layer can be nested in style
+        // rules.
+        //
+        // https://drafts.csswg.org/css-nesting/#conditionals
+        if self.in_style_rule {
+            let allowed = match_ignore_ascii_case! { &*name,
+                "media" | "supports" | "container" | "layer" => true,
+                _ => false,
+            };
+            if !allowed {
+                return Err(input.new_custom_error(StyleParseErrorKind::UnsupportedAtRule(name)));
+            }
+        }
+
         match_ignore_ascii_case! { &*name,
             "media" => {
                 let media_queries = MediaList::parse(self.context, input);
@@ -446,6 +728,32 @@ impl<'a, 'b, 'i> AtRuleParser<'i> for NestedRuleParser<'a, 'b> {
                 let cond = DocumentCondition::parse(self.context, input)?;
                 Ok(AtRuleType::WithBlock(AtRuleBlockPrelude::Document(cond)))
             },
+            "layer" => {
+                let names = input.try(|input| {
+                    input.parse_comma_separated(|input| LayerName::parse(self.context, input))
+                }).unwrap_or_default();
+
+                // A single name may also start a statement, which
+                // `parse_layer_rule` takes care of if no block follows.
+                if names.len() <= 1 {
+                    let name = names.into_iter().next();
+                    Ok(AtRuleType::WithBlock(AtRuleBlockPrelude::Layer(name)))
+                } else {
+                    Ok(AtRuleType::WithoutBlock(AtRuleNonBlockPrelude::Layer(names)))
+                }
+            },
+            "container" => {
+                let condition = ContainerCondition::parse(self.context, input)?;
+                Ok(AtRuleType::WithBlock(AtRuleBlockPrelude::Container(Arc::new(condition))))
+            },
+            "property" => {
+                let location = input.current_source_location();
+                let ident = input.expect_ident()?;
+                let name = custom_properties::parse_name(ident).map_err(|()| {
+                    location.new_custom_error(SelectorParseErrorKind::UnexpectedIdent(ident.clone()))
+                })?;
+                Ok(AtRuleType::WithBlock(AtRuleBlockPrelude::Property(Name::from(name))))
+            },
             _ => Err(input.new_custom_error(StyleParseErrorKind::UnsupportedAtRule(name.clone())))
         }
     }
@@ -566,6 +874,49 @@ impl<'a, 'b, 'i> AtRuleParser<'i> for NestedRuleParser<'a, 'b> {
                     },
                 ))))
             },
+            AtRuleBlockPrelude::Layer(name) => Ok(CssRule::LayerBlock(Arc::new(
+                self.shared_lock.wrap(LayerBlockRule {
+                    name,
+                    rules: self.parse_nested_rules(input, CssRuleType::LayerBlock),
+                    source_location,
+                }),
+            ))),
+            AtRuleBlockPrelude::Container(condition) => Ok(CssRule::Container(Arc::new(
+                self.shared_lock.wrap(ContainerRule {
+                    condition,
+                    rules: self.parse_nested_rules(input, CssRuleType::Container),
+                    source_location,
+                }),
+            ))),
+            AtRuleBlockPrelude::Property(name) => {
+                let context = ParserContext::new_with_rule_type(
+                    self.context,
+                    CssRuleType::Property,
+                    self.namespaces,
+                );
+
+                Ok(CssRule::Property(Arc::new(self.shared_lock.wrap(
+                    PropertyRule::parse_block(&context, input, name, source_location)?,
+                ))))
+            },
+        }
+    }
+
+    fn rule_without_block(
+        &mut self,
+        prelude: AtRuleNonBlockPrelude,
+        source_location: SourceLocation,
+    ) -> CssRule {
+        match prelude {
+            AtRuleNonBlockPrelude::Layer(names) => {
+                CssRule::LayerStatement(Arc::new(self.shared_lock.wrap(LayerStatementRule {
+                    names,
+                    source_location,
+                })))
+            },
+            AtRuleNonBlockPrelude::Import(..) | AtRuleNonBlockPrelude::Namespace(..) => {
+                unreachable!("Nested rule parser shouldn't return these preludes")
+            },
         }
     }
 }
@@ -584,6 +935,9 @@ impl<'a, 'b, 'i> QualifiedRuleParser<'i> for NestedRuleParser<'a, 'b> {
             namespaces: self.namespaces,
             url_data: Some(self.context.url_data),
         };
+        if self.in_style_rule {
+            return SelectorList::parse_nested(&selector_parser, input);
+        }
         SelectorList::parse(&selector_parser, input)
     }
 
@@ -593,14 +947,18 @@ impl<'a, 'b, 'i> QualifiedRuleParser<'i> for NestedRuleParser<'a, 'b> {
         source_location: SourceLocation,
         input: &mut Parser<'i, 't>,
     ) -> Result<CssRule, ParseError<'i>> {
-        let context =
-            ParserContext::new_with_rule_type(self.context, CssRuleType::Style, self.namespaces);
-
-        let declarations = parse_property_declaration_list(&context, input, Some(&selectors));
+        let (declarations, rules) =
+            self.parse_style_rule_body(input, CssRuleType::Style, Some(&selectors));
         let block = Arc::new(self.shared_lock.wrap(declarations));
+        let rules = if rules.is_empty() {
+            None
+        } else {
+            Some(CssRules::new(rules, self.shared_lock))
+        };
         Ok(CssRule::Style(Arc::new(self.shared_lock.wrap(StyleRule {
             selectors,
             block,
+            rules,
             source_location,
         }))))
     }
@@ -52,6 +52,69 @@ where
     pub fn skip_children(&mut self) {
         self.stack.pop();
     }
+
+    /// Returns the children of `rule` that need to be processed, or `None` if
+    /// the rule has no children or they don't need to be processed.
+    pub fn children(
+        rule: &'a CssRule,
+        device: &'a Device,
+        quirks_mode: QuirksMode,
+        guard: &'a SharedRwLockReadGuard<'b>,
+    ) -> Option<slice::Iter<'a, CssRule>> {
+        match *rule {
+            CssRule::Namespace(_) |
+            CssRule::FontFace(_) |
+            CssRule::CounterStyle(_) |
+            CssRule::Viewport(_) |
+            CssRule::Keyframes(_) |
+            CssRule::Page(_) |
+            CssRule::FontFeatureValues(_) |
+            CssRule::LayerStatement(_) |
+            CssRule::Property(_) => None,
+            CssRule::Style(ref lock) => {
+                let style_rule = lock.read_with(guard);
+                Some(style_rule.rules.as_ref()?.read_with(guard).0.iter())
+            },
+            CssRule::Import(ref import_rule) => {
+                let import_rule = import_rule.read_with(guard);
+                if !C::process_import(guard, device, quirks_mode, import_rule) {
+                    return None;
+                }
+                Some(import_rule.stylesheet.rules(guard).iter())
+            },
+            CssRule::Document(ref doc_rule) => {
+                let doc_rule = doc_rule.read_with(guard);
+                if !C::process_document(guard, device, quirks_mode, doc_rule) {
+                    return None;
+                }
+                Some(doc_rule.rules.read_with(guard).0.iter())
+            },
+            CssRule::Media(ref lock) => {
+                let media_rule = lock.read_with(guard);
+                if !C::process_media(guard, device, quirks_mode, media_rule) {
+                    return None;
+                }
+                Some(media_rule.rules.read_with(guard).0.iter())
+            },
+            CssRule::Supports(ref lock) => {
+                let supports_rule = lock.read_with(guard);
+                if !C::process_supports(guard, device, quirks_mode, supports_rule) {
+                    return None;
+                }
+                Some(supports_rule.rules.read_with(guard).0.iter())
+            },
+            CssRule::LayerBlock(ref lock) => {
+                let layer_rule = lock.read_with(guard);
+                Some(layer_rule.rules.read_with(guard).0.iter())
+            },
+            // Container conditions are evaluated per element during selector
+            // matching, so the nested rules are always processed.
+            CssRule::Container(ref lock) => {
+                let container_rule = lock.read_with(guard);
+                Some(container_rule.rules.read_with(guard).0.iter())
+            },
+        }
+    }
 }
 
 impl<'a, 'b, C> Iterator for RulesIterator<'a, 'b, C>
@@ -70,67 +133,34 @@ where
                 continue;
             }
 
-            let rule;
-            let sub_iter = {
-                let nested_iter = self.stack.last_mut().unwrap();
-                rule = match nested_iter.next() {
-                    Some(r) => r,
-                    None => {
-                        nested_iter_finished = true;
-                        continue;
-                    },
-                };
+            let rule = match self.stack.last_mut().unwrap().next() {
+                Some(r) => r,
+                None => {
+                    nested_iter_finished = true;
+                    continue;
+                },
+            };
 
-                match *rule {
-                    CssRule::Namespace(_) |
-                    CssRule::Style(_) |
-                    CssRule::FontFace(_) |
-                    CssRule::CounterStyle(_) |
-                    CssRule::Viewport(_) |
-                    CssRule::Keyframes(_) |
-                    CssRule::Page(_) |
-                    CssRule::FontFeatureValues(_) => return Some(rule),
-                    CssRule::Import(ref import_rule) => {
-                        let import_rule = import_rule.read_with(self.guard);
-                        if !C::process_import(
-                            self.guard,
-                            self.device,
-                            self.quirks_mode,
-                            import_rule,
-                        ) {
-                            continue;
-                        }
-                        import_rule.stylesheet.rules(self.guard).iter()
-                    },
-                    CssRule::Document(ref doc_rule) => {
-                        let doc_rule = doc_rule.read_with(self.guard);
-                        if !C::process_document(self.guard, self.device, self.quirks_mode, doc_rule)
-                        {
-                            continue;
-                        }
-                        doc_rule.rules.read_with(self.guard).0.iter()
-                    },
-                    CssRule::Media(ref lock) => {
-                        let media_rule = lock.read_with(self.guard);
-                        if !C::process_media(self.guard, self.device, self.quirks_mode, media_rule)
-                        {
-                            continue;
-                        }
-                        media_rule.rules.read_with(self.guard).0.iter()
-                    },
-                    CssRule::Supports(ref lock) => {
-                        let supports_rule = lock.read_with(self.guard);
-                        if !C::process_supports(
-                            self.guard,
-                            self.device,
-                            self.quirks_mode,
-                            supports_rule,
-                        ) {
-                            continue;
-                        }
-                        supports_rule.rules.read_with(self.guard).0.iter()
-                    },
-                }
+            let sub_iter = match *rule {
+                CssRule::Import(..) |
+                CssRule::Document(..) |
+                CssRule::Media(..) |
+                CssRule::Supports(..) |
+                CssRule::LayerBlock(..) |
+                CssRule::Container(..) => {
+                    match Self::children(rule, self.device, self.quirks_mode, self.guard) {
+                        Some(children) => children,
+                        None => continue,
+                    }
+                },
+                // Style rules are returned even if they have no nested rules.
+                CssRule::Style(..) => {
+                    match Self::children(rule, self.device, self.quirks_mode, self.guard) {
+                        Some(children) => children,
+                        None => return Some(rule),
+                    }
+                },
+                _ => return Some(rule),
             };
 
             self.stack.push(sub_iter);
@@ -9,6 +9,7 @@ use crate::selector_parser::SelectorImpl;
 use crate::shared_lock::{DeepCloneParams, DeepCloneWithLock, Locked};
 use crate::shared_lock::{SharedRwLock, SharedRwLockReadGuard, ToCssWithGuard};
 use crate::str::CssStringWriter;
+use crate::stylesheets::CssRules;
 use cssparser::SourceLocation;
 #[cfg(feature = "gecko")]
 use malloc_size_of::MallocUnconditionalShallowSizeOf;
@@ -25,6 +26,8 @@ pub struct StyleRule {
     pub selectors: SelectorList<SelectorImpl>,
     /// The declaration block with the properties it contains.
     pub block: Arc<Locked<PropertyDeclarationBlock>>,
+    /// The rules nested in this rule, if any.
+    pub rules: Option<Arc<Locked<CssRules>>>,
     /// The location in the sheet where it was found.
     pub source_location: SourceLocation,
 }
@@ -35,11 +38,15 @@ impl DeepCloneWithLock for StyleRule {
         &self,
         lock: &SharedRwLock,
         guard: &SharedRwLockReadGuard,
-        _params: &DeepCloneParams,
+        params: &DeepCloneParams,
     ) -> StyleRule {
         StyleRule {
             selectors: self.selectors.clone(),
             block: Arc::new(lock.wrap(self.block.read_with(guard).clone())),
+            rules: self.rules.as_ref().map(|rules| {
+                let rules = rules.read_with(guard);
+                Arc::new(lock.wrap(rules.deep_clone_with_lock(lock, guard, params)))
+            }),
             source_location: self.source_location.clone(),
         }
     }
@@ -53,6 +60,10 @@ impl StyleRule {
         n += self.selectors.0.size_of(ops);
         n += self.block.unconditional_shallow_size_of(ops) +
             self.block.read_with(guard).size_of(ops);
+        if let Some(ref rules) = self.rules {
+            n += rules.unconditional_shallow_size_of(ops) +
+                rules.read_with(guard).size_of(guard, ops);
+        }
         n
     }
 }
@@ -73,6 +84,15 @@ impl ToCssWithGuard for StyleRule {
         if !declaration_block.declarations().is_empty() {
             dest.write_str(" ")?;
         }
+        // Nested rules go after the declarations.
+        //
+        // https://drafts.csswg.org/css-nesting/#cssom
+        if let Some(ref rules) = self.rules {
+            for rule in rules.read_with(guard).0.iter() {
+                rule.to_css(guard, dest)?;
+                dest.write_str(" ")?;
+            }
+        }
         // Step 5
         dest.write_str("}")
     }
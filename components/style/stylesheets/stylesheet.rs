@@ -10,7 +10,7 @@ use crate::parser::ParserContext;
 use crate::shared_lock::{DeepCloneParams, DeepCloneWithLock, Locked};
 use crate::shared_lock::{SharedRwLock, SharedRwLockReadGuard};
 use crate::stylesheets::loader::StylesheetLoader;
-use crate::stylesheets::rule_parser::{State, TopLevelRuleParser};
+use crate::stylesheets::rule_parser::{parse_layer_rule, State, TopLevelRuleParser};
 use crate::stylesheets::rules_iterator::{EffectiveRules, EffectiveRulesIterator};
 use crate::stylesheets::rules_iterator::{NestedRuleIterationCondition, RulesIterator};
 use crate::stylesheets::{CssRule, CssRules, Origin, UrlExtraData};
@@ -410,12 +410,20 @@ impl Stylesheet {
             dom_error: None,
             insert_rule_context: None,
             namespaces,
+            in_style_rule: false,
         };
 
         {
             let mut iter = RuleListParser::new_for_stylesheet(&mut input, rule_parser);
 
-            while let Some(result) = iter.next() {
+            loop {
+                let result = match parse_layer_rule(iter.input, &mut iter.parser) {
+                    Some(result) => result,
+                    None => match iter.next() {
+                        Some(result) => result,
+                        None => break,
+                    },
+                };
                 match result {
                     Ok(rule) => {
                         // Use a fallible push here, and if it fails, just
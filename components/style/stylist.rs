@@ -16,6 +16,7 @@ use crate::invalidation::media_queries::{EffectiveMediaQueryResults, ToMediaList
 use crate::media_queries::Device;
 use crate::properties::{self, CascadeMode, ComputedValues};
 use crate::properties::{AnimationRules, PropertyDeclarationBlock};
+use crate::properties_and_values::{CustomPropertyRegistry, PropertyRegistration};
 use crate::rule_cache::{RuleCache, RuleCacheConditions};
 use crate::rule_collector::{containing_shadow_ignoring_svg_use, RuleCollector};
 use crate::rule_tree::{CascadeLevel, RuleTree, ShadowCascadeOrder, StrongRuleNode, StyleSource};
@@ -24,16 +25,20 @@ use crate::selector_parser::{PerPseudoElementMap, PseudoElement, SelectorImpl, S
 use crate::shared_lock::{Locked, SharedRwLockReadGuard, StylesheetGuards};
 use crate::stylesheet_set::{DataValidity, DocumentStylesheetSet, SheetRebuildKind};
 use crate::stylesheet_set::{DocumentStylesheetFlusher, SheetCollectionFlusher};
+use crate::stylesheets::container_rule::ContainerCondition;
 use crate::stylesheets::keyframes_rule::KeyframesAnimation;
+use crate::stylesheets::layer_rule::{LayerName, LayerOrder};
 use crate::stylesheets::viewport_rule::{self, MaybeNew, ViewportRule};
 use crate::stylesheets::StyleRule;
 use crate::stylesheets::StylesheetInDocument;
+use crate::stylesheets::{ContainerRule, CssRule, EffectiveRulesIterator, Origin, OriginSet};
 #[cfg(feature = "gecko")]
 use crate::stylesheets::{CounterStyleRule, FontFaceRule, FontFeatureValuesRule, PageRule};
-use crate::stylesheets::{CssRule, Origin, OriginSet, PerOrigin, PerOriginIter};
+use crate::stylesheets::{PerOrigin, PerOriginIter};
 use crate::thread_state::{self, ThreadState};
 use crate::{Atom, LocalName, Namespace, WeakAtom};
 use fallible::FallibleVec;
+use fxhash::FxHashMap;
 use hashglobe::FailedAllocationError;
 use malloc_size_of::MallocSizeOf;
 #[cfg(feature = "gecko")]
@@ -43,14 +48,15 @@ use selectors::bloom::BloomFilter;
 use selectors::matching::VisitedHandlingMode;
 use selectors::matching::{matches_selector, ElementSelectorFlags, MatchingContext, MatchingMode};
 use selectors::parser::{AncestorHashes, Combinator, Component, Selector};
-use selectors::parser::{SelectorIter, Visit};
+use selectors::parser::{SelectorIter, SelectorList, Visit};
 use selectors::visitor::SelectorVisitor;
 use selectors::NthIndexCache;
 use servo_arc::{Arc, ArcBorrow};
 use smallbitvec::SmallBitVec;
 use smallvec::SmallVec;
+use std::borrow::Cow;
 use std::sync::Mutex;
-use std::{mem, ops};
+use std::{mem, ops, slice};
 use style_traits::viewport::ViewportConstraints;
 
 /// The type of the stylesheets that the stylist contains.
@@ -123,6 +129,7 @@ impl UserAgentCascadeDataCache {
                 Some(&mut new_data.precomputed_pseudo_element_decls),
             )?;
         }
+        new_data.cascade_data.compute_layer_order();
 
         let new_data = Arc::new(new_data);
         self.entries.push(new_data.clone());
@@ -389,6 +396,16 @@ pub struct Stylist {
 
     /// The total number of times the stylist has been rebuilt.
     num_rebuilds: usize,
+
+    /// The custom properties registered from script, which take precedence
+    /// over the ones registered by `@property` rules.
+    #[cfg_attr(feature = "servo", ignore_malloc_size_of = "Arc")]
+    script_custom_properties: CustomPropertyRegistry,
+
+    /// All the registered custom properties of the document, both from
+    /// `@property` rules and from script.
+    #[cfg_attr(feature = "servo", ignore_malloc_size_of = "Arc")]
+    custom_property_registry: CustomPropertyRegistry,
 }
 
 /// What cascade levels to include when styling elements.
@@ -427,6 +444,8 @@ impl Stylist {
             author_styles_enabled: AuthorStylesEnabled::Yes,
             rule_tree: RuleTree::new(),
             num_rebuilds: 0,
+            script_custom_properties: CustomPropertyRegistry::default(),
+            custom_property_registry: CustomPropertyRegistry::default(),
         }
     }
 
@@ -497,6 +516,14 @@ impl Stylist {
             .sum()
     }
 
+    /// Returns whether any of the document's style rules is inside a
+    /// `@container` rule, and thus depends on the layout of its containers.
+    pub fn has_container_queries(&self) -> bool {
+        self.cascade_data
+            .iter_origins()
+            .any(|(d, _)| d.container_conditions.len() > 1)
+    }
+
     /// Returns whether the given DocumentState bit is relied upon by a selector
     /// of some rule.
     pub fn has_document_state_dependency(&self, state: DocumentState) -> bool {
@@ -561,9 +588,38 @@ impl Stylist {
             .rebuild(&self.device, self.quirks_mode, flusher, guards)
             .unwrap_or_else(|_| warn!("OOM in Stylist::flush"));
 
+        self.rebuild_custom_property_registry();
+
         had_invalidations
     }
 
+    /// Registers a custom property from script, with `CSS.registerProperty`.
+    pub fn register_custom_property(&mut self, registration: PropertyRegistration) {
+        self.script_custom_properties
+            .register(Arc::new(registration));
+        self.rebuild_custom_property_registry();
+    }
+
+    /// Returns the registered custom properties of the document.
+    #[inline]
+    pub fn custom_property_registry(&self) -> &CustomPropertyRegistry {
+        &self.custom_property_registry
+    }
+
+    /// Merges the `@property` rules of every origin, and the registrations
+    /// from script, into the registry used for the cascade.
+    fn rebuild_custom_property_registry(&mut self) {
+        self.custom_property_registry.clear();
+        for (data, _) in self.cascade_data.iter_origins_rev() {
+            for registration in data.custom_property_registry.iter() {
+                self.custom_property_registry.register(registration.clone());
+            }
+        }
+        for registration in self.script_custom_properties.iter() {
+            self.custom_property_registry.register(registration.clone());
+        }
+    }
+
     /// Insert a given stylesheet before another stylesheet in the document.
     pub fn insert_stylesheet_before(
         &mut self,
@@ -900,6 +956,7 @@ impl Stylist {
             layout_parent_style,
             visited_rules,
             font_metrics,
+            &self.custom_property_registry,
             self.quirks_mode,
             rule_cache,
             rule_cache_conditions,
@@ -1345,6 +1402,7 @@ impl Stylist {
             CascadeMode::Unvisited {
                 visited_rules: None,
             },
+            &self.custom_property_registry,
             self.quirks_mode,
             /* rule_cache = */ None,
             &mut Default::default(),
@@ -1714,6 +1772,67 @@ impl PartElementAndPseudoRules {
     }
 }
 
+/// An identifier for a cascade layer, unique within a given `CascadeData`.
+#[derive(Clone, Copy, Debug, Eq, Hash, MallocSizeOf, PartialEq)]
+pub struct LayerId(u16);
+
+impl LayerId {
+    /// The id of the root layer, which contains the unlayered rules.
+    #[inline]
+    pub fn root() -> Self {
+        LayerId(0)
+    }
+}
+
+/// An identifier for a container condition, unique within a given
+/// `CascadeData`.
+#[derive(Clone, Copy, Debug, Eq, MallocSizeOf, PartialEq)]
+pub struct ContainerConditionId(u16);
+
+impl ContainerConditionId {
+    /// The id used for rules that are not inside any `@container` rule.
+    #[inline]
+    pub fn none() -> Self {
+        ContainerConditionId(0)
+    }
+}
+
+/// A container condition, along with the condition of the `@container` rule
+/// it is nested in, if any.
+#[derive(Clone, Debug, MallocSizeOf)]
+struct ContainerConditionReference {
+    parent: ContainerConditionId,
+    #[ignore_malloc_size_of = "Arc"]
+    condition: Option<Arc<ContainerCondition>>,
+}
+
+impl ContainerConditionReference {
+    fn none() -> Self {
+        Self {
+            parent: ContainerConditionId::none(),
+            condition: None,
+        }
+    }
+}
+
+/// A cascade layer, as declared by `@layer` and `@import` rules.
+#[derive(Clone, Debug, MallocSizeOf)]
+struct CascadeLayer {
+    /// The order of this layer, computed once all the layers are known.
+    order: LayerOrder,
+    /// The layers nested in this one, in the order they were declared.
+    children: SmallVec<[LayerId; 3]>,
+}
+
+impl CascadeLayer {
+    fn new() -> Self {
+        Self {
+            order: LayerOrder::first(),
+            children: SmallVec::new(),
+        }
+    }
+}
+
 /// Data resulting from performing the CSS cascade that is specific to a given
 /// origin.
 ///
@@ -1785,6 +1904,23 @@ pub struct CascadeData {
     /// Extra data, like different kinds of rules, etc.
     extra_data: ExtraStyleData,
 
+    /// The cascade layers, indexed by `LayerId`. The first one is always the
+    /// root layer.
+    layers: SmallVec<[CascadeLayer; 1]>,
+
+    /// The id of the named layers, keyed by their parent layer and their
+    /// name within it.
+    layer_ids: FxHashMap<(LayerId, Atom), LayerId>,
+
+    /// The container conditions of the `@container` rules, indexed by
+    /// `ContainerConditionId`. The first one is always the empty condition.
+    container_conditions: SmallVec<[ContainerConditionReference; 1]>,
+
+    /// The custom properties registered by `@property` rules at this
+    /// `CascadeData`'s origin.
+    #[ignore_malloc_size_of = "Arc"]
+    custom_property_registry: CustomPropertyRegistry,
+
     /// A monotonically increasing counter to represent the order on which a
     /// style rule appears in a stylesheet, needed to sort them by source order.
     rules_source_order: u32,
@@ -1813,6 +1949,10 @@ impl CascadeData {
             animations: Default::default(),
             extra_data: ExtraStyleData::default(),
             effective_media_query_results: EffectiveMediaQueryResults::new(),
+            layers: SmallVec::from_elem(CascadeLayer::new(), 1),
+            layer_ids: FxHashMap::default(),
+            container_conditions: SmallVec::from_elem(ContainerConditionReference::none(), 1),
+            custom_property_registry: CustomPropertyRegistry::default(),
             rules_source_order: 0,
             num_selectors: 0,
             num_declarations: 0,
@@ -1854,9 +1994,96 @@ impl CascadeData {
             )?;
         }
 
+        self.compute_layer_order();
         Ok(())
     }
 
+    /// Returns the order of the given layer in the cascade.
+    #[inline]
+    pub fn layer_order_for(&self, id: LayerId) -> LayerOrder {
+        self.layers[id.0 as usize].order
+    }
+
+    /// Returns the id of the layer with the given name nested in `parent`,
+    /// declaring it (and any intermediate layer) if needed. Anonymous layers
+    /// are always new.
+    fn declare_layer(&mut self, parent: LayerId, name: Option<&LayerName>) -> LayerId {
+        let name = match name {
+            Some(name) => name,
+            None => return self.new_layer(parent),
+        };
+
+        let mut current = parent;
+        for ident in name.layer_names() {
+            let key = (current, ident.0.clone());
+            current = match self.layer_ids.get(&key) {
+                Some(id) => *id,
+                None => {
+                    let id = self.new_layer(current);
+                    self.layer_ids.insert(key, id);
+                    id
+                },
+            };
+        }
+        current
+    }
+
+    fn new_layer(&mut self, parent: LayerId) -> LayerId {
+        // Put any layer beyond what we can represent in its parent, which
+        // keeps the cascade sane.
+        if self.layers.len() > ::std::u16::MAX as usize {
+            return parent;
+        }
+        let id = LayerId(self.layers.len() as u16);
+        self.layers.push(CascadeLayer::new());
+        self.layers[parent.0 as usize].children.push(id);
+        id
+    }
+
+    /// Returns whether the container condition with the given id, and those
+    /// of the `@container` rules it is nested in, match for `element`.
+    pub fn container_condition_matches<E>(
+        &self,
+        mut id: ContainerConditionId,
+        stylist: &Stylist,
+        element: E,
+    ) -> bool
+    where
+        E: TElement,
+    {
+        while id != ContainerConditionId::none() {
+            let reference = &self.container_conditions[id.0 as usize];
+            let condition = reference
+                .condition
+                .as_ref()
+                .expect("Only the first container condition should be empty");
+            if !condition.matches(stylist.device(), stylist.quirks_mode(), element) {
+                return false;
+            }
+            id = reference.parent;
+        }
+        true
+    }
+
+    /// Computes the order of the layers, once all of them have been declared.
+    ///
+    /// Nested layers come before their parent, in the order they were first
+    /// declared, and the root layer always comes last.
+    fn compute_layer_order(&mut self) {
+        fn compute_for_subtree(layers: &mut [CascadeLayer], id: LayerId, order: &mut LayerOrder) {
+            for i in 0..layers[id.0 as usize].children.len() {
+                let child = layers[id.0 as usize].children[i];
+                compute_for_subtree(layers, child, order);
+            }
+            layers[id.0 as usize].order = *order;
+            order.inc();
+        }
+
+        let mut order = LayerOrder::first();
+        compute_for_subtree(&mut self.layers, LayerId::root(), &mut order);
+        self.layers[LayerId::root().0 as usize].order = LayerOrder::root();
+    }
+
     /// Returns the invalidation map.
     pub fn invalidation_map(&self) -> &InvalidationMap {
         &self.invalidation_map
@@ -1963,12 +2190,55 @@ impl CascadeData {
             self.effective_media_query_results.saw_effective(stylesheet);
         }
 
-        for rule in stylesheet.effective_rules(device, guard) {
+        self.add_rule_list(
+            stylesheet.rules(guard).iter(),
+            device,
+            quirks_mode,
+            guard,
+            rebuild_kind,
+            origin,
+            LayerId::root(),
+            ContainerConditionId::none(),
+            None,
+            &mut precomputed_pseudo_element_decls,
+        )
+    }
+
+    // Returns Err(..) to signify OOM
+    fn add_rule_list<'a, 'b: 'a>(
+        &mut self,
+        rules: slice::Iter<'a, CssRule>,
+        device: &'a Device,
+        quirks_mode: QuirksMode,
+        guard: &'a SharedRwLockReadGuard<'b>,
+        rebuild_kind: SheetRebuildKind,
+        origin: Origin,
+        current_layer: LayerId,
+        current_container_condition: ContainerConditionId,
+        parent_selectors: Option<&SelectorList<SelectorImpl>>,
+        precomputed_pseudo_element_decls: &mut Option<&mut PrecomputedPseudoElementDeclarations>,
+    ) -> Result<(), FailedAllocationError> {
+        for rule in rules {
+            let mut style_rule_selectors = None;
             match *rule {
                 CssRule::Style(ref locked) => {
                     let style_rule = locked.read_with(&guard);
                     self.num_declarations += style_rule.block.read_with(&guard).len();
-                    for selector in &style_rule.selectors.0 {
+
+                    // Nested style rules match what their selectors would
+                    // with the nesting selector replaced by the parent's.
+                    let selectors = match parent_selectors {
+                        Some(parents) => Cow::Owned(SelectorList(
+                            style_rule
+                                .selectors
+                                .0
+                                .iter()
+                                .flat_map(|s| s.replace_parent_selector(&parents.0))
+                                .collect(),
+                        )),
+                        None => Cow::Borrowed(&style_rule.selectors),
+                    };
+                    for selector in &selectors.0 {
                         self.num_selectors += 1;
 
                         let pseudo_element = selector.pseudo_element();
@@ -1988,6 +2258,7 @@ impl CascadeData {
                                         CascadeLevel::UANormal,
                                         selector.specificity(),
                                         0,
+                                        LayerOrder::root(),
                                     ));
                                 continue;
                             }
@@ -2003,6 +2274,8 @@ impl CascadeData {
                             hashes,
                             locked.clone(),
                             self.rules_source_order,
+                            current_layer,
+                            current_container_condition,
                         );
 
                         if rebuild_kind.should_rebuild_invalidation() {
@@ -2058,6 +2331,7 @@ impl CascadeData {
                         }
                     }
                     self.rules_source_order += 1;
+                    style_rule_selectors = Some(selectors);
                 },
                 CssRule::Import(ref lock) => {
                     if rebuild_kind.should_rebuild_invalidation() {
@@ -2066,8 +2340,8 @@ impl CascadeData {
                             .saw_effective(import_rule);
                     }
 
-                    // NOTE: effective_rules visits the inner stylesheet if
-                    // appropriate.
+                    // NOTE: The rules of the inner stylesheet are added below
+                    // if appropriate.
                 },
                 CssRule::Media(ref lock) => {
                     if rebuild_kind.should_rebuild_invalidation() {
@@ -2111,14 +2385,83 @@ impl CascadeData {
                 CssRule::Page(ref rule) => {
                     self.extra_data.add_page(rule);
                 },
+                CssRule::LayerStatement(ref lock) => {
+                    for name in lock.read_with(guard).names.iter() {
+                        self.declare_layer(current_layer, Some(name));
+                    }
+                },
+                CssRule::Property(ref lock) => {
+                    // Later rules override earlier ones with the same name.
+                    let registration = lock.read_with(guard).registration.clone();
+                    self.custom_property_registry.register(registration);
+                },
                 // We don't care about any other rule.
                 _ => {},
             }
+
+            let children = match EffectiveRulesIterator::children(rule, device, quirks_mode, guard)
+            {
+                Some(children) => children,
+                None => continue,
+            };
+
+            // Import and layer rules can put their children in a nested layer.
+            let child_layer = match *rule {
+                CssRule::Import(ref lock) => match lock.read_with(guard).layer {
+                    Some(ref layer) => self.declare_layer(current_layer, layer.name.as_ref()),
+                    None => current_layer,
+                },
+                CssRule::LayerBlock(ref lock) => {
+                    self.declare_layer(current_layer, lock.read_with(guard).name.as_ref())
+                },
+                _ => current_layer,
+            };
+
+            let child_container_condition = match *rule {
+                CssRule::Container(ref lock) => {
+                    self.add_container_condition(current_container_condition, lock, guard)
+                },
+                _ => current_container_condition,
+            };
+
+            self.add_rule_list(
+                children,
+                device,
+                quirks_mode,
+                guard,
+                rebuild_kind,
+                origin,
+                child_layer,
+                child_container_condition,
+                style_rule_selectors
+                    .as_ref()
+                    .map_or(parent_selectors, |s| Some(&**s)),
+                precomputed_pseudo_element_decls,
+            )?;
         }
 
         Ok(())
     }
 
+    fn add_container_condition(
+        &mut self,
+        parent: ContainerConditionId,
+        rule: &Locked<ContainerRule>,
+        guard: &SharedRwLockReadGuard,
+    ) -> ContainerConditionId {
+        // Beyond what we can represent, treat nested rules as if they were
+        // in the parent condition.
+        if self.container_conditions.len() > ::std::u16::MAX as usize {
+            return parent;
+        }
+        let id = ContainerConditionId(self.container_conditions.len() as u16);
+        self.container_conditions.push(ContainerConditionReference {
+            parent,
+            condition: Some(rule.read_with(guard).condition.clone()),
+        });
+        id
+    }
+
     /// Returns whether all the media-feature affected values matched before and
     /// match now in the given stylesheet.
     pub fn media_feature_affected_matches<S>(
@@ -2164,6 +2507,10 @@ impl CascadeData {
                 CssRule::Page(..) |
                 CssRule::Viewport(..) |
                 CssRule::Document(..) |
+                CssRule::LayerBlock(..) |
+                CssRule::LayerStatement(..) |
+                CssRule::Container(..) |
+                CssRule::Property(..) |
                 CssRule::FontFeatureValues(..) => {
                     // Not affected by device changes.
                     continue;
@@ -2229,6 +2576,11 @@ impl CascadeData {
         }
         self.animations.clear();
         self.extra_data.clear();
+        self.layers.truncate(1);
+        self.layers[0] = CascadeLayer::new();
+        self.layer_ids.clear();
+        self.container_conditions.truncate(1);
+        self.custom_property_registry.clear();
         self.rules_source_order = 0;
         self.num_selectors = 0;
         self.num_declarations = 0;
@@ -2291,6 +2643,13 @@ pub struct Rule {
     /// we could repurpose that storage here if we needed to.
     pub source_order: u32,
 
+    /// The cascade layer this style rule is in.
+    pub layer_id: LayerId,
+
+    /// The condition of the innermost `@container` rule this style rule is
+    /// in, if any.
+    pub container_condition_id: ContainerConditionId,
+
     /// The actual style rule.
     #[cfg_attr(
         feature = "gecko",
@@ -2313,11 +2672,12 @@ impl Rule {
     }
 
     /// Turns this rule into an `ApplicableDeclarationBlock` for the given
-    /// cascade level.
+    /// cascade level, with the layer order of the `CascadeData` it belongs to.
     pub fn to_applicable_declaration_block(
         &self,
         level: CascadeLevel,
         shadow_cascade_order: ShadowCascadeOrder,
+        cascade_data: &CascadeData,
     ) -> ApplicableDeclarationBlock {
         let source = StyleSource::from_rule(self.style_rule.clone());
         ApplicableDeclarationBlock::new(
@@ -2326,6 +2686,7 @@ impl Rule {
             level,
             self.specificity(),
             shadow_cascade_order,
+            cascade_data.layer_order_for(self.layer_id),
         )
     }
 
@@ -2335,12 +2696,16 @@ impl Rule {
         hashes: AncestorHashes,
         style_rule: Arc<Locked<StyleRule>>,
         source_order: u32,
+        layer_id: LayerId,
+        container_condition_id: ContainerConditionId,
     ) -> Self {
         Rule {
             selector: selector,
             hashes: hashes,
             style_rule: style_rule,
             source_order: source_order,
+            layer_id: layer_id,
+            container_condition_id: container_condition_id,
         }
     }
 }
@@ -2362,3 +2727,30 @@ pub fn needs_revalidation_for_testing(s: &Selector<SelectorImpl>) -> bool {
     s.visit(&mut visitor);
     visitor.needs_revalidation
 }
+
+/// A function to be able to test how the rules of `stylesheets` are put in
+/// cascade layers, without needing to flush a `Stylist`.
+pub fn cascade_data_for_testing<S>(
+    device: &Device,
+    quirks_mode: QuirksMode,
+    stylesheets: &[&S],
+    guard: &SharedRwLockReadGuard,
+) -> CascadeData
+where
+    S: StylesheetInDocument + ToMediaListKey + 'static,
+{
+    let mut data = CascadeData::new();
+    for stylesheet in stylesheets {
+        data.add_stylesheet(
+            device,
+            quirks_mode,
+            *stylesheet,
+            guard,
+            SheetRebuildKind::Full,
+            /* precomputed_pseudo_element_decls = */ None,
+        )
+        .expect("OOM");
+    }
+    data.compute_layer_order();
+    data
+}
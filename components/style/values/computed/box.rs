@@ -13,10 +13,10 @@ use crate::values::specified::box_ as specified;
 
 pub use crate::values::specified::box_::{AnimationName, Appearance, BreakBetween, BreakWithin};
 pub use crate::values::specified::box_::{Clear as SpecifiedClear, Float as SpecifiedFloat};
-pub use crate::values::specified::box_::{Contain, Display, Overflow};
+pub use crate::values::specified::box_::{Contain, ContainerName, Display, Overflow};
 pub use crate::values::specified::box_::{OverflowAnchor, OverflowClipBox, OverscrollBehavior};
 pub use crate::values::specified::box_::{
-    ScrollSnapAlign, ScrollSnapAxis, ScrollSnapStrictness, ScrollSnapType,
+    ScrollSnapAlign, ScrollSnapAlignKeyword, ScrollSnapAxis, ScrollSnapStrictness, ScrollSnapType,
 };
 pub use crate::values::specified::box_::{TouchAction, TransitionProperty, WillChange};
 
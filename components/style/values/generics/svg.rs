@@ -8,6 +8,19 @@ use crate::parser::{Parse, ParserContext};
 use cssparser::Parser;
 use style_traits::ParseError;
 
+/// Whether the context paint keywords are enabled.
+#[cfg(feature = "gecko")]
+fn is_context_paint_enabled(_context: &ParserContext) -> bool {
+    true
+}
+
+/// Whether the context paint keywords are enabled. Servo has no context
+/// element to resolve them against, as it doesn't support SVG glyphs.
+#[cfg(not(feature = "gecko"))]
+fn is_context_paint_enabled(_context: &ParserContext) -> bool {
+    false
+}
+
 /// The fallback of an SVG paint server value.
 #[derive(
     Animate,
@@ -109,8 +122,10 @@ pub enum GenericSVGPaintKind<C, U> {
     #[animation(error)]
     PaintServer(U),
     /// `context-fill`
+    #[parse(condition = "is_context_paint_enabled")]
     ContextFill,
     /// `context-stroke`
+    #[parse(condition = "is_context_paint_enabled")]
     ContextStroke,
 }
 
@@ -209,9 +224,11 @@ pub enum GenericSVGOpacity<OpacityType> {
     Opacity(OpacityType),
     /// `context-fill-opacity`
     #[animation(error)]
+    #[parse(condition = "is_context_paint_enabled")]
     ContextFillOpacity,
     /// `context-stroke-opacity`
     #[animation(error)]
+    #[parse(condition = "is_context_paint_enabled")]
     ContextStrokeOpacity,
 }
 
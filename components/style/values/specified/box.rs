@@ -888,8 +888,8 @@ pub enum ScrollSnapStrictness {
 )]
 #[repr(C)]
 pub struct ScrollSnapType {
-    axis: ScrollSnapAxis,
-    strictness: ScrollSnapStrictness,
+    pub axis: ScrollSnapAxis,
+    pub strictness: ScrollSnapStrictness,
 }
 
 impl ScrollSnapType {
@@ -983,8 +983,8 @@ pub enum ScrollSnapAlignKeyword {
 )]
 #[repr(C)]
 pub struct ScrollSnapAlign {
-    block: ScrollSnapAlignKeyword,
-    inline: ScrollSnapAlignKeyword,
+    pub block: ScrollSnapAlignKeyword,
+    pub inline: ScrollSnapAlignKeyword,
 }
 
 impl ScrollSnapAlign {
@@ -1401,6 +1401,69 @@ impl Parse for Contain {
     }
 }
 
+/// A specified value for the `container-name` property.
+///
+/// `none` is represented by an empty list.
+///
+/// <https://drafts.csswg.org/css-contain-3/#container-name>
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    MallocSizeOf,
+    PartialEq,
+    SpecifiedValueInfo,
+    ToComputedValue,
+    ToCss,
+    ToResolvedValue,
+    ToShmem,
+)]
+#[repr(C)]
+pub struct ContainerName(#[css(iterable, if_empty = "none")] pub crate::OwnedSlice<CustomIdent>);
+
+impl ContainerName {
+    /// Returns the `none` value.
+    #[inline]
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether this is the `none` value.
+    #[inline]
+    pub fn is_none(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Parses a single container name, as found in this property and in the
+    /// prelude of `@container` rules.
+    pub fn parse_ident<'i, 't>(input: &mut Parser<'i, 't>) -> Result<CustomIdent, ParseError<'i>> {
+        let location = input.current_source_location();
+        CustomIdent::from_ident(
+            location,
+            input.expect_ident()?,
+            &["none", "and", "not", "or"],
+        )
+    }
+}
+
+impl Parse for ContainerName {
+    /// none | <custom-ident>+
+    fn parse<'i, 't>(
+        _context: &ParserContext,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<ContainerName, ParseError<'i>> {
+        if input.try(|i| i.expect_ident_matching("none")).is_ok() {
+            return Ok(Self::none());
+        }
+
+        let mut idents = vec![Self::parse_ident(input)?];
+        while let Ok(ident) = input.try(Self::parse_ident) {
+            idents.push(ident);
+        }
+        Ok(ContainerName(idents.into()))
+    }
+}
+
 /// A specified value for the `perspective` property.
 pub type Perspective = GenericPerspective<NonNegativeLength>;
 
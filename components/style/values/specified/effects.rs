@@ -78,6 +78,16 @@ macro_rules! factor_impl_common {
             fn one() -> Self {
                 Self(NumberOrPercentage::Number(Number::new(1.)))
             }
+
+            /// Returns the value of this factor as a number, without
+            /// computing it.
+            #[inline]
+            pub fn to_number(&self) -> f32 {
+                match self.0 {
+                    NumberOrPercentage::Number(number) => number.get(),
+                    NumberOrPercentage::Percentage(percentage) => percentage.get(),
+                }
+            }
         }
 
         impl ToComputedValue for $ty {
@@ -44,6 +44,33 @@ impl SVGPathData {
         &self.0
     }
 
+    /// Parse an SVG path string that doesn't come from CSS, such as the
+    /// argument of the `Path2D` constructor, and return its commands converted
+    /// to absolute commands.
+    ///
+    /// Following the error handling rules of path data, the commands that
+    /// precede the first error are kept.
+    ///
+    /// https://svgwg.org/svg2-draft/paths.html#PathDataErrorHandling
+    pub fn parse_normalized_commands(path_string: &str) -> Box<[PathCommand]> {
+        let mut path_parser = PathParser::new(path_string);
+        while skip_wsp(&mut path_parser.chars) {
+            if path_parser.parse_subpath().is_err() {
+                break;
+            }
+        }
+
+        let mut state = PathTraversalState {
+            subpath_start: CoordPair::new(0.0, 0.0),
+            pos: CoordPair::new(0.0, 0.0),
+        };
+        path_parser
+            .path
+            .iter()
+            .map(|seg| seg.normalize(&mut state))
+            .collect()
+    }
+
     /// Create a normalized copy of this path by converting each relative
     /// command to an absolute command.
     fn normalize(&self) -> Box<[PathCommand]> {
@@ -524,6 +551,18 @@ impl CoordPair {
     pub fn new(x: CSSFloat, y: CSSFloat) -> Self {
         CoordPair(x, y)
     }
+
+    /// The x coordinate.
+    #[inline]
+    pub fn x(&self) -> CSSFloat {
+        self.0
+    }
+
+    /// The y coordinate.
+    #[inline]
+    pub fn y(&self) -> CSSFloat {
+        self.1
+    }
 }
 
 /// The EllipticalArc flag type.
@@ -531,6 +570,14 @@ impl CoordPair {
 #[repr(C)]
 pub struct ArcFlag(bool);
 
+impl ArcFlag {
+    /// Whether the flag is set.
+    #[inline]
+    pub fn is_set(&self) -> bool {
+        self.0
+    }
+}
+
 impl ToCss for ArcFlag {
     #[inline]
     fn to_css<W>(&self, dest: &mut CssWriter<W>) -> fmt::Result
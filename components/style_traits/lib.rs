@@ -68,6 +68,29 @@ impl PinchZoomFactor {
     }
 }
 
+/// A color scheme the user can prefer pages to use.
+///
+/// <https://drafts.csswg.org/mediaqueries-5/#prefers-color-scheme>
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "servo", derive(Deserialize, Serialize, MallocSizeOf))]
+pub enum ColorScheme {
+    /// A light color scheme, with dark text on light backgrounds.
+    Light,
+    /// A dark color scheme, with light text on dark backgrounds.
+    Dark,
+}
+
+/// The preferences of the user that media queries can test for, as set by
+/// the embedder.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "servo", derive(Deserialize, Serialize, MallocSizeOf))]
+pub struct MediaPreferences {
+    /// The color scheme the user prefers, if any.
+    pub color_scheme: Option<ColorScheme>,
+    /// Whether the user prefers to minimize non-essential motion.
+    pub reduced_motion: bool,
+}
+
 /// One CSS "px" in the coordinate system of the "initial viewport":
 /// <http://www.w3.org/TR/css-device-adapt/#initial-viewport>
 ///
@@ -7,7 +7,7 @@ use crate::window_trait::{WindowPortsMethods, LINE_HEIGHT};
 use euclid::{Point2D, Vector2D};
 use keyboard_types::{Key, KeyboardEvent, Modifiers, ShortcutMatcher};
 use servo::compositing::windowing::{WebRenderDebugOption, WindowEvent};
-use servo::embedder_traits::{EmbedderMsg, FilterPattern};
+use servo::embedder_traits::{ClipboardData, Cursor, DragOperation, EmbedderMsg, FilterPattern};
 use servo::msg::constellation_msg::TopLevelBrowsingContextId as BrowserId;
 use servo::msg::constellation_msg::TraversalDirection;
 use servo::net_traits::pub_domains::is_reg_domain;
@@ -45,6 +45,10 @@ pub struct Browser<Window: WindowPortsMethods + ?Sized> {
     window: Rc<Window>,
     event_queue: Vec<WindowEvent>,
     clipboard_ctx: Option<ClipboardContext>,
+    /// The data last written to the clipboard by Servo. The system clipboard
+    /// only holds its text, so the other formats are kept here for as long as
+    /// the text is unchanged.
+    clipboard_data: Option<ClipboardData>,
     shutdown_requested: bool,
 }
 
@@ -75,6 +79,7 @@ where
                     None
                 },
             },
+            clipboard_data: None,
             event_queue: Vec::new(),
             shutdown_requested: false,
         }
@@ -377,9 +382,48 @@ where
                         }
                     }
                 }
+                EmbedderMsg::GetClipboardData(sender) => {
+                    let text = match self.clipboard_ctx {
+                        Some(ref mut ctx) => match ctx.get_contents() {
+                            Ok(text) => text,
+                            Err(e) => {
+                                warn!("Error getting clipboard contents ({})", e);
+                                String::new()
+                            },
+                        },
+                        None => String::new(),
+                    };
+                    let data = match self.clipboard_data {
+                        Some(ref data) if data.text().map_or(text.is_empty(), |t| t == text) => {
+                            data.clone()
+                        },
+                        _ if text.is_empty() => ClipboardData::default(),
+                        _ => ClipboardData::from_text(text),
+                    };
+                    if let Err(e) = sender.send(data) {
+                        warn!("Failed to send clipboard ({})", e);
+                    }
+                },
+                EmbedderMsg::SetClipboardData(data) => {
+                    if let Some(ref mut ctx) = self.clipboard_ctx {
+                        if let Err(e) = ctx.set_contents(data.text().unwrap_or_default()) {
+                            warn!("Error setting clipboard contents ({})", e);
+                        }
+                    }
+                    self.clipboard_data = Some(data);
+                },
                 EmbedderMsg::SetCursor(cursor) => {
                     self.window.set_cursor(cursor);
                 },
+                EmbedderMsg::UpdateDragOperation(operation) => {
+                    let cursor = match operation {
+                        DragOperation::None => Cursor::NoDrop,
+                        DragOperation::Copy => Cursor::Copy,
+                        DragOperation::Link => Cursor::Alias,
+                        DragOperation::Move => Cursor::Move,
+                    };
+                    self.window.set_cursor(cursor);
+                },
                 EmbedderMsg::NewFavicon(url) => {
                     self.favicon = Some(url);
                 },
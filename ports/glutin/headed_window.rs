@@ -23,7 +23,7 @@ use glutin::{ElementState, KeyboardInput, MouseButton, MouseScrollDelta, TouchPh
 #[cfg(any(target_os = "linux", target_os = "windows"))]
 use image;
 use keyboard_types::{Key, KeyState, KeyboardEvent};
-use servo::compositing::windowing::{AnimationState, MouseWindowEvent, WindowEvent};
+use servo::compositing::windowing::{AnimationState, FileDragEvent, MouseWindowEvent, WindowEvent};
 use servo::compositing::windowing::{EmbedderCoordinates, WindowMethods};
 use servo::embedder_traits::Cursor;
 use servo::script_traits::{TouchEventType, WheelMode, WheelDelta};
@@ -35,6 +35,7 @@ use servo::webrender_api::units::{DeviceIntPoint, DeviceIntRect, DeviceIntSize};
 use servo_media::player::context::{GlApi, GlContext as PlayerGLContext, NativeDisplay};
 use std::cell::{Cell, RefCell};
 use std::mem;
+use std::path::PathBuf;
 use std::rc::Rc;
 #[cfg(target_os = "windows")]
 use winapi;
@@ -66,6 +67,10 @@ pub struct Window {
     primary_monitor: glutin::MonitorId,
     event_queue: RefCell<Vec<WindowEvent>>,
     mouse_pos: Cell<Point2D<i32, DevicePixel>>,
+    /// The files dragged over the window.
+    hovered_files: RefCell<Vec<PathBuf>>,
+    /// The files dropped so far, since winit reports them one at a time.
+    dropped_files: RefCell<Vec<PathBuf>>,
     last_pressed: Cell<Option<KeyboardEvent>>,
     animation_state: Cell<AnimationState>,
     fullscreen: Cell<bool>,
@@ -190,6 +195,8 @@ impl Window {
             mouse_down_button: Cell::new(None),
             mouse_down_point: Cell::new(Point2D::new(0, 0)),
             mouse_pos: Cell::new(Point2D::new(0, 0)),
+            hovered_files: RefCell::new(vec![]),
+            dropped_files: RefCell::new(vec![]),
             last_pressed: Cell::new(None),
             gl: gl.clone(),
             animation_state: Cell::new(AnimationState::Idle),
@@ -439,11 +446,42 @@ impl WindowPortsMethods for Window {
                 let pos = position.to_physical(self.device_hidpi_factor().get() as f64);
                 let (x, y): (i32, i32) = pos.into();
                 self.mouse_pos.set(Point2D::new(x, y));
+                let point = Point2D::new(x as f32, y as f32);
+                let event = if self.hovered_files.borrow().is_empty() {
+                    WindowEvent::MouseWindowMoveEventClass(point)
+                } else {
+                    let files = self.hovered_files.borrow().clone();
+                    WindowEvent::FileDrag(FileDragEvent::Over(files, point))
+                };
+                self.event_queue.borrow_mut().push(event);
+            },
+            glutin::WindowEvent::HoveredFile(path) => {
+                self.hovered_files.borrow_mut().push(path);
+                let files = self.hovered_files.borrow().clone();
+                let point = self.mouse_pos.get().to_f32();
+                self.event_queue
+                    .borrow_mut()
+                    .push(WindowEvent::FileDrag(FileDragEvent::Over(files, point)));
+            },
+            glutin::WindowEvent::DroppedFile(path) => {
+                // Winit reports each file of a drop separately, after the
+                // hovered ones, so wait for the last one.
+                self.hovered_files.borrow_mut().retain(|file| *file != path);
+                self.dropped_files.borrow_mut().push(path);
+                if self.hovered_files.borrow().is_empty() {
+                    let files = mem::replace(&mut *self.dropped_files.borrow_mut(), vec![]);
+                    let point = self.mouse_pos.get().to_f32();
+                    self.event_queue
+                        .borrow_mut()
+                        .push(WindowEvent::FileDrag(FileDragEvent::Drop(files, point)));
+                }
+            },
+            glutin::WindowEvent::HoveredFileCancelled => {
+                self.hovered_files.borrow_mut().clear();
+                self.dropped_files.borrow_mut().clear();
                 self.event_queue
                     .borrow_mut()
-                    .push(WindowEvent::MouseWindowMoveEventClass(Point2D::new(
-                        x as f32, y as f32,
-                    )));
+                    .push(WindowEvent::FileDrag(FileDragEvent::Cancel));
             },
             glutin::WindowEvent::MouseWheel { delta, phase, .. } => {
                 let (mut dx, mut dy, mode) = match delta {
@@ -26,6 +26,7 @@ fn filename(file: Resource) -> &'static str {
         Resource::NetErrorHTML => "neterror.html",
         Resource::UserAgentCSS => "user-agent.css",
         Resource::ServoCSS => "servo.css",
+        Resource::SVGFallbackCSS => "svg-fallback.css",
         Resource::PresentationalHintsCSS => "presentational-hints.css",
         Resource::QuirksModeCSS => "quirks-mode.css",
         Resource::RippyPNG => "rippy.png",
@@ -14,7 +14,7 @@ use servo::compositing::windowing::{
     WindowMethods,
 };
 use servo::embedder_traits::resources::{self, Resource, ResourceReaderMethods};
-use servo::embedder_traits::EmbedderMsg;
+use servo::embedder_traits::{ClipboardData, EmbedderMsg};
 use servo::euclid::{Point2D, Rect, Scale, Size2D, Vector2D};
 use servo::keyboard_types::{Key, KeyState, KeyboardEvent};
 use servo::msg::constellation_msg::TraversalDirection;
@@ -545,6 +545,20 @@ impl ServoGlue {
                 EmbedderMsg::SetClipboardContents(text) => {
                     self.callbacks.host_callbacks.set_clipboard_contents(text);
                 },
+                EmbedderMsg::GetClipboardData(sender) => {
+                    let data = self
+                        .callbacks
+                        .host_callbacks
+                        .get_clipboard_contents()
+                        .map_or(ClipboardData::default(), ClipboardData::from_text);
+                    let _ = sender.send(data);
+                },
+                EmbedderMsg::SetClipboardData(data) => {
+                    // Hosts only support plain text.
+                    if let Some(text) = data.text() {
+                        self.callbacks.host_callbacks.set_clipboard_contents(text);
+                    }
+                },
                 EmbedderMsg::CloseBrowser => {
                     // TODO: close the appropriate "tab".
                     let _ = self.browsers.pop();
@@ -575,7 +589,8 @@ impl ServoGlue {
                 EmbedderMsg::HeadParsed |
                 EmbedderMsg::SetFullscreenState(..) |
                 EmbedderMsg::Panic(..) |
-                EmbedderMsg::ReportProfile(..) => {},
+                EmbedderMsg::ReportProfile(..) |
+                EmbedderMsg::UpdateDragOperation(..) => {},
             }
         }
         Ok(())
@@ -717,6 +732,9 @@ impl ResourceReaderMethods for ResourceReaderInstance {
             Resource::NetErrorHTML => &include_bytes!("../../../../resources/neterror.html")[..],
             Resource::UserAgentCSS => &include_bytes!("../../../../resources/user-agent.css")[..],
             Resource::ServoCSS => &include_bytes!("../../../../resources/servo.css")[..],
+            Resource::SVGFallbackCSS => {
+                &include_bytes!("../../../../resources/svg-fallback.css")[..]
+            },
             Resource::PresentationalHintsCSS => {
                 &include_bytes!("../../../../resources/presentational-hints.css")[..]
             },
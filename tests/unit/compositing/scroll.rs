@@ -0,0 +1,223 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use compositing::scroll::snap_axis_for_testing as snap_axis;
+use compositing::scroll::{contain_scroll_delta, snap_position};
+use euclid::Vector2D;
+use script_traits::{OverscrollBehavior, ScrollAxisBehavior};
+use script_traits::{ScrollContainerInfo, ScrollSnapStrictness};
+use std::collections::HashMap;
+use webrender_api::units::{LayoutPoint, LayoutRect, LayoutSize, LayoutVector2D};
+use webrender_api::{ExternalScrollId, PipelineId};
+
+fn axis(
+    snap_strictness: Option<ScrollSnapStrictness>,
+    snap_positions: &[f32],
+    overscroll_behavior: OverscrollBehavior,
+) -> ScrollAxisBehavior {
+    ScrollAxisBehavior {
+        snap_strictness,
+        snap_positions: snap_positions.to_vec(),
+        overscroll_behavior,
+    }
+}
+
+fn scroll_id(id: u64) -> ExternalScrollId {
+    ExternalScrollId(id, PipelineId::dummy())
+}
+
+/// A scroll container that doesn't snap, with the given scrollport and largest vertical
+/// scroll position.
+fn container(
+    id: u64,
+    parent_id: Option<u64>,
+    scrollport: (f32, f32, f32, f32),
+    max_scroll_y: f32,
+    overscroll_behavior_y: OverscrollBehavior,
+) -> ScrollContainerInfo {
+    let (x, y, width, height) = scrollport;
+    ScrollContainerInfo {
+        scroll_id: scroll_id(id),
+        parent_scroll_id: parent_id.map(scroll_id),
+        scrollport: LayoutRect::new(LayoutPoint::new(x, y), LayoutSize::new(width, height)),
+        max_scroll_position: LayoutVector2D::new(0., max_scroll_y),
+        x: axis(None, &[], OverscrollBehavior::Auto),
+        y: axis(None, &[], overscroll_behavior_y),
+    }
+}
+
+fn positions(positions: &[(u64, f32)]) -> HashMap<ExternalScrollId, LayoutVector2D> {
+    positions
+        .iter()
+        .map(|&(id, y)| (scroll_id(id), LayoutVector2D::new(0., y)))
+        .collect()
+}
+
+#[test]
+fn test_snap_axis_without_snapping() {
+    let behavior = axis(None, &[0., 100.], OverscrollBehavior::Auto);
+    assert_eq!(snap_axis(&behavior, 0., 40., 100., false), 40.);
+}
+
+#[test]
+fn test_snap_axis_mandatory() {
+    let behavior = axis(
+        Some(ScrollSnapStrictness::Mandatory),
+        &[0., 100., 200.],
+        OverscrollBehavior::Auto,
+    );
+    assert_eq!(snap_axis(&behavior, 0., 130., 100., false), 100.);
+    assert_eq!(snap_axis(&behavior, 0., 160., 100., false), 200.);
+    assert_eq!(snap_axis(&behavior, 0., 1000., 100., false), 200.);
+
+    let behavior = axis(
+        Some(ScrollSnapStrictness::Mandatory),
+        &[],
+        OverscrollBehavior::Auto,
+    );
+    assert_eq!(snap_axis(&behavior, 0., 130., 100., false), 130.);
+}
+
+#[test]
+fn test_snap_axis_proximity() {
+    let behavior = axis(
+        Some(ScrollSnapStrictness::Proximity),
+        &[0., 100., 200.],
+        OverscrollBehavior::Auto,
+    );
+    // Scrolls snap within 30% of the size of the snapport.
+    assert_eq!(snap_axis(&behavior, 0., 130., 100., false), 100.);
+    assert_eq!(snap_axis(&behavior, 0., 140., 100., false), 140.);
+    assert_eq!(snap_axis(&behavior, 0., 140., 200., false), 100.);
+}
+
+#[test]
+fn test_snap_axis_directional() {
+    let behavior = axis(
+        Some(ScrollSnapStrictness::Mandatory),
+        &[0., 100., 200.],
+        OverscrollBehavior::Auto,
+    );
+    assert_eq!(snap_axis(&behavior, 100., 110., 100., false), 100.);
+    assert_eq!(snap_axis(&behavior, 100., 110., 100., true), 200.);
+    assert_eq!(snap_axis(&behavior, 100., 90., 100., true), 0.);
+    // Without snap positions past the start, the nearest one is used.
+    assert_eq!(snap_axis(&behavior, 200., 210., 100., true), 200.);
+}
+
+#[test]
+fn test_snap_position() {
+    let mut container = container(
+        1,
+        None,
+        (0., 0., 100., 100.),
+        300.,
+        OverscrollBehavior::Auto,
+    );
+    container.y = axis(
+        Some(ScrollSnapStrictness::Mandatory),
+        &[0., 100., 200., 300.],
+        OverscrollBehavior::Auto,
+    );
+    assert_eq!(
+        snap_position(
+            &container,
+            Vector2D::zero(),
+            LayoutVector2D::new(30., 170.),
+            false,
+        ),
+        LayoutVector2D::new(30., 200.),
+    );
+    assert_eq!(
+        snap_position(
+            &container,
+            LayoutVector2D::new(0., 100.),
+            LayoutVector2D::new(0., 120.),
+            true,
+        ),
+        LayoutVector2D::new(0., 200.),
+    );
+}
+
+#[test]
+fn test_contain_scroll_delta() {
+    let containers = vec![
+        container(
+            1,
+            None,
+            (0., 0., 800., 600.),
+            1000.,
+            OverscrollBehavior::Auto,
+        ),
+        container(
+            2,
+            Some(1),
+            (0., 100., 200., 200.),
+            300.,
+            OverscrollBehavior::Contain,
+        ),
+        container(
+            3,
+            Some(1),
+            (400., 100., 200., 200.),
+            300.,
+            OverscrollBehavior::Auto,
+        ),
+    ];
+    // Deltas move the contents, so a negative delta scrolls down.
+    let down = LayoutVector2D::new(5., -10.);
+    let inner = LayoutPoint::new(10., 150.);
+    let chaining_inner = LayoutPoint::new(410., 150.);
+
+    // Scrolls that move the container under the point aren't contained.
+    let delta = contain_scroll_delta(&containers, &positions(&[(2, 100.)]), inner, down);
+    assert_eq!(delta, down);
+
+    // Scrolls past the edge of a container with `overscroll-behavior: contain` don't chain
+    // vertically.
+    let delta = contain_scroll_delta(&containers, &positions(&[(2, 300.)]), inner, down);
+    assert_eq!(delta, LayoutVector2D::new(5., 0.));
+
+    // Scrolls past the edge of a container with `overscroll-behavior: auto` chain.
+    let delta = contain_scroll_delta(&containers, &positions(&[(3, 300.)]), chaining_inner, down);
+    assert_eq!(delta, down);
+
+    // Scrolls outside of the scrollport of the inner containers chain.
+    let outer = LayoutPoint::new(10., 400.);
+    let delta = contain_scroll_delta(&containers, &positions(&[(2, 300.)]), outer, down);
+    assert_eq!(delta, down);
+}
+
+#[test]
+fn test_contain_scroll_delta_in_scrolled_container() {
+    let containers = vec![
+        container(
+            1,
+            None,
+            (0., 0., 800., 600.),
+            1000.,
+            OverscrollBehavior::Auto,
+        ),
+        container(
+            2,
+            Some(1),
+            (0., 100., 200., 200.),
+            300.,
+            OverscrollBehavior::None,
+        ),
+    ];
+    let down = LayoutVector2D::new(0., -10.);
+    let point = LayoutPoint::new(10., 50.);
+
+    // The scrollport of the inner container moves up as the outer one scrolls down.
+    let delta = contain_scroll_delta(&containers, &positions(&[(2, 300.)]), point, down);
+    assert_eq!(delta, down);
+    let delta = contain_scroll_delta(
+        &containers,
+        &positions(&[(1, 100.), (2, 300.)]),
+        point,
+        down,
+    );
+    assert_eq!(delta, Vector2D::zero());
+}
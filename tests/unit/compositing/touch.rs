@@ -0,0 +1,150 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use compositing::touch::{TouchAction, TouchHandler, TouchState};
+use euclid::{Point2D, Vector2D};
+use script_traits::{EventResult, TouchBehavior, TouchId};
+use style_traits::DevicePixel;
+
+fn point(x: f32, y: f32) -> Point2D<f32, DevicePixel> {
+    Point2D::new(x, y)
+}
+
+fn behavior(pan_x: bool, pan_y: bool, pinch_zoom: bool) -> TouchBehavior {
+    TouchBehavior {
+        pan_x,
+        pan_y,
+        pinch_zoom,
+    }
+}
+
+/// Starts a single touch sequence at the origin, which script allows with the
+/// given behaviors.
+fn touch_down(behavior: TouchBehavior) -> TouchHandler {
+    let mut handler = TouchHandler::new();
+    handler.on_touch_down(TouchId(0), point(0., 0.));
+    assert_eq!(handler.state, TouchState::WaitingForScript);
+    handler.on_event_processed(EventResult::DefaultAllowed(behavior));
+    handler
+}
+
+fn assert_start_scroll(action: TouchAction, x: f32, y: f32) {
+    match action {
+        TouchAction::StartScroll(delta) => assert_eq!(delta, Vector2D::new(x, y)),
+        action => panic!("expected StartScroll, got {:?}", action),
+    }
+}
+
+#[test]
+fn test_tap_clicks() {
+    let mut handler = touch_down(TouchBehavior::auto());
+    assert_eq!(handler.state, TouchState::Touching);
+
+    match handler.on_touch_move(TouchId(0), point(5., 5.)) {
+        TouchAction::DispatchEvent => {},
+        action => panic!("expected DispatchEvent, got {:?}", action),
+    }
+    match handler.on_touch_up(TouchId(0), point(5., 5.)) {
+        TouchAction::Click => {},
+        action => panic!("expected Click, got {:?}", action),
+    }
+    assert_eq!(handler.state, TouchState::Nothing);
+}
+
+#[test]
+fn test_pan_starts_past_threshold() {
+    let mut handler = touch_down(TouchBehavior::auto());
+
+    assert_start_scroll(handler.on_touch_move(TouchId(0), point(0., 30.)), 0., 30.);
+    assert_eq!(handler.state, TouchState::Panning);
+    assert!(handler.is_canceled(TouchId(0)));
+
+    match handler.on_touch_move(TouchId(0), point(0., 40.)) {
+        TouchAction::Scroll(delta) => assert_eq!(delta, Vector2D::new(0., 10.)),
+        action => panic!("expected Scroll, got {:?}", action),
+    }
+    match handler.on_touch_up(TouchId(0), point(0., 40.)) {
+        TouchAction::NoAction => {},
+        action => panic!("expected NoAction, got {:?}", action),
+    }
+    assert_eq!(handler.state, TouchState::Nothing);
+}
+
+#[test]
+fn test_pan_y_ignores_horizontal_movement() {
+    let mut handler = touch_down(behavior(false, true, false));
+
+    // Horizontal movement alone never starts a pan...
+    match handler.on_touch_move(TouchId(0), point(50., 0.)) {
+        TouchAction::DispatchEvent => {},
+        action => panic!("expected DispatchEvent, got {:?}", action),
+    }
+    assert_eq!(handler.state, TouchState::Touching);
+
+    // ...and is dropped from the scroll once vertical movement does.
+    assert_start_scroll(handler.on_touch_move(TouchId(0), point(50., 30.)), 0., 30.);
+}
+
+#[test]
+fn test_pan_x_ignores_vertical_movement() {
+    let mut handler = touch_down(behavior(true, false, false));
+
+    assert_start_scroll(handler.on_touch_move(TouchId(0), point(30., 50.)), 30., 0.);
+}
+
+#[test]
+fn test_default_prevented() {
+    let mut handler = TouchHandler::new();
+    handler.on_touch_down(TouchId(0), point(0., 0.));
+    handler.on_event_processed(EventResult::DefaultPrevented);
+    assert_eq!(handler.state, TouchState::DefaultPrevented);
+
+    match handler.on_touch_move(TouchId(0), point(0., 100.)) {
+        TouchAction::DispatchEvent => {},
+        action => panic!("expected DispatchEvent, got {:?}", action),
+    }
+    match handler.on_touch_up(TouchId(0), point(0., 100.)) {
+        TouchAction::NoAction => {},
+        action => panic!("expected NoAction, got {:?}", action),
+    }
+    assert_eq!(handler.state, TouchState::Nothing);
+}
+
+#[test]
+fn test_second_touch_pinches_when_allowed() {
+    let mut handler = touch_down(TouchBehavior::auto());
+    handler.on_touch_down(TouchId(1), point(100., 0.));
+    assert_eq!(handler.state, TouchState::Pinching);
+
+    match handler.on_touch_move(TouchId(1), point(200., 0.)) {
+        TouchAction::Zoom(magnification, _) => assert_eq!(magnification, 2.),
+        action => panic!("expected Zoom, got {:?}", action),
+    }
+}
+
+#[test]
+fn test_second_touch_does_not_pinch_when_disallowed() {
+    let mut handler = touch_down(behavior(true, true, false));
+    handler.on_touch_down(TouchId(1), point(100., 0.));
+    assert_eq!(handler.state, TouchState::MultiTouch);
+
+    match handler.on_touch_move(TouchId(1), point(200., 0.)) {
+        TouchAction::NoAction => {},
+        action => panic!("expected NoAction, got {:?}", action),
+    }
+}
+
+#[test]
+fn test_new_sequence_resets_allowed_behavior() {
+    let mut handler = touch_down(behavior(false, true, false));
+    handler.on_touch_up(TouchId(0), point(0., 0.));
+    assert_eq!(handler.state, TouchState::Nothing);
+
+    // The next sequence must not inherit the previous `touch-action`, even
+    // if script prevents its default actions.
+    handler.on_touch_down(TouchId(1), point(0., 0.));
+    assert_eq!(handler.allowed_behavior, TouchBehavior::auto());
+    handler.on_event_processed(EventResult::DefaultPrevented);
+    assert_eq!(handler.allowed_behavior, TouchBehavior::auto());
+}
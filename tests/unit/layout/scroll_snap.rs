@@ -0,0 +1,90 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use app_units::Au;
+use layout::display_list::snap_positions_for_testing;
+use style::values::computed::box_::ScrollSnapAlignKeyword;
+
+/// Returns the snap positions of the given snap areas, as their alignment and their start
+/// and end edges in CSS pixels, in a snapport from 0px to 100px with a largest scroll
+/// position of 500px.
+fn positions(snap_areas: &[(ScrollSnapAlignKeyword, i32, i32)], reversed: bool) -> Vec<f32> {
+    let snap_areas: Vec<_> = snap_areas
+        .iter()
+        .map(|&(align, start, end)| (align, Au::from_px(start), Au::from_px(end)))
+        .collect();
+    snap_positions_for_testing(
+        &snap_areas,
+        (Au(0), Au::from_px(100)),
+        Au::from_px(500),
+        reversed,
+    )
+}
+
+#[test]
+fn test_snap_alignment() {
+    assert_eq!(
+        positions(&[(ScrollSnapAlignKeyword::Start, 150, 200)], false),
+        vec![150.]
+    );
+    assert_eq!(
+        positions(&[(ScrollSnapAlignKeyword::End, 150, 200)], false),
+        vec![100.]
+    );
+    assert_eq!(
+        positions(&[(ScrollSnapAlignKeyword::Center, 150, 200)], false),
+        vec![125.]
+    );
+    assert_eq!(
+        positions(&[(ScrollSnapAlignKeyword::None, 150, 200)], false),
+        Vec::<f32>::new()
+    );
+}
+
+#[test]
+fn test_reversed_snap_alignment() {
+    // Along axes that run backwards, the start of a snap area is its end edge.
+    assert_eq!(
+        positions(&[(ScrollSnapAlignKeyword::Start, 150, 200)], true),
+        vec![100.]
+    );
+    assert_eq!(
+        positions(&[(ScrollSnapAlignKeyword::End, 150, 200)], true),
+        vec![150.]
+    );
+    assert_eq!(
+        positions(&[(ScrollSnapAlignKeyword::Center, 150, 200)], true),
+        vec![125.]
+    );
+}
+
+#[test]
+fn test_snap_positions_are_clamped() {
+    assert_eq!(
+        positions(
+            &[
+                (ScrollSnapAlignKeyword::End, 0, 50),
+                (ScrollSnapAlignKeyword::Start, 700, 800),
+            ],
+            false
+        ),
+        vec![0., 500.]
+    );
+}
+
+#[test]
+fn test_snap_positions_are_sorted_and_unique() {
+    assert_eq!(
+        positions(
+            &[
+                (ScrollSnapAlignKeyword::Start, 300, 400),
+                (ScrollSnapAlignKeyword::Start, 100, 200),
+                (ScrollSnapAlignKeyword::End, 100, 200),
+                (ScrollSnapAlignKeyword::Center, 300, 400),
+            ],
+            false
+        ),
+        vec![100., 300.]
+    );
+}
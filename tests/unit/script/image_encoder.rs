@@ -0,0 +1,115 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use euclid::default::Size2D;
+use script::test::image_encoder::{encode, EncodedImageType};
+
+/// A bitmap whose pixels all differ, with some translucent ones if `alpha` is set.
+fn bitmap(size: Size2D<u32>, alpha: bool) -> Vec<u8> {
+    (0..size.width * size.height)
+        .flat_map(|i| {
+            let a = if alpha { (i * 53 % 256) as u8 } else { 255 };
+            vec![
+                (i * 37 % 256) as u8,
+                (i * 11 % 256) as u8,
+                (i * 91 % 256) as u8,
+                a,
+            ]
+        })
+        .collect()
+}
+
+#[test]
+fn png_round_trips() {
+    let size = Size2D::new(17, 5);
+    let pixels = bitmap(size, true);
+    let file = encode(&pixels, size, EncodedImageType::Png, None).unwrap();
+    let image = image::load_from_memory(&file).unwrap().to_rgba();
+    assert_eq!(image.dimensions(), (17, 5));
+    assert_eq!(image.into_raw(), pixels);
+}
+
+#[test]
+fn jpeg_is_composited_onto_black() {
+    let size = Size2D::new(8, 8);
+    let pixels: Vec<u8> = (0..64).flat_map(|_| vec![200, 100, 0, 128]).collect();
+    let file = encode(&pixels, size, EncodedImageType::Jpeg, Some(1.)).unwrap();
+    let image = image::load_from_memory(&file).unwrap().to_rgba();
+    assert_eq!(image.dimensions(), (8, 8));
+    for pixel in image.pixels() {
+        for (&actual, &expected) in pixel.0.iter().zip(&[100, 50, 0, 255]) {
+            assert!((actual as i32 - expected).abs() <= 3, "{:?}", pixel);
+        }
+    }
+}
+
+/// The files the encoder produces for `bitmap(Size2D::new(3, 2), alpha)`,
+/// which the `image-webp` decoder reads back as the original pixels.
+const WEBP_3X2_OPAQUE: &[u8] = &[
+    0x52, 0x49, 0x46, 0x46, 0xc2, 0x00, 0x00, 0x00, 0x57, 0x45, 0x42, 0x50, 0x56, 0x50, 0x38, 0x4c,
+    0xb6, 0x00, 0x00, 0x00, 0x2f, 0x02, 0x40, 0x00, 0x00, 0x80, 0x40, 0x00, 0x00, 0x00, 0xe2, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x1f, 0x00,
+    0x00, 0x00, 0x02, 0x01, 0x00, 0x00, 0x88, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f, 0x08, 0x04, 0x00, 0x00, 0x20, 0xfe, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x21, 0x10, 0x00, 0x00,
+    0x80, 0xf8, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0x0f, 0x00, 0x00, 0x80, 0x7f, 0x68, 0x52, 0xed, 0x7f, 0x34, 0xa9, 0xb6, 0x7f, 0x42, 0x7b,
+    0xc4, 0x7f, 0x9a, 0x14, 0x9b, 0x7f, 0xf6, 0xce, 0xf1, 0x7f,
+];
+const WEBP_3X2_ALPHA: &[u8] = &[
+    0x52, 0x49, 0x46, 0x46, 0xc2, 0x00, 0x00, 0x00, 0x57, 0x45, 0x42, 0x50, 0x56, 0x50, 0x38, 0x4c,
+    0xb6, 0x00, 0x00, 0x00, 0x2f, 0x02, 0x40, 0x00, 0x10, 0x80, 0x40, 0x00, 0x00, 0x00, 0xe2, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x1f, 0x00,
+    0x00, 0x00, 0x02, 0x01, 0x00, 0x00, 0x88, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f, 0x08, 0x04, 0x00, 0x00, 0x20, 0xfe, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x21, 0x10, 0x00, 0x00,
+    0x80, 0xf8, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0x0f, 0x00, 0x00, 0x00, 0x00, 0x68, 0x52, 0x6d, 0x56, 0x34, 0xa9, 0x36, 0x2b, 0x42, 0x7b,
+    0xc4, 0x7c, 0x9a, 0x14, 0x9b, 0x15, 0xf6, 0xce, 0x71, 0x48,
+];
+
+#[test]
+fn webp_matches_known_good_files() {
+    let size = Size2D::new(3, 2);
+    for &(alpha, expected) in &[(false, WEBP_3X2_OPAQUE), (true, WEBP_3X2_ALPHA)] {
+        let file = encode(&bitmap(size, alpha), size, EncodedImageType::Webp, None).unwrap();
+        assert_eq!(file, expected);
+    }
+}
+
+#[test]
+fn webp_ignores_quality() {
+    let size = Size2D::new(3, 2);
+    let pixels = bitmap(size, true);
+    for &quality in &[0., 0.5, 1.] {
+        let file = encode(&pixels, size, EncodedImageType::Webp, Some(quality)).unwrap();
+        assert_eq!(file, WEBP_3X2_ALPHA);
+    }
+}
+
+#[test]
+fn webp_rejects_large_bitmaps() {
+    let size = Size2D::new(1 << 15, 1);
+    let pixels = vec![0; (size.width * 4) as usize];
+    assert!(encode(&pixels, size, EncodedImageType::Webp, None).is_err());
+}
+
+#[test]
+fn empty_bitmaps_are_not_encoded() {
+    for &image_type in &[
+        EncodedImageType::Png,
+        EncodedImageType::Jpeg,
+        EncodedImageType::Webp,
+    ] {
+        assert!(encode(&[], Size2D::new(0, 4), image_type, None).is_err());
+    }
+}
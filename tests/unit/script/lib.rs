@@ -9,6 +9,8 @@ mod htmlareaelement;
 #[cfg(test)]
 mod htmlimageelement;
 #[cfg(test)]
+mod image_encoder;
+#[cfg(test)]
 mod origin;
 #[cfg(all(test, target_pointer_width = "64"))]
 mod size_of;
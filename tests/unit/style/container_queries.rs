@@ -0,0 +1,218 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use app_units::Au;
+use cssparser::{Parser, ParserInput};
+use euclid::{Scale, Size2D};
+use servo_arc::Arc;
+use servo_url::ServoUrl;
+use style::context::QuirksMode;
+use style::logical_geometry::WritingMode;
+use style::media_queries::{Device, MediaList, MediaType};
+use style::parser::ParserContext;
+use style::shared_lock::SharedRwLock;
+use style::stylesheets::container_rule::ContainerCondition;
+use style::stylesheets::{CssRule, CssRuleType, Origin, Stylesheet};
+use style_traits::{ParsingMode, ToCss};
+
+fn parse_condition(css: &str) -> Result<ContainerCondition, ()> {
+    let url = ServoUrl::parse("http://localhost").unwrap();
+    let context = ParserContext::new(
+        Origin::Author,
+        &url,
+        Some(CssRuleType::Container),
+        ParsingMode::DEFAULT,
+        QuirksMode::NoQuirks,
+        None,
+        None,
+    );
+    let mut input = ParserInput::new(css);
+    let mut parser = Parser::new(&mut input);
+    parser
+        .parse_entirely(|input| ContainerCondition::parse(&context, input))
+        .map_err(|_| ())
+}
+
+fn assert_condition_roundtrip(css: &str, expected: &str) {
+    let condition = parse_condition(css).expect(css);
+    assert_eq!(condition.to_css_string(), expected);
+    let reparsed = parse_condition(expected).expect(expected);
+    assert_eq!(reparsed, condition);
+}
+
+/// Whether `condition` matches a horizontal container of the given size, in
+/// CSS pixels.
+fn matches(condition: &str, width: i32, height: i32, has_block_size: bool) -> bool {
+    matches_with_writing_mode(
+        condition,
+        width,
+        height,
+        WritingMode::empty(),
+        has_block_size,
+    )
+}
+
+fn matches_with_writing_mode(
+    condition: &str,
+    width: i32,
+    height: i32,
+    writing_mode: WritingMode,
+    has_block_size: bool,
+) -> bool {
+    let device = Device::new(
+        MediaType::screen(),
+        Size2D::new(800., 600.),
+        Scale::new(1.0),
+    );
+    parse_condition(condition)
+        .expect(condition)
+        .matches_size_for_testing(
+            &device,
+            QuirksMode::NoQuirks,
+            Size2D::new(Au::from_px(width), Au::from_px(height)),
+            writing_mode,
+            has_block_size,
+        )
+}
+
+#[test]
+fn test_container_condition_parsing() {
+    assert_condition_roundtrip("(width > 400px)", "(width > 400px)");
+    assert_condition_roundtrip("(min-inline-size:20em)", "(min-inline-size: 20em)");
+    assert_condition_roundtrip("(aspect-ratio: 16/9)", "(aspect-ratio: 16 / 9)");
+    assert_condition_roundtrip(
+        "sidebar (orientation: portrait)",
+        "sidebar (orientation: portrait)",
+    );
+    assert_condition_roundtrip("not (height)", "not (height)");
+    assert_condition_roundtrip(
+        "(width >= 100px) and (block-size < 50px)",
+        "(width >= 100px) and (block-size < 50px)",
+    );
+    assert_condition_roundtrip("((width) or (height))", "((width) or (height))");
+}
+
+#[test]
+fn test_invalid_container_conditions() {
+    for css in &[
+        "",
+        "sidebar",
+        "(color)",
+        "(min-width)",
+        "(min-width > 10px)",
+        "(min-orientation: portrait)",
+        "(orientation > portrait)",
+        "(width: -1px)",
+        "(width) and (height) or (block-size)",
+    ] {
+        assert!(parse_condition(css).is_err(), "{:?} should not parse", css);
+    }
+}
+
+#[test]
+fn test_container_rule_parsing() {
+    let lock = SharedRwLock::new();
+    let sheet = Stylesheet::from_str(
+        "@container card (width > 10px) { .a { color: red } }
+         @container (width > 10px) and { .b { color: red } }",
+        ServoUrl::parse("http://localhost").unwrap(),
+        Origin::Author,
+        Arc::new(lock.wrap(MediaList::empty())),
+        lock.clone(),
+        None,
+        None,
+        QuirksMode::NoQuirks,
+        0,
+    );
+    let guard = lock.read();
+    let rules = sheet.contents.rules.read_with(&guard);
+    assert_eq!(rules.0.len(), 1);
+    match rules.0[0] {
+        CssRule::Container(ref rule) => {
+            let rule = rule.read_with(&guard);
+            assert_eq!(rule.condition.name().unwrap().0.to_string(), "card");
+            assert_eq!(rule.condition.to_css_string(), "card (width > 10px)");
+            assert_eq!(rule.rules.read_with(&guard).0.len(), 1);
+        },
+        ref rule => panic!("Unexpected rule {:?}", rule),
+    }
+}
+
+#[test]
+fn test_size_features() {
+    assert!(matches("(width > 400px)", 500, 300, true));
+    assert!(!matches("(width > 500px)", 500, 300, true));
+    assert!(matches("(min-width: 500px)", 500, 300, true));
+    assert!(!matches("(max-height: 299px)", 500, 300, true));
+    assert!(matches("(inline-size: 500px)", 500, 300, true));
+    assert!(matches("(block-size < 20em)", 500, 300, true));
+    assert!(matches("(width)", 500, 300, true));
+    assert!(!matches("(height)", 500, 0, true));
+    assert!(matches(
+        "(width > 400px) and (height < 400px)",
+        500,
+        300,
+        true
+    ));
+    assert!(!matches("not (width > 400px)", 500, 300, true));
+    assert!(matches(
+        "(width < 100px) or (height < 400px)",
+        500,
+        300,
+        true
+    ));
+}
+
+#[test]
+fn test_ratio_features() {
+    assert!(matches("(orientation: landscape)", 500, 300, true));
+    assert!(matches("(orientation: portrait)", 300, 300, true));
+    assert!(matches("(aspect-ratio: 5/3)", 500, 300, true));
+    assert!(matches("(min-aspect-ratio: 3/2)", 500, 300, true));
+    assert!(!matches("(aspect-ratio > 2/1)", 500, 300, true));
+}
+
+#[test]
+fn test_inline_size_containers() {
+    // Only the inline axis of an `inline-size` container can be queried.
+    assert!(matches("(width > 400px)", 500, 300, false));
+    assert!(matches("(inline-size > 400px)", 500, 300, false));
+    assert!(!matches("(height > 0px)", 500, 300, false));
+    assert!(!matches("(block-size > 0px)", 500, 300, false));
+    assert!(!matches("(orientation: landscape)", 500, 300, false));
+}
+
+#[test]
+fn test_vertical_containers() {
+    let vertical = WritingMode::VERTICAL;
+    assert!(matches_with_writing_mode(
+        "(inline-size: 300px)",
+        500,
+        300,
+        vertical,
+        true
+    ));
+    assert!(matches_with_writing_mode(
+        "(block-size: 500px)",
+        500,
+        300,
+        vertical,
+        true
+    ));
+    // The inline axis of a vertical container is its height.
+    assert!(matches_with_writing_mode(
+        "(height: 300px)",
+        500,
+        300,
+        vertical,
+        false
+    ));
+    assert!(!matches_with_writing_mode(
+        "(width: 500px)",
+        500,
+        300,
+        vertical,
+        false
+    ));
+}
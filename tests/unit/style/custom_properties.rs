@@ -1,33 +1,43 @@
-/* This Source Code Form is subject to the terms of the Mozilla Public
- * License, v. 2.0. If a copy of the MPL was not distributed with this
- * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
-
 use cssparser::{Parser, ParserInput};
 use servo_arc::Arc;
+use servo_url::ServoUrl;
+use style::context::QuirksMode;
 use style::custom_properties::{
     CssEnvironment, CustomPropertiesBuilder, CustomPropertiesMap, Name, SpecifiedValue,
 };
-use style::properties::{CustomDeclaration, CustomDeclarationValue};
-use style::stylesheets::Origin;
+use style::media_queries::MediaList;
+use style::properties::{CSSWideKeyword, CustomDeclaration, CustomDeclarationValue};
+use style::properties_and_values::{CustomPropertyRegistry, Syntax};
+use style::shared_lock::{SharedRwLock, ToCssWithGuard};
+use style::stylesheets::{CssRule, Origin, Stylesheet};
+use style_traits::ToCss;
 use test::{self, Bencher};
 
 fn cascade(
     name_and_value: &[(&str, &str)],
     inherited: Option<&Arc<CustomPropertiesMap>>,
+    registry: &CustomPropertyRegistry,
 ) -> Option<Arc<CustomPropertiesMap>> {
     let declarations = name_and_value
         .iter()
         .map(|&(name, value)| {
-            let mut input = ParserInput::new(value);
-            let mut parser = Parser::new(&mut input);
             let name = Name::from(name);
-            let value = CustomDeclarationValue::Value(SpecifiedValue::parse(&mut parser).unwrap());
+            let value = match value {
+                "initial" => CustomDeclarationValue::CSSWideKeyword(CSSWideKeyword::Initial),
+                "inherit" => CustomDeclarationValue::CSSWideKeyword(CSSWideKeyword::Inherit),
+                "unset" => CustomDeclarationValue::CSSWideKeyword(CSSWideKeyword::Unset),
+                _ => {
+                    let mut input = ParserInput::new(value);
+                    let mut parser = Parser::new(&mut input);
+                    CustomDeclarationValue::Value(SpecifiedValue::parse(&mut parser).unwrap())
+                },
+            };
             CustomDeclaration { name, value }
         })
         .collect::<Vec<_>>();
 
     let env = CssEnvironment;
-    let mut builder = CustomPropertiesBuilder::new(inherited, &env);
+    let mut builder = CustomPropertiesBuilder::new(inherited, registry, &env);
 
     for declaration in &declarations {
         builder.cascade(declaration, Origin::Author);
@@ -36,14 +46,180 @@ fn cascade(
     builder.build()
 }
 
+/// Parses `css`, and returns the serialization of its `@property` rules.
+fn property_rules(css: &str) -> Vec<String> {
+    let lock = SharedRwLock::new();
+    let sheet = Stylesheet::from_str(
+        css,
+        ServoUrl::parse("http://localhost").unwrap(),
+        Origin::Author,
+        Arc::new(lock.wrap(MediaList::empty())),
+        lock.clone(),
+        None,
+        None,
+        QuirksMode::NoQuirks,
+        0,
+    );
+    let guard = lock.read();
+    let rules = sheet.contents.rules.read_with(&guard);
+    rules
+        .0
+        .iter()
+        .filter_map(|rule| match *rule {
+            CssRule::Property(ref rule) => Some(rule.read_with(&guard).to_css_string(&guard)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns a registry with the `@property` rules of `css`.
+fn registry(css: &str) -> CustomPropertyRegistry {
+    let lock = SharedRwLock::new();
+    let sheet = Stylesheet::from_str(
+        css,
+        ServoUrl::parse("http://localhost").unwrap(),
+        Origin::Author,
+        Arc::new(lock.wrap(MediaList::empty())),
+        lock.clone(),
+        None,
+        None,
+        QuirksMode::NoQuirks,
+        0,
+    );
+    let guard = lock.read();
+    let mut registry = CustomPropertyRegistry::default();
+    for rule in sheet.contents.rules.read_with(&guard).0.iter() {
+        if let CssRule::Property(ref rule) = *rule {
+            registry.register(rule.read_with(&guard).registration.clone());
+        }
+    }
+    registry
+}
+
+fn value(map: &Option<Arc<CustomPropertiesMap>>, name: &str) -> Option<String> {
+    map.as_ref()?
+        .get(&Name::from(name))
+        .map(|value| value.to_css_string())
+}
+
 #[bench]
 fn cascade_custom_simple(b: &mut Bencher) {
     b.iter(|| {
-        let parent = cascade(&[("foo", "10px"), ("bar", "100px")], None);
+        let registry = CustomPropertyRegistry::default();
+        let parent = cascade(&[("foo", "10px"), ("bar", "100px")], None, &registry);
 
         test::black_box(cascade(
             &[("baz", "calc(40em + 4px)"), ("bazz", "calc(30em + 4px)")],
             parent.as_ref(),
+            &registry,
         ))
     })
 }
+
+#[test]
+fn test_syntax_parsing() {
+    let roundtrips = [
+        ("*", "*"),
+        ("<length>", "<length>"),
+        (" <length> | <percentage> ", "<length> | <percentage>"),
+        ("<color>+", "<color>+"),
+        ("foo | <length-percentage>#", "foo | <length-percentage>#"),
+    ];
+    for &(syntax, expected) in &roundtrips {
+        let parsed = Syntax::parse(syntax).expect(syntax);
+        assert_eq!(parsed.to_css_string(), expected);
+        assert_eq!(parsed.is_universal(), syntax == "*");
+    }
+
+    for syntax in &["", "<length> |", "<Length>", "<foo>", "<length>++", "1px"] {
+        assert!(
+            Syntax::parse(syntax).is_err(),
+            "{:?} should not parse",
+            syntax
+        );
+    }
+}
+
+#[test]
+fn test_property_rule_parsing() {
+    let rules = property_rules(
+        r#"@property --valid { syntax: "<length>"; inherits: false; initial-value: 10px; }
+           @property --universal { syntax: "*"; inherits: true }
+           @property --no-inherits { syntax: "*" }
+           @property --no-initial-value { syntax: "<color>"; inherits: true }
+           @property --relative-initial-value { syntax: "<length>"; inherits: false; initial-value: 1em; }
+           @property --mismatched-initial-value { syntax: "<length>"; inherits: false; initial-value: red; }
+           @property --invalid-syntax { syntax: "<foo>"; inherits: false; initial-value: 1px; }
+           @property --invalid-inherits { syntax: "*"; inherits: maybe }
+           @property not-dashed { syntax: "*"; inherits: false }"#,
+    );
+    assert_eq!(
+        rules,
+        [
+            r#"@property --valid { syntax: "<length>"; inherits: false; initial-value: 10px; }"#,
+            r#"@property --universal { syntax: "*"; inherits: true; }"#,
+        ]
+    );
+}
+
+#[test]
+fn test_registered_initial_values() {
+    let registry = registry(
+        r#"@property --inherited { syntax: "<length>"; inherits: true; initial-value: 1px; }
+           @property --reset { syntax: "<length>"; inherits: false; initial-value: 2px; }
+           @property --universal { syntax: "*"; inherits: false }"#,
+    );
+
+    // Registered properties have their initial value even if never
+    // specified, except for the universal syntax without an initial value.
+    let root = cascade(&[], None, &registry);
+    assert_eq!(value(&root, "inherited"), Some("1px".to_owned()));
+    assert_eq!(value(&root, "reset"), Some("2px".to_owned()));
+    assert_eq!(value(&root, "universal"), None);
+
+    // `initial`, and `unset` on a non-inherited property, go back to the
+    // initial value.
+    let root = cascade(&[("inherited", "10px"), ("reset", "20px")], None, &registry);
+    let child = cascade(
+        &[("inherited", "initial"), ("reset", "unset")],
+        root.as_ref(),
+        &registry,
+    );
+    assert_eq!(value(&child, "inherited"), Some("1px".to_owned()));
+    assert_eq!(value(&child, "reset"), Some("2px".to_owned()));
+}
+
+#[test]
+fn test_registered_inheritance() {
+    let registry = registry(
+        r#"@property --inherited { syntax: "<length>"; inherits: true; initial-value: 1px; }
+           @property --reset { syntax: "<length>"; inherits: false; initial-value: 2px; }
+           @property --universal { syntax: "*"; inherits: false }"#,
+    );
+    let parent = cascade(
+        &[
+            ("inherited", "10px"),
+            ("reset", "20px"),
+            ("universal", "foo"),
+            ("unregistered", "bar"),
+        ],
+        None,
+        &registry,
+    );
+
+    // Only properties registered with `inherits: false` aren't inherited.
+    let child = cascade(&[], parent.as_ref(), &registry);
+    assert_eq!(value(&child, "inherited"), Some("10px".to_owned()));
+    assert_eq!(value(&child, "reset"), Some("2px".to_owned()));
+    assert_eq!(value(&child, "universal"), None);
+    assert_eq!(value(&child, "unregistered"), Some("bar".to_owned()));
+
+    // Unless they're explicitly inherited.
+    let child = cascade(
+        &[("reset", "inherit"), ("universal", "inherit")],
+        parent.as_ref(),
+        &registry,
+    );
+    assert_eq!(value(&child, "reset"), Some("20px".to_owned()));
+    assert_eq!(value(&child, "universal"), Some("foo".to_owned()));
+}
@@ -26,8 +26,11 @@ extern crate test;
 
 mod animated_properties;
 mod attr;
+mod container_queries;
 mod custom_properties;
 mod logical_geometry;
+mod media_queries;
+mod nesting;
 mod parsing;
 mod properties;
 mod rule_tree;
@@ -0,0 +1,212 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use cssparser::{Parser, ParserInput};
+use euclid::{Scale, Size2D};
+use servo_url::ServoUrl;
+use style::context::QuirksMode;
+use style::media_queries::{Device, MediaList, MediaType};
+use style::parser::ParserContext;
+use style::stylesheets::{CssRuleType, Origin};
+use style_traits::{ColorScheme, MediaPreferences, ParsingMode, ToCss};
+
+fn parse_media_list(css: &str) -> MediaList {
+    let url = ServoUrl::parse("http://localhost").unwrap();
+    let context = ParserContext::new(
+        Origin::Author,
+        &url,
+        Some(CssRuleType::Media),
+        ParsingMode::DEFAULT,
+        QuirksMode::NoQuirks,
+        None,
+        None,
+    );
+    let mut input = ParserInput::new(css);
+    MediaList::parse(&context, &mut Parser::new(&mut input))
+}
+
+fn assert_roundtrip(css: &str, expected: &str) {
+    assert_eq!(parse_media_list(css).to_css_string(), expected, "{}", css);
+}
+
+fn device(width: f32, height: f32, device_pixel_ratio: f32) -> Device {
+    Device::new(
+        MediaType::screen(),
+        Size2D::new(width, height),
+        Scale::new(device_pixel_ratio),
+    )
+}
+
+fn matches(css: &str, device: &Device) -> bool {
+    parse_media_list(css).evaluate(device, QuirksMode::NoQuirks)
+}
+
+#[test]
+fn test_media_feature_serialization() {
+    assert_roundtrip("(height: 100px)", "(height: 100px)");
+    assert_roundtrip("(MIN-HEIGHT:100px)", "(min-height: 100px)");
+    assert_roundtrip("(aspect-ratio: 16/9)", "(aspect-ratio: 16 / 9)");
+    assert_roundtrip("(max-aspect-ratio: 4 / 3)", "(max-aspect-ratio: 4 / 3)");
+    assert_roundtrip("(orientation: PORTRAIT)", "(orientation: portrait)");
+    assert_roundtrip("(orientation)", "(orientation)");
+    assert_roundtrip("(min-resolution: 2dppx)", "(min-resolution: 2dppx)");
+    assert_roundtrip("(min-resolution: 192dpi)", "(min-resolution: 2dppx)");
+    assert_roundtrip("(hover: hover)", "(hover: hover)");
+    assert_roundtrip("(any-hover: none)", "(any-hover: none)");
+    assert_roundtrip("(pointer: fine)", "(pointer: fine)");
+    assert_roundtrip("(any-pointer: coarse)", "(any-pointer: coarse)");
+    assert_roundtrip(
+        "(prefers-color-scheme: dark)",
+        "(prefers-color-scheme: dark)",
+    );
+    assert_roundtrip(
+        "(prefers-reduced-motion: reduce)",
+        "(prefers-reduced-motion: reduce)",
+    );
+    assert_roundtrip(
+        "screen and (orientation: landscape), print",
+        "screen and (orientation: landscape), print",
+    );
+}
+
+#[test]
+fn test_invalid_media_features() {
+    for css in &[
+        "(height: 10)",
+        "(height: -10px)",
+        "(aspect-ratio: 16 / 0 / 9)",
+        "(orientation: sideways)",
+        "(min-orientation: portrait)",
+        "(resolution: 2px)",
+        "(hover: fine)",
+        "(min-hover: hover)",
+        "(pointer: hover)",
+        "(prefers-color-scheme: blue)",
+        "(prefers-reduced-motion: 1)",
+    ] {
+        assert_roundtrip(css, "not all");
+    }
+}
+
+#[test]
+fn test_height() {
+    let device = device(800., 600., 1.);
+    assert!(matches("(height: 600px)", &device));
+    assert!(matches("(min-height: 600px)", &device));
+    assert!(matches("(max-height: 600px)", &device));
+    assert!(!matches("(min-height: 601px)", &device));
+    assert!(!matches("(max-height: 599px)", &device));
+    assert!(matches("(height)", &device));
+    assert!(!matches("(height: 0px)", &device));
+}
+
+#[test]
+fn test_aspect_ratio() {
+    let device = device(800., 600., 1.);
+    assert!(matches("(aspect-ratio: 4/3)", &device));
+    assert!(matches("(aspect-ratio: 8/6)", &device));
+    assert!(!matches("(aspect-ratio: 16/9)", &device));
+    assert!(matches("(min-aspect-ratio: 1/1)", &device));
+    assert!(!matches("(min-aspect-ratio: 16/9)", &device));
+    assert!(matches("(max-aspect-ratio: 16/9)", &device));
+    assert!(matches("(aspect-ratio)", &device));
+}
+
+#[test]
+fn test_orientation() {
+    assert!(matches("(orientation: landscape)", &device(800., 600., 1.)));
+    assert!(!matches("(orientation: portrait)", &device(800., 600., 1.)));
+    assert!(matches("(orientation: portrait)", &device(600., 800., 1.)));
+    assert!(!matches(
+        "(orientation: landscape)",
+        &device(600., 800., 1.)
+    ));
+    // Square viewports are portrait.
+    assert!(matches("(orientation: portrait)", &device(600., 600., 1.)));
+    assert!(matches("(orientation)", &device(600., 600., 1.)));
+}
+
+#[test]
+fn test_resolution() {
+    let device_1x = device(800., 600., 1.);
+    let device_2x = device(800., 600., 2.);
+    assert!(matches("(resolution: 1dppx)", &device_1x));
+    assert!(matches("(resolution: 96dpi)", &device_1x));
+    assert!(!matches("(min-resolution: 2dppx)", &device_1x));
+    assert!(matches("(min-resolution: 2dppx)", &device_2x));
+    assert!(matches("(min-resolution: 192dpi)", &device_2x));
+    assert!(matches("(max-resolution: 2dppx)", &device_2x));
+    assert!(!matches("(max-resolution: 1.5dppx)", &device_2x));
+    assert!(matches("(resolution)", &device_1x));
+}
+
+#[test]
+fn test_hover_and_pointer() {
+    let device = device(800., 600., 1.);
+    assert!(matches("(hover)", &device));
+    assert!(matches("(hover: hover)", &device));
+    assert!(!matches("(hover: none)", &device));
+    assert!(matches("(any-hover: hover)", &device));
+    assert!(!matches("(any-hover: none)", &device));
+    assert!(matches("(pointer)", &device));
+    assert!(matches("(pointer: fine)", &device));
+    assert!(!matches("(pointer: coarse)", &device));
+    assert!(!matches("(pointer: none)", &device));
+    assert!(matches("(any-pointer: fine)", &device));
+    assert!(!matches("(any-pointer: coarse)", &device));
+}
+
+#[test]
+fn test_prefers_color_scheme() {
+    let mut device = device(800., 600., 1.);
+    assert!(!matches("(prefers-color-scheme)", &device));
+    assert!(matches("(prefers-color-scheme: no-preference)", &device));
+    assert!(!matches("(prefers-color-scheme: light)", &device));
+    assert!(!matches("(prefers-color-scheme: dark)", &device));
+
+    device.set_media_preferences(MediaPreferences {
+        color_scheme: Some(ColorScheme::Dark),
+        ..MediaPreferences::default()
+    });
+    assert!(matches("(prefers-color-scheme)", &device));
+    assert!(!matches("(prefers-color-scheme: no-preference)", &device));
+    assert!(!matches("(prefers-color-scheme: light)", &device));
+    assert!(matches("(prefers-color-scheme: dark)", &device));
+
+    device.set_media_preferences(MediaPreferences {
+        color_scheme: Some(ColorScheme::Light),
+        ..MediaPreferences::default()
+    });
+    assert!(matches("(prefers-color-scheme: light)", &device));
+    assert!(!matches("(prefers-color-scheme: dark)", &device));
+}
+
+#[test]
+fn test_prefers_reduced_motion() {
+    let mut device = device(800., 600., 1.);
+    assert!(!matches("(prefers-reduced-motion)", &device));
+    assert!(matches("(prefers-reduced-motion: no-preference)", &device));
+    assert!(!matches("(prefers-reduced-motion: reduce)", &device));
+
+    device.set_media_preferences(MediaPreferences {
+        reduced_motion: true,
+        ..MediaPreferences::default()
+    });
+    assert!(matches("(prefers-reduced-motion)", &device));
+    assert!(!matches("(prefers-reduced-motion: no-preference)", &device));
+    assert!(matches("(prefers-reduced-motion: reduce)", &device));
+}
+
+#[test]
+fn test_media_query_combinations() {
+    let device = device(800., 600., 2.);
+    assert!(matches(
+        "screen and (orientation: landscape) and (min-resolution: 2dppx)",
+        &device
+    ));
+    assert!(!matches("print and (orientation: landscape)", &device));
+    assert!(matches("not screen and (orientation: portrait)", &device));
+    assert!(matches("(orientation: portrait), (hover: hover)", &device));
+    assert!(!matches("(max-height: 500px), (pointer: coarse)", &device));
+}
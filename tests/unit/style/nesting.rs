@@ -0,0 +1,242 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use cssparser::ToCss;
+use servo_arc::Arc;
+use servo_url::ServoUrl;
+use style::context::QuirksMode;
+use style::media_queries::MediaList;
+use style::shared_lock::{SharedRwLock, SharedRwLockReadGuard, ToCssWithGuard};
+use style::stylesheets::{CssRule, CssRuleType, Origin, StyleRule, Stylesheet};
+
+fn parse(css: &str, lock: &SharedRwLock) -> Stylesheet {
+    Stylesheet::from_str(
+        css,
+        ServoUrl::parse("http://localhost").unwrap(),
+        Origin::Author,
+        Arc::new(lock.wrap(MediaList::empty())),
+        lock.clone(),
+        None,
+        None,
+        QuirksMode::NoQuirks,
+        0,
+    )
+}
+
+/// Parses `css` and returns the serialization of each of its rules.
+fn serialize_rules(css: &str) -> Vec<String> {
+    let lock = SharedRwLock::new();
+    let sheet = parse(css, &lock);
+    let guard = lock.read();
+    let rules = sheet.contents.rules.read_with(&guard);
+    rules
+        .0
+        .iter()
+        .map(|rule| rule.to_css_string(&guard))
+        .collect()
+}
+
+fn nested_rules(rule: &CssRule, guard: &SharedRwLockReadGuard) -> Vec<CssRule> {
+    let rules = match *rule {
+        CssRule::Style(ref lock) => match lock.read_with(guard).rules {
+            Some(ref rules) => rules.clone(),
+            None => return vec![],
+        },
+        CssRule::Media(ref lock) => lock.read_with(guard).rules.clone(),
+        CssRule::Supports(ref lock) => lock.read_with(guard).rules.clone(),
+        CssRule::LayerBlock(ref lock) => lock.read_with(guard).rules.clone(),
+        _ => panic!("{:?} has no nested rules", rule),
+    };
+    let rules = rules.read_with(guard);
+    rules.0.clone()
+}
+
+fn style_rule<'a>(rule: &'a CssRule, guard: &'a SharedRwLockReadGuard) -> &'a StyleRule {
+    match *rule {
+        CssRule::Style(ref lock) => lock.read_with(guard),
+        _ => panic!("{:?} is not a style rule", rule),
+    }
+}
+
+/// Returns the selectors of the style rule nested in `parent` and the
+/// specificity of each of them, after replacing the nesting selector with the
+/// selectors of `parent`.
+fn resolved_selectors(css: &str) -> Vec<(String, u32)> {
+    let lock = SharedRwLock::new();
+    let sheet = parse(css, &lock);
+    let guard = lock.read();
+    let rules = sheet.contents.rules.read_with(&guard);
+    let parent = style_rule(&rules.0[0], &guard);
+    let nested = nested_rules(&rules.0[0], &guard);
+    let nested = style_rule(&nested[0], &guard);
+    nested
+        .selectors
+        .0
+        .iter()
+        .flat_map(|selector| selector.replace_parent_selector(&parent.selectors.0))
+        .map(|selector| (selector.to_css_string(), selector.specificity()))
+        .collect()
+}
+
+const ID: u32 = 1 << 20;
+const CLASS: u32 = 1 << 10;
+const ELEMENT: u32 = 1;
+
+#[test]
+fn test_nested_selectors() {
+    assert_eq!(
+        serialize_rules(".a { color: red; .b { color: green; } }"),
+        [".a { color: red; & .b { color: green; } }"]
+    );
+    assert_eq!(
+        serialize_rules(".a { > .b {} + .c {} ~ .d {} }"),
+        [".a { & > .b { } & + .c { } & ~ .d { } }"]
+    );
+    assert_eq!(
+        serialize_rules(".a { &:hover {} .b & {} & > & {} &.c, .d {} }"),
+        [".a { &:hover { } .b & { } & > & { } &.c, & .d { } }"]
+    );
+    assert_eq!(
+        serialize_rules(".a { .b { .c {} } }"),
+        [".a { & .b { & .c { } } }"]
+    );
+}
+
+#[test]
+fn test_nesting_selector_outside_of_style_rules() {
+    // A top-level nesting selector is allowed, and matches like `:scope`.
+    assert_eq!(serialize_rules("& .a {}"), ["& .a { }"]);
+    // But relative selectors aren't.
+    assert!(serialize_rules("> .a {}").is_empty());
+    // And neither are nesting selectors after pseudo-elements or in :not().
+    assert_eq!(
+        serialize_rules(".a { ::before& {} :not(&) {} }"),
+        [".a { }"]
+    );
+}
+
+#[test]
+fn test_resolved_selectors() {
+    assert_eq!(
+        resolved_selectors(".a { .b {} }"),
+        [(".a .b".to_owned(), 2 * CLASS)]
+    );
+    assert_eq!(
+        resolved_selectors(".a > .b { &:hover {} }"),
+        [(".a > .b:hover".to_owned(), 3 * CLASS)]
+    );
+    assert_eq!(
+        resolved_selectors("div { > p, .c & {} }"),
+        [
+            ("div > p".to_owned(), 2 * ELEMENT),
+            (".c div".to_owned(), CLASS + ELEMENT),
+        ]
+    );
+}
+
+#[test]
+fn test_resolved_selector_specificity() {
+    // Each nesting selector counts as `:is()` of the parent selectors, that
+    // is, with the specificity of the most specific of them.
+    assert_eq!(
+        resolved_selectors(".a, #b { .c {} }"),
+        [
+            (".a .c".to_owned(), ID + CLASS),
+            ("#b .c".to_owned(), ID + CLASS),
+        ]
+    );
+    assert_eq!(
+        resolved_selectors(".a, #b { & + & {} }"),
+        [
+            (".a + .a".to_owned(), 2 * ID),
+            ("#b + .a".to_owned(), 2 * ID),
+            (".a + #b".to_owned(), 2 * ID),
+            ("#b + #b".to_owned(), 2 * ID),
+        ]
+    );
+    // Parents with pseudo-elements can't be represented by `&`.
+    assert_eq!(
+        resolved_selectors(".a, .b::before { .c {} }"),
+        [(".a .c".to_owned(), 2 * CLASS)]
+    );
+}
+
+#[test]
+fn test_declarations_in_nested_conditional_rules() {
+    let lock = SharedRwLock::new();
+    let sheet = parse(
+        ".a {
+            color: red;
+            @media screen {
+                color: green;
+                .b { color: blue; }
+            }
+            @supports (display: grid) {
+                display: grid;
+            }
+            @layer base {
+                color: black;
+            }
+        }",
+        &lock,
+    );
+    let guard = lock.read();
+    let rules = sheet.contents.rules.read_with(&guard);
+    assert_eq!(rules.0.len(), 1);
+
+    let nested = nested_rules(&rules.0[0], &guard);
+    assert_eq!(nested.len(), 3);
+    let serialized = |rules: Vec<CssRule>| {
+        rules
+            .iter()
+            .map(|rule| rule.to_css_string(&guard))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(nested[0].rule_type(), CssRuleType::Media);
+    assert_eq!(
+        serialized(nested_rules(&nested[0], &guard)),
+        ["& { color: green; }", "& .b { color: blue; }"]
+    );
+    assert_eq!(nested[1].rule_type(), CssRuleType::Supports);
+    assert_eq!(
+        serialized(nested_rules(&nested[1], &guard)),
+        ["& { display: grid; }"]
+    );
+    assert_eq!(nested[2].rule_type(), CssRuleType::LayerBlock);
+    assert_eq!(
+        serialized(nested_rules(&nested[2], &guard)),
+        ["& { color: black; }"]
+    );
+
+    // The declarations of the conditional rules don't end up in the style
+    // rule itself.
+    let declarations = style_rule(&rules.0[0], &guard).block.read_with(&guard);
+    assert_eq!(declarations.len(), 1);
+}
+
+#[test]
+fn test_conditional_rules_without_declarations() {
+    assert_eq!(
+        serialize_rules(".a { @media screen { .b {} } }"),
+        [".a { @media screen {\n  & .b { }\n} }"]
+    );
+}
+
+#[test]
+fn test_unsupported_nested_at_rules() {
+    assert_eq!(
+        serialize_rules(
+            ".a {
+                @font-face { font-family: foo; }
+                @keyframes foo { from { color: red; } }
+                @page { margin: 0; }
+                @import url(foo.css);
+                @namespace foo url(bar);
+                @layer foo, bar;
+                color: green;
+            }"
+        ),
+        [".a { color: green; }"]
+    );
+}
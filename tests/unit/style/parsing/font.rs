@@ -0,0 +1,53 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use servo_url::ServoUrl;
+use style::context::QuirksMode;
+use style::properties::{parse_style_attribute, ShorthandId};
+
+/// Parses `declarations` and serializes the `font` shorthand of them.
+fn serialize_font(declarations: &str) -> String {
+    let url = ServoUrl::parse("http://localhost").unwrap();
+    let block = parse_style_attribute(declarations, &url, None, QuirksMode::NoQuirks);
+    let mut css = String::new();
+    block.shorthand_to_css(ShorthandId::Font, &mut css).unwrap();
+    css
+}
+
+#[test]
+fn test_font_serialization() {
+    assert_eq!(serialize_font("font: 12px serif"), "12px serif");
+    assert_eq!(
+        serialize_font("font: italic small-caps bold condensed 12px/2 serif"),
+        "italic small-caps bold condensed 12px / 2 serif"
+    );
+    assert_eq!(
+        serialize_font("font: 12px serif; font-stretch: 50%"),
+        "ultra-condensed 12px serif"
+    );
+}
+
+#[test]
+fn test_font_serialization_with_variations() {
+    // Variations aren't part of the shorthand, so they don't keep it from serializing.
+    assert_eq!(
+        serialize_font("font: 12px serif; font-variation-settings: \"wght\" 400"),
+        "12px serif"
+    );
+}
+
+#[test]
+fn test_font_serialization_with_reset_longhands() {
+    // The shorthand can only represent the initial values of the longhands it resets.
+    assert_eq!(serialize_font("font: 12px serif; font-kerning: none"), "");
+    assert_eq!(
+        serialize_font("font: 12px serif; font-variant-ligatures: none"),
+        ""
+    );
+    assert_eq!(
+        serialize_font("font: 12px serif; font-feature-settings: \"liga\" 0"),
+        ""
+    );
+    assert_eq!(serialize_font("font: 12px serif; font-stretch: 60%"), "");
+}
@@ -117,11 +117,13 @@ mod border;
 mod box_;
 mod column;
 mod effects;
+mod font;
 mod image;
 mod inherited_text;
 mod outline;
 mod selectors;
 mod supports;
+mod svg;
 mod text_overflow;
 mod transition_duration;
 mod transition_timing_function;
@@ -0,0 +1,171 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use crate::parsing::parse;
+use cssparser::Parser;
+use euclid::{Scale, Size2D};
+use style::context::QuirksMode;
+use style::media_queries::{Device, MediaType};
+use style::parser::ParserContext;
+use style::properties::longhands::{clip_rule, fill, fill_opacity, fill_rule, marker_end};
+use style::properties::longhands::{cx, cy, r, rx, ry, stroke_width, x, y};
+use style::properties::longhands::{marker_mid, marker_start, paint_order, stroke};
+use style::properties::longhands::{stroke_dasharray, stroke_dashoffset, stroke_linecap};
+use style::properties::longhands::{stroke_linejoin, stroke_miterlimit, stroke_opacity};
+use style::values::computed::{Context, ToComputedValue};
+use style_traits::{ParseError, ToCss};
+
+/// Parses `specified` with `f`, and checks the serialization of its computed
+/// value.
+fn assert_computed<T, F>(f: F, specified: &'static str, expected: &str)
+where
+    F: for<'t> Fn(&ParserContext, &mut Parser<'static, 't>) -> Result<T, ParseError<'static>>,
+    T: ToComputedValue,
+    T::ComputedValue: ToCss,
+{
+    let value = parse(f, specified).expect(specified);
+    let device = Device::new(
+        MediaType::screen(),
+        Size2D::new(800., 600.),
+        Scale::new(1.0),
+    );
+    let computed = Context::for_media_query_evaluation(&device, QuirksMode::NoQuirks, |context| {
+        value.to_computed_value(context)
+    });
+    assert_eq!(computed.to_css_string(), expected, "{}", specified);
+}
+
+#[test]
+fn test_svg_paint() {
+    for parse_paint in &[fill::parse, stroke::parse] {
+        assert_roundtrip_with_context!(parse_paint, "none");
+        assert_roundtrip_with_context!(parse_paint, "red");
+        assert_roundtrip_with_context!(parse_paint, "currentcolor");
+        assert_roundtrip_with_context!(parse_paint, "url(#paint)", "url(\"#paint\")");
+        assert_roundtrip_with_context!(parse_paint, "url(#paint) blue", "url(\"#paint\") blue");
+        assert_roundtrip_with_context!(parse_paint, "url(#paint) none", "url(\"#paint\") none");
+
+        // Servo has no context element to take these from.
+        assert!(parse(parse_paint, "context-fill").is_err());
+        assert!(parse(parse_paint, "context-stroke").is_err());
+        assert!(parse(parse_paint, "auto").is_err());
+    }
+}
+
+#[test]
+fn test_svg_paint_computed_value() {
+    assert_computed(fill::parse, "none", "none");
+    assert_computed(fill::parse, "red", "rgb(255, 0, 0)");
+    assert_computed(fill::parse, "currentcolor", "currentcolor");
+    assert_computed(
+        stroke::parse,
+        "url(#paint) blue",
+        "url(\"http://localhost/#paint\") rgb(0, 0, 255)",
+    );
+}
+
+#[test]
+fn test_svg_opacity() {
+    for parse_opacity in &[fill_opacity::parse, stroke_opacity::parse] {
+        assert_roundtrip_with_context!(parse_opacity, "0.5");
+        assert_roundtrip_with_context!(parse_opacity, "2");
+        assert!(parse(parse_opacity, "context-fill-opacity").is_err());
+        assert!(parse(parse_opacity, "context-stroke-opacity").is_err());
+    }
+
+    assert_computed(fill_opacity::parse, "0.5", "0.5");
+    assert_computed(fill_opacity::parse, "2", "1");
+    assert_computed(stroke_opacity::parse, "-1", "0");
+}
+
+#[test]
+fn test_stroke() {
+    assert_roundtrip_with_context!(stroke_width::parse, "2px");
+    assert_roundtrip_with_context!(stroke_width::parse, "10%");
+    assert_roundtrip_with_context!(stroke_width::parse, "2", "2px");
+    assert!(parse(stroke_width::parse, "-1px").is_err());
+    assert!(parse(stroke_width::parse, "context-value").is_err());
+
+    assert_roundtrip_with_context!(stroke_dasharray::parse, "none");
+    assert_roundtrip_with_context!(stroke_dasharray::parse, "1px, 2%");
+    assert_roundtrip_with_context!(stroke_dasharray::parse, "1 2px", "1px, 2px");
+    assert!(parse(stroke_dasharray::parse, "1px, -2px").is_err());
+
+    assert_roundtrip_with_context!(stroke_dashoffset::parse, "-5px");
+    assert_roundtrip_with_context!(stroke_miterlimit::parse, "10");
+    assert!(parse(stroke_miterlimit::parse, "-1").is_err());
+    assert_roundtrip_with_context!(stroke_linecap::parse, "round");
+    assert!(parse(stroke_linecap::parse, "bevel").is_err());
+    assert_roundtrip_with_context!(stroke_linejoin::parse, "bevel");
+    assert!(parse(stroke_linejoin::parse, "square").is_err());
+
+    assert_computed(stroke_width::parse, "2", "2px");
+    assert_computed(stroke_width::parse, "1em", "16px");
+    assert_computed(stroke_dasharray::parse, "none", "none");
+    assert_computed(stroke_dasharray::parse, "1 2em 5%", "1px, 32px, 5%");
+    assert_computed(stroke_dashoffset::parse, "0.5em", "8px");
+}
+
+#[test]
+fn test_fill_and_clip_rule() {
+    for parse_rule in &[fill_rule::parse, clip_rule::parse] {
+        assert_roundtrip_with_context!(parse_rule, "nonzero");
+        assert_roundtrip_with_context!(parse_rule, "evenodd");
+        assert!(parse(parse_rule, "auto").is_err());
+    }
+}
+
+#[test]
+fn test_paint_order() {
+    assert_roundtrip_with_context!(paint_order::parse, "normal");
+    assert_roundtrip_with_context!(paint_order::parse, "stroke");
+    assert_roundtrip_with_context!(paint_order::parse, "markers stroke");
+    assert_roundtrip_with_context!(paint_order::parse, "stroke fill markers", "stroke");
+    assert_roundtrip_with_context!(paint_order::parse, "fill stroke markers", "fill");
+    assert!(parse(paint_order::parse, "fill fill").is_err());
+}
+
+#[test]
+fn test_markers() {
+    for parse_marker in &[marker_start::parse, marker_mid::parse, marker_end::parse] {
+        assert_roundtrip_with_context!(parse_marker, "none");
+        assert_roundtrip_with_context!(parse_marker, "url(#marker)", "url(\"#marker\")");
+        assert!(parse(parse_marker, "auto").is_err());
+    }
+
+    assert_computed(marker_start::parse, "none", "none");
+    assert_computed(
+        marker_end::parse,
+        "url(#marker)",
+        "url(\"http://localhost/#marker\")",
+    );
+}
+
+#[test]
+fn test_geometry() {
+    for parse_position in &[x::parse, y::parse, cx::parse, cy::parse] {
+        assert_roundtrip_with_context!(parse_position, "10px");
+        assert_roundtrip_with_context!(parse_position, "-5%");
+        assert_roundtrip_with_context!(parse_position, "calc(10px + 5%)");
+        assert!(parse(parse_position, "auto").is_err());
+    }
+
+    for parse_radius in &[rx::parse, ry::parse] {
+        assert_roundtrip_with_context!(parse_radius, "auto");
+        assert_roundtrip_with_context!(parse_radius, "10px");
+        assert_roundtrip_with_context!(parse_radius, "50%");
+        assert!(parse(parse_radius, "-1px").is_err());
+    }
+
+    assert_roundtrip_with_context!(r::parse, "10px");
+    assert_roundtrip_with_context!(r::parse, "50%");
+    assert!(parse(r::parse, "-1px").is_err());
+    assert!(parse(r::parse, "auto").is_err());
+
+    assert_computed(x::parse, "2em", "32px");
+    assert_computed(cy::parse, "5%", "5%");
+    assert_computed(rx::parse, "auto", "auto");
+    assert_computed(ry::parse, "1em", "16px");
+    assert_computed(r::parse, "calc(1em + 10%)", "calc(16px + 10%)");
+}
@@ -2,23 +2,29 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
-use cssparser::SourceLocation;
+use cssparser::{SourceLocation, ToCss};
 use euclid::Scale;
 use euclid::Size2D;
 use selectors::parser::{AncestorHashes, Selector};
 use servo_arc::Arc;
 use servo_atoms::Atom;
+use servo_url::ServoUrl;
 use style::context::QuirksMode;
-use style::media_queries::{Device, MediaType};
+use style::media_queries::{Device, MediaList, MediaType};
+use style::parser::ParserContext;
 use style::properties::{longhands, Importance};
 use style::properties::{PropertyDeclaration, PropertyDeclarationBlock};
+use style::rule_tree::{CascadeLevel, RuleTree};
 use style::selector_map::SelectorMap;
 use style::selector_parser::{SelectorImpl, SelectorParser};
-use style::shared_lock::SharedRwLock;
-use style::stylesheets::StyleRule;
-use style::stylist::needs_revalidation_for_testing;
-use style::stylist::{Rule, Stylist};
+use style::shared_lock::{Locked, SharedRwLock, StylesheetGuards};
+use style::stylesheets::import_rule::{ImportLayer, ImportSheet};
+use style::stylesheets::layer_rule::LayerOrder;
+use style::stylesheets::{ImportRule, Origin, StyleRule, Stylesheet, StylesheetLoader};
+use style::stylist::{cascade_data_for_testing, needs_revalidation_for_testing};
+use style::stylist::{CascadeData, ContainerConditionId, LayerId, Rule, Stylist};
 use style::thread_state::{self, ThreadState};
+use style::values::CssUrl;
 
 /// Helper method to get some Rules from selector strings.
 /// Each sublist of the result contains the Rules for one StyleRule.
@@ -38,6 +44,7 @@ fn get_mock_rules(css_selectors: &[&str]) -> (Vec<Vec<Rule>>, SharedRwLock) {
                         PropertyDeclaration::Display(longhands::display::SpecifiedValue::Block),
                         Importance::Normal,
                     ))),
+                    rules: None,
                     source_location: SourceLocation { line: 0, column: 0 },
                 }));
 
@@ -52,6 +59,8 @@ fn get_mock_rules(css_selectors: &[&str]) -> (Vec<Vec<Rule>>, SharedRwLock) {
                             AncestorHashes::new(s, QuirksMode::NoQuirks),
                             locked.clone(),
                             i as u32,
+                            LayerId::root(),
+                            ContainerConditionId::none(),
                         )
                     })
                     .collect()
@@ -211,13 +220,16 @@ fn test_insert() {
         .is_none());
 }
 
-fn mock_stylist() -> Stylist {
-    let device = Device::new(
+fn mock_device() -> Device {
+    Device::new(
         MediaType::screen(),
         Size2D::new(0f32, 0f32),
         Scale::new(1.0),
-    );
-    Stylist::new(device, QuirksMode::NoQuirks)
+    )
+}
+
+fn mock_stylist() -> Stylist {
+    Stylist::new(mock_device(), QuirksMode::NoQuirks)
 }
 
 #[test]
@@ -236,3 +248,172 @@ fn test_stylist_rule_tree_accessors() {
     stylist.rule_tree();
     stylist.rule_tree().root();
 }
+
+/// A loader that imports the same stylesheet for every `@import` rule.
+struct MockStylesheetLoader(&'static str);
+
+impl StylesheetLoader for MockStylesheetLoader {
+    fn request_stylesheet(
+        &self,
+        url: CssUrl,
+        location: SourceLocation,
+        context: &ParserContext,
+        lock: &SharedRwLock,
+        media: Arc<Locked<MediaList>>,
+        layer: Option<ImportLayer>,
+    ) -> Arc<Locked<ImportRule>> {
+        let sheet = Stylesheet::from_str(
+            self.0,
+            context.url_data.clone(),
+            context.stylesheet_origin,
+            media,
+            lock.clone(),
+            None,
+            None,
+            context.quirks_mode,
+            0,
+        );
+        Arc::new(lock.wrap(ImportRule {
+            url,
+            stylesheet: ImportSheet(Arc::new(sheet)),
+            layer,
+            source_location: location,
+        }))
+    }
+}
+
+fn author_stylesheet(
+    css: &str,
+    lock: &SharedRwLock,
+    loader: Option<&dyn StylesheetLoader>,
+) -> Stylesheet {
+    Stylesheet::from_str(
+        css,
+        ServoUrl::parse("http://localhost").unwrap(),
+        Origin::Author,
+        Arc::new(lock.wrap(MediaList::empty())),
+        lock.clone(),
+        loader,
+        None,
+        QuirksMode::NoQuirks,
+        0,
+    )
+}
+
+/// Returns the first rule whose rightmost selector has the given class.
+fn rule_with_class<'a>(data: &'a CascadeData, class: &str) -> &'a Rule {
+    &data
+        .normal_rules(None)
+        .unwrap()
+        .class_hash
+        .get(&Atom::from(class), QuirksMode::NoQuirks)
+        .unwrap()[0]
+}
+
+fn layer_order_of(data: &CascadeData, class: &str) -> LayerOrder {
+    data.layer_order_for(rule_with_class(data, class).layer_id)
+}
+
+#[test]
+fn test_layer_ordering() {
+    thread_state::initialize(ThreadState::LAYOUT);
+    let lock = SharedRwLock::new();
+    let sheet = author_stylesheet(
+        "@layer b, a;
+         .unlayered { color: red }
+         @layer a { .a { color: red } }
+         @layer b {
+             .b { color: red }
+             @layer nested { .b-nested { color: red } }
+         }
+         @layer a.inner { .a-inner { color: red } }
+         @layer { .anonymous { color: red } }",
+        &lock,
+        None,
+    );
+    let guard = lock.read();
+    let data = cascade_data_for_testing(&mock_device(), QuirksMode::NoQuirks, &[&sheet], &guard);
+
+    // Layers are ordered by their first declaration, nested layers come
+    // before their parent, and unlayered rules come last.
+    let orders: Vec<_> = ["b-nested", "b", "a-inner", "a", "anonymous", "unlayered"]
+        .iter()
+        .map(|class| layer_order_of(&data, class))
+        .collect();
+    for pair in orders.windows(2) {
+        assert!(pair[0] < pair[1], "{:?}", orders);
+    }
+    assert_eq!(orders[5], LayerOrder::root());
+}
+
+#[test]
+fn test_important_layer_order_is_reversed() {
+    thread_state::initialize(ThreadState::LAYOUT);
+    let lock = SharedRwLock::new();
+    let sheet = author_stylesheet(
+        "@layer a, b;
+         @layer a { .a { color: red !important } }
+         @layer b { .b { color: green !important } }
+         .c { color: blue !important }",
+        &lock,
+        None,
+    );
+    let guard = lock.read();
+    let guards = StylesheetGuards::same(&guard);
+    let data = cascade_data_for_testing(&mock_device(), QuirksMode::NoQuirks, &[&sheet], &guard);
+
+    let rule_tree = RuleTree::new();
+    let declarations = ["a", "b", "c"].iter().map(|class| {
+        rule_with_class(&data, class)
+            .to_applicable_declaration_block(CascadeLevel::SameTreeAuthorNormal, 0, &data)
+            .for_rule_tree()
+    });
+    let node = rule_tree.insert_ordered_rules_with_important(declarations, &guards);
+
+    // From the most to the least important: earlier layers win for
+    // important declarations, and unlayered rules lose.
+    let selectors: Vec<_> = node
+        .self_and_ancestors()
+        .filter_map(|node| {
+            let rule = node.style_source()?.as_rule()?;
+            let selectors = rule.read_with(&guard).selectors.to_css_string();
+            Some((selectors, node.importance().important()))
+        })
+        .collect();
+    let expected = [
+        (".a", true),
+        (".b", true),
+        (".c", true),
+        (".c", false),
+        (".b", false),
+        (".a", false),
+    ];
+    let expected: Vec<_> = expected
+        .iter()
+        .map(|&(selectors, important)| (selectors.to_owned(), important))
+        .collect();
+    assert_eq!(selectors, expected);
+}
+
+#[test]
+fn test_import_layer() {
+    thread_state::initialize(ThreadState::LAYOUT);
+    let lock = SharedRwLock::new();
+    let loader = MockStylesheetLoader(".imported { color: red }");
+    let sheet = author_stylesheet(
+        "@layer first;
+         @import url(imported.css) layer(second);
+         @layer first { .first { color: red } }
+         .unlayered { color: red }",
+        &lock,
+        Some(&loader),
+    );
+    let guard = lock.read();
+    let data = cascade_data_for_testing(&mock_device(), QuirksMode::NoQuirks, &[&sheet], &guard);
+
+    let first = layer_order_of(&data, "first");
+    let imported = layer_order_of(&data, "imported");
+    let unlayered = layer_order_of(&data, "unlayered");
+    assert!(first < imported);
+    assert!(imported < unlayered);
+}
@@ -0,0 +1,16 @@
+importScripts("/resources/testharness.js");
+
+promise_test(function() {
+  var data = new ImageData(2, 2);
+  return createImageBitmap(data, 0, 0, 1, 2).then(function(bitmap) {
+    assert_true(bitmap instanceof ImageBitmap);
+    assert_equals(bitmap.width, 1);
+    assert_equals(bitmap.height, 2);
+  });
+}, "createImageBitmap(ImageData) in a worker");
+
+promise_test(function(t) {
+  return promise_rejects(t, "InvalidStateError", createImageBitmap(new Blob(["not an image"])));
+}, "createImageBitmap(Blob) rejects undecodable data in a worker");
+
+done();
@@ -0,0 +1,8 @@
+// Crops the right pixel out of the bitmap it receives, and sends it back.
+onmessage = function(event) {
+  var bitmap = event.data;
+  var received = bitmap.width + 'x' + bitmap.height;
+  createImageBitmap(bitmap, 1, 0, 1, 1).then(function(cropped) {
+    postMessage({received: received, bitmap: cropped}, [cropped]);
+  });
+};
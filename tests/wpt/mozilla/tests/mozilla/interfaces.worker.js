@@ -33,8 +33,10 @@ test_interfaces([
   "FormData",
   "Headers",
   "History",
+  "ImageBitmap",
   "ImageData",
   "MessageEvent",
+  "Path2D",
   "Performance",
   "PerformanceEntry",
   "PerformanceMark",
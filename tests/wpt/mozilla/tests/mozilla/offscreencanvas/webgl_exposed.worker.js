@@ -0,0 +1,31 @@
+importScripts("/resources/testharness.js");
+
+test(function() {
+  [
+    "WebGLActiveInfo",
+    "WebGLBuffer",
+    "WebGLContextEvent",
+    "WebGLFramebuffer",
+    "WebGLObject",
+    "WebGLProgram",
+    "WebGLRenderbuffer",
+    "WebGLRenderingContext",
+    "WebGLShader",
+    "WebGLShaderPrecisionFormat",
+    "WebGLTexture",
+    "WebGLUniformLocation",
+  ].forEach(function(name) {
+    assert_own_property(self, name);
+  });
+}, "WebGL interfaces are exposed in workers along with OffscreenCanvas");
+
+test(function() {
+  var gl = new OffscreenCanvas(1, 1).getContext("webgl");
+  if (!gl) {
+    return;
+  }
+  assert_true(gl instanceof WebGLRenderingContext);
+  assert_true(gl.createBuffer() instanceof WebGLObject);
+}, "WebGL contexts from an OffscreenCanvas in a worker are WebGLRenderingContexts");
+
+done();
@@ -0,0 +1,83 @@
+// Registration failures, reported by the "reporter" processor.
+var registrationErrors = {};
+
+function tryRegister(label, name, constructor) {
+  try {
+    registerProcessor(name, constructor);
+    registrationErrors[label] = null;
+  } catch (e) {
+    registrationErrors[label] = e.name;
+  }
+}
+
+// Multiplies its input by its "gain" parameter.
+class GainProcessor extends AudioWorkletProcessor {
+  static get parameterDescriptors() {
+    return [{ name: 'gain', defaultValue: 0.5, minValue: 0, maxValue: 1 }];
+  }
+
+  process(inputs, outputs, parameters) {
+    var gain = parameters.gain;
+    var input = inputs[0];
+    var output = outputs[0];
+    for (var channel = 0; channel < input.length; channel++) {
+      for (var i = 0; i < input[channel].length; i++) {
+        output[channel][i] = input[channel][i] * (gain.length > 1 ? gain[i] : gain[0]);
+      }
+    }
+    return true;
+  }
+}
+
+registerProcessor('gain', GainProcessor);
+
+tryRegister('duplicate', 'gain', GainProcessor);
+tryRegister('empty', '', GainProcessor);
+tryRegister('notConstructor', 'arrow', () => {});
+tryRegister('badDefault', 'bad-default', class extends AudioWorkletProcessor {
+  static get parameterDescriptors() {
+    return [{ name: 'x', defaultValue: 2, maxValue: 1 }];
+  }
+});
+tryRegister('duplicateParameter', 'duplicate-parameter', class extends AudioWorkletProcessor {
+  static get parameterDescriptors() {
+    return [{ name: 'x' }, { name: 'x' }];
+  }
+});
+
+// Reports the state of the global scope, and echoes the messages it gets.
+class ReporterProcessor extends AudioWorkletProcessor {
+  constructor(options) {
+    super();
+    this.port.onmessage = (e) => this.port.postMessage('echo: ' + e.data);
+    this.port.postMessage({
+      registrationErrors: registrationErrors,
+      processorOptions: options.processorOptions,
+      sampleRate: sampleRate,
+      isProcessor: this instanceof AudioWorkletProcessor,
+    });
+  }
+
+  process() {
+    return true;
+  }
+}
+
+registerProcessor('reporter', ReporterProcessor);
+
+class ThrowingProcessor extends AudioWorkletProcessor {
+  process() {
+    throw new Error('process failed');
+  }
+}
+
+registerProcessor('throwing', ThrowingProcessor);
+
+class ThrowingConstructorProcessor extends AudioWorkletProcessor {
+  constructor() {
+    super();
+    throw new Error('constructor failed');
+  }
+}
+
+registerProcessor('throwing-constructor', ThrowingConstructorProcessor);